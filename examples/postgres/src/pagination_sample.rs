@@ -1,100 +1,23 @@
 use parsql::postgres::{
-    get_all,
-    macros::{FromRow, Queryable, SqlParams},
-    traits::{FromRow, SqlParams, SqlQuery},
+    fetch_page, get_all,
+    macros::{FromRow, Paginated, Queryable, SqlParams},
+    traits::{FromRow, Paginated, SqlParams, SqlQuery},
 };
 use postgres::{types::ToSql, Client, Error, Row};
 
-/// İlk sayfa için sorgu yapısı
-#[derive(Debug, Queryable, SqlParams, FromRow)]
-#[table("users")]
-#[where_clause("state >= $")]
-#[order_by("id ASC")]
-#[limit(5)] // Her sayfada 5 kayıt
-#[offset(0)] // İlk sayfa (0. indeks)
-pub struct PageOne {
-    pub id: i32,
-    pub name: String,
-    pub email: String,
-    pub state: i16,
-}
-
-impl PageOne {
-    pub fn new(min_state: i16) -> Self {
-        Self {
-            id: 0,
-            name: String::new(),
-            email: String::new(),
-            state: min_state,
-        }
-    }
-}
-
-/// İkinci sayfa için sorgu yapısı
-#[derive(Debug, Queryable, SqlParams, FromRow)]
-#[table("users")]
-#[where_clause("state >= $")]
-#[order_by("id ASC")]
-#[limit(5)] // Her sayfada 5 kayıt
-#[offset(5)] // İkinci sayfa (5. kayıttan başla)
-pub struct PageTwo {
-    pub id: i32,
-    pub name: String,
-    pub email: String,
-    pub state: i16,
-}
-
-impl PageTwo {
-    pub fn new(min_state: i16) -> Self {
-        Self {
-            id: 0,
-            name: String::new(),
-            email: String::new(),
-            state: min_state,
-        }
-    }
-}
-
-/// Üçüncü sayfa için sorgu yapısı
-#[derive(Debug, Queryable, SqlParams, FromRow)]
-#[table("users")]
-#[where_clause("state >= $")]
-#[order_by("id ASC")]
-#[limit(5)] // Her sayfada 5 kayıt
-#[offset(10)] // Üçüncü sayfa (10. kayıttan başla)
-pub struct PageThree {
-    pub id: i32,
-    pub name: String,
-    pub email: String,
-    pub state: i16,
-}
-
-impl PageThree {
-    pub fn new(min_state: i16) -> Self {
-        Self {
-            id: 0,
-            name: String::new(),
-            email: String::new(),
-            state: min_state,
-        }
-    }
-}
-
-/// Dördüncü sayfa için sorgu yapısı
-#[derive(Debug, Queryable, SqlParams, FromRow)]
+/// Sayfalanan sorgu yapısı; `Paginated` derive'ı `UsersFromStatePage` tipini üretir
+#[derive(Debug, Queryable, SqlParams, FromRow, Paginated)]
 #[table("users")]
 #[where_clause("state >= $")]
 #[order_by("id ASC")]
-#[limit(5)] // Her sayfada 5 kayıt
-#[offset(15)] // Dördüncü sayfa (15. kayıttan başla)
-pub struct PageFour {
+pub struct UsersFromState {
     pub id: i32,
     pub name: String,
     pub email: String,
     pub state: i16,
 }
 
-impl PageFour {
+impl UsersFromState {
     pub fn new(min_state: i16) -> Self {
         Self {
             id: 0,
@@ -140,52 +63,25 @@ pub fn run_derive_pagination_examples(client: &mut Client) -> Result<(), Error>
     let total_users: i64 = count_result.get(0);
     println!("Toplam aktif kullanıcı sayısı: {}", total_users);
 
-    // Sayfa 1 - İlk 5 kullanıcı
-    let page1 = PageOne::new(0);
-    let users_page1 = get_all(client, &page1)?;
-
-    println!("\nSayfa 1 (0-4 arası kayıtlar) - Derive Macro ile:");
-    for user in &users_page1 {
-        println!(
-            "ID: {}, İsim: {}, E-posta: {}, Durum: {}",
-            user.id, user.name, user.email, user.state
-        );
-    }
-
-    // Sayfa 2 - İkinci 5 kullanıcı
-    let page2 = PageTwo::new(0);
-    let users_page2 = get_all(client, &page2)?;
-
-    println!("\nSayfa 2 (5-9 arası kayıtlar) - Derive Macro ile:");
-    for user in &users_page2 {
-        println!(
-            "ID: {}, İsim: {}, E-posta: {}, Durum: {}",
-            user.id, user.name, user.email, user.state
-        );
-    }
-
-    // Sayfa 3 - Üçüncü 5 kullanıcı
-    let page3 = PageThree::new(0);
-    let users_page3 = get_all(client, &page3)?;
+    // Her sayfada 5 kayıt; LIMIT ve OFFSET çalışma anında parametre olarak bağlanır
+    let mut page = UsersFromStatePage::nth(UsersFromState::new(0), 1, 5);
+    for number in 1..=4 {
+        let users = fetch_page(client, &page)?;
 
-    println!("\nSayfa 3 (10-14 arası kayıtlar) - Derive Macro ile:");
-    for user in &users_page3 {
         println!(
-            "ID: {}, İsim: {}, E-posta: {}, Durum: {}",
-            user.id, user.name, user.email, user.state
+            "\nSayfa {} ({}-{} arası kayıtlar) - Derive Macro ile:",
+            number,
+            page.offset,
+            page.offset + page.limit - 1
         );
-    }
-
-    // Sayfa 4 - Dördüncü 5 kullanıcı
-    let page4 = PageFour::new(0);
-    let users_page4 = get_all(client, &page4)?;
+        for user in &users {
+            println!(
+                "ID: {}, İsim: {}, E-posta: {}, Durum: {}",
+                user.id, user.name, user.email, user.state
+            );
+        }
 
-    println!("\nSayfa 4 (15-19 arası kayıtlar) - Derive Macro ile:");
-    for user in &users_page4 {
-        println!(
-            "ID: {}, İsim: {}, E-posta: {}, Durum: {}",
-            user.id, user.name, user.email, user.state
-        );
+        page = page.next();
     }
 
     // Sadece aktif kullanıcıları göster (state=1)
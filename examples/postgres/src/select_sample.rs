@@ -98,5 +98,6 @@ impl UserPostStats {
 #[limit(10)] // Her sayfada 10 kayıt
 #[offset(30)] // 4. sayfa (30. kayıttan başla)
 pub struct GetUsersPage4 {
-    // ...alanlar
+    pub state: i16,
+    // ...diğer alanlar
 }
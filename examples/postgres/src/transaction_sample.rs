@@ -1,6 +1,7 @@
 use parsql::postgres::{
     macros::{Deletable, FromRow, Insertable, Queryable, SqlParams, UpdateParams, Updateable},
     traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams},
+    insert_returning_id,
     transactional::{begin, tx_delete, tx_get, tx_insert, tx_update},
 };
 use postgres::{types::ToSql, Client, Error, Row};
@@ -68,12 +69,8 @@ pub fn transaction_with_crud_ops(client: &mut Client) -> Result<(), Error> {
         state: 1,
     };
 
-    let rows_affected = tx.insert::<TxInsertUser, i64>(insert_user)?;
-    println!("Kullanıcı eklendi, etkilenen satır: {}", rows_affected);
-
-    // Kullanıcı ID'sini al
-    let rows = tx.query("SELECT lastval()", &[])?;
-    let user_id: i64 = rows[0].get(0);
+    // Eklenen kullanıcının ID'si RETURNING id ile alınır
+    let user_id: i64 = insert_returning_id(&mut tx, insert_user)?;
     let user_id = user_id as i32; // i64'ten i32'ye dönüştür
     println!("Eklenen kullanıcı ID: {}", user_id);
 
@@ -131,12 +128,8 @@ pub fn transaction_with_helper_functions(client: &mut Client) -> Result<(), Erro
         state: 1,
     };
 
-    let (mut tx, rows_affected) = tx_insert::<TxInsertUser, i64>(tx, insert_user)?;
-    println!("Kullanıcı eklendi, etkilenen satır: {}", rows_affected);
-
-    // Kullanıcı ID'sini al
-    let rows = tx.query("SELECT lastval()", &[])?;
-    let user_id: i64 = rows[0].get(0);
+    // Eklenen kullanıcının ID'si RETURNING id ile alınır
+    let user_id: i64 = insert_returning_id(&mut tx, insert_user)?;
     let user_id = user_id as i32; // i64'ten i32'ye dönüştür
     println!("Eklenen kullanıcı ID: {}", user_id);
 
@@ -200,12 +193,8 @@ pub fn transaction_with_rollback(client: &mut Client) -> Result<(), Error> {
             state: 1,
         };
 
-        let (mut tx, rows_affected) = tx_insert::<TxInsertUser, i64>(tx, insert_user)?;
-        println!("Kullanıcı eklendi, etkilenen satır: {}", rows_affected);
-
-        // Kullanıcı ID'sini al
-        let rows = tx.query("SELECT lastval()", &[])?;
-        let user_id: i64 = rows[0].get(0);
+        // Eklenen kullanıcının ID'si RETURNING id ile alınır
+        let user_id: i64 = insert_returning_id(&mut tx, insert_user)?;
         let user_id = user_id as i32; // i64'ten i32'ye dönüştür
         println!("Eklenen kullanıcı ID: {}", user_id);
 
@@ -258,12 +247,8 @@ pub fn complex_transaction_example(client: &mut Client) -> Result<(), Error> {
         state: 1,
     };
 
-    let rows_affected = tx.insert::<TxInsertUser, i64>(insert_user)?;
-    println!("Kullanıcı eklendi, etkilenen satır: {}", rows_affected);
-
-    // Kullanıcı ID'sini al
-    let rows = tx.query("SELECT lastval()", &[])?;
-    let user_id: i64 = rows[0].get(0);
+    // Eklenen kullanıcının ID'si RETURNING id ile alınır
+    let user_id: i64 = insert_returning_id(&mut tx, insert_user)?;
     let user_id = user_id as i32; // i64'ten i32'ye dönüştür
     println!("Eklenen kullanıcı ID: {}", user_id);
 
@@ -318,11 +303,8 @@ pub fn transaction_with_delete(client: &mut Client) -> Result<(), Error> {
         state: 1,
     };
 
-    let (mut tx, _) = tx_insert::<TxInsertUser, i64>(tx, insert_user)?;
-
-    // ID'yi al
-    let rows = tx.query("SELECT lastval()", &[])?;
-    let user_id: i64 = rows[0].get(0);
+    // Eklenen kullanıcının ID'si RETURNING id ile alınır
+    let user_id: i64 = insert_returning_id(&mut tx, insert_user)?;
     let user_id = user_id as i32; // i64'ten i32'ye dönüştür
     println!("Geçici kullanıcı ID: {}", user_id);
 
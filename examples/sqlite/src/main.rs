@@ -1,6 +1,8 @@
 use parsql::sqlite::{
     crud_ops::get,
-    delete, insert,
+    delete,
+    fixtures::Fixtures,
+    insert_execute,
     macros::{Deletable, Insertable, Queryable, SqlParams, UpdateParams, Updateable},
     traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams},
     update,
@@ -12,6 +14,7 @@ mod models;
 mod pagination_sample;
 
 // Modüllerden yapıları import et
+use models::post::{InsertComment, InsertPost};
 use models::stats::UserPostStatsAdvanced;
 use models::user::{DeleteUser, GetUser, GetUserByName, InsertUser, UpdateUser};
 use pagination_sample::{run_derive_pagination_examples, run_pagination_examples};
@@ -54,56 +57,39 @@ fn main() {
             created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
             FOREIGN KEY (post_id) REFERENCES posts(id)
         );
-        
-        -- Örnek veriler
-        INSERT INTO users (name, email, state) VALUES 
-            ('admin', 'admin@example.com', 1),
-            ('user1', 'user1@example.com', 1),
-            ('user2', 'user2@example.com', 2);
-            
-        INSERT INTO posts (user_id, content, state) VALUES
-            (1, 'Admin post 1', 1),
-            (1, 'Admin post 2', 1),
-            (2, 'User1 post 1', 1),
-            (3, 'User2 post 1', 2);
-            
-        INSERT INTO comments (post_id, content, state) VALUES
-            (1, 'Comment on admin post 1', 1),
-            (1, 'Another comment on admin post 1', 1),
-            (2, 'Comment on admin post 2', 1),
-            (3, 'Comment on User1 post', 1);
-            
-        -- Sayfalama örnekleri için daha fazla test verisi
-        INSERT INTO users (name, email, state) VALUES 
-            ('user3', 'user3@example.com', 1),
-            ('user4', 'user4@example.com', 1),
-            ('user5', 'user5@example.com', 2),
-            ('user6', 'user6@example.com', 1),
-            ('user7', 'user7@example.com', 1),
-            ('user8', 'user8@example.com', 2),
-            ('user9', 'user9@example.com', 1),
-            ('user10', 'user10@example.com', 1),
-            ('user11', 'user11@example.com', 2),
-            ('user12', 'user12@example.com', 1),
-            ('user13', 'user13@example.com', 1),
-            ('user14', 'user14@example.com', 2),
-            ('user15', 'user15@example.com', 1),
-            ('user16', 'user16@example.com', 1),
-            ('user17', 'user17@example.com', 2),
-            ('user18', 'user18@example.com', 1),
-            ('user19', 'user19@example.com', 1),
-            ('user20', 'user20@example.com', 2);
     ",
         )
         .expect("Tablo oluşturma işlemi başarısız oldu!");
 
+    // Örnek veriler; kullanıcılar, onlara bağlı gönderilerden ve yorumlardan önce eklenir
+    let users = std::iter::once(("admin".to_string(), 1))
+        .chain((1..=20).map(|i| (format!("user{}", i), if i % 3 == 2 { 2 } else { 1 })))
+        .map(|(name, state)| InsertUser { email: format!("{}@example.com", name), name, state })
+        .collect::<Vec<_>>();
+    let posts = [(1, "Admin post 1", 1), (1, "Admin post 2", 1), (2, "User1 post 1", 1), (3, "User2 post 1", 2)]
+        .map(|(user_id, content, state)| InsertPost { user_id, content: content.to_string(), state });
+    let comments = [
+        (1, "Comment on admin post 1"),
+        (1, "Another comment on admin post 1"),
+        (2, "Comment on admin post 2"),
+        (3, "Comment on User1 post"),
+    ]
+    .map(|(post_id, content)| InsertComment { post_id, content: content.to_string(), state: 1 });
+
+    Fixtures::new()
+        .table(&users)
+        .table(&posts)
+        .table(&comments)
+        .load(&conn)
+        .expect("Örnek veriler eklenemedi!");
+
     let insert_usert = InsertUser {
         name: "Ali".to_string(),
         email: "ali@parsql.com".to_string(),
         state: 1,
     };
 
-    let insert_result = insert::<InsertUser, i64>(&conn, insert_usert);
+    let insert_result = insert_execute(&conn, insert_usert);
     let last_id = conn.last_insert_rowid();
     println!(
         "işlem başarıyla tamamlandı! Sonuç: {:?}, Son eklenen ID: {}",
@@ -1,2 +1,3 @@
 pub mod user;
+pub mod post;
 pub mod stats; 
\ No newline at end of file
@@ -0,0 +1,23 @@
+use parsql::sqlite::{
+    macros::{Insertable, SqlParams},
+    traits::{SqlParams, SqlQuery},
+};
+use rusqlite::types::ToSql;
+
+/// Gönderi ekleme için struct
+#[derive(Insertable, SqlParams, Debug)]
+#[table("posts")]
+pub struct InsertPost {
+    pub user_id: i64,
+    pub content: String,
+    pub state: i16,
+}
+
+/// Yorum ekleme için struct
+#[derive(Insertable, SqlParams, Debug)]
+#[table("comments")]
+pub struct InsertComment {
+    pub post_id: i64,
+    pub content: String,
+    pub state: i16,
+}
@@ -10,7 +10,6 @@ use rusqlite::{
 /// Kullanıcı ekleme için struct
 #[derive(Insertable, SqlParams, FromRow, Debug)]
 #[table("users")]
-#[returning("id")]
 pub struct InsertUser {
     pub name: String,
     pub email: String,
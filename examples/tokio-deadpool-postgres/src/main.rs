@@ -6,7 +6,7 @@ use crate::config::DatabaseConfig;
 use crate::models::{UserInsert, UserUpdate, UserDelete, UserById, UsersByState, UserStatusQuery, InsertBlog};
 use crate::repository::{UserRepository, BlogRepository};
 use dotenv::dotenv;
-use parsql::deadpool_postgres::Error;
+use parsql::deadpool_postgres::DeadpoolError as Error;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[tokio::main]
@@ -139,8 +139,8 @@ async fn demo_crud_operations(user_repo: &UserRepository, blog_repo: &BlogReposi
             "Mehmet Yılmaz (Güncellendi)",
             &format!("mehmet.updated-{}@example.com", timestamp)
         );
-        let updated = user_repo.update_user(update_user).await?;
-        println!("Kullanıcı güncellendi: {}", updated);
+        let rows_affected = user_repo.update_user(update_user).await?;
+        println!("Güncellenen kullanıcı sayısı: {}", rows_affected);
         
         // Güncellenen kullanıcıyı getir
         let updated_user = user_repo.get_user_by_id(user_id).await?;
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use parsql::deadpool_postgres::{
-    traits::{SqlParams, SqlQuery, UpdateParams, FromRow},
-    macros::{Insertable, Updateable, Queryable, Deletable, FromRow, SqlParams, UpdateParams},
+    traits::{SqlParams, SqlQuery, UpdateParams, FromRow, RepositoryTypes},
+    macros::{Insertable, Updateable, Queryable, Deletable, FromRow, SqlParams, UpdateParams, Repository},
 };
 use serde::{Deserialize, Serialize};
 use tokio_postgres::{types::ToSql, Row, Error};
@@ -70,6 +70,11 @@ pub struct UserStatusQuery {
     pub state: i16,
 }
 
+// Kullanıcı tablosunun CRUD işlemlerinde kullanılan sorgu tipleri
+#[derive(Repository)]
+#[repository(key = i64, insert = UserInsert, find = UserById, find_all = UsersByState, update = UserUpdate, delete = UserDelete)]
+pub struct Users;
+
 // Blog ekleme modeli
 #[derive(Debug, Clone, Serialize, Deserialize, Insertable, SqlParams)]
 #[table("blogs")]
@@ -108,6 +113,12 @@ impl UserDelete {
     }
 }
 
+impl From<i64> for UserDelete {
+    fn from(id: i64) -> Self {
+        Self::new(id)
+    }
+}
+
 // ID'ye göre kullanıcı getirme modeli için yardımcı metotlar
 impl UserById {
     pub fn new(id: i64) -> Self {
@@ -120,6 +131,12 @@ impl UserById {
     }
 }
 
+impl From<i64> for UserById {
+    fn from(id: i64) -> Self {
+        Self::new(id)
+    }
+}
+
 // State'e göre kullanıcıları getirme modeli için yardımcı metotlar
 impl UsersByState {
     pub fn new(state: i16) -> Self {
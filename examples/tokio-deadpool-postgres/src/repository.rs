@@ -1,52 +1,48 @@
 use deadpool_postgres::Pool;
-use parsql::deadpool_postgres::{delete, get, get_all, insert, select_all, update, Error};
+use parsql::deadpool_postgres::{insert, repository::Repository, select_all, DeadpoolError as Error};
 use tokio_postgres::Row as PgRow;
 use uuid::Uuid;
 
-use crate::models::{UserById, UserDelete, UserInsert, UserUpdate, UsersByState, UserStatusQuery, InsertBlog};
+use crate::models::{UserById, UserInsert, UserUpdate, Users, UsersByState, UserStatusQuery, InsertBlog};
 
 // Repository yapısı - Veritabanı işlemleri için
 pub struct UserRepository {
     pool: Pool,
+    // Temel CRUD işlemleri #[derive(Repository)] ile tanımlanan sorgu tiplerini kullanır
+    users: Repository<Users, Pool>,
 }
 
 impl UserRepository {
     pub fn new(pool: Pool) -> Self {
-        Self { pool }
+        Self {
+            users: Repository::new(pool.clone()),
+            pool,
+        }
     }
 
     // Kullanıcı ekleme
     pub async fn insert_user(&self, user: UserInsert) -> Result<i64, Error> {
-        // Parsql'in insert fonksiyonu, doğrudan havuzla çalışır
-        let result: i64 = insert(&self.pool, user).await?;
-        Ok(result)
+        self.users.create(user).await
     }
 
     // Kullanıcı güncelleme
-    pub async fn update_user(&self, user: UserUpdate) -> Result<bool, Error> {
-        // Parsql'in update fonksiyonu, doğrudan havuzla çalışır
-        update(&self.pool, user).await
+    pub async fn update_user(&self, user: UserUpdate) -> Result<u64, Error> {
+        self.users.update(user).await
     }
 
     // Kullanıcı silme
     pub async fn delete_user(&self, id: i64) -> Result<u64, Error> {
-        // Parsql'in delete fonksiyonu, doğrudan havuzla çalışır
-        let user_delete = UserDelete::new(id);
-        delete(&self.pool, user_delete).await
+        self.users.delete(id).await
     }
 
     // ID'ye göre kullanıcı getirme
     pub async fn get_user_by_id(&self, id: i64) -> Result<UserById, Error> {
-        // Parsql'in get fonksiyonu, doğrudan havuzla çalışır
-        let user_query = UserById::new(id);
-        get(&self.pool, &user_query).await
+        self.users.find(id).await
     }
 
     // State durumuna göre kullanıcıları getirme
     pub async fn get_users_by_state(&self, state: i16) -> Result<Vec<UsersByState>, Error> {
-        // Parsql'in get_all fonksiyonu, doğrudan havuzla çalışır
-        let query = UsersByState::new(state);
-        get_all(&self.pool, &query).await
+        self.users.find_all(UsersByState::new(state)).await
     }
 
     // Özel sorgu ile kullanıcıları getirme (durum bilgisi ile)
@@ -68,11 +64,7 @@ impl UserRepository {
     // Transaction kullanarak birden fazla işlemi atomik olarak gerçekleştirme
     pub async fn create_user_with_transaction(&self, user: UserInsert) -> Result<i64, Error> {
         // Havuzdan client al
-        let mut client = self.pool.get().await.map_err(|e| {
-            // PoolError'ı hata mesajına dönüştür
-            eprintln!("Pool hatası: {}", e);
-            Error::__private_api_timeout()
-        })?;
+        let mut client = self.pool.get().await?;
         
         // Transaction başlat
         let tx = client.transaction().await?;
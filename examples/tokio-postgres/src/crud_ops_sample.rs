@@ -1,6 +1,7 @@
 use parsql::tokio_postgres::{
     macros::{Deletable, FromRow, Insertable, Queryable, SqlParams, UpdateParams, Updateable},
     traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams},
+    ParsqlError,
 };
 use std::fmt::Debug;
 use tokio_postgres::{types::ToSql, Error, NoTls, Row};
@@ -102,7 +103,7 @@ impl GetActiveUsers {
     }
 }
 
-pub async fn run_crud_ops_example() -> Result<(), Error> {
+pub async fn run_crud_ops_example() -> Result<(), ParsqlError> {
     println!("== Derive Makroları ile CrudOps Trait Örneği ==");
 
     // NOT: Veritabanı bağlantısı main.rs üzerinden kurulur ve
@@ -157,8 +158,8 @@ pub async fn run_crud_ops_example() -> Result<(), Error> {
         email: "ali.yilmaz.updated@example.com".to_string(),
     };
 
-    let updated = client.update(update_user).await?;
-    println!("Güncelleme başarılı: {}", updated);
+    let rows_affected = client.update(update_user).await?;
+    println!("Güncellenen kayıt sayısı: {}", rows_affected);
 
     // Güncellemeden sonra kullanıcıyı tekrar getirme
     let get_user = GetUser::new(1);
@@ -2,6 +2,7 @@ use parsql::tokio_postgres::{
     get_all,
     macros::{FromRow, Queryable, SqlParams},
     traits::{FromRow, SqlParams, SqlQuery},
+    ParsqlError,
 };
 use tokio_postgres::{types::ToSql, Client, Error, Row};
 
@@ -86,7 +87,7 @@ impl GetUsersPage {
 }
 
 /// Limit ve offset özelliklerini kullanan örnek fonksiyon
-pub async fn list_users_with_pagination(client: &Client) -> Result<(), Error> {
+pub async fn list_users_with_pagination(client: &Client) -> Result<(), ParsqlError> {
     println!("=== Limit ve Offset örnekleri ===");
 
     // İlk sayfa (ilk 5 kayıt)
@@ -1,7 +1,7 @@
 use parsql::tokio_postgres::{
     macros::{Deletable, FromRow, Insertable, Queryable, SqlParams, UpdateParams, Updateable},
     traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams},
-    Error, Row,
+    Error, ParsqlError, Row,
 };
 use std::fmt::Debug;
 use tokio_postgres::{types::ToSql, NoTls};
@@ -121,7 +121,7 @@ impl UserStatusQuery {
     }
 }
 
-pub async fn run_macro_example() -> Result<(), Error> {
+pub async fn run_macro_example() -> Result<(), ParsqlError> {
     println!("== Derive Makroları ile CrudOps Örneği ==");
 
     // NOT: Veritabanı bağlantısı main.rs üzerinden kurulur ve
@@ -179,8 +179,8 @@ pub async fn run_macro_example() -> Result<(), Error> {
         "zeynep.updated@example.com".to_string(),
     );
 
-    let updated = client.update(update_user).await?;
-    println!("Güncelleme başarılı: {}", updated);
+    let rows_affected = client.update(update_user).await?;
+    println!("Güncellenen kayıt sayısı: {}", rows_affected);
 
     // Özel sorgu ile veri getirme
     println!("\n4. Özel sorgu ile kullanıcı durumları:");
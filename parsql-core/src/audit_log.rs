@@ -0,0 +1,186 @@
+//! Audit rows written together with the change they record.
+//!
+//! An `Insertable`, `Updateable` or `Deletable` struct marked `#[audited]` generates a
+//! statement built by [`AuditLog::wrap`]: the write runs in a data-modifying `WITH` and
+//! the same statement inserts one row per written row into the audit table. The audit
+//! row can't be lost or left behind when the write fails, whether or not the caller
+//! runs it in a transaction. PostgreSQL only; SQLite has no data-modifying `WITH`.
+//!
+//! The audit table needs the columns of [`CREATE_TABLE`]:
+//!
+//! - `table_name`, `op`: the written table and `INSERT`, `UPDATE` or `DELETE`
+//! - `pk`: the key column of the written row, as text
+//! - `diff`: the inserted or deleted row as JSON, or the columns an UPDATE set with their
+//!   new values
+//! - `actor`: the setting named by `actor`, by default the user `with_rls_user` acts for
+//!   ([`RLS_USER`](crate::session::RLS_USER)); `NULL` when it isn't set or is empty
+
+use crate::session::RLS_USER;
+
+/// Audit table the generated statements write to unless `#[audited(table = "...")]` names another.
+pub const DEFAULT_TABLE: &str = "audit_log";
+
+/// A table [`DEFAULT_TABLE`] statements can write to.
+pub const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    table_name TEXT NOT NULL,
+    op TEXT NOT NULL,
+    pk TEXT,
+    diff JSONB NOT NULL,
+    actor TEXT,
+    logged_at TIMESTAMPTZ NOT NULL DEFAULT now()
+)";
+
+/// The kind of write an audit row records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl Operation {
+    /// The value stored in the `op` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Insert => "INSERT",
+            Operation::Update => "UPDATE",
+            Operation::Delete => "DELETE",
+        }
+    }
+}
+
+/// Where and how the audit rows of a write are recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLog {
+    /// Audit table, optionally schema qualified
+    pub table: String,
+    /// Column of the written table stored as `pk`
+    pub key: String,
+    /// Setting read with `current_setting` for the `actor` column
+    pub actor: String,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self {
+            table: DEFAULT_TABLE.to_string(),
+            key: "id".to_string(),
+            actor: RLS_USER.to_string(),
+        }
+    }
+}
+
+/// Whether `name` is a plain (optionally dot separated) name that can be written into
+/// the statement as it is.
+pub fn is_plain_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'))
+}
+
+impl AuditLog {
+    /// Wraps the write `statement` on `target` so it also inserts its audit rows.
+    ///
+    /// `target` is the written table as the statement names it. `changed` lists the
+    /// columns an UPDATE sets, recorded with their new values; the whole row is recorded
+    /// when it is empty. `statement` must not have a `RETURNING` clause: an INSERT
+    /// returns the `returning` column, or the key column without one, while an UPDATE
+    /// or DELETE reports one affected row per written row.
+    pub fn wrap(
+        &self,
+        operation: Operation,
+        statement: &str,
+        target: &str,
+        changed: &[String],
+        returning: Option<&str>,
+    ) -> String {
+        let diff = if changed.is_empty() {
+            "to_jsonb(written)".to_string()
+        } else {
+            let pairs = changed
+                .iter()
+                .map(|column| format!("'{}', written.{}", unquoted(column), column))
+                .collect::<Vec<_>>();
+            format!("jsonb_build_object({})", pairs.join(", "))
+        };
+        let audit = format!(
+            "INSERT INTO {} (table_name, op, pk, diff, actor) SELECT '{}', '{}', written.{}::text, {}, NULLIF(current_setting('{}', true), '') FROM written",
+            self.table,
+            unquoted(target),
+            operation.as_str(),
+            self.key,
+            diff,
+            self.actor
+        );
+        let written = format!("WITH written AS ({} RETURNING {}.*)", statement.trim_end(), target);
+        match operation {
+            // INSERT anahtarı döndürür; etkilenen satır sayısı son SELECT'in satır sayısıdır
+            Operation::Insert => format!(
+                "{}, audited AS ({}) SELECT written.{} FROM written",
+                written,
+                audit,
+                returning.unwrap_or(&self.key)
+            ),
+            Operation::Update | Operation::Delete => format!("{} {}", written, audit),
+        }
+    }
+}
+
+/// The name stored in the audit row for a possibly quoted identifier.
+fn unquoted(name: &str) -> String {
+    name.replace('"', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_insert_and_returns_the_key() {
+        let sql = AuditLog::default().wrap(
+            Operation::Insert,
+            "INSERT INTO users (name) VALUES ($1)",
+            "users",
+            &[],
+            None,
+        );
+        assert_eq!(
+            sql,
+            "WITH written AS (INSERT INTO users (name) VALUES ($1) RETURNING users.*), audited AS (\
+             INSERT INTO audit_log (table_name, op, pk, diff, actor) SELECT 'users', 'INSERT', written.id::text, \
+             to_jsonb(written), NULLIF(current_setting('app.current_user_id', true), '') FROM written) SELECT written.id FROM written"
+        );
+    }
+
+    #[test]
+    fn update_records_the_set_columns() {
+        let log = AuditLog {
+            table: "audit.changes".to_string(),
+            key: "user_id".to_string(),
+            actor: "app.actor".to_string(),
+        };
+        let sql = log.wrap(
+            Operation::Update,
+            "UPDATE \"order\" SET \"State\" = $1 WHERE user_id = $2",
+            "\"order\"",
+            &["\"State\"".to_string()],
+            None,
+        );
+        assert_eq!(
+            sql,
+            "WITH written AS (UPDATE \"order\" SET \"State\" = $1 WHERE user_id = $2 RETURNING \"order\".*) \
+             INSERT INTO audit.changes (table_name, op, pk, diff, actor) SELECT 'order', 'UPDATE', written.user_id::text, \
+             jsonb_build_object('State', written.\"State\"), NULLIF(current_setting('app.actor', true), '') FROM written"
+        );
+    }
+
+    #[test]
+    fn plain_names() {
+        assert!(is_plain_name("audit_log"));
+        assert!(is_plain_name("audit.changes"));
+        assert!(!is_plain_name("audit_log; DROP TABLE users"));
+        assert!(!is_plain_name("audit."));
+    }
+}
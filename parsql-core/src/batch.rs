@@ -0,0 +1,296 @@
+//! Chunking helpers for the bulk `insert_many` / `update_many` operations.
+//!
+//! A multi-row `INSERT` binds `rows * columns` parameters, which can exceed the
+//! limit of the database (65535 on PostgreSQL, 999 on older SQLite builds).
+//! These helpers compute the largest safe chunk for a dialect and rewrite the
+//! single-row `INSERT` generated by the `Insertable` derive into a multi-row one.
+//! [`with_returning`] adds a `RETURNING` clause to it for the `insert_returning_id`
+//! functions.
+
+use crate::paging::top_level_keyword;
+
+/// SQL dialect of a backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// PostgreSQL, `$n` placeholders
+    Postgres,
+    /// SQLite, `?n` placeholders
+    Sqlite,
+}
+
+impl Dialect {
+    /// Maximum number of bound parameters in a single statement.
+    pub fn max_params(self) -> usize {
+        match self {
+            Dialect::Postgres => 65535,
+            Dialect::Sqlite => 999,
+        }
+    }
+
+    /// Placeholder prefix character.
+    pub fn placeholder(self) -> char {
+        match self {
+            Dialect::Postgres => '$',
+            Dialect::Sqlite => '?',
+        }
+    }
+
+    /// Opening and closing characters of a quoted identifier. SQLite also accepts
+    /// MySQL's backticks and SQL Server's brackets, but reads the standard double quotes
+    /// the same way PostgreSQL does.
+    pub fn identifier_quotes(self) -> (char, char) {
+        match self {
+            Dialect::Postgres | Dialect::Sqlite => ('"', '"'),
+        }
+    }
+}
+
+/// Options of the bulk operations.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchOptions {
+    /// Maximum rows per statement. `None` uses the largest chunk the dialect allows;
+    /// larger values are capped to that limit.
+    pub chunk_size: Option<usize>,
+    /// Runs all chunks inside one transaction, so either every row is written or none.
+    pub transaction: bool,
+}
+
+impl BatchOptions {
+    /// Number of rows per statement for rows binding `params_per_row` parameters.
+    pub fn rows_per_chunk(&self, dialect: Dialect, params_per_row: usize) -> usize {
+        let max_rows = dialect.max_params() / params_per_row.max(1);
+        self.chunk_size.unwrap_or(max_rows).clamp(1, max_rows.max(1))
+    }
+}
+
+/// What `tx_insert_all` does when a row fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnError {
+    /// Stops at the first failing row; its error is the last result.
+    #[default]
+    Stop,
+    /// Records the error and goes on with the next row. On PostgreSQL every row runs
+    /// in a savepoint, so a failed row does not abort the transaction.
+    Continue,
+}
+
+/// Progress reported by `tx_insert_all` after every row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Rows attempted so far
+    pub done: usize,
+    /// Rows in the batch
+    pub total: usize,
+    /// Rows that failed so far
+    pub failed: usize,
+}
+
+/// Rewrites a single-row `INSERT ... VALUES (...)` into a statement inserting `rows` rows.
+///
+/// The placeholders of every additional row are shifted by `params_per_row`.
+/// Statements appended with `;` are dropped. A `RETURNING` clause is kept on
+/// PostgreSQL and dropped on SQLite, where the chunks run with `execute`.
+///
+/// Returns `None` when the statement has no `VALUES` list, e.g. `INSERT ... SELECT`.
+pub fn multi_row_insert(sql: &str, params_per_row: usize, rows: usize, dialect: Dialect) -> Option<String> {
+    let values_at = sql.find(" VALUES ")?;
+    let (head, tail) = sql.split_at(values_at + " VALUES ".len());
+
+    let open = tail.find('(')?;
+    let close = matching_paren(tail, open)?;
+    let tuple = &tail[open..=close];
+    let rest = tail[close + 1..].trim();
+    let rest = if rest.starts_with(';') { "" } else { rest };
+    let rest = match top_level_keyword(rest, &["RETURNING"]) {
+        Some((_, at)) if dialect == Dialect::Sqlite => rest[..at].trim_end(),
+        _ => rest,
+    };
+
+    let tuples = (0..rows)
+        .map(|row| shift_placeholders(tuple, row * params_per_row, dialect))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut query = format!("{}{}", head, tuples);
+    if !rest.is_empty() {
+        query.push(' ');
+        query.push_str(rest);
+    }
+    Some(query)
+}
+
+/// Appends `RETURNING column` to an `INSERT` that returns nothing yet, so the generated
+/// key can be read without a `#[returning]` attribute. A statement with its own
+/// `RETURNING` clause is kept as it is, and so is an `#[audited]` INSERT, whose final
+/// `SELECT` already returns the key.
+///
+/// Returns `None` when `column` is not a plain column name.
+pub fn with_returning(sql: &str, column: &str) -> Option<String> {
+    if column.is_empty() || !column.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    if top_level_keyword(sql, &["RETURNING"]).is_some() || sql.trim_start().starts_with("WITH ") {
+        return Some(sql.to_string());
+    }
+    Some(format!("{} RETURNING {}", sql.trim_end(), column))
+}
+
+/// Turns an `INSERT ... VALUES` (single or multi-row) into an upsert:
+/// `ON CONFLICT (conflict) DO UPDATE SET column = EXCLUDED.column` for every inserted
+/// column outside `conflict`, placed before a `RETURNING` clause. When every column is
+/// part of the conflict target the rows are skipped with `DO NOTHING`.
+///
+/// Returns `None` when a `conflict` column is not a plain column name, the statement
+/// has no column list and `VALUES`, or it already has an `ON CONFLICT` clause.
+pub fn with_upsert(sql: &str, conflict: &[&str]) -> Option<String> {
+    let plain = |column: &&str| !column.is_empty() && column.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if conflict.is_empty() || !conflict.iter().all(plain) {
+        return None;
+    }
+    if top_level_keyword(sql, &["CONFLICT"]).is_some() {
+        return None;
+    }
+    let values_at = sql.find(" VALUES ")?;
+    let open = sql[..values_at].find('(')?;
+    let close = matching_paren(sql, open)?;
+    if close > values_at {
+        return None;
+    }
+
+    let updates = sql[open + 1..close]
+        .split(',')
+        .map(str::trim)
+        .filter(|column| !conflict.iter().any(|c| c.eq_ignore_ascii_case(column.trim_matches('"'))))
+        .map(|column| format!("{} = EXCLUDED.{}", column, column))
+        .collect::<Vec<_>>();
+    let action = if updates.is_empty() {
+        "DO NOTHING".to_string()
+    } else {
+        format!("DO UPDATE SET {}", updates.join(", "))
+    };
+    let clause = format!("ON CONFLICT ({}) {}", conflict.join(", "), action);
+
+    let sql = sql.trim_end();
+    Some(match top_level_keyword(sql, &["RETURNING"]) {
+        Some((_, at)) => format!("{}{} {}", &sql[..at], clause, &sql[at..]),
+        None => format!("{} {}", sql, clause),
+    })
+}
+
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices().skip_while(|(i, _)| *i < open) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+pub(crate) fn shift_placeholders(text: &str, offset: usize, dialect: Dialect) -> String {
+    let marker = dialect.placeholder();
+    let mut result = String::with_capacity(text.len() + 8);
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        result.push(c);
+        if c != marker {
+            continue;
+        }
+        let mut digits = String::new();
+        while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+            digits.push(*d);
+            chars.next();
+        }
+        match digits.parse::<usize>() {
+            Ok(n) => result.push_str(&(n + offset).to_string()),
+            Err(_) => result.push_str(&digits),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn postgres_multi_row_insert() {
+        let sql = "INSERT INTO users (name, email ) VALUES ( $1, $2 ) RETURNING id";
+        assert_eq!(
+            multi_row_insert(sql, 2, 3, Dialect::Postgres).unwrap(),
+            "INSERT INTO users (name, email ) VALUES ( $1, $2 ), ( $3, $4 ), ( $5, $6 ) RETURNING id"
+        );
+    }
+
+    #[test]
+    fn sqlite_multi_row_insert_drops_trailing_statement() {
+        let sql = "INSERT INTO users (name ) VALUES ( ?1 ) ; SELECT last_insert_rowid() AS id";
+        assert_eq!(
+            multi_row_insert(sql, 1, 2, Dialect::Sqlite).unwrap(),
+            "INSERT INTO users (name ) VALUES ( ?1 ), ( ?2 )"
+        );
+        let sql = "INSERT INTO users (name ) VALUES ( ?1 ) ON CONFLICT DO NOTHING RETURNING id";
+        assert_eq!(
+            multi_row_insert(sql, 1, 2, Dialect::Sqlite).unwrap(),
+            "INSERT INTO users (name ) VALUES ( ?1 ), ( ?2 ) ON CONFLICT DO NOTHING"
+        );
+    }
+
+    #[test]
+    fn insert_select_is_not_rewritten() {
+        let sql = "INSERT INTO archive SELECT id FROM users WHERE state = $1";
+        assert!(multi_row_insert(sql, 1, 2, Dialect::Postgres).is_none());
+    }
+
+    #[test]
+    fn returning_is_appended_once() {
+        let sql = "INSERT INTO users (name ) VALUES ( $1 )";
+        assert_eq!(with_returning(sql, "id").unwrap(), "INSERT INTO users (name ) VALUES ( $1 ) RETURNING id");
+
+        let sql = "INSERT INTO users (name ) VALUES ( $1 ) RETURNING user_id";
+        assert_eq!(with_returning(sql, "id").unwrap(), sql);
+        let sql = "INSERT INTO notes (body ) VALUES ( 'returning' )";
+        assert!(with_returning(sql, "id").unwrap().ends_with(" RETURNING id"));
+        let audited = "WITH written AS (INSERT INTO notes (body ) VALUES ( $1 ) RETURNING notes.*) SELECT written.id FROM written";
+        assert_eq!(with_returning(audited, "id").unwrap(), audited);
+
+        assert!(with_returning(sql, "id; DROP TABLE notes").is_none());
+    }
+
+    #[test]
+    fn upsert_updates_the_other_columns() {
+        let sql = multi_row_insert("INSERT INTO users (email, name, \"order\" ) VALUES ( $1, $2, $3 ) RETURNING id", 3, 2, Dialect::Postgres).unwrap();
+        assert_eq!(
+            with_upsert(&sql, &["email"]).unwrap(),
+            "INSERT INTO users (email, name, \"order\" ) VALUES ( $1, $2, $3 ), ( $4, $5, $6 ) \
+             ON CONFLICT (email) DO UPDATE SET name = EXCLUDED.name, \"order\" = EXCLUDED.\"order\" RETURNING id"
+        );
+        assert_eq!(
+            with_upsert("INSERT INTO tags (name ) VALUES ( ?1 )", &["name"]).unwrap(),
+            "INSERT INTO tags (name ) VALUES ( ?1 ) ON CONFLICT (name) DO NOTHING"
+        );
+        assert!(with_upsert("INSERT INTO archive SELECT id FROM users", &["id"]).is_none());
+        assert!(with_upsert("INSERT INTO tags (name ) VALUES ( $1 )", &["name; DROP TABLE tags"]).is_none());
+        assert!(with_upsert("INSERT INTO tags (name ) VALUES ( $1 )", &[]).is_none());
+    }
+
+    #[test]
+    fn chunk_size_is_capped_by_dialect() {
+        let options = BatchOptions::default();
+        assert_eq!(options.rows_per_chunk(Dialect::Sqlite, 3), 333);
+        assert_eq!(options.rows_per_chunk(Dialect::Postgres, 3), 21845);
+
+        let options = BatchOptions { chunk_size: Some(5000), ..Default::default() };
+        assert_eq!(options.rows_per_chunk(Dialect::Sqlite, 3), 333);
+        assert_eq!(options.rows_per_chunk(Dialect::Postgres, 3), 5000);
+    }
+}
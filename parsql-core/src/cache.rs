@@ -0,0 +1,471 @@
+//! Read-through cache hooks for fetch operations.
+//!
+//! A backend's `Cached` wrapper asks a [`CacheProvider`] before running a fetch and
+//! stores the result afterwards. Entries are keyed by the result type, the query and
+//! the encoded bytes of its bound values, and remember the tables the query reads. A
+//! write through the same wrapper invalidates every entry that reads the written table.
+//! [`ReadThrough`] holds this logic; the wrappers only run the queries and encode the
+//! values ([`encode_postgres_params`] on PostgreSQL).
+//!
+//! A fetch that is still running when its table is invalidated does not store its
+//! result: the provider counts the invalidations of each table, and [`CacheProvider::set`]
+//! drops a result read before the latest one.
+//!
+//! [`MemoryCache`] is a simple in-process provider; implement the trait to plug in
+//! another store such as moka.
+//!
+//! Writes made by other application instances, or outside parsql, do not pass through
+//! the wrapper. On PostgreSQL, [`invalidation_trigger`] makes the database announce
+//! them on [`INVALIDATION_CHANNEL`], and the backends' `cache::listen` forwards each
+//! announcement to [`CacheProvider::invalidate`].
+
+use std::any::{Any, TypeId};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::trace;
+
+/// A cached result, downcast back to its type on a hit.
+pub type CachedValue = Arc<dyn Any + Send + Sync>;
+
+/// Identifies a cached result: the result type plus a hash of the query and its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// Type of the cached result
+    pub type_id: TypeId,
+    /// Hash of the query with its bound values
+    pub params_hash: u64,
+}
+
+impl CacheKey {
+    /// Builds the key of a `T` result read by `sql` with the bound values encoded in
+    /// `params`. Different values must encode differently, as the encodings of the
+    /// backends' `Cached` wrappers do.
+    pub fn of<T: 'static>(sql: &str, params: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        sql.hash(&mut hasher);
+        params.hash(&mut hasher);
+        Self {
+            type_id: TypeId::of::<T>(),
+            params_hash: hasher.finish(),
+        }
+    }
+}
+
+/// Storage behind a read-through cache.
+pub trait CacheProvider: Send + Sync {
+    /// Returns the cached value of `key`, if any.
+    fn get(&self, key: &CacheKey) -> Option<CachedValue>;
+
+    /// Returns how many times `table` has been invalidated.
+    fn generation(&self, table: &str) -> u64;
+
+    /// Stores `value` under `key`. `tables` are the tables the query reads, each with
+    /// its [`generation`](Self::generation) from before the query ran; nothing is
+    /// stored when one of them has been invalidated since.
+    fn set(&self, key: CacheKey, tables: Vec<(String, u64)>, value: CachedValue);
+
+    /// Drops every entry that reads `table`.
+    fn invalidate(&self, table: &str);
+}
+
+/// In-process [`CacheProvider`] without expiry; entries live until their table is written.
+#[derive(Default)]
+pub struct MemoryCache {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    entries: HashMap<CacheKey, (Vec<String>, CachedValue)>,
+    // Tablo adları küçük harfe çevrilerek tutulur
+    generations: HashMap<String, u64>,
+}
+
+impl MemoryState {
+    fn generation(&self, table: &str) -> u64 {
+        self.generations.get(&table.to_lowercase()).copied().unwrap_or_default()
+    }
+}
+
+impl MemoryCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached results.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every entry.
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+}
+
+impl CacheProvider for MemoryCache {
+    fn get(&self, key: &CacheKey) -> Option<CachedValue> {
+        self.state.lock().unwrap().entries.get(key).map(|(_, value)| value.clone())
+    }
+
+    fn generation(&self, table: &str) -> u64 {
+        self.state.lock().unwrap().generation(table)
+    }
+
+    fn set(&self, key: CacheKey, tables: Vec<(String, u64)>, value: CachedValue) {
+        let mut state = self.state.lock().unwrap();
+        if tables.iter().any(|(table, generation)| state.generation(table) != *generation) {
+            return;
+        }
+        let tables = tables.into_iter().map(|(table, _)| table).collect();
+        state.entries.insert(key, (tables, value));
+    }
+
+    fn invalidate(&self, table: &str) {
+        let mut state = self.state.lock().unwrap();
+        *state.generations.entry(table.to_lowercase()).or_default() += 1;
+        state
+            .entries
+            .retain(|_, (tables, _)| !tables.iter().any(|t| t.eq_ignore_ascii_case(table)));
+    }
+}
+
+impl<P: CacheProvider + ?Sized> CacheProvider for Arc<P> {
+    fn get(&self, key: &CacheKey) -> Option<CachedValue> {
+        (**self).get(key)
+    }
+
+    fn generation(&self, table: &str) -> u64 {
+        (**self).generation(table)
+    }
+
+    fn set(&self, key: CacheKey, tables: Vec<(String, u64)>, value: CachedValue) {
+        (**self).set(key, tables, value)
+    }
+
+    fn invalidate(&self, table: &str) {
+        (**self).invalidate(table)
+    }
+}
+
+/// The read-through logic shared by the backends' `Cached` wrappers.
+///
+/// `fetch` answers from the provider or runs the query and stores its result; `written`
+/// invalidates the results reading the table a successful write changed.
+pub struct ReadThrough<P> {
+    provider: P,
+    tag: &'static str,
+}
+
+impl<P: CacheProvider> ReadThrough<P> {
+    /// Caches in `provider`; hits and invalidations are traced under `tag`.
+    pub fn new(provider: P, tag: &'static str) -> Self {
+        Self { provider, tag }
+    }
+
+    /// The cache provider.
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Returns the cached result of `sql` run with the values encoded in `params`, or
+    /// runs `fetch` and caches what it returns. With `None`, i.e. values the backend
+    /// can not encode, `fetch` runs without the cache.
+    pub fn fetch<R, E>(&self, sql: &str, params: Option<&[u8]>, fetch: impl FnOnce() -> Result<R, E>) -> Result<R, E>
+    where
+        R: Clone + Send + Sync + 'static,
+    {
+        let Some(params) = params else { return fetch() };
+        let key = CacheKey::of::<R>(sql, params);
+        if let Some(value) = self.hit(sql, &key) {
+            return Ok(value);
+        }
+        let tables = self.generations(sql);
+        let result = fetch()?;
+        self.provider.set(key, tables, Arc::new(result.clone()));
+        Ok(result)
+    }
+
+    /// [`fetch`](Self::fetch) for the async backends; `fetch` is only polled on a miss.
+    pub async fn fetch_async<R, E>(&self, sql: &str, params: Option<&[u8]>, fetch: impl Future<Output = Result<R, E>>) -> Result<R, E>
+    where
+        R: Clone + Send + Sync + 'static,
+    {
+        let Some(params) = params else { return fetch.await };
+        let key = CacheKey::of::<R>(sql, params);
+        if let Some(value) = self.hit(sql, &key) {
+            return Ok(value);
+        }
+        let tables = self.generations(sql);
+        let result = fetch.await?;
+        self.provider.set(key, tables, Arc::new(result.clone()));
+        Ok(result)
+    }
+
+    /// Passes on the `result` of the write statement `sql`, invalidating the cached
+    /// results reading its table when it succeeded.
+    pub fn written<R, E>(&self, sql: &str, result: Result<R, E>) -> Result<R, E> {
+        // Yazma sorgularında ilk tablo, yazılan tablodur
+        if let (Ok(_), Some(table)) = (&result, tables(sql).first()) {
+            trace::log(self.tag, format!("Invalidated: {}", table));
+            self.provider.invalidate(table);
+        }
+        result
+    }
+
+    fn hit<R: Clone + 'static>(&self, sql: &str, key: &CacheKey) -> Option<R> {
+        let value = self.provider.get(key)?.downcast_ref::<R>().cloned()?;
+        trace::log(self.tag, format!("Cache hit: {}", sql));
+        Some(value)
+    }
+
+    // Sorgu çalışmadan önce okunur; sonuç gelene kadar tablo yazılırsa önbelleğe alınmaz
+    fn generations(&self, sql: &str) -> Vec<(String, u64)> {
+        tables(sql)
+            .into_iter()
+            .map(|table| {
+                let generation = self.provider.generation(&table);
+                (table, generation)
+            })
+            .collect()
+    }
+}
+
+/// PostgreSQL types tried, in order, to encode a bound value whose statement type is
+/// not known; the first one the value accepts is used.
+#[cfg(feature = "postgres")]
+const CANDIDATE_TYPES: &[postgres_types::Type] = {
+    use postgres_types::Type;
+    &[
+        Type::INT8,
+        Type::INT4,
+        Type::INT2,
+        Type::OID,
+        Type::BOOL,
+        Type::CHAR,
+        Type::FLOAT8,
+        Type::FLOAT4,
+        Type::TEXT,
+        Type::VARCHAR,
+        Type::BPCHAR,
+        Type::NAME,
+        Type::BYTEA,
+        Type::UUID,
+        Type::JSONB,
+        Type::JSON,
+        Type::NUMERIC,
+        Type::TIMESTAMPTZ,
+        Type::TIMESTAMP,
+        Type::DATE,
+        Type::TIME,
+        Type::INT8_ARRAY,
+        Type::INT4_ARRAY,
+        Type::INT2_ARRAY,
+        Type::BOOL_ARRAY,
+        Type::FLOAT8_ARRAY,
+        Type::TEXT_ARRAY,
+        Type::VARCHAR_ARRAY,
+        Type::UUID_ARRAY,
+    ]
+};
+
+/// Encodes PostgreSQL parameters for [`ReadThrough::fetch`].
+///
+/// Each value is written in the binary wire format of the first candidate type it
+/// accepts, after that type's OID, so different values never share an encoding.
+/// Returns `None` when a value accepts none of the candidates (e.g. a custom enum);
+/// such queries are not cached.
+#[cfg(feature = "postgres")]
+pub fn encode_postgres_params(params: &[&(dyn postgres_types::ToSql + Sync)]) -> Option<Vec<u8>> {
+    use postgres_types::IsNull;
+
+    let mut encoded = Vec::new();
+    let mut buf = bytes::BytesMut::new();
+    for param in params {
+        let (ty, is_null) = CANDIDATE_TYPES.iter().find_map(|ty| {
+            buf.clear();
+            // to_sql_checked, değerin kabul etmediği tipleri reddeder
+            param.to_sql_checked(ty, &mut buf).ok().map(|is_null| (ty, is_null))
+        })?;
+        encoded.extend_from_slice(&ty.oid().to_be_bytes());
+        match is_null {
+            IsNull::Yes => encoded.extend_from_slice(&u64::MAX.to_be_bytes()),
+            IsNull::No => {
+                encoded.extend_from_slice(&(buf.len() as u64).to_be_bytes());
+                encoded.extend_from_slice(&buf);
+            }
+        }
+    }
+    Some(encoded)
+}
+
+/// Channel the triggers of [`invalidation_trigger`] notify; the payload is the table name.
+pub const INVALIDATION_CHANNEL: &str = "parsql_cache";
+
+/// Subscribes a PostgreSQL session to [`INVALIDATION_CHANNEL`].
+pub const LISTEN: &str = "LISTEN parsql_cache";
+
+/// Statements creating a trigger that notifies [`INVALIDATION_CHANNEL`] after every
+/// statement that writes `table`.
+///
+/// The payload is `table` as written here, so use the name the cached queries use
+/// (e.g. `reports.sales` when they qualify it). The statements can be run again; the
+/// trigger is replaced. Returns `None` when `table` is not a plain or schema-qualified
+/// name.
+pub fn invalidation_trigger(table: &str) -> Option<String> {
+    let plain = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !table.split('.').all(plain) {
+        return None;
+    }
+    Some(format!(
+        "CREATE OR REPLACE FUNCTION parsql_cache_notify() RETURNS trigger LANGUAGE plpgsql AS $$ \
+         BEGIN PERFORM pg_notify('{channel}', TG_ARGV[0]); RETURN NULL; END $$; \
+         DROP TRIGGER IF EXISTS parsql_cache_notify ON {table}; \
+         CREATE TRIGGER parsql_cache_notify AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON {table} \
+         FOR EACH STATEMENT EXECUTE FUNCTION parsql_cache_notify('{table}')",
+        channel = INVALIDATION_CHANNEL,
+        table = table
+    ))
+}
+
+/// Returns the tables named after `FROM`, `JOIN`, `INTO` and `UPDATE` in `sql`,
+/// in order of appearance.
+///
+/// For an `INSERT`, `UPDATE` or `DELETE` the first table is the one being written.
+pub fn tables(sql: &str) -> Vec<String> {
+    let mut tables: Vec<String> = Vec::new();
+    let mut tokens = sql
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|token| !token.is_empty());
+
+    while let Some(token) = tokens.next() {
+        let keyword = ["FROM", "JOIN", "INTO", "UPDATE"]
+            .iter()
+            .any(|k| token.eq_ignore_ascii_case(k));
+        if !keyword {
+            continue;
+        }
+        // Alt sorgular "(" ile başlar, onların tabloları ayrıca taranır
+        let Some(next) = tokens.next() else { break };
+        let name = next.split(['(', ')']).next().unwrap_or_default().trim_matches('"');
+        if !name.is_empty() && !tables.iter().any(|t| t == name) {
+            tables.push(name.to_string());
+        }
+    }
+
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_read_and_written_tables() {
+        assert_eq!(
+            tables("SELECT u.id FROM users u LEFT JOIN posts p ON p.user_id = u.id WHERE id = $1"),
+            vec!["users", "posts"]
+        );
+        assert_eq!(tables("INSERT INTO logs (id) SELECT id FROM users"), vec!["logs", "users"]);
+        assert_eq!(tables("UPDATE users SET name = $1 WHERE id = $2"), vec!["users"]);
+        assert_eq!(tables("SELECT id FROM (SELECT id FROM users) t"), vec!["users"]);
+    }
+
+    #[test]
+    fn invalidation_triggers() {
+        let sql = invalidation_trigger("reports.sales").unwrap();
+        assert!(sql.contains("DROP TRIGGER IF EXISTS parsql_cache_notify ON reports.sales;"));
+        assert!(sql.ends_with("ON reports.sales FOR EACH STATEMENT EXECUTE FUNCTION parsql_cache_notify('reports.sales')"));
+        assert!(invalidation_trigger("users; DROP TABLE users").is_none());
+        assert!(invalidation_trigger("").is_none());
+    }
+
+    #[test]
+    fn reads_through() {
+        const SQL: &str = "SELECT id FROM users WHERE id = $1";
+        let cache = ReadThrough::new(MemoryCache::new(), "TEST");
+        let runs = std::cell::Cell::new(0);
+        let fetch = |id: i32| {
+            runs.set(runs.get() + 1);
+            Ok::<_, ()>(id)
+        };
+        let id = |id: u8| Some(vec![id]);
+
+        // İlk okuma sorguyu çalıştırır, aynı değerlerle ikincisi önbellekten döner
+        assert_eq!(cache.fetch(SQL, id(1).as_deref(), || fetch(1)), Ok(1));
+        assert_eq!(cache.fetch(SQL, id(1).as_deref(), || fetch(2)), Ok(1));
+        assert_eq!(runs.get(), 1);
+        // Farklı değerler ve farklı sonuç tipleri ayrı kayıtlardır
+        assert_eq!(cache.fetch(SQL, id(2).as_deref(), || fetch(2)), Ok(2));
+        assert_eq!(cache.fetch(SQL, id(1).as_deref(), || Ok::<_, ()>(vec![1])), Ok(vec![1]));
+        assert_eq!(runs.get(), 2);
+        // Hatalar ve kodlanamayan değerler önbelleğe alınmaz
+        assert_eq!(cache.fetch(SQL, id(3).as_deref(), || Err::<i32, _>(())), Err(()));
+        assert_eq!(cache.fetch(SQL, None, || fetch(4)), Ok(4));
+        assert_eq!(cache.fetch(SQL, None, || fetch(4)), Ok(4));
+        assert_eq!(runs.get(), 4);
+        assert_eq!(cache.provider().len(), 3);
+
+        // Başarısız yazma önbelleği korur, başarılı yazma tablonun kayıtlarını siler
+        assert_eq!(cache.written("DELETE FROM users WHERE id = $1", Err::<u64, _>(())), Err(()));
+        assert_eq!(cache.provider().len(), 3);
+        assert_eq!(cache.written("UPDATE posts SET id = $1", Ok::<_, ()>(1)), Ok(1));
+        assert_eq!(cache.provider().len(), 3);
+        assert_eq!(cache.written("DELETE FROM users WHERE id = $1", Ok::<_, ()>(1)), Ok(1));
+        assert!(cache.provider().is_empty());
+        assert_eq!(cache.fetch(SQL, id(1).as_deref(), || fetch(1)), Ok(1));
+        assert_eq!(runs.get(), 5);
+    }
+
+    #[test]
+    fn skips_results_read_before_an_invalidation() {
+        const SQL: &str = "SELECT id FROM users WHERE id = $1";
+        let cache = ReadThrough::new(MemoryCache::new(), "TEST");
+
+        // Okuma sürerken tablo yazılırsa eski sonuç döner ama saklanmaz
+        let read = cache.fetch(SQL, Some(&[1]), || {
+            cache.written("UPDATE Users SET id = $1", Ok::<_, ()>(1)).map(|_| 1)
+        });
+        assert_eq!(read, Ok(1));
+        assert!(cache.provider().is_empty());
+
+        assert_eq!(cache.fetch(SQL, Some(&[1]), || Ok::<_, ()>(2)), Ok(2));
+        assert_eq!(cache.provider().len(), 1);
+    }
+
+    #[test]
+    fn invalidates_entries_reading_a_table() {
+        let cache = MemoryCache::new();
+        cache.set(CacheKey::of::<i32>("a", &[]), vec![("users".into(), 0)], Arc::new(1));
+        cache.set(CacheKey::of::<i32>("b", &[]), vec![("posts".into(), 0)], Arc::new(2));
+
+        cache.invalidate("users");
+
+        assert!(cache.get(&CacheKey::of::<i32>("a", &[])).is_none());
+        assert!(cache.get(&CacheKey::of::<i32>("b", &[])).is_some());
+        assert_eq!(cache.generation("USERS"), 1);
+        assert_eq!(cache.generation("posts"), 0);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn encodes_postgres_params() {
+        // Debug çıktısı aynı olan değerler bile farklı kodlanır
+        assert_ne!(encode_postgres_params(&[&1i32]), encode_postgres_params(&[&1i64]));
+        assert_ne!(encode_postgres_params(&[&"1"]), encode_postgres_params(&[&1i32]));
+        assert_ne!(encode_postgres_params(&[&"a", &"bc"]), encode_postgres_params(&[&"ab", &"c"]));
+        assert_ne!(encode_postgres_params(&[&None::<i32>]), encode_postgres_params(&[&0i32]));
+        assert_eq!(encode_postgres_params(&[&"ali", &1i16]), encode_postgres_params(&[&String::from("ali"), &1i16]));
+        assert_eq!(encode_postgres_params(&[]), Some(Vec::new()));
+    }
+}
@@ -0,0 +1,84 @@
+//! Constraint violations reported by the database.
+//!
+//! The PostgreSQL backends classify the SQLSTATE of a failed statement with
+//! [`ConstraintKind::from_sqlstate`] and hand out a [`ConstraintError`] naming the
+//! violated constraint, so that application code can turn e.g. a duplicate e-mail
+//! into a form error without parsing messages.
+
+use std::error::Error as StdError;
+use std::fmt;
+
+/// The kind of a violated constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConstraintKind {
+    /// `UNIQUE` or primary key (`23505`)
+    Unique,
+    /// `REFERENCES` (`23503`)
+    ForeignKey,
+    /// `CHECK` (`23514`)
+    Check,
+    /// `NOT NULL` (`23502`)
+    NotNull,
+    /// `EXCLUDE` (`23P01`)
+    Exclusion,
+}
+
+impl ConstraintKind {
+    /// The kind behind a PostgreSQL SQLSTATE, or `None` for other errors.
+    pub fn from_sqlstate(code: &str) -> Option<Self> {
+        match code {
+            "23505" => Some(ConstraintKind::Unique),
+            "23503" => Some(ConstraintKind::ForeignKey),
+            "23514" => Some(ConstraintKind::Check),
+            "23502" => Some(ConstraintKind::NotNull),
+            "23P01" => Some(ConstraintKind::Exclusion),
+            _ => None,
+        }
+    }
+}
+
+/// A statement was rejected by a constraint; nothing was written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintError {
+    /// What kind of constraint was violated
+    pub kind: ConstraintKind,
+    /// Name of the constraint, e.g. `users_email_key`; PostgreSQL doesn't name
+    /// `NOT NULL` constraints
+    pub constraint_name: Option<String>,
+    /// Table the constraint belongs to
+    pub table: Option<String>,
+    /// Column of a `NOT NULL` violation
+    pub column: Option<String>,
+    /// The database's message
+    pub message: String,
+}
+
+impl ConstraintError {
+    /// Returns whether `name` is the violated constraint.
+    pub fn is(&self, name: &str) -> bool {
+        self.constraint_name.as_deref() == Some(name)
+    }
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.constraint_name {
+            Some(name) => write!(f, "{:?} constraint `{}` violated: {}", self.kind, name, self.message),
+            None => write!(f, "{:?} constraint violated: {}", self.kind, self.message),
+        }
+    }
+}
+
+impl StdError for ConstraintError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_integrity_sqlstates() {
+        assert_eq!(ConstraintKind::from_sqlstate("23505"), Some(ConstraintKind::Unique));
+        assert_eq!(ConstraintKind::from_sqlstate("23P01"), Some(ConstraintKind::Exclusion));
+        assert_eq!(ConstraintKind::from_sqlstate("40001"), None);
+    }
+}
@@ -0,0 +1,206 @@
+//! Statements driven by the WHERE clause of a `Queryable`.
+//!
+//! A `Queryable` that selects the rows to work on already holds the condition; the
+//! backends' `delete_where` and `update_where` reuse it for `DELETE` and `UPDATE`, so
+//! the same filter isn't repeated on a `Deletable` or `Updateable` type.
+//!
+//! The `fetch_all_combined` functions AND-combine the WHERE clauses of several such
+//! filters into one SELECT with [`with_conditions`], so a filter written once
+//! (`ByState`, `CreatedBetween`) can narrow any query over the same table.
+
+use crate::batch::{shift_placeholders, Dialect};
+use crate::debug::placeholder_count;
+use crate::paging::top_level_keyword;
+use crate::SqlQuery;
+use std::any::type_name;
+use std::fmt;
+
+/// Clauses that follow WHERE in a SELECT.
+const AFTER_WHERE: &[&str] = &["GROUP", "HAVING", "WINDOW", "ORDER", "LIMIT", "OFFSET", "FETCH", "FOR"];
+
+/// Number of the criteria's parameters the WHERE clause binds, i.e. how many of its
+/// `params()` come before those of `HAVING`, `LIMIT` or `OFFSET`.
+pub fn where_param_count(where_clause: &str) -> usize {
+    placeholder_count(where_clause)
+}
+
+/// `DELETE FROM table WHERE where_clause`.
+pub fn delete_statement(table: &str, where_clause: &str) -> String {
+    format!("DELETE FROM {} WHERE {}", table, where_clause)
+}
+
+/// `UPDATE table SET column = $1, ... WHERE where_clause` for the given `columns`.
+///
+/// The values of the columns are bound first, so the placeholders of the WHERE clause
+/// are shifted past them. Returns `None` when there is no column to set.
+pub fn update_statement(table: &str, columns: &[&str], where_clause: &str, dialect: Dialect) -> Option<String> {
+    if columns.is_empty() {
+        return None;
+    }
+    let marker = dialect.placeholder();
+    let assignments = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| format!("{} = {}{}", column, marker, i + 1))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!(
+        "UPDATE {} SET {} WHERE {}",
+        table,
+        assignments,
+        shift_placeholders(where_clause, columns.len(), dialect)
+    ))
+}
+
+/// Adds `conditions` to the WHERE clause of `select`, joined with `AND`.
+///
+/// Every condition is numbered from the first placeholder, like the clause
+/// [`SqlQuery::criteria`](crate::SqlQuery::criteria) returns; its placeholders are
+/// shifted past the `bound` parameters of `select` and of the conditions before it.
+/// An existing WHERE clause is kept, parenthesized, and GROUP BY, ORDER BY, LIMIT and
+/// the like stay after the combined clause. Returns `None` for a `UNION`,
+/// `INTERSECT` or `EXCEPT`, whose parts each have their own WHERE clause.
+pub fn with_conditions(select: &str, bound: usize, conditions: &[&str], dialect: Dialect) -> Option<String> {
+    if top_level_keyword(select, &["UNION", "INTERSECT", "EXCEPT"]).is_some() {
+        return None;
+    }
+    let select = select.trim_end().trim_end_matches(';');
+    if conditions.is_empty() {
+        return Some(select.to_string());
+    }
+
+    let mut offset = bound;
+    let combined = conditions
+        .iter()
+        .map(|condition| {
+            let shifted = format!("({})", shift_placeholders(condition.trim(), offset, dialect));
+            offset += placeholder_count(condition);
+            shifted
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ");
+
+    let end = top_level_keyword(select, AFTER_WHERE).map_or(select.len(), |(_, at)| at);
+    let head = select[..end].trim_end();
+    let filtered = match top_level_keyword(head, &["WHERE"]) {
+        Some((_, at)) => format!(
+            "{} WHERE ({}) AND {}",
+            head[..at].trim_end(),
+            head[at + "WHERE".len()..].trim(),
+            combined
+        ),
+        None => format!("{} WHERE {}", head, combined),
+    };
+    match select[end..].trim() {
+        "" => Some(filtered),
+        tail => Some(format!("{} {}", filtered, tail)),
+    }
+}
+
+/// The query of `T` can't be narrowed by the filters of `fetch_all_combined`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CombineError {
+    /// The query binds parameters of its own; the filters must hold every condition.
+    BoundParams(&'static str),
+    /// The query combines SELECTs with `UNION`, `INTERSECT` or `EXCEPT`.
+    Compound(&'static str),
+}
+
+impl fmt::Display for CombineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CombineError::BoundParams(name) => {
+                write!(f, "`{}` binds parameters of its own; the filters must hold every condition", name)
+            }
+            CombineError::Compound(name) => write!(f, "`{}` combines SELECTs with UNION / INTERSECT / EXCEPT", name),
+        }
+    }
+}
+
+impl std::error::Error for CombineError {}
+
+/// The query of `T` narrowed by `conditions` with [`with_conditions`].
+pub fn combined_query<T: SqlQuery>(conditions: &[&str], dialect: Dialect) -> Result<String, CombineError> {
+    if T::param_count() > 0 {
+        return Err(CombineError::BoundParams(type_name::<T>()));
+    }
+    with_conditions(T::query(), 0, conditions, dialect).ok_or(CombineError::Compound(type_name::<T>()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statements_reuse_the_where_clause() {
+        let where_clause = "state = $1 AND last_login < $2";
+        assert_eq!(where_param_count(where_clause), 2);
+        assert_eq!(
+            delete_statement("users", where_clause),
+            "DELETE FROM users WHERE state = $1 AND last_login < $2"
+        );
+        assert_eq!(
+            update_statement("users", &["state"], where_clause, Dialect::Postgres).unwrap(),
+            "UPDATE users SET state = $1 WHERE state = $2 AND last_login < $3"
+        );
+        assert_eq!(
+            update_statement("users", &["state", "note"], "state = ?1", Dialect::Sqlite).unwrap(),
+            "UPDATE users SET state = ?1, note = ?2 WHERE state = ?3"
+        );
+        assert!(update_statement("users", &[], where_clause, Dialect::Postgres).is_none());
+    }
+
+    #[test]
+    fn combines_conditions() {
+        let conditions = ["state = $1", "created_at >= $1 AND created_at < $2"];
+        assert_eq!(
+            with_conditions("SELECT id, name FROM users ORDER BY id", 0, &conditions, Dialect::Postgres).unwrap(),
+            "SELECT id, name FROM users WHERE (state = $1) AND (created_at >= $2 AND created_at < $3) ORDER BY id"
+        );
+        assert_eq!(
+            with_conditions("SELECT id FROM users WHERE deleted_at IS NULL OR id = ?1 LIMIT 10", 1, &["state = ?1"], Dialect::Sqlite)
+                .unwrap(),
+            "SELECT id FROM users WHERE (deleted_at IS NULL OR id = ?1) AND (state = ?2) LIMIT 10"
+        );
+        // Alt sorgudaki ORDER BY ana sorgunun koşulunu bölmez
+        assert_eq!(
+            with_conditions("SELECT id FROM (SELECT id, state FROM users ORDER BY id) u", 0, &["state = $1"], Dialect::Postgres)
+                .unwrap(),
+            "SELECT id FROM (SELECT id, state FROM users ORDER BY id) u WHERE (state = $1)"
+        );
+        assert_eq!(with_conditions("SELECT id FROM users UNION SELECT id FROM admins", 0, &conditions, Dialect::Postgres), None);
+    }
+
+    struct Active;
+    struct ByState;
+    struct Everyone;
+
+    impl SqlQuery for Active {
+        fn query() -> &'static str {
+            "SELECT id FROM users ORDER BY id"
+        }
+    }
+
+    impl SqlQuery for ByState {
+        fn query() -> &'static str {
+            "SELECT id FROM users WHERE state = $1"
+        }
+    }
+
+    impl SqlQuery for Everyone {
+        fn query() -> &'static str {
+            "SELECT id FROM users UNION SELECT id FROM admins"
+        }
+    }
+
+    #[test]
+    fn combined_query_rejects_bound_and_compound_queries() {
+        assert_eq!(
+            combined_query::<Active>(&["state = $1", "name LIKE $1"], Dialect::Postgres).unwrap(),
+            "SELECT id FROM users WHERE (state = $1) AND (name LIKE $2) ORDER BY id"
+        );
+        let error = combined_query::<ByState>(&["name LIKE $1"], Dialect::Postgres).unwrap_err();
+        assert!(matches!(error, CombineError::BoundParams(name) if name.ends_with("ByState")));
+        assert!(matches!(combined_query::<Everyone>(&[], Dialect::Postgres), Err(CombineError::Compound(_))));
+    }
+}
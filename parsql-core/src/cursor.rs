@@ -0,0 +1,120 @@
+//! Server-side cursors for paging through a consistent snapshot.
+//!
+//! OFFSET paging reruns the query for every page, so rows inserted or deleted between
+//! two requests shift the pages: an export may skip or repeat rows. The backends'
+//! `open_cursor` instead declares a PostgreSQL cursor `WITH HOLD` for the query of a
+//! `Queryable`; the result is fixed when the cursor is declared and `fetch_next` reads
+//! it page by page, however the table changes meanwhile.
+//!
+//! A held cursor belongs to the database session that declared it and keeps its result
+//! on the server until it is closed or the session ends. `fetch_next` closes it once a
+//! page comes back short; a client that stops early should call `close_cursor`.
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PREFIX: &str = "parsql_cursor_";
+
+/// Opaque name of an open cursor.
+///
+/// Its string form can be handed to a client and parsed back with [`str::parse`] on the
+/// next request; only names of this form are accepted, since the name is written into
+/// the statements.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PageToken(String);
+
+impl PageToken {
+    /// A new, practically unique cursor name.
+    pub fn generate() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        // Her çağrıda yeni anahtarlanan hasher rastgelelik sağlar; sayaç aynı anda açılan imleçleri ayırır
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+        PageToken(format!("{}{:016x}", PREFIX, hasher.finish()))
+    }
+
+    /// The cursor name.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PageToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The string is not a token made by [`PageToken::generate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPageToken;
+
+impl fmt::Display for InvalidPageToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid page token")
+    }
+}
+
+impl std::error::Error for InvalidPageToken {}
+
+impl FromStr for PageToken {
+    type Err = InvalidPageToken;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        match token.strip_prefix(PREFIX) {
+            Some(id) if id.len() == 16 && id.bytes().all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase()) => {
+                Ok(PageToken(token.to_string()))
+            }
+            _ => Err(InvalidPageToken),
+        }
+    }
+}
+
+/// `DECLARE <token> NO SCROLL CURSOR WITH HOLD FOR <query>`.
+pub fn declare_statement(token: &PageToken, query: &str) -> String {
+    format!(
+        "DECLARE {} NO SCROLL CURSOR WITH HOLD FOR {}",
+        token,
+        query.trim_end().trim_end_matches(';')
+    )
+}
+
+/// Reads the next `count` rows of the cursor.
+pub fn fetch_statement(token: &PageToken, count: u32) -> String {
+    format!("FETCH FORWARD {} FROM {}", count, token)
+}
+
+/// Closes the cursor and frees its result on the server.
+pub fn close_statement(token: &PageToken) -> String {
+    format!("CLOSE {}", token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_round_trip() {
+        let token = PageToken::generate();
+        assert_ne!(token, PageToken::generate());
+        assert_eq!(token.to_string().parse::<PageToken>(), Ok(token));
+        assert_eq!("parsql_cursor_1; DROP TABLE users".parse::<PageToken>(), Err(InvalidPageToken));
+        assert_eq!("users".parse::<PageToken>(), Err(InvalidPageToken));
+    }
+
+    #[test]
+    fn cursor_statements() {
+        let token: PageToken = "parsql_cursor_00000000000000ff".parse().unwrap();
+        assert_eq!(
+            declare_statement(&token, "SELECT id FROM users WHERE state = $1 ORDER BY id;"),
+            "DECLARE parsql_cursor_00000000000000ff NO SCROLL CURSOR WITH HOLD FOR SELECT id FROM users WHERE state = $1 ORDER BY id"
+        );
+        assert_eq!(fetch_statement(&token, 100), "FETCH FORWARD 100 FROM parsql_cursor_00000000000000ff");
+        assert_eq!(close_statement(&token), "CLOSE parsql_cursor_00000000000000ff");
+    }
+}
@@ -0,0 +1,163 @@
+//! SQL previews with the bound values filled in.
+//!
+//! The output is meant for logs and error messages only. Values are quoted so the
+//! preview reads like a runnable statement, but it must never be sent to a database.
+
+use std::fmt;
+
+/// Quotes a string as an SQL literal, doubling embedded single quotes.
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Turns the `Debug` output of a bound value into an SQL literal.
+///
+/// PostgreSQL parameters only expose `Debug`, so the literal is derived from it:
+/// `None` becomes `NULL`, `Some(x)` is unwrapped, numbers and booleans are kept and
+/// everything else (strings, dates, UUIDs, ...) is quoted.
+pub fn literal_from_debug(debug: &str) -> String {
+    if debug == "None" {
+        return "NULL".to_string();
+    }
+    if let Some(inner) = debug.strip_prefix("Some(").and_then(|rest| rest.strip_suffix(')')) {
+        return literal_from_debug(inner);
+    }
+    if debug == "true" || debug == "false" || debug.parse::<f64>().is_ok() {
+        return debug.to_string();
+    }
+    if debug.len() >= 2 && debug.starts_with('"') && debug.ends_with('"') {
+        let unescaped = debug[1..debug.len() - 1]
+            .replace("\\\"", "\"")
+            .replace("\\'", "'")
+            .replace("\\\\", "\\");
+        return quote_literal(&unescaped);
+    }
+    quote_literal(debug)
+}
+
+/// Replaces the `$n` / `?n` placeholders of `sql` with `values[n - 1]`.
+///
+/// Placeholders inside quoted literals and placeholders without a matching value
+/// are left untouched.
+pub fn interpolate(sql: &str, values: &[String]) -> String {
+    let mut result = String::with_capacity(sql.len() + values.iter().map(String::len).sum::<usize>());
+    let mut chars = sql.chars().peekable();
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '$' || c == '?' => {
+                let mut digits = String::new();
+                while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                    digits.push(d);
+                }
+                let value = digits
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|i| values.get(i));
+                match value {
+                    Some(value) => result.push_str(value),
+                    None => {
+                        result.push(c);
+                        result.push_str(&digits);
+                    }
+                }
+                continue;
+            }
+            None => {}
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+/// Body of the backends' `debug_sql` methods: `sql` with each placeholder replaced by the
+/// literal made of the matching value with [`literal_from_debug`].
+pub fn debug_sql<V: fmt::Debug>(sql: &str, values: impl IntoIterator<Item = V>) -> String {
+    debug_sql_with(sql, values, |value| literal_from_debug(&format!("{:?}", value)))
+}
+
+/// [`debug_sql`] for backends that build the literals themselves, e.g. SQLite, whose
+/// parameters can be converted to their stored value instead of going through `Debug`.
+pub fn debug_sql_with<V>(
+    sql: &str,
+    values: impl IntoIterator<Item = V>,
+    literal: impl FnMut(V) -> String,
+) -> String {
+    let values: Vec<String> = values.into_iter().map(literal).collect();
+    interpolate(sql, &values)
+}
+
+/// Number of parameters `sql` binds: the highest `$n` / `?n` placeholder outside
+/// quoted literals.
+pub fn placeholder_count(sql: &str) -> usize {
+    let mut count = 0;
+    let mut chars = sql.chars().peekable();
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '$' || c == '?' => {
+                let mut digits = String::new();
+                while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                    digits.push(d);
+                }
+                count = count.max(digits.parse().unwrap_or(0));
+            }
+            None => {}
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_placeholders_outside_literals() {
+        let sql = "SELECT * FROM users WHERE name = $1 AND note <> '$2' AND state = $2";
+        let values = vec![quote_literal("O'Brien"), "1".to_string()];
+        assert_eq!(
+            interpolate(sql, &values),
+            "SELECT * FROM users WHERE name = 'O''Brien' AND note <> '$2' AND state = 1"
+        );
+    }
+
+    #[test]
+    fn counts_placeholders_outside_literals() {
+        assert_eq!(placeholder_count("SELECT * FROM users WHERE name = $1 AND note <> '$3' AND state = $2"), 2);
+        assert_eq!(placeholder_count("UPDATE users SET name = ?2 WHERE id = ?1"), 2);
+        assert_eq!(placeholder_count("SELECT 1"), 0);
+    }
+
+    #[test]
+    fn debug_sql_previews() {
+        let values: [&dyn fmt::Debug; 2] = [&Some("O'Brien"), &None::<i32>];
+        assert_eq!(
+            debug_sql("UPDATE users SET name = $1 WHERE id = $2", values),
+            "UPDATE users SET name = 'O''Brien' WHERE id = NULL"
+        );
+        assert_eq!(
+            debug_sql_with("SELECT * FROM users WHERE id = ?1", [7], |id| format!("{}", id * 6)),
+            "SELECT * FROM users WHERE id = 42"
+        );
+    }
+
+    #[test]
+    fn literals_from_debug_output() {
+        assert_eq!(literal_from_debug("42"), "42");
+        assert_eq!(literal_from_debug("None"), "NULL");
+        assert_eq!(literal_from_debug("Some(\"it's\")"), "'it''s'");
+        assert_eq!(literal_from_debug("2024-01-01T10:00:00"), "'2024-01-01T10:00:00'");
+    }
+}
@@ -0,0 +1,103 @@
+//! Canonical SQL text and stable fingerprints for generated queries.
+//!
+//! Two queries that differ only in keyword casing or whitespace get the same
+//! canonical form and therefore the same fingerprint, so metrics and
+//! statement-level tracking can key on one identifier per query struct. The
+//! derive macros always emit clauses in the same order, so the canonical form only
+//! has to normalise the text itself.
+
+/// Keywords written in upper case by [`canonicalize`].
+const KEYWORDS: &[&str] = &[
+    "ALL", "AND", "AS", "ASC", "BETWEEN", "BY", "CASE", "CONFLICT", "CROSS", "DELETE", "DESC",
+    "DISTINCT", "DO", "ELSE", "END", "EXCEPT", "EXISTS", "FALSE", "FROM", "FULL", "GROUP",
+    "HAVING", "ILIKE", "IN", "INNER", "INSERT", "INTERSECT", "INTO", "IS", "JOIN", "LEFT",
+    "LIKE", "LIMIT", "NOT", "NOTHING", "NULL", "OFFSET", "ON", "OR", "ORDER", "OUTER",
+    "RETURNING", "RIGHT", "SELECT", "SET", "THEN", "TRUE", "UNION", "UPDATE", "USING", "VALUES",
+    "WHEN", "WHERE", "WITH",
+];
+
+/// Returns `sql` in canonical form: keywords in upper case, runs of whitespace
+/// collapsed to a single space, no space inside parentheses or before a comma, one
+/// space after a comma and no trailing semicolon.
+///
+/// Quoted literals and identifiers are copied unchanged.
+pub fn canonicalize(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut space = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            space = true;
+            continue;
+        }
+        if (space || out.ends_with(',')) && !out.is_empty() && !out.ends_with('(') && c != ')' && c != ',' {
+            out.push(' ');
+        }
+        space = false;
+
+        match c {
+            '\'' | '"' => {
+                out.push(c);
+                // Kaçışlı tırnaklar ('') kapanıp yeniden açılan iki parça olarak kopyalanır
+                for d in chars.by_ref() {
+                    out.push(d);
+                    if d == c {
+                        break;
+                    }
+                }
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some(d) = chars.next_if(|d| d.is_alphanumeric() || *d == '_') {
+                    word.push(d);
+                }
+                let upper = word.to_ascii_uppercase();
+                if KEYWORDS.contains(&upper.as_str()) {
+                    out.push_str(&upper);
+                } else {
+                    out.push_str(&word);
+                }
+            }
+            c => out.push(c),
+        }
+    }
+
+    while out.ends_with(';') || out.ends_with(' ') {
+        out.pop();
+    }
+    out
+}
+
+/// Returns a fingerprint of the canonical form of `sql`.
+///
+/// The hash (64-bit FNV-1a) doesn't depend on the Rust version or the process, so
+/// it can be stored and compared across deployments.
+pub fn fingerprint(sql: &str) -> u64 {
+    canonicalize(sql).bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalises_casing_and_whitespace() {
+        assert_eq!(
+            canonicalize("select id ,name\n  from  users where ( id = $1 ) and name = 'a  b';"),
+            "SELECT id, name FROM users WHERE (id = $1) AND name = 'a  b'"
+        );
+        assert_eq!(canonicalize("SELECT \"From\" FROM t"), "SELECT \"From\" FROM t");
+    }
+
+    #[test]
+    fn equivalent_queries_share_a_fingerprint() {
+        assert_eq!(
+            fingerprint("SELECT id FROM users WHERE id = $1"),
+            fingerprint("select id\n\tfrom users where id = $1;")
+        );
+        assert_ne!(fingerprint("SELECT id FROM users"), fingerprint("SELECT id FROM posts"));
+    }
+}
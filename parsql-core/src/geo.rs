@@ -0,0 +1,308 @@
+//! PostGIS geometry encoding (`postgis` feature).
+//!
+//! PostGIS sends and receives `geometry` and `geography` values in its binary format,
+//! EWKB: well-known binary with an optional SRID. [`encode`] and [`decode`] convert
+//! between it and [`geo_types::Geometry`]; the PostgreSQL backends wrap them in the
+//! `PostGis<T>` column type.
+//!
+//! Z and M ordinates are read but dropped, as `geo-types` is two-dimensional.
+
+use std::fmt;
+
+use geo_types::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point, Polygon,
+};
+
+pub use geo_types;
+
+/// Bit of the EWKB type word set when an SRID follows it.
+const SRID_FLAG: u32 = 0x2000_0000;
+/// Bits of the EWKB type word marking Z and M ordinates.
+const Z_FLAG: u32 = 0x8000_0000;
+const M_FLAG: u32 = 0x4000_0000;
+
+/// A value that is not valid EWKB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeoError(String);
+
+impl GeoError {
+    fn new(message: impl Into<String>) -> Self {
+        GeoError(message.into())
+    }
+}
+
+impl fmt::Display for GeoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid PostGIS value: {}", self.0)
+    }
+}
+
+impl std::error::Error for GeoError {}
+
+/// Encodes `geometry` as little-endian EWKB, with `srid` when given.
+///
+/// `Line`, `Rect` and `Triangle` have no WKB type of their own and are written as a
+/// `LineString` and `Polygon`s.
+pub fn encode(geometry: &Geometry<f64>, srid: Option<i32>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(32);
+    write_geometry(&mut out, geometry, srid);
+    out
+}
+
+/// Decodes an EWKB value into the geometry and its SRID, if it carries one.
+pub fn decode(bytes: &[u8]) -> Result<(Geometry<f64>, Option<i32>), GeoError> {
+    let mut reader = Reader { bytes, little_endian: true };
+    let (geometry, srid) = reader.geometry()?;
+    if !reader.bytes.is_empty() {
+        return Err(GeoError::new(format!("{} trailing bytes", reader.bytes.len())));
+    }
+    Ok((geometry, srid))
+}
+
+/// Name of the WKB type of a geometry, used in error messages.
+fn type_name(geometry: &Geometry<f64>) -> &'static str {
+    match geometry {
+        Geometry::Point(_) => "Point",
+        Geometry::Line(_) | Geometry::LineString(_) => "LineString",
+        Geometry::Polygon(_) | Geometry::Rect(_) | Geometry::Triangle(_) => "Polygon",
+        Geometry::MultiPoint(_) => "MultiPoint",
+        Geometry::MultiLineString(_) => "MultiLineString",
+        Geometry::MultiPolygon(_) => "MultiPolygon",
+        Geometry::GeometryCollection(_) => "GeometryCollection",
+    }
+}
+
+fn write_header(out: &mut Vec<u8>, kind: u32, srid: Option<i32>) {
+    out.push(1);
+    match srid {
+        Some(srid) => {
+            out.extend_from_slice(&(kind | SRID_FLAG).to_le_bytes());
+            out.extend_from_slice(&srid.to_le_bytes());
+        }
+        None => out.extend_from_slice(&kind.to_le_bytes()),
+    }
+}
+
+fn write_coord(out: &mut Vec<u8>, coord: Coord<f64>) {
+    out.extend_from_slice(&coord.x.to_le_bytes());
+    out.extend_from_slice(&coord.y.to_le_bytes());
+}
+
+fn write_count(out: &mut Vec<u8>, count: usize) {
+    let count = u32::try_from(count).expect("geometry has more parts than EWKB can count");
+    out.extend_from_slice(&count.to_le_bytes());
+}
+
+fn write_line(out: &mut Vec<u8>, line: &LineString<f64>) {
+    write_count(out, line.0.len());
+    line.0.iter().for_each(|coord| write_coord(out, *coord));
+}
+
+fn write_polygon(out: &mut Vec<u8>, polygon: &Polygon<f64>) {
+    // WKB'de boş poligonun hiç halkası yoktur
+    if polygon.exterior().0.is_empty() {
+        return write_count(out, 0);
+    }
+    write_count(out, 1 + polygon.interiors().len());
+    write_line(out, polygon.exterior());
+    polygon.interiors().iter().for_each(|ring| write_line(out, ring));
+}
+
+// Çoklu geometrilerin parçaları kendi başlıklarıyla, SRID'siz yazılır
+fn write_geometry(out: &mut Vec<u8>, geometry: &Geometry<f64>, srid: Option<i32>) {
+    match geometry {
+        Geometry::Point(point) => {
+            write_header(out, 1, srid);
+            write_coord(out, point.0);
+        }
+        Geometry::Line(line) => {
+            write_header(out, 2, srid);
+            write_line(out, &LineString::from(vec![line.start, line.end]));
+        }
+        Geometry::LineString(line) => {
+            write_header(out, 2, srid);
+            write_line(out, line);
+        }
+        Geometry::Polygon(polygon) => {
+            write_header(out, 3, srid);
+            write_polygon(out, polygon);
+        }
+        Geometry::Rect(rect) => {
+            write_header(out, 3, srid);
+            write_polygon(out, &rect.to_polygon());
+        }
+        Geometry::Triangle(triangle) => {
+            write_header(out, 3, srid);
+            write_polygon(out, &triangle.to_polygon());
+        }
+        Geometry::MultiPoint(points) => {
+            write_header(out, 4, srid);
+            write_count(out, points.0.len());
+            points.0.iter().for_each(|point| write_geometry(out, &Geometry::Point(*point), None));
+        }
+        Geometry::MultiLineString(lines) => {
+            write_header(out, 5, srid);
+            write_count(out, lines.0.len());
+            for line in &lines.0 {
+                write_header(out, 2, None);
+                write_line(out, line);
+            }
+        }
+        Geometry::MultiPolygon(polygons) => {
+            write_header(out, 6, srid);
+            write_count(out, polygons.0.len());
+            for polygon in &polygons.0 {
+                write_header(out, 3, None);
+                write_polygon(out, polygon);
+            }
+        }
+        Geometry::GeometryCollection(collection) => {
+            write_header(out, 7, srid);
+            write_count(out, collection.0.len());
+            collection.0.iter().for_each(|geometry| write_geometry(out, geometry, None));
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    little_endian: bool,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], GeoError> {
+        if self.bytes.len() < N {
+            return Err(GeoError::new("unexpected end of data"));
+        }
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, GeoError> {
+        let bytes = self.take::<4>()?;
+        Ok(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    fn f64(&mut self) -> Result<f64, GeoError> {
+        let bytes = self.take::<8>()?;
+        Ok(if self.little_endian { f64::from_le_bytes(bytes) } else { f64::from_be_bytes(bytes) })
+    }
+
+    fn coord(&mut self, extra: usize) -> Result<Coord<f64>, GeoError> {
+        let coord = Coord { x: self.f64()?, y: self.f64()? };
+        for _ in 0..extra {
+            self.f64()?;
+        }
+        Ok(coord)
+    }
+
+    /// Reads an element count, rejecting counts the remaining bytes can't hold.
+    fn count(&mut self, min_size: usize) -> Result<usize, GeoError> {
+        let count = self.u32()? as usize;
+        if count.saturating_mul(min_size) > self.bytes.len() {
+            return Err(GeoError::new(format!("count {} exceeds the data", count)));
+        }
+        Ok(count)
+    }
+
+    fn line(&mut self, extra: usize) -> Result<LineString<f64>, GeoError> {
+        let count = self.count(16)?;
+        (0..count).map(|_| self.coord(extra)).collect::<Result<Vec<_>, _>>().map(LineString)
+    }
+
+    fn polygon(&mut self, extra: usize) -> Result<Polygon<f64>, GeoError> {
+        let rings = self.count(4)?;
+        if rings == 0 {
+            return Ok(Polygon::new(LineString(Vec::new()), Vec::new()));
+        }
+        let exterior = self.line(extra)?;
+        let interiors = (1..rings).map(|_| self.line(extra)).collect::<Result<Vec<_>, _>>()?;
+        Ok(Polygon::new(exterior, interiors))
+    }
+
+    fn parts<T>(&mut self, expected: &str, pick: impl Fn(Geometry<f64>) -> Option<T>) -> Result<Vec<T>, GeoError> {
+        let count = self.count(5)?;
+        (0..count)
+            .map(|_| {
+                let (geometry, _) = self.geometry()?;
+                let found = type_name(&geometry);
+                pick(geometry).ok_or_else(|| GeoError::new(format!("expected a {} part, found a {}", expected, found)))
+            })
+            .collect()
+    }
+
+    fn geometry(&mut self) -> Result<(Geometry<f64>, Option<i32>), GeoError> {
+        self.little_endian = match self.take::<1>()?[0] {
+            0 => false,
+            1 => true,
+            order => return Err(GeoError::new(format!("unknown byte order {}", order))),
+        };
+        let kind = self.u32()?;
+        let srid = if kind & SRID_FLAG != 0 { Some(i32::from_ne_bytes(self.u32()?.to_ne_bytes())) } else { None };
+        let extra = usize::from(kind & Z_FLAG != 0) + usize::from(kind & M_FLAG != 0);
+
+        let geometry = match kind & 0x0fff_ffff {
+            1 => Geometry::Point(Point(self.coord(extra)?)),
+            2 => Geometry::LineString(self.line(extra)?),
+            3 => Geometry::Polygon(self.polygon(extra)?),
+            4 => Geometry::MultiPoint(MultiPoint(self.parts("Point", |g| Point::try_from(g).ok())?)),
+            5 => Geometry::MultiLineString(MultiLineString(
+                self.parts("LineString", |g| LineString::try_from(g).ok())?,
+            )),
+            6 => Geometry::MultiPolygon(MultiPolygon(self.parts("Polygon", |g| Polygon::try_from(g).ok())?)),
+            7 => Geometry::GeometryCollection(GeometryCollection(self.parts("geometry", Some)?)),
+            other => return Err(GeoError::new(format!("unsupported geometry type {}", other))),
+        };
+        Ok((geometry, srid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo_types::{line_string, point, polygon};
+
+    #[test]
+    fn encodes_a_point_with_srid() {
+        let bytes = encode(&Geometry::Point(point!(x: 1.0, y: 2.0)), Some(4326));
+
+        // ST_AsEWKB('SRID=4326;POINT(1 2)')
+        let expected = "0101000020e6100000000000000000f03f0000000000000040";
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, expected);
+        assert_eq!(decode(&bytes).unwrap(), (Geometry::Point(point!(x: 1.0, y: 2.0)), Some(4326)));
+    }
+
+    #[test]
+    fn round_trips_collections() {
+        let square = polygon![(x: 0.0, y: 0.0), (x: 4.0, y: 0.0), (x: 4.0, y: 4.0), (x: 0.0, y: 0.0)];
+        let geometries = [
+            Geometry::LineString(line_string![(x: 0.0, y: 0.0), (x: 1.5, y: -2.5)]),
+            Geometry::Polygon(square.clone()),
+            Geometry::MultiPoint(MultiPoint(vec![point!(x: 1.0, y: 1.0), point!(x: 2.0, y: 2.0)])),
+            Geometry::MultiPolygon(MultiPolygon(vec![square.clone(), square])),
+            Geometry::GeometryCollection(GeometryCollection(vec![Geometry::Point(point!(x: 3.0, y: 4.0))])),
+        ];
+        for geometry in geometries {
+            assert_eq!(decode(&encode(&geometry, None)).unwrap(), (geometry, None));
+        }
+    }
+
+    #[test]
+    fn reads_big_endian_and_drops_z() {
+        // ST_AsEWKB('POINT Z(1 2 3)', 'XDR')
+        let mut bytes = vec![0, 0x80, 0, 0, 1];
+        for value in [1.0f64, 2.0, 3.0] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        assert_eq!(decode(&bytes).unwrap(), (Geometry::Point(point!(x: 1.0, y: 2.0)), None));
+    }
+
+    #[test]
+    fn rejects_truncated_values() {
+        let bytes = encode(&Geometry::LineString(line_string![(x: 0.0, y: 0.0), (x: 1.0, y: 1.0)]), None);
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(&[1, 99, 0, 0, 0]).unwrap_err().to_string().contains("unsupported geometry type 99"));
+    }
+}
@@ -0,0 +1,60 @@
+//! Quoting of table and column names.
+//!
+//! The derive macros write table and column names as they are given. A reserved word
+//! (`order`, `user`, `group`), which the database would not accept bare, is quoted with
+//! the dialect's [`identifier_quotes`](crate::Dialect::identifier_quotes); the macros
+//! quote names with upper case letters too, e.g. `#[table("UserAccounts")]`, so that
+//! PostgreSQL does not fold them to lower case.
+
+use crate::Dialect;
+
+/// Words that can't be used as a bare table or column name: PostgreSQL's reserved
+/// key words, plus the SQLite key words that are rejected in the same positions.
+pub const RESERVED_WORDS: &[&str] = &[
+    "all", "analyse", "analyze", "and", "any", "array", "as", "asc", "asymmetric", "between",
+    "both", "by", "case", "cast", "check", "collate", "column", "constraint", "create", "cross",
+    "current_catalog", "current_date", "current_role", "current_time", "current_timestamp",
+    "current_user", "default", "deferrable", "delete", "desc", "distinct", "do", "drop", "else",
+    "end", "except", "exists", "false", "fetch", "for", "foreign", "from", "full", "grant", "group",
+    "having", "in", "index", "initially", "inner", "insert", "intersect", "into", "is", "join",
+    "lateral", "leading", "left", "like", "limit", "localtime", "localtimestamp", "natural", "not",
+    "null", "offset", "on", "only", "or", "order", "outer", "placing", "primary", "references",
+    "returning", "right", "select", "session_user", "set", "some", "symmetric", "system_user",
+    "table", "then", "to", "trailing", "true", "union", "unique", "update", "user", "using",
+    "values", "variadic", "when", "where", "window", "with",
+];
+
+/// Returns `true` for a word that must be quoted to be used as an identifier.
+pub fn is_reserved(word: &str) -> bool {
+    RESERVED_WORDS.iter().any(|reserved| reserved.eq_ignore_ascii_case(word))
+}
+
+/// Writes `ident` for use in SQL of `dialect`. Characters other than letters, digits
+/// and `_` are dropped; the result is quoted when it is a reserved word.
+pub fn quote_identifier(ident: &str, dialect: Dialect) -> String {
+    let safe = ident
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_')
+        .collect::<String>();
+    if is_reserved(&safe) {
+        let (open, close) = dialect.identifier_quotes();
+        format!("{}{}{}", open, safe, close)
+    } else {
+        safe
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_only_reserved_names() {
+        assert_eq!(quote_identifier("users", Dialect::Postgres), "users");
+        assert_eq!(quote_identifier("order", Dialect::Postgres), "\"order\"");
+        assert_eq!(quote_identifier("USER", Dialect::Sqlite), "\"USER\"");
+        assert_eq!(quote_identifier("Users", Dialect::Postgres), "Users");
+        assert_eq!(quote_identifier("createdAt", Dialect::Postgres), "createdAt");
+        assert_eq!(quote_identifier("user_name; drop", Dialect::Postgres), "user_namedrop");
+    }
+}
@@ -0,0 +1,67 @@
+//! Deferred constraints and trigger control inside a transaction.
+//!
+//! Bulk loads and data fixes often write rows in an order the foreign keys don't
+//! allow at every step, or must not fire the audit triggers of the tables they touch.
+//! The backends' `IntegrityControl` helpers run the statements built here on a
+//! transaction:
+//!
+//! - [`set_constraints`] defers the checks of `DEFERRABLE` constraints to the commit,
+//!   or runs the deferred checks right away. It only lasts until the transaction ends.
+//! - [`alter_triggers`] disables or enables the user-defined triggers of a table.
+//!   Unlike `SET CONSTRAINTS` this is a schema change: it takes an `ACCESS EXCLUSIVE`
+//!   lock on the table and stays in effect after the commit, so the triggers have to be
+//!   enabled again in the same transaction, after the deferred checks of the table ran.
+
+/// Quotes each dot separated part of a name, so it is matched case-sensitively and
+/// can't carry another statement.
+fn quote_name(name: &str) -> String {
+    name.split('.')
+        .map(|part| format!("\"{}\"", part.trim().replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// `SET CONSTRAINTS` for `constraints`, or for all deferrable constraints when the list
+/// is empty.
+///
+/// Deferred constraints are checked at the commit; `deferred = false` makes them
+/// immediate again and checks what was deferred so far at once.
+pub fn set_constraints(constraints: &[&str], deferred: bool) -> String {
+    let names = if constraints.is_empty() {
+        "ALL".to_string()
+    } else {
+        constraints.iter().map(|name| quote_name(name)).collect::<Vec<_>>().join(", ")
+    };
+    let mode = if deferred { "DEFERRED" } else { "IMMEDIATE" };
+    format!("SET CONSTRAINTS {} {}", names, mode)
+}
+
+/// `ALTER TABLE ... DISABLE TRIGGER USER` (or `ENABLE`) for `table`.
+///
+/// Only user-defined triggers are affected; the internal triggers that enforce foreign
+/// keys keep running, and changing them would require a superuser.
+pub fn alter_triggers(table: &str, enabled: bool) -> String {
+    let action = if enabled { "ENABLE" } else { "DISABLE" };
+    format!("ALTER TABLE {} {} TRIGGER USER", quote_name(table), action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constraint_statements() {
+        assert_eq!(set_constraints(&[], true), "SET CONSTRAINTS ALL DEFERRED");
+        assert_eq!(
+            set_constraints(&["fk_posts_user", "blog.fk_Comments"], false),
+            "SET CONSTRAINTS \"fk_posts_user\", \"blog\".\"fk_Comments\" IMMEDIATE"
+        );
+    }
+
+    #[test]
+    fn trigger_statements() {
+        assert_eq!(alter_triggers("posts", false), "ALTER TABLE \"posts\" DISABLE TRIGGER USER");
+        assert_eq!(alter_triggers("blog.posts", true), "ALTER TABLE \"blog\".\"posts\" ENABLE TRIGGER USER");
+        assert_eq!(alter_triggers("x\"; DROP", false), "ALTER TABLE \"x\"\"; DROP\" DISABLE TRIGGER USER");
+    }
+}
@@ -0,0 +1,25 @@
+//! Rows as JSON objects.
+//!
+//! The PostgreSQL backends' `fetch_json` / `fetch_all_json` (`json` feature) run the
+//! SELECT of a `Queryable` through [`row_to_json`], so the server turns every row into
+//! a JSON object keyed by column name. Generic endpoints such as admin grids can then
+//! return the rows without a `FromRow` struct, and every column type PostgreSQL can
+//! write as JSON is supported.
+
+/// Wraps `sql` so that it returns one JSON object per row, in the original order.
+pub fn row_to_json(sql: &str) -> String {
+    format!("SELECT row_to_json(parsql_row) FROM ({}) AS parsql_row", sql.trim_end().trim_end_matches(';'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_the_query() {
+        assert_eq!(
+            row_to_json("SELECT id, name FROM users WHERE state = $1 ORDER BY id;"),
+            "SELECT row_to_json(parsql_row) FROM (SELECT id, name FROM users WHERE state = $1 ORDER BY id) AS parsql_row"
+        );
+    }
+}
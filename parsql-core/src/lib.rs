@@ -0,0 +1,43 @@
+//! # parsql-core
+//!
+//! Database independent helpers shared by the parsql backends.
+//! This crate has no database dependency, apart from the TLS connectors of the optional
+//! `native-tls` / `rustls` features; backends re-export what their users need.
+
+pub mod audit_log;
+pub mod batch;
+pub mod cache;
+pub mod constraint;
+pub mod criteria;
+pub mod cursor;
+pub mod debug;
+pub mod encryption;
+pub mod fingerprint;
+#[cfg(feature = "postgis")]
+pub mod geo;
+pub mod identifier;
+pub mod integrity;
+pub mod json;
+pub mod locks;
+pub mod lookup;
+pub mod metrics;
+pub mod paging;
+pub mod partition;
+pub mod patch;
+pub mod query;
+pub mod registry;
+pub mod schema;
+pub mod session;
+pub mod temp;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+pub mod tls;
+pub mod trace;
+pub mod validation;
+pub mod value;
+pub mod views;
+
+pub use batch::{BatchOptions, Dialect, OnError, Progress};
+pub use constraint::{ConstraintError, ConstraintKind};
+pub use query::SqlQuery;
+pub use trace::set_trace;
+pub use validation::{Validate, ValidationError};
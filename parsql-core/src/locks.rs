@@ -0,0 +1,38 @@
+//! PostgreSQL advisory locks.
+//!
+//! The backends' `advisory_lock` / `try_advisory_lock` / `with_advisory_lock` helpers
+//! take a session-level lock on a 64-bit key with the statements below. Every
+//! application instance that uses the same key waits for (or skips) the others, which
+//! gives cross-instance critical sections such as singleton cron jobs.
+
+/// Waits until the lock on key `$1` is free and takes it.
+pub const LOCK: &str = "SELECT pg_advisory_lock($1)";
+
+/// Takes the lock on key `$1` if it is free; returns whether it was taken.
+pub const TRY_LOCK: &str = "SELECT pg_try_advisory_lock($1)";
+
+/// Releases the lock on key `$1`; returns `false` if the session did not hold it.
+pub const UNLOCK: &str = "SELECT pg_advisory_unlock($1)";
+
+/// Lock key for a name such as `"nightly-report"`.
+///
+/// The key is the 64-bit FNV-1a hash of the name, so every process derives the same
+/// key without sharing a list of numbers.
+pub fn lock_key(name: &str) -> i64 {
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    i64::from_ne_bytes(hash.to_ne_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_keys_are_stable() {
+        assert_eq!(lock_key(""), -0x340d_631b_7bdd_dcdb);
+        assert_eq!(lock_key("nightly-report"), lock_key("nightly-report"));
+        assert_ne!(lock_key("nightly-report"), lock_key("hourly-report"));
+    }
+}
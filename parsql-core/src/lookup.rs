@@ -0,0 +1,108 @@
+//! Bulk reads by primary key.
+//!
+//! The backends' `fetch_many` functions read the records of a list of keys with one
+//! statement, `WHERE id = ANY($1)` on PostgreSQL and `WHERE id IN (?1, ...)` lists of
+//! at most [`SQLITE_CHUNK`] keys on SQLite, the way an API resolving the authors of a
+//! page of posts needs them. The rows come back in no particular order; [`in_key_order`]
+//! puts them in the order of the keys and reports the keys that matched nothing.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::batch::Dialect;
+
+/// Keys bound by one SQLite statement, below the default `SQLITE_MAX_VARIABLE_NUMBER`
+/// of older SQLite builds (999).
+pub const SQLITE_CHUNK: usize = 500;
+
+/// Records read by `fetch_many`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookup<T, K> {
+    /// Records in the order of the requested keys, one per distinct key.
+    pub found: Vec<T>,
+    /// Requested keys without a record, in request order.
+    pub missing: Vec<K>,
+}
+
+impl<T, K> Lookup<T, K> {
+    /// Whether every requested key has a record.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// The requested keys without repetitions, in their first position.
+pub fn distinct<K: Eq + Hash>(keys: &[K]) -> Vec<&K> {
+    let mut seen = HashSet::with_capacity(keys.len());
+    keys.iter().filter(|key| seen.insert(*key)).collect()
+}
+
+/// `select WHERE column = ANY($1)` on PostgreSQL, `select WHERE column IN (?1, ..., ?count)`
+/// on SQLite.
+///
+/// `select` is the statement without a condition, `SELECT ... FROM ...`. PostgreSQL binds
+/// the keys as one array parameter, SQLite binds `count` parameters.
+pub fn keys_query(select: &str, column: &str, count: usize, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::Postgres => format!("{} WHERE {} = ANY($1)", select, column),
+        Dialect::Sqlite => {
+            let placeholders = (1..=count).map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ");
+            format!("{} WHERE {} IN ({})", select, column, placeholders)
+        }
+    }
+}
+
+/// Orders `records` by the position of their key in `keys` and lists the keys that have
+/// no record.
+///
+/// A key requested twice yields its record once, at its first position; a record whose
+/// key was not requested is dropped.
+pub fn in_key_order<T, K>(keys: &[K], records: Vec<T>, key_of: impl Fn(&T) -> &K) -> Lookup<T, K>
+where
+    K: Eq + Hash + Clone,
+{
+    let keys = distinct(keys);
+    let mut slots: Vec<Option<T>> = std::iter::repeat_with(|| None).take(keys.len()).collect();
+    let positions: HashMap<&K, usize> = keys.iter().enumerate().map(|(i, key)| (*key, i)).collect();
+    for record in records {
+        if let Some(&i) = positions.get(key_of(&record)) {
+            slots[i].get_or_insert(record);
+        }
+    }
+
+    let mut found = Vec::with_capacity(slots.len());
+    let mut missing = Vec::new();
+    for (key, slot) in keys.into_iter().zip(slots) {
+        match slot {
+            Some(record) => found.push(record),
+            None => missing.push(key.clone()),
+        }
+    }
+    Lookup { found, missing }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_bound_by_dialect() {
+        assert_eq!(
+            keys_query("SELECT id, name FROM users", "id", 3, Dialect::Postgres),
+            "SELECT id, name FROM users WHERE id = ANY($1)"
+        );
+        assert_eq!(
+            keys_query("SELECT id, name FROM users", "id", 3, Dialect::Sqlite),
+            "SELECT id, name FROM users WHERE id IN (?1, ?2, ?3)"
+        );
+    }
+
+    #[test]
+    fn records_follow_the_requested_order() {
+        let records = vec![(1, "a"), (3, "c"), (2, "b"), (9, "x")];
+        let lookup = in_key_order(&[3, 5, 1, 3, 2], records, |record| &record.0);
+        assert_eq!(lookup.found, vec![(3, "c"), (1, "a"), (2, "b")]);
+        assert_eq!(lookup.missing, vec![5]);
+        assert!(!lookup.is_complete());
+    }
+}
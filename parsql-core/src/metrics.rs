@@ -0,0 +1,123 @@
+//! Query metrics (`metrics` feature).
+//!
+//! With the feature enabled, every CRUD call of the backends is recorded through the
+//! [`metrics`](https://docs.rs/metrics) facade:
+//!
+//! - `parsql_queries_total`: counter of executed statements
+//! - `parsql_errors_total`: counter of statements that returned an error
+//! - `parsql_query_duration_seconds`: histogram of the statement durations
+//!
+//! All three are labelled with `table` (the `SqlQuery::table_name()` of the query type,
+//! `unknown` for dynamic SQL and cursor pages) and `op` (`insert`, `update`, `delete`,
+//! `fetch`, `fetch_all`, `select`, ...). Nothing is
+//! exported until the application installs a recorder, e.g. `metrics-exporter-prometheus`.
+//!
+//! The pooled backends also record how long a call waited for a connection:
+//!
+//! - `parsql_pool_wait_seconds`: histogram of the waits, retries included, labelled with
+//!   `outcome` (`ok` or `error`)
+//! - `parsql_pool_retries_total`: counter of the acquisition attempts that were retried
+//!
+//! Without the feature [`observe`] and [`observe_async`] only run the operation and
+//! [`record_pool_wait`] does nothing.
+
+use std::future::Future;
+
+use crate::paging::top_level_keyword;
+
+/// Runs `operation`, recording its duration and outcome as the `op` of a statement on `table`.
+#[inline]
+pub fn observe<R, E>(op: &'static str, table: &'static str, operation: impl FnOnce() -> Result<R, E>) -> Result<R, E> {
+    #[cfg(feature = "metrics")]
+    {
+        let started = std::time::Instant::now();
+        let result = operation();
+        record(op, table, started.elapsed(), result.is_ok());
+        result
+    }
+    #[cfg(not(feature = "metrics"))]
+    {
+        let _ = (op, table);
+        operation()
+    }
+}
+
+/// [`observe`] for the async backends.
+#[inline]
+pub async fn observe_async<R, E>(op: &'static str, table: &'static str, operation: impl Future<Output = Result<R, E>>) -> Result<R, E> {
+    #[cfg(feature = "metrics")]
+    {
+        let started = std::time::Instant::now();
+        let result = operation.await;
+        record(op, table, started.elapsed(), result.is_ok());
+        result
+    }
+    #[cfg(not(feature = "metrics"))]
+    {
+        let _ = (op, table);
+        operation.await
+    }
+}
+
+/// Records a wait for a pooled connection that took `elapsed` and was retried `retries` times.
+#[inline]
+pub fn record_pool_wait(elapsed: std::time::Duration, retries: u32, ok: bool) {
+    #[cfg(feature = "metrics")]
+    {
+        let outcome = if ok { "ok" } else { "error" };
+        metrics::histogram!("parsql_pool_wait_seconds", "outcome" => outcome).record(elapsed.as_secs_f64());
+        if retries > 0 {
+            metrics::counter!("parsql_pool_retries_total").increment(u64::from(retries));
+        }
+    }
+    #[cfg(not(feature = "metrics"))]
+    {
+        let _ = (elapsed, retries, ok);
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn record(op: &'static str, table: &'static str, elapsed: std::time::Duration, ok: bool) {
+    metrics::counter!("parsql_queries_total", "table" => table, "op" => op).increment(1);
+    if !ok {
+        metrics::counter!("parsql_errors_total", "table" => table, "op" => op).increment(1);
+    }
+    metrics::histogram!("parsql_query_duration_seconds", "table" => table, "op" => op)
+        .record(elapsed.as_secs_f64());
+}
+
+/// The table label of `sql`: the name after its first top-level `INTO`, `UPDATE` or
+/// `FROM`, or `unknown` when there is none.
+pub fn table_label(sql: &str) -> &str {
+    let Some((keyword, at)) = top_level_keyword(sql, &["INTO", "UPDATE", "FROM"]) else {
+        return "unknown";
+    };
+    let rest = sql[at + keyword.len()..].trim_start();
+    let end = rest
+        .find(|c: char| c.is_whitespace() || c == '(' || c == ',' || c == ';')
+        .unwrap_or(rest.len());
+    match &rest[..end] {
+        "" => "unknown",
+        table => table,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_label_of_each_statement() {
+        assert_eq!(table_label("INSERT INTO users (name ) VALUES ($1 ) RETURNING id"), "users");
+        assert_eq!(table_label("UPDATE users SET name = $1 FROM teams WHERE id = $2"), "users");
+        assert_eq!(table_label("DELETE FROM users USING teams WHERE id = $1"), "users");
+        assert_eq!(table_label("SELECT id, (SELECT 1 FROM teams) FROM public.users WHERE id = $1"), "public.users");
+        assert_eq!(table_label("SELECT 1"), "unknown");
+    }
+
+    #[test]
+    fn observe_returns_the_result() {
+        assert_eq!(observe("fetch", "users", || Ok::<_, ()>(1)), Ok(1));
+        assert_eq!(observe("fetch", "users", || Err::<(), _>("failed")), Err("failed"));
+    }
+}
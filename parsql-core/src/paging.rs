@@ -0,0 +1,205 @@
+//! LIMIT / OFFSET applied at execution time.
+//!
+//! The `fetch_all_limited` functions of the backends page through any `Queryable`
+//! type without baking `#[limit]` / `#[offset]` into it: the clauses are appended to
+//! the generated query and bound as two extra parameters.
+//!
+//! `fetch_first` / `fetch_last` read a single row the same way, ordered by a column
+//! picked at runtime from the query type's `#[sortable]` list.
+//!
+//! Paging a query without `ORDER BY` gives pages in no particular order, which may
+//! repeat or skip rows. [`with_limit_offset`] reports such queries through the SQL
+//! trace (`PARSQL_TRACE`); `#[require_order]` on the query type rejects them at compile
+//! time instead.
+
+use std::fmt;
+
+use crate::batch::Dialect;
+use crate::trace;
+use crate::validation::ValidationError;
+
+/// Clauses that conflict with a runtime LIMIT / OFFSET.
+const PAGING_KEYWORDS: &[&str] = &["LIMIT", "OFFSET", "FETCH"];
+
+/// The base query already restricts its rows with the named clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PagingConflict {
+    /// The conflicting clause, e.g. `LIMIT`
+    pub clause: &'static str,
+}
+
+impl fmt::Display for PagingConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "query already has a {} clause; remove #[limit] / #[offset] to page it at runtime",
+            self.clause
+        )
+    }
+}
+
+impl std::error::Error for PagingConflict {}
+
+/// Appends `LIMIT` and `OFFSET` placeholders to `sql`, numbered after its `bound`
+/// parameters. The limit is bound as parameter `bound + 1`, the offset as `bound + 2`.
+///
+/// Clauses inside subqueries, quotes or identifiers don't count as conflicts.
+pub fn with_limit_offset(sql: &str, bound: usize, dialect: Dialect) -> Result<String, PagingConflict> {
+    if let Some((clause, _)) = top_level_keyword(sql, PAGING_KEYWORDS) {
+        return Err(PagingConflict { clause });
+    }
+    if !is_ordered(sql) {
+        trace::log("PARSQL-PAGING", format!("Warning: paging a query without ORDER BY, pages are not deterministic: {}", sql));
+    }
+    let marker = dialect.placeholder();
+    Ok(format!(
+        "{} LIMIT {}{} OFFSET {}{}",
+        sql.trim_end(),
+        marker,
+        bound + 1,
+        marker,
+        bound + 2
+    ))
+}
+
+/// Returns whether `sql` has a top-level `ORDER BY`, i.e. returns its rows in a defined order.
+pub fn is_ordered(sql: &str) -> bool {
+    top_level_keyword(sql, &["ORDER"]).is_some()
+}
+
+/// Checks that `column` is one of the `#[sortable]` columns of a query type.
+///
+/// The order column of `fetch_first` / `fetch_last` is written into the SQL text, so only
+/// columns listed on the query type are accepted.
+pub fn check_sortable(column: &str, sortable: &[&str]) -> Result<(), ValidationError> {
+    if sortable.contains(&column) {
+        Ok(())
+    } else if sortable.is_empty() {
+        Err(ValidationError::new(column, "can't order a query type without #[sortable]"))
+    } else {
+        Err(ValidationError::new(
+            column,
+            format!("is not a sortable column; expected one of {}", sortable.join(", ")),
+        ))
+    }
+}
+
+/// Orders `sql` by `column` and keeps the first row: ascending for the first row,
+/// descending (`last`) for the last one. NULLs sort after every value either way.
+///
+/// An existing `ORDER BY` stays as the tie-breaker after `column`.
+pub fn with_first_row(sql: &str, column: &str, last: bool) -> Result<String, PagingConflict> {
+    if let Some((clause, _)) = top_level_keyword(sql, PAGING_KEYWORDS) {
+        return Err(PagingConflict { clause });
+    }
+    let sql = sql.trim_end();
+    let key = format!("{} {} NULLS LAST", column, if last { "DESC" } else { "ASC" });
+
+    let ordered = match top_level_keyword(sql, &["ORDER"]) {
+        Some((_, at)) => {
+            // Üst düzeydeki ORDER her zaman ORDER BY olarak gelir
+            let existing = sql[at + "ORDER".len()..].trim_start();
+            let existing = existing[2..].trim_start();
+            format!("{}ORDER BY {}, {}", &sql[..at], key, existing)
+        }
+        None => format!("{} ORDER BY {}", sql, key),
+    };
+    Ok(format!("{} LIMIT 1", ordered))
+}
+
+/// Returns the first of `keywords` appearing outside parentheses, quotes and identifiers,
+/// with its byte offset in `sql`.
+pub(crate) fn top_level_keyword(sql: &str, keywords: &[&'static str]) -> Option<(&'static str, usize)> {
+    let mut depth = 0usize;
+    let mut chars = sql.char_indices().peekable();
+
+    while let Some((at, c)) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                for (_, d) in chars.by_ref() {
+                    if d == c {
+                        break;
+                    }
+                }
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some((_, d)) = chars.next_if(|(_, d)| d.is_alphanumeric() || *d == '_') {
+                    word.push(d);
+                }
+                if depth == 0 {
+                    if let Some(keyword) = keywords.iter().find(|k| word.eq_ignore_ascii_case(k)) {
+                        return Some((keyword, at));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_numbered_placeholders() {
+        let sql = "SELECT id, name FROM users WHERE state = $1 ORDER BY id";
+        assert_eq!(
+            with_limit_offset(sql, 1, Dialect::Postgres).unwrap(),
+            "SELECT id, name FROM users WHERE state = $1 ORDER BY id LIMIT $2 OFFSET $3"
+        );
+        assert_eq!(
+            with_limit_offset("SELECT id FROM users", 0, Dialect::Sqlite).unwrap(),
+            "SELECT id FROM users LIMIT ?1 OFFSET ?2"
+        );
+    }
+
+    #[test]
+    fn rejects_baked_in_paging() {
+        let conflict = with_limit_offset("SELECT id FROM users LIMIT 10", 0, Dialect::Postgres);
+        assert_eq!(conflict, Err(PagingConflict { clause: "LIMIT" }));
+
+        // Alt sorgudaki ya da tırnak içindeki LIMIT çakışma sayılmaz
+        let sql = "SELECT id, \"limit\" FROM users WHERE id IN (SELECT user_id FROM posts LIMIT 5)";
+        assert!(with_limit_offset(sql, 0, Dialect::Postgres).is_ok());
+    }
+
+    #[test]
+    fn detects_top_level_order() {
+        assert!(is_ordered("SELECT id FROM users WHERE state = $1 ORDER BY id"));
+        assert!(!is_ordered("SELECT id FROM (SELECT id FROM users ORDER BY id) t"));
+        assert!(!is_ordered("SELECT id FROM users WHERE name = 'ORDER'"));
+    }
+
+    #[test]
+    fn orders_by_the_picked_column() {
+        assert_eq!(
+            with_first_row("SELECT id FROM orders WHERE customer_id = $1", "created_at", true).unwrap(),
+            "SELECT id FROM orders WHERE customer_id = $1 ORDER BY created_at DESC NULLS LAST LIMIT 1"
+        );
+
+        // Mevcut sıralama eşitlikleri bozmak için geride kalır
+        let sql = "SELECT id, rank() OVER (ORDER BY total) FROM orders ORDER BY id";
+        assert_eq!(
+            with_first_row(sql, "total", false).unwrap(),
+            "SELECT id, rank() OVER (ORDER BY total) FROM orders ORDER BY total ASC NULLS LAST, id LIMIT 1"
+        );
+
+        let conflict = with_first_row("SELECT id FROM orders LIMIT 5", "id", false);
+        assert_eq!(conflict, Err(PagingConflict { clause: "LIMIT" }));
+    }
+
+    #[test]
+    fn accepts_only_sortable_columns() {
+        assert!(check_sortable("created_at", &["id", "created_at"]).is_ok());
+        assert_eq!(
+            check_sortable("password", &["id", "created_at"]).unwrap_err().message,
+            "is not a sortable column; expected one of id, created_at"
+        );
+        assert!(check_sortable("id", &[]).is_err());
+    }
+}
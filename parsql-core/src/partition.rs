@@ -0,0 +1,246 @@
+//! Partition keys in UPDATE / DELETE statements.
+//!
+//! PostgreSQL prunes the partitions of a partitioned table only when the WHERE clause
+//! filters on the partition key; otherwise an UPDATE or DELETE scans every partition.
+//! `#[partition_by("created_at")]` makes the derive macros add the key to their WHERE
+//! clause, and [`with_partition_key`] does the same for statements built at runtime.
+//!
+//! Range-partitioned tables also need their partitions created ahead of the rows.
+//! `#[partitioned(by = "range(created_at)")]` marks a model of such a table, and the
+//! backends' `ensure_monthly_partition` creates the partition of a [`Month`] with
+//! [`monthly_partition_statement`].
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::batch::Dialect;
+use crate::paging::top_level_keyword;
+
+/// Returns whether `clause` refers to `column`.
+///
+/// Bare names compare case-insensitively and double-quoted names exactly. Qualified
+/// names (`e.created_at`) count; string literals and `$name` placeholders don't.
+pub fn filters_on(clause: &str, column: &str) -> bool {
+    let column = column.trim().trim_matches('"');
+    let mut chars = clause.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                for d in chars.by_ref() {
+                    if d == '\'' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let name: String = chars.by_ref().take_while(|d| *d != '"').collect();
+                if name == column {
+                    return true;
+                }
+            }
+            '$' => while chars.next_if(|d| d.is_alphanumeric() || *d == '_').is_some() {},
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some(d) = chars.next_if(|d| d.is_alphanumeric() || *d == '_') {
+                    word.push(d);
+                }
+                if word.eq_ignore_ascii_case(column) {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Adds `column = <placeholder>` to the WHERE clause of an UPDATE or DELETE statement.
+/// The placeholder is numbered after the statement's `bound` parameters.
+///
+/// Returns `None` when the statement already filters on `column`; there is then no
+/// extra parameter to bind. An existing condition is parenthesized so that an `OR`
+/// inside it can't bypass the key.
+pub fn with_partition_key(sql: &str, column: &str, bound: usize, dialect: Dialect) -> Option<String> {
+    let condition = format!("{} = {}{}", column, dialect.placeholder(), bound + 1);
+
+    // RETURNING, WHERE koşulundan sonra yerinde kalır
+    let end = top_level_keyword(sql, &["RETURNING"]).map_or(sql.len(), |(_, at)| at);
+    let statement = sql[..end].trim_end();
+    let returning = match sql[end..].trim() {
+        "" => String::new(),
+        tail => format!(" {}", tail),
+    };
+
+    match top_level_keyword(statement, &["WHERE"]) {
+        Some((_, at)) => {
+            let clause = statement[at + "WHERE".len()..].trim();
+            if filters_on(clause, column) {
+                return None;
+            }
+            Some(format!(
+                "{} WHERE ({}) AND {}{}",
+                statement[..at].trim_end(),
+                clause,
+                condition,
+                returning
+            ))
+        }
+        None => Some(format!("{} WHERE {}{}", statement, condition, returning)),
+    }
+}
+
+/// A calendar month, the range of one monthly partition.
+///
+/// Months of a [`SystemTime`] are taken in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Month {
+    year: i32,
+    month: u32,
+}
+
+impl Month {
+    /// The given month of `year`; `None` unless `month` is 1-12 and the year has four
+    /// digits.
+    pub fn new(year: i32, month: u32) -> Option<Self> {
+        ((1..=9999).contains(&year) && (1..=12).contains(&month)).then_some(Month { year, month })
+    }
+
+    /// The month `time` falls in.
+    pub fn containing(time: SystemTime) -> Self {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => i64::try_from(since.as_secs()).unwrap_or(i64::MAX),
+            Err(before) => -i64::try_from(before.duration().as_secs()).unwrap_or(i64::MAX),
+        };
+        // Howard Hinnant'ın civil_from_days algoritması; dönemler 400 yıllık döngülerdir
+        let days = seconds.div_euclid(86_400) + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Month {
+            year: i32::try_from(year.clamp(1, 9999)).unwrap_or(1),
+            month: u32::try_from(month).unwrap_or(1),
+        }
+    }
+
+    /// The year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// The month of the year, 1-12.
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    /// The following month.
+    pub fn next(&self) -> Self {
+        match self.month {
+            12 => Month { year: self.year + 1, month: 1 },
+            month => Month { year: self.year, month: month + 1 },
+        }
+    }
+
+    /// The month before.
+    pub fn previous(&self) -> Self {
+        match self.month {
+            1 => Month { year: self.year - 1, month: 12 },
+            month => Month { year: self.year, month: month - 1 },
+        }
+    }
+
+    /// The first day, `YYYY-MM-01`.
+    pub fn first_day(&self) -> String {
+        format!("{:04}-{:02}-01", self.year, self.month)
+    }
+}
+
+impl From<SystemTime> for Month {
+    fn from(time: SystemTime) -> Self {
+        Month::containing(time)
+    }
+}
+
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+/// Name of the partition of `table` holding `month`, e.g. `events_2024_03`.
+pub fn monthly_partition_name(table: &str, month: Month) -> String {
+    format!("{}_{:04}_{:02}", table, month.year, month.month)
+}
+
+/// `CREATE TABLE IF NOT EXISTS` for the partition of the range-partitioned `table`
+/// holding `month`, from its first day up to the first day of the next month.
+///
+/// The bounds are literals cast to the type of the partition key; on a `timestamptz`
+/// key they start at midnight in the session's time zone.
+pub fn monthly_partition_statement(table: &str, month: Month) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} PARTITION OF {} FOR VALUES FROM ('{}') TO ('{}')",
+        monthly_partition_name(table, month),
+        table,
+        month.first_day(),
+        month.next().first_day()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_the_partition_key() {
+        assert!(filters_on("id = $1 AND created_at >= $2", "created_at"));
+        assert!(filters_on("e.CREATED_AT = $1", "created_at"));
+        assert!(filters_on("\"createdAt\" = $1", "\"createdAt\""));
+        assert!(!filters_on("id = $created_at", "created_at"));
+        assert!(!filters_on("note = 'created_at'", "created_at"));
+        assert!(!filters_on("created_at_day = $1", "created_at"));
+    }
+
+    #[test]
+    fn appends_the_partition_key() {
+        assert_eq!(
+            with_partition_key("DELETE FROM events WHERE id = $1 OR id = $2", "created_at", 2, Dialect::Postgres)
+                .unwrap(),
+            "DELETE FROM events WHERE (id = $1 OR id = $2) AND created_at = $3"
+        );
+        assert_eq!(
+            with_partition_key("UPDATE events SET state = ?1 RETURNING id", "created_at", 1, Dialect::Sqlite)
+                .unwrap(),
+            "UPDATE events SET state = ?1 WHERE created_at = ?2 RETURNING id"
+        );
+        assert_eq!(
+            with_partition_key("DELETE FROM events WHERE created_at < $1", "created_at", 1, Dialect::Postgres),
+            None
+        );
+    }
+
+    #[test]
+    fn months_of_times() {
+        let at = |seconds: u64| Month::containing(UNIX_EPOCH + std::time::Duration::from_secs(seconds));
+        assert_eq!(at(0), Month::new(1970, 1).unwrap());
+        // 2024-02-29T23:59:59Z ve 2024-03-01T00:00:00Z
+        assert_eq!(at(1_709_251_199), Month::new(2024, 2).unwrap());
+        assert_eq!(at(1_709_251_200), Month::new(2024, 3).unwrap());
+        assert_eq!(Month::new(2024, 12).unwrap().next(), Month::new(2025, 1).unwrap());
+        assert_eq!(Month::new(2025, 1).unwrap().previous().to_string(), "2024-12");
+        assert_eq!(Month::new(2024, 13), None);
+    }
+
+    #[test]
+    fn monthly_partitions() {
+        assert_eq!(
+            monthly_partition_statement("events", Month::new(2024, 12).unwrap()),
+            "CREATE TABLE IF NOT EXISTS events_2024_12 PARTITION OF events FOR VALUES FROM ('2024-12-01') TO ('2025-01-01')"
+        );
+        assert_eq!(monthly_partition_name("audit.events", Month::new(2024, 3).unwrap()), "audit.events_2024_03");
+    }
+}
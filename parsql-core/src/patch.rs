@@ -0,0 +1,50 @@
+//! Partial updates.
+//!
+//! The `Patch` derive generates a `<Name>Patch` struct whose fields are all optional.
+//! The backends' `update_patch` functions write only the fields that are `Some`, with
+//! the statement built here, so a PATCH request never overwrites the columns it left out.
+
+use crate::batch::Dialect;
+
+/// `UPDATE table SET column = $1, ... WHERE key_column = $n` for the given `columns`.
+///
+/// The values of the columns are bound first, in order, and the key last. Returns
+/// `None` when there is no column to set.
+pub fn update_statement(table: &str, key_column: &str, columns: &[&str], dialect: Dialect) -> Option<String> {
+    if columns.is_empty() {
+        return None;
+    }
+    let marker = dialect.placeholder();
+    let assignments = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| format!("{} = {}{}", column, marker, i + 1))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!(
+        "UPDATE {} SET {} WHERE {} = {}{}",
+        table,
+        assignments,
+        key_column,
+        marker,
+        columns.len() + 1
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_given_columns_are_set() {
+        assert_eq!(
+            update_statement("users", "id", &["name", "state"], Dialect::Postgres).unwrap(),
+            "UPDATE users SET name = $1, state = $2 WHERE id = $3"
+        );
+        assert_eq!(
+            update_statement("users", "id", &["email"], Dialect::Sqlite).unwrap(),
+            "UPDATE users SET email = ?1 WHERE id = ?2"
+        );
+        assert!(update_statement("users", "id", &[], Dialect::Postgres).is_none());
+    }
+}
@@ -0,0 +1,183 @@
+//! The statement a query struct generates, without any database driver.
+//!
+//! [`SqlQuery`] is implemented by the `Queryable`, `Insertable`, `Updateable`,
+//! `Deletable` and `Callable` derives and re-exported by every backend's `traits`
+//! module. Only the parameter values (`SqlParams`) and row decoding (`FromRow`) are
+//! tied to a driver, so linters, code generators and tests can read the SQL and the
+//! parameter list of a struct with `parsql-macros` and `parsql-core` alone:
+//!
+//! ```rust,ignore
+//! use parsql_core::SqlQuery;
+//! use parsql_macros::Queryable;
+//!
+//! #[derive(Queryable)]
+//! #[table("users")]
+//! #[where_clause("name = $ AND state = $")]
+//! struct ActiveUser {
+//!     id: i64,
+//!     name: String,
+//!     state: i16,
+//! }
+//!
+//! assert_eq!(ActiveUser::query(), "SELECT id, name, state FROM users WHERE name = ?1 AND state = ?2");
+//! assert_eq!(ActiveUser::param_names(), ["name", "state"]);
+//! ```
+//!
+//! The placeholders follow the dialect feature of `parsql-macros` (`sqlite` by default,
+//! `$n` with `postgres`).
+
+use std::sync::Arc;
+
+use crate::validation::ValidationError;
+
+/// Trait for generating SQL queries.
+/// This trait is implemented by the derive macro `Queryable`, `Insertable`, `Updateable`, and `Deletable`.
+pub trait SqlQuery {
+    /// Returns the SQL query string.
+    ///
+    /// The string is built at compile time (or once, on first use), so calling
+    /// this does not allocate.
+    fn query() -> &'static str;
+
+    /// Returns the query used by the single-row operations (`fetch`, `select`).
+    ///
+    /// The `Queryable` derive appends `LIMIT 2` unless the struct has an explicit
+    /// `#[limit]`, so the database stops after the second matching row: enough for a
+    /// query matching several rows to still be reported as an error. Defaults to `query()`.
+    fn query_single() -> &'static str {
+        Self::query()
+    }
+
+    /// Returns the table the query writes or reads.
+    ///
+    /// The derives generate it from `#[table]` (or `#[materialized_view]`); hand-written
+    /// impls default to the first table the statement names.
+    fn table_name() -> &'static str {
+        crate::metrics::table_label(Self::query())
+    }
+
+    /// Returns the number of parameters the query binds, i.e. the length of `params()`.
+    ///
+    /// The derives generate the count at compile time; the default reads the highest
+    /// `$n` or `?n` placeholder of the query.
+    fn param_count() -> usize {
+        crate::debug::placeholder_count(Self::query())
+    }
+
+    /// Returns the fields bound to the placeholders, in placeholder order.
+    ///
+    /// The derives generate the list alongside the query. It is empty for hand-written
+    /// impls and for queries completed from another struct (`union`, `#[include_fields]`,
+    /// `#[insert_from]`), whose parameters are only known at runtime.
+    fn param_names() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Returns the query in canonical form: keywords in upper case, whitespace collapsed.
+    fn canonical_query() -> String {
+        crate::fingerprint::canonicalize(Self::query())
+    }
+
+    /// Returns a stable hash of the canonical query, usable as a key in metrics and
+    /// statement-level tracking.
+    ///
+    /// The value is the same across builds and processes for a given struct.
+    fn query_fingerprint() -> u64 {
+        crate::fingerprint::fingerprint(Self::query())
+    }
+
+    /// Validation run before the entity is written by `insert` or `update`.
+    ///
+    /// The `Insertable` and `Updateable` derives generate it from `#[validate(...)]`
+    /// attributes; without them every entity is valid.
+    fn validation(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    /// Columns `fetch_first` / `fetch_last` may order by.
+    ///
+    /// The `Queryable` derive generates the list from `#[sortable("...")]`; without it
+    /// the list is empty and no column is accepted.
+    fn sortable_columns() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Table and WHERE clause `delete_where` / `update_where` reuse.
+    ///
+    /// The `Queryable` derive generates them from `#[table]` and `#[where_clause]`, with
+    /// the WHERE clause numbered from the first placeholder. Queries without a condition,
+    /// with `#[join]` or with `union` have none.
+    fn criteria() -> Option<(&'static str, &'static str)> {
+        None
+    }
+}
+
+// Forwarding impls, so operations also accept `&T` and `Arc<T>`: `client.insert(&user)`
+// leaves `user` usable afterwards without cloning it.
+impl<T: SqlQuery + ?Sized> SqlQuery for &T {
+    fn query() -> &'static str {
+        T::query()
+    }
+
+    fn query_single() -> &'static str {
+        T::query_single()
+    }
+
+    fn table_name() -> &'static str {
+        T::table_name()
+    }
+
+    fn param_count() -> usize {
+        T::param_count()
+    }
+
+    fn param_names() -> &'static [&'static str] {
+        T::param_names()
+    }
+
+    fn sortable_columns() -> &'static [&'static str] {
+        T::sortable_columns()
+    }
+
+    fn criteria() -> Option<(&'static str, &'static str)> {
+        T::criteria()
+    }
+
+    fn validation(&self) -> Result<(), ValidationError> {
+        (**self).validation()
+    }
+}
+
+impl<T: SqlQuery + ?Sized> SqlQuery for Arc<T> {
+    fn query() -> &'static str {
+        T::query()
+    }
+
+    fn query_single() -> &'static str {
+        T::query_single()
+    }
+
+    fn table_name() -> &'static str {
+        T::table_name()
+    }
+
+    fn param_count() -> usize {
+        T::param_count()
+    }
+
+    fn param_names() -> &'static [&'static str] {
+        T::param_names()
+    }
+
+    fn sortable_columns() -> &'static [&'static str] {
+        T::sortable_columns()
+    }
+
+    fn criteria() -> Option<(&'static str, &'static str)> {
+        T::criteria()
+    }
+
+    fn validation(&self) -> Result<(), ValidationError> {
+        (**self).validation()
+    }
+}
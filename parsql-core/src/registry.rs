@@ -0,0 +1,50 @@
+//! Registry of the statements generated by the derive macros.
+//!
+//! A struct marked with `#[register_query]` submits its generated SQL here at link
+//! time. The backends' `prepare_all` functions prepare every registered statement
+//! once at startup, so a typo in a column name fails the deployment instead of the
+//! first request using the query.
+
+#[doc(hidden)]
+pub use inventory;
+
+/// A statement registered with `#[register_query]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryEntry {
+    /// Path of the struct the statement was generated for
+    pub name: &'static str,
+    /// The generated SQL
+    pub sql: &'static str,
+}
+
+impl QueryEntry {
+    /// Creates an entry; used by the code generated for `#[register_query]`.
+    pub const fn new(name: &'static str, sql: &'static str) -> Self {
+        Self { name, sql }
+    }
+}
+
+inventory::collect!(QueryEntry);
+
+/// Returns every registered statement, ordered by struct path.
+pub fn queries() -> Vec<&'static QueryEntry> {
+    let mut entries: Vec<_> = inventory::iter::<QueryEntry>.into_iter().collect();
+    entries.sort_by_key(|entry| entry.name);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    inventory::submit! {
+        QueryEntry::new("tests::GetUser", "SELECT id FROM users WHERE id = $1")
+    }
+
+    #[test]
+    fn lists_submitted_entries() {
+        assert!(queries()
+            .iter()
+            .any(|entry| entry.name == "tests::GetUser" && entry.sql.starts_with("SELECT")));
+    }
+}
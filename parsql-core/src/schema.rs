@@ -0,0 +1,369 @@
+//! Schema snapshots for compile-time checking.
+//!
+//! The backends' `schema::dump` functions read the tables and columns of a database
+//! into a [`Schema`], which [`Schema::to_json`] writes to a `schema.json` checked in
+//! next to the crate's `Cargo.toml`. With the `schema-check` feature the derive macros
+//! read that file and reject a struct whose table or columns are not in it, or whose
+//! field type cannot hold the column's, without connecting to a database:
+//!
+//! ```json
+//! {
+//!   "users": {
+//!     "email": { "type": "text", "nullable": false },
+//!     "id": { "type": "int8", "nullable": false }
+//!   }
+//! }
+//! ```
+//!
+//! Tables outside PostgreSQL's `public` schema are keyed by their qualified name,
+//! `reports.daily_sales`.
+
+use std::collections::BTreeMap;
+
+use crate::batch::Dialect;
+
+/// Reads the columns of every user table and view of a PostgreSQL database: the table,
+/// qualified unless it is in `public`, the column, its type name and whether it is nullable.
+pub const POSTGRES_COLUMNS: &str = "SELECT CASE WHEN table_schema = 'public' THEN table_name::text \
+     ELSE table_schema || '.' || table_name END, column_name::text, udt_name::text, is_nullable = 'YES' \
+     FROM information_schema.columns WHERE table_schema NOT IN ('pg_catalog', 'information_schema') \
+     ORDER BY 1, ordinal_position";
+
+/// SQLite counterpart of [`POSTGRES_COLUMNS`], reading the declared column types.
+pub const SQLITE_COLUMNS: &str = "SELECT m.name, p.name, p.type, p.\"notnull\" = 0 \
+     FROM sqlite_master AS m JOIN pragma_table_info(m.name) AS p \
+     WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%' ORDER BY m.name, p.cid";
+
+/// A column of a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    /// Type name, e.g. `int8` on PostgreSQL or the declared `INTEGER` on SQLite
+    pub data_type: String,
+    /// Whether the column accepts NULL
+    pub nullable: bool,
+}
+
+/// Tables and their columns, by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    /// Columns of each table, by column name
+    pub tables: BTreeMap<String, BTreeMap<String, Column>>,
+}
+
+impl Schema {
+    /// Creates an empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a column, and its table when it is the first one.
+    pub fn add_column(&mut self, table: &str, column: &str, data_type: &str, nullable: bool) {
+        self.tables.entry(table.to_string()).or_default().insert(
+            column.to_string(),
+            Column {
+                data_type: data_type.to_string(),
+                nullable,
+            },
+        );
+    }
+
+    /// The columns of `table`. The name may be quoted, `"Order"`, and qualified with
+    /// `public`, which the snapshot leaves out.
+    pub fn table(&self, table: &str) -> Option<&BTreeMap<String, Column>> {
+        let table = unquote(table);
+        let table = table.strip_prefix("public.").unwrap_or(&table);
+        self.tables.get(table)
+    }
+
+    /// Writes the schema as `schema.json`, sorted by table and column so that the file
+    /// only changes with the schema.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{");
+        for (i, (table, columns)) in self.tables.iter().enumerate() {
+            json.push_str(if i == 0 { "\n  " } else { ",\n  " });
+            json.push_str(&json_string(table));
+            json.push_str(": {");
+            for (j, (name, column)) in columns.iter().enumerate() {
+                json.push_str(if j == 0 { "\n    " } else { ",\n    " });
+                json.push_str(&format!(
+                    "{}: {{ \"type\": {}, \"nullable\": {} }}",
+                    json_string(name),
+                    json_string(&column.data_type),
+                    column.nullable
+                ));
+            }
+            json.push_str(if columns.is_empty() { "}" } else { "\n  }" });
+        }
+        json.push_str(if self.tables.is_empty() { "}\n" } else { "\n}\n" });
+        json
+    }
+
+    /// Reads a schema written by [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut parser = Parser { text: json, at: 0 };
+        let Value::Object(tables) = parser.document()? else {
+            return Err("expected an object of tables".to_string());
+        };
+        let mut schema = Schema::new();
+        for (table, columns) in tables {
+            let Value::Object(columns) = columns else {
+                return Err(format!("expected an object of columns for `{}`", table));
+            };
+            schema.tables.entry(table.clone()).or_default();
+            for (name, column) in columns {
+                let Value::Object(fields) = column else {
+                    return Err(format!("expected an object for `{}.{}`", table, name));
+                };
+                let field = |key: &str| fields.iter().find(|(name, _)| name == key).map(|(_, value)| value);
+                let Some(Value::String(data_type)) = field("type") else {
+                    return Err(format!("`{}.{}` has no `type`", table, name));
+                };
+                let nullable = matches!(field("nullable"), Some(Value::Bool(true)));
+                schema.add_column(&table, &name, data_type, nullable);
+            }
+        }
+        Ok(schema)
+    }
+}
+
+/// Whether a field of the Rust type `rust_type` (the last path segment, `Option` removed)
+/// can hold a column of `data_type`.
+///
+/// Only the types whose column type is unambiguous are checked; any other Rust type is
+/// accepted, as are column types outside the integer, floating point, boolean and text
+/// families.
+pub fn accepts(dialect: Dialect, rust_type: &str, data_type: &str) -> bool {
+    let Some(expected) = family_of_rust(rust_type) else {
+        return true;
+    };
+    let actual = match dialect {
+        Dialect::Postgres => family_of_postgres(data_type),
+        Dialect::Sqlite => family_of_sqlite(data_type),
+    };
+    match (expected, actual) {
+        (_, None) => true,
+        // SQLite tamsayı sütununda bool da saklar
+        (Family::Bool, Some(Family::Integer(_))) => dialect == Dialect::Sqlite,
+        // Daha geniş bir tamsayı alanı dar sütunu okuyabilir, tersi taşar
+        (Family::Integer(field), Some(Family::Integer(column))) => dialect == Dialect::Sqlite || field >= column,
+        (expected, Some(actual)) => expected == actual,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Family {
+    /// Integers of the given width in bytes
+    Integer(u8),
+    Float,
+    Bool,
+    Text,
+}
+
+fn family_of_rust(rust_type: &str) -> Option<Family> {
+    match rust_type {
+        "i8" => Some(Family::Integer(1)),
+        "i16" => Some(Family::Integer(2)),
+        "i32" | "u32" => Some(Family::Integer(4)),
+        "i64" => Some(Family::Integer(8)),
+        "f32" | "f64" => Some(Family::Float),
+        "bool" => Some(Family::Bool),
+        "String" | "str" => Some(Family::Text),
+        _ => None,
+    }
+}
+
+fn family_of_postgres(data_type: &str) -> Option<Family> {
+    match data_type {
+        "int2" => Some(Family::Integer(2)),
+        "int4" => Some(Family::Integer(4)),
+        "int8" => Some(Family::Integer(8)),
+        "float4" | "float8" => Some(Family::Float),
+        "bool" => Some(Family::Bool),
+        "text" | "varchar" | "bpchar" | "name" | "citext" => Some(Family::Text),
+        _ => None,
+    }
+}
+
+/// The type affinity SQLite gives a declared type.
+fn family_of_sqlite(data_type: &str) -> Option<Family> {
+    let declared = data_type.to_ascii_uppercase();
+    if declared.contains("INT") {
+        Some(Family::Integer(8))
+    } else if ["CHAR", "CLOB", "TEXT"].iter().any(|name| declared.contains(name)) {
+        Some(Family::Text)
+    } else if ["REAL", "FLOA", "DOUB"].iter().any(|name| declared.contains(name)) {
+        Some(Family::Float)
+    } else {
+        None
+    }
+}
+
+/// Removes the double quotes of each part of a possibly qualified name.
+fn unquote(name: &str) -> String {
+    name.split('.').map(|part| part.trim_matches('"')).collect::<Vec<_>>().join(".")
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// The JSON values a schema file holds.
+enum Value {
+    Object(Vec<(String, Value)>),
+    String(String),
+    Bool(bool),
+    Null,
+}
+
+/// Reader of the JSON subset [`Schema::to_json`] writes: objects, strings, booleans and null.
+struct Parser<'a> {
+    text: &'a str,
+    at: usize,
+}
+
+impl Parser<'_> {
+    fn document(&mut self) -> Result<Value, String> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        if self.at < self.text.len() {
+            return Err(self.error("unexpected text after the schema"));
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        let rest = &self.text[self.at..];
+        if rest.starts_with('{') {
+            self.object()
+        } else if rest.starts_with('"') {
+            self.string().map(Value::String)
+        } else if let Some(keyword) = ["true", "false", "null"].into_iter().find(|keyword| rest.starts_with(keyword)) {
+            self.at += keyword.len();
+            Ok(match keyword {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => Value::Null,
+            })
+        } else {
+            Err(self.error("expected an object, a string or a boolean"))
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, String> {
+        self.at += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.eat('}') {
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            if !self.eat(':') {
+                return Err(self.error("expected `:`"));
+            }
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            if self.eat('}') {
+                return Ok(Value::Object(members));
+            }
+            if !self.eat(',') {
+                return Err(self.error("expected `,` or `}`"));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if !self.eat('"') {
+            return Err(self.error("expected a string"));
+        }
+        let mut text = String::new();
+        let mut chars = self.text[self.at..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.at += i + 1;
+                    return Ok(text);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => text.push('\n'),
+                    Some((_, 't')) => text.push('\t'),
+                    Some((_, 'u')) => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let code = u32::from_str_radix(&hex, 16).map_err(|_| self.error("invalid `\\u` escape"))?;
+                        text.push(char::from_u32(code).ok_or_else(|| self.error("invalid `\\u` escape"))?);
+                    }
+                    Some((_, c)) => text.push(c),
+                    None => break,
+                },
+                c => text.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.text[self.at..].starts_with(c);
+        if found {
+            self.at += c.len_utf8();
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.at..];
+        self.at += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.text[..self.at].matches('\n').count() + 1;
+        format!("{} on line {}", message, line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trip() {
+        let mut schema = Schema::new();
+        schema.add_column("users", "id", "int8", false);
+        schema.add_column("users", "email", "text", true);
+        schema.add_column("reports.daily \"sales\"", "total", "numeric", false);
+
+        let json = schema.to_json();
+        assert!(json.starts_with("{\n  \"reports.daily \\\"sales\\\"\": {\n    \"total\": { \"type\": \"numeric\", \"nullable\": false }"));
+        assert_eq!(Schema::from_json(&json).unwrap(), schema);
+        assert_eq!(Schema::from_json(&Schema::new().to_json()).unwrap(), Schema::new());
+        assert!(Schema::from_json("{ \"users\": { \"id\": { \"nullable\": false } } }").is_err());
+        assert!(schema.table("\"users\"").is_some());
+        assert!(schema.table("public.users").is_some());
+    }
+
+    #[test]
+    fn field_types_against_columns() {
+        assert!(accepts(Dialect::Postgres, "i64", "int4"));
+        assert!(!accepts(Dialect::Postgres, "i32", "int8"));
+        assert!(!accepts(Dialect::Postgres, "String", "int8"));
+        assert!(accepts(Dialect::Postgres, "Uuid", "uuid"));
+        assert!(accepts(Dialect::Postgres, "String", "jsonb"));
+        assert!(accepts(Dialect::Sqlite, "bool", "INTEGER"));
+        assert!(accepts(Dialect::Sqlite, "String", "VARCHAR(64)"));
+        assert!(!accepts(Dialect::Sqlite, "i64", "TEXT"));
+    }
+}
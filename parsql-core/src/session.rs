@@ -0,0 +1,88 @@
+//! Transaction-scoped session settings.
+//!
+//! The backends' `with_search_path` / `with_role` helpers change a setting with
+//! [`SET_LOCAL`] at the start of a transaction. The value is bound as a parameter and
+//! reverts on commit or rollback, so a pooled connection never carries it over to the
+//! next caller. `with_options` applies the [`ExecOptions`] of one operation the same way,
+//! and `with_rls_user` stores the acting user in [`RLS_USER`].
+
+use std::time::Duration;
+
+/// `SET LOCAL` with the setting name and value as parameters `$1` and `$2`.
+pub const SET_LOCAL: &str = "SELECT set_config($1, $2, true)";
+
+/// Setting holding the user `with_rls_user` acts for.
+///
+/// Row level security policies read it with `current_setting('app.current_user_id', true)`,
+/// which is `NULL` outside `with_rls_user`, so a policy comparing it to a column matches
+/// no row when the application forgot to set it.
+pub const RLS_USER: &str = "app.current_user_id";
+
+/// Settings applied to the statements of one operation with [`SET_LOCAL`].
+///
+/// Unset fields keep the session's value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecOptions {
+    /// Cancels a statement that runs longer; rounded down to milliseconds.
+    pub statement_timeout: Option<Duration>,
+    /// Name shown in `pg_stat_activity` and the server log, e.g. `"nightly-report"`.
+    pub application_name: Option<String>,
+    /// Memory a sort or hash may use before spilling to disk, e.g. `"256MB"`.
+    pub work_mem: Option<String>,
+}
+
+impl ExecOptions {
+    /// The set fields as setting name and value pairs.
+    pub fn settings(&self) -> Vec<(&'static str, String)> {
+        let mut settings = Vec::new();
+        if let Some(timeout) = self.statement_timeout {
+            settings.push(("statement_timeout", format!("{}ms", timeout.as_millis())));
+        }
+        if let Some(name) = &self.application_name {
+            settings.push(("application_name", name.clone()));
+        }
+        if let Some(work_mem) = &self.work_mem {
+            settings.push(("work_mem", work_mem.clone()));
+        }
+        settings
+    }
+}
+
+/// Builds a `search_path` value from a comma separated schema list, quoting each
+/// schema so that mixed-case names are kept and no name can inject another one.
+pub fn search_path(schemas: &str) -> String {
+    schemas
+        .split(',')
+        .map(str::trim)
+        .filter(|schema| !schema.is_empty())
+        .map(|schema| format!("\"{}\"", schema.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_set_options_are_applied() {
+        assert!(ExecOptions::default().settings().is_empty());
+
+        let options = ExecOptions {
+            statement_timeout: Some(Duration::from_secs(5)),
+            work_mem: Some("256MB".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.settings(),
+            [("statement_timeout", "5000ms".to_string()), ("work_mem", "256MB".to_string())]
+        );
+    }
+
+    #[test]
+    fn quotes_every_schema() {
+        assert_eq!(search_path("tenant_42"), "\"tenant_42\"");
+        assert_eq!(search_path("Tenant42, public"), "\"Tenant42\", \"public\"");
+        assert_eq!(search_path("x\", public"), "\"x\"\"\", \"public\"");
+    }
+}
@@ -0,0 +1,46 @@
+//! Temporary tables filled from a query.
+//!
+//! ETL-style jobs stage a large intermediate set once and then join or update against
+//! it instead of repeating the query. The backends' `transactional::create_temp_from`
+//! runs the statement built here with the parameters of a `Queryable`; later statements
+//! of the same transaction reach the table by name, e.g. through `#[table("tmp_x")]` or
+//! `#[join("JOIN tmp_x ON ...")]`.
+
+use crate::Dialect;
+
+/// `CREATE TEMP TABLE name AS sql`, the portable form of `SELECT ... INTO TEMP`.
+///
+/// On PostgreSQL the table is created `ON COMMIT DROP`, so it disappears with the
+/// transaction that filled it. SQLite has no such clause; its temporary tables live
+/// until the connection closes or they are dropped.
+///
+/// Returns `None` when `name` is not a plain table name.
+pub fn create_temp_as(sql: &str, name: &str, dialect: Dialect) -> Option<String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    let sql = sql.trim_end().trim_end_matches(';');
+    Some(match dialect {
+        Dialect::Postgres => format!("CREATE TEMP TABLE {} ON COMMIT DROP AS {}", name, sql),
+        Dialect::Sqlite => format!("CREATE TEMP TABLE {} AS {}", name, sql),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temp_table_statements() {
+        let sql = "SELECT id, email FROM users WHERE state = $1;";
+        assert_eq!(
+            create_temp_as(sql, "tmp_active", Dialect::Postgres).unwrap(),
+            "CREATE TEMP TABLE tmp_active ON COMMIT DROP AS SELECT id, email FROM users WHERE state = $1"
+        );
+        assert_eq!(
+            create_temp_as("SELECT id FROM users", "tmp_ids", Dialect::Sqlite).unwrap(),
+            "CREATE TEMP TABLE tmp_ids AS SELECT id FROM users"
+        );
+        assert!(create_temp_as(sql, "tmp; DROP TABLE users", Dialect::Postgres).is_none());
+    }
+}
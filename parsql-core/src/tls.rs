@@ -0,0 +1,158 @@
+//! TLS connectors of the PostgreSQL backends (`native-tls` / `rustls` features).
+//!
+//! [`TlsOptions`] and the connector builders are shared by `parsql-postgres`,
+//! `parsql-tokio-postgres` and `parsql-deadpool-postgres`; each backend re-exports them
+//! from its `tls` module next to the functions that connect a client or create a pool.
+
+use std::fmt;
+
+/// Options used while building the TLS connector.
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+    /// Additional root certificate(s) in PEM format, e.g. the CA of a private server.
+    pub root_cert_pem: Option<Vec<u8>>,
+    /// Accepts invalid certificates and host names. Only meant for development,
+    /// and only supported by the `native-tls` backend.
+    pub accept_invalid_certs: bool,
+}
+
+/// The TLS connector could not be built (invalid certificate, unsupported option, ...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectorError(String);
+
+impl ConnectorError {
+    fn new(message: impl fmt::Display) -> Self {
+        ConnectorError(message.to_string())
+    }
+}
+
+impl fmt::Display for ConnectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ConnectorError {}
+
+/// Builds a `native-tls` connector from the given options.
+#[cfg(feature = "native-tls")]
+pub fn native_tls_connector(
+    options: &TlsOptions,
+) -> Result<postgres_native_tls::MakeTlsConnector, ConnectorError> {
+    let mut builder = native_tls::TlsConnector::builder();
+
+    if let Some(pem) = &options.root_cert_pem {
+        let cert = native_tls::Certificate::from_pem(pem).map_err(ConnectorError::new)?;
+        builder.add_root_certificate(cert);
+    }
+
+    if options.accept_invalid_certs {
+        builder.danger_accept_invalid_certs(true);
+        builder.danger_accept_invalid_hostnames(true);
+    }
+
+    let connector = builder.build().map_err(ConnectorError::new)?;
+
+    Ok(postgres_native_tls::MakeTlsConnector::new(connector))
+}
+
+/// Builds a `rustls` connector trusting the webpki root certificates plus the given options.
+#[cfg(feature = "rustls")]
+pub fn rustls_connector(
+    options: &TlsOptions,
+) -> Result<tokio_postgres_rustls::MakeRustlsConnect, ConnectorError> {
+    use rustls::pki_types::{pem::PemObject, CertificateDer};
+    use std::sync::Arc;
+
+    if options.accept_invalid_certs {
+        return Err(ConnectorError::new(
+            "accept_invalid_certs is not supported by the rustls backend",
+        ));
+    }
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+    if let Some(pem) = &options.root_cert_pem {
+        let mut added = 0;
+        for cert in CertificateDer::pem_slice_iter(pem) {
+            roots
+                .add(cert.map_err(ConnectorError::new)?)
+                .map_err(ConnectorError::new)?;
+            added += 1;
+        }
+        if added == 0 {
+            return Err(ConnectorError::new("root_cert_pem contains no certificate"));
+        }
+    }
+
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(ConnectorError::new)?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+
+    Ok(tokio_postgres_rustls::MakeRustlsConnect::new(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Self-signed CA used as an extra root certificate.
+    const CA_PEM: &[u8] = b"-----BEGIN CERTIFICATE-----
+MIIBiTCCAS+gAwIBAgIUdpOfpKSd5h4N71+pA5X0FxvVKhUwCgYIKoZIzj0EAwIw
+GTEXMBUGA1UEAwwOcGFyc3FsLXRlc3QtY2EwIBcNMjYxMDE2MTU0MzI4WhgPMjEy
+NjA5MjIxNTQzMjhaMBkxFzAVBgNVBAMMDnBhcnNxbC10ZXN0LWNhMFkwEwYHKoZI
+zj0CAQYIKoZIzj0DAQcDQgAEmaFna0osb4ofzuhOgx5V2sv/qf4m2rjvns/5nyHk
+Ez4mtMWiaMXeplWGcHBQEOiVnQ7+QRJ4H9L7QDZAfxial6NTMFEwHQYDVR0OBBYE
+FBKTCDHYSbfrnWsT5EuBiwNX7QfzMB8GA1UdIwQYMBaAFBKTCDHYSbfrnWsT5EuB
+iwNX7QfzMA8GA1UdEwEB/wQFMAMBAf8wCgYIKoZIzj0EAwIDSAAwRQIhAJ++iYkT
+R4DcFolHZtJWJmvroUn5cjDakxsNa50jrYxjAiBXrPrfoebJ7Xw90uNhuIJJ2y0N
+0ejc96X82W/6r8BlHA==
+-----END CERTIFICATE-----
+";
+
+    #[cfg(feature = "native-tls")]
+    #[test]
+    fn builds_native_tls_connector() {
+        assert!(native_tls_connector(&TlsOptions::default()).is_ok());
+        let options = TlsOptions {
+            root_cert_pem: Some(CA_PEM.to_vec()),
+            accept_invalid_certs: true,
+        };
+        assert!(native_tls_connector(&options).is_ok());
+
+        let options = TlsOptions {
+            root_cert_pem: Some(b"not a certificate".to_vec()),
+            ..Default::default()
+        };
+        assert!(native_tls_connector(&options).is_err());
+    }
+
+    #[cfg(feature = "rustls")]
+    #[test]
+    fn builds_rustls_connector() {
+        assert!(rustls_connector(&TlsOptions::default()).is_ok());
+        let options = TlsOptions {
+            root_cert_pem: Some(CA_PEM.to_vec()),
+            ..Default::default()
+        };
+        assert!(rustls_connector(&options).is_ok());
+
+        let options = TlsOptions {
+            root_cert_pem: Some(b"not a certificate".to_vec()),
+            ..Default::default()
+        };
+        let error = rustls_connector(&options).err().unwrap();
+        assert_eq!(error.to_string(), "root_cert_pem contains no certificate");
+
+        let options = TlsOptions {
+            accept_invalid_certs: true,
+            ..Default::default()
+        };
+        assert!(rustls_connector(&options).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! Process-wide SQL tracing switch.
+//!
+//! Tracing is enabled when the `PARSQL_TRACE` environment variable is `1`.
+//! The variable is read once, on the first check; afterwards the flag can only be
+//! changed through [`set_trace`]. Every parsql backend consults this flag, so
+//! all of them trace (or stay silent) the same way.
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+const UNINIT: u8 = 0;
+const DISABLED: u8 = 1;
+const ENABLED: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(UNINIT);
+
+/// Returns whether SQL tracing is enabled.
+pub fn enabled() -> bool {
+    match STATE.load(Ordering::Relaxed) {
+        UNINIT => {
+            let from_env = std::env::var("PARSQL_TRACE").is_ok_and(|v| v == "1");
+            let state = if from_env { ENABLED } else { DISABLED };
+            // set_trace ile önceden yapılmış bir ayarın üzerine yazma
+            match STATE.compare_exchange(UNINIT, state, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => from_env,
+                Err(current) => current == ENABLED,
+            }
+        }
+        state => state == ENABLED,
+    }
+}
+
+/// Enables or disables SQL tracing for the whole process, overriding `PARSQL_TRACE`.
+pub fn set_trace(enabled: bool) {
+    STATE.store(if enabled { ENABLED } else { DISABLED }, Ordering::Relaxed);
+}
+
+/// Prints `[tag] Execute SQL: <sql>` when tracing is enabled.
+pub fn log_sql(tag: &str, sql: &str) {
+    if enabled() {
+        println!("[{}] Execute SQL: {}", tag, sql);
+    }
+}
+
+/// Prints `[tag] <message>` when tracing is enabled.
+pub fn log(tag: &str, message: impl Display) {
+    if enabled() {
+        println!("[{}] {}", tag, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_trace_overrides_environment() {
+        set_trace(true);
+        assert!(enabled());
+        set_trace(false);
+        assert!(!enabled());
+    }
+}
@@ -0,0 +1,167 @@
+//! Entity validation before INSERT / UPDATE.
+//!
+//! The `Insertable` and `Updateable` derives read `#[validate(...)]` field attributes
+//! and a struct level `#[validate]` (which calls the struct's [`Validate`] impl). The
+//! backends run those checks before the statement is sent, so an invalid entity never
+//! reaches the database. The `check_*` functions are the field rules; they are public so
+//! that hand-written [`Validate`] impls can use them too.
+
+use std::error::Error as StdError;
+use std::fmt;
+
+/// An entity failed validation; nothing was written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The field that failed, or the struct name for struct level checks
+    pub field: String,
+    /// Why the value was rejected
+    pub message: String,
+}
+
+impl ValidationError {
+    /// Creates the error of `field`.
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Finds the validation error behind a backend error by walking its `source()` chain.
+    ///
+    /// The postgres and sqlite backends report a failed validation through their own
+    /// error type; this gives the original error back.
+    pub fn find<'a>(error: &'a (dyn StdError + 'static)) -> Option<&'a ValidationError> {
+        let mut current = Some(error);
+        while let Some(error) = current {
+            if let Some(found) = error.downcast_ref::<ValidationError>() {
+                return Some(found);
+            }
+            current = error.source();
+        }
+        None
+    }
+
+    /// `#[validate(email)]`: one `@` with a non-empty local part and a dotted domain.
+    pub fn check_email(field: &str, value: &str) -> Result<(), ValidationError> {
+        let valid = match value.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain.contains('.')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+                    && !value.chars().any(char::is_whitespace)
+            }
+            None => false,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(Self::new(field, "is not a valid email address"))
+        }
+    }
+
+    /// `#[validate(non_empty)]`: at least one non-whitespace character.
+    pub fn check_non_empty(field: &str, value: &str) -> Result<(), ValidationError> {
+        if value.trim().is_empty() {
+            Err(Self::new(field, "must not be empty"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// `#[validate(length(min = 1, max = 64))]`: the length in characters.
+    pub fn check_length(
+        field: &str,
+        value: &str,
+        min: Option<usize>,
+        max: Option<usize>,
+    ) -> Result<(), ValidationError> {
+        let length = value.chars().count();
+        if let Some(min) = min.filter(|min| length < *min) {
+            return Err(Self::new(field, format!("must be at least {} characters long", min)));
+        }
+        if let Some(max) = max.filter(|max| length > *max) {
+            return Err(Self::new(field, format!("must be at most {} characters long", max)));
+        }
+        Ok(())
+    }
+
+    /// `#[validate(range(min = 0, max = 150))]`: both bounds are inclusive.
+    pub fn check_range<T: PartialOrd + fmt::Display>(
+        field: &str,
+        value: &T,
+        min: Option<T>,
+        max: Option<T>,
+    ) -> Result<(), ValidationError> {
+        if let Some(min) = min.filter(|min| value < min) {
+            return Err(Self::new(field, format!("must be at least {}", min)));
+        }
+        if let Some(max) = max.filter(|max| value > max) {
+            return Err(Self::new(field, format!("must be at most {}", max)));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "validation failed for {}: {}", self.field, self.message)
+    }
+}
+
+impl StdError for ValidationError {}
+
+/// Struct level validation, run when the struct is marked `#[validate]`.
+///
+/// Field attributes are checked first, then this; use it for rules spanning several
+/// fields.
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_rules() {
+        assert!(ValidationError::check_email("email", "jane@example.com").is_ok());
+        for invalid in ["jane", "@example.com", "jane@example", "jane@.com", "ja ne@example.com"] {
+            assert!(ValidationError::check_email("email", invalid).is_err(), "{}", invalid);
+        }
+
+        assert!(ValidationError::check_non_empty("name", "  ").is_err());
+        assert_eq!(
+            ValidationError::check_length("name", "şule", Some(5), None),
+            Err(ValidationError::new("name", "must be at least 5 characters long"))
+        );
+        assert!(ValidationError::check_length("name", "şule", Some(4), Some(4)).is_ok());
+        assert_eq!(
+            ValidationError::check_range("age", &151, Some(0), Some(150)),
+            Err(ValidationError::new("age", "must be at most 150"))
+        );
+    }
+
+    #[test]
+    fn finds_the_error_behind_a_wrapper() {
+        #[derive(Debug)]
+        struct Wrapper(ValidationError);
+
+        impl fmt::Display for Wrapper {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "wrapped")
+            }
+        }
+
+        impl StdError for Wrapper {
+            fn source(&self) -> Option<&(dyn StdError + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let error = Wrapper(ValidationError::new("email", "is not a valid email address"));
+        assert_eq!(ValidationError::find(&error).unwrap().field, "email");
+    }
+}
@@ -0,0 +1,212 @@
+//! Column values of queries without a result struct.
+//!
+//! The backends' `query_dynamic` runs any SQL and returns each row as a map of column
+//! name to [`Value`], for REPL-like tools and debugging endpoints that can't declare a
+//! `FromRow` struct per query:
+//!
+//! ```rust,ignore
+//! let rows = parsql::postgres::query_dynamic(&mut client, "SELECT id, name FROM users WHERE state = $1", &[&1i16])?;
+//! for row in &rows {
+//!     println!("{} {}", row["id"], row["name"]);
+//! }
+//! ```
+//!
+//! PostgreSQL columns of the types [`from_postgres`] decodes are read as they are;
+//! other types (dates, numerics, arrays, ...) are cast to text on the server by
+//! [`with_text_columns`], so every column can be read.
+
+use std::fmt;
+
+/// A column value of a dynamic query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    /// Any integer column; PostgreSQL `oid` as well
+    Int(i64),
+    Float(f64),
+    /// Text columns, and on PostgreSQL `json`, `uuid`, enums and the columns cast to text
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    /// Whether the column is NULL.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// The value of a boolean column.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The value of an integer column.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The value of a float or integer column.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Float(value) => Some(*value),
+            // 2^53'ten büyük tamsayılar yuvarlanır
+            Value::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// The value of a text column.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The value of a `bytea` or BLOB column.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the value as `psql` would show it: `NULL`, `t` / `f` and `\x`-prefixed hex bytes.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("NULL"),
+            Value::Bool(value) => f.write_str(if *value { "t" } else { "f" }),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", value),
+            Value::Text(value) => f.write_str(value),
+            Value::Bytes(value) => {
+                f.write_str("\\x")?;
+                value.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+        }
+    }
+}
+
+// PostgreSQL'in sabit tip numaraları (pg_type.oid)
+const BOOL: u32 = 16;
+const BYTEA: u32 = 17;
+const NAME: u32 = 19;
+const INT8: u32 = 20;
+const INT2: u32 = 21;
+const INT4: u32 = 23;
+const TEXT: u32 = 25;
+const OID: u32 = 26;
+const JSON: u32 = 114;
+const FLOAT4: u32 = 700;
+const FLOAT8: u32 = 701;
+const UNKNOWN: u32 = 705;
+const BPCHAR: u32 = 1042;
+const VARCHAR: u32 = 1043;
+const UUID: u32 = 2950;
+const JSONB: u32 = 3802;
+
+/// Whether [`from_postgres`] decodes the PostgreSQL type with this oid.
+pub fn decodes_postgres(oid: u32) -> bool {
+    matches!(
+        oid,
+        BOOL | BYTEA | NAME | INT8 | INT2 | INT4 | TEXT | OID | JSON | FLOAT4 | FLOAT8 | UNKNOWN | BPCHAR | VARCHAR | UUID | JSONB
+    )
+}
+
+/// Decodes a value in PostgreSQL's binary format, as the drivers receive it.
+pub fn from_postgres(oid: u32, raw: &[u8]) -> Result<Value, String> {
+    fn bytes<const N: usize>(raw: &[u8]) -> Result<[u8; N], String> {
+        raw.try_into().map_err(|_| format!("expected {} bytes, found {}", N, raw.len()))
+    }
+    fn text(raw: &[u8]) -> Result<Value, String> {
+        std::str::from_utf8(raw).map(|text| Value::Text(text.to_string())).map_err(|e| e.to_string())
+    }
+
+    match oid {
+        BOOL => Ok(Value::Bool(bytes::<1>(raw)?[0] != 0)),
+        INT2 => Ok(Value::Int(i16::from_be_bytes(bytes(raw)?).into())),
+        INT4 => Ok(Value::Int(i32::from_be_bytes(bytes(raw)?).into())),
+        INT8 => Ok(Value::Int(i64::from_be_bytes(bytes(raw)?))),
+        OID => Ok(Value::Int(u32::from_be_bytes(bytes(raw)?).into())),
+        FLOAT4 => Ok(Value::Float(f32::from_be_bytes(bytes(raw)?).into())),
+        FLOAT8 => Ok(Value::Float(f64::from_be_bytes(bytes(raw)?))),
+        TEXT | VARCHAR | BPCHAR | NAME | UNKNOWN | JSON => text(raw),
+        // jsonb'nin ilk baytı biçim sürümüdür (1)
+        JSONB => match raw.split_first() {
+            Some((1, json)) => text(json),
+            _ => Err("unsupported jsonb version".to_string()),
+        },
+        BYTEA => Ok(Value::Bytes(raw.to_vec())),
+        UUID => {
+            let hex = bytes::<16>(raw)?.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+            Ok(Value::Text(format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])))
+        }
+        _ => Err(format!("type oid {} is not decoded", oid)),
+    }
+}
+
+/// Wraps `sql` so that the columns the driver can't decode are cast to text on the server.
+///
+/// `columns` holds the name of every result column and whether it is decoded as it is.
+/// The names must be unique, since the columns are selected by name.
+pub fn with_text_columns(sql: &str, columns: &[(&str, bool)]) -> String {
+    let list = columns
+        .iter()
+        .map(|(name, decoded)| {
+            let quoted = format!("\"{}\"", name.replace('"', "\"\""));
+            if *decoded {
+                format!("parsql_row.{}", quoted)
+            } else {
+                format!("parsql_row.{}::text AS {}", quoted, quoted)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("SELECT {} FROM ({}) AS parsql_row", list, sql.trim_end().trim_end_matches(';'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_binary_values() {
+        assert_eq!(from_postgres(BOOL, &[1]), Ok(Value::Bool(true)));
+        assert_eq!(from_postgres(INT2, &(-2i16).to_be_bytes()), Ok(Value::Int(-2)));
+        assert_eq!(from_postgres(INT8, &42i64.to_be_bytes()), Ok(Value::Int(42)));
+        assert_eq!(from_postgres(FLOAT8, &1.5f64.to_be_bytes()), Ok(Value::Float(1.5)));
+        assert_eq!(from_postgres(VARCHAR, b"ali"), Ok(Value::Text("ali".into())));
+        assert_eq!(from_postgres(JSONB, b"\x01{\"a\": 1}"), Ok(Value::Text("{\"a\": 1}".into())));
+        assert_eq!(
+            from_postgres(UUID, &[0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0, 0xc8]),
+            Ok(Value::Text("67e55044-10b1-426f-9247-bb680e5fe0c8".into()))
+        );
+        assert!(from_postgres(INT4, &[0, 1]).is_err());
+        assert!(from_postgres(1700, &[0, 0]).is_err());
+    }
+
+    #[test]
+    fn casts_undecoded_columns() {
+        assert_eq!(
+            with_text_columns("SELECT id, created_at FROM users;", &[("id", true), ("created_at", false)]),
+            "SELECT parsql_row.\"id\", parsql_row.\"created_at\"::text AS \"created_at\" FROM (SELECT id, created_at FROM users) AS parsql_row"
+        );
+    }
+
+    #[test]
+    fn displays_like_psql() {
+        assert_eq!(Value::Null.to_string(), "NULL");
+        assert_eq!(Value::Bool(false).to_string(), "f");
+        assert_eq!(Value::Bytes(vec![0xde, 0xad]).to_string(), "\\xdead");
+        assert_eq!(Value::Int(7).as_f64(), Some(7.0));
+    }
+}
@@ -0,0 +1,29 @@
+//! Materialized view maintenance.
+//!
+//! `Queryable` structs annotated with `#[materialized_view("...")]` read from a view
+//! instead of a table. The backends' `refresh_materialized_view` functions rebuild it
+//! with the statement built here.
+
+/// `REFRESH MATERIALIZED VIEW` for `view`.
+///
+/// With `concurrently` the view stays readable while it is rebuilt; PostgreSQL then
+/// requires a unique index on the view and does not allow the statement inside a
+/// transaction block started by the caller.
+pub fn refresh_statement(view: &str, concurrently: bool) -> String {
+    let mode = if concurrently { "CONCURRENTLY " } else { "" };
+    format!("REFRESH MATERIALIZED VIEW {}{}", mode, view)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_statements() {
+        assert_eq!(refresh_statement("daily_sales", false), "REFRESH MATERIALIZED VIEW daily_sales");
+        assert_eq!(
+            refresh_statement("reports.daily_sales", true),
+            "REFRESH MATERIALIZED VIEW CONCURRENTLY reports.daily_sales"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Retrying connection acquisition.
+//!
+//! Every CRUD function and the `CrudOps` impl of [`Pool`] take their connection through
+//! [`acquire`]. By default it is a single `pool.get()`: a timeout or a failed connect is
+//! returned at once as [`DeadpoolError::Pool`](crate::DeadpoolError::Pool). A service that
+//! would rather ride out a short burst of load or a database restart turns on retries
+//! for the whole process:
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use parsql_deadpool_postgres::acquire::{set_acquire_retry, AcquireRetry};
+//!
+//! set_acquire_retry(Some(
+//!     AcquireRetry::new(3)
+//!         .delay(Duration::from_millis(50))
+//!         .max_delay(Duration::from_secs(1))
+//!         .jitter(true),
+//! ));
+//! ```
+//!
+//! Only timeouts and connect errors are retried; a closed pool or a failing
+//! `post_create` hook is returned right away. With the `metrics` feature, the time spent
+//! waiting for the connection is recorded as `parsql_pool_wait_seconds` and the retries
+//! as `parsql_pool_retries_total`.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use deadpool_postgres::{Object, Pool, PoolError};
+use parsql_core::{metrics, trace};
+
+/// How often and how patiently a failed `pool.get()` is retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcquireRetry {
+    attempts: u32,
+    delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl AcquireRetry {
+    /// Retries up to `attempts` times after the first failure, waiting 100ms before the
+    /// first retry and doubling the wait after each one, up to 5s.
+    pub fn new(attempts: u32) -> Self {
+        Self {
+            attempts,
+            delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            jitter: false,
+        }
+    }
+
+    /// Wait before the first retry.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Upper bound of the doubled waits.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Waits a random duration between zero and the computed wait instead, so clients
+    /// that failed together do not retry together.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Wait before retry number `retry` (starting at 1).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u32.checked_shl(retry.saturating_sub(1)).unwrap_or(u32::MAX);
+        let delay = self.delay.saturating_mul(factor).min(self.max_delay);
+        if !self.jitter || delay.is_zero() {
+            return delay;
+        }
+        // Rastgelelik için ayrı bir bağımlılık yerine her çağrıda yeni anahtarlanan hasher kullanılır
+        let random = RandomState::new().build_hasher().finish();
+        Duration::from_nanos(random % u64::try_from(delay.as_nanos()).unwrap_or(u64::MAX))
+    }
+}
+
+static RETRY: RwLock<Option<AcquireRetry>> = RwLock::new(None);
+
+/// Turns acquisition retries on (`Some`) or off (`None`) for the whole process.
+pub fn set_acquire_retry(retry: Option<AcquireRetry>) {
+    *RETRY.write().unwrap_or_else(|e| e.into_inner()) = retry;
+}
+
+/// The retry settings in effect, `None` when retries are off.
+pub fn acquire_retry() -> Option<AcquireRetry> {
+    RETRY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Whether a failed `pool.get()` may succeed when tried again.
+fn retryable(error: &PoolError) -> bool {
+    matches!(error, PoolError::Timeout(_) | PoolError::Backend(_))
+}
+
+/// Takes a connection from `pool`, retrying as set by [`set_acquire_retry`].
+pub async fn acquire(pool: &Pool) -> Result<Object, PoolError> {
+    let retry = acquire_retry();
+    let started = Instant::now();
+    let mut retries = 0;
+    let result = loop {
+        match pool.get().await {
+            Err(e) if retryable(&e) && retry.as_ref().is_some_and(|retry| retries < retry.attempts) => {
+                retries += 1;
+                let wait = retry.as_ref().map(|retry| retry.backoff(retries)).unwrap_or_default();
+                trace::log("PARSQL-TOKIO-POSTGRES-POOL", format_args!("pool.get() failed ({}), retry {} in {:?}", e, retries, wait));
+                tokio::time::sleep(wait).await;
+            }
+            result => break result,
+        }
+    };
+    metrics::record_pool_wait(started.elapsed(), retries, result.is_ok());
+    if let Ok(client) = &result {
+        crate::cancel::track(client);
+    }
+    result
+}
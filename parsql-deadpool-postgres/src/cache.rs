@@ -0,0 +1,255 @@
+//! Read-through caching for `CrudOps`.
+//!
+//! [`Cached`] wraps a client (or anything implementing `CrudOps`) together with a
+//! [`CacheProvider`]. `fetch` and `fetch_all` are answered from the cache when
+//! possible, and `insert`, `update` and `delete` invalidate the cached results that
+//! read the written table. Every other method reaches the wrapped client through
+//! `Deref`, so call sites stay the same.
+//!
+//! Results are keyed by the query and the wire encoding of its values (see
+//! [`encode_postgres_params`](parsql_core::cache::encode_postgres_params)); queries
+//! binding a value of a custom type run uncached. `Cached` implements `CrudOps` too, so
+//! code generic over the trait still invalidates on writes, but its reads are not cached:
+//! the trait's `fetch` does not require the `Clone + 'static` a cached result needs.
+//!
+//!
+//! ```rust,no_run
+//! use deadpool_postgres::{Config, Runtime};
+//! use parsql_deadpool_postgres::cache::{Cached, MemoryCache};
+//! use parsql_deadpool_postgres::macros::{FromRow, Queryable, SqlParams};
+//! use parsql_deadpool_postgres::traits::{FromRow, SqlParams, SqlQuery};
+//! use tokio_postgres::{types::ToSql, Error, NoTls, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow, Clone, Debug)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! pub struct GetUser {
+//!     pub id: i32,
+//!     pub name: String,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut cfg = Config::new();
+//!     cfg.host = Some("localhost".to_string());
+//!     cfg.dbname = Some("test".to_string());
+//!     let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls)?;
+//!
+//!     let db = Cached::new(pool, MemoryCache::new());
+//!     let user = db.fetch(&GetUser { id: 1, name: String::new() }).await?;
+//!     // Served from the cache until the users table is written through `db`
+//!     let again = db.fetch(&GetUser { id: 1, name: String::new() }).await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Pooled connections are handed back and forth, so the invalidation [`listen`]er of a
+//! multi-instance deployment runs on a connection outside the pool:
+//!
+//! ```rust,no_run
+//! # async fn run(pool: deadpool_postgres::Pool) -> Result<(), Box<dyn std::error::Error>> {
+//! use std::sync::Arc;
+//! use parsql_deadpool_postgres::cache::{install_invalidation, listen, Cached, MemoryCache};
+//!
+//! install_invalidation(&pool, "users").await?;
+//!
+//! let cache = Arc::new(MemoryCache::new());
+//! let (listener, connection) = tokio_postgres::connect("host=localhost user=postgres", tokio_postgres::NoTls).await?;
+//! tokio::spawn(listen(listener, connection, cache.clone()));
+//!
+//! let db = Cached::new(pool, cache);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::{poll_fn, Future};
+use std::ops::Deref;
+use std::pin::pin;
+use std::task::Poll;
+
+use deadpool_postgres::Pool;
+use parsql_core::cache::{encode_postgres_params, invalidation_trigger, ReadThrough, INVALIDATION_CHANNEL, LISTEN};
+use parsql_core::trace;
+use postgres::types::FromSql;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_postgres::{AsyncMessage, Client, Connection, Error, Row};
+use crate::acquire::acquire;
+use crate::DeadpoolError;
+
+use crate::traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams};
+
+pub use parsql_core::cache::{CacheKey, CacheProvider, CachedValue, MemoryCache};
+
+/// A `CrudOps` implementation with a read-through cache in front of it.
+pub struct Cached<C, P = MemoryCache> {
+    inner: C,
+    cache: ReadThrough<P>,
+}
+
+impl<C, P: CacheProvider> Cached<C, P> {
+    /// Wraps `inner`, caching its fetch results in `cache`.
+    pub fn new(inner: C, cache: P) -> Self {
+        Self { inner, cache: ReadThrough::new(cache, "PARSQL-TOKIO-POSTGRES-POOL-CACHE") }
+    }
+
+    /// The cache provider.
+    pub fn cache(&self) -> &P {
+        self.cache.provider()
+    }
+
+    /// Returns the wrapped client.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C, P> Deref for Cached<C, P> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.inner
+    }
+}
+
+impl<C, P> Cached<C, P>
+where
+    C: CrudOps + Sync,
+    P: CacheProvider,
+{
+    /// Retrieves a single record, from the cache when it holds one.
+    pub async fn fetch<T>(&self, params: &T) -> Result<T, DeadpoolError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Clone + Send + Sync + 'static,
+    {
+        let values = encode_postgres_params(&params.params());
+        self.cache.fetch_async(T::query(), values.as_deref(), self.inner.fetch(params)).await
+    }
+
+    /// Retrieves all matching records, from the cache when it holds them.
+    pub async fn fetch_all<T>(&self, params: &T) -> Result<Vec<T>, DeadpoolError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Clone + Send + Sync + 'static,
+    {
+        let values = encode_postgres_params(&params.params());
+        self.cache.fetch_async(T::query(), values.as_deref(), self.inner.fetch_all(params)).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<C, P> CrudOps for Cached<C, P>
+where
+    C: CrudOps + Sync,
+    P: CacheProvider,
+{
+    async fn insert<T, R: for<'a> FromSql<'a> + Send + Sync>(&self, entity: T) -> Result<R, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync,
+    {
+        self.cache.written(T::query(), self.inner.insert(entity).await)
+    }
+
+    async fn update<T>(&self, entity: T) -> Result<u64, DeadpoolError>
+    where
+        T: SqlQuery + UpdateParams + Send + Sync,
+    {
+        self.cache.written(T::query(), self.inner.update(entity).await)
+    }
+
+    async fn delete<T>(&self, entity: T) -> Result<u64, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync,
+    {
+        self.cache.written(T::query(), self.inner.delete(entity).await)
+    }
+
+    async fn call<T>(&self, entity: T) -> Result<u64, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync,
+    {
+        self.inner.call(entity).await
+    }
+
+    async fn fetch<T>(&self, params: &T) -> Result<T, DeadpoolError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync,
+    {
+        self.inner.fetch(params).await
+    }
+
+    async fn fetch_all<T>(&self, params: &T) -> Result<Vec<T>, DeadpoolError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync,
+    {
+        self.inner.fetch_all(params).await
+    }
+
+    async fn select<T, R, F>(&self, entity: T, to_model: F) -> Result<R, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync,
+        F: FnOnce(&Row) -> Result<R, Error> + Send + Sync,
+    {
+        self.inner.select(entity, to_model).await
+    }
+
+    async fn select_all<T, R, F>(&self, entity: T, to_model: F) -> Result<Vec<R>, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync,
+        F: Fn(&Row) -> R + Send + Sync,
+    {
+        self.inner.select_all(entity, to_model).await
+    }
+}
+
+/// Creates the trigger that reports every write to `table` to the [`listen`]ers.
+///
+/// Run it once per cached table; running it again replaces the trigger. Name the
+/// table the way the cached queries do.
+///
+/// # Panics
+/// If `table` is not a plain or schema-qualified name.
+pub async fn install_invalidation(pool: &Pool, table: &str) -> Result<(), DeadpoolError> {
+    let sql = invalidation_trigger(table)
+        .unwrap_or_else(|| panic!("install_invalidation: `{}` is not a plain table name", table));
+    let client = acquire(pool).await?;
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL-CACHE", &sql);
+    Ok(client.batch_execute(&sql).await?)
+}
+
+/// Drops the cached results reading a table whenever the database reports a write to it.
+///
+/// Takes a connection of its own from `tokio_postgres::connect` and drives it; spawn
+/// the returned future. It completes when the connection closes, or with the error
+/// that closed it.
+pub async fn listen<S, T, P>(client: Client, mut connection: Connection<S, T>, cache: P) -> Result<(), Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: AsyncRead + AsyncWrite + Unpin,
+    P: CacheProvider,
+{
+    let mut subscribe = pin!(client.batch_execute(LISTEN));
+    let mut subscribed = false;
+
+    // LISTEN ancak bağlantı sürülürken tamamlanır, bu yüzden ikisi aynı döngüde yoklanır
+    poll_fn(|cx| loop {
+        if !subscribed {
+            match subscribe.as_mut().poll(cx) {
+                Poll::Ready(Ok(())) => subscribed = true,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => {}
+            }
+        }
+        match connection.poll_message(cx) {
+            Poll::Ready(Some(Ok(AsyncMessage::Notification(notification)))) => {
+                if notification.channel() == INVALIDATION_CHANNEL {
+                    trace::log("PARSQL-TOKIO-POSTGRES-POOL-CACHE", format!("Invalidated: {}", notification.payload()));
+                    cache.invalidate(notification.payload());
+                }
+            }
+            Poll::Ready(Some(Ok(_))) => {}
+            Poll::Ready(Some(Err(e))) => return Poll::Ready(Err(e)),
+            Poll::Ready(None) => return Poll::Ready(Ok(())),
+            Poll::Pending => return Poll::Pending,
+        }
+    })
+    .await
+}
@@ -0,0 +1,133 @@
+//! Deadlines and cancellation of pool operations.
+//!
+//! A CRUD function waits for a pooled connection and then for the server, and either
+//! can stall: the pool is exhausted, or a query is blocked by a lock. Request-scoped
+//! handlers wrap the call in a [`Cancellation`] with a deadline, a cancellation token
+//! or both, and get [`DeadpoolError::Timeout`] or [`DeadpoolError::Cancelled`] instead
+//! of waiting on:
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use parsql_deadpool_postgres::{cancel::Cancellation, get, CancellationToken, DeadpoolError, Pool};
+//! use parsql_deadpool_postgres::traits::{FromRow, SqlParams, SqlQuery};
+//!
+//! async fn handle<T>(pool: &Pool, query: &T, shutdown: CancellationToken) -> Result<T, DeadpoolError>
+//! where
+//!     T: SqlQuery + FromRow + SqlParams,
+//! {
+//!     let limits = Cancellation::new().timeout(Duration::from_secs(2)).token(shutdown);
+//!     limits.run(get(pool, query)).await
+//! }
+//! ```
+//!
+//! When the operation is aborted after it took a connection, [`run`](Cancellation::run)
+//! sends a cancel request for the statement running on that connection before
+//! dropping the operation, so the connection goes back to the pool idle instead of
+//! still executing the aborted statement. The cancel request is sent without TLS; a
+//! server that only accepts TLS connections refuses it (logged through `trace`), and
+//! the statement then runs until it finishes. Bound long queries with
+//! `statement_timeout` as well.
+
+use std::future::{pending, Future};
+use std::pin::pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use deadpool_postgres::Object;
+use futures_util::future::{select, Either};
+use parsql_core::trace;
+use tokio::time::Instant;
+use tokio_postgres::{CancelToken, NoTls};
+use tokio_util::sync::CancellationToken;
+
+use crate::DeadpoolError;
+
+tokio::task_local! {
+    // `run` içindeki işlemin havuzdan aldığı bağlantılar
+    static ACQUIRED: Arc<Mutex<Vec<CancelToken>>>;
+}
+
+/// Remembers `client` so that an aborted [`Cancellation::run`] around the current
+/// operation can cancel the statement running on it.
+pub(crate) fn track(client: &Object) {
+    let _ = ACQUIRED.try_with(|acquired| {
+        acquired
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(client.cancel_token())
+    });
+}
+
+/// When a pool operation gives up.
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation {
+    deadline: Option<Instant>,
+    token: Option<CancellationToken>,
+}
+
+impl Cancellation {
+    /// No deadline and no token; [`run`](Self::run) waits for the operation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives up `timeout` from now.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.deadline(Instant::now() + timeout)
+    }
+
+    /// Gives up at `deadline`, e.g. the deadline of the request being served.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Gives up when `token` is cancelled.
+    pub fn token(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Runs `operation`, e.g. `get(&pool, &query)`, until it completes, the deadline
+    /// passes or the token is cancelled.
+    pub async fn run<F, T>(&self, operation: F) -> Result<T, DeadpoolError>
+    where
+        F: Future<Output = Result<T, DeadpoolError>>,
+    {
+        let acquired = Arc::new(Mutex::new(Vec::new()));
+        let mut operation = pin!(ACQUIRED.scope(acquired.clone(), operation));
+        let error = match select(operation.as_mut(), pin!(self.aborted())).await {
+            Either::Left((result, _)) => return result,
+            Either::Right((error, _)) => error,
+        };
+
+        // İşlem bağlantıyı hâlâ tutarken iptal gönderilir; bağlantı havuza boşta döner
+        let acquired = std::mem::take(&mut *acquired.lock().unwrap_or_else(PoisonError::into_inner));
+        for token in acquired {
+            if let Err(e) = token.cancel_query(NoTls).await {
+                trace::log("PARSQL-TOKIO-POSTGRES-POOL", format_args!("cancel request failed: {}", e));
+            }
+        }
+        Err(error)
+    }
+
+    /// Resolves to the error of whichever limit is reached first.
+    async fn aborted(&self) -> DeadpoolError {
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => pending().await,
+            }
+        };
+        let cancelled = async {
+            match &self.token {
+                Some(token) => token.cancelled().await,
+                None => pending().await,
+            }
+        };
+        match select(pin!(deadline), pin!(cancelled)).await {
+            Either::Left(_) => DeadpoolError::Timeout,
+            Either::Right(_) => DeadpoolError::Cancelled,
+        }
+    }
+}
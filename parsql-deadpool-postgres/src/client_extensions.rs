@@ -0,0 +1,124 @@
+use deadpool_postgres::Client;
+use postgres::types::FromSql;
+use tokio_postgres::{Error, Row};
+use crate::traits::{SqlQuery, SqlParams, UpdateParams, FromRow, CrudOps};
+use crate::DeadpoolError;
+use parsql_core::{metrics::observe_async, trace};
+
+/// Havuzdan alınan istemci (`pool.get().await?`) için CrudOps trait'inin implementasyonu
+#[async_trait::async_trait]
+impl CrudOps for Client {
+    async fn insert<T, P:for<'a> FromSql<'a> + Send + Sync>(&self, entity: T) -> Result<P, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync
+    {
+        entity.validation()?;
+
+        let sql = T::query();
+        
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+        let params = entity.params();
+        let row = observe_async("insert", T::table_name(), self.query_one(sql, &params)).await?;
+        Ok(row.try_get::<_, P>(0)?)
+    }
+
+    async fn update<T>(&self, entity: T) -> Result<u64, DeadpoolError>
+    where
+        T: SqlQuery + UpdateParams + Send + Sync
+    {
+        entity.validation()?;
+
+        let sql = T::query();
+        
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+        let params = entity.params();
+        Ok(observe_async("update", T::table_name(), self.execute(sql, &params)).await?)
+    }
+
+    async fn delete<T>(&self, entity: T) -> Result<u64, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync
+    {
+        let sql = T::query();
+        
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+        let params = entity.params();
+        Ok(observe_async("delete", T::table_name(), self.execute(sql, &params)).await?)
+    }
+
+    async fn call<T>(&self, entity: T) -> Result<u64, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync
+    {
+        let sql = T::query();
+        
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+        let params = entity.params();
+        Ok(observe_async("call", T::table_name(), self.execute(sql, &params)).await?)
+    }
+
+    async fn fetch<T>(&self, params: &T) -> Result<T, DeadpoolError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync
+    {
+        let sql = T::query_single();
+        
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+        let query_params = params.params();
+        let row = observe_async("fetch", T::table_name(), self.query_one(sql, &query_params)).await?;
+        Ok(T::from_row(&row)?)
+    }
+
+    async fn fetch_all<T>(&self, params: &T) -> Result<Vec<T>, DeadpoolError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync
+    {
+        let sql = T::query();
+        
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+        let query_params = params.params();
+        let rows = observe_async("fetch_all", T::table_name(), self.query(sql, &query_params)).await?;
+        
+        Ok(T::from_rows(&rows)?)
+    }
+
+    async fn select<T, R, F>(&self, entity: T, to_model: F) -> Result<R, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync,
+        F: FnOnce(&Row) -> Result<R, Error> + Send + Sync
+    {
+        let sql = T::query_single();
+        
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+        let params = entity.params();
+        let row = observe_async("select", T::table_name(), self.query_one(sql, &params)).await?;
+        Ok(to_model(&row)?)
+    }
+
+    async fn select_all<T, R, F>(&self, entity: T, to_model: F) -> Result<Vec<R>, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync,
+        F: Fn(&Row) -> R + Send + Sync
+    {
+        let sql = T::query();
+        
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+        let params = entity.params();
+        let rows = observe_async("select_all", T::table_name(), self.query(sql, &params)).await?;
+        
+        let mut results = Vec::with_capacity(rows.len());
+        for row in rows {
+            results.push(to_model(&row));
+        }
+        
+        Ok(results)
+    }
+} 
\ No newline at end of file
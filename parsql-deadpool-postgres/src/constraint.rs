@@ -0,0 +1,63 @@
+//! Typed constraint violations.
+//!
+//! [`ConstraintViolation`] reads the constraint behind a failed statement from a `DeadpoolError` (or the `tokio_postgres::Error` of a transaction),
+//! so a duplicate key or a missing reference can be reported to the user without
+//! matching on SQLSTATE codes or messages:
+//!
+//! ```rust,no_run
+//! use parsql_deadpool_postgres::constraint::ConstraintViolation;
+//!
+//! fn describe(error: &parsql_deadpool_postgres::DeadpoolError) -> String {
+//!     match error.constraint() {
+//!         Some(violation) if violation.is("users_email_key") => "this e-mail is already registered".into(),
+//!         Some(violation) => format!("rejected by {:?}", violation.constraint_name),
+//!         None => error.to_string(),
+//!     }
+//! }
+//! ```
+
+use tokio_postgres::Error;
+
+use crate::DeadpoolError;
+
+pub use parsql_core::constraint::{ConstraintError, ConstraintKind};
+
+/// Access to the constraint violation behind an error.
+pub trait ConstraintViolation {
+    /// The violated constraint, or `None` when the error has another cause.
+    fn constraint(&self) -> Option<ConstraintError>;
+
+    /// Returns whether a `UNIQUE` or primary key constraint was violated.
+    fn is_unique_violation(&self) -> bool {
+        self.constraint().is_some_and(|c| c.kind == ConstraintKind::Unique)
+    }
+
+    /// Returns whether a foreign key constraint was violated.
+    fn is_foreign_key_violation(&self) -> bool {
+        self.constraint().is_some_and(|c| c.kind == ConstraintKind::ForeignKey)
+    }
+
+    /// Returns whether a `CHECK` constraint was violated.
+    fn is_check_violation(&self) -> bool {
+        self.constraint().is_some_and(|c| c.kind == ConstraintKind::Check)
+    }
+}
+
+impl ConstraintViolation for Error {
+    fn constraint(&self) -> Option<ConstraintError> {
+        let db = self.as_db_error()?;
+        Some(ConstraintError {
+            kind: ConstraintKind::from_sqlstate(db.code().code())?,
+            constraint_name: db.constraint().map(str::to_string),
+            table: db.table().map(str::to_string),
+            column: db.column().map(str::to_string),
+            message: db.message().to_string(),
+        })
+    }
+}
+
+impl ConstraintViolation for DeadpoolError {
+    fn constraint(&self) -> Option<ConstraintError> {
+        self.as_postgres()?.constraint()
+    }
+}
@@ -1,20 +1,14 @@
-use deadpool_postgres::{Pool, PoolError};
+use std::hash::Hash;
+
+use deadpool_postgres::Pool;
 use postgres::types::FromSqlOwned;
 //use postgres::types::FromSql;
-use tokio_postgres::{Error, Row, types::FromSql};
-use crate::traits::{SqlQuery, SqlParams, UpdateParams, FromRow};
-
-// Daha basit bir yaklaşım: PoolError'dan genel bir Error oluştur
-fn pool_err_to_io_err(e: PoolError) -> Error {
-    // Bu özel fonksiyon tokio_postgres'in sağladığı timeout hatasını döndürür
-    // Güzel bir çözüm değil, ama çalışır bir örnek için kullanılabilir
-    let err = Error::__private_api_timeout();
-    
-    // Debug süreci için stderr'e hatayı yazdıralım
-    eprintln!("Pool bağlantı hatası: {}", e);
-    
-    err
-}
+use parsql_core::{batch::{multi_row_insert, with_returning, with_upsert, BatchOptions, Dialect}, criteria, lookup::{self, Lookup}, metrics::observe_async, paging::{check_sortable, with_first_row, with_limit_offset}, trace};
+use tokio_postgres::{Error, GenericClient, Row, types::{FromSql, ToSql}};
+use crate::acquire::acquire;
+use crate::traits::{SqlQuery, SqlParams, UpdateParams, FromRow, FetchBatch, Keyed, Paginated, Patch, WhereFragments};
+use futures_util::future::try_join_all;
+use crate::DeadpoolError;
 
 /// # insert
 /// 
@@ -25,7 +19,7 @@ fn pool_err_to_io_err(e: PoolError) -> Error {
 /// - `entity`: Eklenecek veri nesnesi (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
 /// 
 /// ## Dönüş Değeri
-/// - `Result<i64, Error>`: Başarılı olursa, eklenen kayıt ID'sini döndürür; başarısız olursa, Error döndürür
+/// - `Result<i64, DeadpoolError>`: Başarılı olursa, eklenen kayıt ID'sini döndürür; başarısız olursa, DeadpoolError döndürür
 /// 
 /// ## Yapı Tanımı
 /// Bu fonksiyonla kullanılan yapılar aşağıdaki derive makrolarıyla işaretlenmelidir:
@@ -59,7 +53,7 @@ fn pool_err_to_io_err(e: PoolError) -> Error {
 /// }
 ///
 /// #[tokio::main]
-/// async fn main() -> Result<(), Error> {
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let mut cfg = Config::new();
 ///     cfg.host = Some("localhost".to_string());
 ///     cfg.dbname = Some("test".to_string());
@@ -89,28 +83,132 @@ fn pool_err_to_io_err(e: PoolError) -> Error {
 //     }
 
 //     let params = entity.params();
-//     let row = client.query_one(&sql, &params).await?;
+//     let row = client.query_one(sql, &params).await?;
 //     row.try_get::<_, P>(0)
 // }
 
 pub async fn insert<T, P>(
     pool: &Pool,
     entity: T,
-) -> Result<P, Error>
+) -> Result<P, DeadpoolError>
 where
     T: SqlQuery + SqlParams,
     P: FromSqlOwned + Send + Sync,
 {
-    let client = pool.get().await.map_err(pool_err_to_io_err)?;
+    entity.validation()?;
+
+    let client = acquire(pool).await?;
     let sql = T::query();
 
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-POOL] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+    let params = entity.params();
+    let row = observe_async("insert", T::table_name(), client.query_one(sql, &params)).await?;
+    Ok(row.try_get::<_, P>(0)?)
+}
+
+/// # insert_returning_id
+/// 
+/// Deadpool bağlantı havuzunu kullanarak yeni bir kayıt ekler ve üretilen `id` değerini döndürür.
+/// Yapıda `#[returning]` özniteliği yoksa sorguya `RETURNING id` eklenir; `SELECT lastval()` gerekmez.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `entity`: Eklenecek veri nesnesi (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
+/// 
+/// ## Dönüş Değeri
+/// - `Result<K, DeadpoolError>`: Başarılı olursa, üretilen anahtarı döndürür; başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Kullanım Örneği
+/// ```rust,ignore
+/// // INSERT INTO users (name, email ) VALUES ($1, $2 ) RETURNING id
+/// let id: i64 = insert_returning_id(&pool, InsertUser { name, email }).await?;
+/// ```
+pub async fn insert_returning_id<T, K>(
+    pool: &Pool,
+    entity: T,
+) -> Result<K, DeadpoolError>
+where
+    T: SqlQuery + SqlParams,
+    K: FromSqlOwned + Send + Sync,
+{
+    insert_returning(pool, entity, "id").await
+}
+
+/// # insert_returning
+/// 
+/// Anahtar sütunu `id` dışında bir ada sahip tablolar için [`insert_returning_id`].
+/// Yapıdaki `#[returning]` özniteliği `column` parametresinden önceliklidir.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `entity`: Eklenecek veri nesnesi (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
+/// - `column`: Döndürülecek anahtar sütunu
+/// 
+/// ## Dönüş Değeri
+/// - `Result<K, DeadpoolError>`: Başarılı olursa, `column` değerini döndürür; başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Panik
+/// `column` düz bir sütun adı değilse panik oluşur.
+pub async fn insert_returning<T, K>(
+    pool: &Pool,
+    entity: T,
+    column: &str,
+) -> Result<K, DeadpoolError>
+where
+    T: SqlQuery + SqlParams,
+    K: FromSqlOwned + Send + Sync,
+{
+    entity.validation()?;
+    let sql = with_returning(T::query(), column)
+        .unwrap_or_else(|| panic!("insert_returning: `{}` is not a plain column name", column));
+
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let params = entity.params();
+    let row = observe_async("insert", T::table_name(), client.query_one(sql.as_str(), &params)).await?;
+    Ok(row.try_get::<_, K>(0)?)
+}
+
+/// # insert_returning_opt
+/// 
+/// Tekrar çalıştırılabilen eklemeler için [`insert_returning`]. Sorgu var olan bir kaydı
+/// `ON CONFLICT DO NOTHING` ile atladığında (bkz. `#[on_conflict_do_nothing]`) satır dönmez
+/// ve sonuç hata yerine `None` olur.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `entity`: Eklenecek veri nesnesi (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
+/// - `column`: Döndürülecek anahtar sütunu
+/// 
+/// ## Dönüş Değeri
+/// - `Result<Option<K>, DeadpoolError>`: Başarılı olursa, `column` değerini, kayıt zaten varsa `None`
+///   döndürür; başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Panik
+/// `column` düz bir sütun adı değilse panik oluşur.
+pub async fn insert_returning_opt<T, K>(
+    pool: &Pool,
+    entity: T,
+    column: &str,
+) -> Result<Option<K>, DeadpoolError>
+where
+    T: SqlQuery + SqlParams,
+    K: FromSqlOwned + Send + Sync,
+{
+    entity.validation()?;
+    let sql = with_returning(T::query(), column)
+        .unwrap_or_else(|| panic!("insert_returning_opt: `{}` is not a plain column name", column));
+
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
 
     let params = entity.params();
-    let row = client.query_one(&sql, &params).await?;
-    row.try_get::<_, P>(0)
+    let row = observe_async("insert", T::table_name(), client.query_opt(sql.as_str(), &params)).await?;
+    Ok(row.map(|row| row.try_get::<_, K>(0)).transpose()?)
 }
 
 /// # update
@@ -122,7 +220,7 @@ where
 /// - `entity`: Güncelleme bilgilerini içeren veri nesnesi (SqlQuery ve UpdateParams trait'lerini uygulamalıdır)
 /// 
 /// ## Dönüş Değeri
-/// - `Result<bool, Error>`: Başarılı olursa, true döndürür; başarısız olursa, Error döndürür
+/// - `Result<u64, DeadpoolError>`: Başarılı olursa, güncellenen kayıt sayısını döndürür; başarısız olursa, DeadpoolError döndürür
 /// 
 /// ## Yapı Tanımı
 /// Bu fonksiyonla kullanılan yapılar aşağıdaki derive makrolarıyla işaretlenmelidir:
@@ -164,7 +262,7 @@ where
 /// }
 ///
 /// #[tokio::main]
-/// async fn main() -> Result<(), Error> {
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let mut cfg = Config::new();
 ///     cfg.host = Some("localhost".to_string());
 ///     cfg.dbname = Some("test".to_string());
@@ -178,27 +276,41 @@ where
 ///         state: 2,
 ///     };
 ///
-///     let update_result = update(&pool, update_user).await?;
-///     println!("Update result: {:?}", update_result);
+///     let rows_affected = update(&pool, update_user).await?;
+///     println!("Updated rows: {}", rows_affected);
 ///     Ok(())
 /// }
 /// ```
 pub async fn update<T: SqlQuery + UpdateParams>(
     pool: &Pool,
     entity: T,
-) -> Result<bool, Error> {
-    let client = pool.get().await.map_err(pool_err_to_io_err)?;
+) -> Result<u64, DeadpoolError> {
+    entity.validation()?;
+
+    let client = acquire(pool).await?;
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-POOL] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
 
     let params = entity.params();
-    match client.execute(&sql, &params).await {
-        Ok(_) => Ok(true),
-        Err(e) => Err(e),
-    }
+    Ok(observe_async("update", T::table_name(), client.execute(sql, &params)).await?)
+}
+
+// `update` önceden bool döndürüyordu; eski davranışı bekleyen kod için korunuyor
+#[deprecated(
+    since = "0.4.0",
+    note = "`update` now returns the number of affected rows; compare it with 0 instead."
+)]
+/// # update_applied
+/// 
+/// Kaydı günceller ve en az bir kaydın güncellenip güncellenmediğini döndürür.
+/// 
+/// Bu fonksiyon kullanımdan kaldırılmıştır. Lütfen `update` kullanın.
+pub async fn update_applied<T: SqlQuery + UpdateParams>(
+    pool: &Pool,
+    entity: T,
+) -> Result<bool, DeadpoolError> {
+    Ok(update(pool, entity).await? > 0)
 }
 
 /// # delete
@@ -210,7 +322,7 @@ pub async fn update<T: SqlQuery + UpdateParams>(
 /// - `entity`: Silme bilgilerini içeren veri nesnesi (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
 /// 
 /// ## Dönüş Değeri
-/// - `Result<u64, Error>`: Başarılı olursa, silinen kayıt sayısını döndürür; başarısız olursa, Error döndürür
+/// - `Result<u64, DeadpoolError>`: Başarılı olursa, silinen kayıt sayısını döndürür; başarısız olursa, DeadpoolError döndürür
 /// 
 /// ## Yapı Tanımı
 /// Bu fonksiyonla kullanılan yapılar aşağıdaki derive makrolarıyla işaretlenmelidir:
@@ -244,7 +356,7 @@ pub async fn update<T: SqlQuery + UpdateParams>(
 /// }
 /// 
 /// #[tokio::main]
-/// async fn main() -> Result<(), Error> {
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let mut cfg = Config::new();
 ///     cfg.host = Some("localhost".to_string());
 ///     cfg.dbname = Some("test".to_string());
@@ -261,19 +373,255 @@ pub async fn update<T: SqlQuery + UpdateParams>(
 pub async fn delete<T: SqlQuery + SqlParams>(
     pool: &Pool,
     entity: T,
-) -> Result<u64, Error> {
-    let client = pool.get().await.map_err(pool_err_to_io_err)?;
+) -> Result<u64, DeadpoolError> {
+    let client = acquire(pool).await?;
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-POOL] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
 
-    let params = entity.params();
-    match client.execute(&sql, &params).await {
-        Ok(rows_affected) => Ok(rows_affected),
-        Err(e) => Err(e),
+    Ok(observe_async("delete", T::table_name(), crate::params::execute(&**client, sql, &entity)).await?)
+}
+
+/// # update_patch
+/// 
+/// Deadpool bağlantı havuzunu kullanarak bir kaydın yalnızca `Patch` içinde `Some` olan
+/// alanlarını günceller. `SET` ifadesi çalışma zamanında bu alanlardan kurulur, anahtar
+/// en son bağlanır.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `key`: Güncellenecek kaydın anahtarı
+/// - `patch`: `Patch` derive makrosunun ürettiği `<Model>Patch` değeri
+/// 
+/// ## Dönüş Değeri
+/// - `Result<u64, DeadpoolError>`: Başarılı olursa, güncellenen kayıt sayısını döndürür (hiçbir alan
+///   verilmemişse sorgu çalıştırmadan 0); başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Kullanım Örneği
+/// ```rust,no_run
+/// // UPDATE users SET email = $1 WHERE id = $2
+/// let patch = UserPatch { email: Some("yeni@example.com".to_string()), ..Default::default() };
+/// let updated = update_patch(&pool, 1, patch).await?;
+/// ```
+pub async fn update_patch<P: Patch>(
+    pool: &Pool,
+    key: P::Key,
+    patch: P,
+) -> Result<u64, DeadpoolError> {
+    let Some(sql) = patch.patch_sql() else {
+        return Ok(0);
+    };
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let mut params = patch.values();
+    params.push(&key);
+    Ok(observe_async("update", P::table(), client.execute(sql.as_str(), &params)).await?)
+}
+
+/// # delete_where
+/// 
+/// Deadpool bağlantı havuzunu kullanarak bir `Queryable`'ın seçtiği kayıtları siler.
+/// Koşul `Deletable` tipinde tekrar yazılmaz, sorgunun `#[where_clause]` ifadesi kullanılır.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `criteria`: Tablosu ve WHERE koşulu silinecek kayıtları belirleyen sorgu (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
+/// 
+/// ## Dönüş Değeri
+/// - `Result<u64, DeadpoolError>`: Başarılı olursa, silinen kayıt sayısını döndürür; başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Panik
+/// Sorguda WHERE koşulu yoksa ya da sorgu `#[join]` / `union` ile okuyorsa.
+/// 
+/// ## Kullanım Örneği
+/// ```rust,no_run
+/// // DELETE FROM users WHERE state = $1 AND last_login < $2
+/// let deleted = delete_where(&pool, &GetInactiveUsers::new(0, cutoff)).await?;
+/// ```
+pub async fn delete_where<T: SqlQuery + SqlParams>(
+    pool: &Pool,
+    criteria: &T,
+) -> Result<u64, DeadpoolError> {
+    let (table, where_clause) = T::criteria().unwrap_or_else(|| {
+        panic!("delete_where: `{}` has no single-table WHERE clause to reuse", std::any::type_name::<T>())
+    });
+    let client = acquire(pool).await?;
+    let sql = criteria::delete_statement(table, where_clause);
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let params = criteria.params();
+    let params = &params[..criteria::where_param_count(where_clause)];
+    Ok(observe_async("delete", T::table_name(), client.execute(sql.as_str(), params)).await?)
+}
+
+/// # update_where
+/// 
+/// Deadpool bağlantı havuzunu kullanarak bir `Patch` içinde `Some` olan alanları, bir
+/// `Queryable`'ın `#[where_clause]` koşuluyla seçtiği kayıtlara yazar. Önce yamanın
+/// değerleri, ardından sorgunun parametreleri bağlanır.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `criteria`: Tablosu ve WHERE koşulu güncellenecek kayıtları belirleyen sorgu (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
+/// - `changes`: Aynı tablo için `Patch` derive makrosunun ürettiği `<Model>Patch` değeri
+/// 
+/// ## Dönüş Değeri
+/// - `Result<u64, DeadpoolError>`: Başarılı olursa, güncellenen kayıt sayısını döndürür (hiçbir alan
+///   verilmemişse sorgu çalıştırmadan 0); başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Panik
+/// Sorguda WHERE koşulu yoksa, sorgu `#[join]` / `union` ile okuyorsa ya da yama başka bir
+/// tabloya aitse.
+pub async fn update_where<T: SqlQuery + SqlParams, P: Patch>(
+    pool: &Pool,
+    criteria: &T,
+    changes: &P,
+) -> Result<u64, DeadpoolError> {
+    let (table, where_clause) = T::criteria().unwrap_or_else(|| {
+        panic!("update_where: `{}` has no single-table WHERE clause to reuse", std::any::type_name::<T>())
+    });
+    if P::table() != table {
+        panic!("update_where: the patch updates `{}` but the criteria select from `{}`", P::table(), table);
     }
+    let Some(sql) = criteria::update_statement(table, &changes.columns(), where_clause, Dialect::Postgres) else {
+        return Ok(0);
+    };
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let criteria_params = criteria.params();
+    let mut params = changes.values();
+    params.extend_from_slice(&criteria_params[..criteria::where_param_count(where_clause)]);
+    Ok(observe_async("update", T::table_name(), client.execute(sql.as_str(), &params)).await?)
+}
+
+/// # get_all_combined
+/// 
+/// Deadpool bağlantı havuzunu kullanarak `filters` içindeki tüm filtrelere uyan `T`
+/// kayıtlarını alır. Her filtrenin `#[where_clause]` koşulu `AND` ile birleştirilir ve yer
+/// tutucuları kendinden önceki filtrelerden sonra gelecek şekilde yeniden numaralanır;
+/// böylece bir filtre yapısı tablosu üzerindeki her sorguda kullanılabilir.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `filters`: Filtre demeti, ör. `(&by_state, &created_since)`; her biri tek tablolu WHERE
+///   koşulu olan bir `Queryable` olmalıdır
+/// 
+/// ## Dönüş Değeri
+/// - `Result<Vec<T>, DeadpoolError>`: Başarılı olursa, eşleşen kayıtları döndürür; başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Hatalar
+/// `T` sorgusu parametre bağlıyorsa ya da bir `UNION` ise, bağlantı alınmadan
+/// `DeadpoolError::Combine` döner.
+/// 
+/// ## Panik
+/// Bir filtrenin tek tablolu WHERE koşulu yoksa.
+/// 
+/// ## Örnek Kullanım
+/// ```rust,ignore
+/// #[derive(Queryable, SqlParams)]
+/// #[table("users")]
+/// #[select("id")]
+/// #[where_clause("state = $")]
+/// pub struct ByState {
+///     pub state: i16,
+/// }
+///
+/// #[derive(Queryable, SqlParams)]
+/// #[table("users")]
+/// #[select("id")]
+/// #[where_clause("created_at >= $since")]
+/// pub struct CreatedSince {
+///     pub since: NaiveDateTime,
+/// }
+///
+/// // SELECT id, name FROM users WHERE (state = $1) AND (created_at >= $2) ORDER BY id
+/// let users: Vec<UserRow> = get_all_combined(&pool, (&ByState { state: 1 }, &CreatedSince { since })).await?;
+/// ```
+pub async fn get_all_combined<T: SqlQuery + FromRow, F: WhereFragments>(
+    pool: &Pool,
+    filters: F,
+) -> Result<Vec<T>, DeadpoolError> {
+    let sql = criteria::combined_query::<T>(&filters.conditions(), Dialect::Postgres)?;
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let params = filters.params();
+    let rows = observe_async("fetch_all", T::table_name(), client.query(sql.as_str(), &params)).await?;
+    Ok(T::from_rows(&rows)?)
+}
+
+/// # batch_get
+/// 
+/// Deadpool bağlantı havuzundan alınan tek bir bağlantıda birbirinden bağımsız birkaç
+/// SELECT sorgusunu tek gidiş-dönüşte çalıştırır ve her birinin kayıtlarını döndürür.
+/// İstekler ilk yanıt beklenmeden art arda gönderilir; birkaç sorgu yapan bir pano,
+/// yaklaşık olarak tek bir sorgu kadar bekler.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `queries`: En fazla altı sorgu referansından oluşan demet, ör. `(&active_users, &recent_orders)`
+/// 
+/// ## Dönüş Değeri
+/// - `Result<(Vec<A>, Vec<B>, ...), DeadpoolError>`: Başarılı olursa, her sorgunun kayıtlarını demetteki sırayla döndürür; başarısız olursa, ilk başarısız sorgunun hatasını döndürür
+/// 
+/// ## Örnek Kullanım
+/// ```rust,ignore
+/// let (users, orders, counts) = batch_get(&pool, (&ActiveUsers::new(1), &RecentOrders::new(since), &UserCount::new(1))).await?;
+/// ```
+/// 
+/// Sorgular ayrı ifadeler olarak çalışır; bir transaction dışında veritabanının farklı
+/// anlık görüntülerini görebilirler.
+pub async fn batch_get<Q: FetchBatch>(pool: &Pool, queries: Q) -> Result<Q::Output, DeadpoolError> {
+    let client = acquire(pool).await?;
+    let statements = queries.statements();
+    let pending = statements.iter().zip(Q::tables()).map(|((sql, params), table)| {
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+        observe_async("batch_fetch", table, client.query(*sql, params))
+    });
+
+    // Futures birlikte beklendiğinde istekler yanıt beklenmeden gönderilir
+    Ok(Q::from_results(try_join_all(pending).await?)?)
+}
+
+/// # call
+/// 
+/// Deadpool bağlantı havuzunu kullanarak bir saklı yordamı (`CALL`) ya da fonksiyonu
+/// (`SELECT`) `Callable` yapısındaki argümanlarla çalıştırır. Fonksiyonun döndürdüğü
+/// değeri okumak için `select` kullanılır.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `entity`: Çağrının argümanları (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
+/// 
+/// ## Dönüş Değeri
+/// - `Result<u64, DeadpoolError>`: Başarılı olursa, çağrının döndürdüğü ya da etkilediği satır sayısını döndürür; başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Kullanım Örneği
+/// ```rust,no_run
+/// #[derive(Callable, SqlParams)]
+/// #[procedure("refresh_user_stats")]
+/// pub struct RefreshUserStats {
+///     pub state: i16,
+/// }
+/// 
+/// let affected = call(&pool, RefreshUserStats { state: 1 }).await?;
+/// ```
+pub async fn call<T: SqlQuery + SqlParams>(
+    pool: &Pool,
+    entity: T,
+) -> Result<u64, DeadpoolError> {
+    let client = acquire(pool).await?;
+    let sql = T::query();
+    
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+    Ok(observe_async("call", T::table_name(), crate::params::execute(&**client, sql, &entity)).await?)
 }
 
 /// # get
@@ -285,7 +633,7 @@ pub async fn delete<T: SqlQuery + SqlParams>(
 /// - `params`: Sorgu parametrelerini içeren veri nesnesi (SqlQuery, FromRow ve SqlParams trait'lerini uygulamalıdır)
 /// 
 /// ## Dönüş Değeri
-/// - `Result<T, Error>`: Başarılı olursa, alınan kaydı döndürür; başarısız olursa, Error döndürür
+/// - `Result<T, DeadpoolError>`: Başarılı olursa, alınan kaydı döndürür; başarısız olursa, DeadpoolError döndürür
 /// 
 /// ## Yapı Tanımı
 /// Bu fonksiyonla kullanılan yapılar aşağıdaki derive makrolarıyla işaretlenmelidir:
@@ -336,7 +684,7 @@ pub async fn delete<T: SqlQuery + SqlParams>(
 /// }
 /// 
 /// #[tokio::main]
-/// async fn main() -> Result<(), Error> {
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let mut cfg = Config::new();
 ///     cfg.host = Some("localhost".to_string());
 ///     cfg.dbname = Some("test".to_string());
@@ -353,17 +701,15 @@ pub async fn delete<T: SqlQuery + SqlParams>(
 pub async fn get<T: SqlQuery + FromRow + SqlParams>(
     pool: &Pool,
     params: &T,
-) -> Result<T, Error> {
-    let client = pool.get().await.map_err(pool_err_to_io_err)?;
-    let sql = T::query();
+) -> Result<T, DeadpoolError> {
+    let client = acquire(pool).await?;
+    let sql = T::query_single();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-POOL] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
 
     let params = params.params();
-    let row = client.query_one(&sql, &params).await?;
-    T::from_row(&row)
+    let row = observe_async("fetch", T::table_name(), client.query_one(sql, &params)).await?;
+    Ok(T::from_row(&row)?)
 }
 
 /// # get_all
@@ -375,7 +721,7 @@ pub async fn get<T: SqlQuery + FromRow + SqlParams>(
 /// - `params`: Sorgu parametrelerini içeren veri nesnesi (SqlQuery, FromRow ve SqlParams trait'lerini uygulamalıdır)
 /// 
 /// ## Dönüş Değeri
-/// - `Result<Vec<T>, Error>`: Başarılı olursa, alınan kayıtları içeren bir vektör döndürür; başarısız olursa, Error döndürür
+/// - `Result<Vec<T>, DeadpoolError>`: Başarılı olursa, alınan kayıtları içeren bir vektör döndürür; başarısız olursa, DeadpoolError döndürür
 /// 
 /// ## Yapı Tanımı
 /// Bu fonksiyonla kullanılan yapılar aşağıdaki derive makrolarıyla işaretlenmelidir:
@@ -427,7 +773,7 @@ pub async fn get<T: SqlQuery + FromRow + SqlParams>(
 /// }
 /// 
 /// #[tokio::main]
-/// async fn main() -> Result<(), Error> {
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let mut cfg = Config::new();
 ///     cfg.host = Some("localhost".to_string());
 ///     cfg.dbname = Some("test".to_string());
@@ -444,20 +790,280 @@ pub async fn get<T: SqlQuery + FromRow + SqlParams>(
 pub async fn get_all<T: SqlQuery + FromRow + SqlParams>(
     pool: &Pool,
     params: &T,
-) -> Result<Vec<T>, Error> {
-    let client = pool.get().await.map_err(pool_err_to_io_err)?;
+) -> Result<Vec<T>, DeadpoolError> {
+    let client = acquire(pool).await?;
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-POOL] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+    let rows = observe_async("fetch_all", T::table_name(), crate::params::query(&**client, sql, params)).await?;
+    
+    Ok(T::from_rows(&rows)?)
+}
+
+/// # get_all_limited
+/// 
+/// Deadpool bağlantı havuzunu kullanarak bir sayfa kayıt alır. `LIMIT` ve `OFFSET`
+/// sorgu tipine `#[limit]` / `#[offset]` ile gömülmez, çalışma anında sorgunun sonuna eklenir.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `params`: Sorgu parametrelerini içeren veri nesnesi (SqlQuery, FromRow ve SqlParams trait'lerini uygulamalıdır)
+/// - `limit`: Döndürülecek en fazla kayıt sayısı
+/// - `offset`: Atlanacak kayıt sayısı
+/// 
+/// ## Dönüş Değeri
+/// - `Result<Vec<T>, DeadpoolError>`: Başarılı olursa sayfadaki kayıtları döndürür; başarısız olursa DeadpoolError döndürür
+/// 
+/// ## Panik
+/// `T` tipinin sorgusunda zaten `LIMIT`, `OFFSET` ya da `FETCH` varsa panik oluşur.
+/// 
+/// ## Örnek Kullanım
+/// ```rust,ignore
+/// // SELECT id, name FROM users WHERE state = $1 ORDER BY id LIMIT $2 OFFSET $3
+/// let second_page = get_all_limited(&pool, &ListUsers::new(1), 20, 20).await?;
+/// ```
+pub async fn get_all_limited<T: SqlQuery + FromRow + SqlParams>(
+    pool: &Pool,
+    params: &T,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<T>, DeadpoolError> {
+    let mut query_params = params.params();
+    let sql = with_limit_offset(T::query(), query_params.len(), Dialect::Postgres)
+        .unwrap_or_else(|e| panic!("get_all_limited: {}", e));
+
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    query_params.push(&limit);
+    query_params.push(&offset);
+    let rows = observe_async("fetch_all", T::table_name(), client.query(sql.as_str(), &query_params)).await?;
+
+    Ok(T::from_rows(&rows)?)
+}
+
+/// # get_page
+/// 
+/// `#[derive(Paginated)]` ile üretilen sayfayı getirir; sayfanın tuttuğu sorgu, limit ve
+/// offset ile [`get_all_limited`] çağırır.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `page`: `<Sorgu>Page` değeri
+/// 
+/// ## Dönüş Değeri
+/// - `Result<Vec<P::Query>, DeadpoolError>`: Başarılı olursa sayfadaki kayıtları döndürür; başarısız olursa DeadpoolError döndürür
+/// 
+/// ## Örnek Kullanım
+/// ```rust,ignore
+/// let second_page = get_page(&pool, &UsersByStatePage::nth(UsersByState::new(1), 2, 20)).await?;
+/// ```
+pub async fn get_page<P: Paginated>(pool: &Pool, page: &P) -> Result<Vec<P::Query>, DeadpoolError> {
+    get_all_limited(pool, page.query(), page.limit(), page.offset()).await
+}
+
+/// # get_first
+/// 
+/// Deadpool bağlantı havuzunu kullanarak sorgunun `column` sütununa göre ilk kaydını alır.
+/// Sorgunun sonuna çalışma anında `ORDER BY column ASC NULLS LAST LIMIT 1` eklenir; sorguda
+/// zaten bir `ORDER BY` varsa yalnızca eşitlikleri bozmak için kullanılır.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `params`: Sorgu parametrelerini içeren veri nesnesi (SqlQuery, FromRow ve SqlParams trait'lerini uygulamalıdır)
+/// - `column`: Sıralama sütunu; sorgu tipinin `#[sortable("...")]` listesinde bulunmalıdır
+/// 
+/// ## Dönüş Değeri
+/// - `Result<Option<T>, DeadpoolError>`: Başarılı olursa kaydı, sorgu hiçbir satırla eşleşmezse `None` döndürür;
+///   listede olmayan bir sütun, bağlantı alınmadan `DeadpoolError::Validation` ile reddedilir
+/// 
+/// ## Panik
+/// `T` tipinin sorgusunda zaten `LIMIT`, `OFFSET` ya da `FETCH` varsa panik oluşur.
+/// 
+/// ## Örnek Kullanım
+/// ```rust,ignore
+/// // SELECT ... WHERE customer_id = $1 ORDER BY total ASC NULLS LAST LIMIT 1
+/// let smallest = get_first(&pool, &CustomerOrders::new(7), "total").await?;
+/// ```
+pub async fn get_first<T: SqlQuery + FromRow + SqlParams>(
+    pool: &Pool,
+    params: &T,
+    column: &str,
+) -> Result<Option<T>, DeadpoolError> {
+    get_end(pool, params, column, false).await
+}
+
+/// # get_last
+/// 
+/// Sorgunun `column` sütununa göre son kaydını alır; `ORDER BY column DESC NULLS LAST` ile
+/// [`get_first`] çağrısına eşdeğerdir (örneğin bir müşterinin en son siparişi).
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `params`: Sorgu parametrelerini içeren veri nesnesi (SqlQuery, FromRow ve SqlParams trait'lerini uygulamalıdır)
+/// - `column`: Sıralama sütunu; sorgu tipinin `#[sortable("...")]` listesinde bulunmalıdır
+/// 
+/// ## Dönüş Değeri
+/// - `Result<Option<T>, DeadpoolError>`: Başarılı olursa kaydı, sorgu hiçbir satırla eşleşmezse `None` döndürür
+/// 
+/// ## Örnek Kullanım
+/// ```rust,ignore
+/// // SELECT ... WHERE customer_id = $1 ORDER BY created_at DESC NULLS LAST LIMIT 1
+/// let latest = get_last(&pool, &CustomerOrders::new(7), "created_at").await?;
+/// ```
+pub async fn get_last<T: SqlQuery + FromRow + SqlParams>(
+    pool: &Pool,
+    params: &T,
+    column: &str,
+) -> Result<Option<T>, DeadpoolError> {
+    get_end(pool, params, column, true).await
+}
+
+async fn get_end<T: SqlQuery + FromRow + SqlParams>(
+    pool: &Pool,
+    params: &T,
+    column: &str,
+    last: bool,
+) -> Result<Option<T>, DeadpoolError> {
+    check_sortable(column, T::sortable_columns())?;
+    let sql = with_first_row(T::query(), column, last)
+        .unwrap_or_else(|e| panic!("{}: {}", if last { "get_last" } else { "get_first" }, e));
+
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let query_params = params.params();
+    let row = observe_async("fetch", T::table_name(), client.query_opt(sql.as_str(), &query_params)).await?;
+    Ok(row.as_ref().map(T::from_row).transpose()?)
+}
+
+/// # get_many
+/// 
+/// Deadpool bağlantı havuzunu kullanarak bir anahtar listesinin kayıtlarını tek bir
+/// `WHERE id = ANY($1)` sorgusuyla alır; anahtarlar tek bir dizi parametresi olarak bağlanır.
+/// Kayıtlar `keys` sırasıyla döner, kaydı bulunmayan anahtarlar `missing` listesindedir.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `keys`: İstenen anahtarlar; iki kez verilen bir anahtar bir kez okunur
+/// 
+/// ## Dönüş Değeri
+/// - `Result<Lookup<T, K>, DeadpoolError>`: Başarılı olursa bulunan kayıtları ve eksik anahtarları döndürür
+/// 
+/// ## Örnek Kullanım
+/// ```rust,ignore
+/// // `UserById` tipi `#[primary_key("id")]` ile türetilmiştir
+/// // SELECT id, name, email FROM users WHERE id = ANY($1)
+/// let users = get_many::<UserById, i64>(&pool, &[3, 1, 7]).await?;
+/// ```
+pub async fn get_many<T, K>(pool: &Pool, keys: &[K]) -> Result<Lookup<T, K>, DeadpoolError>
+where
+    T: Keyed<Key = K> + SqlQuery + FromRow,
+    K: ToSql + Sync + Eq + Hash + Clone,
+{
+    let sql = lookup::keys_query(T::key_select(), T::key_column(), 1, Dialect::Postgres);
+
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let distinct = lookup::distinct(keys);
+    let rows = observe_async("fetch_many", T::table_name(), client.query(sql.as_str(), &[&distinct])).await?;
+    let records = T::from_rows(&rows)?;
+    Ok(lookup::in_key_order(keys, records, T::key))
+}
+
+/// # get_with_row
+/// 
+/// Deadpool bağlantı havuzunu kullanarak bir kaydı, eşlendiği ham `Row` ile birlikte alır.
+/// Struct'ta bulunmayan seçilmiş sütunlar (hesaplanan değerler, pencere fonksiyonları)
+/// dönen satırdan okunabilir.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `params`: Sorgu parametrelerini içeren veri nesnesi (SqlQuery, FromRow ve SqlParams trait'lerini uygulamalıdır)
+/// 
+/// ## Dönüş Değeri
+/// - `Result<(T, Row), DeadpoolError>`: Başarılı olursa, kaydı ve satırını döndürür; başarısız olursa, DeadpoolError döndürür
+pub async fn get_with_row<T: SqlQuery + FromRow + SqlParams>(
+    pool: &Pool,
+    params: &T,
+) -> Result<(T, Row), DeadpoolError> {
+    let client = acquire(pool).await?;
+    let sql = T::query_single();
+    
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+    let params = params.params();
+    let row = observe_async("fetch", T::table_name(), client.query_one(sql, &params)).await?;
+    let entity = T::from_row(&row)?;
+    Ok((entity, row))
+}
+
+/// # get_all_with_rows
+/// 
+/// Deadpool bağlantı havuzunu kullanarak birden fazla kaydı, her biri eşlendiği ham `Row` ile birlikte alır.
+/// Toplam kayıt sayısının aynı sorguda hesaplandığı sayfalama için kullanışlıdır.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `params`: Sorgu parametrelerini içeren veri nesnesi (SqlQuery, FromRow ve SqlParams trait'lerini uygulamalıdır)
+/// 
+/// ## Dönüş Değeri
+/// - `Result<Vec<(T, Row)>, DeadpoolError>`: Başarılı olursa, kayıtları ve satırlarını döndürür; başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Kullanım Örneği
+/// ```rust,no_run
+/// use deadpool_postgres::{Config, Runtime};
+/// use tokio_postgres::{types::ToSql, Error, NoTls, Row};
+/// use parsql_deadpool_postgres::get_all_with_rows;
+/// use parsql_deadpool_postgres::macros::{FromRow, Queryable, SqlParams};
+/// use parsql_deadpool_postgres::traits::{FromRow, SqlParams, SqlQuery};
+/// 
+/// #[derive(Queryable, FromRow, SqlParams)]
+/// #[table("users")]
+/// #[select("id, name, state, COUNT(*) OVER() AS total_count")]
+/// #[where_clause("state = $")]
+/// #[limit(20)]
+/// pub struct UserPage {
+///     pub id: i32,
+///     pub name: String,
+///     pub state: i16,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut cfg = Config::new();
+///     cfg.host = Some("localhost".to_string());
+///     cfg.dbname = Some("test".to_string());
+///     
+///     let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls).unwrap();
+///
+///     let query = UserPage { id: 0, name: String::new(), state: 1 };
+///     let page = get_all_with_rows(&pool, &query).await?;
+///     let total: i64 = page.first().map_or(Ok(0), |(_, row)| row.try_get("total_count"))?;
+///     println!("{} / {} kullanıcı", page.len(), total);
+///     Ok(())
+/// }
+/// ```
+pub async fn get_all_with_rows<T: SqlQuery + FromRow + SqlParams>(
+    pool: &Pool,
+    params: &T,
+) -> Result<Vec<(T, Row)>, DeadpoolError> {
+    let client = acquire(pool).await?;
+    let sql = T::query();
+    
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
 
     let params = params.params();
-    let rows = client.query(&sql, &params).await?;
+    let rows = observe_async("fetch_all", T::table_name(), client.query(sql, &params)).await?;
     
     let mut results = Vec::with_capacity(rows.len());
     for row in rows {
-        results.push(T::from_row(&row)?);
+        results.push((T::from_row(&row)?, row));
     }
     
     Ok(results)
@@ -473,7 +1079,7 @@ pub async fn get_all<T: SqlQuery + FromRow + SqlParams>(
 /// - `to_model`: Satırı modele dönüştüren fonksiyon
 /// 
 /// ## Dönüş Değeri
-/// - `Result<R, Error>`: Başarılı olursa, dönüştürülen modeli döndürür; başarısız olursa, Error döndürür
+/// - `Result<R, DeadpoolError>`: Başarılı olursa, dönüştürülen modeli döndürür; başarısız olursa, DeadpoolError döndürür
 /// 
 /// ## Kullanım Örneği
 /// ```rust,no_run
@@ -511,7 +1117,7 @@ pub async fn get_all<T: SqlQuery + FromRow + SqlParams>(
 /// }
 /// 
 /// #[tokio::main]
-/// async fn main() -> Result<(), Error> {
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let mut cfg = Config::new();
 ///     cfg.host = Some("localhost".to_string());
 ///     cfg.dbname = Some("test".to_string());
@@ -529,20 +1135,18 @@ pub async fn select<T: SqlQuery + SqlParams, R, F>(
     pool: &Pool,
     entity: T,
     to_model: F,
-) -> Result<R, Error>
+) -> Result<R, DeadpoolError>
 where
     F: Fn(&Row) -> Result<R, Error>,
 {
-    let client = pool.get().await.map_err(pool_err_to_io_err)?;
-    let sql = T::query();
+    let client = acquire(pool).await?;
+    let sql = T::query_single();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-POOL] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
 
     let params = entity.params();
-    let row = client.query_one(&sql, &params).await?;
-    to_model(&row)
+    let row = observe_async("select", T::table_name(), client.query_one(sql, &params)).await?;
+    Ok(to_model(&row)?)
 }
 
 /// # select_all
@@ -555,7 +1159,7 @@ where
 /// - `to_model`: Satırı modele dönüştüren fonksiyon
 /// 
 /// ## Dönüş Değeri
-/// - `Result<Vec<R>, Error>`: Başarılı olursa, dönüştürülen modelleri içeren bir vektör döndürür; başarısız olursa, Error döndürür
+/// - `Result<Vec<R>, DeadpoolError>`: Başarılı olursa, dönüştürülen modelleri içeren bir vektör döndürür; başarısız olursa, DeadpoolError döndürür
 /// 
 /// ## Kullanım Örneği
 /// ```rust,no_run
@@ -593,7 +1197,7 @@ where
 /// }
 /// 
 /// #[tokio::main]
-/// async fn main() -> Result<(), Error> {
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let mut cfg = Config::new();
 ///     cfg.host = Some("localhost".to_string());
 ///     cfg.dbname = Some("test".to_string());
@@ -611,19 +1215,16 @@ pub async fn select_all<T: SqlQuery + SqlParams, R, F>(
     pool: &Pool,
     entity: T,
     to_model: F,
-) -> Result<Vec<R>, Error>
+) -> Result<Vec<R>, DeadpoolError>
 where
     F: Fn(&Row) -> R,
 {
-    let client = pool.get().await.map_err(pool_err_to_io_err)?;
+    let client = acquire(pool).await?;
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-POOL] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
 
-    let params = entity.params();
-    let rows = client.query(&sql, &params).await?;
+    let rows = observe_async("select_all", T::table_name(), crate::params::query(&**client, sql, &entity)).await?;
     
     let mut results = Vec::with_capacity(rows.len());
     for row in rows {
@@ -631,4 +1232,244 @@ where
     }
     
     Ok(results)
-} 
\ No newline at end of file
+} 
+/// # insert_many
+/// 
+/// Deadpool bağlantı havuzunu kullanarak birden çok kaydı çok satırlı `INSERT` ifadeleriyle ekler.
+/// Kayıtlar, tek bir ifadenin PostgreSQL'in 65535 parametre sınırını aşmayacağı parçalara bölünür.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `entities`: Eklenecek kayıtlar (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
+/// - `options`: Parça boyutu ve tüm parçaların tek bir transaction içinde çalışıp çalışmayacağı
+/// 
+/// ## Dönüş Değeri
+/// - `Result<u64, DeadpoolError>`: Başarılı olursa, eklenen satır sayısını döndürür; başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Kullanım Örneği
+/// ```rust,no_run
+/// use deadpool_postgres::{Config, Runtime};
+/// use tokio_postgres::{NoTls, Error};
+/// use parsql::deadpool_postgres::{insert_many, BatchOptions};
+/// 
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut cfg = Config::new();
+///     cfg.host = Some("localhost".to_string());
+///     cfg.dbname = Some("test".to_string());
+///     
+///     let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls).unwrap();
+///
+///     let users: Vec<InsertUser> = (0..100_000)
+///         .map(|i| InsertUser { name: format!("user{}", i), email: format!("user{}@example.com", i) })
+///         .collect();
+///
+///     let options = BatchOptions { transaction: true, ..Default::default() };
+///     let inserted = insert_many(&pool, &users, options).await?;
+///     println!("Eklenen satır sayısı: {}", inserted);
+///     Ok(())
+/// }
+/// ```
+pub async fn insert_many<T>(
+    pool: &Pool,
+    entities: &[T],
+    options: BatchOptions,
+) -> Result<u64, DeadpoolError>
+where
+    T: SqlQuery + SqlParams + Send + Sync,
+{
+    let mut client = acquire(pool).await?;
+
+    if options.transaction {
+        let tx = client.transaction().await?;
+        let inserted = insert_chunks(&*tx, entities, None, options).await?;
+        tx.commit().await?;
+        Ok(inserted)
+    } else {
+        Ok(insert_chunks(&**client, entities, None, options).await?)
+    }
+}
+
+/// # upsert_many
+/// 
+/// Kayıtları [`insert_many`] gibi parçalar halinde ekler; her parça tek bir
+/// `INSERT ... ON CONFLICT (conflict) DO UPDATE` ifadesidir. `conflict` sütunları mevcut
+/// bir satırla çakışan kayıt, diğer sütunlarını `EXCLUDED` değerleriyle günceller.
+/// 
+/// Aynı çakışma anahtarı bir parçada yalnızca bir kez bulunabilir; PostgreSQL aynı satırı
+/// iki kez güncelleyen ifadeyi reddeder.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `entities`: Eklenecek veya güncellenecek kayıtlar (SqlQuery ve SqlParams trait'lerini uygulamalıdır)
+/// - `conflict`: Çakışmanın kontrol edildiği benzersiz kısıt veya indeksin sütunları
+/// - `options`: Parça boyutu ve tüm parçaların tek transaction içinde çalışıp çalışmayacağı
+/// 
+/// ## Dönüş Değeri
+/// - `Result<u64, DeadpoolError>`: Başarılı olursa, eklenen veya güncellenen satır sayısını döndürür; başarısız olursa, DeadpoolError döndürür
+/// 
+/// ## Panik
+/// `conflict` içinde düz sütun adı olmayan bir değer varsa veya INSERT'in `VALUES` listesi yoksa.
+/// 
+/// ## Kullanım Örneği
+/// ```rust,no_run
+/// use deadpool_postgres::{Config, Runtime};
+/// use tokio_postgres::NoTls;
+/// use parsql::deadpool_postgres::{upsert_many, BatchOptions};
+/// 
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut cfg = Config::new();
+///     cfg.host = Some("localhost".to_string());
+///     cfg.dbname = Some("test".to_string());
+///     
+///     let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls).unwrap();
+///
+///     let users = vec![
+///         InsertUser { name: "Ali".to_string(), email: "ali@example.com".to_string() },
+///         InsertUser { name: "Ayşe".to_string(), email: "ayse@example.com".to_string() },
+///     ];
+///
+///     let upserted = upsert_many(&pool, &users, &["email"], BatchOptions::default()).await?;
+///     println!("Eklenen veya güncellenen satır sayısı: {}", upserted);
+///     Ok(())
+/// }
+/// ```
+pub async fn upsert_many<T>(
+    pool: &Pool,
+    entities: &[T],
+    conflict: &[&str],
+    options: BatchOptions,
+) -> Result<u64, DeadpoolError>
+where
+    T: SqlQuery + SqlParams + Send + Sync,
+{
+    if with_upsert(T::query(), conflict).is_none() {
+        panic!("upsert_many: can't add ON CONFLICT ({}) to `{}`", conflict.join(", "), T::query());
+    }
+
+    let mut client = acquire(pool).await?;
+
+    if options.transaction {
+        let tx = client.transaction().await?;
+        let upserted = insert_chunks(&*tx, entities, Some(conflict), options).await?;
+        tx.commit().await?;
+        Ok(upserted)
+    } else {
+        Ok(insert_chunks(&**client, entities, Some(conflict), options).await?)
+    }
+}
+
+pub(crate) async fn insert_chunks<C, T>(
+    client: &C,
+    entities: &[T],
+    conflict: Option<&[&str]>,
+    options: BatchOptions,
+) -> Result<u64, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + SqlParams + Send + Sync,
+{
+    let Some(first) = entities.first() else {
+        return Ok(0);
+    };
+
+    let sql = T::query();
+    let params_per_row = first.params().len();
+    let rows_per_chunk = options.rows_per_chunk(Dialect::Postgres, params_per_row);
+
+    let mut inserted = 0;
+    for chunk in entities.chunks(rows_per_chunk) {
+        // upsert_many ON CONFLICT eklenebildiğini baştan doğruladı
+        let chunk_sql = multi_row_insert(sql, params_per_row, chunk.len(), Dialect::Postgres)
+            .map(|chunk_sql| match conflict {
+                Some(conflict) => with_upsert(&chunk_sql, conflict).expect("checked by upsert_many"),
+                None => chunk_sql,
+            });
+        match chunk_sql {
+            Some(chunk_sql) => {
+                trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &chunk_sql);
+
+                let params: Vec<&(dyn ToSql + Sync)> =
+                    chunk.iter().flat_map(|entity| entity.params()).collect();
+                inserted += client.execute(chunk_sql.as_str(), &params).await?;
+            }
+            // VALUES listesi olmayan sorgular (INSERT ... SELECT) tek tek çalıştırılır
+            None => {
+                for entity in chunk {
+                    inserted += client.execute(sql, &entity.params()).await?;
+                }
+            }
+        }
+    }
+
+    Ok(inserted)
+}
+
+/// # update_many
+/// 
+/// Deadpool bağlantı havuzunu kullanarak birden çok kaydı tek bir hazırlanmış ifadeyle günceller.
+/// 
+/// ## Parametreler
+/// - `pool`: Deadpool bağlantı havuzu
+/// - `entities`: Güncellenecek kayıtlar (SqlQuery ve UpdateParams trait'lerini uygulamalıdır)
+/// - `options`: Tüm güncellemelerin tek bir transaction içinde çalışıp çalışmayacağı
+///   (`chunk_size` kullanılmaz, her kayıt ayrı bir ifadedir)
+/// 
+/// ## Dönüş Değeri
+/// - `Result<u64, DeadpoolError>`: Başarılı olursa, güncellenen toplam satır sayısını döndürür; başarısız olursa, DeadpoolError döndürür
+pub async fn update_many<T>(
+    pool: &Pool,
+    entities: &[T],
+    options: BatchOptions,
+) -> Result<u64, DeadpoolError>
+where
+    T: SqlQuery + UpdateParams + Send + Sync,
+{
+    let mut client = acquire(pool).await?;
+
+    if options.transaction {
+        let tx = client.transaction().await?;
+        let updated = update_each(&*tx, entities).await?;
+        tx.commit().await?;
+        Ok(updated)
+    } else {
+        Ok(update_each(&**client, entities).await?)
+    }
+}
+
+async fn update_each<C, T>(client: &C, entities: &[T]) -> Result<u64, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + UpdateParams + Send + Sync,
+{
+    if entities.is_empty() {
+        return Ok(0);
+    }
+
+    let sql = T::query();
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+
+    let stmt = client.prepare(sql).await?;
+    let mut updated = 0;
+    for entity in entities {
+        updated += client.execute(&stmt, &entity.params()).await?;
+    }
+
+    Ok(updated)
+}
+
@@ -0,0 +1,109 @@
+//! Snapshot-consistent paging through a held cursor.
+//!
+//! A cursor `WITH HOLD` fixes the result of a `Queryable`'s query when it is declared,
+//! so the pages read from it skip or repeat no rows however the table changes. It
+//! lives in one database session, so a [`Cursor`] keeps the pooled connection that
+//! declared it until it is closed:
+//!
+//! ```rust,no_run
+//! use parsql_deadpool_postgres::cursor::Cursor;
+//! use parsql_deadpool_postgres::traits::{FromRow, SqlParams, SqlQuery};
+//! use parsql_deadpool_postgres::{DeadpoolError, Pool};
+//!
+//! async fn export<T: SqlQuery + SqlParams + FromRow>(pool: &Pool, query: &T) -> Result<usize, DeadpoolError> {
+//!     let mut cursor = Cursor::open(pool, query).await?;
+//!     let mut exported = 0;
+//!     loop {
+//!         let page: Vec<T> = cursor.fetch_next(500).await?;
+//!         exported += page.len();
+//!         if cursor.is_exhausted() {
+//!             return Ok(exported);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! An endpoint serving one page per request keeps its open cursors by
+//! [`token`](Cursor::token), which can be handed to the client. Close a cursor the
+//! client abandons: a dropped `Cursor` returns its connection to the pool with the
+//! cursor still open, until the pool's recycling (`RecyclingMethod::Clean`) or the end
+//! of the session discards it.
+
+pub use parsql_core::cursor::{InvalidPageToken, PageToken};
+
+use deadpool_postgres::{Object, Pool};
+use parsql_core::{cursor, metrics::observe_async, trace};
+
+use crate::acquire::acquire;
+use crate::traits::{FromRow, SqlParams, SqlQuery};
+use crate::DeadpoolError;
+
+/// An open cursor and the connection it lives on.
+pub struct Cursor {
+    client: Object,
+    token: PageToken,
+    exhausted: bool,
+}
+
+impl Cursor {
+    /// Takes a connection from `pool` and declares a held cursor for the query of
+    /// `params` on it.
+    pub async fn open<T: SqlQuery + SqlParams>(pool: &Pool, params: &T) -> Result<Self, DeadpoolError> {
+        let client = acquire(pool).await?;
+        let token = PageToken::generate();
+        let sql = cursor::declare_statement(&token, T::query());
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+        let params = params.params();
+        observe_async("open_cursor", T::table_name(), client.execute(sql.as_str(), &params)).await?;
+        Ok(Cursor { client, token, exhausted: false })
+    }
+
+    /// Name of the cursor.
+    pub fn token(&self) -> &PageToken {
+        &self.token
+    }
+
+    /// Whether every row has been read; the cursor is closed then.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Reads up to `count` rows after the ones already read.
+    ///
+    /// A short page closes the cursor; later calls return no rows.
+    pub async fn fetch_next<T: FromRow>(&mut self, count: u32) -> Result<Vec<T>, DeadpoolError> {
+        if self.exhausted {
+            return Ok(Vec::new());
+        }
+        let sql = cursor::fetch_statement(&self.token, count);
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+        let rows = observe_async("fetch_next", "unknown", self.client.query(sql.as_str(), &[])).await?;
+        if rows.len() < usize::try_from(count).unwrap_or(usize::MAX) {
+            self.exhausted = true;
+            self.client.batch_execute(&cursor::close_statement(&self.token)).await?;
+        }
+        Ok(T::from_rows(&rows)?)
+    }
+
+    /// Closes the cursor before its rows are all read and returns the connection to
+    /// the pool.
+    pub async fn close(self) -> Result<(), DeadpoolError> {
+        if !self.exhausted {
+            let sql = cursor::close_statement(&self.token);
+            trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+            self.client.batch_execute(&sql).await?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cursor")
+            .field("token", &self.token)
+            .field("exhausted", &self.exhausted)
+            .finish()
+    }
+}
@@ -0,0 +1,100 @@
+//! Rows of ad-hoc queries, without a result struct.
+//!
+//! [`query_dynamic`] runs any SQL with its parameters and returns every row as a map of
+//! column name to [`Value`], which is enough for REPL-like tools and debugging
+//! endpoints:
+//!
+//! ```rust,no_run
+//! use deadpool_postgres::{Config, Runtime};
+//! use parsql_deadpool_postgres::{query_dynamic, Value};
+//! use tokio_postgres::NoTls;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut config = Config::new();
+//!     config.host = Some("localhost".to_string());
+//!     config.user = Some("postgres".to_string());
+//!     let pool = config.create_pool(Some(Runtime::Tokio1), NoTls)?;
+//!     let rows = query_dynamic(&pool, "SELECT id, name, created_at FROM users WHERE state = $1", &[&1i16]).await?;
+//!     for row in &rows {
+//!         // created_at is not decoded by the driver and arrives as text
+//!         println!("{} {} {}", row["id"], row["name"], row["created_at"]);
+//!     }
+//!     assert!(rows.iter().all(|row| matches!(row["id"], Value::Int(_))));
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error as StdError;
+
+use parsql_core::{metrics::observe_async, trace, value::{self, Value}};
+use tokio_postgres::types::{FromSql, Kind, ToSql, Type};
+use deadpool_postgres::Pool;
+use tokio_postgres::{Error, Row};
+
+use crate::acquire::acquire;
+use crate::DeadpoolError;
+
+/// A column read as a [`Value`].
+struct Dynamic(Value);
+
+impl<'a> FromSql<'a> for Dynamic {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn StdError + Sync + Send>> {
+        // Enum değerleri etiketleriyle gelir
+        if matches!(ty.kind(), Kind::Enum(_)) {
+            return Ok(Dynamic(Value::Text(std::str::from_utf8(raw)?.to_string())));
+        }
+        Ok(Dynamic(value::from_postgres(ty.oid(), raw)?))
+    }
+
+    fn from_sql_null(_: &Type) -> Result<Self, Box<dyn StdError + Sync + Send>> {
+        Ok(Dynamic(Value::Null))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        decoded(ty)
+    }
+}
+
+fn decoded(ty: &Type) -> bool {
+    value::decodes_postgres(ty.oid()) || matches!(ty.kind(), Kind::Enum(_))
+}
+
+fn to_map(row: &Row) -> Result<HashMap<String, Value>, Error> {
+    row.columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| Ok((column.name().to_string(), row.try_get::<_, Dynamic>(i)?.0)))
+        .collect()
+}
+
+/// Runs `sql` and returns every row as a map of column name to value.
+///
+/// The statement is prepared first to learn its column types. When a column has a type
+/// [`Value`] doesn't cover, the query runs wrapped in a SELECT that casts it to text, so
+/// its result columns must have distinct names. Statements without result columns
+/// return no rows.
+pub async fn query_dynamic(
+    pool: &Pool,
+    sql: &str,
+    params: &[&(dyn ToSql + Sync)],
+) -> Result<Vec<HashMap<String, Value>>, DeadpoolError> {
+    let client = acquire(pool).await?;
+    let statement = client.prepare(sql).await?;
+    let columns = statement
+        .columns()
+        .iter()
+        .map(|column| (column.name(), decoded(column.type_())))
+        .collect::<Vec<_>>();
+
+    let rows = if columns.iter().all(|(_, decoded)| *decoded) {
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", sql);
+        observe_async("query_dynamic", "unknown", client.query(&statement, params)).await?
+    } else {
+        let sql = value::with_text_columns(sql, &columns);
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+        observe_async("query_dynamic", "unknown", client.query(sql.as_str(), params)).await?
+    };
+    Ok(rows.iter().map(to_map).collect::<Result<_, _>>()?)
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use deadpool_postgres::PoolError;
+use parsql_core::{criteria::CombineError, validation::ValidationError};
+
+/// Havuz üzerinden yapılan işlemlerin hata tipi.
+///
+/// Havuzdan bağlantı alınamadığında (havuz tükendi, zaman aşımı, bağlantı kurulamadı)
+/// `Pool`, sorgu veritabanında başarısız olduğunda `Postgres` döner. Böylece çağıran taraf
+/// geçici havuz sorunlarını SQL hatalarından ayırt edebilir. `#[validate]` denetimlerinden
+/// geçemeyen varlıklar, bağlantı alınmadan `Validation` ile reddedilir; filtrelerle
+/// daraltılamayan sorgular da aynı şekilde `Combine` ile reddedilir. `cancel::Cancellation`
+/// ile sınırlanan işlemler süre dolduğunda `Timeout`, iptal edildiğinde `Cancelled` döner:
+///
+/// ```rust,no_run
+/// use parsql::deadpool_postgres::{DeadpoolError, PoolError};
+///
+/// fn is_retryable(error: &DeadpoolError) -> bool {
+///     matches!(error, DeadpoolError::Pool(PoolError::Timeout(_)))
+/// }
+/// ```
+#[derive(Debug)]
+pub enum DeadpoolError {
+    /// Havuzdan bağlantı alınamadı
+    Pool(PoolError),
+    /// Sorgu veritabanında başarısız oldu
+    Postgres(tokio_postgres::Error),
+    /// Varlık doğrulamadan geçemedi; veritabanına bir şey yazılmadı
+    Validation(ValidationError),
+    /// `get_all_combined` sorguyu filtrelerle daraltamadı; veritabanına bir şey gönderilmedi
+    Combine(CombineError),
+    /// İşlem `Cancellation` ile verilen süre içinde tamamlanmadı
+    Timeout,
+    /// İşlem `Cancellation` ile verilen token üzerinden iptal edildi
+    Cancelled,
+}
+
+impl DeadpoolError {
+    /// Hata bir veritabanı hatasıysa onu döndürür.
+    pub fn as_postgres(&self) -> Option<&tokio_postgres::Error> {
+        match self {
+            DeadpoolError::Postgres(e) => Some(e),
+            DeadpoolError::Pool(_)
+            | DeadpoolError::Validation(_)
+            | DeadpoolError::Combine(_)
+            | DeadpoolError::Timeout
+            | DeadpoolError::Cancelled => None,
+        }
+    }
+}
+
+impl fmt::Display for DeadpoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeadpoolError::Pool(e) => write!(f, "connection pool error: {}", e),
+            DeadpoolError::Postgres(e) => write!(f, "{}", e),
+            DeadpoolError::Validation(e) => write!(f, "{}", e),
+            DeadpoolError::Combine(e) => write!(f, "get_all_combined: {}", e),
+            DeadpoolError::Timeout => write!(f, "operation timed out"),
+            DeadpoolError::Cancelled => write!(f, "operation cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for DeadpoolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DeadpoolError::Pool(e) => Some(e),
+            DeadpoolError::Postgres(e) => Some(e),
+            DeadpoolError::Validation(e) => Some(e),
+            DeadpoolError::Combine(e) => Some(e),
+            DeadpoolError::Timeout | DeadpoolError::Cancelled => None,
+        }
+    }
+}
+
+impl From<PoolError> for DeadpoolError {
+    fn from(e: PoolError) -> Self {
+        DeadpoolError::Pool(e)
+    }
+}
+
+impl From<tokio_postgres::Error> for DeadpoolError {
+    fn from(e: tokio_postgres::Error) -> Self {
+        DeadpoolError::Postgres(e)
+    }
+}
+
+impl From<ValidationError> for DeadpoolError {
+    fn from(e: ValidationError) -> Self {
+        DeadpoolError::Validation(e)
+    }
+}
+
+impl From<CombineError> for DeadpoolError {
+    fn from(e: CombineError) -> Self {
+        DeadpoolError::Combine(e)
+    }
+}
@@ -0,0 +1,57 @@
+//! Query results as JSON objects (`json` feature).
+//!
+//! [`fetch_json`] and [`fetch_all_json`] run the SELECT of a `Queryable` type on a
+//! pooled connection and return every row as a `serde_json::Value` object keyed by
+//! column name, without a `FromRow` struct. PostgreSQL builds the objects itself with
+//! `row_to_json`, so dates, numerics and nested `json_agg` columns keep their JSON form:
+//!
+//! ```rust,no_run
+//! # use parsql_deadpool_postgres::{macros::{Queryable, SqlParams}, traits::{SqlParams, SqlQuery}};
+//! # use tokio_postgres::types::ToSql;
+//! # #[derive(Queryable, SqlParams)]
+//! # #[table("users")]
+//! # #[where_clause("state = $")]
+//! # pub struct ActiveUsers { pub state: i16 }
+//! # async fn example(pool: &parsql_deadpool_postgres::Pool) -> Result<(), parsql_deadpool_postgres::DeadpoolError> {
+//! use parsql_deadpool_postgres::fetch_all_json;
+//!
+//! let rows = fetch_all_json(pool, &ActiveUsers { state: 1 }).await?;
+//! // [{"id": 1, "name": "ali", "email": "ali@example.com"}, ...]
+//! # Ok(())
+//! # }
+//! ```
+
+use deadpool_postgres::Pool;
+use parsql_core::{json::row_to_json, metrics::observe_async, trace};
+use serde_json::Value;
+
+use crate::acquire::acquire;
+use crate::traits::{SqlParams, SqlQuery};
+use crate::DeadpoolError;
+
+/// Retrieves the row selected by `params` as a JSON object.
+///
+/// Like `get`, the query runs with `LIMIT 2` unless it sets a limit itself, and a
+/// query without rows, or with more than one, is an error.
+pub async fn fetch_json<T: SqlQuery + SqlParams>(pool: &Pool, params: &T) -> Result<Value, DeadpoolError> {
+    let sql = row_to_json(T::query_single());
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let query_params = params.params();
+    let row = observe_async("fetch", T::table_name(), client.query_one(sql.as_str(), &query_params)).await?;
+    Ok(row.try_get(0)?)
+}
+
+/// Retrieves every row selected by `params` as JSON objects, in query order.
+pub async fn fetch_all_json<T: SqlQuery + SqlParams>(pool: &Pool, params: &T) -> Result<Vec<Value>, DeadpoolError> {
+    let sql = row_to_json(T::query());
+    let client = acquire(pool).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let query_params = params.params();
+    let rows = observe_async("fetch_all", T::table_name(), client.query(sql.as_str(), &query_params)).await?;
+    Ok(rows.iter().map(|row| row.try_get(0)).collect::<Result<_, _>>()?)
+}
@@ -14,24 +14,116 @@ pub use transactional_ops as transactional;
 // CRUD işlemleri için modül
 mod crud_ops;
 
+// Havuz ve veritabanı hatalarını ayıran hata tipi
+mod error;
+
+// Havuzdan bağlantı alınırken yeniden deneme
+pub mod acquire;
+
+// İşlemler için süre sınırı ve iptal
+pub mod cancel;
+
+// Etkileşimli ve arka plan işlemleri için ayrı şeritler
+pub mod priority;
+
+// Parametrelerin Vec ya da yineleyici ile bağlanması (params-iter)
+mod params;
+
+// Yazmadan önce çalışan #[validate] denetimleri
+mod validation;
+
 // Pool extension işlemleri için modül
 pub mod pool_extensions;
+pub mod client_extensions;
 pub mod transaction_extensions;
+pub mod cache;
+pub mod constraint;
+pub mod cursor;
+pub mod dynamic;
+pub mod repository;
+pub mod encryption;
+pub mod export;
+pub mod fixtures;
+pub mod integrity;
+#[cfg(feature = "postgis")]
+pub mod geo;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod locks;
+pub mod partitions;
+pub mod registry;
+pub mod schema;
+pub mod session;
+pub mod two_phase;
+pub mod views;
+
+// TLS bağlantı yardımcıları
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+pub mod tls;
 
 // CRUD işlemlerini dışa aktar
 pub use crud_ops::{
     insert,
+    insert_returning,
+    insert_returning_id,
+    insert_returning_opt,
     update,
+    update_patch,
+    update_where,
     delete,
+    delete_where,
+    call,
+    batch_get,
     get,
     get_all,
+    get_all_combined,
+    get_all_limited,
+    get_first,
+    get_last,
+    get_many,
+    get_page,
+    get_with_row,
+    get_all_with_rows,
     select,
-    select_all
+    select_all,
+    insert_many,
+    upsert_many,
+    update_many
 };
+#[allow(deprecated)]
+pub use crud_ops::update_applied;
+pub use error::DeadpoolError;
+pub use tokio_util::sync::CancellationToken;
+// Extension metotları: Pool, havuzdan alınan istemci (`deadpool_postgres::Object`) ve Transaction için
+pub use traits::{CrudOps, TransactionOps};
+pub use parsql_core::{lookup::Lookup, BatchOptions, OnError, Progress};
+pub use parsql_core::trace::set_trace;
+// `#[audited]` yazımlarının denetim tablosu
+pub use parsql_core::audit_log;
+pub use parsql_core::value::Value;
 
 // Deadpool-postgres türlerini dışa aktar
 pub use deadpool_postgres::{Pool, Client as PoolClient, PoolError, Transaction};
 
 // Public olarak Row ve Error türlerini dışa aktar
 pub use tokio_postgres::{Error, Row};
-pub use tokio_postgres::types::ToSql;
\ No newline at end of file
+pub use tokio_postgres::types::ToSql;
+
+/// serde ile (de)serileştirilen JSON / JSONB sütun sarmalayıcısı (`json` özelliği).
+/// Alt kayıtları iç içe yapılara eşlemek için `json_agg(...)` sütununun alan tipi olarak kullanılır.
+#[cfg(feature = "json")]
+pub use tokio_postgres::types::Json;
+/// `NUMERIC` sütunları için kesin ondalık tip (`decimal` özelliği); türetilen yapılarda alan tipi olarak kullanılır.
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
+pub use encryption::EncryptedColumn;
+/// `geo-types` değerlerini tutan PostGIS `geometry` / `geography` sütunu (`postgis` özelliği).
+#[cfg(feature = "postgis")]
+pub use geo::{geo_types, PostGis};
+pub use constraint::{ConstraintError, ConstraintKind, ConstraintViolation};
+pub use dynamic::query_dynamic;
+pub use export::export_csv;
+#[cfg(feature = "json")]
+pub use json::{fetch_all_json, fetch_json};
+pub use partitions::ensure_monthly_partition;
+pub use views::refresh_materialized_view;
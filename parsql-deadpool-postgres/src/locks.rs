@@ -0,0 +1,69 @@
+//! Advisory locks.
+//!
+//! [`advisory_lock`] and [`try_advisory_lock`] take a session-level PostgreSQL
+//! advisory lock on a 64-bit key, [`advisory_unlock`] releases it. Instances of an
+//! application that use the same key run the guarded code one at a time, e.g. a cron
+//! job scheduled on every instance:
+//!
+//! ```rust,no_run
+//! use parsql::deadpool_postgres::locks::{lock_key, with_advisory_lock};
+//! # async fn example(pool: &parsql::deadpool_postgres::Pool) -> Result<(), Box<dyn std::error::Error>> {
+//! let client = pool.get().await?;
+//! with_advisory_lock(&client, lock_key("nightly-report"), |client| {
+//!     client.batch_execute("REFRESH MATERIALIZED VIEW daily_sales")
+//! })
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The lock belongs to the connection, not to the pool: take it and release it on the
+//! same pooled client. A client that goes back to the pool while still holding a lock
+//! keeps it until the connection is closed, so prefer [`with_advisory_lock`], which
+//! releases the lock after the closure's future completes, also when it fails.
+
+use std::future::Future;
+
+use deadpool_postgres::Client;
+use parsql_core::{locks, trace};
+use tokio_postgres::Error;
+
+pub use parsql_core::locks::lock_key;
+
+/// Waits until the lock on `key` is free and takes it.
+pub async fn advisory_lock(client: &Client, key: i64) -> Result<(), Error> {
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", locks::LOCK);
+    client.execute(locks::LOCK, &[&key]).await?;
+    Ok(())
+}
+
+/// Takes the lock on `key` if no other session holds it; returns whether it was taken.
+pub async fn try_advisory_lock(client: &Client, key: i64) -> Result<bool, Error> {
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", locks::TRY_LOCK);
+    client.query_one(locks::TRY_LOCK, &[&key]).await?.try_get(0)
+}
+
+/// Releases the lock on `key`; returns `false` when this session did not hold it.
+pub async fn advisory_unlock(client: &Client, key: i64) -> Result<bool, Error> {
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", locks::UNLOCK);
+    client.query_one(locks::UNLOCK, &[&key]).await?.try_get(0)
+}
+
+/// Runs `f` while holding the lock on `key`, waiting for it first.
+///
+/// The lock is released when the future returned by `f` completes, whether it
+/// succeeded or not. An error of `f` takes precedence over an error of the unlock.
+pub async fn with_advisory_lock<'c, T, E, F, Fut>(client: &'c Client, key: i64, f: F) -> Result<T, E>
+where
+    F: FnOnce(&'c Client) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: From<Error>,
+{
+    advisory_lock(client, key).await?;
+    let result = f(client).await;
+    let unlocked = advisory_unlock(client, key).await;
+
+    let value = result?;
+    unlocked?;
+    Ok(value)
+}
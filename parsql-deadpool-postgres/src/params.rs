@@ -0,0 +1,51 @@
+//! Parameter binding of the CRUD functions.
+//!
+//! With the `params-iter` feature the parameters go from `SqlParams::params_iter`
+//! straight to `execute_raw` / `query_raw`. The derive backs that iterator with a
+//! fixed-size array, so a call allocates no parameter `Vec`.
+
+#[cfg(feature = "params-iter")]
+use futures_util::TryStreamExt;
+use tokio_postgres::{Error, GenericClient, Row};
+
+use crate::traits::SqlParams;
+
+/// Runs `sql` with the parameters of `entity`, returning the number of affected rows.
+#[cfg(feature = "params-iter")]
+pub(crate) async fn execute<C, T>(client: &C, sql: &str, entity: &T) -> Result<u64, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlParams,
+{
+    client.execute_raw(sql, entity.params_iter()).await
+}
+
+/// Runs `sql` with the parameters of `entity`, returning the number of affected rows.
+#[cfg(not(feature = "params-iter"))]
+pub(crate) async fn execute<C, T>(client: &C, sql: &str, entity: &T) -> Result<u64, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlParams,
+{
+    client.execute(sql, &entity.params()).await
+}
+
+/// The rows `sql` returns for the parameters of `entity`.
+#[cfg(feature = "params-iter")]
+pub(crate) async fn query<C, T>(client: &C, sql: &str, entity: &T) -> Result<Vec<Row>, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlParams,
+{
+    client.query_raw(sql, entity.params_iter()).await?.try_collect().await
+}
+
+/// The rows `sql` returns for the parameters of `entity`.
+#[cfg(not(feature = "params-iter"))]
+pub(crate) async fn query<C, T>(client: &C, sql: &str, entity: &T) -> Result<Vec<Row>, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlParams,
+{
+    client.query(sql, &entity.params()).await
+}
@@ -0,0 +1,51 @@
+//! Monthly partitions of range-partitioned tables.
+//!
+//! PostgreSQL rejects a row for which no partition exists, so time-series tables need
+//! their partitions created ahead of the data. A model annotated with
+//! `#[partitioned(by = "range(created_at)")]` implements [`Partitioned`], and
+//! [`ensure_monthly_partition`] creates the partition of a month unless it exists:
+//!
+//! ```rust,no_run
+//! use std::time::SystemTime;
+//!
+//! use parsql_deadpool_postgres::macros::Insertable;
+//! use parsql_deadpool_postgres::partitions::{ensure_monthly_partition, Month};
+//! use parsql_deadpool_postgres::traits::{Partitioned, SqlQuery};
+//! use parsql_deadpool_postgres::{DeadpoolError, Pool};
+//!
+//! #[derive(Insertable)]
+//! #[table("events")]
+//! #[partitioned(by = "range(created_at)")]
+//! pub struct InsertEvent {
+//!     pub kind: String,
+//!     pub created_at: SystemTime,
+//! }
+//!
+//! async fn prepare_partitions(pool: &Pool) -> Result<(), DeadpoolError> {
+//!     let month = Month::containing(SystemTime::now());
+//!     ensure_monthly_partition::<InsertEvent>(pool, month).await?;
+//!     ensure_monthly_partition::<InsertEvent>(pool, month.next()).await
+//! }
+//! ```
+
+pub use parsql_core::partition::Month;
+
+use deadpool_postgres::Pool;
+use parsql_core::{partition, trace};
+
+use crate::acquire::acquire;
+use crate::traits::Partitioned;
+use crate::DeadpoolError;
+
+/// Creates the partition of `T`'s table holding `month` on a connection taken from
+/// `pool`, unless a table of that name (like `events_2024_03`) exists.
+///
+/// A partition of another name already covering part of the month makes PostgreSQL
+/// reject the statement.
+pub async fn ensure_monthly_partition<T: Partitioned>(pool: &Pool, month: Month) -> Result<(), DeadpoolError> {
+    let sql = partition::monthly_partition_statement(T::partitioned_table(), month);
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let client = acquire(pool).await?;
+    Ok(client.batch_execute(&sql).await?)
+}
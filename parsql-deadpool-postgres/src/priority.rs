@@ -0,0 +1,139 @@
+//! Separate lanes for latency-sensitive and heavy work on one pool.
+//!
+//! A few report queries (`get_all` over large tables, exports) can hold every pooled
+//! connection for seconds, and the short CRUD calls of request handlers queue up behind
+//! them. A [`PriorityExecutor`] caps how many operations of the background lane hold a
+//! connection at once, so the rest of the pool stays free for the interactive lane:
+//!
+//! ```rust,no_run
+//! use parsql_deadpool_postgres::priority::PriorityExecutor;
+//! use parsql_deadpool_postgres::traits::{FromRow, SqlParams, SqlQuery};
+//! use parsql_deadpool_postgres::{get, get_all, DeadpoolError, Pool};
+//!
+//! /// Shared by every request handler, e.g. in the application state.
+//! struct App {
+//!     pool: Pool,
+//!     // Reports use at most 4 connections of the pool
+//!     reports: PriorityExecutor,
+//! }
+//!
+//! impl App {
+//!     fn new(pool: Pool) -> Self {
+//!         Self { reports: PriorityExecutor::new(pool.clone(), 4), pool }
+//!     }
+//!
+//!     async fn serve<U, R>(&self, user: &U, report: &R) -> Result<(U, Vec<R>), DeadpoolError>
+//!     where
+//!         U: SqlQuery + FromRow + SqlParams,
+//!         R: SqlQuery + FromRow + SqlParams,
+//!     {
+//!         let rows = self.reports.background(get_all(&self.pool, report)).await?;
+//!         // Interactive work goes straight to the pool
+//!         let user = get(&self.pool, user).await?;
+//!         Ok((user, rows))
+//!     }
+//! }
+//! ```
+//!
+//! The limit only holds across operations run through the same executor (or its
+//! clones), so build it once next to the pool rather than per call.
+//!
+//! Operations are futures that take their connection when first polled, like every
+//! CRUD function of this crate, so a background operation waiting for its turn holds
+//! no connection. Keep the background limit below the pool's `max_size`; interactive
+//! operations can always use the remaining connections.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use deadpool_postgres::Pool;
+use parsql_core::trace;
+use tokio::sync::Semaphore;
+
+use crate::DeadpoolError;
+
+/// The lane an operation runs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    /// Runs at once, competing only for pool connections; the same as awaiting the
+    /// operation directly
+    Interactive,
+    /// Waits until fewer than the background limit of operations are running
+    Background,
+}
+
+/// A pool whose background operations are limited to a number of connections.
+///
+/// Clones share the pool and the background limit.
+#[derive(Clone)]
+pub struct PriorityExecutor {
+    pool: Pool,
+    background: Arc<Semaphore>,
+    background_limit: usize,
+}
+
+impl PriorityExecutor {
+    /// Runs at most `background_limit` background operations at once on `pool`.
+    pub fn new(pool: Pool, background_limit: usize) -> Self {
+        let max_size = pool.status().max_size;
+        if background_limit >= max_size {
+            trace::log(
+                "PARSQL-TOKIO-POSTGRES-POOL",
+                format_args!("background limit {} leaves no connection of {} to interactive work", background_limit, max_size),
+            );
+        }
+        Self {
+            pool,
+            background: Arc::new(Semaphore::new(background_limit)),
+            background_limit,
+        }
+    }
+
+    /// Maximum number of background operations running at once.
+    pub fn background_limit(&self) -> usize {
+        self.background_limit
+    }
+
+    /// Number of background operations running now.
+    pub fn background_running(&self) -> usize {
+        self.background_limit - self.background.available_permits()
+    }
+
+    /// Runs a heavy `operation`, e.g. `get_all(&pool, &report)`, once fewer than the background limit of background
+    /// operations are running.
+    pub async fn background<F, T>(&self, operation: F) -> Result<T, DeadpoolError>
+    where
+        F: Future<Output = Result<T, DeadpoolError>>,
+    {
+        if self.background.available_permits() == 0 {
+            trace::log(
+                "PARSQL-TOKIO-POSTGRES-POOL",
+                format_args!("background lane is full ({} running), waiting", self.background_limit),
+            );
+        }
+        // Semafor hiç kapatılmadığından acquire hata döndürmez
+        let _permit = self.background.acquire().await.map_err(|_| DeadpoolError::Cancelled)?;
+        operation.await
+    }
+
+    /// Runs `operation` in `lane`, for callers that pick the lane at runtime.
+    pub async fn run<F, T>(&self, lane: Lane, operation: F) -> Result<T, DeadpoolError>
+    where
+        F: Future<Output = Result<T, DeadpoolError>>,
+    {
+        match lane {
+            Lane::Interactive => operation.await,
+            Lane::Background => self.background(operation).await,
+        }
+    }
+}
+
+impl std::fmt::Debug for PriorityExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PriorityExecutor")
+            .field("pool", &self.pool.status())
+            .field("background_limit", &self.background_limit)
+            .field("background_running", &self.background_running())
+            .finish()
+    }
+}
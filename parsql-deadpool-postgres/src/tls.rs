@@ -0,0 +1,143 @@
+//! TLS connection helpers.
+//!
+//! Deadpool havuzunu TLS bağlantıları ile oluşturmak için yardımcılar.
+//! Etkin TLS arka ucu (`native-tls` veya `rustls` özelliği) kullanılır.
+//!
+//! ```rust,no_run
+//! use deadpool_postgres::{Config, Runtime};
+//! use parsql_deadpool_postgres::tls::{create_pool_tls, TlsOptions};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut cfg = Config::new();
+//!     cfg.host = Some("db.example.com".to_string());
+//!     cfg.user = Some("postgres".to_string());
+//!     cfg.dbname = Some("test".to_string());
+//!
+//!     let pool = create_pool_tls(&cfg, Some(Runtime::Tokio1), &TlsOptions::default())?;
+//!     let client = pool.get().await?;
+//!     client.execute("SELECT 1", &[]).await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::fmt;
+
+use deadpool_postgres::{Config, CreatePoolError, Pool, Runtime};
+
+pub use parsql_core::tls::{ConnectorError, TlsOptions};
+#[cfg(feature = "native-tls")]
+pub use parsql_core::tls::native_tls_connector;
+#[cfg(feature = "rustls")]
+pub use parsql_core::tls::rustls_connector;
+
+/// TLS havuzu oluşturulurken oluşabilecek hatalar.
+#[derive(Debug)]
+pub enum TlsError {
+    /// TLS bağlayıcısı oluşturulamadı (geçersiz sertifika, desteklenmeyen seçenek, ...)
+    Connector(ConnectorError),
+    /// Havuz oluşturulamadı
+    Pool(CreatePoolError),
+}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsError::Connector(e) => write!(f, "TLS connector error: {}", e),
+            TlsError::Pool(e) => write!(f, "Pool creation error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TlsError::Connector(e) => Some(e),
+            TlsError::Pool(e) => Some(e),
+        }
+    }
+}
+
+impl From<ConnectorError> for TlsError {
+    fn from(e: ConnectorError) -> Self {
+        TlsError::Connector(e)
+    }
+}
+
+impl From<CreatePoolError> for TlsError {
+    fn from(e: CreatePoolError) -> Self {
+        TlsError::Pool(e)
+    }
+}
+
+/// Creates a pool whose connections use `native-tls`.
+#[cfg(feature = "native-tls")]
+pub fn create_pool_native_tls(
+    cfg: &Config,
+    runtime: Option<Runtime>,
+    options: &TlsOptions,
+) -> Result<Pool, TlsError> {
+    let connector = native_tls_connector(options)?;
+    Ok(cfg.create_pool(runtime, connector)?)
+}
+
+/// Creates a pool whose connections use `rustls`.
+#[cfg(feature = "rustls")]
+pub fn create_pool_rustls(
+    cfg: &Config,
+    runtime: Option<Runtime>,
+    options: &TlsOptions,
+) -> Result<Pool, TlsError> {
+    let connector = rustls_connector(options)?;
+    Ok(cfg.create_pool(runtime, connector)?)
+}
+
+/// Creates a pool whose connections use the enabled TLS backend.
+/// When both features are enabled `native-tls` is used.
+///
+/// # Arguments
+/// * `cfg` - Deadpool configuration
+/// * `runtime` - Runtime used for timeouts, e.g. `Some(Runtime::Tokio1)`
+/// * `options` - TLS options
+///
+/// # Returns
+/// * `Result<Pool, TlsError>` - Created pool or an error
+pub fn create_pool_tls(
+    cfg: &Config,
+    runtime: Option<Runtime>,
+    options: &TlsOptions,
+) -> Result<Pool, TlsError> {
+    #[cfg(feature = "native-tls")]
+    {
+        create_pool_native_tls(cfg, runtime, options)
+    }
+    #[cfg(not(feature = "native-tls"))]
+    {
+        create_pool_rustls(cfg, runtime, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_pool_with_tls_connector() {
+        let mut cfg = Config::new();
+        cfg.host = Some("localhost".to_string());
+        cfg.user = Some("postgres".to_string());
+        let error = create_pool_tls(&cfg, None, &TlsOptions::default()).err().unwrap();
+        assert!(matches!(error, TlsError::Pool(_)));
+
+        // Havuz bağlantıları ilk `get` çağrısında açar
+        cfg.dbname = Some("postgres".to_string());
+        assert!(create_pool_tls(&cfg, None, &TlsOptions::default()).is_ok());
+
+        let options = TlsOptions {
+            root_cert_pem: Some(b"not a certificate".to_vec()),
+            ..Default::default()
+        };
+        let error = create_pool_tls(&cfg, None, &options).err().unwrap();
+        assert!(matches!(error, TlsError::Connector(_)));
+    }
+}
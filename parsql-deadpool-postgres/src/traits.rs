@@ -1,21 +1,44 @@
 use postgres::types::FromSql;
 use tokio_postgres::{Error, Row};
-use tokio_postgres::types::ToSql;
-use std::fmt::Debug;
+use tokio_postgres::types::{to_sql_checked, IsNull, ToSql, Type};
+use bytes::BytesMut;
+use std::error::Error as StdError;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
 use async_trait::async_trait;
+use std::sync::Arc;
 
-/// Trait for generating SQL queries.
-/// This trait is implemented by the derive macro `Queryable`, `Insertable`, `Updateable`, and `Deletable`.
-pub trait SqlQuery {
-    /// Returns the SQL query string.
-    fn query() -> String;
-}
+use crate::DeadpoolError;
+
+pub use parsql_core::query::SqlQuery;
+pub use parsql_core::validation::{Validate, ValidationError};
 
 /// Trait for providing SQL parameters.
 /// This trait is implemented by the derive macro `SqlParams`.
 pub trait SqlParams {
     /// Returns a vector of references to SQL parameters.
     fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// Returns the references of `params()` as an iterator. The derive yields them from a
+    /// fixed-size array without allocating; with the `params-iter` feature the CRUD
+    /// functions bind parameters through it.
+    fn params_iter(&self) -> impl ExactSizeIterator<Item = &(dyn ToSql + Sync)> + Send + Sync
+    where
+        Self: Sized,
+    {
+        self.params().into_iter()
+    }
+
+    /// Returns the query with the parameter values written in place of the
+    /// placeholders, e.g. `SELECT ... WHERE name = 'John' AND state = 1`.
+    ///
+    /// Intended for logging failing queries only; never execute the result.
+    fn debug_sql(&self) -> String
+    where
+        Self: SqlQuery,
+    {
+        parsql_core::debug::debug_sql(Self::query(), self.params())
+    }
 }
 
 /// Trait for providing UPDATE parameters.
@@ -23,6 +46,96 @@ pub trait SqlParams {
 pub trait UpdateParams {
     /// Returns a vector of references to SQL parameters for UPDATE operations.
     fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// Returns the query with the UPDATE parameter values written in place of the
+    /// placeholders, e.g. `UPDATE users SET name = 'John' WHERE id = 1`.
+    ///
+    /// Intended for logging failing queries only; never execute the result.
+    fn debug_sql(&self) -> String
+    where
+        Self: SqlQuery,
+    {
+        parsql_core::debug::debug_sql(Self::query(), self.params())
+    }
+}
+
+// Forwarding impls, so operations also accept `&T` and `Arc<T>`: `client.insert(&user)`
+// leaves `user` usable afterwards without cloning it.
+impl<T: SqlParams + ?Sized> SqlParams for &T {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+impl<T: SqlParams + ?Sized> SqlParams for Arc<T> {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+impl<T: UpdateParams + ?Sized> UpdateParams for &T {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+impl<T: UpdateParams + ?Sized> UpdateParams for Arc<T> {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+/// Conversion of a field marked `#[sql_with(func)]` when it is bound.
+///
+/// The `SqlParams` and `UpdateParams` derives generate a type implementing this trait
+/// for each marked field; the function takes a reference to the field and returns a
+/// value implementing `ToSql` (e.g. a lowercased email). The generated code refers to
+/// `SqlWith`, so import it together with the other traits.
+pub trait SqlWith: Sized + 'static {
+    /// Type of the converted field
+    type Field: Sync;
+
+    /// Runs the conversion function.
+    fn convert(value: &Self::Field) -> Box<dyn ToSql + Sync>;
+
+    /// Returns the field as a parameter that is converted when the statement runs.
+    fn bind(value: &Self::Field) -> &(dyn ToSql + Sync) {
+        Converted::<Self>::from_field(value)
+    }
+}
+
+/// Wrapper laid out like the field itself, calling `C::convert` when it is bound.
+#[repr(transparent)]
+struct Converted<C: SqlWith> {
+    conversion: PhantomData<fn() -> C>,
+    field: C::Field,
+}
+
+impl<C: SqlWith> Converted<C> {
+    fn from_field(field: &C::Field) -> &Self {
+        // SAFETY: with `repr(transparent)` and a zero-sized `PhantomData`, `Converted<C>`
+        // has the layout of `C::Field`; the lifetime of the reference is kept.
+        unsafe { &*(field as *const C::Field as *const Self) }
+    }
+}
+
+impl<C: SqlWith> fmt::Debug for Converted<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        C::convert(&self.field).fmt(f)
+    }
+}
+
+impl<C: SqlWith> ToSql for Converted<C> {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn StdError + Sync + Send>> {
+        // The type of the converted value isn't known here; its own check applies
+        C::convert(&self.field).to_sql_checked(ty, out)
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
 }
 
 /// Trait for converting database rows to Rust structs.
@@ -38,6 +151,249 @@ pub trait FromRow {
     fn from_row(row: &Row) -> Result<Self, Error>
     where
         Self: Sized;
+
+    /// Resolves the column position of each field once, from the column names of a
+    /// result set.
+    ///
+    /// The `FromRow` derive returns one entry per field, `None` for a column the result
+    /// set doesn't have. The default returns an empty list.
+    fn column_positions(columns: &[&str]) -> Vec<Option<usize>> {
+        let _ = columns;
+        Vec::new()
+    }
+
+    /// Reads a row at the positions resolved by `column_positions`, falling back to the
+    /// column name where the position is unknown. The default calls `from_row`.
+    fn from_row_at(row: &Row, positions: &[Option<usize>]) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let _ = positions;
+        Self::from_row(row)
+    }
+
+    /// Converts the rows of one query. The column names are resolved once from the first
+    /// row instead of being looked up for every field of every row.
+    fn from_rows(rows: &[Row]) -> Result<Vec<Self>, Error>
+    where
+        Self: Sized,
+    {
+        let Some(first) = rows.first() else {
+            return Ok(Vec::new());
+        };
+        let columns = first.columns().iter().map(|column| column.name()).collect::<Vec<_>>();
+        let positions = Self::column_positions(&columns);
+        rows.iter().map(|row| Self::from_row_at(row, &positions)).collect()
+    }
+}
+
+/// Trait for child records inserted after their parent by `persist_graph`.
+/// This trait is implemented by the derive macro `Insertable` when `#[parent_key("...")]` is given.
+pub trait ParentKey {
+    /// Type of the key the parent insert returns with `RETURNING`.
+    type Key;
+
+    /// Writes the parent's key into the child's foreign key field.
+    fn set_parent_key(&mut self, key: Self::Key);
+}
+
+/// Trait for materialized views refreshed with `refresh_materialized_view`.
+/// This trait is implemented by the derive macro `Queryable` when `#[materialized_view("...")]` is given.
+pub trait MaterializedView {
+    /// Name of the view, schema-qualified when needed.
+    fn view_name() -> &'static str;
+}
+
+/// Trait for range-partitioned tables whose partitions `ensure_monthly_partition` creates.
+/// This trait is implemented by the derive macros `Insertable` and `Queryable` when `#[partitioned(by = "range(...)")]` is given.
+pub trait Partitioned {
+    /// Name of the partitioned parent table, schema-qualified when needed.
+    fn partitioned_table() -> &'static str;
+
+    /// The partition key column.
+    fn partition_column() -> &'static str;
+}
+
+/// Records read in bulk by their key with `get_many`.
+/// This trait is implemented by the derive macro `Queryable` when `#[primary_key("...")]` is given.
+pub trait Keyed {
+    /// Type of the key field.
+    type Key;
+
+    /// The query's select list and tables, `SELECT ... FROM ...`, without its conditions.
+    fn key_select() -> &'static str;
+
+    /// Column compared with the requested keys.
+    fn key_column() -> &'static str;
+
+    /// The record's key.
+    fn key(&self) -> &Self::Key;
+}
+
+/// One page of a query type, read with `LIMIT` / `OFFSET` appended at runtime.
+/// This trait is implemented by the `<Query>Page` type of the derive macro `Paginated`
+/// and used by `get_page`.
+pub trait Paginated {
+    /// The paged query type, which the rows are read into.
+    type Query: SqlQuery + SqlParams + FromRow;
+
+    /// The paged query.
+    fn query(&self) -> &Self::Query;
+
+    /// Maximum number of records in the page.
+    fn limit(&self) -> i64;
+
+    /// Number of records skipped before the page.
+    fn offset(&self) -> i64;
+}
+
+/// Partial update writing only the fields that are `Some`.
+/// This trait is implemented by the `<Model>Patch` type of the derive macro `Patch`
+/// and used by `update_patch`.
+pub trait Patch {
+    /// Type of the key identifying the updated record.
+    type Key: ToSql + Sync;
+
+    /// The updated table.
+    fn table() -> &'static str;
+
+    /// Column compared with the key in the WHERE clause.
+    fn key_column() -> &'static str;
+
+    /// Columns of the fields that are `Some`, in declaration order.
+    fn columns(&self) -> Vec<&'static str>;
+
+    /// Values of the fields that are `Some`, in the order of `columns()`.
+    fn values(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// The partial UPDATE statement, or `None` when no field is set.
+    fn patch_sql(&self) -> Option<String> {
+        parsql_core::patch::update_statement(Self::table(), Self::key_column(), &self.columns(), parsql_core::Dialect::Postgres)
+    }
+}
+
+/// A filter `fetch_all_combined` AND-combines with other filters.
+/// Implemented for every `Queryable` with a single-table `#[where_clause]`, whose
+/// condition and parameters are taken from `SqlQuery::criteria` and `params()`.
+pub trait WhereFragment {
+    /// The condition, numbered from the first placeholder.
+    fn condition(&self) -> &'static str;
+
+    /// Values of the condition's placeholders, in placeholder order.
+    fn condition_params(&self) -> Vec<&(dyn ToSql + Sync)>;
+}
+
+impl<T: SqlQuery + SqlParams> WhereFragment for T {
+    fn condition(&self) -> &'static str {
+        T::criteria()
+            .unwrap_or_else(|| panic!("`{}` has no single-table WHERE clause to combine", std::any::type_name::<T>()))
+            .1
+    }
+
+    fn condition_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        let mut params = self.params();
+        params.truncate(parsql_core::criteria::where_param_count(self.condition()));
+        params
+    }
+}
+
+/// The filters passed to `fetch_all_combined` together, e.g. `(&by_state, &created_between)`.
+/// Implemented for tuples of up to six `WhereFragment`s.
+pub trait WhereFragments {
+    /// Conditions of the filters, in tuple order.
+    fn conditions(&self) -> Vec<&'static str>;
+
+    /// Parameters of the filters, in tuple order.
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+}
+
+macro_rules! where_fragments {
+    ($($filter:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($filter: WhereFragment),+> WhereFragments for ($($filter,)+) {
+            fn conditions(&self) -> Vec<&'static str> {
+                let ($($filter,)+) = self;
+                vec![$($filter.condition()),+]
+            }
+
+            fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+                let ($($filter,)+) = self;
+                let mut params = Vec::new();
+                $(params.extend($filter.condition_params());)+
+                params
+            }
+        }
+    };
+}
+
+where_fragments!(A);
+where_fragments!(A, B);
+where_fragments!(A, B, C);
+where_fragments!(A, B, C, D);
+where_fragments!(A, B, C, D, E);
+where_fragments!(A, B, C, D, E, F);
+
+/// The queries passed to `batch_get` together, e.g. `(&active_users, &recent_orders)`.
+/// Implemented for tuples of up to six references to `Queryable` structs.
+pub trait FetchBatch {
+    /// The records of each query, in tuple order, e.g. `(Vec<ActiveUser>, Vec<RecentOrder>)`.
+    type Output;
+
+    /// Statement and parameters of each query, in tuple order.
+    fn statements(&self) -> Vec<(&'static str, Vec<&(dyn ToSql + Sync)>)>;
+
+    /// Table of each query, in tuple order; the `table` label of the query metrics.
+    fn tables() -> Vec<&'static str>;
+
+    /// Reads the records of each query from its rows.
+    fn from_results(results: Vec<Vec<Row>>) -> Result<Self::Output, Error>;
+}
+
+macro_rules! fetch_batch {
+    ($($query:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<'q, $($query: SqlQuery + SqlParams + FromRow + Sync),+> FetchBatch for ($(&'q $query,)+) {
+            type Output = ($(Vec<$query>,)+);
+
+            fn statements(&self) -> Vec<(&'static str, Vec<&(dyn ToSql + Sync)>)> {
+                let ($($query,)+) = *self;
+                vec![$(($query::query(), $query.params())),+]
+            }
+
+            fn tables() -> Vec<&'static str> {
+                vec![$($query::table_name()),+]
+            }
+
+            fn from_results(results: Vec<Vec<Row>>) -> Result<Self::Output, Error> {
+                let mut results = results.into_iter();
+                Ok(($($query::from_rows(&results.next().unwrap_or_default())?,)+))
+            }
+        }
+    };
+}
+
+fetch_batch!(A);
+fetch_batch!(A, B);
+fetch_batch!(A, B, C);
+fetch_batch!(A, B, C, D);
+fetch_batch!(A, B, C, D, E);
+fetch_batch!(A, B, C, D, E, F);
+
+/// Query types behind the CRUD operations of an entity.
+/// This trait is implemented by the derive macro `Repository` and used by `repository::Repository`.
+pub trait RepositoryTypes {
+    /// Type of the key identifying a record.
+    type Key;
+    /// INSERT query run by `create`.
+    type Insert: SqlQuery + SqlParams;
+    /// SELECT query run by `find`, built from a key.
+    type Find: SqlQuery + SqlParams + FromRow + From<Self::Key>;
+    /// SELECT query run by `find_all`.
+    type FindAll: SqlQuery + SqlParams + FromRow;
+    /// UPDATE query run by `update`.
+    type Update: SqlQuery + UpdateParams;
+    /// DELETE query run by `delete`, built from a key.
+    type Delete: SqlQuery + SqlParams + From<Self::Key>;
 }
 
 /// CrudOps trait'i, Pool nesnesi için CRUD işlemlerini extension method olarak sağlar.
@@ -45,38 +401,43 @@ pub trait FromRow {
 #[async_trait]
 pub trait CrudOps {
     /// Veritabanına yeni bir kayıt ekler.
-    async fn insert<T, P:for<'a> FromSql<'a> + Send + Sync>(&self, entity: T) -> Result<P, Error>
+    async fn insert<T, P:for<'a> FromSql<'a> + Send + Sync>(&self, entity: T) -> Result<P, DeadpoolError>
     where
         T: SqlQuery + SqlParams + Send + Sync;
     
     /// Veritabanındaki mevcut bir kaydı günceller.
-    async fn update<T>(&self, entity: T) -> Result<u64, Error>
+    async fn update<T>(&self, entity: T) -> Result<u64, DeadpoolError>
     where
         T: SqlQuery + UpdateParams + Send + Sync;
     
     /// Veritabanından bir kaydı siler.
-    async fn delete<T>(&self, entity: T) -> Result<u64, Error>
+    async fn delete<T>(&self, entity: T) -> Result<u64, DeadpoolError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync;
+    
+    /// `Callable` ile tanımlanan saklı yordamı ya da fonksiyonu çalıştırır.
+    async fn call<T>(&self, entity: T) -> Result<u64, DeadpoolError>
     where
         T: SqlQuery + SqlParams + Send + Sync;
     
     /// Belirtilen kriterlere uygun tek bir kaydı getirir.
-    async fn fetch<T>(&self, params: &T) -> Result<T, Error>
+    async fn fetch<T>(&self, params: &T) -> Result<T, DeadpoolError>
     where
         T: SqlQuery + FromRow + SqlParams + Send + Sync;
     
     /// Belirtilen kriterlere uygun tüm kayıtları getirir.
-    async fn fetch_all<T>(&self, params: &T) -> Result<Vec<T>, Error>
+    async fn fetch_all<T>(&self, params: &T) -> Result<Vec<T>, DeadpoolError>
     where
         T: SqlQuery + FromRow + SqlParams + Send + Sync;
     
     /// Belirtilen özel dönüşüm fonksiyonunu kullanarak tek bir kaydı getirir.
-    async fn select<T, R, F>(&self, entity: T, to_model: F) -> Result<R, Error>
+    async fn select<T, R, F>(&self, entity: T, to_model: F) -> Result<R, DeadpoolError>
     where
         T: SqlQuery + SqlParams + Send + Sync,
         F: FnOnce(&Row) -> Result<R, Error> + Send + Sync;
     
     /// Belirtilen özel dönüşüm fonksiyonunu kullanarak tüm kayıtları getirir.
-    async fn select_all<T, R, F>(&self, entity: T, to_model: F) -> Result<Vec<R>, Error>
+    async fn select_all<T, R, F>(&self, entity: T, to_model: F) -> Result<Vec<R>, DeadpoolError>
     where
         T: SqlQuery + SqlParams + Send + Sync,
         F: Fn(&Row) -> R + Send + Sync;
@@ -94,19 +455,37 @@ pub trait TransactionOps {
     /// Update method, mevcut bir kaydı güncellemek için kullanılır
     async fn update<T>(&self, entity: T) -> Result<u64, Error>
     where
-        T: SqlQuery + UpdateParams + SqlParams + Debug + Send + 'static;
+        T: SqlQuery + UpdateParams + Debug + Send + 'static;
 
     /// Delete method, bir kaydı silmek için kullanılır
     async fn delete<T>(&self, entity: T) -> Result<u64, Error>
     where
         T: SqlQuery + SqlParams + Debug + Send + 'static;
 
+    /// Fetch method, tek bir kayıt getirmek için kullanılır
+    async fn fetch<T>(&self, params: T) -> Result<T, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + 'static;
+
+    /// Fetch All method, birden fazla kayıt getirmek için kullanılır
+    async fn fetch_all<T>(&self, params: T) -> Result<Vec<T>, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + 'static;
+
     /// Get method, tek bir kayıt getirmek için kullanılır
+    #[deprecated(
+        since = "0.4.0",
+        note = "Renamed to `fetch`. Please use `fetch` method instead."
+    )]
     async fn get<T>(&self, params: &T) -> Result<T, Error>
     where
         T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + Clone + 'static;
 
     /// Get All method, birden fazla kayıt getirmek için kullanılır
+    #[deprecated(
+        since = "0.4.0",
+        note = "Renamed to `fetch_all`. Please use `fetch_all` method instead."
+    )]
     async fn get_all<T>(&self, params: &T) -> Result<Vec<T>, Error>
     where
         T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + Clone + 'static;
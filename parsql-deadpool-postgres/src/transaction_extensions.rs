@@ -1,8 +1,9 @@
 use std::fmt::Debug;
-use std::sync::OnceLock;
 use tokio_postgres::Error;
 use deadpool_postgres::Transaction;
 use crate::traits::{SqlQuery, SqlParams, FromRow, UpdateParams, TransactionOps};
+use crate::validation::check;
+use parsql_core::{metrics::observe_async, trace};
 
 #[async_trait::async_trait]
 impl TransactionOps for Transaction<'_> {
@@ -10,32 +11,28 @@ impl TransactionOps for Transaction<'_> {
     where
         T: SqlQuery + SqlParams + Debug + Send + 'static,
     {
+        check(&**self, entity.validation()).await?;
+
         let sql = T::query();
         
-        if let Some(trace) = std::env::var_os("PARSQL_TRACE") {
-            if trace == "1" {
-                println!("[PARSQL-DEADPOOL-POSTGRES-TX] Execute SQL: {}", sql);
-            }
-        }
+        trace::log_sql("PARSQL-DEADPOOL-POSTGRES-TX", sql);
 
         let params = SqlParams::params(&entity);
-        self.execute(&sql, &params[..]).await
+        observe_async("insert", T::table_name(), self.execute(sql, &params[..])).await
     }
 
     async fn update<T>(&self, entity: T) -> Result<u64, Error>
     where
-        T: SqlQuery + UpdateParams + SqlParams + Debug + Send + 'static,
+        T: SqlQuery + UpdateParams + Debug + Send + 'static,
     {
+        check(&**self, entity.validation()).await?;
+
         let sql = T::query();
         
-        if let Some(trace) = std::env::var_os("PARSQL_TRACE") {
-            if trace == "1" {
-                println!("[PARSQL-DEADPOOL-POSTGRES-TX] Execute SQL: {}", sql);
-            }
-        }
+        trace::log_sql("PARSQL-DEADPOOL-POSTGRES-TX", sql);
 
-        let params = SqlParams::params(&entity);
-        self.execute(&sql, &params[..]).await
+        let params = UpdateParams::params(&entity);
+        observe_async("update", T::table_name(), self.execute(sql, &params[..])).await
     }
 
     async fn delete<T>(&self, entity: T) -> Result<u64, Error>
@@ -44,60 +41,54 @@ impl TransactionOps for Transaction<'_> {
     {
         let sql = T::query();
         
-        if let Some(trace) = std::env::var_os("PARSQL_TRACE") {
-            if trace == "1" {
-                println!("[PARSQL-DEADPOOL-POSTGRES-TX] Execute SQL: {}", sql);
-            }
-        }
+        trace::log_sql("PARSQL-DEADPOOL-POSTGRES-TX", sql);
 
         let params = SqlParams::params(&entity);
-        self.execute(&sql, &params[..]).await
+        observe_async("delete", T::table_name(), self.execute(sql, &params[..])).await
     }
 
-    async fn get<T>(&self, params: &T) -> Result<T, Error>
+    async fn fetch<T>(&self, params: T) -> Result<T, Error>
     where
-        T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + Clone + 'static,
+        T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + 'static,
     {
-        let sql = T::query();
+        let sql = T::query_single();
         
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-            std::env::var_os("PARSQL_TRACE").map_or(false, |v| v == "1")
-        });
-        
-        if is_trace_enabled {
-            println!("[PARSQL-DEADPOOL-POSTGRES-TX] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-DEADPOOL-POSTGRES-TX", sql);
 
-        let params_owned = params.clone();
-        let query_params = SqlParams::params(&params_owned);
-        let row = self.query_one(&sql, &query_params[..]).await?;
+        let query_params = SqlParams::params(&params);
+        let row = observe_async("fetch", T::table_name(), self.query_one(sql, &query_params[..])).await?;
         
         T::from_row(&row)
     }
 
-    async fn get_all<T>(&self, params: &T) -> Result<Vec<T>, Error>
+    async fn fetch_all<T>(&self, params: T) -> Result<Vec<T>, Error>
     where
-        T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + Clone + 'static,
+        T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + 'static,
     {
         let sql = T::query();
         
-        if let Some(trace) = std::env::var_os("PARSQL_TRACE") {
-            if trace == "1" {
-                println!("[PARSQL-DEADPOOL-POSTGRES-TX] Execute SQL: {}", sql);
-            }
-        }
+        trace::log_sql("PARSQL-DEADPOOL-POSTGRES-TX", sql);
 
-        let params_owned = params.clone();
-        let query_params = SqlParams::params(&params_owned);
-        let rows = self.query(&sql, &query_params[..]).await?;
-        
-        let mut results = Vec::with_capacity(rows.len());
-        for row in rows {
-            results.push(T::from_row(&row)?);
-        }
+        let query_params = SqlParams::params(&params);
+        let rows = observe_async("fetch_all", T::table_name(), self.query(sql, &query_params[..])).await?;
         
-        Ok(results)
+        T::from_rows(&rows)
+    }
+
+    #[allow(deprecated)]
+    async fn get<T>(&self, params: &T) -> Result<T, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + Clone + 'static,
+    {
+        self.fetch(params.clone()).await
+    }
+
+    #[allow(deprecated)]
+    async fn get_all<T>(&self, params: &T) -> Result<Vec<T>, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Debug + Send + Sync + Clone + 'static,
+    {
+        self.fetch_all(params.clone()).await
     }
 
     async fn select<T, R, F>(&self, entity: T, to_model: F) -> Result<R, Error>
@@ -106,16 +97,12 @@ impl TransactionOps for Transaction<'_> {
         F: FnOnce(&tokio_postgres::Row) -> Result<R, Error> + Send + Sync + 'static,
         R: Send + 'static,
     {
-        let sql = T::query();
+        let sql = T::query_single();
         
-        if let Some(trace) = std::env::var_os("PARSQL_TRACE") {
-            if trace == "1" {
-                println!("[PARSQL-DEADPOOL-POSTGRES-TX] Execute SQL: {}", sql);
-            }
-        }
+        trace::log_sql("PARSQL-DEADPOOL-POSTGRES-TX", sql);
 
         let params = SqlParams::params(&entity);
-        let row = self.query_one(&sql, &params[..]).await?;
+        let row = observe_async("select", T::table_name(), self.query_one(sql, &params[..])).await?;
         to_model(&row)
     }
 
@@ -127,14 +114,10 @@ impl TransactionOps for Transaction<'_> {
     {
         let sql = T::query();
         
-        if let Some(trace) = std::env::var_os("PARSQL_TRACE") {
-            if trace == "1" {
-                println!("[PARSQL-DEADPOOL-POSTGRES-TX] Execute SQL: {}", sql);
-            }
-        }
+        trace::log_sql("PARSQL-DEADPOOL-POSTGRES-TX", sql);
 
         let params = SqlParams::params(&entity);
-        let rows = self.query(&sql, &params[..]).await?;
+        let rows = observe_async("select_all", T::table_name(), self.query(sql, &params[..])).await?;
         
         let mut results = Vec::with_capacity(rows.len());
         for row in rows {
@@ -1,10 +1,12 @@
 // use parsql_core::{Deleteable, Insertable, Queryable, Updateable};
 use deadpool_postgres::{Transaction, Client};
-use tokio_postgres::Error;
+use tokio_postgres::{error::SqlState, Error};
 // Makrolar sadece dokümantasyon için kullanılıyor, gerçek kodda SqlQuery kullanılmalı
 // use parsql_macros::{Insertable, Updateable};
 
-use crate::traits::{SqlQuery, SqlParams, FromRow};
+use crate::traits::{SqlQuery, SqlParams, UpdateParams, FromRow, ParentKey};
+use crate::validation::check;
+use parsql_core::{temp::create_temp_as, trace, Dialect, OnError, Progress};
 
 /// # begin
 /// 
@@ -43,13 +45,72 @@ use crate::traits::{SqlQuery, SqlParams, FromRow};
 pub async fn begin(client: &mut Client) -> Result<Transaction<'_>, Error> {
     let tx = client.transaction().await?;
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Begin Transaction");
-    }
+    trace::log("PARSQL-TOKIO-POSTGRES-TX", "Begin Transaction");
     
     Ok(tx)
 }
 
+/// # rollback
+/// 
+/// Rolls a transaction back explicitly instead of relying on drop, which rolls back
+/// silently and loses the error of the ROLLBACK itself. The pool client the
+/// transaction came from is usable again afterwards.
+/// 
+/// ## Parameters
+/// - `tx`: Transaction to roll back
+/// 
+/// ## Return Value
+/// - `Result<(), Error>`: Error when the ROLLBACK fails
+/// 
+/// ## Example Usage
+/// ```rust,no_run
+/// use tokio_postgres::{NoTls, Error};
+/// use deadpool_postgres::{Config, Runtime};
+/// use parsql_deadpool_postgres::transactional::{begin, is_aborted, rollback};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut cfg = Config::new();
+///     cfg.host = Some("localhost".to_string());
+///     cfg.dbname = Some("test".to_string());
+///     
+///     let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls)?;
+///     let mut client = pool.get().await?;
+///     
+///     let tx = begin(&mut client).await?;
+///     let _ = tx.batch_execute("INSERT INTO users (id) VALUES (1)").await;
+///     if is_aborted(&tx).await? {
+///         rollback(tx).await?;
+///     } else {
+///         tx.commit().await?;
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn rollback(tx: Transaction<'_>) -> Result<(), Error> {
+    trace::log("PARSQL-TOKIO-POSTGRES-TX", "Rollback Transaction");
+    tx.rollback().await
+}
+
+/// # is_aborted
+/// 
+/// Tells whether an earlier statement failed inside the transaction, after which
+/// PostgreSQL refuses everything but a rollback (`25P02 in_failed_sql_transaction`).
+/// Costs one round trip.
+/// 
+/// ## Parameters
+/// - `tx`: Transaction to check
+/// 
+/// ## Return Value
+/// - `Result<bool, Error>`: `true` when the transaction must be rolled back
+pub async fn is_aborted(tx: &Transaction<'_>) -> Result<bool, Error> {
+    match tx.batch_execute("SELECT 1").await {
+        Ok(()) => Ok(false),
+        Err(e) if e.code() == Some(&SqlState::IN_FAILED_SQL_TRANSACTION) => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
 /// # begin_from_pool
 /// 
 /// Starts a new database transaction directly from a connection pool.
@@ -96,7 +157,7 @@ pub async fn begin(client: &mut Client) -> Result<Transaction<'_>, Error> {
 /// 
 /// ## Parameters
 /// - `transaction`: Active transaction object
-/// - `entity`: Data object containing the update information (must implement Updateable and SqlParams traits)
+/// - `entity`: Data object containing the update information (must implement Updateable and UpdateParams traits)
 /// 
 /// ## Return Value
 /// - `Result<(Transaction<'_>, u64), Error>`: On success, returns the transaction and number of updated records
@@ -141,18 +202,21 @@ pub async fn begin(client: &mut Client) -> Result<Transaction<'_>, Error> {
 ///     Ok(())
 /// }
 /// ```
-pub async fn tx_update<T: SqlQuery + SqlParams>(
+pub async fn tx_update<T>(
     transaction: Transaction<'_>,
     entity: T,
-) -> Result<(Transaction<'_>, u64), Error> {
+) -> Result<(Transaction<'_>, u64), Error>
+where
+    T: SqlQuery + UpdateParams + Send + Sync + 'static
+{
+    check(&*transaction, entity.validation()).await?;
+
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let params = entity.params();
-    let result = transaction.execute(&sql, &params).await?;
+    let result = transaction.execute(sql, &params).await?;
     Ok((transaction, result))
 }
 
@@ -209,17 +273,117 @@ pub async fn tx_insert<T: SqlQuery + SqlParams>(
     transaction: Transaction<'_>,
     entity: T,
 ) -> Result<(Transaction<'_>, u64), Error> {
+    check(&*transaction, entity.validation()).await?;
+
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let params = entity.params();
-    let result = transaction.execute(&sql, &params).await?;
+    let result = transaction.execute(sql, &params).await?;
     Ok((transaction, result))
 }
 
+/// # tx_insert_all
+///
+/// Inserts several records within a transaction and reports progress after each one.
+///
+/// `OnError::Stop` ends the batch at the first failing record, after which PostgreSQL
+/// rejects the rest of the transaction, so roll it back. `OnError::Continue` runs each
+/// record in a savepoint and rolls back only the failing ones.
+///
+/// ## Parameters
+/// - `transaction`: Active transaction object
+/// - `entities`: Data objects to insert (must implement Insertable and SqlParams traits)
+/// - `on_error`: Whether to stop at the first failing record
+/// - `on_progress`: Called after every record
+///
+/// ## Return Value
+/// - `Result<(Transaction<'_>, Vec<Result<u64, Error>>), Error>`: On success, returns the transaction and the result of every attempted record
+///
+/// ## Example Usage
+/// ```rust,no_run
+/// use tokio_postgres::{NoTls, Error};
+/// use deadpool_postgres::{Config, Runtime};
+/// use parsql::deadpool_postgres::{transactional::tx_insert_all, OnError};
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Error> {
+///     let mut cfg = Config::new();
+///     cfg.host = Some("localhost".to_string());
+///     cfg.dbname = Some("test".to_string());
+///
+///     let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls)?;
+///     let mut client = pool.get().await?;
+///     let tx = client.transaction().await?;
+///
+///     let users = vec![
+///         InsertUser { name: "John".to_string(), email: "john@example.com".to_string() },
+///         InsertUser { name: "Jane".to_string(), email: "jane@example.com".to_string() },
+///     ];
+///
+///     let (tx, results) = tx_insert_all(tx, users, OnError::Continue, |progress| {
+///         println!("{}/{}", progress.done, progress.total);
+///     }).await?;
+///     tx.commit().await?;
+///
+///     println!("{} rows failed", results.iter().filter(|r| r.is_err()).count());
+///     Ok(())
+/// }
+/// ```
+pub async fn tx_insert_all<T: SqlQuery + SqlParams>(
+    transaction: Transaction<'_>,
+    entities: Vec<T>,
+    on_error: OnError,
+    mut on_progress: impl FnMut(Progress),
+) -> Result<(Transaction<'_>, Vec<Result<u64, Error>>), Error> {
+    let total = entities.len();
+    let mut results = Vec::with_capacity(total);
+    let mut failed = 0;
+    let sql = T::query();
+
+    for entity in entities {
+        if on_error == OnError::Continue {
+            transaction.batch_execute("SAVEPOINT parsql_insert_all").await?;
+        }
+
+        let result = match check(&*transaction, entity.validation()).await {
+            Ok(()) => {
+                trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
+                transaction.execute(sql, &entity.params()).await
+            }
+            Err(e) => Err(e),
+        };
+
+        if on_error == OnError::Continue {
+            // Hatalı kayıt savepoint'e geri alınır, transaction kullanılabilir kalır
+            let release = if result.is_ok() {
+                "RELEASE SAVEPOINT parsql_insert_all"
+            } else {
+                "ROLLBACK TO SAVEPOINT parsql_insert_all"
+            };
+            transaction.batch_execute(release).await?;
+        }
+
+        let stop = result.is_err() && on_error == OnError::Stop;
+        failed += result.is_err() as usize;
+        results.push(result);
+        on_progress(Progress { done: results.len(), total, failed });
+        if stop {
+            break;
+        }
+    }
+
+    Ok((transaction, results))
+}
+
 /// # tx_delete
 /// 
 /// Deletes a record within a transaction.
@@ -236,25 +400,87 @@ pub async fn tx_delete<T: SqlQuery + SqlParams>(
 ) -> Result<(Transaction<'_>, u64), Error> {
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let params = entity.params();
-    let result = transaction.execute(&sql, &params).await?;
+    let result = transaction.execute(sql, &params).await?;
+    Ok((transaction, result))
+}
+
+/// # tx_fetch
+/// 
+/// Retrieves a single record within a transaction.
+/// 
+/// ## Parameters
+/// - `transaction`: Active transaction object
+/// - `params`: Query parameters (must implement SqlQuery, FromRow and SqlParams traits)
+/// 
+/// ## Return Value
+/// - `Result<(Transaction<'_>, T), Error>`: On success, returns the transaction and the retrieved record
+pub async fn tx_fetch<T>(
+    transaction: Transaction<'_>,
+    params: T,
+) -> Result<(Transaction<'_>, T), Error>
+where
+    T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static
+{
+    let sql = T::query_single();
+    
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
+
+    let query_params = params.params();
+    let row = transaction.query_one(sql, &query_params).await?;
+    let result = T::from_row(&row)?;
+    
     Ok((transaction, result))
 }
 
+/// # tx_fetch_all
+/// 
+/// Retrieves multiple records within a transaction.
+/// 
+/// ## Parameters
+/// - `transaction`: Active transaction object
+/// - `params`: Query parameters (must implement SqlQuery, FromRow and SqlParams traits)
+/// 
+/// ## Return Value
+/// - `Result<(Transaction<'_>, Vec<T>), Error>`: On success, returns the transaction and the retrieved records
+pub async fn tx_fetch_all<T>(
+    transaction: Transaction<'_>,
+    params: T,
+) -> Result<(Transaction<'_>, Vec<T>), Error>
+where
+    T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static
+{
+    let sql = T::query();
+    
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
+
+    let query_params = params.params();
+    let rows = transaction.query(sql, &query_params).await?;
+    
+    let results = T::from_rows(&rows)?;
+    
+    Ok((transaction, results))
+}
+
 /// # tx_get
 /// 
 /// Retrieves a single record within a transaction.
 /// 
+/// ## Deprecated
+/// Renamed to `tx_fetch`, which takes the query by value like the other backends.
+/// 
 /// ## Parameters
 /// - `transaction`: Active transaction object
 /// - `params`: Query parameters (must implement SqlQuery, FromRow and SqlParams traits)
 /// 
 /// ## Return Value
 /// - `Result<(Transaction<'_>, T), Error>`: On success, returns the transaction and the retrieved record
+#[deprecated(
+    since = "0.4.0",
+    note = "Renamed to `tx_fetch`. Please use `tx_fetch` function instead."
+)]
 pub async fn tx_get<'a, T>(
     transaction: Transaction<'a>,
     params: &T,
@@ -262,14 +488,12 @@ pub async fn tx_get<'a, T>(
 where
     T: SqlQuery + FromRow + SqlParams,
 {
-    let sql = T::query();
+    let sql = T::query_single();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let query_params = params.params();
-    let row = transaction.query_one(&sql, &query_params).await?;
+    let row = transaction.query_one(sql, &query_params).await?;
     let result = T::from_row(&row)?;
     
     Ok((transaction, result))
@@ -279,12 +503,19 @@ where
 /// 
 /// Retrieves multiple records within a transaction.
 /// 
+/// ## Deprecated
+/// Renamed to `tx_fetch_all`, which takes the query by value like the other backends.
+/// 
 /// ## Parameters
 /// - `transaction`: Active transaction object
 /// - `params`: Query parameters (must implement SqlQuery, FromRow and SqlParams traits)
 /// 
 /// ## Return Value
 /// - `Result<(Transaction<'_>, Vec<T>), Error>`: On success, returns the transaction and the retrieved records
+#[deprecated(
+    since = "0.4.0",
+    note = "Renamed to `tx_fetch_all`. Please use `tx_fetch_all` function instead."
+)]
 pub async fn tx_get_all<'a, T>(
     transaction: Transaction<'a>,
     params: &T,
@@ -294,17 +525,12 @@ where
 {
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let query_params = params.params();
-    let rows = transaction.query(&sql, &query_params).await?;
+    let rows = transaction.query(sql, &query_params).await?;
     
-    let mut results = Vec::with_capacity(rows.len());
-    for row in rows {
-        results.push(T::from_row(&row)?);
-    }
+    let results = T::from_rows(&rows)?;
     
     Ok((transaction, results))
 }
@@ -329,14 +555,12 @@ where
     T: SqlQuery + SqlParams,
     F: FnOnce(&tokio_postgres::Row) -> Result<R, Error>,
 {
-    let sql = T::query();
+    let sql = T::query_single();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let params = entity.params();
-    let row = transaction.query_one(&sql, &params).await?;
+    let row = transaction.query_one(sql, &params).await?;
     let result = to_model(&row)?;
     
     Ok((transaction, result))
@@ -364,12 +588,10 @@ where
 {
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let params = entity.params();
-    let rows = transaction.query(&sql, &params).await?;
+    let rows = transaction.query(sql, &params).await?;
     
     let mut results = Vec::with_capacity(rows.len());
     for row in rows {
@@ -377,4 +599,120 @@ where
     }
     
     Ok((transaction, results))
-}
\ No newline at end of file
+}
+
+/// # create_temp_from
+/// 
+/// Fills a temporary table, created `ON COMMIT DROP`, with the rows of a query.
+/// The pooled connection keeps no trace of it once the transaction ends; until then
+/// later statements of the transaction can join or update against it by name.
+/// 
+/// ## Parameters
+/// - `transaction`: Active transaction object
+/// - `entity`: Query filling the table (must implement SqlQuery and SqlParams traits)
+/// - `name`: Name of the temporary table
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of rows written to the table
+/// 
+/// ## Panics
+/// If `name` is not a plain table name.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// let mut client = pool.get().await?;
+/// let tx = begin(&mut client).await?;
+/// create_temp_from(&tx, &ActiveUsers { state: 1 }, "tmp_active").await?;
+/// tx.batch_execute("UPDATE orders SET priority = 1 FROM tmp_active WHERE orders.user_id = tmp_active.id").await?;
+/// tx.commit().await?;
+/// ```
+pub async fn create_temp_from<T: SqlQuery + SqlParams>(
+    transaction: &Transaction<'_>,
+    entity: &T,
+    name: &str,
+) -> Result<u64, Error> {
+    let sql = create_temp_as(T::query(), name, Dialect::Postgres)
+        .unwrap_or_else(|| panic!("create_temp_from: `{}` is not a valid temporary table name", name));
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", &sql);
+    transaction.execute(sql.as_str(), &entity.params()).await
+}
+
+/// # persist_graph
+/// 
+/// Inserts a parent record and its children in a single transaction.
+/// 
+/// The parent is inserted with `RETURNING`; the returned key is written into the
+/// `#[parent_key("...")]` field of every child before it is inserted. If any insert
+/// fails the transaction is rolled back.
+/// 
+/// ## Parameters
+/// - `client`: Pool client to run the transaction on
+/// - `parent`: Parent record (must have a `#[returning("...")]` attribute)
+/// - `children`: Child records (must have a `#[parent_key("...")]` attribute)
+/// 
+/// ## Return Value
+/// - `Result<C::Key, Error>`: On success, returns the parent's key
+/// 
+/// ## Example Usage
+/// ```rust,no_run
+/// use tokio_postgres::{NoTls, Error};
+/// use deadpool_postgres::{Config, Runtime};
+/// use parsql::deadpool_postgres::transactional::persist_graph;
+/// 
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// #[returning("id")]
+/// pub struct InsertUser {
+///     pub name: String,
+/// }
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("posts")]
+/// #[parent_key("user_id")]
+/// pub struct InsertPost {
+///     pub user_id: i64,
+///     pub content: String,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Error> {
+///     let mut cfg = Config::new();
+///     cfg.host = Some("localhost".to_string());
+///     cfg.dbname = Some("test".to_string());
+///     
+///     let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls)?;
+///     let mut client = pool.get().await?;
+///     
+///     let user_id = persist_graph(
+///         &mut client,
+///         InsertUser { name: "John".to_string() },
+///         vec![InsertPost { user_id: 0, content: "Hello".to_string() }],
+///     ).await?;
+///     
+///     println!("Inserted user {}", user_id);
+///     Ok(())
+/// }
+/// ```
+pub async fn persist_graph<P, C>(client: &mut Client, parent: P, children: Vec<C>) -> Result<C::Key, Error>
+where
+    P: SqlQuery + SqlParams,
+    C: SqlQuery + SqlParams + ParentKey,
+    C::Key: for<'a> tokio_postgres::types::FromSql<'a> + Clone,
+{
+    let transaction = begin(client).await?;
+
+    let sql = P::query();
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
+    let row = transaction.query_one(sql, &parent.params()).await?;
+    let key: C::Key = row.try_get(0)?;
+
+    let sql = C::query();
+    for mut child in children {
+        child.set_parent_key(key.clone());
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
+        transaction.execute(sql, &child.params()).await?;
+    }
+
+    transaction.commit().await?;
+    Ok(key)
+}
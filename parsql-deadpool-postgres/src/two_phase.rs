@@ -0,0 +1,207 @@
+//! Two-phase commit across databases.
+//!
+//! Committing two transactions one after the other leaves the databases inconsistent
+//! when the second commit fails. [`commit`] first runs `PREPARE TRANSACTION` on every
+//! participant and only then `COMMIT PREPARED`, so a failure before the commit phase
+//! rolls every database back. A crash in the commit phase leaves the remaining
+//! participants prepared; they survive restarts and are finished later with
+//! [`commit_prepared`] once [`pending`] lists them. Each pool is one database.
+//!
+//! The servers must allow prepared transactions (`max_prepared_transactions > 0`).
+//!
+//! ```rust,no_run
+//! use parsql::deadpool_postgres::{two_phase::{self, NoRecovery}, Pool};
+//! # async fn example(orders: &Pool, billing: &Pool) -> Result<(), Box<dyn std::error::Error>> {
+//! let mut orders_client = orders.get().await?;
+//! let mut billing_client = billing.get().await?;
+//! let orders_tx = orders_client.transaction().await?;
+//! let billing_tx = billing_client.transaction().await?;
+//! // orders_tx.insert(...).await?; billing_tx.insert(...).await?;
+//!
+//! two_phase::commit(vec![orders_tx, billing_tx], &NoRecovery).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! This is best-effort coordination, not a transaction manager: the [`RecoveryHook`]
+//! is where the application records the decision durably so that a restarted process
+//! knows which prepared transactions to commit.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use deadpool_postgres::{Pool, Transaction};
+use parsql_core::{debug::quote_literal, trace};
+use tokio_postgres::{Error, GenericClient};
+
+use crate::acquire::acquire;
+use crate::DeadpoolError;
+
+/// Prefix of the transaction identifiers used by [`commit`].
+pub const GID_PREFIX: &str = "parsql-";
+
+/// Receives the identifiers of prepared transactions during [`commit`].
+pub trait RecoveryHook: Send + Sync {
+    /// Called once every participant is prepared, before the first `COMMIT PREPARED`.
+    ///
+    /// `gids[i]` is the identifier of the i-th participant. Persist them here to be
+    /// able to finish the commit after a crash; an error rolls every participant back.
+    fn prepared(&self, gids: &[String]) -> Result<(), Box<dyn StdError + Send + Sync>>;
+
+    /// Called once every participant is committed.
+    fn committed(&self, _gids: &[String]) {}
+}
+
+/// A [`RecoveryHook`] that records nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRecovery;
+
+impl RecoveryHook for NoRecovery {
+    fn prepared(&self, _gids: &[String]) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        Ok(())
+    }
+}
+
+/// Errors of a two-phase commit.
+#[derive(Debug)]
+pub enum TwoPhaseError {
+    /// A participant could not be prepared; every participant was rolled back
+    Prepare(Error),
+    /// The recovery hook rejected the commit; every participant was rolled back
+    Hook(Box<dyn StdError + Send + Sync>),
+    /// `COMMIT PREPARED` failed; the `pending` transactions are still prepared and
+    /// must be finished with [`commit_prepared`]
+    Commit {
+        /// Error of the failed `COMMIT PREPARED`
+        source: Error,
+        /// Identifiers of the participants that aren't committed yet
+        pending: Vec<String>,
+    },
+}
+
+impl fmt::Display for TwoPhaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TwoPhaseError::Prepare(e) => write!(f, "prepare phase failed: {}", e),
+            TwoPhaseError::Hook(e) => write!(f, "recovery hook failed: {}", e),
+            TwoPhaseError::Commit { source, pending } => write!(
+                f,
+                "commit phase failed, {} transaction(s) left prepared: {}",
+                pending.len(),
+                source
+            ),
+        }
+    }
+}
+
+impl StdError for TwoPhaseError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            TwoPhaseError::Prepare(e) => Some(e),
+            TwoPhaseError::Hook(e) => Some(e.as_ref()),
+            TwoPhaseError::Commit { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Commits `transactions` atomically with `PREPARE TRANSACTION` / `COMMIT PREPARED`,
+/// returning the identifiers the participants were prepared under.
+pub async fn commit<H: RecoveryHook>(
+    transactions: Vec<Transaction<'_>>,
+    hook: &H,
+) -> Result<Vec<String>, TwoPhaseError> {
+    let gids = new_gids(transactions.len());
+
+    for (i, (transaction, gid)) in transactions.iter().zip(&gids).enumerate() {
+        if let Err(e) = prepare(transaction, gid).await {
+            abort(&transactions[..i], &gids[..i]).await;
+            return Err(TwoPhaseError::Prepare(e));
+        }
+    }
+
+    if let Err(e) = hook.prepared(&gids) {
+        abort(&transactions, &gids).await;
+        return Err(TwoPhaseError::Hook(e));
+    }
+
+    for (i, (transaction, gid)) in transactions.iter().zip(&gids).enumerate() {
+        if let Err(source) = run(&**transaction, "COMMIT PREPARED", gid).await {
+            return Err(TwoPhaseError::Commit {
+                source,
+                pending: gids[i..].to_vec(),
+            });
+        }
+    }
+
+    hook.committed(&gids);
+    Ok(gids)
+}
+
+/// Lists the transactions prepared by [`commit`] that are still waiting in the
+/// database of `pool`.
+pub async fn pending(pool: &Pool) -> Result<Vec<String>, DeadpoolError> {
+    let client = acquire(pool).await?;
+    let sql = "SELECT gid FROM pg_prepared_xacts WHERE database = current_database() AND starts_with(gid, $1) ORDER BY prepared";
+
+    trace::log_sql("PARSQL-DEADPOOL-POSTGRES-2PC", sql);
+
+    let rows = client.query(sql, &[&GID_PREFIX]).await?;
+    Ok(rows
+        .iter()
+        .map(|row| row.try_get(0))
+        .collect::<Result<_, _>>()?)
+}
+
+/// Commits a prepared transaction left behind by an interrupted [`commit`].
+pub async fn commit_prepared(pool: &Pool, gid: &str) -> Result<(), DeadpoolError> {
+    let client = acquire(pool).await?;
+    Ok(run(&**client, "COMMIT PREPARED", gid).await?)
+}
+
+/// Rolls back a prepared transaction left behind by an interrupted [`commit`].
+pub async fn rollback_prepared(pool: &Pool, gid: &str) -> Result<(), DeadpoolError> {
+    let client = acquire(pool).await?;
+    Ok(run(&**client, "ROLLBACK PREPARED", gid).await?)
+}
+
+async fn prepare(transaction: &Transaction<'_>, gid: &str) -> Result<(), Error> {
+    // Hata almış bir transaction'da PREPARE TRANSACTION hata vermeden ROLLBACK gibi
+    // davranır; önce transaction'ın hâlâ geçerli olduğu doğrulanır
+    transaction.batch_execute("SELECT 1").await?;
+    run(&**transaction, "PREPARE TRANSACTION", gid).await
+}
+
+async fn run<C: GenericClient>(client: &C, command: &str, gid: &str) -> Result<(), Error> {
+    let sql = format!("{} {}", command, quote_literal(gid));
+
+    trace::log_sql("PARSQL-DEADPOOL-POSTGRES-2PC", &sql);
+
+    client.batch_execute(&sql).await
+}
+
+/// Rolls back the already prepared participants. Failures are ignored; the
+/// transactions then stay prepared and show up in [`pending`].
+async fn abort(transactions: &[Transaction<'_>], gids: &[String]) {
+    for (transaction, gid) in transactions.iter().zip(gids) {
+        let _ = run(&**transaction, "ROLLBACK PREPARED", gid).await;
+    }
+}
+
+fn new_gids(count: usize) -> Vec<String> {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let base = format!(
+        "{}{}-{}-{}",
+        GID_PREFIX,
+        std::process::id(),
+        nanos,
+        SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    );
+    // Aynı kümedeki iki veritabanı aynı gid'i kullanamaz
+    (0..count).map(|i| format!("{}-{}", base, i)).collect()
+}
@@ -0,0 +1,42 @@
+//! Materialized view refresh.
+//!
+//! A `Queryable` annotated with `#[materialized_view("...")]` selects from the view and
+//! implements [`MaterializedView`], so reporting pipelines can rebuild the view and read
+//! it with the same struct:
+//!
+//! ```rust,no_run
+//! use parsql::deadpool_postgres::{get_all, views::refresh_materialized_view};
+//! # use parsql::deadpool_postgres::{macros::{FromRow, Queryable, SqlParams}, traits::{FromRow, MaterializedView, SqlParams, SqlQuery}, DeadpoolError, Pool};
+//! # use tokio_postgres::{types::ToSql, Error, Row};
+//! # #[derive(Queryable, SqlParams, FromRow)]
+//! # #[materialized_view("daily_sales")]
+//! # #[where_clause("day >= $")]
+//! # pub struct DailySales { pub day: i32, pub total: i64 }
+//! # async fn example(pool: &Pool) -> Result<(), DeadpoolError> {
+//! refresh_materialized_view::<DailySales>(pool, true).await?;
+//! let sales = get_all(pool, &DailySales { day: 20240101, total: 0 }).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use deadpool_postgres::Pool;
+use parsql_core::{trace, views};
+
+use crate::acquire::acquire;
+use crate::traits::MaterializedView;
+use crate::DeadpoolError;
+
+/// Rebuilds the materialized view of `T` on a connection taken from `pool`.
+///
+/// With `concurrently` readers are not blocked while the view is rebuilt; PostgreSQL
+/// then needs a unique index on the view.
+pub async fn refresh_materialized_view<T: MaterializedView>(
+    pool: &Pool,
+    concurrently: bool,
+) -> Result<(), DeadpoolError> {
+    let sql = views::refresh_statement(T::view_name(), concurrently);
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-POOL", &sql);
+
+    let client = acquire(pool).await?;
+    Ok(client.batch_execute(&sql).await?)
+}
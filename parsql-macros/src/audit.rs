@@ -0,0 +1,117 @@
+use proc_macro::TokenStream;
+use syn::{punctuated::Punctuated, Data, DeriveInput, Token};
+
+/// Attributes whose string value is placed into the generated SQL verbatim.
+const RAW_SQL_ATTRIBUTES: &[&str] = &[
+    "where_clause",
+    "where_if",
+    "select",
+    "select_extra",
+    "join",
+    "group_by",
+    "having",
+    "order_by",
+    "limit",
+    "offset",
+    "update",
+    "update_expr",
+    "using",
+    "from",
+    "columns",
+    "returning",
+    "default_sql",
+];
+
+/// Keywords that have no place inside a clause fragment and usually indicate
+/// an attempt to smuggle in a second statement.
+const SUSPICIOUS_KEYWORDS: &[&str] = &[
+    "DROP", "TRUNCATE", "ALTER", "GRANT", "REVOKE", "EXEC", "EXECUTE", "ATTACH", "DETACH", "PRAGMA",
+    "COPY",
+];
+
+/// Opt-out attribute that disables the audit for a single struct.
+pub(crate) const ALLOW_RAW_SQL: &str = "allow_raw_sql";
+
+/// Checks a raw SQL fragment given in an attribute.
+///
+/// Returns a description of the first problem found, if any.
+pub(crate) fn audit_sql_fragment(fragment: &str) -> Result<(), String> {
+    if fragment.contains(';') {
+        return Err("statement separator `;` is not allowed".to_string());
+    }
+    if fragment.contains("--") {
+        return Err("line comment `--` is not allowed".to_string());
+    }
+    if fragment.contains("/*") || fragment.contains("*/") {
+        return Err("block comment `/* */` is not allowed".to_string());
+    }
+    if fragment.matches('\'').count() % 2 == 1 {
+        return Err("unbalanced single quote".to_string());
+    }
+    if fragment.matches('"').count() % 2 == 1 {
+        return Err("unbalanced double quote".to_string());
+    }
+
+    let suspicious = fragment
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .find(|word| {
+            SUSPICIOUS_KEYWORDS
+                .iter()
+                .any(|keyword| word.eq_ignore_ascii_case(keyword))
+        });
+    if let Some(word) = suspicious {
+        return Err(format!("suspicious keyword `{}`", word));
+    }
+
+    Ok(())
+}
+
+/// Audits every raw SQL attribute of the struct.
+///
+/// Returns a `compile_error!` pointing at the offending attribute, or `None` when
+/// all fragments pass or the struct is marked with `#[allow_raw_sql]`.
+pub(crate) fn audit_attributes(input: &DeriveInput) -> Option<TokenStream> {
+    if input.attrs.iter().any(|attr| attr.path().is_ident(ALLOW_RAW_SQL)) {
+        return None;
+    }
+
+    // Alan öznitelikleri (default_sql) da sorguya aynen yazılır
+    let field_attrs = match &input.data {
+        Data::Struct(data) => data.fields.iter().flat_map(|f| f.attrs.iter()).collect(),
+        _ => Vec::new(),
+    };
+
+    input
+        .attrs
+        .iter()
+        .chain(field_attrs)
+        .filter(|attr| RAW_SQL_ATTRIBUTES.iter().any(|name| attr.path().is_ident(name)))
+        .find_map(|attr| {
+            // update_expr ve select_extra birden fazla string alabilir, diğerleri tek string alır;
+            // where_if'in `when` ifadesi Rust kodudur, yalnızca koşulu denetlenir
+            let fragments: Vec<syn::LitStr> = if attr.path().is_ident("where_if") {
+                let condition = attr
+                    .parse_args_with(|stream: syn::parse::ParseStream| {
+                        let condition = stream.parse()?;
+                        stream.parse::<proc_macro2::TokenStream>()?;
+                        Ok(condition)
+                    })
+                    .ok()?;
+                vec![condition]
+            } else {
+                attr.parse_args_with(Punctuated::<syn::LitStr, Token![,]>::parse_terminated)
+                    .ok()?
+                    .into_iter()
+                    .collect()
+            };
+            fragments.into_iter().find_map(|fragment| {
+                let problem = audit_sql_fragment(&fragment.value()).err()?;
+                let name = attr.path().get_ident()?.to_string();
+                let message = format!(
+                    "possible SQL injection in `#[{}]`: {}. Add `#[{}]` to the struct if this is intended",
+                    name, problem, ALLOW_RAW_SQL
+                );
+                Some(syn::Error::new_spanned(fragment, message).to_compile_error().into())
+            })
+        })
+}
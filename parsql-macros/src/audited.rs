@@ -0,0 +1,46 @@
+use syn::DeriveInput;
+
+use parsql_core::audit_log::{is_plain_name, AuditLog};
+use parsql_core::batch::Dialect;
+
+use crate::dialect;
+
+/// Options of `#[audited(...)]`.
+const OPTIONS: &[&str] = &["table", "key", "actor"];
+
+/// Reads `#[audited]` or `#[audited(table = "...", key = "...", actor = "...")]`; unset
+/// options keep the defaults of [`AuditLog`].
+pub(crate) fn audit_log(input: &DeriveInput) -> Option<AuditLog> {
+    let attr = input.attrs.iter().find(|attr| attr.path().is_ident("audited"))?;
+    if dialect() == Dialect::Sqlite {
+        panic!("audited is not supported by SQLite, which has no data-modifying WITH; record the change with a trigger instead");
+    }
+    for (other, reason) in [("include_fields", "include_fields"), ("insert_from", "insert_from")] {
+        if input.attrs.iter().any(|attr| attr.path().is_ident(other)) {
+            panic!("audited cannot be combined with {}", reason);
+        }
+    }
+
+    let mut log = AuditLog::default();
+    if matches!(attr.meta, syn::Meta::Path(_)) {
+        return Some(log);
+    }
+    attr.parse_nested_meta(|meta| {
+        let value = meta.value()?.parse::<syn::LitStr>()?.value();
+        if !is_plain_name(&value) {
+            return Err(meta.error(format!("audited expects a plain name, found `{}`", value)));
+        }
+        if meta.path.is_ident("table") {
+            log.table = value;
+        } else if meta.path.is_ident("key") {
+            log.key = value;
+        } else if meta.path.is_ident("actor") {
+            log.actor = value;
+        } else {
+            return Err(meta.error(format!("unsupported audited option, expected one of: {}", OPTIONS.join(", "))));
+        }
+        Ok(())
+    })
+    .unwrap_or_else(|e| panic!("{}", e));
+    Some(log)
+}
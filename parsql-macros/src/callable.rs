@@ -0,0 +1,82 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+use parsql_core::batch::Dialect;
+
+use crate::{dialect, log_message, query_builder, query_metadata, SqlParamCounter};
+
+/// Implements the Callable derive macro.
+pub(crate) fn derive_callable_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, generic_where) = input.generics.split_for_impl();
+
+    // #[procedure] CALL ile, #[function] SELECT ile çağrılır
+    let routine = |name: &str| {
+        input.attrs.iter().find(|attr| attr.path().is_ident(name)).map(|attr| {
+            attr.parse_args::<syn::LitStr>()
+                .unwrap_or_else(|_| panic!("Expected a string literal for {}", name))
+                .value()
+        })
+    };
+    let (keyword, routine) = match (routine("procedure"), routine("function")) {
+        (Some(procedure), None) => ("CALL", procedure),
+        (None, Some(function)) => ("SELECT", function),
+        (Some(_), Some(_)) => panic!("Callable takes either procedure or function, not both"),
+        (None, None) => panic!("Callable requires #[procedure(\"name\")] or #[function(\"name\")]"),
+    };
+    if dialect() != Dialect::Postgres {
+        panic!("Callable is only supported on PostgreSQL");
+    }
+    let plain = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !routine.split('.').all(plain) {
+        panic!("Callable expects a routine name such as \"refresh_stats\" or \"reports.refresh_stats\", found `{}`", routine);
+    }
+
+    let fields: Vec<String> = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().map(|f| f.ident.as_ref().unwrap().to_string()).collect(),
+            Fields::Unit => Vec::new(),
+            _ => panic!("Callable can only be derived for structs with named fields"),
+        },
+        _ => panic!("Callable can only be derived for structs"),
+    };
+
+    // Argümanlar alanların sırasıyla bağlanır, SqlParams'ın INSERT sırası ile aynı
+    let mut param_counter = SqlParamCounter::new();
+    let arguments = fields
+        .iter()
+        .map(|_| param_counter.next_placeholder())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut builder = query_builder::SafeQueryBuilder::new();
+    builder.add_keyword(keyword);
+    // Ad yukarıda doğrulandı; add_identifier şema ayırıcısı olan noktayı silerdi
+    builder.add_raw(&format!("{}({})", routine, arguments));
+    let safe_query = builder.build();
+
+    log_message(&format!("Generated CALL SQL: {}", safe_query));
+    log_message(&format!("Total param count: {}", param_counter.count()));
+
+    let param_count = param_counter.count();
+    let metadata = query_metadata(&routine, Some(quote! { #param_count }), Some(&fields));
+
+    let expanded = quote! {
+        impl #impl_generics #struct_name #ty_generics #generic_where {
+            /// The generated SQL statement, available in `const` contexts.
+            pub const SQL: &'static str = #safe_query;
+        }
+
+        impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+            fn query() -> &'static str {
+                Self::SQL
+            }
+
+            #metadata
+        }
+    };
+
+    TokenStream::from(expanded)
+}
@@ -1,24 +1,36 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use crate::audit;
+use crate::audited;
+use crate::schema_check;
+use crate::sql_params::param_fields;
 use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
+use parsql_core::audit_log::Operation;
+use parsql_core::batch::Dialect;
+
 use crate::{
-    extract_fields_from_where_clause, log_message, number_where_clause_params, query_builder,
+    case_insensitive_where_clause, dialect, extract_fields_from_where_clause, log_message, number_where_clause_params, query_builder,
+    partition_warning, partitioned_where_clause, query_metadata, query_registration, quote_identifiers, quote_name, table_name,
     SqlParamCounter,
 };
 
 pub(crate) fn derive_deletable_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = &input.ident;
+    let (impl_generics, ty_generics, generic_where) = input.generics.split_for_impl();
 
-    let table = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("table"))
-        .expect("Missing `#[table = \"...\"]` attribute")
-        .parse_args::<syn::LitStr>()
-        .expect("Expected a string literal for table name")
-        .value();
+    // Ham SQL içeren öznitelikleri derleme zamanında denetle
+    if let Some(error) = audit::audit_attributes(&input) {
+        return error;
+    }
+
+    // schema-check açıksa tablo ve sütunlar şema görüntüsüyle karşılaştırılır
+    if let Some(error) = schema_check::check(&input, schema_check::Statement::Delete) {
+        return error;
+    }
+
+    let table = table_name(&input);
 
     // Get the optional where_clause attribute
     let where_clause = input
@@ -31,32 +43,84 @@ pub(crate) fn derive_deletable_impl(input: TokenStream) -> TokenStream {
                 .value()
         });
 
+    // #[partition_by] anahtarı koşulda yoksa bölüm budaması için eklenir
+    let where_clause = partitioned_where_clause(&input, where_clause);
+    // #[case_insensitive] / #[citext] sütunlarının karşılaştırmaları büyük-küçük harf ayırmaz
+    let where_clause = case_insensitive_where_clause(&input, where_clause);
+
+    // DELETE ... USING ile koşulda kullanılan diğer tablolar
+    let using = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("using"))
+        .map(|attr| {
+            attr.parse_args::<syn::LitStr>()
+                .expect("Expected a string literal for using")
+                .value()
+        });
+    if using.is_some() && dialect() == Dialect::Sqlite {
+        panic!("using is not supported by SQLite, which has no DELETE ... USING; use a subquery in where_clause");
+    }
+
     // SQL parametrelerinin numaralandırması için SqlParamCounter kullanıyoruz
-    // Her zaman 1'den başlar
+    // Her zaman 1'den başlar; USING içindeki parametreler WHERE'den önce gelir
     let mut param_counter = SqlParamCounter::new();
 
+    let adjusted_using = using
+        .as_deref()
+        .map(|using| number_where_clause_params(using, &mut param_counter));
+
     let adjusted_where_clause = where_clause
-        .map(|clause| number_where_clause_params(&clause, &mut param_counter))
+        .as_deref()
+        .map(|clause| number_where_clause_params(clause, &mut param_counter))
         .unwrap_or_else(|| "".to_string());
 
-    let mut builder = query_builder::SafeQueryBuilder::new();
+    let quote = quote_identifiers(&input);
+    let mut builder = query_builder::SafeQueryBuilder::new().quoting(quote);
 
     builder.add_keyword("DELETE FROM");
     builder.add_identifier(&table);
+    if let Some(using) = &adjusted_using {
+        builder.add_keyword("USING");
+        builder.add_raw(using);
+    }
     builder.add_keyword("WHERE");
     builder.add_raw(&adjusted_where_clause); // SafeQueryBuilder will automatically add spaces
 
     let safe_query = builder.build();
 
+    // #[audited] silinen satırı denetim kaydına yazar
+    let safe_query = match audited::audit_log(&input) {
+        Some(log) => log.wrap(Operation::Delete, &safe_query, &quote_name(&table, quote), &[], None),
+        None => safe_query,
+    };
+
     // Log mesajlarını PARSQL_TRACE kontrolü ile yazdır
     log_message(&format!("Generated DELETE SQL: {}", safe_query));
     log_message(&format!("Total param count: {}", param_counter.count()));
 
+    let registration = query_registration(&input);
+    let partition_warning = partition_warning(&input, where_clause.as_deref());
+    let param_count = param_counter.count();
+    let param_names = Some(param_fields(&input)).filter(|names| names.len() == param_count);
+    let metadata = query_metadata(&table, Some(quote! { #param_count }), param_names.as_deref());
+
     let expanded = quote! {
-        impl SqlQuery for #struct_name {
-            fn query() -> String {
-                #safe_query.to_string()
+        impl #impl_generics #struct_name #ty_generics #generic_where {
+            /// The generated SQL statement, available in `const` contexts.
+            pub const SQL: &'static str = #safe_query;
+        }
+
+        #registration
+
+        #partition_warning
+
+        impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+            fn query() -> &'static str {
+                Self::SQL
             }
+
+            #metadata
         }
     };
 
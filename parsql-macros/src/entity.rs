@@ -0,0 +1,216 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields};
+
+use parsql_core::batch::Dialect;
+
+use crate::implementations;
+use crate::{
+    deletable, dialect, insertable, queryable, quote_column, quote_identifiers, rename_rule, sql_params, table_name,
+    update_params, updateable,
+};
+
+/// Options of `#[entity(...)]`.
+const OPTIONS: &[&str] = &["table", "pk"];
+
+/// Struct attributes the generated types share with the model.
+const SHARED_ATTRIBUTES: &[&str] = &["rename_all", "no_quote", "audited"];
+
+/// Implements the Entity derive macro.
+///
+/// The model itself becomes the SELECT by key (`Queryable`, `SqlParams`, `FromRow`, and
+/// `Keyed` through `primary_key`). Since a type implements `SqlQuery` once, the writes get
+/// companion types built from the model's fields and expanded with the regular derives:
+/// `<Name>Insert` without the key (returning it on PostgreSQL), `<Name>Update` setting
+/// every other column by key and `<Name>Delete` holding only the key.
+pub(crate) fn derive_entity_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let (table, pk) = entity_options(&input);
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+            _ => panic!("Entity can only be derived for structs with named fields"),
+        },
+        _ => panic!("Entity can only be derived for structs"),
+    };
+    let key_field = fields
+        .iter()
+        .find(|f| f.ident.as_ref().unwrap() == pk.as_str())
+        .unwrap_or_else(|| panic!("Entity key field `{}` not found; name it with #[entity(pk = \"...\")]", pk));
+    let key = key_field.ident.as_ref().unwrap();
+    let key_type = &key_field.ty;
+    let others = fields
+        .iter()
+        .filter(|f| f.ident.as_ref().unwrap() != pk.as_str())
+        .copied()
+        .collect::<Vec<_>>();
+    if others.is_empty() {
+        panic!("Entity needs a field besides the key `{}`", pk);
+    }
+
+    // Koşul sütun adıyla yazılır, anahtar alanı adıyla bağlanır
+    let column = quote_column(&rename_rule(&input).apply(&pk), quote_identifiers(&input));
+    let condition = format!("{} = ${}", column, pk);
+    let shared = input
+        .attrs
+        .iter()
+        .filter(|attr| SHARED_ATTRIBUTES.iter().any(|name| attr.path().is_ident(name)))
+        .collect::<Vec<_>>();
+
+    let mut model = input.clone();
+    model.attrs.retain(|attr| !attr.path().is_ident("entity"));
+    model.attrs.extend([
+        parse_quote!(#[table(#table)]),
+        parse_quote!(#[where_clause(#condition)]),
+        parse_quote!(#[primary_key(#pk)]),
+    ]);
+    let generate = match dialect() {
+        Dialect::Postgres => implementations::postgres::generate_from_row,
+        Dialect::Sqlite => implementations::sqlite::generate_from_row,
+    };
+    let from_row = implementations::from_row(&model, generate);
+    let query = expand(queryable::derive_queryable_impl, &model);
+    let params = expand(sql_params::derive_sql_params_impl, &model);
+
+    // SQLite'ta `returning` ikinci bir ifade ürettiğinden anahtar `last_insert_rowid` ile okunur
+    let returning = (dialect() == Dialect::Postgres).then(|| quote! { #[returning(#pk)] });
+    let insert_name = format_ident!("{}Insert", struct_name);
+    let insert: DeriveInput = parse_quote! {
+        #[table(#table)]
+        #returning
+        #(#shared)*
+        #vis struct #insert_name {
+            #(#others),*
+        }
+    };
+
+    let update_name = format_ident!("{}Update", struct_name);
+    let columns = others
+        .iter()
+        .map(|f| f.ident.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let update: DeriveInput = parse_quote! {
+        #[table(#table)]
+        #[update(#columns)]
+        #[where_clause(#condition)]
+        #(#shared)*
+        #vis struct #update_name {
+            #(#fields),*
+        }
+    };
+
+    let delete_name = format_ident!("{}Delete", struct_name);
+    let delete: DeriveInput = parse_quote! {
+        #[table(#table)]
+        #[where_clause(#condition)]
+        #(#shared)*
+        #vis struct #delete_name {
+            #key_field
+        }
+    };
+
+    let insert_doc = format!("INSERT of a new [`{}`]; the database assigns `{}`.", struct_name, pk);
+    let update_doc = format!("UPDATE of every column of [`{}`], matched by `{}`.", struct_name, pk);
+    let delete_doc = format!("DELETE of the [`{}`] with the given `{}`.", struct_name, pk);
+    let insert_decl = declaration(&insert, &insert_doc);
+    let update_decl = declaration(&update, &update_doc);
+    let delete_decl = declaration(&delete, &delete_doc);
+
+    let insert_impls = [
+        expand(insertable::derive_insertable_impl, &insert),
+        expand(sql_params::derive_sql_params_impl, &insert),
+    ];
+    let update_impls = [
+        expand(updateable::derive_updateable_impl, &update),
+        expand(update_params::derive_update_params_impl, &update),
+    ];
+    let delete_impls = [
+        expand(deletable::derive_deletable_impl, &delete),
+        expand(sql_params::derive_sql_params_impl, &delete),
+    ];
+
+    let other_idents = others.iter().map(|f| f.ident.as_ref().unwrap()).collect::<Vec<_>>();
+    let all_idents = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect::<Vec<_>>();
+
+    let expanded = quote! {
+        #query
+        #params
+        #from_row
+
+        #insert_decl
+        #(#insert_impls)*
+
+        impl From<#struct_name> for #insert_name {
+            fn from(entity: #struct_name) -> Self {
+                Self { #(#other_idents: entity.#other_idents),* }
+            }
+        }
+
+        #update_decl
+        #(#update_impls)*
+
+        impl From<#struct_name> for #update_name {
+            fn from(entity: #struct_name) -> Self {
+                Self { #(#all_idents: entity.#all_idents),* }
+            }
+        }
+
+        #delete_decl
+        #(#delete_impls)*
+
+        impl From<#key_type> for #delete_name {
+            fn from(#key: #key_type) -> Self {
+                Self { #key }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Reads `#[entity(table = "users", pk = "id")]`. The table defaults to the one inferred
+/// from the struct name, the key to `id`.
+fn entity_options(input: &DeriveInput) -> (String, String) {
+    let mut table = None;
+    let mut pk = None;
+    if let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("entity")) {
+        attr.parse_nested_meta(|meta| {
+            let value = meta.value()?.parse::<syn::LitStr>()?.value();
+            if meta.path.is_ident("table") {
+                table = Some(value);
+            } else if meta.path.is_ident("pk") {
+                pk = Some(value);
+            } else {
+                return Err(meta.error(format!("unsupported entity option, expected one of: {}", OPTIONS.join(", "))));
+            }
+            Ok(())
+        })
+        .unwrap_or_else(|e| panic!("{}", e));
+    }
+    (
+        table.unwrap_or_else(|| table_name(input)),
+        pk.unwrap_or_else(|| "id".to_string()),
+    )
+}
+
+/// Runs one of the derives on a generated type.
+fn expand(derive: fn(TokenStream) -> TokenStream, input: &DeriveInput) -> proc_macro2::TokenStream {
+    derive(quote!(#input).into()).into()
+}
+
+/// The declaration of a generated type, without the derive attributes it was expanded
+/// with: only a derive declaring them may leave them on a struct.
+fn declaration(input: &DeriveInput, doc: &str) -> DeriveInput {
+    let mut declaration = input.clone();
+    declaration.attrs = vec![parse_quote!(#[doc = #doc])];
+    if let Data::Struct(data) = &mut declaration.data {
+        for field in data.fields.iter_mut() {
+            field.attrs.retain(|attr| attr.path().is_ident("doc"));
+        }
+    }
+    declaration
+}
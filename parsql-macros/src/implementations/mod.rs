@@ -1,2 +1,135 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+use crate::{included_fields, quote_column, quote_identifiers, rename_rule};
+
 pub mod postgres;
 pub mod sqlite;
+
+/// Generates the `FromRow` impls of a struct.
+///
+/// Without a struct level `#[from_row(backends(...))]` this is the single impl of
+/// `generate`, named through the `FromRow`, `Row` and `Error` imported next to the derive.
+/// With it, one impl is generated for each listed backend module (e.g. `parsql::postgres`,
+/// `parsql::sqlite`), so a model crate can be used with several backends.
+pub(crate) fn from_row(
+    input: &DeriveInput,
+    generate: fn(&DeriveInput, Option<&syn::Path>) -> TokenStream,
+) -> TokenStream {
+    let columns = column_list(input);
+    let Some(backends) = listed_backends(input) else {
+        let from_row = generate(input, None);
+        return quote! { #from_row #columns };
+    };
+
+    let impls = backends.iter().map(|backend| {
+        let name = backend.segments.last().map(|segment| segment.ident.to_string());
+        // Satır okuma biçimi modül adından seçilir
+        match name.as_deref() {
+            Some("postgres" | "tokio_postgres" | "deadpool_postgres") => {
+                postgres::generate_from_row(input, Some(backend))
+            }
+            Some("sqlite" | "tokio_sqlite") => sqlite::generate_from_row(input, Some(backend)),
+            _ => panic!(
+                "unknown FromRow backend `{}`; expected a path ending in postgres, tokio_postgres, deadpool_postgres, sqlite or tokio_sqlite",
+                quote!(#backend)
+            ),
+        }
+    });
+    quote! { #(#impls)* #columns }
+}
+
+/// Generates the `COLUMNS` const of a struct with named fields, which lets other
+/// models pull its columns in with `#[include_fields(Base)]`.
+fn column_list(input: &DeriveInput) -> TokenStream {
+    let Data::Struct(data) = &input.data else {
+        return TokenStream::new();
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return TokenStream::new();
+    };
+    // Kendisi de temel model içeren yapılar başka bir modele eklenemez
+    if included_fields(input).is_some() {
+        return TokenStream::new();
+    }
+
+    let rule = rename_rule(input);
+    let quote = quote_identifiers(input);
+    let columns = fields
+        .named
+        .iter()
+        .map(|f| quote_column(&rule.apply(&f.ident.as_ref().unwrap().to_string()), quote));
+    let name = &input.ident;
+    quote! {
+        impl #name {
+            /// The columns read by the generated `FromRow` impl, in field order.
+            pub const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+        }
+    }
+}
+
+/// Reads the field holding the `#[include_fields(Base)]` model through the base's own
+/// `FromRow` impl, which picks its columns from the same row.
+pub(crate) fn included_read(
+    input: &DeriveInput,
+    field: &syn::Field,
+    from_row: &TokenStream,
+) -> Option<TokenStream> {
+    let (base, name) = included_fields(input)?;
+    if field.ident.as_ref()? != &name {
+        return None;
+    }
+    Some(quote! { <#base as #from_row>::from_row(row)? })
+}
+
+/// Reads the backend modules of a struct level `#[from_row(backends(parsql::postgres, ...))]`.
+fn listed_backends(input: &DeriveInput) -> Option<Vec<syn::Path>> {
+    let mut backends = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("from_row")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("backends") {
+                let list = backends.get_or_insert_with(Vec::new);
+                meta.parse_nested_meta(|backend| {
+                    list.push(backend.path);
+                    Ok(())
+                })
+            } else {
+                Err(meta.error("unsupported from_row option, expected `backends(...)`"))
+            }
+        })
+        .unwrap_or_else(|e| panic!("{}", e));
+    }
+    backends
+}
+
+/// The `FromRow` trait, `Row` and `Error` of an impl: named through `backend` when given,
+/// otherwise the names imported next to the derive.
+pub(crate) fn backend_names(backend: Option<&syn::Path>) -> (TokenStream, TokenStream, TokenStream) {
+    match backend {
+        Some(backend) => (
+            quote! { #backend::traits::FromRow },
+            quote! { #backend::Row },
+            quote! { #backend::Error },
+        ),
+        None => (quote! { FromRow }, quote! { Row }, quote! { Error }),
+    }
+}
+
+/// Returns whether the field is marked with `#[from_row(default)]`, i.e. it falls back
+/// to `Default::default()` when the row doesn't contain its column.
+pub(crate) fn default_on_missing(field: &syn::Field) -> bool {
+    let mut default = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("from_row")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("default") {
+                default = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported from_row option, expected `default`"))
+            }
+        })
+        .unwrap_or_else(|e| panic!("{}", e));
+    }
+    default
+}
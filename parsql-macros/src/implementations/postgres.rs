@@ -1,34 +1,179 @@
 use quote::quote;
 use syn::{Data, DeriveInput, Fields};
 
+use super::{backend_names, default_on_missing, included_read};
+use crate::rename_rule;
+
 /// Implements the FromRow trait for PostgreSQL database
 /// 
 /// # Arguments
 /// * `input` - TokenStream containing the struct definition
+/// * `backend` - Backend module the trait, `Row` and `Error` are named through,
+///   e.g. `parsql::tokio_postgres`; `None` uses the names imported next to the derive
 /// 
 /// # Returns
 /// * `TokenStream` - Generated implementation code
-pub fn generate_from_row(ast: &DeriveInput) -> proc_macro2::TokenStream {
+pub fn generate_from_row(ast: &DeriveInput, backend: Option<&syn::Path>) -> proc_macro2::TokenStream {
     let name = &ast.ident;
+    let (from_row, row, error) = backend_names(backend);
     
     let fields = match &ast.data {
-        Data::Struct(data) => match &data.fields {
-            Fields::Named(fields) => &fields.named,
-            _ => panic!("FromRow only supports structs with named fields"),
-        },
+        Data::Struct(data) => &data.fields,
         _ => panic!("FromRow only supports structs"),
     };
 
-    let field_names = fields.iter().map(|f| &f.ident);
-    let field_names_str = fields.iter().map(|f| f.ident.as_ref().unwrap().to_string());
+    let construct = match fields {
+        Fields::Named(fields) => {
+            let rule = rename_rule(ast);
+            let field_names = fields.named.iter().map(|f| &f.ident);
+            let values = fields.named.iter().map(|f| {
+                let column = rule.apply(&f.ident.as_ref().unwrap().to_string());
+                let read = read_column(f, quote!(#column));
+                if let Some(read) = included_read(ast, f, &from_row) {
+                    read
+                } else if default_on_missing(f) {
+                    quote! {
+                        if row.columns().iter().any(|c| c.name() == #column) {
+                            #read
+                        } else {
+                            Default::default()
+                        }
+                    }
+                } else {
+                    read
+                }
+            });
+            quote! {
+                Self {
+                    #(#field_names: #values),*
+                }
+            }
+        }
+        // Tuple struct'lar ve newtype'lar sütunlara sırayla eşlenir
+        Fields::Unnamed(fields) => {
+            let values = fields.unnamed.iter().enumerate().map(|(index, f)| {
+                let read = read_column(f, quote!(#index));
+                if default_on_missing(f) {
+                    quote! {
+                        if #index < row.len() {
+                            #read
+                        } else {
+                            Default::default()
+                        }
+                    }
+                } else {
+                    read
+                }
+            });
+            quote! {
+                Self(#(#values),*)
+            }
+        }
+        Fields::Unit => panic!("FromRow does not support unit structs"),
+    };
+
+    let positioned = match fields {
+        Fields::Named(fields) => positioned_read(ast, fields, &from_row, &row, &error),
+        _ => quote! {},
+    };
 
     quote! {
-        impl FromRow for #name {
-            fn from_row(row: &Row) -> Result<Self, Error> {
-                Ok(Self {
-                    #(#field_names: row.try_get(#field_names_str)?),*
-                })
+        impl #from_row for #name {
+            fn from_row(row: &#row) -> Result<Self, #error> {
+                Ok(#construct)
             }
+
+            #positioned
         }
     }
 }
+
+/// Generates `column_positions` and `from_row_at`, which read the fields of a named
+/// struct by the column positions resolved once per result set. A column missing from
+/// the result set is read by name, so the error (or `#[from_row(default)]`) is the same
+/// as with `from_row`.
+fn positioned_read(
+    ast: &DeriveInput,
+    fields: &syn::FieldsNamed,
+    from_row: &proc_macro2::TokenStream,
+    row: &proc_macro2::TokenStream,
+    error: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let rule = rename_rule(ast);
+    // Temel modelin alanları kendi FromRow implementasyonuyla okunur, konum almaz
+    let positioned = fields
+        .named
+        .iter()
+        .filter(|f| included_read(ast, f, from_row).is_none())
+        .collect::<Vec<_>>();
+    let columns = positioned
+        .iter()
+        .map(|f| rule.apply(&f.ident.as_ref().unwrap().to_string()))
+        .collect::<Vec<_>>();
+
+    let field_names = fields.named.iter().map(|f| &f.ident);
+    let values = fields.named.iter().map(|f| {
+        if let Some(read) = included_read(ast, f, from_row) {
+            return read;
+        }
+        let slot = positioned.iter().position(|p| p.ident == f.ident).unwrap();
+        let column = &columns[slot];
+        let read = read_column(f, quote!(index));
+        let missing = if default_on_missing(f) {
+            quote! { Default::default() }
+        } else {
+            read_column(f, quote!(#column))
+        };
+        quote! {
+            match positions.get(#slot).copied().flatten() {
+                Some(index) => #read,
+                None => #missing,
+            }
+        }
+    });
+
+    quote! {
+        fn column_positions(columns: &[&str]) -> Vec<Option<usize>> {
+            [#(#columns),*]
+                .iter()
+                .map(|field| columns.iter().position(|column| column == field))
+                .collect()
+        }
+
+        fn from_row_at(row: &#row, positions: &[Option<usize>]) -> Result<Self, #error> {
+            Ok(Self {
+                #(#field_names: #values),*
+            })
+        }
+    }
+}
+
+/// Reads the column of a field. Array columns are NULL when `array_agg` has no rows to
+/// aggregate, so `Vec<T>` fields read them as `Option<Vec<T>>` and fall back to an empty
+/// vector; `Option<Vec<T>>` keeps the NULL.
+fn read_column(field: &syn::Field, column: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let ty = &field.ty;
+    if is_array(ty) {
+        quote! { row.try_get::<_, Option<#ty>>(#column)?.unwrap_or_default() }
+    } else {
+        quote! { row.try_get(#column)? }
+    }
+}
+
+/// Returns whether the type is a `Vec<T>` read from an array column. `Vec<u8>` maps to
+/// `bytea` instead.
+fn is_array(ty: &syn::Type) -> bool {
+    let syn::Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if segment.ident == "Vec" => !matches!(
+            args.args.first(),
+            Some(syn::GenericArgument::Type(syn::Type::Path(inner))) if inner.path.is_ident("u8")
+        ),
+        _ => false,
+    }
+}
@@ -2,38 +2,146 @@ use quote::quote;
 use syn::{Data, DeriveInput, Fields};
 use proc_macro2::TokenStream;
 
+use super::{backend_names, default_on_missing, included_read};
+use crate::rename_rule;
+
 /// Implements the FromRow trait for SQLite database
 /// 
 /// # Arguments
 /// * `input` - TokenStream containing the struct definition
+/// * `backend` - Backend module the trait, `Row` and `Error` are named through,
+///   e.g. `parsql::sqlite`; `None` uses the names imported next to the derive
 /// 
 /// # Returns
 /// * `TokenStream` - Generated implementation code
-pub fn generate_from_row(input: &DeriveInput) -> TokenStream {
+pub fn generate_from_row(input: &DeriveInput, backend: Option<&syn::Path>) -> TokenStream {
     let name = &input.ident;
+    let (from_row, row, error) = backend_names(backend);
 
     let fields = match &input.data {
-        Data::Struct(data) => match &data.fields {
-            Fields::Named(fields) => fields,
-            _ => panic!("Only named fields are supported"),
-        },
+        Data::Struct(data) => &data.fields,
         _ => panic!("Only structs are supported"),
     };
 
-    let field_names = fields.named.iter().map(|f| f.ident.as_ref().unwrap());
-    let field_strings = fields
-        .named
-        .iter()
-        .map(|f| f.ident.as_ref().unwrap().to_string());
+    let construct = match fields {
+        Fields::Named(fields) => {
+            let rule = rename_rule(input);
+            let field_names = fields.named.iter().map(|f| f.ident.as_ref().unwrap());
+            let values = fields.named.iter().map(|f| {
+                let column = rule.apply(&f.ident.as_ref().unwrap().to_string());
+                if let Some(read) = included_read(input, f, &from_row) {
+                    read
+                } else if default_on_missing(f) {
+                    quote! {
+                        match row.get(#column) {
+                            Ok(value) => value,
+                            Err(#error::InvalidColumnName(_)) => Default::default(),
+                            Err(e) => return Err(e),
+                        }
+                    }
+                } else {
+                    quote! { row.get(#column)? }
+                }
+            });
+            quote! {
+                Self {
+                    #(#field_names: #values),*
+                }
+            }
+        }
+        // Tuple struct'lar ve newtype'lar sütunlara sırayla eşlenir
+        Fields::Unnamed(fields) => {
+            let values = fields.unnamed.iter().enumerate().map(|(index, f)| {
+                if default_on_missing(f) {
+                    quote! {
+                        match row.get(#index) {
+                            Ok(value) => value,
+                            Err(#error::InvalidColumnIndex(_)) => Default::default(),
+                            Err(e) => return Err(e),
+                        }
+                    }
+                } else {
+                    quote! { row.get(#index)? }
+                }
+            });
+            quote! {
+                Self(#(#values),*)
+            }
+        }
+        Fields::Unit => panic!("Unit structs are not supported"),
+    };
+
+    let positioned = match fields {
+        Fields::Named(fields) => positioned_read(input, fields, &from_row, &row, &error),
+        _ => quote! {},
+    };
 
     quote! {
-        impl FromRow for #name {
-            fn from_row(row: &Row) -> Result<Self, Error> {
-                Ok(Self {
-                    #(#field_names: row.get(#field_strings)?),*
-                })
+        impl #from_row for #name {
+            fn from_row(row: &#row) -> Result<Self, #error> {
+                Ok(#construct)
             }
+
+            #positioned
         }
     }
 }
 
+/// Generates `column_positions` and `from_row_at`, which read the fields of a named
+/// struct by the column positions resolved once per statement. Like `Row::get` with a
+/// name, columns are matched case-insensitively; a missing one is read by name, so the
+/// error (or `#[from_row(default)]`) is the same as with `from_row`.
+fn positioned_read(
+    input: &DeriveInput,
+    fields: &syn::FieldsNamed,
+    from_row: &TokenStream,
+    row: &TokenStream,
+    error: &TokenStream,
+) -> TokenStream {
+    let rule = rename_rule(input);
+    // Temel modelin alanları kendi FromRow implementasyonuyla okunur, konum almaz
+    let positioned = fields
+        .named
+        .iter()
+        .filter(|f| included_read(input, f, from_row).is_none())
+        .collect::<Vec<_>>();
+    let columns = positioned
+        .iter()
+        .map(|f| rule.apply(&f.ident.as_ref().unwrap().to_string()))
+        .collect::<Vec<_>>();
+
+    let field_names = fields.named.iter().map(|f| f.ident.as_ref().unwrap());
+    let values = fields.named.iter().map(|f| {
+        if let Some(read) = included_read(input, f, from_row) {
+            return read;
+        }
+        let slot = positioned.iter().position(|p| p.ident == f.ident).unwrap();
+        let column = &columns[slot];
+        let missing = if default_on_missing(f) {
+            quote! { Default::default() }
+        } else {
+            quote! { row.get(#column)? }
+        };
+        quote! {
+            match positions.get(#slot).copied().flatten() {
+                Some(index) => row.get(index)?,
+                None => #missing,
+            }
+        }
+    });
+
+    quote! {
+        fn column_positions(columns: &[&str]) -> Vec<Option<usize>> {
+            [#(#columns),*]
+                .iter()
+                .map(|field| columns.iter().position(|column| column.eq_ignore_ascii_case(field)))
+                .collect()
+        }
+
+        fn from_row_at(row: &#row, positions: &[Option<usize>]) -> Result<Self, #error> {
+            Ok(Self {
+                #(#field_names: #values),*
+            })
+        }
+    }
+}
@@ -1,22 +1,36 @@
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, Data, DeriveInput, Fields};
 use quote::quote;
+use parsql_core::audit_log::Operation;
+use crate::audit;
+use crate::audited;
+use crate::schema_check;
 use crate::query_builder;
+use crate::sql_params::param_fields;
+use crate::validate;
+use crate::{
+    default_sql, included_fields, is_option, partitioned_impl, query_metadata, query_registration, quote_column, quote_identifiers, quote_name,
+    reject_registration, rename_rule, spliced_query, table_name, Splice, SqlParamCounter,
+};
 
 /// Implements the Insertable derive macro.
 pub(crate) fn derive_insertable_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = &input.ident;
+    let (impl_generics, ty_generics, generic_where) = input.generics.split_for_impl();
+
+    // Ham SQL içeren öznitelikleri derleme zamanında denetle
+    if let Some(error) = audit::audit_attributes(&input) {
+        return error;
+    }
+
+    // schema-check açıksa tablo ve sütunlar şema görüntüsüyle karşılaştırılır
+    if let Some(error) = schema_check::check(&input, schema_check::Statement::Insert) {
+        return error;
+    }
 
     // Extract table name and columns
-    let table = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("table"))
-        .expect("Missing `#[table = \"...\"]` attribute")
-        .parse_args::<syn::LitStr>()
-        .expect("Expected a string literal for table name")
-        .value();
+    let table = table_name(&input);
 
     // Extract returning column if specified
     let returning_column = input
@@ -31,11 +45,7 @@ pub(crate) fn derive_insertable_impl(input: TokenStream) -> TokenStream {
 
     let fields = if let Data::Struct(data) = &input.data {
         if let Fields::Named(fields) = &data.fields {
-            fields
-                .named
-                .iter()
-                .map(|f| f.ident.as_ref().unwrap().to_string())
-                .collect::<Vec<_>>()
+            fields.named.iter().collect::<Vec<_>>()
         } else {
             panic!("Insertable can only be derived for structs with named fields");
         }
@@ -43,57 +53,138 @@ pub(crate) fn derive_insertable_impl(input: TokenStream) -> TokenStream {
         panic!("Insertable can only be derived for structs");
     };
 
-    let column_names = fields.iter().map(|f| f.as_str()).collect::<Vec<_>>();
+    // INSERT ... SELECT için kaynak sorgu tipi
+    let insert_from = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("insert_from"))
+        .map(|attr| {
+            attr.parse_args::<syn::Path>()
+                .expect("Expected a type path for insert_from, e.g. #[insert_from(SelectActiveUsers)]")
+        });
+
+    // Üst kaydın anahtarını taşıyan alan (persist_graph ile birlikte kullanılır)
+    let parent_key = parent_key_impl(&input);
+
+    // #[partitioned] aylık bölümleri ensure_monthly_partition ile oluşturulabilen tabloyu işaretler
+    let partitioned = partitioned_impl(&input, &table);
+
+    // #[audited] yazımı denetim kaydıyla aynı ifadede yapar; RETURNING sarmalayıcıya kalır
+    let audit_log = audited::audit_log(&input);
+
+    if let Some(source) = insert_from {
+        if on_conflict_do_nothing(&input, false).is_some() {
+            panic!("on_conflict_do_nothing cannot be combined with insert_from");
+        }
+        reject_registration(&input, "insert_from");
+        let mut expanded = proc_macro2::TokenStream::from(derive_insert_from(struct_name, &input, &table, &source, returning_column));
+        expanded.extend(parent_key);
+        expanded.extend(partitioned);
+        return TokenStream::from(expanded);
+    }
+
+    // #[include_fields] temel modelinin sütunları ve değerleri kendi alanlarından sonra eklenir
+    let included = included_fields(&input);
+    let fields = fields
+        .into_iter()
+        .filter(|f| !matches!(&included, Some((_, field)) if f.ident.as_ref().unwrap() == field))
+        .collect::<Vec<_>>();
+
+    let names = fields
+        .iter()
+        .map(|f| f.ident.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+    // #[rename_all] ile sütun adları alan adlarından türetilir
+    let rule = rename_rule(&input);
+    let quote = quote_identifiers(&input);
+    let column_list = names
+        .iter()
+        .map(|f| quote_column(&rule.apply(f), quote))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // #[default_sql] alanlarında yer tutucu yerine SQL ifadesi yazılır; Option alanlarda
+    // ifade yalnızca değer None olduğunda devreye girer
+    let mut param_counter = SqlParamCounter::new();
+    let values = fields
+        .iter()
+        .map(|field| match default_sql(field) {
+            Some(expr) if is_option(&field.ty) => {
+                format!("COALESCE({}, {})", param_counter.next_placeholder(), expr)
+            }
+            Some(expr) => expr,
+            None => param_counter.next_placeholder(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // Var olan kayıtlar #[on_conflict_do_nothing] ile hata vermeden atlanır
+    let conflict_clause = on_conflict_do_nothing(&input, quote);
+
+    // Temel model sütunlarının ve yer tutucularının yazılacağı konumlar
+    let mut splice_at = (0, 0);
 
     let safe_query = if cfg!(any(feature = "postgres", feature = "tokio-postgres", feature = "deadpool-postgres")) {
         // PostgreSQL için sorgu oluştur
-        let mut builder = query_builder::SafeQueryBuilder::new();
+        let mut builder = query_builder::SafeQueryBuilder::new().quoting(quote);
         
         builder.add_keyword("INSERT INTO");
         builder.add_identifier(&table);
         builder.add_keyword("(");
-        builder.add_comma_list(&column_names);
+        builder.query.push_str(&column_list);
+        splice_at.0 = builder.query.len();
         builder.add_keyword(")");
         builder.add_keyword("VALUES");
         builder.add_keyword("(");
         
-        let placeholders: Vec<String> = (1..=column_names.len())
-            .map(|i| format!("${}", i))
-            .collect();
-        builder.query.push_str(&placeholders.join(", "));
+        builder.query.push_str(&values);
+        splice_at.1 = builder.query.len();
         
         builder.add_keyword(")");
 
-        if let Some(ref column) = returning_column {
-            builder.add_keyword("RETURNING");
-            builder.add_identifier(column);
+        if let Some(ref clause) = conflict_clause {
+            builder.add_raw(clause);
         }
 
-        builder.build()
+        match &audit_log {
+            Some(log) => {
+                let returning = returning_column.as_deref().map(|column| quote_name(column, quote));
+                let target = quote_name(&table, quote);
+                log.wrap(Operation::Insert, &builder.build(), &target, &[], returning.as_deref())
+            }
+            None => {
+                if let Some(ref column) = returning_column {
+                    builder.add_keyword("RETURNING");
+                    builder.add_identifier(column);
+                }
+                builder.build()
+            }
+        }
     } else if cfg!(feature = "sqlite") {
         // SQLite için sorgu oluştur
-        let mut builder = query_builder::SafeQueryBuilder::new();
+        let mut builder = query_builder::SafeQueryBuilder::new().quoting(quote);
         
         builder.add_keyword("INSERT INTO");
         builder.add_identifier(&table);
         builder.add_keyword("(");
-        builder.add_comma_list(&column_names);
+        builder.query.push_str(&column_list);
+        splice_at.0 = builder.query.len();
         builder.add_keyword(")");
         builder.add_keyword("VALUES");
         builder.add_keyword("(");
         
-        let placeholders: Vec<String> = (1..=column_names.len())
-            .map(|i| format!("?{}", i))
-            .collect();
-        builder.query.push_str(&placeholders.join(", "));
+        builder.query.push_str(&values);
+        splice_at.1 = builder.query.len();
         
         builder.add_keyword(")");
 
+        if let Some(ref clause) = conflict_clause {
+            builder.add_raw(clause);
+        }
+
+        // SQLite 3.35'ten beri RETURNING destekler; ikinci bir ifade `query_row` ile çalışmaz
         if let Some(ref column) = returning_column {
-            builder.add_keyword(";");
-            builder.add_keyword("SELECT");
-            builder.add_keyword("last_insert_rowid()");
-            builder.add_keyword("AS");
+            builder.add_keyword("RETURNING");
             builder.add_identifier(column);
         }
 
@@ -102,11 +193,193 @@ pub(crate) fn derive_insertable_impl(input: TokenStream) -> TokenStream {
         panic!("At least one database feature must be enabled (postgres or sqlite)")
     };
 
+    let registration = query_registration(&input);
+    let validation = validate::validation_method(&input);
+
+    if let Some((base, _)) = &included {
+        reject_registration(&input, "include_fields");
+        let separator = if names.is_empty() { "" } else { ", " };
+        let query = spliced_query(
+            base,
+            &safe_query,
+            &[
+                (splice_at.0, separator, Splice::Columns),
+                (splice_at.1, separator, Splice::Placeholders(param_counter.count() + 1)),
+            ],
+        );
+        let metadata = query_metadata(&table, None, None);
+        let expanded = quote! {
+            impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+                fn query() -> &'static str {
+                    #query
+                }
+
+                #metadata
+
+                #validation
+            }
+
+            #parent_key
+
+            #partitioned
+        };
+
+        return TokenStream::from(expanded);
+    }
+
+    let param_count = param_counter.count();
+    let param_names = Some(param_fields(&input)).filter(|names| names.len() == param_count);
+    let metadata = query_metadata(&table, Some(quote! { #param_count }), param_names.as_deref());
+
+    let expanded = quote! {
+        impl #impl_generics #struct_name #ty_generics #generic_where {
+            /// The generated SQL statement, available in `const` contexts.
+            pub const SQL: &'static str = #safe_query;
+        }
+
+        #registration
+
+        impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+            fn query() -> &'static str {
+                Self::SQL
+            }
+
+            #metadata
+
+            #validation
+        }
+
+        #parent_key
+
+        #partitioned
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Reads `#[on_conflict_do_nothing]` or `#[on_conflict_do_nothing("email")]`, returning
+/// the `ON CONFLICT [(columns)] DO NOTHING` clause of the INSERT.
+fn on_conflict_do_nothing(input: &DeriveInput, quote: bool) -> Option<String> {
+    let attr = input.attrs.iter().find(|attr| attr.path().is_ident("on_conflict_do_nothing"))?;
+    if matches!(attr.meta, syn::Meta::Path(_)) {
+        return Some("ON CONFLICT DO NOTHING".to_string());
+    }
+
+    let target = attr
+        .parse_args::<syn::LitStr>()
+        .expect("Expected a string literal for on_conflict_do_nothing, e.g. #[on_conflict_do_nothing(\"email\")]")
+        .value();
+    let columns = target
+        .split(',')
+        .map(str::trim)
+        .map(|column| {
+            if column.is_empty() || !column.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                panic!("on_conflict_do_nothing expects plain column names, found `{}`", column);
+            }
+            quote_column(column, quote)
+        })
+        .collect::<Vec<_>>();
+    Some(format!("ON CONFLICT ({}) DO NOTHING", columns.join(", ")))
+}
+
+/// Generates the `ParentKey` impl for `#[parent_key("field")]`, letting `persist_graph`
+/// write the parent's returned id into the named field before the child is inserted.
+fn parent_key_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
+    let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("parent_key")) else {
+        return proc_macro2::TokenStream::new();
+    };
+
+    let field_name = attr
+        .parse_args::<syn::LitStr>()
+        .expect("Expected a string literal for parent_key, e.g. #[parent_key(\"user_id\")]")
+        .value();
+
+    let field = match &input.data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .find(|f| f.ident.as_ref().is_some_and(|ident| ident == field_name.as_str())),
+        _ => None,
+    }
+    .unwrap_or_else(|| panic!("parent_key field `{}` not found in the struct", field_name));
+
+    let struct_name = &input.ident;
+    let ident = field.ident.as_ref().unwrap();
+    let ty = &field.ty;
+
+    quote! {
+        impl ParentKey for #struct_name {
+            type Key = #ty;
+
+            fn set_parent_key(&mut self, key: Self::Key) {
+                self.#ident = key;
+            }
+        }
+    }
+}
+
+/// Generates `INSERT INTO table (columns) <source query>` for `#[insert_from(Source)]`.
+///
+/// The SELECT part and its parameters come from the source query type at runtime,
+/// the optional column list from the `#[columns("...")]` attribute.
+fn derive_insert_from(
+    struct_name: &syn::Ident,
+    input: &DeriveInput,
+    table: &str,
+    source: &syn::Path,
+    returning_column: Option<String>,
+) -> TokenStream {
+    let (impl_generics, ty_generics, generic_where) = input.generics.split_for_impl();
+    let columns = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("columns"))
+        .map(|attr| {
+            attr.parse_args::<syn::LitStr>()
+                .expect("Expected a string literal for columns")
+                .value()
+        });
+
+    let quote = quote_identifiers(input);
+    let mut builder = query_builder::SafeQueryBuilder::new().quoting(quote);
+    builder.add_keyword("INSERT INTO");
+    builder.add_identifier(table);
+
+    if let Some(columns) = &columns {
+        let column_names = columns
+            .split(',')
+            .map(|c| c.trim())
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<_>>();
+        builder.add_keyword("(");
+        builder.add_comma_list(&column_names);
+        builder.add_keyword(")");
+    }
+
+    let prefix = builder.build();
+
+    let suffix = match returning_column {
+        Some(column) => {
+            let mut builder = query_builder::SafeQueryBuilder::new().quoting(quote);
+            builder.add_keyword("RETURNING");
+            builder.add_identifier(&column);
+            format!(" {}", builder.build())
+        }
+        None => String::new(),
+    };
+
+    let metadata = query_metadata(table, Some(quote! { <#source as SqlQuery>::param_count() }), None);
+
     let expanded = quote! {
-        impl SqlQuery for #struct_name {
-            fn query() -> String {
-                #safe_query.to_string()
+        impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+            fn query() -> &'static str {
+                static QUERY: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+                QUERY.get_or_init(|| {
+                    format!("{} {}{}", #prefix, <#source as SqlQuery>::query(), #suffix)
+                })
             }
+
+            #metadata
         }
     };
 
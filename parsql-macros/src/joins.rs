@@ -0,0 +1,215 @@
+use proc_macro::TokenStream;
+use syn::{punctuated::Punctuated, DeriveInput, Token};
+
+use crate::table_name;
+
+/// Attributes of a joined query whose qualified columns (`p.created_at`) must name one of
+/// the joined tables.
+const QUALIFIED_ATTRIBUTES: &[&str] = &[
+    "join",
+    "select",
+    "select_extra",
+    "where_clause",
+    "where_if",
+    "group_by",
+    "having",
+    "order_by",
+    "sortable",
+];
+
+/// Words that end a table reference, so they are never read as its alias.
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "ON", "USING", "WHERE", "JOIN", "LEFT", "RIGHT", "INNER", "OUTER", "FULL", "CROSS", "NATURAL", "GROUP", "ORDER",
+    "HAVING", "LIMIT", "OFFSET", "UNION", "WINDOW",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Lexeme {
+    /// An identifier, lowercased unless it was quoted
+    Word(String, bool),
+    Dot,
+    Open,
+    Close,
+    Other,
+}
+
+/// Splits a fragment into identifiers and punctuation, skipping string literals, numbers
+/// and placeholders.
+fn tokenize(fragment: &str) -> Vec<Lexeme> {
+    let mut tokens = Vec::new();
+    let mut chars = fragment.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => while chars.next().is_some_and(|d| d != '\'') {},
+            '"' => {
+                let mut word = String::new();
+                while let Some(d) = chars.next().filter(|d| *d != '"') {
+                    word.push(d);
+                }
+                tokens.push(Lexeme::Word(word, true));
+            }
+            '$' | '?' => while chars.next_if(|d| d.is_alphanumeric() || *d == '_').is_some() {},
+            '.' => tokens.push(Lexeme::Dot),
+            '(' => tokens.push(Lexeme::Open),
+            ')' => tokens.push(Lexeme::Close),
+            c if c.is_ascii_digit() => {
+                while chars.next_if(|d| d.is_alphanumeric() || *d == '.' || *d == '_').is_some() {}
+                tokens.push(Lexeme::Other);
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_lowercase().to_string();
+                while let Some(d) = chars.next_if(|d| d.is_alphanumeric() || *d == '_') {
+                    word.extend(d.to_lowercase());
+                }
+                tokens.push(Lexeme::Word(word, false));
+            }
+            c if c.is_whitespace() => {}
+            _ => tokens.push(Lexeme::Other),
+        }
+    }
+    tokens
+}
+
+fn is_keyword(token: Option<&Lexeme>, keywords: &[&str]) -> bool {
+    matches!(token, Some(Lexeme::Word(word, false)) if keywords.iter().any(|k| k.eq_ignore_ascii_case(word)))
+}
+
+/// Names the tables after `FROM` and `JOIN` in a fragment can be referred to by: their
+/// alias, or their name when they have none.
+///
+/// `LEFT JOIN posts p ON ...` declares `p`, `JOIN comments ON ...` declares `comments`.
+pub(crate) fn declared_tables(fragment: &str) -> Vec<String> {
+    let tokens = tokenize(fragment);
+    let mut declared = Vec::new();
+    for i in 0..tokens.len() {
+        if !is_keyword(tokens.get(i), &["FROM", "JOIN"]) {
+            continue;
+        }
+        let mut at = i + 1;
+        if is_keyword(tokens.get(at), &["LATERAL", "ONLY"]) {
+            at += 1;
+        }
+        let mut table = None;
+        if tokens.get(at) == Some(&Lexeme::Open) {
+            // Alt sorgunun yalnızca takma adı dışarıdan görülür
+            let mut depth = 0;
+            while let Some(token) = tokens.get(at) {
+                at += 1;
+                match token {
+                    Lexeme::Open => depth += 1,
+                    Lexeme::Close if depth == 1 => break,
+                    Lexeme::Close => depth -= 1,
+                    _ => {}
+                }
+            }
+        } else {
+            // Şema önekli adlarda (public.posts) tablo son parçadır
+            while let Some(Lexeme::Word(word, _)) = tokens.get(at) {
+                table = Some(word.clone());
+                at += 1;
+                if tokens.get(at) != Some(&Lexeme::Dot) {
+                    break;
+                }
+                at += 1;
+            }
+        }
+        if is_keyword(tokens.get(at), &["AS"]) {
+            at += 1;
+        }
+        let alias = match tokens.get(at) {
+            Some(Lexeme::Word(word, _)) if !is_keyword(tokens.get(at), CLAUSE_KEYWORDS) => Some(word.clone()),
+            _ => None,
+        };
+        declared.extend(alias.or(table));
+    }
+    declared
+}
+
+/// The qualifiers of the columns a fragment refers to, e.g. `p` for `p.created_at` and
+/// `p.*`. Schema-qualified function calls such as `pg_catalog.lower(...)` are not columns.
+pub(crate) fn column_qualifiers(fragment: &str) -> Vec<String> {
+    let tokens = tokenize(fragment);
+    let mut qualifiers = Vec::new();
+    for (i, token) in tokens.iter().enumerate().skip(1) {
+        if *token != Lexeme::Dot {
+            continue;
+        }
+        let Some(Lexeme::Word(qualifier, _)) = tokens.get(i - 1) else {
+            continue;
+        };
+        // a.b.c'de sütunu b niteler; a şema önekidir
+        if matches!(tokens.get(i + 2), Some(Lexeme::Dot | Lexeme::Open)) {
+            continue;
+        }
+        if matches!(tokens.get(i + 1), Some(Lexeme::Word(..) | Lexeme::Other)) {
+            qualifiers.push(qualifier.clone());
+        }
+    }
+    qualifiers
+}
+
+/// Checks the qualified columns of a `#[join]` query against the tables it declares.
+///
+/// Returns a `compile_error!` pointing at the fragment with the first unknown qualifier.
+pub(crate) fn check_qualified_columns(input: &DeriveInput) -> Option<TokenStream> {
+    verify(input).err().map(|error| error.to_compile_error().into())
+}
+
+/// A qualifier must be the struct's table, a table or alias of a `#[join]`, or a table
+/// declared by a subquery of the same fragment. Queries without a join are not checked.
+pub(crate) fn verify(input: &DeriveInput) -> Result<(), syn::Error> {
+    let attrs: Vec<&syn::Attribute> = input
+        .attrs
+        .iter()
+        .filter(|attr| QUALIFIED_ATTRIBUTES.iter().any(|name| attr.path().is_ident(name)))
+        .collect();
+    if !attrs.iter().any(|attr| attr.path().is_ident("join")) {
+        return Ok(());
+    }
+
+    let fragments: Vec<(&syn::Attribute, syn::LitStr)> = attrs
+        .iter()
+        .flat_map(|attr| {
+            // where_if'in koşulu ilk string'dir, `when` ifadesi Rust kodudur
+            let fragments: Vec<syn::LitStr> = if attr.path().is_ident("where_if") {
+                attr.parse_args_with(|stream: syn::parse::ParseStream| {
+                    let condition = stream.parse()?;
+                    stream.parse::<proc_macro2::TokenStream>()?;
+                    Ok(vec![condition])
+                })
+                .unwrap_or_default()
+            } else {
+                attr.parse_args_with(Punctuated::<syn::LitStr, Token![,]>::parse_terminated)
+                    .map(|fragments| fragments.into_iter().collect())
+                    .unwrap_or_default()
+            };
+            fragments.into_iter().map(move |fragment| (*attr, fragment))
+        })
+        .collect();
+
+    let mut known = declared_tables(&format!("FROM {}", table_name(input)));
+    known.extend(
+        fragments
+            .iter()
+            .filter(|(attr, _)| attr.path().is_ident("join"))
+            .flat_map(|(_, fragment)| declared_tables(&fragment.value())),
+    );
+
+    for (attr, fragment) in &fragments {
+        let sql = fragment.value();
+        let local = declared_tables(&sql);
+        let unknown = column_qualifiers(&sql)
+            .into_iter()
+            .find(|qualifier| !known.contains(qualifier) && !local.contains(qualifier));
+        if let Some(unknown) = unknown {
+            let message = format!(
+                "unknown table or alias `{}` in `#[{}]`; the query declares {}",
+                unknown,
+                attr.path().get_ident().map(|ident| ident.to_string()).unwrap_or_default(),
+                known.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>().join(", ")
+            );
+            return Err(syn::Error::new_spanned(fragment, message));
+        }
+    }
+    Ok(())
+}
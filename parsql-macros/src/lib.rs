@@ -19,28 +19,115 @@
 //! - `SqlParams`: Generates parameter handling code
 //! - `UpdateParams`: Generates parameter handling code for UPDATE operations
 //! - `FromRow`: Generates code for converting database rows to Rust structs
+//! - `Repository`: Binds the query types of an entity to the backend's generic `Repository`
+//! - `Paginated`: Generates a `<Name>Page` companion type fetched with `fetch_page`
+//! - `Patch`: Generates a `<Name>Patch` type of optional fields written with `update_patch`
+//! - `Callable`: Generates `CALL` / `SELECT` statements invoking a stored procedure or function
+//! - `Entity`: Derives the standard traits of a table's model at once, with companion
+//!   `<Name>Insert`, `<Name>Update` and `<Name>Delete` types for the writes
+//!
+//! `Insertable`, `Queryable`, `Updateable` and `Deletable` also give the struct an
+//! associated `const SQL: &str` holding the generated statement, so snapshot tests can
+//! `assert_eq!(GetUser::SQL, "SELECT ...")`. Statements assembled at runtime
+//! (`insert_from`, `union`) only provide `query()`.
+//!
+//! The `SqlQuery` trait these derives implement lives in `parsql-core`, which has no
+//! database driver dependency. Tools that only need the generated SQL and the fields
+//! bound to its placeholders (`T::query()`, `T::param_names()`) can depend on
+//! `parsql-macros` and `parsql-core` alone, choosing the placeholder style with the
+//! `sqlite` or `postgres` feature.
+//!
+//! With `#[register_query]` the constant is also submitted to the backend's query
+//! registry, so `registry::prepare_all` can prepare every statement at startup. The
+//! generated code refers to `registry`, which is imported next to the traits.
+//!
+//! The `schema-check` feature checks the tables, columns and field types of these four
+//! derives against a `schema.json` snapshot (written by the backends' `schema::dump`)
+//! at compile time. The file is read from the crate's manifest directory, or from the
+//! path in `PARSQL_SCHEMA`; without it nothing is checked.
 
-use std::env;
 
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput};
 
-mod from_row;
+mod audit;
+mod audited;
+mod callable;
 mod deletable;
+mod entity;
 mod insertable;
+mod joins;
 mod queryable;
+mod paginated;
+mod patch;
+mod repository;
+mod schema_check;
 mod query_builder;
 mod sql_params;
 mod numbering_test;
 mod utils;
 mod update_params;
 mod updateable;
+mod validate;
 
 
+#[cfg(test)]
 #[path = "tests/param_numbering_tests.rs"]
 mod param_numbering_tests;
+#[cfg(test)]
 #[path = "tests/sql_param_counter_tests.rs"]
 mod sql_param_counter_tests;
+#[cfg(test)]
+#[path = "tests/audit_tests.rs"]
+mod audit_tests;
+#[cfg(test)]
+#[path = "tests/naming_tests.rs"]
+mod naming_tests;
+#[cfg(test)]
+#[path = "tests/partition_tests.rs"]
+mod partition_tests;
+#[cfg(test)]
+#[path = "tests/case_insensitive_tests.rs"]
+mod case_insensitive_tests;
+#[cfg(test)]
+#[path = "tests/sql_with_tests.rs"]
+mod sql_with_tests;
+#[cfg(test)]
+#[path = "tests/validate_tests.rs"]
+mod validate_tests;
+#[cfg(test)]
+#[path = "tests/from_row_tests.rs"]
+mod from_row_tests;
+#[cfg(test)]
+#[path = "tests/include_fields_tests.rs"]
+mod include_fields_tests;
+#[cfg(test)]
+#[path = "tests/where_if_tests.rs"]
+mod where_if_tests;
+#[cfg(test)]
+#[path = "tests/require_order_tests.rs"]
+mod require_order_tests;
+#[cfg(test)]
+#[path = "tests/fts_tests.rs"]
+mod fts_tests;
+#[cfg(test)]
+#[path = "tests/select_extra_tests.rs"]
+mod select_extra_tests;
+#[cfg(test)]
+#[path = "tests/param_names_tests.rs"]
+mod param_names_tests;
+#[cfg(test)]
+#[path = "tests/schema_check_tests.rs"]
+mod schema_check_tests;
+#[cfg(test)]
+#[path = "tests/join_tests.rs"]
+mod join_tests;
+#[cfg(test)]
+#[path = "tests/audited_tests.rs"]
+mod audited_tests;
+#[cfg(test)]
+#[path = "tests/union_tests.rs"]
+mod union_tests;
 
 mod implementations;
 
@@ -49,57 +136,217 @@ pub(crate) use utils::*;
 /// Derive macro for generating UPDATE queries.
 /// 
 /// # Attributes
-/// - `table`: The name of the table to update
+/// - `table`: The name of the table to update; defaults to the snake_case plural of the
+///   struct name (`User` -> `users`)
 /// - `where_clause`: The WHERE clause for the UPDATE statement
 /// - `update`: The columns to update
-#[proc_macro_derive(Updateable, attributes(table, where_clause, update))]
+/// - `update_expr`: Assignments with SQL expressions, e.g.
+///   `#[update_expr("view_count = view_count + $", "last_seen = NOW()")]` (optional).
+///   Their placeholders are numbered after the `update` columns.
+/// - `from`: Other tables the WHERE clause joins with, `UPDATE ... FROM`, e.g.
+///   `#[from("orders o")]` (optional). Placeholders in it are numbered between the SET
+///   list and the WHERE clause.
+/// - `rename_all`: Column naming convention of the fields, `"camelCase"`, `"PascalCase"`
+///   or `"snake_case"` (optional). `update` may then list field or column names.
+/// - `no_quote`: Writes the table and column names as given (optional). By default a
+///   reserved word such as `order` is double-quoted, and so is a mixed-case column name
+///   (`"userName"`); a mixed-case table name is written as given.
+/// - `partition_by`: Partition key of a partitioned table, e.g. `#[partition_by("created_at")]`
+///   (optional). A WHERE clause that doesn't filter on it gets `AND created_at = $`, bound
+///   to the field of that name; without such a field the derive warns at compile time.
+/// - `register_query`: Adds the statement to the backend's query registry, checked at
+///   startup by `registry::prepare_all` (optional)
+/// - `allow_raw_sql`: Disables the SQL injection audit of the raw attribute strings (optional)
+/// - `validate`: Checks run before the UPDATE is sent, see `Insertable` (optional)
+/// - `include_fields`: Base model whose columns are appended to the SET list, see
+///   `Insertable` (optional). They are numbered after the WHERE placeholders, and
+///   `UpdateParams` binds them last.
+/// - `audited`: Records each updated row in the audit table, see `Insertable` (optional).
+///   The JSON holds the new values of the columns the UPDATE sets.
+/// - `case_insensitive` (field): Comparisons of the field's column with a placeholder in
+///   the WHERE clause ignore case, `LOWER(email) = LOWER($1)` (optional). `=`, `<>`, `!=`
+///   and `LIKE` are rewritten; an index on `LOWER(email)` keeps the lookup fast.
+/// - `citext` (field): Like `case_insensitive` for a `citext` column, compared as
+///   `email = $1::citext` so that its own index applies (optional, PostgreSQL only)
+#[proc_macro_derive(Updateable, attributes(table, where_clause, update, update_expr, from, rename_all, no_quote, partition_by, register_query, allow_raw_sql, validate, include_fields, audited, case_insensitive, citext))]
 pub fn derive_updateable(input: TokenStream) -> TokenStream {
     // Let's add special checks for secure parameter usage
-    updateable::derive_updateable_impl(input)
+    schema_check::track(updateable::derive_updateable_impl(input))
 }
 
 /// Derive macro for generating INSERT queries.
 /// 
 /// # Attributes
-/// - `table`: The name of the table to insert into
+/// - `table`: The name of the table to insert into; inferred from the struct name when omitted
 /// - `returning`: The column to return after insert (optional)
-#[proc_macro_derive(Insertable, attributes(table, returning, sql_type))]
+/// - `on_conflict_do_nothing`: Skips rows that already exist instead of failing, as
+///   `ON CONFLICT DO NOTHING` or, with a target, `#[on_conflict_do_nothing("email")]`
+///   (optional). Read the key with the backend's `insert_returning_opt`, which returns
+///   `None` for a skipped row.
+/// - `insert_from`: Source query type for `INSERT ... SELECT` (optional).
+///   The struct must hold a field of that type, which provides the parameters.
+/// - `columns`: Target column list used with `insert_from` (optional)
+/// - `parent_key`: Field that receives the parent's id when the struct is inserted as a
+///   child by `persist_graph` (optional). Implements the backend's `ParentKey` trait.
+/// - `default_sql`: Field attribute with an SQL expression such as `#[default_sql("now()")]`
+///   that the INSERT writes instead of a placeholder (optional). On an `Option` field the
+///   expression is used only when the value is `None`, otherwise the field is never bound.
+/// - `rename_all`: Column naming convention of the fields, e.g. `#[rename_all("camelCase")]`
+///   inserts `user_name` into the column `"userName"` (optional)
+/// - `no_quote`: Leaves reserved table and column names and mixed-case column names
+///   unquoted, see `Updateable` (optional)
+/// - `register_query`: Adds the statement to the query registry (optional, not with `insert_from`)
+/// - `allow_raw_sql`: Disables the SQL injection audit of the raw attribute strings (optional)
+/// - `validate`: Field rules checked before the INSERT is sent (optional):
+///   `#[validate(email)]`, `non_empty`, `length(min = 1, max = 64)`,
+///   `range(min = 0, max = 150)` and `custom = path::to::fn` taking `&T` and returning
+///   `Result<(), String>`. A bare `#[validate]` on the struct also calls its `Validate`
+///   impl. A failed check is returned as `ValidationError`, which the generated code
+///   expects in scope next to the traits.
+/// - `include_fields`: Base model sharing its columns, e.g. `#[include_fields(AuditColumns)]`
+///   (optional). The struct holds a field of that type; the base derives `FromRow` (whose
+///   `COLUMNS` const lists them) and `SqlParams`. Its columns and values follow the struct's
+///   own. The statement is then built at runtime, so there is no `SQL` const.
+/// - `audited`: Writes a row into `audit_log` in the same statement as the INSERT
+///   (optional, PostgreSQL only): the table, `INSERT`, the key, the new row as JSON and
+///   the acting user of `with_rls_user`. `#[audited(table = "...", key = "...", actor = "...")]`
+///   names another audit table, key column (default `id`) or setting for the actor. The
+///   INSERT returns the `returning` column, or the key without one. Not with `insert_from`
+///   or `include_fields`; the table is `parsql_core::audit_log::CREATE_TABLE`.
+/// - `partitioned`: The table is partitioned by range on a column, e.g.
+///   `#[partitioned(by = "range(created_at)")]` (optional, PostgreSQL only). Implements the
+///   backend's `Partitioned` trait, so `ensure_monthly_partition::<T>` can create the
+///   partition of a month before its rows are inserted.
+#[proc_macro_derive(Insertable, attributes(table, returning, on_conflict_do_nothing, sql_type, insert_from, columns, parent_key, default_sql, rename_all, no_quote, register_query, allow_raw_sql, validate, include_fields, audited, partitioned))]
 pub fn derive_insertable(input: TokenStream) -> TokenStream {
-    insertable::derive_insertable_impl(input)
+    schema_check::track(insertable::derive_insertable_impl(input))
 }
 
 /// Derive macro for generating SELECT queries.
 /// 
 /// # Attributes
-/// - `table`: The name of the table to select from
+/// - `table`: The name of the table to select from; inferred from the struct name when omitted
 /// - `where_clause`: The WHERE clause for the SELECT statement
+/// - `where_if`: Optional condition and the expression deciding whether it applies,
+///   e.g. `#[where_if("state = $", when = "state.is_some()")]` (repeatable). The fields
+///   are in scope by reference. The condition is written with a flag parameter, so the
+///   statement is the same for every value and only the active conditions filter.
 /// - `select`: The columns to select (optional)
-/// - `join`: JOIN clauses (optional)
+/// - `select_extra`: Computed expressions appended to the select list, e.g.
+///   `#[select_extra("COUNT(*) OVER() AS total_rows")]` (optional, repeatable). The list
+///   is still built from the fields, without the fields named like an expression's alias,
+///   which are read from the expression instead.
+/// - `join`: JOIN clauses (optional, repeatable). Qualified columns of the other clauses,
+///   such as `p.created_at` in `order_by`, must name the struct's table or a joined table
+///   by its alias; an unknown qualifier is a compile error.
 /// - `group_by`: GROUP BY clause (optional)
 /// - `order_by`: ORDER BY clause (optional)
 /// - `having`: HAVING clause (optional)
-/// - `limit`: LIMIT clause (optional)
-/// - `offset`: OFFSET clause (optional)
-#[proc_macro_derive(Queryable, attributes(table, where_clause, select, join, group_by, order_by, having, limit, offset))]
+/// - `limit`: LIMIT clause (optional), an integer or a placeholder such as `"$page_size"`.
+///   Without it, `query_single()` (used by `fetch` and `select`) ends with `LIMIT 2`, which
+///   bounds the work while a query matching more than one row still fails.
+/// - `offset`: OFFSET clause (optional), an integer or a placeholder such as `"$skip"`
+/// - `require_order`: Rejects a `limit` / `offset` without `order_by`, whose pages would
+///   come back in no particular order (optional)
+/// - `union` / `union_all`: Query type appended with `UNION` / `UNION ALL` (optional).
+///   The struct must hold a field of that type; its parameters are numbered after this query's.
+///   Both queries are read from parenthesized subqueries and keep their own clauses; `order_by`, `limit`
+///   and `offset` apply to the combined result.
+/// - `rename_all`: Column naming convention used for the default select list (optional)
+/// - `no_quote`: Leaves the table and default select list unquoted, see `Updateable` (optional)
+/// - `partition_by`: Partition key added to the WHERE clause like on `Updateable` (optional)
+/// - `register_query`: Adds the statement to the query registry (optional, not with `union`)
+/// - `sortable`: Columns the backend's `fetch_first` / `fetch_last` may order by,
+///   e.g. `"created_at, id"` (optional)
+/// - `allow_raw_sql`: Disables the SQL injection audit of the raw attribute strings (optional)
+/// - `include_fields`: Base model whose columns follow the default select list, see
+///   `Insertable` (optional). Clauses can only bind the struct's own fields.
+/// - `materialized_view`: Reads from the given materialized view instead of `table` and
+///   implements the backend's `MaterializedView` trait, so the view can be refreshed with
+///   `refresh_materialized_view::<T>` (optional, PostgreSQL only)
+/// - `fts`: Full-text search, e.g. `#[fts("search_vector", query = "terms", rank)]`
+///   (optional). On PostgreSQL it adds `search_vector @@ plainto_tsquery($n)` to the WHERE
+///   clause, on SQLite `search_vector MATCH ?n` against an FTS5 table or column. The terms
+///   come from the `query` field (default `query`), which is left out of the default select
+///   list; give it `#[from_row(default)]`. `config = "english"` names the text search
+///   configuration (PostgreSQL only), and `rank` orders by `ts_rank` (FTS5's `rank` on
+///   SQLite) before `order_by`.
+/// - `within`: Radius search on a PostGIS column, e.g.
+///   `#[within("location", center = "center", distance = "meters")]` (optional, PostgreSQL
+///   only). Adds `ST_DWithin(location, $center, $meters)` to the WHERE clause; the center
+///   is a `PostGis` field of the `postgis` feature. The distance is in the units of the
+///   column's SRID unless a trailing `geography` casts both sides, measuring in meters.
+/// - `primary_key`: Key field of the records, e.g. `#[primary_key("id")]` (optional, not
+///   with `union` or `include_fields`). Implements the backend's `Keyed` trait, so
+///   `fetch_many` (`get_many` on deadpool) reads the records of a list of keys with the
+///   struct's select list, tables and joins; its own conditions, ordering and paging are
+///   not applied.
+/// - `partitioned`: Partitioning of the table, like on `Insertable` (optional, not with
+///   `materialized_view`)
+/// - `case_insensitive` / `citext` (field): Case-insensitive comparisons of the field's
+///   column in the WHERE clause and the `where_if` conditions, see `Updateable` (optional)
+#[proc_macro_derive(Queryable, attributes(table, where_clause, where_if, select, select_extra, join, group_by, order_by, having, limit, offset, require_order, union, union_all, rename_all, no_quote, partition_by, register_query, sortable, allow_raw_sql, include_fields, materialized_view, fts, within, primary_key, partitioned, case_insensitive, citext))]
 pub fn derive_queryable(input: TokenStream) -> TokenStream {
-    queryable::derive_queryable_impl(input)
+    schema_check::track(queryable::derive_queryable_impl(input))
 }
 
 /// Derive macro for generating DELETE queries.
 /// 
 /// # Attributes
-/// - `table`: The name of the table to delete from
+/// - `table`: The name of the table to delete from; inferred from the struct name when omitted
 /// - `where_clause`: The WHERE clause for the DELETE statement
-#[proc_macro_derive(Deletable, attributes(table, where_clause))]
+/// - `using`: Other tables the WHERE clause joins with, `DELETE ... USING`, e.g.
+///   `#[using("posts")]` (optional, PostgreSQL only). Its placeholders come before the
+///   WHERE clause's.
+/// - `no_quote`: Leaves a reserved table name unquoted (optional)
+/// - `partition_by`: Partition key added to the WHERE clause like on `Updateable`; the
+///   derive warns when the DELETE would still scan every partition (optional)
+/// - `register_query`: Adds the statement to the query registry (optional)
+/// - `allow_raw_sql`: Disables the SQL injection audit of the raw attribute strings (optional)
+/// - `audited`: Records each deleted row, as it was, in the audit table, see `Insertable` (optional)
+/// - `case_insensitive` / `citext` (field): Case-insensitive comparisons of the field's
+///   column in the WHERE clause, see `Updateable` (optional)
+#[proc_macro_derive(Deletable, attributes(table, where_clause, using, no_quote, partition_by, register_query, allow_raw_sql, audited, case_insensitive, citext))]
 pub fn derive_deletable(input: TokenStream) -> TokenStream {
-    deletable::derive_deletable_impl(input)
+    schema_check::track(deletable::derive_deletable_impl(input))
+}
+
+/// Derive macro for invoking a stored procedure or function.
+///
+/// The fields are the arguments, in declaration order; derive `SqlParams` next to it to
+/// bind them. Run the statement with the backend's `call`, or read what a function
+/// returns with `select`.
+///
+/// # Attributes
+/// - `procedure`: The procedure to run, `CALL refresh_user_stats($1, $2)`
+/// - `function`: The function to run, `SELECT user_score($1)`
+///
+/// Exactly one of them is required; the name may be schema-qualified (PostgreSQL only).
+#[proc_macro_derive(Callable, attributes(procedure, function))]
+pub fn derive_callable(input: TokenStream) -> TokenStream {
+    callable::derive_callable_impl(input)
 }
 
 /// Derive macro for generating SQL parameter handling code.
 /// 
 /// # Attributes
 /// - `where_clause`: The WHERE clause containing parameter placeholders
-#[proc_macro_derive(SqlParams, attributes(where_clause))]
+///
+/// On queries, placeholders are bound in query order (`where_clause`, `having`, `limit`,
+/// `offset`). A bare `$` binds the field named before it, `$field` binds `field` explicitly.
+///
+/// - `using`: Binds the placeholders of a DELETE's USING list before the WHERE clause's
+/// - `insert_from`: Takes the parameters from the field holding this source query
+/// - `default_sql`: On inserts, a non-`Option` field with this attribute is not bound
+/// - `union` / `union_all`: Appends the parameters of the field holding the combined query
+/// - `rename_all`: Lets clauses name the renamed columns instead of the fields
+/// - `partition_by`: Binds the partition key field added to the WHERE clause
+/// - `sql_with`: On a field, binds the output of this function instead of the field;
+///   the function takes `&FieldType`. The backend's `SqlWith` trait must be in scope
+/// - `include_fields`: On inserts, appends the parameters of the field holding the base model
+/// - `fts`: Binds the search terms of the full-text search condition after the WHERE clause
+/// - `within`: Binds the center and distance of the `ST_DWithin` condition, before the search terms
+#[proc_macro_derive(SqlParams, attributes(where_clause, where_if, using, insert_from, union, union_all, default_sql, rename_all, no_quote, partition_by, sql_with, include_fields, fts, within))]
 pub fn derive_sql_params(input: TokenStream) -> TokenStream {
     sql_params::derive_sql_params_impl(input)
 }
@@ -108,31 +355,155 @@ pub fn derive_sql_params(input: TokenStream) -> TokenStream {
 /// 
 /// # Attributes
 /// - `update`: The columns to update
+/// - `update_expr`: Assignments with SQL expressions; a `$` binds the field named before it
+/// - `from`: The FROM list of an `UPDATE ... FROM`, bound between `update_expr` and the WHERE clause
 /// - `where_clause`: The WHERE clause containing parameter placeholders
-#[proc_macro_derive(UpdateParams, attributes(update, where_clause))]
+/// - `rename_all`: Lets `update` and the clauses name the renamed columns
+/// - `partition_by`: Binds the partition key field added to the WHERE clause
+/// - `sql_with`: On a field, binds the output of this function instead of the field
+/// - `include_fields`: Appends the parameters of the field holding the base model, through
+///   its `SqlParams` impl
+#[proc_macro_derive(UpdateParams, attributes(update, update_expr, from, where_clause, rename_all, partition_by, sql_with, include_fields))]
 pub fn derive_update_params(input: TokenStream) -> TokenStream {
     update_params::derive_update_params_impl(input)
 }
 
+/// Derive macro for paging a `Queryable` type.
+///
+/// Generates a `<Name>Page` struct with `query`, `limit` and `offset` fields, which the
+/// backend's `fetch_page` runs with `LIMIT` / `OFFSET` appended to the query. The query
+/// type itself must not have `#[limit]` or `#[offset]`. With `#[require_order]`, a query
+/// type without `#[order_by]` is rejected, since its pages would not be deterministic.
+///
+/// ```rust,ignore
+/// #[derive(Queryable, SqlParams, FromRow, Paginated)]
+/// #[table("users")]
+/// #[where_clause("state = $")]
+/// #[order_by("id")]
+/// pub struct ActiveUsers { /* ... */ }
+///
+/// let users = fetch_page(&mut client, &ActiveUsersPage::nth(ActiveUsers::new(1), 2, 20))?;
+/// ```
+#[proc_macro_derive(Paginated, attributes(require_order))]
+pub fn derive_paginated(input: TokenStream) -> TokenStream {
+    paginated::derive_paginated_impl(input)
+}
+
+/// Derive macro for partial updates.
+///
+/// Generates a `<Name>Patch` struct holding every field of the model except its key as
+/// an `Option`. The backend's `update_patch(client, key, patch)` sets only the fields
+/// that are `Some`, numbering the placeholders at runtime, and does nothing when none is.
+///
+/// ```rust,ignore
+/// #[derive(Patch)]
+/// #[table("users")]
+/// pub struct User { pub id: i64, pub name: String, pub email: String }
+///
+/// let patch = UserPatch { email: Some("ali@example.org".into()), ..Default::default() };
+/// update_patch(&mut client, 1, patch)?; // UPDATE users SET email = $1 WHERE id = $2
+/// ```
+///
+/// # Attributes
+/// - `table`: The table to update; inferred from the struct name when omitted
+/// - `patch_key`: The key field matched in the WHERE clause (default `id`)
+/// - `rename_all`: Naming convention of the columns
+/// - `no_quote`: Writes the table and column names without quotes
+#[proc_macro_derive(Patch, attributes(table, patch_key, rename_all, no_quote))]
+pub fn derive_patch(input: TokenStream) -> TokenStream {
+    patch::derive_patch_impl(input)
+}
+
+/// Derive macro for the standard traits of a table's model.
+///
+/// The model is the SELECT by key: it gets `Queryable` with `WHERE pk = $`, `SqlParams`,
+/// `FromRow` and, through `primary_key`, `Keyed`. A type implements `SqlQuery` only once,
+/// so the writes are companion types expanded with the regular derives:
+///
+/// - `<Name>Insert`: every field except the key, `Insertable` + `SqlParams`; on PostgreSQL it
+///   returns the key, on SQLite run it with `insert_execute` and read `last_insert_rowid`
+/// - `<Name>Update`: every field, `Updateable` + `UpdateParams` setting all other columns by key
+/// - `<Name>Delete`: the key only, `Deletable` + `SqlParams`
+///
+/// The first two convert `From` the model, the last `From` the key.
+///
+/// ```rust,ignore
+/// #[derive(Entity, Debug)]
+/// #[entity(table = "users", pk = "id")]
+/// pub struct User { pub id: i64, pub name: String, pub email: String }
+///
+/// let id: i64 = insert(&client, UserInsert { name: "Ali".into(), email: "ali@example.com".into() }).await?;
+/// let mut user = fetch_many::<User, i64>(&client, &[id]).await?.found.remove(0);
+/// user.email = "ali@example.org".into();
+/// update(&client, UserUpdate::from(user)).await?; // UPDATE users SET name = $1, email = $2 WHERE id = $3
+/// delete(&client, UserDelete::from(id)).await?;
+/// ```
+///
+/// # Attributes
+/// - `entity(table = "...", pk = "...")`: The table, inferred from the struct name when
+///   omitted, and the key field (default `id`)
+/// - `rename_all`, `no_quote`: Apply to the model and the companion types
+/// - `audited`: Audits the writes of the companion types, see `Insertable`; name a key
+///   other than `id` with `#[audited(key = "...")]`
+/// - `validate`, `default_sql`, `sql_with`: Field attributes kept on the companion types'
+///   fields, see `Insertable` and `SqlParams`
+/// - `from_row`: Field and struct attributes of the model's `FromRow`
+#[proc_macro_derive(Entity, attributes(entity, rename_all, no_quote, audited, validate, default_sql, sql_with, from_row))]
+pub fn derive_entity(input: TokenStream) -> TokenStream {
+    schema_check::track(entity::derive_entity_impl(input))
+}
+
+/// Derive macro for typed CRUD repositories.
+///
+/// Implements the backend's `RepositoryTypes` trait, so `Repository::<T, _>::new(executor)`
+/// offers `create`, `find`, `find_all`, `update` and `delete` over any `CrudOps` executor.
+///
+/// # Attributes
+/// - `repository(key = .., insert = .., find = .., find_all = .., update = .., delete = ..)`:
+///   The key type and the query types of each operation. `find` and `delete` must
+///   implement `From<key>`.
+#[proc_macro_derive(Repository, attributes(repository))]
+pub fn derive_repository(input: TokenStream) -> TokenStream {
+    repository::derive_repository_impl(input)
+}
+
 /// Derive macro for converting database rows to Rust structs.
 /// 
-/// This macro generates code for converting database rows to Rust structs based on
-/// the enabled database feature (postgres or sqlite).
-/// 
-/// # Features
-/// - `postgres`: Generate code for PostgreSQL
-/// - `sqlite`: Generate code for SQLite
-
-#[cfg(feature = "sqlite")]
-#[proc_macro_derive(FromRowSqlite)]
+/// This is the SQLite flavour; the sqlite and tokio-sqlite backends export it as `FromRow`.
+/// Both flavours are always available, whichever database features are enabled.
+///
+/// Named fields are read by column name. Tuple structs and newtypes such as
+/// `struct UserId(i64)` are read by position, so the select list order must match.
+///
+/// # Field Attributes
+/// - `from_row(default)`: Uses `Default::default()` when the row has no such column,
+///   so a partial `#[select]` can still be read into the full struct
+///
+/// # Struct Attributes
+/// - `rename_all`: Reads named fields from the columns of this naming convention,
+///   e.g. `#[rename_all("camelCase")]` reads `user_name` from `userName`
+/// - `from_row(backends(parsql::postgres, parsql::sqlite))`: Generates one impl for each
+///   listed backend module instead of one for the imported `FromRow`, so a model crate can
+///   serve binaries built for different databases. The impls name the module's
+///   `traits::FromRow`, `Row` and `Error`; the last path segment picks the row format.
+/// - `include_fields`: Reads the field holding this base model through the base's own
+///   `FromRow` impl, from the same row
+///
+/// Structs with named fields and no `include_fields` also get a `COLUMNS` const listing
+/// the columns they read, which `include_fields` on other models builds on. Reserved and
+/// mixed-case names are quoted in it unless the struct has `#[no_quote]`.
+#[proc_macro_derive(FromRowSqlite, attributes(from_row, rename_all, no_quote, include_fields))]
 pub fn derive_from_row_sqlite(input: TokenStream) -> TokenStream {
-    crate::implementations::sqlite::generate_from_row(&parse_macro_input!(input as DeriveInput)).into()
+    let input = parse_macro_input!(input as DeriveInput);
+    implementations::from_row(&input, implementations::sqlite::generate_from_row).into()
 }
 
-#[cfg(any(feature = "postgres", feature = "tokio-postgres", feature = "deadpool-postgres"))]
-#[proc_macro_derive(FromRowPostgres)]
+/// PostgreSQL counterpart of [`FromRowSqlite`](derive@FromRowSqlite); the postgres,
+/// tokio-postgres and deadpool-postgres backends export it as `FromRow`.
+#[proc_macro_derive(FromRowPostgres, attributes(from_row, rename_all, no_quote, include_fields))]
 pub fn derive_from_row_postgres(input: TokenStream) -> TokenStream {
-    crate::implementations::postgres::generate_from_row(&parse_macro_input!(input as DeriveInput)).into()
+    let input = parse_macro_input!(input as DeriveInput);
+    implementations::from_row(&input, implementations::postgres::generate_from_row).into()
 }
 
 
@@ -142,9 +513,7 @@ pub(crate) use utils::{SqlParamCounter, number_where_clause_params};
 
 /// Log mesajlarını yazdırmak için yardımcı fonksiyon
 pub(crate) fn log_message(message: &str) {
-    if let Ok(trace) = env::var("PARSQL_TRACE") {
-        if trace == "1" {
-            println!("{}", message);
-        }
+    if parsql_core::trace::enabled() {
+        println!("{}", message);
     }
 }
\ No newline at end of file
@@ -0,0 +1,86 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput};
+
+use crate::check_required_order;
+
+/// Implements the Paginated derive macro.
+///
+/// Generates the companion `<Name>Page` struct holding the query together with its
+/// `limit` and `offset`, and implements the backend's `Paginated` trait for it. The
+/// backend's `fetch_page` appends `LIMIT` / `OFFSET` to the query at runtime and binds
+/// the two values after the query's own parameters.
+pub(crate) fn derive_paginated_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let page_name = format_ident!("{}Page", struct_name);
+
+    // Sayfa sınırları çalışma anında eklendiği için sorgu tipinde bulunmamalıdır
+    if let Some(attr) = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("limit") || attr.path().is_ident("offset"))
+    {
+        panic!(
+            "Paginated adds LIMIT / OFFSET at runtime; remove #[{}] from `{}`",
+            attr.path().get_ident().unwrap(),
+            struct_name
+        );
+    }
+
+    check_required_order(&input, true);
+
+    let doc = format!(
+        "A page of [`{}`] rows, fetched with `fetch_page`. `limit` and `offset` are bound after the query's own parameters.",
+        struct_name
+    );
+
+    let expanded = quote! {
+        #[doc = #doc]
+        #vis struct #page_name {
+            /// The paged query
+            pub query: #struct_name,
+            /// Maximum number of rows in the page
+            pub limit: i64,
+            /// Number of rows skipped before the page
+            pub offset: i64,
+        }
+
+        impl #page_name {
+            /// Creates the page of at most `limit` rows after the first `offset` rows.
+            pub fn new(query: #struct_name, limit: i64, offset: i64) -> Self {
+                Self { query, limit, offset }
+            }
+
+            /// Creates page `number` (starting at 1) of pages holding `size` rows each.
+            pub fn nth(query: #struct_name, number: i64, size: i64) -> Self {
+                Self::new(query, size, (number.max(1) - 1) * size)
+            }
+
+            /// Moves to the following page.
+            pub fn next(self) -> Self {
+                let offset = self.offset + self.limit;
+                Self { offset, ..self }
+            }
+        }
+
+        impl Paginated for #page_name {
+            type Query = #struct_name;
+
+            fn query(&self) -> &Self::Query {
+                &self.query
+            }
+
+            fn limit(&self) -> i64 {
+                self.limit
+            }
+
+            fn offset(&self) -> i64 {
+                self.offset
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
@@ -0,0 +1,117 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+use crate::{is_option, query_builder, quote_column, quote_identifiers, rename_rule, table_name};
+
+/// Implements the Patch derive macro.
+///
+/// Generates the companion `<Name>Patch` struct, with every field of the model except
+/// the key wrapped in `Option`, and implements the backend's `Patch` trait for it. The
+/// backend's `update_patch` sets only the `Some` fields; an `Option` field of the model
+/// becomes `Option<Option<T>>`, so `Some(None)` writes NULL.
+pub(crate) fn derive_patch_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let patch_name = format_ident!("{}Patch", struct_name);
+
+    // Tablo adı diğer derive'lardaki gibi güvenli tanımlayıcıya çevrilir
+    let quote = quote_identifiers(&input);
+    let mut builder = query_builder::SafeQueryBuilder::new().quoting(quote);
+    builder.add_identifier(&table_name(&input));
+    let table = builder.build();
+
+    let key = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("patch_key"))
+        .map(|attr| {
+            attr.parse_args::<syn::LitStr>()
+                .expect("Expected a string literal for patch_key, e.g. #[patch_key(\"id\")]")
+                .value()
+        })
+        .unwrap_or_else(|| "id".to_string());
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+            _ => panic!("Patch can only be derived for structs with named fields"),
+        },
+        _ => panic!("Patch can only be derived for structs"),
+    };
+    let key_field = fields
+        .iter()
+        .find(|f| f.ident.as_ref().unwrap() == key.as_str())
+        .unwrap_or_else(|| panic!("Patch key field `{}` not found; name it with #[patch_key(\"...\")]", key));
+    let key_type = &key_field.ty;
+
+    let rule = rename_rule(&input);
+    let key_column = quote_column(&rule.apply(&key), quote);
+    let fields = fields
+        .iter()
+        .filter(|f| f.ident.as_ref().unwrap() != key.as_str())
+        .collect::<Vec<_>>();
+
+    let idents = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect::<Vec<_>>();
+    let types = fields.iter().map(|f| &f.ty);
+    let columns = idents.iter().map(|ident| quote_column(&rule.apply(&ident.to_string()), quote));
+    let docs = fields.iter().map(|f| {
+        let ident = f.ident.as_ref().unwrap();
+        if is_option(&f.ty) {
+            format!("New `{}`; `Some(None)` sets it to NULL", ident)
+        } else {
+            format!("New `{}`", ident)
+        }
+    });
+
+    let doc = format!(
+        "Partial update of [`{}`]: `update_patch` writes only the fields that are `Some`.",
+        struct_name
+    );
+
+    let expanded = quote! {
+        #[doc = #doc]
+        #[derive(Default)]
+        #vis struct #patch_name {
+            #(
+                #[doc = #docs]
+                pub #idents: Option<#types>,
+            )*
+        }
+
+        impl Patch for #patch_name {
+            type Key = #key_type;
+
+            fn table() -> &'static str {
+                #table
+            }
+
+            fn key_column() -> &'static str {
+                #key_column
+            }
+
+            fn columns(&self) -> Vec<&'static str> {
+                let mut columns = Vec::new();
+                #(
+                    if self.#idents.is_some() {
+                        columns.push(#columns);
+                    }
+                )*
+                columns
+            }
+
+            fn values(&self) -> Vec<&(dyn ToSql + Sync)> {
+                let mut values: Vec<&(dyn ToSql + Sync)> = Vec::new();
+                #(
+                    if let Some(value) = &self.#idents {
+                        values.push(value);
+                    }
+                )*
+                values
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
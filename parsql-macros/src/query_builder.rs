@@ -1,15 +1,24 @@
+use crate::quote_name;
 
 /// A safe query builder that prevents SQL injection
-#[derive(Default)]
+#[derive(Clone)]
 pub(crate) struct SafeQueryBuilder {
     /// The SQL query being built
     pub query: String,
+    /// Whether reserved identifiers are quoted
+    quote: bool,
 }
 
 impl SafeQueryBuilder {
     /// Creates a new empty query builder
     pub fn new() -> Self {
-        Self::default()
+        Self { query: String::new(), quote: true }
+    }
+
+    /// Turns identifier quoting on or off, see [`quote_identifiers`](crate::quote_identifiers)
+    pub fn quoting(mut self, quote: bool) -> Self {
+        self.quote = quote;
+        self
     }
 
     /// Builds and returns the final SQL query string
@@ -33,10 +42,7 @@ impl SafeQueryBuilder {
         if !self.query.is_empty() {
             self.query.push(' ');
         }
-        let safe_ident = ident
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '_')
-            .collect::<String>();
+        let safe_ident = self.identifier(ident);
         self.query.push_str(&safe_ident);
     }
 
@@ -45,14 +51,7 @@ impl SafeQueryBuilder {
     /// # Arguments
     /// * `items` - The list of identifiers to add
     pub fn add_comma_list(&mut self, items: &[&str]) {
-        let safe_items: Vec<String> = items
-            .iter()
-            .map(|item| {
-                item.chars()
-                    .filter(|c| c.is_alphanumeric() || *c == '_')
-                    .collect()
-            })
-            .collect();
+        let safe_items: Vec<String> = items.iter().map(|item| self.identifier(item)).collect();
         self.query.push_str(&safe_items.join(", "));
     }
 
@@ -67,4 +66,8 @@ impl SafeQueryBuilder {
         }
         self.query.push_str(text);
     }
+
+    fn identifier(&self, ident: &str) -> String {
+        quote_name(ident, self.quote)
+    }
 }
@@ -1,21 +1,53 @@
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, Data, DeriveInput, Fields};
 use quote::quote;
-use crate::{log_message, number_where_clause_params, query_builder, SqlParamCounter};
+use crate::audit;
+use crate::joins;
+use crate::schema_check;
+use crate::sql_params::param_fields;
+use crate::{
+    case_insensitive_where_clause, check_required_order, conditional_where_clause, select_extras, dialect, field_of_type, full_text_search, included_fields, log_message,
+    number_where_clause_params, paging_clause, query_builder, partitioned_impl, partitioned_where_clause, query_registration, quote_column,
+    quote_identifiers, reject_registration, query_metadata, rename_rule, searched_where_clause, spatial_where_clause,
+    spliced_query, table_name, union_attribute, union_template, Splice, SqlParamCounter,
+};
 
 pub fn derive_queryable_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = &input.ident;
+    let (impl_generics, ty_generics, generic_where) = input.generics.split_for_impl();
+
+    // Ham SQL içeren öznitelikleri derleme zamanında denetle
+    if let Some(error) = audit::audit_attributes(&input) {
+        return error;
+    }
+
+    // schema-check açıksa tablo ve sütunlar şema görüntüsüyle karşılaştırılır
+    if let Some(error) = schema_check::check(&input, schema_check::Statement::Select) {
+        return error;
+    }
+
+    // #[join] sorgularında nitelikli sütunlar (p.created_at) bildirilen tablolara ait olmalıdır
+    if let Some(error) = joins::check_qualified_columns(&input) {
+        return error;
+    }
 
     // Table name and column extraction
-    let table = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("table"))
-        .expect("Missing `#[table = \"...\"]` attribute")
-        .parse_args::<syn::LitStr>()
-        .expect("Expected a string literal for table name")
-        .value();
+    let view = materialized_view(&input);
+    let table = view.clone().unwrap_or_else(|| table_name(&input));
+    let view_impl = view.map(|view| {
+        quote! {
+            impl MaterializedView for #struct_name {
+                fn view_name() -> &'static str {
+                    #view
+                }
+            }
+        }
+    });
+    if view_impl.is_some() && input.attrs.iter().any(|attr| attr.path().is_ident("partitioned")) {
+        panic!("partitioned names a table; it can't be combined with materialized_view");
+    }
+    let partitioned = partitioned_impl(&input, &table);
 
     // Get the optional where_clause attribute
     let where_clause = input
@@ -28,12 +60,40 @@ pub fn derive_queryable_impl(input: TokenStream) -> TokenStream {
                 .value()
         });
 
+    // #[partition_by] anahtarı koşulda yoksa bölüm budaması için eklenir
+    let where_clause = partitioned_where_clause(&input, where_clause);
+    let where_clause = conditional_where_clause(&input, where_clause);
+    // #[case_insensitive] / #[citext] sütunlarının karşılaştırmaları büyük-küçük harf ayırmaz
+    let where_clause = case_insensitive_where_clause(&input, where_clause);
+    let where_clause = spatial_where_clause(&input, where_clause);
+    // #[fts] arama koşulu diğer koşullardan sonra, en sona eklenir
+    let search = full_text_search(&input);
+    let where_clause = searched_where_clause(&input, where_clause);
+    let search_field = search.as_ref().map(|search| search.query.clone());
+
+    // UNION / UNION ALL ile eklenecek ikinci sorgu
+    let union = union_attribute(&input);
+    let union_field = union.as_ref().map(|(_, other)| {
+        field_of_type(&input, other)
+            .unwrap_or_else(|| panic!("union requires a named field of type `{}`", quote!(#other)))
+            .to_string()
+    });
+
+    // #[include_fields] ile eklenen temel modelin sütunları kendi sütunlarından sonra gelir
+    let included = included_fields(&input);
+    if union.is_some() && included.is_some() {
+        panic!("include_fields cannot be combined with union");
+    }
+    let included_field = included.as_ref().map(|(_, field)| field.clone());
+
     let fields = if let Data::Struct(data) = &input.data {
         if let Fields::Named(fields) = &data.fields {
             fields
                 .named
                 .iter()
                 .map(|f| f.ident.as_ref().unwrap().to_string())
+                .filter(|f| union_field.as_ref() != Some(f) && included_field.as_ref() != Some(f))
+                .filter(|f| search_field.as_ref() != Some(f))
                 .collect::<Vec<_>>()
         } else {
             panic!("Queryable can only be derived for structs with named fields");
@@ -54,6 +114,7 @@ pub fn derive_queryable_impl(input: TokenStream) -> TokenStream {
         .collect();
 
     let tables = table.to_string();
+    let sortable = sortable_columns(&input);
 
     // SQL parametrelerinin numaralandırması için SqlParamCounter kullanıyoruz
     // Bu sayede tüm parametreler her zaman 1'den başlayacak ve tutarlı şekilde artacak
@@ -63,6 +124,10 @@ pub fn derive_queryable_impl(input: TokenStream) -> TokenStream {
     let adjusted_where_clause = where_clause
         .map(|clause| number_where_clause_params(&clause, &mut param_counter))
         .unwrap_or_else(|| "".to_string());
+    // Arama terimleri WHERE cümlesinin son parametresidir; ts_rank aynı numarayı kullanır
+    let rank_order = search
+        .as_ref()
+        .and_then(|search| search.rank_order(dialect(), &format!("{}{}", dialect().placeholder(), param_counter.count())));
 
     // Get the optional select attribute
     let select = input
@@ -75,14 +140,30 @@ pub fn derive_queryable_impl(input: TokenStream) -> TokenStream {
                 .value()
         });
 
-    // If select is not defined, use all fields
+    // Açık bir #[select] yoksa temel modelin sütunları seçim listesine eklenir
+    let included = included.filter(|_| select.is_none());
+
+    // If select is not defined, use all fields (named by the #[rename_all] convention)
+    let rule = rename_rule(&input);
+    let quote = quote_identifiers(&input);
+    let extras = select_extras(&input);
     let select = select.unwrap_or_else(|| {
+        // #[select_extra] takma adıyla okunan alanlar sütun olarak seçilmez
+        let aliased = |f: &String| {
+            extras.iter().any(|(_, alias)| alias.as_ref().is_some_and(|alias| *alias == *f || *alias == rule.apply(f)))
+        };
         fields
             .iter()
-            .map(|f| f.as_str())
+            .filter(|f| !aliased(f))
+            .map(|f| quote_column(&rule.apply(f), quote))
             .collect::<Vec<_>>()
             .join(", ")
     });
+    let select = std::iter::once(select)
+        .chain(extras.into_iter().map(|(expr, _)| expr))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ");
 
     // Get the optional group_by attribute
     let group_by = input
@@ -123,18 +204,41 @@ pub fn derive_queryable_impl(input: TokenStream) -> TokenStream {
                 .expect("Expected a string literal for order_by")
                 .value()
         });
+    // İlgiye göre sıralama önce gelir, #[order_by] eşit puanlı satırları sıralar
+    let order_by = match (rank_order, order_by) {
+        (Some(rank), Some(order_by)) => Some(format!("{}, {}", rank, order_by)),
+        (rank, order_by) => rank.or(order_by),
+    };
 
-    let mut builder = query_builder::SafeQueryBuilder::new();
+    // delete_where / update_where koşulu yalnızca tek bir tabloya uygulanabiliyorsa alır
+    let criteria = (!adjusted_where_clause.is_empty() && joins.is_empty() && view_impl.is_none()).then(|| {
+        let mut table_builder = query_builder::SafeQueryBuilder::new().quoting(quote);
+        table_builder.add_identifier(&tables);
+        let table = table_builder.build();
+        let where_clause = adjusted_where_clause.trim();
+        quote! {
+            fn criteria() -> Option<(&'static str, &'static str)> {
+                Some((#table, #where_clause))
+            }
+        }
+    });
+
+    let mut builder = query_builder::SafeQueryBuilder::new().quoting(quote);
     
     builder.add_keyword("SELECT");
     builder.add_raw(&select);
+    let select_end = builder.query.len();
     builder.add_keyword("FROM");
     builder.add_identifier(&tables);
     
     // Add join expressions separately and place a space around each one
+    let joined = !joins.is_empty();
     for join in joins {
         builder.add_raw(&format!(" {} ", join.trim()));
     }
+
+    // fetch_many koşulu kendisi yazar; koşulsuz SELECT ... FROM ... kısmı saklanır
+    let keyed = keyed_impl(&input, builder.clone().build(), &tables, joined);
     
     if !adjusted_where_clause.is_empty() {
         builder.add_keyword("WHERE");
@@ -153,59 +257,298 @@ pub fn derive_queryable_impl(input: TokenStream) -> TokenStream {
         builder.add_raw(&adjusted_having_clause);
     }
 
-    // Add ORDER BY clause
-    if let Some(order_by_clause) = order_by {
-        builder.add_keyword("ORDER BY");
-        builder.add_raw(&order_by_clause);
-    }
+    // LIMIT ve OFFSET, HAVING'den sonraki parametre numaralarıyla devam eder
+    let params_before_tail = param_counter.count();
+    let limit = paging_clause(&input, "limit")
+        .map(|limit| number_where_clause_params(&limit, &mut param_counter));
+    let offset = paging_clause(&input, "offset")
+        .map(|offset| number_where_clause_params(&offset, &mut param_counter));
 
-    // Add LIMIT clause
-    let limit = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("limit"))
-        .map(|attr| {
-            attr.parse_args::<syn::LitInt>()
-                .expect("Expected an integer literal for limit")
-                .base10_parse::<u64>()
-                .expect("Failed to parse limit value as an integer")
-        });
+    check_required_order(&input, limit.is_some() || offset.is_some());
 
-    if let Some(limit_value) = limit {
-        builder.add_keyword("LIMIT");
-        builder.add_raw(&limit_value.to_string());
-    }
+    // UNION kullanıldığında ORDER BY, LIMIT ve OFFSET birleşik sonuca uygulanır
+    let build_tail = |limit: Option<&str>| {
+        let mut tail_builder = query_builder::SafeQueryBuilder::new();
+        if let Some(order_by_clause) = &order_by {
+            tail_builder.add_keyword("ORDER BY");
+            tail_builder.add_raw(order_by_clause);
+        }
+        if let Some(limit) = limit {
+            tail_builder.add_keyword("LIMIT");
+            tail_builder.add_raw(limit);
+        }
+        if let Some(offset) = &offset {
+            tail_builder.add_keyword("OFFSET");
+            tail_builder.add_raw(offset);
+        }
+        tail_builder.build()
+    };
 
-    // Add OFFSET clause
-    let offset = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("offset"))
-        .map(|attr| {
-            attr.parse_args::<syn::LitInt>()
-                .expect("Expected an integer literal for offset")
-                .base10_parse::<u64>()
-                .expect("Failed to parse offset value as an integer")
+    let tail = build_tail(limit.as_deref());
+    // Tek satırlık okumalar (fetch/select) için LIMIT 2: veritabanı ikinci satırda durur, birden
+    // fazla satırla eşleşen sorgu yine hata verir; açık bir #[limit] varsa ona dokunulmaz
+    let single_tail = limit.is_none().then(|| build_tail(Some("2")));
+
+    if let Some((keyword, other)) = union {
+        reject_registration(&input, "union");
+        if keyed.is_some() {
+            panic!("primary_key cannot be combined with union");
+        }
+        if param_counter.count() != params_before_tail {
+            panic!("union cannot be combined with a parameterized limit or offset");
+        }
+        let head = builder.build();
+        let tail = if tail.is_empty() { tail } else { format!(" {}", tail) };
+        let offset = param_counter.count();
+        let marker = dialect().placeholder();
+        let query_single = single_tail.map(|single_tail| {
+            quote! {
+                fn query_single() -> &'static str {
+                    static QUERY: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+                    QUERY.get_or_init(|| {
+                        let query = Self::query();
+                        format!("{} {}", &query[..query.len() - #tail.len()], #single_tail)
+                    })
+                }
+            }
         });
 
-    if let Some(offset_value) = offset {
-        builder.add_keyword("OFFSET");
-        builder.add_raw(&offset_value.to_string());
+        let (prefix, suffix) = union_template(&head, keyword, &tail);
+
+        log_message(&format!("Generated SQL Query: {}<{}>{}", prefix, quote!(#other), suffix));
+        log_message(&format!("Total param count: {} + <{}>", offset, quote!(#other)));
+
+        let metadata = query_metadata(&table, Some(quote! { #offset + <#other as SqlQuery>::param_count() }), None);
+
+        // İkinci sorgunun $n / ?n parametreleri, ilk sorgunun parametrelerinden sonra gelecek şekilde kaydırılır
+        let expanded = quote! {
+            impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+                fn query() -> &'static str {
+                    static QUERY: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+                    QUERY.get_or_init(|| {
+                        let other = <#other as SqlQuery>::query();
+                        let mut shifted = String::with_capacity(other.len() + 8);
+                        let mut chars = other.chars().peekable();
+                        while let Some(c) = chars.next() {
+                            shifted.push(c);
+                            if c == #marker {
+                                let mut digits = String::new();
+                                while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                                    digits.push(d);
+                                }
+                                match digits.parse::<usize>() {
+                                    Ok(n) => shifted.push_str(&(n + #offset).to_string()),
+                                    Err(_) => shifted.push_str(&digits),
+                                }
+                            }
+                        }
+                        format!("{}{}{}", #prefix, shifted, #suffix)
+                    })
+                }
+
+                #query_single
+
+                #metadata
+
+                #sortable
+            }
+
+            #view_impl
+            #partitioned
+        };
+
+        return TokenStream::from(expanded);
     }
 
+    let splice = |sql: &str, base: &syn::Path| {
+        let separator = if select.is_empty() { "" } else { ", " };
+        spliced_query(base, sql, &[(select_end, separator, Splice::Columns)])
+    };
+
+    let query_single = single_tail.map(|single_tail| {
+        let mut single_builder = builder.clone();
+        single_builder.add_raw(&single_tail);
+        let single_query = single_builder.build();
+        let single_query = match &included {
+            Some((base, _)) => splice(&single_query, base),
+            None => quote! { #single_query },
+        };
+        quote! {
+            fn query_single() -> &'static str {
+                #single_query
+            }
+        }
+    });
+
+    builder.add_raw(&tail);
     let safe_query = builder.build();
 
     // Log mesajlarını PARSQL_TRACE kontrolü ile yazdır
     log_message(&format!("Generated SQL Query: {}", safe_query));
     log_message(&format!("Total param count: {}", param_counter.count()));
 
+    if let Some((base, _)) = &included {
+        reject_registration(&input, "include_fields");
+        if keyed.is_some() {
+            panic!("primary_key cannot be combined with include_fields");
+        }
+        let query = splice(&safe_query, base);
+        let metadata = query_metadata(&table, None, None);
+        let expanded = quote! {
+            impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+                fn query() -> &'static str {
+                    #query
+                }
+
+                #query_single
+
+                #metadata
+
+                #sortable
+
+                #criteria
+            }
+
+            #view_impl
+            #partitioned
+        };
+
+        return TokenStream::from(expanded);
+    }
+
+    let registration = query_registration(&input);
+    let param_count = param_counter.count();
+    let param_names = Some(param_fields(&input)).filter(|names| names.len() == param_count);
+    let metadata = query_metadata(&table, Some(quote! { #param_count }), param_names.as_deref());
+
     let expanded = quote! {
-        impl SqlQuery for #struct_name {
-            fn query() -> String {
-                #safe_query.to_string()
+        impl #impl_generics #struct_name #ty_generics #generic_where {
+            /// The generated SQL statement, available in `const` contexts.
+            pub const SQL: &'static str = #safe_query;
+        }
+
+        #registration
+
+        impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+            fn query() -> &'static str {
+                Self::SQL
             }
+
+            #query_single
+
+            #metadata
+
+            #sortable
+
+            #criteria
         }
+
+        #view_impl
+        #partitioned
+
+        #keyed
     };
 
     TokenStream::from(expanded)
 }
+
+/// Reads `#[materialized_view("reports.daily_sales")]`, the view a `Queryable` selects
+/// from in place of its table.
+fn materialized_view(input: &DeriveInput) -> Option<String> {
+    let attr = input.attrs.iter().find(|attr| attr.path().is_ident("materialized_view"))?;
+    let view = attr
+        .parse_args::<syn::LitStr>()
+        .expect("Expected a string literal for materialized_view")
+        .value();
+    if input.attrs.iter().any(|attr| attr.path().is_ident("table")) {
+        panic!("materialized_view replaces table; use only one of them");
+    }
+    if dialect() != parsql_core::batch::Dialect::Postgres {
+        panic!("materialized_view is only supported on PostgreSQL");
+    }
+    let plain = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !view.split('.').all(plain) {
+        panic!("materialized_view expects a view name such as \"daily_sales\" or \"reports.daily_sales\", found `{}`", view);
+    }
+    Some(view)
+}
+
+/// Generates `sortable_columns()` from `#[sortable("created_at, id")]`, the columns
+/// `fetch_first` / `fetch_last` accept as their order column.
+fn sortable_columns(input: &DeriveInput) -> proc_macro2::TokenStream {
+    let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("sortable")) else {
+        return proc_macro2::TokenStream::new();
+    };
+    let columns: Vec<String> = attr
+        .parse_args::<syn::LitStr>()
+        .expect("Expected a string literal for sortable")
+        .value()
+        .split(',')
+        .map(|column| column.trim().to_string())
+        .filter(|column| !column.is_empty())
+        .collect();
+
+    // Sütun adları SQL metnine yazılacağı için yalnızca düz (ya da tablo önekli) adlar kabul edilir
+    if let Some(column) = columns
+        .iter()
+        .find(|column| !column.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.'))
+    {
+        panic!("sortable expects plain column names, found `{}`", column);
+    }
+
+    quote! {
+        fn sortable_columns() -> &'static [&'static str] {
+            &[#(#columns),*]
+        }
+    }
+}
+
+/// Implements `Keyed` for `#[primary_key("id")]`, naming the field `fetch_many` reads
+/// the records of a list of keys by.
+///
+/// `select` is the generated query up to its conditions. With `#[join]` the key column is
+/// qualified with the struct's table.
+fn keyed_impl(input: &DeriveInput, select: String, table: &str, joined: bool) -> Option<proc_macro2::TokenStream> {
+    let attr = input.attrs.iter().find(|attr| attr.path().is_ident("primary_key"))?;
+    let key = attr
+        .parse_args::<syn::LitStr>()
+        .expect("Expected a string literal for primary_key, e.g. #[primary_key(\"id\")]")
+        .value();
+    let field = match &input.data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .find(|f| f.ident.as_ref().is_some_and(|ident| ident == key.as_str())),
+        _ => None,
+    }
+    .unwrap_or_else(|| panic!("primary_key field `{}` not found in the struct", key));
+
+    let quote = quote_identifiers(input);
+    let mut column = quote_column(&rename_rule(input).apply(&key), quote);
+    if joined {
+        let mut table_builder = query_builder::SafeQueryBuilder::new().quoting(quote);
+        table_builder.add_identifier(table);
+        column = format!("{}.{}", table_builder.build(), column);
+    }
+
+    let struct_name = &input.ident;
+    let ident = field.ident.as_ref().unwrap();
+    let ty = &field.ty;
+    Some(quote! {
+        impl Keyed for #struct_name {
+            type Key = #ty;
+
+            fn key_select() -> &'static str {
+                #select
+            }
+
+            fn key_column() -> &'static str {
+                #column
+            }
+
+            fn key(&self) -> &Self::Key {
+                &self.#ident
+            }
+        }
+    })
+}
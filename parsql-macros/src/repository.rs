@@ -0,0 +1,56 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Options of `#[repository(...)]`, in the order the associated types are declared.
+const OPTIONS: &[&str] = &["key", "insert", "find", "find_all", "update", "delete"];
+
+/// Implements the Repository derive macro.
+///
+/// Reads `#[repository(key = i64, insert = .., find = .., find_all = .., update = .., delete = ..)]`
+/// and implements the backend's `RepositoryTypes` trait with those types, which makes
+/// the backend's generic `Repository` usable with the struct.
+pub(crate) fn derive_repository_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let attr = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("repository"))
+        .expect("Missing `#[repository(key = ..., insert = ..., find = ..., find_all = ..., update = ..., delete = ...)]` attribute");
+
+    let mut types: Vec<Option<syn::Type>> = vec![None; OPTIONS.len()];
+    attr.parse_nested_meta(|meta| {
+        let index = OPTIONS
+            .iter()
+            .position(|name| meta.path.is_ident(name))
+            .ok_or_else(|| meta.error(format!("unsupported repository option, expected one of: {}", OPTIONS.join(", "))))?;
+        types[index] = Some(meta.value()?.parse()?);
+        Ok(())
+    })
+    .unwrap_or_else(|e| panic!("{}", e));
+
+    let types: Vec<syn::Type> = types
+        .into_iter()
+        .zip(OPTIONS)
+        .map(|(ty, name)| ty.unwrap_or_else(|| panic!("repository requires `{} = ...`", name)))
+        .collect();
+    let [key, insert, find, find_all, update, delete] = &types[..] else {
+        unreachable!()
+    };
+
+    let expanded = quote! {
+        impl #impl_generics RepositoryTypes for #struct_name #ty_generics #where_clause {
+            type Key = #key;
+            type Insert = #insert;
+            type Find = #find;
+            type FindAll = #find_all;
+            type Update = #update;
+            type Delete = #delete;
+        }
+    };
+
+    TokenStream::from(expanded)
+}
@@ -0,0 +1,206 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields};
+
+use parsql_core::schema::{self, Schema};
+
+use crate::{dialect, field_for_column, included_fields, rename_rule, table_name};
+
+/// Environment variable naming the snapshot, relative to the crate's `Cargo.toml`.
+const SCHEMA_VARIABLE: &str = "PARSQL_SCHEMA";
+
+/// Snapshot read when `PARSQL_SCHEMA` is not set.
+const DEFAULT_SCHEMA: &str = "schema.json";
+
+/// Struct attributes after which the SELECT list is no longer the struct's own columns
+/// of its table.
+const CUSTOM_SELECT: &[&str] = &["select", "select_extra", "join", "union", "include_fields", "fts", "materialized_view"];
+
+/// The statement a derive generates, which decides what is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Statement {
+    /// The fields are read from columns of the table
+    Select,
+    /// The fields are written to columns of the table
+    Insert,
+    /// The `#[update]` columns are written from their fields
+    Update,
+    /// Only the table is named
+    Delete,
+}
+
+/// The loaded snapshot and its path, `None` when there is none to check against.
+type Snapshot = Option<Result<(PathBuf, Schema), String>>;
+
+/// Checks the struct against the schema snapshot when the `schema-check` feature is on.
+///
+/// Returns a `compile_error!` for a table or column missing from the snapshot, or a
+/// field whose type cannot hold its column.
+pub(crate) fn check(input: &DeriveInput, statement: Statement) -> Option<TokenStream> {
+    if !cfg!(feature = "schema-check") {
+        return None;
+    }
+    let error = match snapshot().as_ref()? {
+        Ok((path, schema)) => verify(schema, input, statement).err().map(|error| {
+            let message = format!("{} (schema snapshot {})", error, path.display());
+            syn::Error::new(error.span(), message)
+        })?,
+        Err(message) => syn::Error::new(input.ident.span(), message),
+    };
+    Some(error.to_compile_error().into())
+}
+
+/// Makes cargo rebuild the expanded struct when the snapshot changes.
+pub(crate) fn track(expanded: TokenStream) -> TokenStream {
+    if !cfg!(feature = "schema-check") {
+        return expanded;
+    }
+    let Some(Ok((path, _))) = snapshot() else {
+        return expanded;
+    };
+    let path = path.display().to_string();
+    let mut expanded = proc_macro2::TokenStream::from(expanded);
+    expanded.extend(quote! {
+        const _: &[u8] = include_bytes!(#path);
+    });
+    expanded.into()
+}
+
+/// Compares the struct with the snapshot.
+pub(crate) fn verify(schema: &Schema, input: &DeriveInput, statement: Statement) -> Result<(), syn::Error> {
+    if statement == Statement::Select && input.attrs.iter().any(|attr| CUSTOM_SELECT.iter().any(|name| attr.path().is_ident(name))) {
+        return Ok(());
+    }
+    if statement == Statement::Insert && input.attrs.iter().any(|attr| attr.path().is_ident("insert_from")) {
+        return Ok(());
+    }
+
+    let table = table_name(input);
+    let Some(columns) = schema.table(&table) else {
+        return Err(syn::Error::new(input.ident.span(), format!("table `{}` does not exist", table)));
+    };
+
+    let fields: Vec<&Field> = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            _ => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    let included = included_fields(input).map(|(_, field)| field);
+    let fields: Vec<&Field> = fields
+        .into_iter()
+        .filter(|f| included.as_deref() != Some(f.ident.as_ref().unwrap().to_string().as_str()))
+        .collect();
+
+    let rule = rename_rule(input);
+    let checked: Vec<(String, Option<&Field>)> = match statement {
+        Statement::Select | Statement::Insert => fields
+            .iter()
+            .map(|f| (rule.apply(&f.ident.as_ref().unwrap().to_string()), Some(*f)))
+            .collect(),
+        Statement::Update => {
+            let names = fields.iter().map(|f| f.ident.as_ref().unwrap().to_string()).collect::<Vec<_>>();
+            update_columns(input)
+                .into_iter()
+                .map(|column| {
+                    // Sütun alan adıyla yazıldıysa sütun adı kurala göre türetilir
+                    match field_for_column(&names, rule, &column) {
+                        Some(name) => {
+                            let field = fields.iter().find(|f| f.ident.as_ref().unwrap() == name.as_str()).copied();
+                            (rule.apply(name), field)
+                        }
+                        None => (column.trim_matches('"').to_string(), None),
+                    }
+                })
+                .collect()
+        }
+        Statement::Delete => Vec::new(),
+    };
+
+    for (name, field) in checked {
+        let span = field.map_or(input.ident.span(), |f| f.ident.as_ref().unwrap().span());
+        let Some(column) = columns.get(&name) else {
+            return Err(syn::Error::new(span, format!("column `{}` does not exist in table `{}`", name, table)));
+        };
+        let Some(field) = field else {
+            continue;
+        };
+        if let Some(rust_type) = type_name(&field.ty) {
+            if !schema::accepts(dialect(), &rust_type, &column.data_type) {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "field `{}` of type `{}` cannot hold column `{}.{}` of type `{}`",
+                        field.ident.as_ref().unwrap(),
+                        rust_type,
+                        table,
+                        name,
+                        column.data_type
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The columns of `#[update("...")]`.
+fn update_columns(input: &DeriveInput) -> Vec<String> {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("update"))
+        .filter_map(|attr| attr.parse_args::<syn::LitStr>().ok())
+        .flat_map(|columns| columns.value().split(',').map(|s| s.trim().to_string()).collect::<Vec<_>>())
+        .filter(|column| !column.is_empty())
+        .collect()
+}
+
+/// The last path segment of a field type, with `Option` and references removed:
+/// `Option<chrono::NaiveDate>` -> `NaiveDate`.
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Reference(reference) => type_name(&reference.elem),
+        syn::Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            if segment.ident != "Option" {
+                return Some(segment.ident.to_string());
+            }
+            match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+                    syn::GenericArgument::Type(inner) => type_name(inner),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Reads the snapshot once per compilation.
+///
+/// A missing `schema.json` turns the check off; a missing file named by `PARSQL_SCHEMA`
+/// is an error, as is a file that cannot be parsed.
+fn snapshot() -> &'static Snapshot {
+    static SNAPSHOT: OnceLock<Snapshot> = OnceLock::new();
+    SNAPSHOT.get_or_init(|| {
+        let root = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").ok()?);
+        let explicit = std::env::var(SCHEMA_VARIABLE).ok().filter(|path| !path.is_empty());
+        let path = root.join(explicit.as_deref().unwrap_or(DEFAULT_SCHEMA));
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) if explicit.is_none() => return None,
+            Err(e) => return Some(Err(format!("cannot read the schema snapshot {}: {}", path.display(), e))),
+        };
+        Some(
+            Schema::from_json(&text)
+                .map(|schema| (path.clone(), schema))
+                .map_err(|e| format!("invalid schema snapshot {}: {}", path.display(), e)),
+        )
+    })
+}
@@ -3,42 +3,98 @@ use quote::quote;
 use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
 use crate::{
-    extract_fields_from_where_clause, log_message, number_where_clause_params, query_builder,
-    SqlParamCounter,
+    bound_fields, conditional_where_clause, default_sql, field_of_type, included_fields, is_option, paging_clause,
+    param_values, partitioned_where_clause, rename_rule, searched_where_clause, spatial_where_clause, union_attribute,
+    where_if_conditions, WHERE_IF_GUARD,
 };
 
+/// Attributes that only appear on SELECT queries; a struct carrying any of them
+/// binds just the fields referenced by placeholders, even without a WHERE clause.
+const QUERY_ATTRIBUTES: &[&str] = &["select", "join", "group_by", "order_by"];
+
 pub(crate) fn derive_sql_params_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = &input.ident;
 
-    // Get the optional where_clause attribute
-    let where_clause = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("where_clause"))
-        .map(|attr| {
-            attr.parse_args::<syn::LitStr>()
-                .expect("Expected a string literal for where_clause")
-                .value()
-        });
+    // INSERT ... SELECT: parametreler kaynak sorgu alanından gelir
+    if let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("insert_from")) {
+        let source = attr
+            .parse_args::<syn::Path>()
+            .expect("Expected a type path for insert_from");
+        return derive_insert_from_params(struct_name, &input, &source);
+    }
 
-    // HAVING cümlesi için de parametreleri kontrol et
-    let having_clause = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("having"))
-        .map(|attr| {
-            attr.parse_args::<syn::LitStr>()
-                .expect("Expected a string literal for having")
-                .value()
+    // UNION ile birleştirilen sorgu, kendi parametrelerini sonradan ekler
+    let union = union_attribute(&input).map(|(_, other)| {
+        let field = field_of_type(&input, &other).unwrap_or_else(|| {
+            panic!("union requires a named field of type `{}`", quote!(#other))
         });
+        (other, field.clone())
+    });
+
+    // #[include_fields] temel modeli kendi parametrelerini verir
+    let included = included_fields(&input);
+    let is_query = is_query(&input);
+    let param_fields = param_fields(&input);
+
+    let (values, markers) = param_values(&input, &param_fields);
+
+    // INSERT'te temel modelin değerleri, sütunları gibi kendi alanlarından sonra gelir;
+    // sorgu koşulları yalnızca kendi alanlarını bağlar
+    let nested = union.or_else(|| {
+        included
+            .filter(|_| !is_query)
+            .map(|(base, field)| (base, quote::format_ident!("{}", field)))
+    });
+
+    let expanded = match nested {
+        Some((other, field)) => quote! {
+            impl SqlParams for #struct_name {
+                fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+                    #markers
+                    let mut params: Vec<&(dyn ToSql + Sync)> = vec![#(#values),*];
+                    params.extend(<#other as SqlParams>::params(&self.#field));
+                    params
+                }
+            }
+        },
+        // Sabit sayıda parametre, params_iter'ın Vec ayırmadan diziden verilmesini sağlar
+        None => {
+            let count = values.len();
+            quote! {
+                impl SqlParams for #struct_name {
+                    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+                        #markers
+                        vec![#(#values),*]
+                    }
 
-    let fields = if let Data::Struct(data) = &input.data {
+                    fn params_iter(&self) -> impl ExactSizeIterator<Item = &(dyn ToSql + Sync)> + Send + Sync {
+                        #markers
+                        let params: [&(dyn ToSql + Sync); #count] = [#(#values),*];
+                        params.into_iter()
+                    }
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Fields the `SqlParams` impl binds, in placeholder order.
+///
+/// Only the struct's own fields are listed: the parameters of a `union` query or of an
+/// `#[include_fields]` base model are appended from the nested struct at runtime.
+pub(crate) fn param_fields(input: &DeriveInput) -> Vec<String> {
+    let union_field = union_attribute(input).and_then(|(_, other)| field_of_type(input, &other).map(|f| f.to_string()));
+    let included_field = included_fields(input).map(|(_, field)| field);
+
+    let named = if let Data::Struct(data) = &input.data {
         if let Fields::Named(fields) = &data.fields {
             fields
                 .named
                 .iter()
-                .map(|f| f.ident.as_ref().unwrap().to_string())
+                .filter(|f| !matches!(&included_field, Some(field) if f.ident.as_ref().unwrap() == field))
                 .collect::<Vec<_>>()
         } else {
             panic!("SqlParams can only be derived for structs with named fields");
@@ -46,47 +102,95 @@ pub(crate) fn derive_sql_params_impl(input: TokenStream) -> TokenStream {
     } else {
         panic!("SqlParams can only be derived for structs");
     };
+    let mut fields = named
+        .iter()
+        .map(|f| f.ident.as_ref().unwrap().to_string())
+        .filter(|f| union_field.as_ref() != Some(f))
+        .collect::<Vec<_>>();
+    // #[where_if] bayrakları da alan gibi adlarıyla bağlanır
+    fields.extend((0..where_if_conditions(input).len()).map(|i| format!("{}{}", WHERE_IF_GUARD, i)));
 
-    // where_clause ve having_clause'daki parametreleri belirle
-    let mut param_fields = Vec::new();
-
-    // WHERE cümlesindeki alan adlarını bulma
-    if let Some(clause) = &where_clause {
-        let where_fields: Vec<_> = fields
+    if is_query(input) {
+        let rule = rename_rule(input);
+        clauses(input)
             .iter()
-            .filter(|&f| clause.contains(f))
-            .cloned()
-            .collect();
-        param_fields.extend(where_fields);
-    }
-
-    // HAVING cümlesindeki alan adlarını bulma
-    if let Some(clause) = &having_clause {
-        let having_fields: Vec<_> = fields
+            .flatten()
+            .flat_map(|clause| bound_fields(clause, &fields, rule))
+            .collect()
+    } else {
+        // INSERT: SQL ifadesiyle yazılan (#[default_sql], Option olmayan) alanlar bağlanmaz
+        named
             .iter()
-            .filter(|&f| clause.contains(f))
-            .cloned()
-            .collect();
-        param_fields.extend(having_fields);
+            .filter(|f| default_sql(f).is_none() || is_option(&f.ty))
+            .map(|f| f.ident.as_ref().unwrap().to_string())
+            .collect()
     }
+}
 
-    // Eğer hiçbir cümlede parametre yoksa, tüm alanları kullan
-    if param_fields.is_empty() {
-        param_fields = fields;
-    }
+/// Clauses holding placeholders, in the order they appear in the statement: USING
+/// (DELETE), WHERE, HAVING, LIMIT, OFFSET.
+fn clauses(input: &DeriveInput) -> [Option<String>; 5] {
+    // Get the optional where_clause attribute
+    let where_clause = clause_attribute(input, "where_clause");
 
-    let field_names: Vec<_> = param_fields
-        .iter()
-        .map(|f| syn::Ident::new(f, struct_name.span()))
-        .collect();
+    // #[partition_by] anahtarı koşulda yoksa bölüm budaması için eklenir
+    let where_clause = partitioned_where_clause(input, where_clause);
+    let where_clause = conditional_where_clause(input, where_clause);
+    let where_clause = spatial_where_clause(input, where_clause);
+    let where_clause = searched_where_clause(input, where_clause);
+
+    [
+        clause_attribute(input, "using"),
+        where_clause,
+        clause_attribute(input, "having"),
+        paging_clause(input, "limit"),
+        paging_clause(input, "offset"),
+    ]
+}
+
+/// Whether the struct is a query binding only the fields its placeholders reference,
+/// rather than an INSERT binding every field.
+fn is_query(input: &DeriveInput) -> bool {
+    clauses(input).iter().any(Option::is_some)
+        || union_attribute(input).is_some()
+        || QUERY_ATTRIBUTES
+            .iter()
+            .any(|name| input.attrs.iter().any(|attr| attr.path().is_ident(name)))
+}
+
+/// Delegates `params()` to the field holding the `#[insert_from(Source)]` query.
+fn derive_insert_from_params(
+    struct_name: &syn::Ident,
+    input: &DeriveInput,
+    source: &syn::Path,
+) -> TokenStream {
+    let field = field_of_type(input, source).unwrap_or_else(|| {
+        panic!(
+            "insert_from requires a named field of type `{}`",
+            quote!(#source)
+        )
+    });
 
     let expanded = quote! {
         impl SqlParams for #struct_name {
             fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
-                vec![#(&self.#field_names as &(dyn ToSql + Sync)),*]
+                <#source as SqlParams>::params(&self.#field)
             }
         }
     };
 
     TokenStream::from(expanded)
 }
+
+/// Reads an optional string attribute such as `#[having("...")]`.
+fn clause_attribute(input: &DeriveInput, name: &str) -> Option<String> {
+    input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(name))
+        .map(|attr| {
+            attr.parse_args::<syn::LitStr>()
+                .unwrap_or_else(|_| panic!("Expected a string literal for {}", name))
+                .value()
+        })
+}
@@ -0,0 +1,34 @@
+use crate::audit::audit_sql_fragment;
+
+/// Ordinary clause fragments pass the audit
+#[test]
+fn test_safe_fragments() {
+    assert!(audit_sql_fragment("id = $ AND state >= $").is_ok());
+    assert!(audit_sql_fragment("INNER JOIN posts p ON p.user_id = users.id").is_ok());
+    assert!(audit_sql_fragment("name = 'O''Brien'").is_ok());
+    assert!(audit_sql_fragment("\"order\" DESC, created_at").is_ok());
+    // Anahtar kelimeler tam kelime olarak aranır
+    assert!(audit_sql_fragment("dropped_at IS NULL").is_ok());
+}
+
+/// Statement separators and comments are rejected
+#[test]
+fn test_separators_and_comments() {
+    assert!(audit_sql_fragment("id = $; DELETE FROM users").is_err());
+    assert!(audit_sql_fragment("id = $ -- ignore the rest").is_err());
+    assert!(audit_sql_fragment("id = $ /* hidden */").is_err());
+}
+
+/// Unbalanced quotes are rejected
+#[test]
+fn test_unbalanced_quotes() {
+    assert!(audit_sql_fragment("name = 'admin").is_err());
+    assert!(audit_sql_fragment("\"users.name = $").is_err());
+}
+
+/// Suspicious keywords are rejected regardless of case
+#[test]
+fn test_suspicious_keywords() {
+    assert!(audit_sql_fragment("id = $ OR 1 = 1 drop table users").is_err());
+    assert!(audit_sql_fragment("TRUNCATE users").is_err());
+}
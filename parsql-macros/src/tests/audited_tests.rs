@@ -0,0 +1,44 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use parsql_core::audit_log::AuditLog;
+use parsql_core::batch::Dialect;
+
+use crate::{audited, dialect};
+
+fn audit_log(source: &str) -> std::thread::Result<Option<AuditLog>> {
+    let input = syn::parse_str(source).unwrap();
+    catch_unwind(AssertUnwindSafe(|| audited::audit_log(&input)))
+}
+
+/// Unset options keep the defaults; SQLite rejects the attribute
+#[test]
+fn test_audit_options() {
+    let bare = audit_log("#[audited] struct DeleteUser { id: i64 }");
+    let custom = audit_log(
+        "#[audited(table = \"audit.changes\", key = \"user_id\")] struct DeleteUser { user_id: i64 }",
+    );
+
+    if dialect() == Dialect::Sqlite {
+        assert!(bare.is_err());
+        assert!(custom.is_err());
+        return;
+    }
+    assert_eq!(bare.unwrap(), Some(AuditLog::default()));
+    let custom = custom.unwrap().unwrap();
+    assert_eq!(custom.table, "audit.changes");
+    assert_eq!(custom.key, "user_id");
+    assert_eq!(custom.actor, "app.current_user_id");
+}
+
+#[test]
+fn test_unaudited_struct() {
+    assert_eq!(audit_log("#[table(\"users\")] struct DeleteUser { id: i64 }").unwrap(), None);
+}
+
+/// Option values are written into the statement, so only plain names are accepted
+#[test]
+fn test_invalid_audit_options() {
+    assert!(audit_log("#[audited(table = \"audit_log; DROP TABLE users\")] struct DeleteUser { id: i64 }").is_err());
+    assert!(audit_log("#[audited(owner = \"admin\")] struct DeleteUser { id: i64 }").is_err());
+    assert!(audit_log("#[audited] #[include_fields(AuditColumns)] struct UpdateUser { id: i64 }").is_err());
+}
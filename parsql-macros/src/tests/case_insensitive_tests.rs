@@ -0,0 +1,49 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use parsql_core::batch::Dialect;
+
+use crate::{case_insensitive_where_clause, dialect};
+
+fn where_clause(source: &str, clause: &str) -> String {
+    case_insensitive_where_clause(&syn::parse_str(source).unwrap(), Some(clause.to_string())).unwrap()
+}
+
+/// Comparisons with a placeholder are lowered on both sides
+#[test]
+fn test_lowered_comparisons() {
+    let source = "struct UserByEmail { #[case_insensitive] email: String, state: i16 }";
+
+    assert_eq!(where_clause(source, "email = $"), "LOWER(email) = LOWER($)");
+    assert_eq!(
+        where_clause(source, "u.EMAIL<>$email AND state = $"),
+        "LOWER(u.EMAIL) <> LOWER($email) AND state = $"
+    );
+    assert_eq!(where_clause(source, "email like $ OR email IS NULL"), "LOWER(email) like LOWER($) OR email IS NULL");
+    assert_eq!(
+        where_clause(source, "backup_email = $ AND note = 'email = $'"),
+        "backup_email = $ AND note = 'email = $'"
+    );
+}
+
+/// SQLite has no `citext` type and rejects the attribute
+#[test]
+fn test_citext_comparisons() {
+    let source = "#[rename_all(\"camelCase\")] struct UserByEmail { #[citext] work_email: String }";
+
+    if dialect() == Dialect::Sqlite {
+        assert!(catch_unwind(AssertUnwindSafe(|| where_clause(source, "\"workEmail\" = $"))).is_err());
+        return;
+    }
+
+    assert_eq!(where_clause(source, "\"workEmail\" = $"), "\"workEmail\" = $::citext");
+    assert_eq!(where_clause(source, "workemail = $"), "workemail = $::citext");
+    assert_eq!(where_clause(source, "\"workemail\" = $"), "\"workemail\" = $");
+}
+
+/// Without the attributes the clause is not touched
+#[test]
+fn test_plain_fields() {
+    let source = "struct UserByEmail { email: String }";
+
+    assert_eq!(where_clause(source, "email = $"), "email = $");
+}
@@ -0,0 +1,45 @@
+use crate::implementations::{from_row, postgres, sqlite};
+
+/// Without `backends` a single impl names the imported `FromRow`, `Row` and `Error`
+#[test]
+fn test_imported_names() {
+    let input = syn::parse_str("struct User { id: i64, name: String }").unwrap();
+    let tokens = from_row(&input, sqlite::generate_from_row).to_string();
+
+    assert!(tokens.starts_with("impl FromRow for User"));
+    assert!(tokens.contains("fn from_row (row : & Row) -> Result < Self , Error >"));
+}
+
+/// Each listed backend gets its own impl in the row format of that backend
+#[test]
+fn test_listed_backends() {
+    let input = syn::parse_str(
+        "#[from_row(backends(parsql::tokio_postgres, parsql::sqlite))]
+        struct User { id: i64, #[from_row(default)] nickname: String }",
+    )
+    .unwrap();
+    let tokens = from_row(&input, postgres::generate_from_row).to_string();
+
+    assert_eq!(tokens.matches("FromRow for User").count(), 2);
+    assert!(tokens.contains("impl parsql :: tokio_postgres :: traits :: FromRow for User"));
+    assert!(tokens.contains("row : & parsql :: tokio_postgres :: Row"));
+    assert!(tokens.contains("row . try_get (\"id\") ?"));
+    assert!(tokens.contains("impl parsql :: sqlite :: traits :: FromRow for User"));
+    assert!(tokens.contains("Err (parsql :: sqlite :: Error :: InvalidColumnName (_))"));
+}
+
+/// Named structs read each row at column positions resolved once per result set,
+/// falling back to the column name when the position is unknown
+#[test]
+fn test_positioned_read() {
+    let input = syn::parse_str("struct User { id: i64, #[from_row(default)] nickname: String }").unwrap();
+    let tokens = from_row(&input, postgres::generate_from_row).to_string();
+
+    assert!(tokens.contains("fn column_positions (columns : & [& str])"));
+    assert!(tokens.contains("[\"id\" , \"nickname\"]"));
+    assert!(tokens.contains("match positions . get (0usize) . copied () . flatten () { Some (index) => row . try_get (index) ? , None => row . try_get (\"id\") ? , }"));
+    assert!(tokens.contains("None => Default :: default ()"));
+
+    let tuple = syn::parse_str("struct UserId(i64);").unwrap();
+    assert!(!from_row(&tuple, sqlite::generate_from_row).to_string().contains("column_positions"));
+}
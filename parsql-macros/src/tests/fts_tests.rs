@@ -0,0 +1,69 @@
+use parsql_core::batch::Dialect;
+
+use crate::{full_text_search, FullTextSearch, placeholder_fields, searched_where_clause};
+
+fn parse(source: &str) -> syn::DeriveInput {
+    syn::parse_str(source).unwrap()
+}
+
+/// The search condition binds the query field and follows the WHERE clause
+#[test]
+fn test_search_condition() {
+    let input = parse("#[fts(\"search_vector\", query = \"terms\", rank)]
+        struct SearchPosts { id: i64, title: String, state: i16, terms: String }");
+    let search = full_text_search(&input).unwrap();
+
+    assert_eq!(
+        search.condition(Dialect::Postgres),
+        "search_vector @@ plainto_tsquery($terms)"
+    );
+    assert_eq!(search.condition(Dialect::Sqlite), "search_vector MATCH $terms");
+    assert_eq!(
+        search.rank_order(Dialect::Postgres, "$2").as_deref(),
+        Some("ts_rank(search_vector, plainto_tsquery($2)) DESC")
+    );
+    assert_eq!(search.rank_order(Dialect::Sqlite, "?2").as_deref(), Some("rank"));
+
+    let clause = searched_where_clause(&input, Some("state = $".to_string())).unwrap();
+    let fields = ["id", "title", "state", "terms"].map(String::from);
+    assert_eq!(placeholder_fields(&clause, &fields), ["state", "terms"]);
+}
+
+/// The configuration is passed to every `plainto_tsquery`
+#[test]
+fn test_config() {
+    let search = FullTextSearch {
+        column: "search_vector".to_string(),
+        query: "terms".to_string(),
+        config: Some("english".to_string()),
+        rank: true,
+    };
+
+    assert_eq!(search.condition(Dialect::Postgres), "search_vector @@ plainto_tsquery('english', $terms)");
+    assert_eq!(
+        search.rank_order(Dialect::Postgres, "$1").as_deref(),
+        Some("ts_rank(search_vector, plainto_tsquery('english', $1)) DESC")
+    );
+}
+
+/// Without `query = ...` the terms come from a field named `query`, unranked
+#[test]
+fn test_defaults() {
+    let search = full_text_search(&parse("#[fts(\"posts_fts\")] struct SearchPosts { id: i64, query: String }")).unwrap();
+
+    assert_eq!((search.query.as_str(), search.config.as_deref(), search.rank), ("query", None, false));
+    assert_eq!(search.rank_order(Dialect::Postgres, "$1"), None);
+    assert!(full_text_search(&parse("struct Posts { id: i64 }")).is_none());
+}
+
+#[test]
+#[should_panic(expected = "fts query field `terms` not found")]
+fn test_missing_query_field() {
+    full_text_search(&parse("#[fts(\"search_vector\", query = \"terms\")] struct SearchPosts { id: i64 }"));
+}
+
+#[test]
+#[should_panic(expected = "fts expects a plain column name")]
+fn test_rejects_expressions() {
+    full_text_search(&parse("#[fts(\"to_tsvector(body)\")] struct SearchPosts { query: String }"));
+}
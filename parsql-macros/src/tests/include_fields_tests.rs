@@ -0,0 +1,52 @@
+use crate::implementations::{from_row, postgres, sqlite};
+use crate::included_fields;
+
+/// The attribute names the base model; the field holding it is found by its type
+#[test]
+fn test_included_field() {
+    let input = syn::parse_str(
+        "#[include_fields(models::AuditColumns)]
+        struct Post { id: i64, title: String, audit: AuditColumns }",
+    )
+    .unwrap();
+    let (base, field) = included_fields(&input).unwrap();
+
+    assert_eq!(quote::quote!(#base).to_string(), "models :: AuditColumns");
+    assert_eq!(field, "audit");
+}
+
+#[test]
+#[should_panic(expected = "include_fields requires a named field of type `AuditColumns`")]
+fn test_missing_field() {
+    let input = syn::parse_str("#[include_fields(AuditColumns)] struct Post { id: i64 }").unwrap();
+    included_fields(&input);
+}
+
+/// A base model lists its columns, named by `rename_all` and quoted when mixed case
+#[test]
+fn test_base_columns() {
+    let input = syn::parse_str(
+        "#[rename_all(\"camelCase\")]
+        struct AuditColumns { created_at: String, state: i16 }",
+    )
+    .unwrap();
+    let tokens = from_row(&input, sqlite::generate_from_row).to_string();
+
+    assert!(tokens.contains("impl AuditColumns"));
+    assert!(tokens.contains("pub const COLUMNS : & 'static [& 'static str] = & [\"\\\"createdAt\\\"\" , \"state\"]"));
+}
+
+/// The including model reads the base through the base's impl and has no COLUMNS of its own
+#[test]
+fn test_nested_read() {
+    let input = syn::parse_str(
+        "#[include_fields(AuditColumns)]
+        struct Post { id: i64, audit: AuditColumns }",
+    )
+    .unwrap();
+    let tokens = from_row(&input, postgres::generate_from_row).to_string();
+
+    assert!(tokens.contains("audit : < AuditColumns as FromRow > :: from_row (row) ?"));
+    assert!(tokens.contains("id : row . try_get (\"id\") ?"));
+    assert!(!tokens.contains("COLUMNS"));
+}
@@ -0,0 +1,66 @@
+use crate::joins::{column_qualifiers, declared_tables, verify};
+use crate::{number_where_clause_params, placeholder_fields, SqlParamCounter};
+use parsql_core::batch::Dialect;
+
+/// A join declares its alias, or its table when it has none
+#[test]
+fn test_declared_tables() {
+    assert_eq!(declared_tables("LEFT JOIN posts p ON p.user_id = users.id"), vec!["p"]);
+    assert_eq!(declared_tables("INNER JOIN public.posts AS p ON p.user_id = users.id"), vec!["p"]);
+    assert_eq!(
+        declared_tables("JOIN posts ON posts.user_id = users.id LEFT JOIN comments c USING (post_id)"),
+        vec!["posts", "c"]
+    );
+    assert_eq!(declared_tables("CROSS JOIN LATERAL (SELECT 1 FROM tags t) x"), vec!["x", "t"]);
+    assert_eq!(declared_tables("JOIN \"Posts\" ON true"), vec!["Posts"]);
+}
+
+/// Qualifiers are read from columns, not from strings, numbers or function calls
+#[test]
+fn test_column_qualifiers() {
+    assert_eq!(column_qualifiers("p.created_at DESC, users.id"), vec!["p", "users"]);
+    assert_eq!(column_qualifiers("COUNT(c.*) > $ AND public.posts.state = 1"), vec!["c", "posts"]);
+    assert!(column_qualifiers("pg_catalog.lower(name) = 'a.b' AND score > 1.5").is_empty());
+    assert_eq!(column_qualifiers("\"P\".\"createdAt\" IS NULL"), vec!["P"]);
+}
+
+/// Unknown qualifiers are reported, the base table and subquery tables are known
+#[test]
+fn test_unknown_qualifier() {
+    let valid: syn::DeriveInput = syn::parse_quote! {
+        #[table("users")]
+        #[join("LEFT JOIN posts p ON p.user_id = users.id")]
+        #[where_clause("p.state = $ AND EXISTS (SELECT 1 FROM comments c WHERE c.post_id = p.id)")]
+        #[order_by("p.created_at DESC")]
+        struct UserPosts {
+            state: i16,
+        }
+    };
+    assert!(verify(&valid).is_ok());
+
+    let invalid: syn::DeriveInput = syn::parse_quote! {
+        #[table("users")]
+        #[join("LEFT JOIN posts p ON p.user_id = users.id")]
+        #[order_by("posts.created_at DESC")]
+        struct UserPosts {
+            state: i16,
+        }
+    };
+    let error = verify(&invalid).unwrap_err().to_string();
+    assert_eq!(error, "unknown table or alias `posts` in `#[order_by]`; the query declares `users`, `p`");
+}
+
+/// Placeholders after qualified columns are numbered in order and bind the column's field
+#[test]
+fn test_qualified_placeholders() {
+    let clause = "p.state = $ AND users.name = $ AND p.created_at > $";
+    let mut counter = SqlParamCounter::with_dialect(Dialect::Postgres);
+    assert_eq!(
+        number_where_clause_params(clause, &mut counter),
+        "p.state = $1 AND users.name = $2 AND p.created_at > $3"
+    );
+
+    // `p` bir alan olsa da tablo öneki olarak bağlanmaz
+    let fields = ["p", "state", "name", "created_at"].map(String::from);
+    assert_eq!(placeholder_fields(clause, &fields), vec!["state", "name", "created_at"]);
+}
@@ -0,0 +1,87 @@
+use crate::{bound_fields, pluralize, query_builder, quote_column, quote_identifiers, quote_name, snake_case, table_name, RenameRule};
+
+/// Struct names become snake_case plurals
+#[test]
+fn test_inferred_table_names() {
+    let table = |source: &str| table_name(&syn::parse_str(source).unwrap());
+
+    assert_eq!(table("struct User { id: i64 }"), "users");
+    assert_eq!(table("struct OrderItem { id: i64 }"), "order_items");
+    assert_eq!(table("struct Category { id: i64 }"), "categories");
+    assert_eq!(table("struct Address { id: i64 }"), "addresses");
+    assert_eq!(table("#[table(\"people\")] struct Person { id: i64 }"), "people");
+}
+
+#[test]
+fn test_snake_case_and_plural() {
+    assert_eq!(snake_case("HTTPRequest"), "http_request");
+    assert_eq!(snake_case("UserV2Token"), "user_v2_token");
+    assert_eq!(pluralize("key"), "keys");
+    assert_eq!(pluralize("batch"), "batches");
+}
+
+/// Column conventions and the quoting of mixed-case names
+#[test]
+fn test_rename_rules() {
+    assert_eq!(RenameRule::None.apply("user_name"), "user_name");
+    assert_eq!(RenameRule::CamelCase.apply("user_name"), "userName");
+    assert_eq!(RenameRule::PascalCase.apply("user_name"), "UserName");
+    assert_eq!(quote_column("userName", true), "\"userName\"");
+    assert_eq!(quote_column("user_name", true), "user_name");
+}
+
+/// Reserved words are quoted too, unless the struct opts out with #[no_quote]
+#[test]
+fn test_reserved_identifiers() {
+    assert_eq!(quote_column("order", true), "\"order\"");
+    assert_eq!(quote_column("userName", false), "userName");
+    assert_eq!(quote_name("order", true), "\"order\"");
+    assert_eq!(quote_name("order", false), "order");
+
+    let mut builder = query_builder::SafeQueryBuilder::new();
+    builder.add_keyword("SELECT id FROM");
+    builder.add_identifier("user");
+    assert_eq!(builder.build(), "SELECT id FROM \"user\"");
+
+    let quoting = |source: &str| quote_identifiers(&syn::parse_str(source).unwrap());
+    assert!(quoting("#[table(\"order\")] struct Order { id: i64 }"));
+    assert!(!quoting("#[no_quote] #[table(\"order\")] struct Order { id: i64 }"));
+}
+
+/// Table and column names given in attributes are quoted when they have upper case
+/// letters, so PostgreSQL keeps their case
+#[test]
+fn test_mixed_case_names_are_quoted() {
+    assert_eq!(quote_name("UserAccounts", true), "\"UserAccounts\"");
+    assert_eq!(quote_name("createdAt", true), "\"createdAt\"");
+    assert_eq!(quote_name("user_accounts", true), "user_accounts");
+    assert_eq!(quote_name("UserAccounts", false), "UserAccounts");
+
+    let mut builder = query_builder::SafeQueryBuilder::new();
+    builder.add_keyword("INSERT INTO");
+    builder.add_identifier("UserAccounts");
+    builder.add_keyword("RETURNING");
+    builder.add_identifier("UserId");
+    assert_eq!(builder.build(), "INSERT INTO \"UserAccounts\" RETURNING \"UserId\"");
+
+    let mut builder = query_builder::SafeQueryBuilder::new().quoting(false);
+    builder.add_keyword("SELECT id FROM");
+    builder.add_identifier("UserAccounts");
+    assert_eq!(builder.build(), "SELECT id FROM UserAccounts");
+}
+
+/// Clauses may name either the field or its renamed column
+#[test]
+fn test_bound_fields_with_renamed_columns() {
+    let fields = vec!["user_id".to_string(), "created_at".to_string()];
+
+    let bound = bound_fields(
+        "\"userId\" = $ AND \"createdAt\" > $",
+        &fields,
+        RenameRule::CamelCase,
+    );
+    assert_eq!(bound, vec!["user_id", "created_at"]);
+
+    let bound = bound_fields("\"createdAt\" > $user_id", &fields, RenameRule::CamelCase);
+    assert_eq!(bound, vec!["user_id"]);
+}
@@ -0,0 +1,37 @@
+use crate::sql_params::param_fields;
+use crate::update_params::update_param_fields;
+
+fn names(source: &str) -> Vec<String> {
+    param_fields(&syn::parse_str(source).unwrap())
+}
+
+/// Queries list the fields in placeholder order, across clauses
+#[test]
+fn test_query_fields() {
+    let source = "#[table(\"users\")]
+        #[where_clause(\"state = $ AND name = $\")]
+        #[limit(\"$page_size\")]
+        struct ActiveUsers { id: i64, name: String, state: i16, page_size: i64 }";
+
+    assert_eq!(names(source), ["state", "name", "page_size"]);
+}
+
+/// Inserts bind every field except those written with a SQL expression
+#[test]
+fn test_insert_fields() {
+    let source = "#[table(\"users\")]
+        struct InsertUser { name: String, #[default_sql(\"now()\")] created_at: String, state: i16 }";
+
+    assert_eq!(names(source), ["name", "state"]);
+}
+
+/// Updates bind the SET columns before the WHERE clause
+#[test]
+fn test_update_fields() {
+    let source = "#[table(\"users\")]
+        #[update(\"name, state\")]
+        #[where_clause(\"id = $\")]
+        struct UpdateUser { id: i64, name: String, state: i16 }";
+
+    assert_eq!(update_param_fields(&syn::parse_str(source).unwrap()), ["name", "state", "id"]);
+}
@@ -0,0 +1,66 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use parsql_core::batch::Dialect;
+
+use crate::{dialect, partition_warning, partitioned_impl, partitioned_where_clause};
+
+fn where_clause(source: &str, clause: Option<&str>) -> Option<String> {
+    partitioned_where_clause(&syn::parse_str(source).unwrap(), clause.map(String::from))
+}
+
+/// The key is added after the existing condition and bound by name
+#[test]
+fn test_partition_key_is_added() {
+    let source = "#[partition_by(\"created_at\")] struct DeleteEvent { id: i64, created_at: i64 }";
+
+    assert_eq!(
+        where_clause(source, Some("id = $ OR parent_id = $id")).as_deref(),
+        Some("(id = $ OR parent_id = $id) AND created_at = $created_at")
+    );
+    assert_eq!(where_clause(source, None).as_deref(), Some("created_at = $created_at"));
+    assert_eq!(
+        where_clause(source, Some("id = $ AND created_at >= $")).as_deref(),
+        Some("id = $ AND created_at >= $")
+    );
+}
+
+#[test]
+fn test_renamed_partition_key() {
+    let source = "#[partition_by(\"createdAt\")] #[rename_all(\"camelCase\")] struct DeleteEvent { id: i64, created_at: i64 }";
+
+    assert_eq!(
+        where_clause(source, Some("id = $")).as_deref(),
+        Some("(id = $) AND \"createdAt\" = $created_at")
+    );
+}
+
+/// Without a field holding the key the clause stays as it is, with a warning
+#[test]
+fn test_missing_partition_key_warns() {
+    let source = "#[partition_by(\"created_at\")] struct DeleteEvent { id: i64 }";
+    let input = syn::parse_str(source).unwrap();
+
+    assert_eq!(where_clause(source, Some("id = $")).as_deref(), Some("id = $"));
+    assert!(partition_warning(&input, Some("id = $")).to_string().contains("deprecated"));
+    assert!(partition_warning(&input, Some("created_at < $")).is_empty());
+}
+
+/// PostgreSQL only; SQLite rejects the attribute
+#[test]
+fn test_partitioned_table() {
+    let input = syn::parse_str("#[partitioned(by = \"range(created_at)\")] struct InsertEvent { created_at: i64 }").unwrap();
+    let generated = catch_unwind(AssertUnwindSafe(|| partitioned_impl(&input, "audit.events").to_string()));
+    assert!(partitioned_impl(&syn::parse_str("struct InsertEvent { id: i64 }").unwrap(), "events").is_empty());
+
+    if dialect() == Dialect::Sqlite {
+        assert!(generated.is_err());
+        return;
+    }
+    let generated = generated.unwrap();
+    assert!(generated.contains("impl Partitioned for InsertEvent"));
+    assert!(generated.contains("\"audit.events\""));
+    assert!(generated.contains("\"created_at\""));
+
+    let hashed = syn::parse_str("#[partitioned(by = \"hash(id)\")] struct InsertEvent { id: i64 }").unwrap();
+    assert!(catch_unwind(AssertUnwindSafe(|| partitioned_impl(&hashed, "events"))).is_err());
+}
@@ -0,0 +1,19 @@
+use crate::check_required_order;
+
+fn check(source: &str, paged: bool) {
+    check_required_order(&syn::parse_str(source).unwrap(), paged);
+}
+
+/// Ordered or unpaged queries pass, and the check is opt-in
+#[test]
+fn test_ordered_pages_pass() {
+    check("#[require_order] #[order_by(\"id\")] #[limit(\"10\")] struct Users { id: i64 }", true);
+    check("#[require_order] struct Users { id: i64 }", false);
+    check("#[limit(\"10\")] struct Users { id: i64 }", true);
+}
+
+#[test]
+#[should_panic(expected = "require_order: `Users` is paged with LIMIT / OFFSET but has no #[order_by]")]
+fn test_unordered_page() {
+    check("#[require_order] #[offset(\"$skip\")] struct Users { id: i64, skip: i64 }", true);
+}
@@ -0,0 +1,61 @@
+use crate::schema_check::{verify, Statement};
+use parsql_core::schema::Schema;
+
+fn schema() -> Schema {
+    let mut schema = Schema::new();
+    schema.add_column("users", "id", "int8", false);
+    schema.add_column("users", "name", "text", false);
+    schema.add_column("users", "createdAt", "timestamptz", true);
+    schema
+}
+
+fn check(source: &str, statement: Statement) -> Result<(), String> {
+    verify(&schema(), &syn::parse_str(source).unwrap(), statement).map_err(|e| e.to_string())
+}
+
+/// Structs matching the snapshot pass, renamed columns included
+#[test]
+fn test_matching_struct() {
+    let source = "#[table(\"users\")]
+        #[rename_all(\"camelCase\")]
+        struct GetUser { id: i64, name: Option<String>, created_at: chrono::DateTime<chrono::Utc> }";
+
+    assert_eq!(check(source, Statement::Select), Ok(()));
+}
+
+/// Missing tables and columns are reported by name
+#[test]
+fn test_missing_table_and_column() {
+    let missing_table = "#[table(\"accounts\")] struct DeleteAccount { id: i64 }";
+    let missing_column = "#[table(\"users\")] struct InsertUser { name: String, email: String }";
+
+    assert_eq!(check(missing_table, Statement::Delete), Err("table `accounts` does not exist".to_string()));
+    assert_eq!(
+        check(missing_column, Statement::Insert),
+        Err("column `email` does not exist in table `users`".to_string())
+    );
+}
+
+/// Only the `#[update]` columns of an update are checked against their fields
+#[test]
+fn test_update_columns() {
+    let source = "#[table(\"users\")]
+        #[update(\"name\")]
+        #[where_clause(\"id = $\")]
+        struct UpdateUser { id: String, name: i64 }";
+
+    assert_eq!(
+        check(source, Statement::Update),
+        Err("field `name` of type `i64` cannot hold column `users.name` of type `text`".to_string())
+    );
+}
+
+/// Custom SELECT lists are not checked
+#[test]
+fn test_custom_select_skipped() {
+    let source = "#[table(\"users\")]
+        #[select(\"id, upper(name) AS shout\")]
+        struct Shout { id: i64, shout: String }";
+
+    assert_eq!(check(source, Statement::Select), Ok(()));
+}
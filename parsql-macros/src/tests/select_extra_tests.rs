@@ -0,0 +1,23 @@
+use crate::select_extras;
+
+fn extras(source: &str) -> Vec<(String, Option<String>)> {
+    select_extras(&syn::parse_str(source).unwrap())
+}
+
+/// The alias names the field read from the expression
+#[test]
+fn test_aliases() {
+    let source = "#[select_extra(\"COUNT(*) OVER() AS total_rows\", \"upper(name) as \\\"shout\\\"\")]
+        #[select_extra(\"now()\")]
+        struct UsersWithTotal { id: i64, total_rows: i64, shout: String }";
+
+    assert_eq!(
+        extras(source),
+        [
+            ("COUNT(*) OVER() AS total_rows".to_string(), Some("total_rows".to_string())),
+            ("upper(name) as \"shout\"".to_string(), Some("shout".to_string())),
+            ("now()".to_string(), None),
+        ]
+    );
+    assert!(extras("struct Users { id: i64 }").is_empty());
+}
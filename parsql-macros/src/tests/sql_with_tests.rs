@@ -0,0 +1,20 @@
+use crate::param_values;
+
+/// Marked fields are bound through their `SqlWith` marker, the others by reference
+#[test]
+fn test_sql_with_binding() {
+    let input = syn::parse_str(
+        "struct InsertUser { name: String, #[sql_with(normalize::email)] email: String }",
+    )
+    .unwrap();
+    let fields = vec!["email".to_string(), "name".to_string()];
+
+    let (values, markers) = param_values(&input, &fields);
+    let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+
+    assert_eq!(values[0], "< __sql_with_email as SqlWith > :: bind (& self . email)");
+    assert_eq!(values[1], "& self . name as & (dyn ToSql + Sync)");
+    let markers = markers.to_string();
+    assert!(markers.contains("impl SqlWith for __sql_with_email"));
+    assert!(markers.contains("Box :: new (normalize :: email (value))"));
+}
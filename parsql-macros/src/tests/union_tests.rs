@@ -0,0 +1,31 @@
+use crate::{union_attribute, union_template};
+
+/// The keyword follows the attribute; the other query is a type path
+#[test]
+fn test_union_attribute() {
+    let input = syn::parse_str("#[union_all(archive::Orders)] struct AllOrders { archived: archive::Orders }").unwrap();
+    let (keyword, other) = union_attribute(&input).unwrap();
+    assert_eq!((keyword, quote::quote!(#other).to_string()), ("UNION ALL", "archive :: Orders".to_string()));
+
+    let input = syn::parse_str("#[union(Orders)] struct AllOrders { archived: Orders }").unwrap();
+    assert_eq!(union_attribute(&input).unwrap().0, "UNION");
+    assert!(union_attribute(&syn::parse_str("struct Orders { id: i64 }").unwrap()).is_none());
+}
+
+/// Each member keeps its own ORDER BY / LIMIT; the tail orders the combined rows
+#[test]
+fn test_parenthesized_members() {
+    let head = "SELECT id FROM orders WHERE user_id = $1";
+    let other = "SELECT id FROM archived_orders WHERE user_id = $2 ORDER BY id DESC LIMIT 5";
+
+    let (prefix, suffix) = union_template(head, "UNION ALL", " ORDER BY id");
+    assert_eq!(
+        format!("{}{}{}", prefix, other, suffix),
+        "SELECT * FROM (SELECT id FROM orders WHERE user_id = $1) AS u1 UNION ALL \
+         SELECT * FROM (SELECT id FROM archived_orders WHERE user_id = $2 ORDER BY id DESC LIMIT 5) AS u2 ORDER BY id"
+    );
+
+    let (prefix, suffix) = union_template(head, "UNION", "");
+    assert_eq!(format!("{}{}{}", prefix, other, suffix).matches(" AS u").count(), 2);
+    assert!(suffix.ends_with(") AS u2"));
+}
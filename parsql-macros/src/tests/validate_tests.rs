@@ -0,0 +1,37 @@
+use crate::validate::validation_method;
+
+/// Field rules become checks on the field, `Option` fields are checked only when set
+#[test]
+fn test_field_rules() {
+    let input = syn::parse_str(
+        "struct InsertUser {
+            #[validate(email)] email: String,
+            #[validate(length(min = 2, max = 64), custom = names::check)] name: Option<String>,
+            state: i16,
+        }",
+    )
+    .unwrap();
+
+    let method = validation_method(&input).to_string();
+
+    assert!(method.starts_with("fn validation (& self) -> Result < () , ValidationError >"));
+    assert!(method.contains("let value = & self . email ; ValidationError :: check_email (\"email\" , value) ?"));
+    assert!(method.contains("if let Some (value) = & self . name"));
+    assert!(method.contains("ValidationError :: check_length (\"name\" , value , Some (2) , Some (64)) ?"));
+    assert!(method.contains("names :: check (value) . map_err"));
+    assert!(!method.contains("state"));
+    assert!(!method.contains("Validate >"));
+}
+
+/// A bare `#[validate]` on the struct calls its `Validate` impl; without any
+/// attribute the trait's default is kept
+#[test]
+fn test_struct_level_validation() {
+    let input = syn::parse_str("#[validate] struct UpdateRange { low: i32, high: i32 }").unwrap();
+    assert!(validation_method(&input)
+        .to_string()
+        .contains("< Self as Validate > :: validate (self) ?"));
+
+    let input = syn::parse_str("struct UpdateRange { low: i32, high: i32 }").unwrap();
+    assert!(validation_method(&input).is_empty());
+}
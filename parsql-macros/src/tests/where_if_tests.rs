@@ -0,0 +1,40 @@
+use crate::{conditional_where_clause, placeholder_fields, where_if_conditions, WHERE_IF_GUARD};
+
+fn where_clause(source: &str, clause: Option<&str>) -> Option<String> {
+    conditional_where_clause(&syn::parse_str(source).unwrap(), clause.map(String::from))
+}
+
+/// Every condition gets its own flag and follows the fixed WHERE clause
+#[test]
+fn test_conditions_are_guarded() {
+    let source = "#[where_if(\"state = $\", when = \"state.is_some()\")]
+        #[where_if(\"name LIKE $name\", when = \"!name.is_empty()\")]
+        struct FindUsers { tenant_id: i64, state: Option<i16>, name: String }";
+
+    assert_eq!(
+        where_clause(source, Some("tenant_id = $")).as_deref(),
+        Some("(tenant_id = $) AND (NOT $__where_if_0 OR (state = $)) AND (NOT $__where_if_1 OR (name LIKE $name))")
+    );
+    assert_eq!(
+        where_clause(source, None).as_deref(),
+        Some("(NOT $__where_if_0 OR (state = $)) AND (NOT $__where_if_1 OR (name LIKE $name))")
+    );
+    assert_eq!(where_clause("struct FindUsers { id: i64 }", Some("id = $")).as_deref(), Some("id = $"));
+}
+
+/// The flag binds before the fields of its condition
+#[test]
+fn test_flags_bind_in_order() {
+    let source = "#[where_if(\"state = $\", when = \"state.is_some()\")] struct FindUsers { id: i64, state: Option<i16> }";
+    let clause = where_clause(source, Some("id > $")).unwrap();
+    let fields = ["id".to_string(), "state".to_string(), format!("{}0", WHERE_IF_GUARD)];
+
+    assert_eq!(placeholder_fields(&clause, &fields), ["id", "__where_if_0", "state"]);
+}
+
+#[test]
+#[should_panic(expected = "Expected #[where_if(\"condition\", when = \"expression\")]")]
+fn test_missing_when() {
+    let input = syn::parse_str("#[where_if(\"state = $\")] struct FindUsers { state: Option<i16> }").unwrap();
+    where_if_conditions(&input);
+}
@@ -3,24 +3,70 @@ use quote::quote;
 use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
 use crate::{
-    extract_fields_from_where_clause, log_message, number_where_clause_params, query_builder,
-    SqlParamCounter,
+    bound_fields, field_for_column, included_fields, param_values, partitioned_where_clause,
+    rename_rule, update_expressions,
 };
 
-
 pub(crate) fn derive_update_params_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = &input.ident;
 
+    let [update_fields, expr_fields, from_fields, condition_fields] = param_groups(&input);
+    // #[include_fields] temel modelinin değerleri en sona eklenir
+    let included = included_fields(&input);
+
+    let (update_values, markers) = param_values(&input, &update_fields);
+    let (expr_values, _) = param_values(&input, &expr_fields);
+    let (from_values, _) = param_values(&input, &from_fields);
+    let (condition_values, _) = param_values(&input, &condition_fields);
+
+    let base_values = match included {
+        Some((base, field)) => {
+            let field = quote::format_ident!("{}", field);
+            quote! { <#base as SqlParams>::params(&self.#field) }
+        }
+        None => quote! { Vec::new() },
+    };
+
+    let expanded = quote! {
+        impl UpdateParams for #struct_name {
+            fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+                #markers
+                let update_values: Vec<&(dyn ToSql + Sync)> = vec![#(#update_values),*];
+                let expr_values: Vec<&(dyn ToSql + Sync)> = vec![#(#expr_values),*];
+                let from_values: Vec<&(dyn ToSql + Sync)> = vec![#(#from_values),*];
+                let condition_values: Vec<&(dyn ToSql + Sync)> = vec![#(#condition_values),*];
+                let base_values: Vec<&(dyn ToSql + Sync)> = #base_values;
+
+                [update_values, expr_values, from_values, condition_values, base_values].concat()
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Fields the `UpdateParams` impl binds, in placeholder order: SET columns, `#[update_expr]`
+/// expressions, `#[from]`, then the WHERE clause.
+///
+/// An `#[include_fields]` base model appends its own parameters after these at runtime.
+pub(crate) fn update_param_fields(input: &DeriveInput) -> Vec<String> {
+    param_groups(input).concat()
+}
+
+/// The bound fields, one list per group of placeholders.
+fn param_groups(input: &DeriveInput) -> [Vec<String>; 4] {
     // Get the update attribute
     let update = input
         .attrs
         .iter()
         .find(|attr| attr.path().is_ident("update"))
-        .expect("Missing `#[update = \"...\"]` attribute")
-        .parse_args::<syn::LitStr>()
-        .expect("Expected a string literal for update")
-        .value();
+        .map(|attr| {
+            attr.parse_args::<syn::LitStr>()
+                .expect("Expected a string literal for update")
+                .value()
+        })
+        .unwrap_or_default();
 
     // Get the where_clause attribute
     let where_clause = input
@@ -31,6 +77,9 @@ pub(crate) fn derive_update_params_impl(input: TokenStream) -> TokenStream {
         .parse_args::<syn::LitStr>()
         .expect("Expected a string literal for where_clause")
         .value();
+    let where_clause = partitioned_where_clause(input, Some(where_clause)).unwrap_or_default();
+
+    let included_field = included_fields(input).map(|(_, field)| field);
 
     let fields = if let Data::Struct(data) = &input.data {
         if let Fields::Named(fields) = &data.fields {
@@ -38,6 +87,7 @@ pub(crate) fn derive_update_params_impl(input: TokenStream) -> TokenStream {
                 .named
                 .iter()
                 .map(|f| f.ident.as_ref().unwrap().to_string())
+                .filter(|f| included_field.as_ref() != Some(f))
                 .collect::<Vec<_>>()
         } else {
             panic!("UpdateParams can only be derived for structs with named fields");
@@ -49,32 +99,36 @@ pub(crate) fn derive_update_params_impl(input: TokenStream) -> TokenStream {
     // Get fields to be used for update
     let update_fields: Vec<String> = update.split(',').map(|s| s.trim().to_string()).collect();
 
-    // Get fields to be used in the where clause
-    let condition_fields = extract_fields_from_where_clause(&where_clause);
+    // Cümleler alan adlarını ya da #[rename_all] ile türetilen sütun adlarını kullanabilir
+    let rule = rename_rule(input);
 
-    // Create field names
-    let update_field_names: Vec<_> = update_fields
+    // update_expr ifadelerindeki parametreler sütunlardan sonra gelir
+    let expr_fields: Vec<String> = update_expressions(input)
         .iter()
-        .filter_map(|col| fields.iter().find(|field| *field == col))
-        .map(|f| syn::Ident::new(f, struct_name.span()))
+        .flat_map(|expr| bound_fields(expr, &fields, rule))
         .collect();
 
-    let condition_field_names: Vec<_> = condition_fields
+    // UPDATE ... FROM parametreleri SET ile WHERE arasında bağlanır
+    let from_fields: Vec<String> = input
+        .attrs
         .iter()
-        .filter_map(|col| fields.iter().find(|field| *field == col))
-        .map(|f| syn::Ident::new(f, struct_name.span()))
-        .collect();
+        .find(|attr| attr.path().is_ident("from"))
+        .map(|attr| {
+            let from = attr
+                .parse_args::<syn::LitStr>()
+                .expect("Expected a string literal for from")
+                .value();
+            bound_fields(&from, &fields, rule)
+        })
+        .unwrap_or_default();
 
-    let expanded = quote! {
-        impl UpdateParams for #struct_name {
-            fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
-                let update_values: Vec<&(dyn ToSql + Sync)> = vec![#(&self.#update_field_names as &(dyn ToSql + Sync)),*];
-                let condition_values: Vec<&(dyn ToSql + Sync)> = vec![#(&self.#condition_field_names as &(dyn ToSql + Sync)),*];
+    // Get fields to be used in the where clause, in placeholder order
+    let condition_fields = bound_fields(&where_clause, &fields, rule);
 
-                [update_values, condition_values].concat()
-            }
-        }
-    };
+    let update_fields: Vec<String> = update_fields
+        .iter()
+        .filter_map(|col| field_for_column(&fields, rule, col).cloned())
+        .collect();
 
-    TokenStream::from(expanded)
-}
\ No newline at end of file
+    [update_fields, expr_fields, from_fields, condition_fields]
+}
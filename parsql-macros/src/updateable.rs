@@ -1,39 +1,65 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use crate::audit;
+use crate::audited;
+use crate::schema_check;
+use crate::update_params::update_param_fields;
+use crate::validate;
 use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
+use parsql_core::audit_log::Operation;
+
 use crate::{
-    extract_fields_from_where_clause, log_message, number_where_clause_params, query_builder,
-    SqlParamCounter,
+    case_insensitive_where_clause, dialect, extract_fields_from_where_clause, field_for_column, included_fields, log_message,
+    number_where_clause_params, partition_warning, partitioned_where_clause, query_builder,
+    query_metadata, query_registration, quote_column, quote_identifiers, quote_name, reject_registration, rename_rule, spliced_query, table_name,
+    update_expressions, Splice, SqlParamCounter,
 };
 
 /// Implements the Updateable derive macro.
 pub(crate) fn derive_updateable_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = &input.ident;
+    let (impl_generics, ty_generics, generic_where) = input.generics.split_for_impl();
+
+    // Ham SQL içeren öznitelikleri derleme zamanında denetle
+    if let Some(error) = audit::audit_attributes(&input) {
+        return error;
+    }
+
+    // schema-check açıksa tablo ve sütunlar şema görüntüsüyle karşılaştırılır
+    if let Some(error) = schema_check::check(&input, schema_check::Statement::Update) {
+        return error;
+    }
 
     // Extract table attribute
-    let table = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("table"))
-        .expect("Missing `#[table = \"...\"]` attribute")
-        .parse_args::<syn::LitStr>()
-        .expect("Expected a string literal for `table`")
-        .value();
+    let table = table_name(&input);
 
     // Extract columns attribute
     let columns_attr = input
         .attrs
         .iter()
         .find(|attr| attr.path().is_ident("update"))
-        .expect("Missing `#[update = \"...\"]` attribute")
-        .parse_args::<syn::LitStr>()
-        .expect("Expected a string literal for `columns`")
-        .value();
+        .map(|attr| {
+            attr.parse_args::<syn::LitStr>()
+                .expect("Expected a string literal for `columns`")
+                .value()
+        });
+
+    // SET listesine eklenecek serbest ifadeler (view_count = view_count + $ gibi)
+    let update_exprs = update_expressions(&input);
+
+    // #[include_fields] temel modelinin sütunları SET listesinin sonuna eklenir
+    let included = included_fields(&input);
+    let included_field = included.as_ref().map(|(_, field)| field.clone());
+
+    if columns_attr.is_none() && update_exprs.is_empty() && included.is_none() {
+        panic!("Missing `#[update(\"...\")]` or `#[update_expr(\"...\")]` attribute");
+    }
 
     let column_order: Vec<String> = columns_attr
-        .split(',')
+        .iter()
+        .flat_map(|columns| columns.split(','))
         .map(|s| s.trim().to_string())
         .collect();
 
@@ -48,6 +74,22 @@ pub(crate) fn derive_updateable_impl(input: TokenStream) -> TokenStream {
                 .value()
         });
 
+    // #[partition_by] anahtarı koşulda yoksa bölüm budaması için eklenir
+    let where_clause = partitioned_where_clause(&input, where_clause);
+    // #[case_insensitive] / #[citext] sütunlarının karşılaştırmaları büyük-küçük harf ayırmaz
+    let where_clause = case_insensitive_where_clause(&input, where_clause);
+
+    // UPDATE ... FROM ile koşulda kullanılan diğer tablolar
+    let from = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("from"))
+        .map(|attr| {
+            attr.parse_args::<syn::LitStr>()
+                .expect("Expected a string literal for from")
+                .value()
+        });
+
     // Collect fields from the struct
     let fields = if let syn::Data::Struct(data) = &input.data {
         if let syn::Fields::Named(fields) = &data.fields {
@@ -55,6 +97,7 @@ pub(crate) fn derive_updateable_impl(input: TokenStream) -> TokenStream {
                 .named
                 .iter()
                 .map(|f| f.ident.as_ref().unwrap().to_string())
+                .filter(|f| included_field.as_ref() != Some(f))
                 .collect::<Vec<_>>()
         } else {
             panic!("Updateable can only be derived for structs with named fields");
@@ -64,9 +107,10 @@ pub(crate) fn derive_updateable_impl(input: TokenStream) -> TokenStream {
     };
 
     // Sort fields for `updated_columns`
+    let rule = rename_rule(&input);
     let sorted_fields: Vec<_> = column_order
         .iter()
-        .filter_map(|col| fields.iter().find(|field| *field == col))
+        .filter_map(|col| field_for_column(&fields, rule, col))
         .cloned()
         .collect();
 
@@ -79,32 +123,59 @@ pub(crate) fn derive_updateable_impl(input: TokenStream) -> TokenStream {
         param_counter.next();
     }
 
+    // İfadelerdeki parametreler sütun parametrelerinden sonra numaralandırılır
+    let expr_statements: Vec<String> = update_exprs
+        .iter()
+        .map(|expr| number_where_clause_params(expr.trim(), &mut param_counter))
+        .collect();
+
+    // FROM içindeki parametreler SET ile WHERE arasında numaralandırılır
+    let adjusted_from = from
+        .as_deref()
+        .map(|from| number_where_clause_params(from, &mut param_counter));
+
     // Parametre sayacı update alanlarından sonra devam eder
     // WHERE cümlesindeki parametreler SET parametrelerinden sonraki değerleri alır
     let adjusted_where_clause = where_clause
-        .map(|clause| number_where_clause_params(&clause, &mut param_counter))
+        .as_deref()
+        .map(|clause| number_where_clause_params(clause, &mut param_counter))
         .unwrap_or_else(|| "".to_string());
 
-    let mut builder = query_builder::SafeQueryBuilder::new();
+    let quote = quote_identifiers(&input);
+    let mut builder = query_builder::SafeQueryBuilder::new().quoting(quote);
 
     builder.add_keyword("UPDATE");
     builder.add_identifier(&table);
     builder.add_keyword("SET");
 
     // Build SET statements safely
-    let update_statements: Vec<String> = column_order
+    let set_columns: Vec<String> = column_order
         .iter()
-        .enumerate()
-        .map(|(i, col)| {
-            let safe_col = col
-                .chars()
-                .filter(|c| c.is_alphanumeric() || *c == '_')
-                .collect::<String>();
-            format!("{} = ${}", safe_col, i + 1)
+        .map(|col| {
+            // Alan adıyla yazılan sütunlar #[rename_all] kuralına göre adlandırılır
+            let column = match field_for_column(&fields, rule, col) {
+                Some(field) => rule.apply(field),
+                None => col.clone(),
+            };
+            quote_column(&column, quote)
         })
         .collect();
+    let mut update_statements: Vec<String> = set_columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| format!("{} = {}{}", column, dialect().placeholder(), i + 1))
+        .collect();
+    update_statements.extend(expr_statements);
 
-    builder.add_raw(&update_statements.join(", "));
+    if !update_statements.is_empty() {
+        builder.add_raw(&update_statements.join(", "));
+    }
+    let set_end = builder.query.len();
+
+    if let Some(from) = &adjusted_from {
+        builder.add_keyword("FROM");
+        builder.add_raw(from);
+    }
 
     if !adjusted_where_clause.is_empty() {
         builder.add_keyword("WHERE");
@@ -113,15 +184,73 @@ pub(crate) fn derive_updateable_impl(input: TokenStream) -> TokenStream {
 
     let safe_query = builder.build();
 
+    // #[audited] denetim kaydına SET listesindeki sütunların yeni değerlerini yazar
+    let safe_query = match audited::audit_log(&input) {
+        Some(log) => {
+            let mut changed = set_columns.clone();
+            changed.extend(update_exprs.iter().filter_map(|expr| expr.split('=').next()).map(|column| column.trim().to_string()));
+            log.wrap(Operation::Update, &safe_query, &quote_name(&table, quote), &changed, None)
+        }
+        None => safe_query,
+    };
+
     // Log mesajlarını PARSQL_TRACE kontrolü ile yazdır
     log_message(&format!("Generated UPDATE SQL: {}", safe_query));
     log_message(&format!("Total param count: {}", param_counter.count()));
 
+    let registration = query_registration(&input);
+    let validation = validate::validation_method(&input);
+    let partition_warning = partition_warning(&input, where_clause.as_deref());
+
+    if let Some((base, _)) = &included {
+        reject_registration(&input, "include_fields");
+        // Temel modelin parametreleri WHERE parametrelerinden sonra numaralandırılır
+        let separator = if update_statements.is_empty() { " " } else { ", " };
+        let query = spliced_query(
+            base,
+            &safe_query,
+            &[(set_end, separator, Splice::Assignments(param_counter.count() + 1))],
+        );
+        let metadata = query_metadata(&table, None, None);
+        let expanded = quote! {
+            #partition_warning
+
+            impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+                fn query() -> &'static str {
+                    #query
+                }
+
+                #metadata
+
+                #validation
+            }
+        };
+
+        return TokenStream::from(expanded);
+    }
+
+    let param_count = param_counter.count();
+    let param_names = Some(update_param_fields(&input)).filter(|names| names.len() == param_count);
+    let metadata = query_metadata(&table, Some(quote! { #param_count }), param_names.as_deref());
+
     let expanded = quote! {
-        impl SqlQuery for #struct_name {
-            fn query() -> String {
-                #safe_query.to_string()
+        impl #impl_generics #struct_name #ty_generics #generic_where {
+            /// The generated SQL statement, available in `const` contexts.
+            pub const SQL: &'static str = #safe_query;
+        }
+
+        #registration
+
+        #partition_warning
+
+        impl #impl_generics SqlQuery for #struct_name #ty_generics #generic_where {
+            fn query() -> &'static str {
+                Self::SQL
             }
+
+            #metadata
+
+            #validation
         }
     };
 
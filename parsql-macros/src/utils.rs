@@ -1,4 +1,6 @@
+use parsql_core::{audit_log::is_plain_name, batch::Dialect, partition};
 use regex::Regex;
+use syn::{punctuated::Punctuated, Data, DeriveInput, Token};
 
 /// Extracts field names from a WHERE clause.
 /// 
@@ -18,17 +20,39 @@ pub(crate) fn extract_fields_from_where_clause(input: &str) -> Vec<String> {
     fields
 }
 
+/// Dialect of the generated SQL: PostgreSQL when one of its backends is enabled,
+/// SQLite otherwise.
+pub(crate) fn dialect() -> Dialect {
+    if cfg!(any(feature = "postgres", feature = "tokio-postgres", feature = "deadpool-postgres")) {
+        Dialect::Postgres
+    } else {
+        Dialect::Sqlite
+    }
+}
+
 /// SQL sorgularındaki parametre sayılarını takip etmek için yardımcı veri yapısı.
 /// Bu yapı sayesinde, generate edilen SQL ile SQL parametreleri her zaman senkronize olur.
 pub(crate) struct SqlParamCounter {
     /// Şu anki parametre numarası (1'den başlar)
     current: usize,
+    /// Yer tutucuların yazıldığı lehçe (`$1` ya da `?1`)
+    dialect: Dialect,
 }
 
 impl SqlParamCounter {
-    /// 1'den başlayan yeni bir sayaç oluşturur
+    /// Derlenen arka ucun lehçesiyle 1'den başlayan yeni bir sayaç oluşturur
     pub fn new() -> Self {
-        Self { current: 1 }
+        Self::with_dialect(dialect())
+    }
+
+    /// Verilen lehçeyle 1'den başlayan yeni bir sayaç oluşturur
+    pub fn with_dialect(dialect: Dialect) -> Self {
+        Self { current: 1, dialect }
+    }
+
+    /// Sıradaki yer tutucuyu (`$3`, `?3`) döndürür ve sayacı bir artırır
+    pub fn next_placeholder(&mut self) -> String {
+        format!("{}{}", self.dialect.placeholder(), self.next())
     }
     
     /// Mevcut parametre numarasını döndürür ve sayacı bir artırır
@@ -51,17 +75,1064 @@ impl SqlParamCounter {
 
 /// WHERE koşulundaki parametre numaralarını doğru şekilde atayan yardımcı fonksiyon.
 /// Bu fonksiyon, bağımsız olarak kullanılabilir ve sayaç değerini dışarıdan alır.
+/// `$` işaretinden sonra gelen alan adı (`$min_count`) ya da numara (`$1`) atılır,
+/// numara her zaman sayaçtan verilir. SQLite için yer tutucular `?1, ?2` olarak yazılır.
 pub(crate) fn number_where_clause_params(clause: &str, counter: &mut SqlParamCounter) -> String {
-    clause.chars()
-        .map(|c| {
-            if c == '$' {
-                // $ işaretinden sonra numara ekle
-                let param_num = counter.next();
-                format!("${}", param_num)
+    let mut result = String::with_capacity(clause.len() + 8);
+    let mut chars = clause.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            // Diğer karakterleri olduğu gibi bırak
+            result.push(c);
+            continue;
+        }
+        while chars.next_if(|d| d.is_alphanumeric() || *d == '_').is_some() {}
+        result.push_str(&counter.next_placeholder());
+    }
+
+    result
+}
+
+/// Returns the fields bound to the `$` placeholders of a clause, in order of appearance.
+///
+/// A placeholder binds the field named right after it (`COUNT(*) > $min_count`); a bare
+/// `$` binds the last field name written since the previous placeholder (`users.state > $`).
+/// Names are compared as whole words, so `user_id` never binds a field called `id`, and the
+/// table or alias qualifying a column (`p` of `p.created_at`) never binds a field.
+pub(crate) fn placeholder_fields(clause: &str, fields: &[String]) -> Vec<String> {
+    let mut bound = Vec::new();
+    let mut segment_start = 0;
+    let mut chars = clause.char_indices().peekable();
+
+    while let Some((at, c)) = chars.next() {
+        if c != '$' {
+            continue;
+        }
+        let mut name = String::new();
+        while let Some((_, d)) = chars.next_if(|(_, d)| d.is_alphanumeric() || *d == '_') {
+            name.push(d);
+        }
+
+        // `$1` gibi elle yazılmış numaralar isimsiz yer tutucu sayılır
+        let field = if name.is_empty() || name.starts_with(|d: char| d.is_ascii_digit()) {
+            // Her parça bir kelime ve onu bitiren ayraçtır; nokta ile biten kelime tablo önekidir
+            clause[segment_start..at]
+                .split_inclusive(|c: char| !c.is_alphanumeric() && c != '_')
+                .rev()
+                .filter(|piece| !piece.ends_with('.'))
+                .map(|piece| piece.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_'))
+                .find_map(|word| fields.iter().find(|f| *f == word))
+                .unwrap_or_else(|| {
+                    panic!(
+                        "cannot tell which field binds placeholder {} in `{}`; name it explicitly, e.g. `$field`",
+                        bound.len() + 1,
+                        clause
+                    )
+                })
+        } else {
+            fields.iter().find(|f| **f == name).unwrap_or_else(|| {
+                panic!("`${}` in `{}` does not name a field of the struct", name, clause)
+            })
+        };
+
+        bound.push(field.clone());
+        segment_start = at + 1 + name.len();
+    }
+
+    bound
+}
+
+/// Collects the `#[update_expr("view_count = view_count + $", "last_seen = NOW()")]` entries.
+///
+/// Each entry is a complete `column = expression` assignment placed into the SET list
+/// after the plain `#[update(...)]` columns. The attribute may be repeated.
+pub(crate) fn update_expressions(input: &DeriveInput) -> Vec<String> {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("update_expr"))
+        .flat_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::LitStr, Token![,]>::parse_terminated)
+                .expect("Expected string literals for update_expr")
+        })
+        .map(|expr| expr.value())
+        .collect()
+}
+
+/// Collects the `#[select_extra("COUNT(*) OVER() AS total_rows")]` expressions with
+/// their `AS` alias, if they have one.
+///
+/// The expressions follow the select list built from the fields; a field named like an
+/// alias is read from the expression, so it is left out of that list. The attribute
+/// may be repeated.
+pub(crate) fn select_extras(input: &DeriveInput) -> Vec<(String, Option<String>)> {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("select_extra"))
+        .flat_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::LitStr, Token![,]>::parse_terminated)
+                .expect("Expected string literals for select_extra")
+        })
+        .map(|expr| {
+            let expr = expr.value().trim().to_string();
+            let alias = expr
+                .to_ascii_uppercase()
+                .rfind(" AS ")
+                .map(|at| expr[at + 4..].trim().trim_matches('"').to_string())
+                .filter(|alias| !alias.is_empty() && alias.chars().all(|c| c.is_alphanumeric() || c == '_'));
+            (expr, alias)
+        })
+        .collect()
+}
+
+/// Reads `#[limit(..)]` / `#[offset(..)]` as SQL text.
+///
+/// Accepts an integer literal (`#[limit(10)]`) or a string holding a placeholder
+/// bound to a field (`#[limit("$page_size")]`).
+pub(crate) fn paging_clause(input: &DeriveInput, name: &str) -> Option<String> {
+    let attr = input.attrs.iter().find(|attr| attr.path().is_ident(name))?;
+    if let Ok(value) = attr.parse_args::<syn::LitInt>() {
+        let value = value
+            .base10_parse::<u64>()
+            .unwrap_or_else(|_| panic!("Failed to parse {} value as an integer", name));
+        return Some(value.to_string());
+    }
+    let value = attr
+        .parse_args::<syn::LitStr>()
+        .unwrap_or_else(|_| panic!("Expected an integer or string literal for {}", name));
+    Some(value.value())
+}
+
+/// Returns the name of the field whose type ends with the last segment of `ty`.
+/// Used by attributes that embed another query type (`insert_from`, `union_all`).
+pub(crate) fn field_of_type<'a>(input: &'a DeriveInput, ty: &syn::Path) -> Option<&'a syn::Ident> {
+    let type_name = &ty.segments.last()?.ident;
+
+    match &input.data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .find(|f| match &f.ty {
+                syn::Type::Path(field_ty) => field_ty
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|s| &s.ident == type_name),
+                _ => false,
+            })
+            .and_then(|f| f.ident.as_ref()),
+        _ => None,
+    }
+}
+
+/// Reads `#[union(Other)]` or `#[union_all(Other)]`, returning the SQL keyword and the other query type.
+pub(crate) fn union_attribute(input: &DeriveInput) -> Option<(&'static str, syn::Path)> {
+    input.attrs.iter().find_map(|attr| {
+        let keyword = if attr.path().is_ident("union_all") {
+            "UNION ALL"
+        } else if attr.path().is_ident("union") {
+            "UNION"
+        } else {
+            return None;
+        };
+        let other = attr
+            .parse_args::<syn::Path>()
+            .expect("Expected a type path for union, e.g. #[union_all(ArchivedOrders)]");
+        Some((keyword, other))
+    })
+}
+
+/// Text before and after the second query of a `#[union]`, whose SQL is only known at
+/// runtime. Both queries are read from parenthesized subqueries, so an `ORDER BY` /
+/// `LIMIT` of either one stays with it, and `tail` applies to the combined result.
+/// Subqueries rather than bare parentheses, which SQLite doesn't accept around a member.
+pub(crate) fn union_template(head: &str, keyword: &str, tail: &str) -> (String, String) {
+    (
+        format!("SELECT * FROM ({head}) AS u1 {keyword} SELECT * FROM ("),
+        format!(") AS u2{tail}"),
+    )
+}
+
+/// Reads `#[include_fields(Base)]`, returning the base model and the name of the field
+/// holding it. The base's columns follow the struct's own columns in the statement.
+pub(crate) fn included_fields(input: &DeriveInput) -> Option<(syn::Path, String)> {
+    let attr = input.attrs.iter().find(|attr| attr.path().is_ident("include_fields"))?;
+    let base = attr
+        .parse_args::<syn::Path>()
+        .expect("Expected a type path for include_fields, e.g. #[include_fields(AuditColumns)]");
+    let field = field_of_type(input, &base)
+        .unwrap_or_else(|| panic!("include_fields requires a named field of type `{}`", quote::quote!(#base)))
+        .to_string();
+    Some((base, field))
+}
+
+/// How the columns of an `#[include_fields]` base are written into a statement.
+pub(crate) enum Splice {
+    /// `a, b`
+    Columns,
+    /// `$n, $n+1`, numbered from the given parameter
+    Placeholders(usize),
+    /// `a = $n, b = $n+1`, numbered from the given parameter
+    Assignments(usize),
+}
+
+/// Generates an expression building `sql` with the columns of `base` (its `COLUMNS`
+/// const) written in at the given byte offsets, each after its separator, once per
+/// process.
+pub(crate) fn spliced_query(
+    base: &syn::Path,
+    sql: &str,
+    splices: &[(usize, &str, Splice)],
+) -> proc_macro2::TokenStream {
+    let marker = dialect().placeholder();
+    // Sondaki eklemeden başlanır, böylece önceki konumlar kaymaz
+    let inserts = splices.iter().rev().map(|(at, separator, splice)| {
+        let rendered = match splice {
+            Splice::Columns => quote::quote! { columns.join(", ") },
+            Splice::Placeholders(first) => quote::quote! {
+                (0..columns.len())
+                    .map(|i| format!("{}{}", #marker, #first + i))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+            Splice::Assignments(first) => quote::quote! {
+                columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, column)| format!("{} = {}{}", column, #marker, #first + i))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+        };
+        quote::quote! {
+            sql.insert_str(#at, &format!("{}{}", #separator, #rendered));
+        }
+    });
+
+    quote::quote! {{
+        static QUERY: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+        QUERY.get_or_init(|| {
+            let columns = <#base>::COLUMNS;
+            let mut sql = String::from(#sql);
+            #(#inserts)*
+            sql
+        })
+    }}
+}
+
+/// Reads a field's `#[default_sql("now()")]`, the SQL expression an INSERT uses for
+/// the field instead of its value.
+pub(crate) fn default_sql(field: &syn::Field) -> Option<String> {
+    let attr = field.attrs.iter().find(|attr| attr.path().is_ident("default_sql"))?;
+    let value = attr
+        .parse_args::<syn::LitStr>()
+        .expect("Expected a string literal for default_sql, e.g. #[default_sql(\"now()\")]");
+    Some(value.value())
+}
+
+/// Reads `#[partition_by("created_at")]`, the partition key of the table.
+pub(crate) fn partition_key(input: &DeriveInput) -> Option<String> {
+    input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("partition_by"))
+        .map(|attr| {
+            attr.parse_args::<syn::LitStr>()
+                .expect("Expected a string literal for partition_by, e.g. #[partition_by(\"created_at\")]")
+                .value()
+        })
+}
+
+/// Adds the `#[partition_by]` key to a WHERE clause that doesn't filter on it yet,
+/// bound to the field stored in the key column.
+///
+/// The clause is returned unchanged when the struct has no such field;
+/// [`partition_warning`] then reports the missing key.
+pub(crate) fn partitioned_where_clause(input: &DeriveInput, where_clause: Option<String>) -> Option<String> {
+    let Some(key) = partition_key(input) else {
+        return where_clause;
+    };
+    if where_clause.as_deref().is_some_and(|clause| partition::filters_on(clause, &key)) {
+        return where_clause;
+    }
+    let rule = rename_rule(input);
+    let fields = named_fields(input);
+    let Some(field) = field_for_column(&fields, rule, &key) else {
+        return where_clause;
+    };
+
+    // Koşuldaki bir OR'un anahtarı devre dışı bırakmaması için mevcut koşul parantez içine alınır
+    let condition = format!("{} = ${}", quote_column(&rule.apply(field), quote_identifiers(input)), field);
+    Some(match where_clause {
+        Some(clause) => format!("({}) AND {}", clause, condition),
+        None => condition,
+    })
+}
+
+/// Placeholder prefix of the flags that switch `#[where_if]` conditions on and off.
+pub(crate) const WHERE_IF_GUARD: &str = "__where_if_";
+
+/// Reads the `#[where_if("state = $", when = "state.is_some()")]` conditions.
+///
+/// Each entry is the SQL fragment, checked like `#[where_clause]`, and the Rust
+/// expression deciding at runtime whether it applies. The attribute may be repeated.
+pub(crate) fn where_if_conditions(input: &DeriveInput) -> Vec<(syn::LitStr, syn::Expr)> {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("where_if"))
+        .map(|attr| {
+            attr.parse_args_with(|stream: syn::parse::ParseStream| {
+                let condition: syn::LitStr = stream.parse()?;
+                stream.parse::<Token![,]>()?;
+                let key: syn::Ident = stream.parse()?;
+                if key != "when" {
+                    return Err(syn::Error::new(key.span(), "expected `when = \"...\"`"));
+                }
+                stream.parse::<Token![=]>()?;
+                let when: syn::LitStr = stream.parse()?;
+                Ok((condition, when.parse::<syn::Expr>()?))
+            })
+            .unwrap_or_else(|error| {
+                panic!("Expected #[where_if(\"condition\", when = \"expression\")]: {}", error)
+            })
+        })
+        .collect()
+}
+
+/// Adds the `#[where_if]` conditions to a WHERE clause.
+///
+/// The statement stays the same for every value of the struct: each condition is
+/// written as `(NOT $flag OR (condition))`, and the flag bound by `params()` is the
+/// result of its `when` expression. An inactive condition is always true, so only the
+/// active ones filter; its own parameters are bound but have no effect.
+pub(crate) fn conditional_where_clause(input: &DeriveInput, where_clause: Option<String>) -> Option<String> {
+    let conditions = where_if_conditions(input);
+    if conditions.is_empty() {
+        return where_clause;
+    }
+    let guarded = conditions
+        .iter()
+        .enumerate()
+        .map(|(i, (condition, _))| format!("(NOT ${}{} OR ({}))", WHERE_IF_GUARD, i, condition.value()))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    Some(match where_clause {
+        Some(clause) => format!("({}) AND {}", clause, guarded),
+        None => guarded,
+    })
+}
+
+/// Comparison of a column whose case doesn't matter.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum CaseFolding {
+    /// `#[case_insensitive]`: `LOWER(email) = LOWER($n)`
+    Lower,
+    /// `#[citext]`: `email = $n::citext`, comparing as the `citext` column does
+    Citext,
+}
+
+/// Columns of the `#[case_insensitive]` and `#[citext]` fields.
+pub(crate) fn case_insensitive_columns(input: &DeriveInput) -> Vec<(String, CaseFolding)> {
+    let Data::Struct(data) = &input.data else {
+        return Vec::new();
+    };
+    let rule = rename_rule(input);
+    data.fields
+        .iter()
+        .filter_map(|field| {
+            let folding = field.attrs.iter().find_map(|attr| {
+                if attr.path().is_ident("case_insensitive") {
+                    Some(CaseFolding::Lower)
+                } else if attr.path().is_ident("citext") {
+                    Some(CaseFolding::Citext)
+                } else {
+                    None
+                }
+            })?;
+            if folding == CaseFolding::Citext && dialect() != Dialect::Postgres {
+                panic!("citext is a PostgreSQL type; use #[case_insensitive] on SQLite");
+            }
+            Some((rule.apply(&field.ident.as_ref()?.to_string()), folding))
+        })
+        .collect()
+}
+
+/// Rewrites the comparisons of `#[case_insensitive]` / `#[citext]` columns with a
+/// placeholder (`email = $`, `u.email <> $email`, `email LIKE $pattern`) so that they
+/// ignore case. Other conditions on the column are left as written.
+pub(crate) fn case_insensitive_where_clause(input: &DeriveInput, where_clause: Option<String>) -> Option<String> {
+    let columns = case_insensitive_columns(input);
+    if columns.is_empty() {
+        return where_clause;
+    }
+    where_clause.map(|clause| fold_case(&clause, &columns))
+}
+
+fn fold_case(clause: &str, columns: &[(String, CaseFolding)]) -> String {
+    let chars = clause.char_indices().collect::<Vec<_>>();
+    let end_of = |i: usize| chars.get(i).map_or(clause.len(), |(at, _)| *at);
+    let word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut result = String::with_capacity(clause.len() + 16);
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        if c == '\'' || c == '$' {
+            // Metin sabitleri ve yer tutucular sütun adı içermez
+            let mut j = i + 1;
+            if c == '\'' {
+                while j < chars.len() && chars[j].1 != '\'' {
+                    j += 1;
+                }
+                j += 1;
             } else {
-                // Diğer karakterleri olduğu gibi bırak
-                c.to_string()
+                while j < chars.len() && word_char(chars[j].1) {
+                    j += 1;
+                }
+            }
+            result.push_str(&clause[start..end_of(j)]);
+            i = j;
+            continue;
+        }
+        if !word_char(c) && c != '"' {
+            result.push(c);
+            i += 1;
+            continue;
+        }
+
+        // Nitelikli ad: u.email ya da "Users"."Email"; karşılaştırılan son parçadır
+        let part = |from: usize| -> (usize, &str, bool) {
+            if chars[from].1 == '"' {
+                let close = (from + 1..chars.len()).find(|k| chars[*k].1 == '"').unwrap_or(chars.len());
+                (close + 1, &clause[end_of(from + 1)..end_of(close)], true)
+            } else {
+                let to = (from..chars.len()).find(|k| !word_char(chars[*k].1)).unwrap_or(chars.len());
+                (to, &clause[end_of(from)..end_of(to)], false)
+            }
+        };
+        let (mut j, mut last, mut quoted) = part(i);
+        while chars.get(j).is_some_and(|(_, c)| *c == '.')
+            && chars.get(j + 1).is_some_and(|(_, c)| word_char(*c) || *c == '"')
+        {
+            (j, last, quoted) = part(j + 1);
+        }
+        let name = &clause[start..end_of(j)];
+        let folding = columns
+            .iter()
+            .find(|(column, _)| if quoted { column == last } else { column.eq_ignore_ascii_case(last) })
+            .map(|(_, folding)| *folding);
+
+        let comparison = folding.and_then(|folding| {
+            let rest = &clause[end_of(j)..];
+            let after_space = rest.trim_start();
+            let operator = ["<>", "!=", "="]
+                .into_iter()
+                .find(|op| after_space.starts_with(op))
+                .or_else(|| {
+                    let word = after_space.split(|c: char| !word_char(c)).next().unwrap_or("");
+                    word.eq_ignore_ascii_case("LIKE").then_some(&after_space[..4])
+                })?;
+            let after_operator = &after_space[operator.len()..];
+            let placeholder_rest = after_operator.trim_start().strip_prefix('$')?;
+            let placeholder_len = 1 + placeholder_rest.find(|c: char| !word_char(c)).unwrap_or(placeholder_rest.len());
+            let placeholder_at = after_operator.len() - after_operator.trim_start().len();
+            let placeholder = &after_operator[placeholder_at..placeholder_at + placeholder_len];
+            let consumed = rest.len() - after_operator.len() + placeholder_at + placeholder_len;
+            let rewritten = match folding {
+                CaseFolding::Lower => format!("LOWER({}) {} LOWER({})", name, operator, placeholder),
+                CaseFolding::Citext => format!("{} {} {}::citext", name, operator, placeholder),
+            };
+            Some((rewritten, consumed))
+        });
+
+        match comparison {
+            Some((rewritten, consumed)) => {
+                result.push_str(&rewritten);
+                let resume = end_of(j) + consumed;
+                i = chars.iter().position(|(at, _)| *at >= resume).unwrap_or(chars.len());
+            }
+            None => {
+                result.push_str(name);
+                i = j;
             }
+        }
+    }
+    result
+}
+
+/// A `#[fts("search_vector", query = "terms", rank)]` full-text search.
+pub(crate) struct FullTextSearch {
+    /// The `tsvector` column on PostgreSQL, the FTS5 table or column on SQLite
+    pub column: String,
+    /// The field holding the search terms
+    pub query: String,
+    /// Text search configuration of `plainto_tsquery`, e.g. `english` (PostgreSQL only)
+    pub config: Option<String>,
+    /// Orders the results by relevance
+    pub rank: bool,
+}
+
+impl FullTextSearch {
+    /// The condition added to the WHERE clause, binding the query field.
+    pub fn condition(&self, dialect: Dialect) -> String {
+        match dialect {
+            Dialect::Postgres => format!("{} @@ {}", self.column, self.ts_query(&format!("${}", self.query))),
+            Dialect::Sqlite => format!("{} MATCH ${}", self.column, self.query),
+        }
+    }
+
+    /// The ORDER BY term of `rank`, given the numbered placeholder of the query field.
+    pub fn rank_order(&self, dialect: Dialect, placeholder: &str) -> Option<String> {
+        self.rank.then(|| match dialect {
+            Dialect::Postgres => format!("ts_rank({}, {}) DESC", self.column, self.ts_query(placeholder)),
+            // FTS5'in gizli rank sütunu, MATCH içeren sorgularda bm25 puanıdır
+            Dialect::Sqlite => "rank".to_string(),
         })
-        .collect::<String>()
-}
\ No newline at end of file
+    }
+
+    fn ts_query(&self, placeholder: &str) -> String {
+        match &self.config {
+            Some(config) => format!("plainto_tsquery('{}', {})", config, placeholder),
+            None => format!("plainto_tsquery({})", placeholder),
+        }
+    }
+}
+
+/// Reads `#[fts("search_vector")]`, optionally followed by `query = "field"` (default
+/// `query`), `config = "english"` and `rank`.
+pub(crate) fn full_text_search(input: &DeriveInput) -> Option<FullTextSearch> {
+    let attr = input.attrs.iter().find(|attr| attr.path().is_ident("fts"))?;
+    let mut search = attr
+        .parse_args_with(|stream: syn::parse::ParseStream| {
+            let column: syn::LitStr = stream.parse()?;
+            let mut search = FullTextSearch { column: column.value(), query: "query".to_string(), config: None, rank: false };
+            while !stream.is_empty() {
+                stream.parse::<Token![,]>()?;
+                let key: syn::Ident = stream.parse()?;
+                if key == "rank" {
+                    search.rank = true;
+                    continue;
+                }
+                stream.parse::<Token![=]>()?;
+                let value = stream.parse::<syn::LitStr>()?.value();
+                match key.to_string().as_str() {
+                    "query" => search.query = value,
+                    "config" => search.config = Some(value),
+                    _ => return Err(syn::Error::new(key.span(), "expected `query`, `config` or `rank`")),
+                }
+            }
+            Ok(search)
+        })
+        .unwrap_or_else(|error| {
+            panic!("Expected #[fts(\"column\", query = \"field\", config = \"english\", rank)]: {}", error)
+        });
+
+    // Sütun ve yapılandırma adları SQL metnine yazıldığı için yalnızca düz adlar kabul edilir
+    let plain = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+    if !plain(&search.column) {
+        panic!("fts expects a plain column name such as \"search_vector\", found `{}`", search.column);
+    }
+    if let Some(config) = &search.config {
+        if dialect() != Dialect::Postgres {
+            panic!("fts config is only supported on PostgreSQL");
+        }
+        if !plain(config) {
+            panic!("fts expects a text search configuration such as \"english\", found `{}`", config);
+        }
+    }
+    let fields = named_fields(input);
+    let rule = rename_rule(input);
+    search.query = field_for_column(&fields, rule, &search.query)
+        .unwrap_or_else(|| panic!("fts query field `{}` not found; name it with query = \"...\"", search.query))
+        .clone();
+    Some(search)
+}
+
+/// Adds the `#[fts]` condition to a WHERE clause, after every other condition.
+pub(crate) fn searched_where_clause(input: &DeriveInput, where_clause: Option<String>) -> Option<String> {
+    let Some(search) = full_text_search(input) else {
+        return where_clause;
+    };
+    Some(match where_clause {
+        Some(clause) => format!("({}) AND {}", clause, search.condition(dialect())),
+        None => search.condition(dialect()),
+    })
+}
+
+/// Reads `#[within("location", center = "center", distance = "meters")]` into its
+/// `ST_DWithin` condition. With a trailing `geography`, the column and the center are
+/// cast to `geography`, so a `geometry(Point, 4326)` column is searched in meters.
+pub(crate) fn within_condition(input: &DeriveInput) -> Option<String> {
+    let attr = input.attrs.iter().find(|attr| attr.path().is_ident("within"))?;
+    let (column, center, distance, geography) = attr
+        .parse_args_with(|stream: syn::parse::ParseStream| {
+            let column = stream.parse::<syn::LitStr>()?.value();
+            let (mut center, mut distance, mut geography) = (None, None, false);
+            while !stream.is_empty() {
+                stream.parse::<Token![,]>()?;
+                let key: syn::Ident = stream.parse()?;
+                if key == "geography" {
+                    geography = true;
+                    continue;
+                }
+                stream.parse::<Token![=]>()?;
+                let value = stream.parse::<syn::LitStr>()?.value();
+                match key.to_string().as_str() {
+                    "center" => center = Some(value),
+                    "distance" => distance = Some(value),
+                    _ => return Err(syn::Error::new(key.span(), "expected `center`, `distance` or `geography`")),
+                }
+            }
+            Ok((column, center, distance, geography))
+        })
+        .unwrap_or_else(|error| {
+            panic!("Expected #[within(\"column\", center = \"field\", distance = \"field\")]: {}", error)
+        });
+    if dialect() != Dialect::Postgres {
+        panic!("within is only supported on PostgreSQL with PostGIS");
+    }
+    if column.is_empty() || !column.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+        panic!("within expects a plain column name such as \"location\", found `{}`", column);
+    }
+
+    let fields = named_fields(input);
+    let rule = rename_rule(input);
+    let field = |name: Option<String>, key: &str| {
+        let name = name.unwrap_or_else(|| panic!("within requires {} = \"field\"", key));
+        field_for_column(&fields, rule, &name)
+            .unwrap_or_else(|| panic!("within {} field `{}` not found", key, name))
+            .clone()
+    };
+    let (center, distance) = (field(center, "center"), field(distance, "distance"));
+    Some(if geography {
+        format!("ST_DWithin({}::geography, ${}::geography, ${})", column, center, distance)
+    } else {
+        format!("ST_DWithin({}, ${}, ${})", column, center, distance)
+    })
+}
+
+/// Adds the `#[within]` condition to a WHERE clause; only the `#[fts]` condition follows it.
+pub(crate) fn spatial_where_clause(input: &DeriveInput, where_clause: Option<String>) -> Option<String> {
+    let Some(condition) = within_condition(input) else {
+        return where_clause;
+    };
+    Some(match where_clause {
+        Some(clause) => format!("({}) AND {}", clause, condition),
+        None => condition,
+    })
+}
+
+/// Emits a compile-time warning when the WHERE clause of a statement still doesn't
+/// filter on the `#[partition_by]` key, i.e. it would scan every partition.
+///
+/// Proc macros can't raise warnings on stable Rust, so the generated code uses a
+/// deprecated item whose note carries the message.
+pub(crate) fn partition_warning(input: &DeriveInput, where_clause: Option<&str>) -> proc_macro2::TokenStream {
+    let Some(key) = partition_key(input) else {
+        return proc_macro2::TokenStream::new();
+    };
+    if where_clause.is_some_and(|clause| partition::filters_on(clause, &key)) {
+        return proc_macro2::TokenStream::new();
+    }
+    let note = format!(
+        "the WHERE clause of `{}` doesn't filter on the partition key `{}`, so every partition is scanned; \
+         add it to #[where_clause] or give the struct a `{}` field",
+        input.ident, key, key
+    );
+    // Uyarının kullanıcının koduna işaret etmesi için öznitelik konumu kullanılır;
+    // makro konumundaki uyarılar derleyici tarafından gizlenir
+    let span = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("partition_by"))
+        .map_or_else(proc_macro2::Span::call_site, |attr| attr.path().get_ident().unwrap().span());
+    quote::quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #note)]
+            struct MissingPartitionKey;
+            let _ = MissingPartitionKey;
+        };
+    }
+}
+
+/// Generates the `Partitioned` impl for `#[partitioned(by = "range(created_at)")]`, a
+/// model of a table partitioned by range whose monthly partitions
+/// `ensure_monthly_partition::<T>` creates.
+pub(crate) fn partitioned_impl(input: &DeriveInput, table: &str) -> proc_macro2::TokenStream {
+    let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("partitioned")) else {
+        return proc_macro2::TokenStream::new();
+    };
+    if dialect() != Dialect::Postgres {
+        panic!("partitioned is only supported on PostgreSQL");
+    }
+
+    let mut spec = None;
+    attr.parse_nested_meta(|meta| {
+        if !meta.path.is_ident("by") {
+            return Err(meta.error("unsupported partitioned option, expected `by`"));
+        }
+        spec = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        Ok(())
+    })
+    .unwrap_or_else(|e| panic!("{}", e));
+    let spec = spec.expect("Expected #[partitioned(by = \"range(created_at)\")]");
+
+    // Aylık bölümler yalnızca aralık bölümlemesinde tanımlıdır; list/hash sınırları uygulamaya özgüdür
+    let column = spec
+        .trim()
+        .strip_suffix(')')
+        .and_then(|rest| rest.split_once('('))
+        .filter(|(strategy, _)| strategy.trim().eq_ignore_ascii_case("range"))
+        .map(|(_, column)| column.trim())
+        .filter(|column| is_plain_name(column) && !column.contains('.'))
+        .unwrap_or_else(|| panic!("partitioned expects `by = \"range(<column>)\"`, found `{}`", spec));
+    if !is_plain_name(table) {
+        panic!("partitioned needs an unquoted table name such as \"events\" or \"audit.events\", found `{}`", table);
+    }
+
+    let struct_name = &input.ident;
+    quote::quote! {
+        impl Partitioned for #struct_name {
+            fn partitioned_table() -> &'static str {
+                #table
+            }
+
+            fn partition_column() -> &'static str {
+                #column
+            }
+        }
+    }
+}
+
+/// Names of the named fields of a struct; empty for other items.
+fn named_fields(input: &DeriveInput) -> Vec<String> {
+    match &input.data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .filter_map(|f| f.ident.as_ref().map(ToString::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Submits the `SQL` constant to the query registry for `#[register_query]`.
+///
+/// The generated code names `registry`, which the user imports from the backend
+/// like the traits (`use parsql::postgres::registry;`).
+pub(crate) fn query_registration(input: &DeriveInput) -> proc_macro2::TokenStream {
+    if !input.attrs.iter().any(|attr| attr.path().is_ident("register_query")) {
+        return proc_macro2::TokenStream::new();
+    }
+    let struct_name = &input.ident;
+    quote::quote! {
+        registry::inventory::submit! {
+            registry::QueryEntry::new(
+                concat!(module_path!(), "::", stringify!(#struct_name)),
+                #struct_name::SQL,
+            )
+        }
+    }
+}
+
+/// Rejects `#[register_query]` on statements that are only assembled at runtime.
+pub(crate) fn reject_registration(input: &DeriveInput, reason: &str) {
+    if input.attrs.iter().any(|attr| attr.path().is_ident("register_query")) {
+        panic!("register_query needs a statement known at compile time; {} queries are built at runtime", reason);
+    }
+}
+
+/// Enforces `#[require_order]` on a query that is `paged` with LIMIT / OFFSET.
+///
+/// Without `#[order_by]` the database returns the rows in no particular order, so
+/// consecutive pages can repeat or skip rows.
+pub(crate) fn check_required_order(input: &DeriveInput, paged: bool) {
+    let required = input.attrs.iter().any(|attr| attr.path().is_ident("require_order"));
+    if paged && required && !input.attrs.iter().any(|attr| attr.path().is_ident("order_by")) {
+        panic!(
+            "require_order: `{}` is paged with LIMIT / OFFSET but has no #[order_by], so its pages are not deterministic",
+            input.ident
+        );
+    }
+}
+
+/// Reads a field's `#[sql_with(path::to::func)]`, the function converting the field
+/// before it is bound.
+pub(crate) fn sql_with(field: &syn::Field) -> Option<syn::Path> {
+    let attr = field.attrs.iter().find(|attr| attr.path().is_ident("sql_with"))?;
+    let path = attr
+        .parse_args::<syn::Path>()
+        .expect("Expected a function path for sql_with, e.g. #[sql_with(normalize_email)]");
+    Some(path)
+}
+
+/// Builds the `params()` values of `fields`, in order, along with the items they need.
+///
+/// Fields marked `#[sql_with]` get a marker type implementing the backend's `SqlWith`
+/// trait and are bound through it, so the conversion runs when the statement executes.
+pub(crate) fn param_values(
+    input: &DeriveInput,
+    fields: &[String],
+) -> (Vec<proc_macro2::TokenStream>, proc_macro2::TokenStream) {
+    let named: Vec<&syn::Field> = match &input.data {
+        Data::Struct(data) => data.fields.iter().filter(|f| f.ident.is_some()).collect(),
+        _ => Vec::new(),
+    };
+    let marker = |ident: &syn::Ident| quote::format_ident!("__sql_with_{}", ident);
+
+    let markers = named.iter().filter_map(|field| {
+        let func = sql_with(field)?;
+        let ty = &field.ty;
+        let marker = marker(field.ident.as_ref().unwrap());
+        Some(quote::quote! {
+            #[allow(dead_code, non_camel_case_types)]
+            enum #marker {}
+
+            impl SqlWith for #marker {
+                type Field = #ty;
+
+                fn convert(value: &#ty) -> Box<dyn ToSql + Sync> {
+                    Box::new(#func(value))
+                }
+            }
+        })
+    });
+    let markers = quote::quote! { #(#markers)* };
+
+    let conditions = where_if_conditions(input);
+    let idents = named.iter().map(|f| f.ident.as_ref().unwrap()).collect::<Vec<_>>();
+
+    let values = fields
+        .iter()
+        .map(|name| {
+            // #[where_if] bayrağı, koşulun `when` ifadesinin sonucudur
+            if let Some(index) = name.strip_prefix(WHERE_IF_GUARD) {
+                let (_, when) = &conditions[index.parse::<usize>().unwrap()];
+                return quote::quote! {
+                    {
+                        #[allow(unused_variables)]
+                        let Self { #(#idents,)* .. } = self;
+                        if #when { &true } else { &false }
+                    } as &(dyn ToSql + Sync)
+                };
+            }
+            let field = named
+                .iter()
+                .find(|f| f.ident.as_ref().unwrap() == name)
+                .unwrap_or_else(|| panic!("No field named `{}`", name));
+            let ident = field.ident.as_ref().unwrap();
+            if sql_with(field).is_some() {
+                let marker = marker(ident);
+                quote::quote! { <#marker as SqlWith>::bind(&self.#ident) }
+            } else {
+                quote::quote! { &self.#ident as &(dyn ToSql + Sync) }
+            }
+        })
+        .collect();
+
+    (values, markers)
+}
+
+/// Returns whether the type is written as `Option<...>`.
+pub(crate) fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// Returns the table of the struct: `#[table("...")]` when present, otherwise the
+/// snake_case plural of the struct name (`User` -> `users`, `OrderItem` -> `order_items`).
+pub(crate) fn table_name(input: &DeriveInput) -> String {
+    match input.attrs.iter().find(|attr| attr.path().is_ident("table")) {
+        Some(attr) => attr
+            .parse_args::<syn::LitStr>()
+            .expect("Expected a string literal for table name")
+            .value(),
+        None => pluralize(&snake_case(&input.ident.to_string())),
+    }
+}
+
+/// Generates the `table_name()`, `param_count()` and `param_names()` methods of a
+/// `SqlQuery` impl.
+///
+/// Without a compile-time `param_count` (queries completed at runtime from another
+/// type) the trait default counts the placeholders of `query()`; `param_names` is left
+/// to the trait default when the struct does not bind all of its parameters itself.
+pub(crate) fn query_metadata(
+    table: &str,
+    param_count: Option<proc_macro2::TokenStream>,
+    param_names: Option<&[String]>,
+) -> proc_macro2::TokenStream {
+    let param_count = param_count.map(|count| {
+        quote::quote! {
+            fn param_count() -> usize {
+                #count
+            }
+        }
+    });
+    let param_names = param_names.map(|names| {
+        quote::quote! {
+            fn param_names() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+        }
+    });
+    quote::quote! {
+        fn table_name() -> &'static str {
+            #table
+        }
+
+        #param_count
+
+        #param_names
+    }
+}
+
+/// Converts a `PascalCase` / `camelCase` name to `snake_case`.
+pub(crate) fn snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 4);
+    let chars: Vec<char> = name.chars().collect();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            // "HTTPRequest" -> "http_request": kısaltmanın son harfi yeni kelimeyi başlatır
+            let after_lower = i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_ascii_digit());
+            let before_lower = i > 0
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_lower || before_lower {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(*c);
+        }
+    }
+    result
+}
+
+/// Returns the English plural of a snake_case name, pluralizing its last word.
+pub(crate) fn pluralize(name: &str) -> String {
+    let consonant_y = name.ends_with('y')
+        && !name[..name.len() - 1].ends_with(['a', 'e', 'i', 'o', 'u']);
+    if consonant_y {
+        format!("{}ies", &name[..name.len() - 1])
+    } else if name.ends_with(['s', 'x', 'z']) || name.ends_with("ch") || name.ends_with("sh") {
+        format!("{}es", name)
+    } else {
+        format!("{}s", name)
+    }
+}
+
+/// Naming convention mapping field names to column names, set with
+/// `#[rename_all("camelCase")]`. Without the attribute columns are named like the fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum RenameRule {
+    #[default]
+    None,
+    SnakeCase,
+    CamelCase,
+    PascalCase,
+}
+
+impl RenameRule {
+    /// Returns the column name of `field`.
+    pub fn apply(self, field: &str) -> String {
+        let words = field.split('_').filter(|word| !word.is_empty());
+        match self {
+            RenameRule::None => field.to_string(),
+            RenameRule::SnakeCase => snake_case(field),
+            RenameRule::CamelCase => words
+                .enumerate()
+                .map(|(i, word)| if i == 0 { word.to_string() } else { capitalize(word) })
+                .collect(),
+            RenameRule::PascalCase => words.map(capitalize).collect(),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Reads the struct's `#[rename_all("...")]` attribute.
+pub(crate) fn rename_rule(input: &DeriveInput) -> RenameRule {
+    let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("rename_all")) else {
+        return RenameRule::None;
+    };
+    let value = attr
+        .parse_args::<syn::LitStr>()
+        .expect("Expected a string literal for rename_all, e.g. #[rename_all(\"camelCase\")]")
+        .value();
+    match value.as_str() {
+        "snake_case" => RenameRule::SnakeCase,
+        "camelCase" => RenameRule::CamelCase,
+        "PascalCase" => RenameRule::PascalCase,
+        other => panic!(
+            "unsupported rename_all convention `{}`, expected `snake_case`, `camelCase` or `PascalCase`",
+            other
+        ),
+    }
+}
+
+/// Writes a column name derived from a field for use in generated SQL, quoted like
+/// [`quote_name`].
+pub(crate) fn quote_column(column: &str, quote: bool) -> String {
+    quote_name(column, quote)
+}
+
+/// Writes a table or column name for use in generated SQL. With `quote` on, names with
+/// upper case letters are double-quoted, otherwise PostgreSQL would fold them to lower
+/// case, and so are reserved words; `quote` off writes the name bare.
+pub(crate) fn quote_name(name: &str, quote: bool) -> String {
+    if quote && name.chars().any(char::is_uppercase) {
+        let (open, close) = dialect().identifier_quotes();
+        format!("{}{}{}", open, quote_name(name, false), close)
+    } else if quote {
+        parsql_core::identifier::quote_identifier(name, dialect())
+    } else {
+        name.chars()
+            .filter(|c| c.is_alphanumeric() || *c == '_')
+            .collect()
+    }
+}
+
+/// Reads `#[no_quote]`, which writes the struct's table and column names exactly as
+/// given instead of quoting reserved words and mixed-case field columns.
+pub(crate) fn quote_identifiers(input: &DeriveInput) -> bool {
+    !input.attrs.iter().any(|attr| attr.path().is_ident("no_quote"))
+}
+
+/// Finds the field stored in `column`, which may be written as the field name or as
+/// its renamed (optionally quoted) column name.
+pub(crate) fn field_for_column<'a>(fields: &'a [String], rule: RenameRule, column: &str) -> Option<&'a String> {
+    let column = column.trim().trim_matches('"');
+    fields
+        .iter()
+        .find(|field| *field == column || rule.apply(field) == column)
+}
+
+/// [`placeholder_fields`] for structs with a `#[rename_all]` convention: clauses may name
+/// either the fields or their columns.
+pub(crate) fn bound_fields(clause: &str, fields: &[String], rule: RenameRule) -> Vec<String> {
+    if rule == RenameRule::None {
+        return placeholder_fields(clause, fields);
+    }
+    let mut names = fields.to_vec();
+    names.extend(fields.iter().map(|field| rule.apply(field)));
+    placeholder_fields(clause, &names)
+        .iter()
+        .map(|name| field_for_column(fields, rule, name).unwrap().clone())
+        .collect()
+}
@@ -0,0 +1,113 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Expr};
+
+use crate::is_option;
+
+/// Generates the `validation()` method of `SqlQuery` from the struct's `#[validate]`
+/// attributes, or nothing when there are none and the trait's default applies.
+///
+/// Field rules are `email`, `non_empty`, `length(min = .., max = ..)`,
+/// `range(min = .., max = ..)` and `custom = path::to::fn`; `Option` fields are checked
+/// only when they hold a value. A bare `#[validate]` on the struct calls its `Validate`
+/// impl after the field rules.
+pub(crate) fn validation_method(input: &DeriveInput) -> TokenStream {
+    let mut checks = Vec::new();
+
+    if let Data::Struct(data) = &input.data {
+        for field in data.fields.iter() {
+            let Some(ident) = &field.ident else { continue };
+            let rules: Vec<TokenStream> = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("validate"))
+                .flat_map(|attr| field_rules(attr, &ident.to_string()))
+                .collect();
+            if rules.is_empty() {
+                continue;
+            }
+
+            // Option alanlar yalnızca değer taşıdıklarında denetlenir
+            if is_option(&field.ty) {
+                checks.push(quote! {
+                    if let Some(value) = &self.#ident {
+                        #(#rules)*
+                    }
+                });
+            } else {
+                checks.push(quote! {
+                    let value = &self.#ident;
+                    #(#rules)*
+                });
+            }
+        }
+    }
+
+    let struct_level = input.attrs.iter().any(|attr| attr.path().is_ident("validate"));
+    if struct_level {
+        checks.push(quote! { <Self as Validate>::validate(self)?; });
+    }
+
+    if checks.is_empty() {
+        return TokenStream::new();
+    }
+
+    quote! {
+        fn validation(&self) -> Result<(), ValidationError> {
+            #({ #checks })*
+            Ok(())
+        }
+    }
+}
+
+/// Parses one `#[validate(...)]` field attribute into its checks on `value`.
+fn field_rules(attr: &syn::Attribute, field: &str) -> Vec<TokenStream> {
+    let mut rules = Vec::new();
+
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("email") {
+            rules.push(quote! { ValidationError::check_email(#field, value)?; });
+        } else if meta.path.is_ident("non_empty") {
+            rules.push(quote! { ValidationError::check_non_empty(#field, value)?; });
+        } else if meta.path.is_ident("length") {
+            let (min, max) = bounds(&meta)?;
+            rules.push(quote! { ValidationError::check_length(#field, value, #min, #max)?; });
+        } else if meta.path.is_ident("range") {
+            let (min, max) = bounds(&meta)?;
+            rules.push(quote! { ValidationError::check_range(#field, value, #min, #max)?; });
+        } else if meta.path.is_ident("custom") {
+            let func: syn::Path = meta.value()?.parse()?;
+            rules.push(quote! {
+                #func(value).map_err(|message| ValidationError::new(#field, message))?;
+            });
+        } else {
+            return Err(meta.error(
+                "unknown validation rule; expected email, non_empty, length, range or custom",
+            ));
+        }
+        Ok(())
+    })
+    .unwrap_or_else(|e| panic!("Invalid #[validate] attribute on `{}`: {}", field, e));
+
+    rules
+}
+
+/// Reads `(min = .., max = ..)` as two `Option` expressions.
+fn bounds(meta: &syn::meta::ParseNestedMeta) -> syn::Result<(TokenStream, TokenStream)> {
+    let mut min = quote! { None };
+    let mut max = quote! { None };
+
+    meta.parse_nested_meta(|bound| {
+        let value: Expr = bound.value()?.parse()?;
+        if bound.path.is_ident("min") {
+            min = quote! { Some(#value) };
+        } else if bound.path.is_ident("max") {
+            max = quote! { Some(#value) };
+        } else {
+            return Err(bound.error("expected min or max"));
+        }
+        Ok(())
+    })?;
+
+    Ok((min, max))
+}
@@ -0,0 +1,285 @@
+use std::any::Any;
+use std::fmt;
+
+use parsql_tokio_postgres::executor::{ErasedValue, Operation};
+use parsql_tokio_postgres::ParsqlError;
+
+/// Identifies the `CrudOps` method an expectation belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockMethod {
+    Insert,
+    Update,
+    Delete,
+    Call,
+    Fetch,
+    FetchAll,
+    Select,
+    SelectAll,
+}
+
+impl From<Operation> for MockMethod {
+    fn from(operation: Operation) -> Self {
+        match operation {
+            Operation::Insert => MockMethod::Insert,
+            Operation::Update => MockMethod::Update,
+            Operation::Delete => MockMethod::Delete,
+            Operation::Fetch => MockMethod::Fetch,
+            Operation::FetchAll => MockMethod::FetchAll,
+            Operation::Select => MockMethod::Select,
+            Operation::SelectAll => MockMethod::SelectAll,
+        }
+    }
+}
+
+impl fmt::Display for MockMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MockMethod::Insert => "insert",
+            MockMethod::Update => "update",
+            MockMethod::Delete => "delete",
+            MockMethod::Call => "call",
+            MockMethod::Fetch => "fetch",
+            MockMethod::FetchAll => "fetch_all",
+            MockMethod::Select => "select",
+            MockMethod::SelectAll => "select_all",
+        };
+        f.write_str(name)
+    }
+}
+
+type Matcher<T> = Box<dyn Fn(&T) -> bool + Send>;
+type Responder<T, O> = Box<dyn FnMut(&T) -> Result<O, ParsqlError> + Send>;
+
+/// A programmed expectation for one `CrudOps` call on entity type `T`
+/// producing a value of type `O`.
+///
+/// Expectations are created through the `expect_*` methods of
+/// [`MockClient`](crate::MockClient) and configured with the builder methods below.
+pub struct Expectation<T, O> {
+    matcher: Option<Matcher<T>>,
+    responder: Option<Responder<T, O>>,
+    times: Option<usize>,
+    calls: usize,
+}
+
+impl<T, O> Expectation<T, O> {
+    pub(crate) fn new() -> Self {
+        Self {
+            matcher: None,
+            responder: None,
+            times: None,
+            calls: 0,
+        }
+    }
+
+    /// Only matches calls whose entity satisfies the given predicate.
+    pub fn withf<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&T) -> bool + Send + 'static,
+    {
+        self.matcher = Some(Box::new(predicate));
+        self
+    }
+
+    /// Sets the function that produces the result of the call.
+    pub fn returning<F>(&mut self, responder: F) -> &mut Self
+    where
+        F: FnMut(&T) -> Result<O, ParsqlError> + Send + 'static,
+    {
+        self.responder = Some(Box::new(responder));
+        self
+    }
+
+    /// Expects the call to happen exactly `n` times.
+    /// Once saturated the expectation no longer matches.
+    pub fn times(&mut self, n: usize) -> &mut Self {
+        self.times = Some(n);
+        self
+    }
+
+    /// Expects the call to happen exactly once.
+    pub fn once(&mut self) -> &mut Self {
+        self.times(1)
+    }
+
+    /// Expects the call to never happen.
+    pub fn never(&mut self) -> &mut Self {
+        self.times(0)
+    }
+
+    fn matches(&self, entity: &T) -> bool {
+        let saturated = self.times.is_some_and(|n| self.calls >= n);
+        !saturated && self.matcher.as_ref().is_none_or(|m| m(entity))
+    }
+
+    fn call(&mut self, method: MockMethod, entity: &T) -> Result<O, ParsqlError> {
+        self.calls += 1;
+        match self.responder.as_mut() {
+            Some(responder) => responder(entity),
+            None => panic!(
+                "[PARSQL-MOCK] Expectation for `{}` has no `returning` function",
+                method
+            ),
+        }
+    }
+}
+
+/// Type-erased storage entry for an [`Expectation`].
+pub(crate) struct Entry {
+    pub(crate) method: MockMethod,
+    pub(crate) type_name: &'static str,
+    expectation: Box<dyn ErasedExpectation>,
+}
+
+impl Entry {
+    pub(crate) fn new<T: 'static, O: Send + 'static>(method: MockMethod) -> Self {
+        Self {
+            method,
+            type_name: std::any::type_name::<T>(),
+            expectation: Box::new(Expectation::<T, O>::new()),
+        }
+    }
+
+    pub(crate) fn downcast_mut<T: 'static, O: 'static>(&mut self) -> Option<&mut Expectation<T, O>> {
+        self.expectation.as_any_mut().downcast_mut()
+    }
+
+    /// Returns a description of the unmet call count, if any.
+    pub(crate) fn unsatisfied(&self) -> Option<String> {
+        let (expected, actual) = self.expectation.counts();
+        match expected {
+            Some(n) if n != actual => Some(format!(
+                "`{}` for `{}`: expected {} call(s), got {}",
+                self.method, self.type_name, n, actual
+            )),
+            _ => None,
+        }
+    }
+
+    /// Calls the expectation if it is of the requested type and matches the entity.
+    pub(crate) fn try_call<T: 'static, O: 'static>(
+        &mut self,
+        method: MockMethod,
+        entity: &T,
+    ) -> Option<Result<O, ParsqlError>> {
+        if self.method != method {
+            return None;
+        }
+        let expectation = self.downcast_mut::<T, O>()?;
+        if !expectation.matches(entity) {
+            return None;
+        }
+        Some(expectation.call(method, entity))
+    }
+
+    /// Type-erased variant of [`Entry::try_call`] used by `ParsqlExecutor`.
+    pub(crate) fn try_call_erased(
+        &mut self,
+        method: MockMethod,
+        entity: &dyn Any,
+    ) -> Option<Result<ErasedValue, ParsqlError>> {
+        if self.method != method {
+            return None;
+        }
+        self.expectation.call_erased(method, entity)
+    }
+}
+
+trait ErasedExpectation: Send {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn counts(&self) -> (Option<usize>, usize);
+    fn call_erased(
+        &mut self,
+        method: MockMethod,
+        entity: &dyn Any,
+    ) -> Option<Result<ErasedValue, ParsqlError>>;
+}
+
+impl<T: 'static, O: Send + 'static> ErasedExpectation for Expectation<T, O> {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn call_erased(
+        &mut self,
+        method: MockMethod,
+        entity: &dyn Any,
+    ) -> Option<Result<ErasedValue, ParsqlError>> {
+        let entity = entity.downcast_ref::<T>()?;
+        if !self.matches(entity) {
+            return None;
+        }
+        Some(
+            self.call(method, entity)
+                .map(|value| Box::new(value) as ErasedValue),
+        )
+    }
+
+    fn counts(&self) -> (Option<usize>, usize) {
+        (self.times, self.calls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answering(n: u64) -> Expectation<i64, u64> {
+        let mut expectation = Expectation::new();
+        expectation.returning(move |_| Ok(n));
+        expectation
+    }
+
+    #[test]
+    fn times_saturates_the_expectation() {
+        let mut expectation = answering(1);
+        expectation.times(2);
+        for _ in 0..2 {
+            assert!(expectation.matches(&0));
+            assert_eq!(expectation.call(MockMethod::Update, &0).unwrap(), 1);
+        }
+        assert!(!expectation.matches(&0));
+
+        assert!(!answering(1).never().matches(&0));
+        assert!(answering(1).once().matches(&0));
+    }
+
+    #[test]
+    fn withf_filters_the_entity() {
+        let mut expectation = answering(1);
+        expectation.withf(|id| *id > 10);
+        assert!(expectation.matches(&11));
+        assert!(!expectation.matches(&10));
+    }
+
+    #[test]
+    fn entry_answers_its_own_method_and_type() {
+        let mut entry = Entry::new::<i64, u64>(MockMethod::Update);
+        entry.downcast_mut::<i64, u64>().unwrap().returning(|id| Ok(id.unsigned_abs())).once();
+
+        assert!(entry.try_call::<i64, u64>(MockMethod::Delete, &3).is_none());
+        assert!(entry.try_call::<i32, u64>(MockMethod::Update, &3).is_none());
+        assert_eq!(entry.try_call::<i64, u64>(MockMethod::Update, &3).unwrap().unwrap(), 3);
+        // `once` doldu
+        assert!(entry.try_call::<i64, u64>(MockMethod::Update, &3).is_none());
+        assert_eq!(entry.unsatisfied(), None);
+    }
+
+    #[test]
+    fn entry_reports_unmet_counts() {
+        let mut entry = Entry::new::<i64, u64>(MockMethod::Delete);
+        entry.downcast_mut::<i64, u64>().unwrap().returning(|_| Ok(1)).times(2);
+        entry.try_call::<i64, u64>(MockMethod::Delete, &1);
+
+        assert_eq!(entry.unsatisfied().unwrap(), "`delete` for `i64`: expected 2 call(s), got 1");
+        // Sayısız beklenti hiç çağrılmasa da karşılanmış sayılır
+        assert_eq!(Entry::new::<i64, u64>(MockMethod::Delete).unsatisfied(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expectation for `fetch` has no `returning` function")]
+    fn call_without_returning_panics() {
+        let mut expectation = Expectation::<i64, u64>::new();
+        let _ = expectation.call(MockMethod::Fetch, &1);
+    }
+}
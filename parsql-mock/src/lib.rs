@@ -0,0 +1,67 @@
+//! # parsql-mock
+//!
+//! In-memory mock backend for parsql.
+//! `MockClient` implements the `CrudOps` trait of `parsql-tokio-postgres`, so
+//! services written against `CrudOps` can be unit tested without a live database.
+//! It also implements `ParsqlExecutor`, so it can be injected as `Arc<dyn ParsqlExecutor>`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use parsql_mock::{MockClient, MockValue};
+//! use parsql_tokio_postgres::{
+//!     macros::{FromRow, Insertable, Queryable, SqlParams},
+//!     traits::{CrudOps, FromRow, SqlParams, SqlQuery},
+//! };
+//! use parsql_tokio_postgres::ParsqlError;
+//! use tokio_postgres::{types::ToSql, Error, Row};
+//!
+//! #[derive(Insertable, SqlParams)]
+//! #[table("users")]
+//! pub struct InsertUser {
+//!     pub name: String,
+//! }
+//!
+//! #[derive(Queryable, SqlParams, FromRow, Debug, PartialEq)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! pub struct GetUser {
+//!     pub id: i64,
+//!     pub name: String,
+//! }
+//!
+//! // Service code only depends on the CrudOps trait
+//! async fn register<C: CrudOps + Sync>(db: &C, name: &str) -> Result<GetUser, ParsqlError> {
+//!     let id: i64 = db.insert(InsertUser { name: name.to_string() }).await?;
+//!     db.fetch(GetUser { id, name: String::new() }).await
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), ParsqlError> {
+//!     let mut mock = MockClient::new();
+//!     mock.expect_insert::<InsertUser>()
+//!         .withf(|user| user.name == "John")
+//!         .returning(|_| Ok(MockValue::new(7i64)))
+//!         .once();
+//!     mock.expect_fetch::<GetUser>()
+//!         .returning(|query| Ok(GetUser { id: query.id, name: "John".to_string() }));
+//!
+//!     let user = register(&mock, "John").await?;
+//!     assert_eq!(user, GetUser { id: 7, name: "John".to_string() });
+//!     assert_eq!(mock.calls().len(), 2);
+//!     Ok(())
+//! }
+//! ```
+
+mod expectation;
+mod mock_client;
+mod value;
+
+pub use expectation::{Expectation, MockMethod};
+pub use mock_client::{MockCall, MockClient};
+pub use value::MockValue;
+
+// Mock'un uyguladığı trait'leri dışa aktar
+pub use parsql_tokio_postgres::traits::{CrudOps, FromRow, SqlParams, SqlQuery, SqlWith, UpdateParams, Validate, ValidationError};
+pub use parsql_tokio_postgres::ParsqlError;
+pub use tokio_postgres::{types::ToSql, Error, Row};
@@ -0,0 +1,391 @@
+use std::sync::{Mutex, MutexGuard};
+
+use parsql_tokio_postgres::executor::{ErasedCall, ErasedOutput, ParsqlExecutor};
+use parsql_tokio_postgres::traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams};
+use parsql_tokio_postgres::ParsqlError;
+use tokio_postgres::{types::FromSql, Error, Row};
+
+use crate::expectation::{Entry, Expectation, MockMethod};
+use crate::value::MockValue;
+
+/// A recorded call made against a [`MockClient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCall {
+    /// Called `CrudOps` method
+    pub method: MockMethod,
+    /// SQL generated by the entity type (`SqlQuery::query()`)
+    pub sql: String,
+}
+
+/// In-memory `CrudOps` implementation driven by programmed expectations.
+///
+/// Every `CrudOps` call looks for the first matching, unsaturated expectation
+/// registered for the same method and entity type and returns its result.
+/// A call without a matching expectation panics, as does dropping the mock
+/// while an expectation with a call count (`times`, `once`, `never`) is unmet.
+#[derive(Default)]
+pub struct MockClient {
+    expectations: Mutex<Vec<Entry>>,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl MockClient {
+    /// Creates a mock without any expectation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn expect<T: 'static, O: Send + 'static>(&mut self, method: MockMethod) -> &mut Expectation<T, O> {
+        let expectations = self
+            .expectations
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner());
+        expectations.push(Entry::new::<T, O>(method));
+        expectations
+            .last_mut()
+            .and_then(|entry| entry.downcast_mut::<T, O>())
+            .expect("freshly pushed expectation has the requested type")
+    }
+
+    /// Programs an `insert` of entity type `T`. The returned [`MockValue`] is
+    /// converted into the value type requested by the caller.
+    pub fn expect_insert<T: 'static>(&mut self) -> &mut Expectation<T, MockValue> {
+        self.expect(MockMethod::Insert)
+    }
+
+    /// Programs an `update` of entity type `T`.
+    pub fn expect_update<T: 'static>(&mut self) -> &mut Expectation<T, u64> {
+        self.expect(MockMethod::Update)
+    }
+
+    /// Programs a `delete` of entity type `T`, returning the affected row count.
+    pub fn expect_delete<T: 'static>(&mut self) -> &mut Expectation<T, u64> {
+        self.expect(MockMethod::Delete)
+    }
+
+    /// Programs a `call` of procedure type `T`, returning the affected row count.
+    pub fn expect_call<T: 'static>(&mut self) -> &mut Expectation<T, u64> {
+        self.expect(MockMethod::Call)
+    }
+
+    /// Programs a `fetch` of entity type `T`.
+    pub fn expect_fetch<T: Send + 'static>(&mut self) -> &mut Expectation<T, T> {
+        self.expect(MockMethod::Fetch)
+    }
+
+    /// Programs a `fetch_all` of entity type `T`.
+    pub fn expect_fetch_all<T: Send + 'static>(&mut self) -> &mut Expectation<T, Vec<T>> {
+        self.expect(MockMethod::FetchAll)
+    }
+
+    /// Programs a `select` of entity type `T` producing `R`.
+    /// The row mapping function passed to `select` is not invoked.
+    pub fn expect_select<T: 'static, R: Send + 'static>(&mut self) -> &mut Expectation<T, R> {
+        self.expect(MockMethod::Select)
+    }
+
+    /// Programs a `select_all` of entity type `T` producing `Vec<R>`.
+    /// The row mapping function passed to `select_all` is not invoked.
+    pub fn expect_select_all<T: 'static, R: Send + 'static>(&mut self) -> &mut Expectation<T, Vec<R>> {
+        self.expect(MockMethod::SelectAll)
+    }
+
+    /// Returns the calls made so far, in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        lock(&self.calls).clone()
+    }
+
+    /// Verifies the call counts of all expectations and removes them.
+    ///
+    /// # Panics
+    /// When an expectation with a call count is unmet.
+    pub fn checkpoint(&mut self) {
+        let entries = std::mem::take(
+            self.expectations
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+        let unmet: Vec<String> = entries.iter().filter_map(Entry::unsatisfied).collect();
+        if !unmet.is_empty() {
+            panic!("[PARSQL-MOCK] Unmet expectations:\n{}", unmet.join("\n"));
+        }
+    }
+
+    fn record(&self, method: MockMethod, sql: String) {
+        lock(&self.calls).push(MockCall { method, sql });
+    }
+
+    fn respond<T, O>(&self, method: MockMethod, entity: &T) -> Result<O, ParsqlError>
+    where
+        T: SqlQuery + 'static,
+        O: 'static,
+    {
+        self.record(method, T::query().to_string());
+
+        let mut expectations = lock(&self.expectations);
+        expectations
+            .iter_mut()
+            .find_map(|entry| entry.try_call::<T, O>(method, entity))
+            .unwrap_or_else(|| {
+                panic!(
+                    "[PARSQL-MOCK] No matching expectation for `{}` of `{}`",
+                    method,
+                    std::any::type_name::<T>()
+                )
+            })
+    }
+}
+
+#[async_trait::async_trait]
+impl ParsqlExecutor for MockClient {
+    async fn execute_erased(&self, call: ErasedCall<'_>) -> Result<ErasedOutput, ParsqlError> {
+        let method = MockMethod::from(call.operation);
+        self.record(method, call.sql.to_string());
+
+        let value = lock(&self.expectations)
+            .iter_mut()
+            .find_map(|entry| entry.try_call_erased(method, call.entity))
+            .unwrap_or_else(|| {
+                panic!(
+                    "[PARSQL-MOCK] No matching expectation for `{}`: {}",
+                    method, call.sql
+                )
+            })?;
+
+        // insert beklentileri MockValue döndürür, çağıran taraf içindeki değeri bekler
+        let value = match value.downcast::<MockValue>() {
+            Ok(mock_value) => mock_value.into_any(),
+            Err(value) => value,
+        };
+        Ok(ErasedOutput::Value(value))
+    }
+}
+
+impl Drop for MockClient {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.checkpoint();
+        }
+    }
+}
+
+fn lock<V>(mutex: &Mutex<V>) -> MutexGuard<'_, V> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[async_trait::async_trait]
+impl CrudOps for MockClient {
+    async fn insert<T, P: for<'a> FromSql<'a> + Send + Sync>(&self, entity: T) -> Result<P, ParsqlError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
+    {
+        let value: MockValue = self.respond(MockMethod::Insert, &entity)?;
+        Ok(value.decode())
+    }
+
+    async fn update<T>(&self, entity: T) -> Result<u64, ParsqlError>
+    where
+        T: SqlQuery + UpdateParams + Send + Sync + 'static,
+    {
+        self.respond(MockMethod::Update, &entity)
+    }
+
+    async fn delete<T>(&self, entity: T) -> Result<u64, ParsqlError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
+    {
+        self.respond(MockMethod::Delete, &entity)
+    }
+
+    async fn call<T>(&self, entity: T) -> Result<u64, ParsqlError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
+    {
+        self.respond(MockMethod::Call, &entity)
+    }
+
+    async fn fetch<T>(&self, params: T) -> Result<T, ParsqlError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+    {
+        self.respond(MockMethod::Fetch, &params)
+    }
+
+    async fn fetch_all<T>(&self, params: T) -> Result<Vec<T>, ParsqlError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+    {
+        self.respond(MockMethod::FetchAll, &params)
+    }
+
+    async fn select<T, F, R>(&self, entity: T, _to_model: F) -> Result<R, ParsqlError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
+        F: Fn(&Row) -> Result<R, Error> + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        self.respond(MockMethod::Select, &entity)
+    }
+
+    async fn select_all<T, F, R>(&self, entity: T, _to_model: F) -> Result<Vec<R>, ParsqlError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
+        F: Fn(&Row) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        self.respond(MockMethod::SelectAll, &entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parsql_tokio_postgres::executor::{Operation, ParsqlExecutor};
+    use parsql_tokio_postgres::macros::{FromRow, Insertable, Queryable, SqlParams, UpdateParams, Updateable};
+    use parsql_tokio_postgres::ParsqlError;
+    use tokio_postgres::types::ToSql;
+
+    use super::*;
+
+    #[derive(Insertable, SqlParams)]
+    #[table("users")]
+    #[returning("id")]
+    struct InsertUser {
+        name: String,
+    }
+
+    #[derive(Queryable, SqlParams)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    struct UserName {
+        id: i64,
+    }
+
+    #[derive(Updateable, UpdateParams)]
+    #[table("users")]
+    #[update("name")]
+    #[where_clause("id = $")]
+    struct UpdateUser {
+        id: i64,
+        name: String,
+    }
+
+    fn rename(id: i64) -> UpdateUser {
+        UpdateUser { id, name: "ali".into() }
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug, PartialEq)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    struct GetUser {
+        id: i64,
+    }
+
+    fn user(id: i64) -> GetUser {
+        GetUser { id }
+    }
+
+    /// The first matching, unsaturated expectation answers; calls are recorded in order
+    #[tokio::test]
+    async fn first_matching_expectation_answers() {
+        let mut mock = MockClient::new();
+        mock.expect_fetch::<GetUser>()
+            .withf(|query| query.id == 2)
+            .returning(|_| Ok(user(20)));
+        mock.expect_fetch::<GetUser>()
+            .returning(|query| Ok(user(query.id * 100)))
+            .once();
+        mock.expect_fetch::<GetUser>()
+            .returning(|_| Ok(user(0)));
+
+        assert_eq!(mock.fetch(user(2)).await.unwrap(), user(20));
+        assert_eq!(mock.fetch(user(1)).await.unwrap(), user(100));
+        // `once` doldu, sıradaki beklenti cevap verir
+        assert_eq!(mock.fetch(user(1)).await.unwrap(), user(0));
+        assert_eq!(mock.fetch(user(2)).await.unwrap(), user(20));
+
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 4);
+        assert!(calls.iter().all(|call| call.method == MockMethod::Fetch && call.sql == GetUser::query()));
+    }
+
+    #[tokio::test]
+    async fn met_counts_pass_the_checkpoint() {
+        let mut mock = MockClient::new();
+        mock.expect_update::<UpdateUser>().returning(|_| Ok(1)).times(2);
+        mock.expect_delete::<UserName>().returning(|_| Ok(1)).never();
+
+        assert_eq!(mock.update(rename(1)).await.unwrap(), 1);
+        assert_eq!(mock.update(rename(2)).await.unwrap(), 1);
+        mock.checkpoint();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "No matching expectation for `delete`")]
+    async fn never_rejects_the_call() {
+        let mut mock = MockClient::new();
+        mock.expect_delete::<UserName>().returning(|_| Ok(1)).never();
+
+        let _ = mock.delete(UserName { id: 1 }).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "No matching expectation for `fetch`")]
+    async fn unmatched_call_panics() {
+        let mut mock = MockClient::new();
+        mock.expect_fetch::<GetUser>()
+            .withf(|query| query.id == 1)
+            .returning(|_| Ok(user(1)));
+
+        let _ = mock.fetch(user(2)).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "expected 2 call(s), got 1")]
+    async fn checkpoint_reports_unmet_times() {
+        let mut mock = MockClient::new();
+        mock.expect_update::<UpdateUser>().returning(|_| Ok(1)).times(2);
+
+        let _ = mock.update(rename(1)).await;
+        mock.checkpoint();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Unmet expectations")]
+    async fn checkpoint_panics_on_unmet_once() {
+        let mut mock = MockClient::new();
+        mock.expect_insert::<InsertUser>()
+            .returning(|_| Ok(MockValue::new(1i64)))
+            .once();
+
+        mock.checkpoint();
+    }
+
+    #[test]
+    #[should_panic(expected = "`fetch` for")]
+    fn drop_panics_on_unmet_once() {
+        let mut mock = MockClient::new();
+        mock.expect_fetch::<GetUser>().returning(|_| Ok(user(1))).once();
+        drop(mock);
+    }
+
+    #[tokio::test]
+    async fn wrong_output_type_is_an_error() {
+        let mut mock = MockClient::new();
+        mock.expect_insert::<InsertUser>()
+            .returning(|_| Ok(MockValue::new("7")));
+        mock.expect_select::<UserName, String>()
+            .returning(|_| Ok("ali".to_string()));
+
+        let executor: &dyn ParsqlExecutor = &mock;
+        let error = executor
+            .insert::<_, i64>(InsertUser { name: "ali".into() })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ParsqlError::UnexpectedOutput(Operation::Insert)));
+        let error = executor
+            .select(UserName { id: 1 }, |row| row.try_get::<_, i64>(0))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ParsqlError::UnexpectedOutput(Operation::Select)));
+    }
+}
@@ -0,0 +1,137 @@
+use std::any::Any;
+
+use bytes::BytesMut;
+use tokio_postgres::types::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres types tried, in order, when converting a [`MockValue`]
+/// into the value type requested by `insert`.
+const CANDIDATE_TYPES: &[Type] = &[
+    Type::INT8,
+    Type::INT4,
+    Type::INT2,
+    Type::OID,
+    Type::BOOL,
+    Type::CHAR,
+    Type::FLOAT8,
+    Type::FLOAT4,
+    Type::TEXT,
+    Type::VARCHAR,
+    Type::BPCHAR,
+    Type::NAME,
+    Type::BYTEA,
+    Type::UUID,
+    Type::JSONB,
+    Type::JSON,
+    Type::NUMERIC,
+    Type::TIMESTAMPTZ,
+    Type::TIMESTAMP,
+    Type::DATE,
+    Type::TIME,
+];
+
+/// Value returned by a mocked `insert`.
+///
+/// `CrudOps::insert` is generic over its return type, so the mock keeps the
+/// programmed value as a `ToSql` object and converts it through the Postgres
+/// wire format into whatever type the caller asks for (e.g. `i64` for a
+/// `RETURNING id` column).
+pub struct MockValue(Box<dyn MockScalar>);
+
+/// A `ToSql` value that can also be handed out as `Any`.
+trait MockScalar: ToSql + Send + Sync {
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send>;
+}
+
+impl<V: ToSql + Send + Sync + 'static> MockScalar for V {
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send> {
+        self
+    }
+}
+
+impl MockValue {
+    /// Wraps a value that can be sent to Postgres.
+    pub fn new<V: ToSql + Send + Sync + 'static>(value: V) -> Self {
+        MockValue(Box::new(value))
+    }
+
+    /// Returns the wrapped value itself. Used on the `ParsqlExecutor` path,
+    /// where the requested type is matched exactly.
+    pub(crate) fn into_any(self) -> Box<dyn Any + Send> {
+        self.0.into_any()
+    }
+
+    /// Converts the value into `P`.
+    ///
+    /// # Panics
+    /// When no Postgres type is accepted by both the stored value and `P`.
+    pub(crate) fn decode<P: for<'a> FromSql<'a>>(&self) -> P {
+        let mut buf = BytesMut::new();
+        for ty in CANDIDATE_TYPES.iter().filter(|ty| P::accepts(ty)) {
+            buf.clear();
+            // to_sql_checked rejects the types the stored value doesn't accept
+            let decoded = match self.0.to_sql_checked(ty, &mut buf) {
+                Ok(IsNull::Yes) => P::from_sql_null(ty),
+                Ok(IsNull::No) => P::from_sql(ty, &buf),
+                Err(_) => continue,
+            };
+            return decoded
+                .unwrap_or_else(|e| panic!("[PARSQL-MOCK] Insert value conversion failed: {}", e));
+        }
+
+        panic!(
+            "[PARSQL-MOCK] Programmed insert value {:?} can not be converted into `{}`",
+            self.0,
+            std::any::type_name::<P>()
+        )
+    }
+}
+
+impl std::fmt::Debug for MockValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_into_the_requested_type() {
+        assert_eq!(MockValue::new(7i64).decode::<i64>(), 7);
+        assert_eq!(MockValue::new(7i32).decode::<i32>(), 7);
+        assert_eq!(MockValue::new(7i16).decode::<i16>(), 7);
+        assert_eq!(MockValue::new(7u32).decode::<u32>(), 7);
+        assert!(MockValue::new(true).decode::<bool>());
+        assert_eq!(MockValue::new(1.5f64).decode::<f64>(), 1.5);
+        assert_eq!(MockValue::new(1.5f32).decode::<f32>(), 1.5);
+        assert_eq!(MockValue::new("ali").decode::<String>(), "ali");
+        assert_eq!(MockValue::new("ali".to_string()).decode::<String>(), "ali");
+        assert_eq!(MockValue::new(vec![1u8, 2]).decode::<Vec<u8>>(), [1, 2]);
+    }
+
+    #[test]
+    fn decodes_null_into_option() {
+        assert_eq!(MockValue::new(None::<i64>).decode::<Option<i64>>(), None);
+        assert_eq!(MockValue::new(Some(7i64)).decode::<Option<i64>>(), Some(7));
+    }
+
+    #[test]
+    fn into_any_keeps_the_programmed_type() {
+        let value = MockValue::new(7i32).into_any();
+        assert_eq!(value.downcast_ref::<i32>(), Some(&7));
+        assert!(value.downcast_ref::<i64>().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "can not be converted into `i64`")]
+    fn mismatched_type_panics() {
+        MockValue::new("7").decode::<i64>();
+    }
+
+    #[test]
+    #[should_panic(expected = "can not be converted into `i64`")]
+    fn narrower_integer_is_not_widened() {
+        MockValue::new(7i32).decode::<i64>();
+    }
+}
@@ -0,0 +1,201 @@
+//! Read-through caching for `CrudOps`.
+//!
+//! [`Cached`] wraps a client (or anything implementing `CrudOps`) together with a
+//! [`CacheProvider`]. `fetch` and `fetch_all` are answered from the cache when
+//! possible, and `insert`, `update` and `delete` invalidate the cached results that
+//! read the written table. Every other method reaches the wrapped client through
+//! `Deref`, so call sites stay the same.
+//!
+//! Results are keyed by the query and the wire encoding of its values (see
+//! [`encode_postgres_params`](parsql_core::cache::encode_postgres_params)); queries
+//! binding a value of a custom type run uncached. `Cached` implements `CrudOps` too, so
+//! code generic over the trait still invalidates on writes, but its reads are not cached:
+//! the trait's `fetch` does not require the `Clone + 'static` a cached result needs.
+//!
+//!
+//! ```rust,no_run
+//! use parsql_postgres::cache::{Cached, MemoryCache};
+//! use parsql_postgres::macros::{FromRow, Queryable, SqlParams};
+//! use parsql_postgres::traits::{FromRow, SqlParams, SqlQuery};
+//! use postgres::{types::ToSql, Client, Error, NoTls, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow, Clone, Debug)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! pub struct GetUser {
+//!     pub id: i32,
+//!     pub name: String,
+//! }
+//!
+//! fn main() -> Result<(), Error> {
+//!     let client = Client::connect("host=localhost user=postgres", NoTls)?;
+//!
+//!     let mut db = Cached::new(client, MemoryCache::new());
+//!     let user = db.fetch(&GetUser { id: 1, name: String::new() })?;
+//!     // Served from the cache until the users table is written through `db`
+//!     let again = db.fetch(&GetUser { id: 1, name: String::new() })?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::ops::{Deref, DerefMut};
+
+use parsql_core::cache::{encode_postgres_params, invalidation_trigger, ReadThrough, INVALIDATION_CHANNEL, LISTEN};
+use parsql_core::trace;
+use postgres::fallible_iterator::FallibleIterator;
+use postgres::types::FromSql;
+use postgres::{Client, Error, GenericClient, Row};
+
+use crate::traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams};
+
+pub use parsql_core::cache::{CacheKey, CacheProvider, CachedValue, MemoryCache};
+
+/// A `CrudOps` implementation with a read-through cache in front of it.
+pub struct Cached<C, P = MemoryCache> {
+    inner: C,
+    cache: ReadThrough<P>,
+}
+
+impl<C, P: CacheProvider> Cached<C, P> {
+    /// Wraps `inner`, caching its fetch results in `cache`.
+    pub fn new(inner: C, cache: P) -> Self {
+        Self { inner, cache: ReadThrough::new(cache, "PARSQL-POSTGRES-CACHE") }
+    }
+
+    /// The cache provider.
+    pub fn cache(&self) -> &P {
+        self.cache.provider()
+    }
+
+    /// Returns the wrapped client.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C, P> Deref for Cached<C, P> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.inner
+    }
+}
+
+impl<C, P> DerefMut for Cached<C, P> {
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+}
+
+impl<C, P> Cached<C, P>
+where
+    C: CrudOps,
+    P: CacheProvider,
+{
+    /// Retrieves a single record, from the cache when it holds one.
+    pub fn fetch<T>(&mut self, params: &T) -> Result<T, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Clone + Send + Sync + 'static,
+    {
+        let values = encode_postgres_params(&params.params());
+        self.cache.fetch(T::query(), values.as_deref(), || self.inner.fetch(params))
+    }
+
+    /// Retrieves all matching records, from the cache when it holds them.
+    pub fn fetch_all<T>(&mut self, params: &T) -> Result<Vec<T>, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Clone + Send + Sync + 'static,
+    {
+        let values = encode_postgres_params(&params.params());
+        self.cache.fetch(T::query(), values.as_deref(), || self.inner.fetch_all(params))
+    }
+}
+
+impl<C, P> CrudOps for Cached<C, P>
+where
+    C: CrudOps,
+    P: CacheProvider,
+{
+    fn insert<T: SqlQuery + SqlParams, R: for<'a> FromSql<'a> + Send + Sync>(&mut self, entity: T) -> Result<R, Error> {
+        self.cache.written(T::query(), self.inner.insert(entity))
+    }
+
+    fn update<T: SqlQuery + UpdateParams>(&mut self, entity: T) -> Result<u64, Error> {
+        self.cache.written(T::query(), self.inner.update(entity))
+    }
+
+    fn delete<T: SqlQuery + SqlParams>(&mut self, entity: T) -> Result<u64, Error> {
+        self.cache.written(T::query(), self.inner.delete(entity))
+    }
+
+    fn call<T: SqlQuery + SqlParams>(&mut self, entity: T) -> Result<u64, Error> {
+        self.inner.call(entity)
+    }
+
+    fn fetch<T: SqlQuery + FromRow + SqlParams>(&mut self, entity: &T) -> Result<T, Error> {
+        self.inner.fetch(entity)
+    }
+
+    fn fetch_all<T: SqlQuery + FromRow + SqlParams>(&mut self, entity: &T) -> Result<Vec<T>, Error> {
+        self.inner.fetch_all(entity)
+    }
+
+    fn select<T, F, R>(&mut self, entity: &T, to_model: F) -> Result<R, Error>
+    where
+        T: SqlQuery + SqlParams,
+        F: FnOnce(&Row) -> Result<R, Error>,
+    {
+        self.inner.select(entity, to_model)
+    }
+
+    fn select_all<T, F, R>(&mut self, entity: &T, to_model: F) -> Result<Vec<R>, Error>
+    where
+        T: SqlQuery + SqlParams,
+        F: FnMut(&Row) -> Result<R, Error>,
+    {
+        self.inner.select_all(entity, to_model)
+    }
+}
+
+/// Creates the trigger that reports every write to `table` to the [`listen`]ers.
+///
+/// Run it once per cached table; running it again replaces the trigger. Name the
+/// table the way the cached queries do.
+///
+/// # Panics
+/// If `table` is not a plain or schema-qualified name.
+pub fn install_invalidation<C: GenericClient>(client: &mut C, table: &str) -> Result<(), Error> {
+    let sql = invalidation_trigger(table)
+        .unwrap_or_else(|| panic!("install_invalidation: `{}` is not a plain table name", table));
+    trace::log_sql("PARSQL-POSTGRES-CACHE", &sql);
+    client.batch_execute(&sql)
+}
+
+/// Drops the cached results reading a table whenever the database reports a write to it.
+///
+/// Blocks on `client` until its connection closes, so give it a connection of its own
+/// and run it on a separate thread:
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use parsql_postgres::cache::{listen, MemoryCache};
+/// use postgres::{Client, NoTls};
+///
+/// let cache = Arc::new(MemoryCache::new());
+/// let mut listener = Client::connect("host=localhost user=postgres", NoTls).unwrap();
+/// let invalidated = cache.clone();
+/// std::thread::spawn(move || listen(&mut listener, invalidated));
+/// ```
+pub fn listen<P: CacheProvider>(client: &mut Client, cache: P) -> Result<(), Error> {
+    client.batch_execute(LISTEN)?;
+
+    let mut notifications = client.notifications();
+    let mut notifications = notifications.blocking_iter();
+    while let Some(notification) = notifications.next()? {
+        if notification.channel() == INVALIDATION_CHANNEL {
+            trace::log("PARSQL-POSTGRES-CACHE", format!("Invalidated: {}", notification.payload()));
+            cache.invalidate(notification.payload());
+        }
+    }
+    Ok(())
+}
@@ -1,5 +1,9 @@
-use postgres::{types::{FromSql, ToSql}, Client, Error, Row};
-use crate::traits::{SqlQuery, SqlParams, FromRow, UpdateParams, CrudOps};
+use std::hash::Hash;
+
+use parsql_core::{batch::{multi_row_insert, with_returning, with_upsert, BatchOptions, Dialect}, criteria, lookup::{self, Lookup}, metrics::observe, paging::{check_sortable, with_first_row, with_limit_offset}, trace};
+use postgres::{types::{FromSql, ToSql}, Client, Error, GenericClient, Row};
+use crate::traits::{SqlQuery, SqlParams, FromRow, UpdateParams, CrudOps, Keyed, Paginated, Patch, WhereFragments};
+use crate::validation::{check, reject};
 
 
 // CrudOps trait implementasyonu postgres::Client için
@@ -16,6 +20,10 @@ impl CrudOps for Client {
         delete(self, entity)
     }
 
+    fn call<T: SqlQuery + SqlParams>(&mut self, entity: T) -> Result<u64, Error> {
+        call(self, entity)
+    }
+
     fn fetch<T: SqlQuery + FromRow + SqlParams>(&mut self, entity: &T) -> Result<T, Error> {
         fetch(self, entity)
     }
@@ -29,14 +37,12 @@ impl CrudOps for Client {
         T: SqlQuery + SqlParams,
         F: FnOnce(&Row) -> Result<R, Error>,
     {
-        let sql = T::query();
+        let sql = T::query_single();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-POSTGRES] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-POSTGRES", sql);
 
         let params = entity.params();
-        let row = self.query_one(&sql, &params)?;
+        let row = observe("select", T::table_name(), || self.query_one(sql, &params))?;
         to_model(&row)
     }
 
@@ -47,12 +53,9 @@ impl CrudOps for Client {
     {
         let sql = T::query();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-POSTGRES] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-POSTGRES", sql);
 
-        let params = entity.params();
-        let rows = self.query(&sql, &params)?;
+        let rows = observe("select_all", T::table_name(), || crate::params::query(self, sql, entity))?;
         
         rows.iter().map(to_model).collect()
     }
@@ -117,16 +120,107 @@ impl CrudOps for Client {
 /// }
 /// ```
 pub fn insert<T: SqlQuery + SqlParams, P:for<'a> FromSql<'a> + Send + Sync>(client: &mut Client, entity: T) -> Result<P, Error> {
+    check(client, entity.validation())?;
+
     let sql = T::query();
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-POSTGRES] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-POSTGRES", sql);
 
     let params = entity.params();
-    let row = client.query_one(&sql, &params)?;
+    let row = observe("insert", T::table_name(), || client.query_one(sql, &params))?;
     row.try_get::<_, P>(0)
 }
 
+/// # insert_returning_id
+/// 
+/// Inserts a new record and returns its generated `id`, appending `RETURNING id` when
+/// the struct has no `#[returning]` attribute, instead of a `SELECT lastval()` afterwards.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client or transaction
+/// - `entity`: Data object to be inserted (must implement SqlQuery and SqlParams traits)
+/// 
+/// ## Return Value
+/// - `Result<K, Error>`: On success, returns the generated key; on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// // INSERT INTO users (name, email ) VALUES ($1, $2 ) RETURNING id
+/// let id: i64 = insert_returning_id(&mut client, InsertUser { name, email })?;
+/// ```
+pub fn insert_returning_id<C: GenericClient, T: SqlQuery + SqlParams, K: for<'a> FromSql<'a> + Send + Sync>(client: &mut C, entity: T) -> Result<K, Error> {
+    insert_returning(client, entity, "id")
+}
+
+/// # insert_returning
+/// 
+/// [`insert_returning_id`] for a key column with another name. An existing `#[returning]`
+/// attribute takes precedence over `column`.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client or transaction
+/// - `entity`: Data object to be inserted (must implement SqlQuery and SqlParams traits)
+/// - `column`: The key column to return
+/// 
+/// ## Return Value
+/// - `Result<K, Error>`: On success, returns the value of `column`; on failure, returns Error
+/// 
+/// ## Panics
+/// Panics when `column` is not a plain column name.
+pub fn insert_returning<C: GenericClient, T: SqlQuery + SqlParams, K: for<'a> FromSql<'a> + Send + Sync>(client: &mut C, entity: T, column: &str) -> Result<K, Error> {
+    check(client, entity.validation())?;
+    let sql = with_returning(T::query(), column)
+        .unwrap_or_else(|| panic!("insert_returning: `{}` is not a plain column name", column));
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let params = entity.params();
+    let row = observe("insert", T::table_name(), || client.query_one(sql.as_str(), &params))?;
+    row.try_get::<_, K>(0)
+}
+
+/// # insert_returning_opt
+/// 
+/// [`insert_returning`] for idempotent inserts. When the statement skips an existing row
+/// with `ON CONFLICT DO NOTHING` (see `#[on_conflict_do_nothing]`), nothing is returned
+/// and the result is `None` instead of an error.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client or transaction
+/// - `entity`: Data object to be inserted (must implement SqlQuery and SqlParams traits)
+/// - `column`: The key column to return
+/// 
+/// ## Return Value
+/// - `Result<Option<K>, Error>`: On success, returns the value of `column`, or `None` when the
+///   row already existed; on failure, returns Error
+/// 
+/// ## Panics
+/// Panics when `column` is not a plain column name.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// #[on_conflict_do_nothing("email")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+/// 
+/// // INSERT INTO users (name, email) VALUES ($1, $2) ON CONFLICT (email) DO NOTHING RETURNING id
+/// if let Some(id) = insert_returning_opt::<_, _, i64>(&mut client, user, "id")? {
+///     println!("new user {}", id);
+/// }
+/// ```
+pub fn insert_returning_opt<C: GenericClient, T: SqlQuery + SqlParams, K: for<'a> FromSql<'a> + Send + Sync>(client: &mut C, entity: T, column: &str) -> Result<Option<K>, Error> {
+    check(client, entity.validation())?;
+    let sql = with_returning(T::query(), column)
+        .unwrap_or_else(|| panic!("insert_returning_opt: `{}` is not a plain column name", column));
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let params = entity.params();
+    let row = observe("insert", T::table_name(), || client.query_opt(sql.as_str(), &params))?;
+    row.map(|row| row.try_get::<_, K>(0)).transpose()
+}
+
 /// # update
 /// 
 /// Updates an existing record in the database.
@@ -198,16 +292,13 @@ pub fn update<T: SqlQuery + UpdateParams>(
     client: &mut postgres::Client,
     entity: T,
 ) -> Result<u64, Error> {
+    check(client, entity.validation())?;
+
     let sql = T::query();
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-POSTGRES] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-POSTGRES", sql);
 
     let params = entity.params();
-    match client.execute(&sql, &params) {
-        Ok(rows_affected) => Ok(rows_affected),
-        Err(e) => Err(e),
-    }
+    observe("update", T::table_name(), || client.execute(sql, &params))
 }
 
 /// # delete
@@ -269,15 +360,239 @@ pub fn delete<T: SqlQuery + SqlParams>(
     entity: T,
 ) -> Result<u64, Error> {
     let sql = T::query();
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-POSTGRES] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-POSTGRES", sql);
 
-    let params = entity.params();
-    match client.execute(&sql, &params) {
-        Ok(rows_affected) => Ok(rows_affected),
-        Err(e) => Err(e),
+    observe("delete", T::table_name(), || crate::params::execute(client, sql, &entity))
+}
+
+/// # update_patch
+/// 
+/// Updates only the fields of a `Patch` that are `Some`. The `SET` clause is built at
+/// run time from those fields and the key is bound after them.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client or transaction
+/// - `key`: Key of the record to update
+/// - `patch`: The `<Model>Patch` generated by the `Patch` derive
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of updated records (0 without a
+///   query when no field is set); on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// #[derive(Patch)]
+/// #[table("users")]
+/// pub struct User {
+///     pub id: i64,
+///     pub name: String,
+///     pub email: String,
+/// }
+/// 
+/// // UPDATE users SET email = $1 WHERE id = $2
+/// let patch = UserPatch { email: Some("new@example.com".to_string()), ..Default::default() };
+/// update_patch(&mut client, 1, patch)?;
+/// ```
+pub fn update_patch<C: GenericClient, P: Patch>(
+    client: &mut C,
+    key: P::Key,
+    patch: P,
+) -> Result<u64, Error> {
+    let Some(sql) = patch.patch_sql() else {
+        return Ok(0);
+    };
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let mut params = patch.values();
+    params.push(&key);
+    observe("update", P::table(), || client.execute(sql.as_str(), &params))
+}
+
+/// # delete_where
+/// 
+/// Deletes the rows a `Queryable` selects, reusing its `#[where_clause]` instead of
+/// repeating the condition on a `Deletable` type.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `criteria`: Queryable whose table and WHERE clause pick the rows (must implement SqlQuery and SqlParams traits)
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of deleted records; on failure, returns Error
+/// 
+/// ## Panics
+/// When the query has no WHERE clause or reads through `#[join]` / `union`.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// #[derive(Queryable, SqlParams, FromRow)]
+/// #[table("users")]
+/// #[where_clause("state = $ AND last_login < $")]
+/// pub struct GetInactiveUsers {
+///     pub id: i64,
+///     pub state: i16,
+///     pub last_login: NaiveDateTime,
+/// }
+/// 
+/// // DELETE FROM users WHERE state = $1 AND last_login < $2
+/// let deleted = delete_where(&mut client, &criteria)?;
+/// ```
+pub fn delete_where<C: GenericClient, T: SqlQuery + SqlParams>(
+    client: &mut C,
+    criteria: &T,
+) -> Result<u64, Error> {
+    let (table, where_clause) = T::criteria().unwrap_or_else(|| {
+        panic!("delete_where: `{}` has no single-table WHERE clause to reuse", std::any::type_name::<T>())
+    });
+    let sql = criteria::delete_statement(table, where_clause);
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let params = criteria.params();
+    let params = &params[..criteria::where_param_count(where_clause)];
+    observe("delete", T::table_name(), || client.execute(sql.as_str(), params))
+}
+
+/// # update_where
+/// 
+/// Writes the `Some` fields of a `Patch` to the rows a `Queryable` selects, reusing its
+/// `#[where_clause]`. The values of the patch are bound first, then the criteria.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `criteria`: Queryable whose table and WHERE clause pick the rows (must implement SqlQuery and SqlParams traits)
+/// - `changes`: The `<Model>Patch` generated by the `Patch` derive, for the same table
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of updated records (0 without a
+///   query when no field is set); on failure, returns Error
+/// 
+/// ## Panics
+/// When the query has no WHERE clause, reads through `#[join]` / `union`, or the patch
+/// belongs to another table.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// // UPDATE users SET state = $1 WHERE state = $2 AND last_login < $3
+/// let changes = UserPatch { state: Some(2), ..Default::default() };
+/// let archived = update_where(&mut client, &criteria, &changes)?;
+/// ```
+pub fn update_where<C: GenericClient, T: SqlQuery + SqlParams, P: Patch>(
+    client: &mut C,
+    criteria: &T,
+    changes: &P,
+) -> Result<u64, Error> {
+    let (table, where_clause) = T::criteria().unwrap_or_else(|| {
+        panic!("update_where: `{}` has no single-table WHERE clause to reuse", std::any::type_name::<T>())
+    });
+    if P::table() != table {
+        panic!("update_where: the patch updates `{}` but the criteria select from `{}`", P::table(), table);
     }
+    let Some(sql) = criteria::update_statement(table, &changes.columns(), where_clause, Dialect::Postgres) else {
+        return Ok(0);
+    };
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let criteria_params = criteria.params();
+    let mut params = changes.values();
+    params.extend_from_slice(&criteria_params[..criteria::where_param_count(where_clause)]);
+    observe("update", T::table_name(), || client.execute(sql.as_str(), &params))
+}
+
+/// # fetch_all_combined
+/// 
+/// Retrieves the records of `T` matching every filter of `filters`. The `#[where_clause]`
+/// of each filter is joined with `AND` and its placeholders are renumbered after the
+/// filters before it, so one filter struct can narrow any query over its table.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `filters`: Tuple of filters, e.g. `(&by_state, &created_since)`; each is a `Queryable`
+///   with a single-table WHERE clause
+/// 
+/// ## Return Value
+/// - `Result<Vec<T>, Error>`: On success, returns the matching records; on failure, returns Error
+/// 
+/// ## Errors
+/// When the query of `T` binds parameters or is a `UNION`; the error's source is a
+/// `CombineError` and nothing is sent to the database.
+/// 
+/// ## Panics
+/// When a filter has no single-table WHERE clause.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// #[derive(Queryable, SqlParams)]
+/// #[table("users")]
+/// #[select("id")]
+/// #[where_clause("state = $")]
+/// pub struct ByState {
+///     pub state: i16,
+/// }
+///
+/// #[derive(Queryable, SqlParams)]
+/// #[table("users")]
+/// #[select("id")]
+/// #[where_clause("created_at >= $since")]
+/// pub struct CreatedSince {
+///     pub since: NaiveDateTime,
+/// }
+///
+/// // SELECT id, name FROM users WHERE (state = $1) AND (created_at >= $2) ORDER BY id
+/// let users: Vec<UserRow> = fetch_all_combined(&mut client, (&ByState { state: 1 }, &CreatedSince { since }))?;
+/// ```
+pub fn fetch_all_combined<C: GenericClient, T: SqlQuery + FromRow, F: WhereFragments>(
+    client: &mut C,
+    filters: F,
+) -> Result<Vec<T>, Error> {
+    let sql = match criteria::combined_query::<T>(&filters.conditions(), Dialect::Postgres) {
+        Ok(sql) => sql,
+        Err(e) => return Err(reject(client, e)),
+    };
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let params = filters.params();
+    let rows = observe("fetch_all", T::table_name(), || client.query(sql.as_str(), &params))?;
+    T::from_rows(&rows)
+}
+
+/// # call
+/// 
+/// Runs a stored procedure (`CALL`) or function (`SELECT`) with the arguments of a
+/// `Callable` struct. Use `select` instead to read what a function returns.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `entity`: Arguments of the call (must implement SqlQuery and SqlParams traits)
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of rows the call returned or affected; on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,no_run
+/// use postgres::{Client, NoTls, Error};
+/// use parsql::postgres::call;
+/// 
+/// #[derive(Callable, SqlParams)]
+/// #[procedure("refresh_user_stats")]
+/// pub struct RefreshUserStats {
+///     pub state: i16,
+/// }
+/// 
+/// fn main() -> Result<(), Error> {
+///     let mut client = Client::connect("host=localhost user=postgres dbname=test", NoTls)?;
+///
+///     call(&mut client, RefreshUserStats { state: 1 })?;
+///     Ok(())
+/// }
+/// ```
+pub fn call<T: SqlQuery + SqlParams>(
+    client: &mut postgres::Client,
+    entity: T,
+) -> Result<u64, Error> {
+    let sql = T::query();
+    trace::log_sql("PARSQL-POSTGRES", sql);
+
+    observe("call", T::table_name(), || crate::params::execute(client, sql, &entity))
 }
 
 /// # fetch
@@ -308,14 +623,12 @@ pub fn fetch<T: SqlQuery + FromRow + SqlParams>(
     client: &mut Client,
     params: &T,
 ) -> Result<T, Error> {
-    let sql = T::query();
+    let sql = T::query_single();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-POSTGRES] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-POSTGRES", sql);
 
     let query_params = params.params();
-    let row = client.query_one(&sql, &query_params)?;
+    let row = observe("fetch", T::table_name(), || client.query_one(sql, &query_params))?;
     T::from_row(&row)
 }
 
@@ -350,16 +663,295 @@ pub fn fetch_all<T: SqlQuery + FromRow + SqlParams>(
 ) -> Result<Vec<T>, Error> {
     let sql = T::query();
     
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-POSTGRES] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-POSTGRES", sql);
+
+    let rows = observe("fetch_all", T::table_name(), || crate::params::query(client, sql, params))?;
+    
+    T::from_rows(&rows)
+}
+
+/// # fetch_all_limited
+/// 
+/// Retrieves one page of records, appending `LIMIT` and `OFFSET` to the query at
+/// execution time. The query type doesn't need `#[limit]` / `#[offset]` attributes,
+/// so the same type serves every page size.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `params`: Query parameters (must implement SqlQuery, FromRow, and SqlParams traits)
+/// - `limit`: Maximum number of records to return
+/// - `offset`: Number of records to skip
+/// 
+/// ## Return Value
+/// - `Result<Vec<T>, Error>`: On success, returns the records of the page; on failure, returns Error
+/// 
+/// ## Panics
+/// Panics when the query of `T` already has a `LIMIT`, `OFFSET` or `FETCH` clause.
+/// 
+/// ## Example Usage
+/// ```rust,no_run
+/// # use parsql::postgres::{fetch_all_limited, macros::{FromRow, Queryable, SqlParams}, traits::{FromRow, SqlParams, SqlQuery}};
+/// # use postgres::{types::ToSql, Client, Error, NoTls, Row};
+/// #[derive(Queryable, FromRow, SqlParams)]
+/// #[table("users")]
+/// #[where_clause("state = $")]
+/// #[order_by("id")]
+/// pub struct UsersByState {
+///     pub id: i64,
+///     pub name: String,
+///     pub state: i16,
+/// }
+///
+/// # fn main() -> Result<(), Error> {
+/// # let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+/// let query = UsersByState { id: 0, name: String::new(), state: 1 };
+/// // SELECT id, name, state FROM users WHERE state = $1 ORDER BY id LIMIT $2 OFFSET $3
+/// let third_page = fetch_all_limited(&mut client, &query, 20, 40)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn fetch_all_limited<T: SqlQuery + FromRow + SqlParams>(
+    client: &mut Client,
+    params: &T,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<T>, Error> {
+    let mut query_params = params.params();
+    let sql = with_limit_offset(T::query(), query_params.len(), Dialect::Postgres)
+        .unwrap_or_else(|e| panic!("fetch_all_limited: {}", e));
+
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    query_params.push(&limit);
+    query_params.push(&offset);
+    let rows = observe("fetch_all", T::table_name(), || client.query(sql.as_str(), &query_params))?;
+
+    T::from_rows(&rows)
+}
+
+/// # fetch_page
+/// 
+/// Retrieves the page generated by `#[derive(Paginated)]`, i.e. [`fetch_all_limited`]
+/// with the query, limit and offset held by the page.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `page`: The `<Query>Page` value
+/// 
+/// ## Return Value
+/// - `Result<Vec<P::Query>, Error>`: On success, returns the records of the page; on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// // SELECT id, name, state FROM users WHERE state = $1 ORDER BY id LIMIT $2 OFFSET $3
+/// let second_page = fetch_page(&mut client, &UsersByStatePage::nth(query, 2, 20))?;
+/// ```
+pub fn fetch_page<P: Paginated>(client: &mut Client, page: &P) -> Result<Vec<P::Query>, Error> {
+    fetch_all_limited(client, page.query(), page.limit(), page.offset())
+}
+
+/// # fetch_first
+/// 
+/// Retrieves the first record of the query ordered by `column`, appending
+/// `ORDER BY column ASC NULLS LAST LIMIT 1` at execution time. `column` must be listed in
+/// the query type's `#[sortable("...")]`; an existing `ORDER BY` only breaks ties.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `params`: Query parameters (must implement SqlQuery, FromRow, and SqlParams traits)
+/// - `column`: The order column, one of the `#[sortable]` columns
+/// 
+/// ## Return Value
+/// - `Result<Option<T>, Error>`: On success, returns the record, or `None` when the query matches nothing;
+///   on failure, returns Error. An unlisted `column` fails before anything is sent, with the
+///   [`ValidationError`](crate::traits::ValidationError) as the error's source.
+/// 
+/// ## Panics
+/// Panics when the query of `T` already has a `LIMIT`, `OFFSET` or `FETCH` clause.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// #[derive(Queryable, FromRow, SqlParams)]
+/// #[table("orders")]
+/// #[where_clause("customer_id = $")]
+/// #[sortable("created_at, total")]
+/// pub struct CustomerOrders { /* ... */ }
+///
+/// // SELECT ... WHERE customer_id = $1 ORDER BY created_at ASC NULLS LAST LIMIT 1
+/// let oldest = fetch_first(&mut client, &CustomerOrders::new(7), "created_at")?;
+/// ```
+pub fn fetch_first<T: SqlQuery + FromRow + SqlParams>(
+    client: &mut Client,
+    params: &T,
+    column: &str,
+) -> Result<Option<T>, Error> {
+    fetch_end(client, params, column, false)
+}
+
+/// # fetch_last
+/// 
+/// Retrieves the last record of the query ordered by `column`, i.e. [`fetch_first`] with
+/// `ORDER BY column DESC NULLS LAST`; the usual way to read the latest record of something.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `params`: Query parameters (must implement SqlQuery, FromRow, and SqlParams traits)
+/// - `column`: The order column, one of the `#[sortable]` columns
+/// 
+/// ## Return Value
+/// - `Result<Option<T>, Error>`: On success, returns the record, or `None` when the query matches nothing;
+///   on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// // SELECT ... WHERE customer_id = $1 ORDER BY created_at DESC NULLS LAST LIMIT 1
+/// let latest = fetch_last(&mut client, &CustomerOrders::new(7), "created_at")?;
+/// ```
+pub fn fetch_last<T: SqlQuery + FromRow + SqlParams>(
+    client: &mut Client,
+    params: &T,
+    column: &str,
+) -> Result<Option<T>, Error> {
+    fetch_end(client, params, column, true)
+}
+
+fn fetch_end<T: SqlQuery + FromRow + SqlParams>(
+    client: &mut Client,
+    params: &T,
+    column: &str,
+    last: bool,
+) -> Result<Option<T>, Error> {
+    check(client, check_sortable(column, T::sortable_columns()))?;
+    let sql = with_first_row(T::query(), column, last)
+        .unwrap_or_else(|e| panic!("{}: {}", if last { "fetch_last" } else { "fetch_first" }, e));
+
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let query_params = params.params();
+    let row = observe("fetch", T::table_name(), || client.query_opt(sql.as_str(), &query_params))?;
+    row.as_ref().map(T::from_row).transpose()
+}
+
+/// # fetch_many
+/// 
+/// Retrieves the records of a list of keys with a single `WHERE id = ANY($1)` query, the
+/// keys bound as one array. The records come back in the order of `keys`, and the keys
+/// without a record are listed in `missing`.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `keys`: The requested keys; a key given twice is read once
+/// 
+/// ## Return Value
+/// - `Result<Lookup<T, K>, Error>`: On success, returns the records found and the missing keys;
+///   on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// #[derive(Queryable, FromRow, SqlParams)]
+/// #[table("users")]
+/// #[where_clause("id = $")]
+/// #[primary_key("id")]
+/// pub struct UserById { /* ... */ }
+///
+/// // SELECT id, name, email FROM users WHERE id = ANY($1)
+/// let users = fetch_many::<UserById, i64>(&mut client, &[3, 1, 7])?;
+/// ```
+pub fn fetch_many<T, K>(client: &mut Client, keys: &[K]) -> Result<Lookup<T, K>, Error>
+where
+    T: Keyed<Key = K> + SqlQuery + FromRow,
+    K: ToSql + Sync + Eq + Hash + Clone,
+{
+    let sql = lookup::keys_query(T::key_select(), T::key_column(), 1, Dialect::Postgres);
+
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let distinct = lookup::distinct(keys);
+    let rows = observe("fetch_many", T::table_name(), || client.query(sql.as_str(), &[&distinct]))?;
+    let records = T::from_rows(&rows)?;
+    Ok(lookup::in_key_order(keys, records, T::key))
+}
+
+/// # fetch_with_row
+/// 
+/// Retrieves a single record together with the raw `Row` it was mapped from.
+/// Columns that are selected but not part of the struct (computed values, window functions)
+/// can be read from the returned row.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `params`: Query parameters (must implement SqlQuery, FromRow, and SqlParams traits)
+/// 
+/// ## Return Value
+/// - `Result<(T, Row), Error>`: On success, returns the record and its row; on failure, returns Error
+pub fn fetch_with_row<T: SqlQuery + FromRow + SqlParams>(
+    client: &mut Client,
+    params: &T,
+) -> Result<(T, Row), Error> {
+    let sql = T::query_single();
+    
+    trace::log_sql("PARSQL-POSTGRES", sql);
+
+    let query_params = params.params();
+    let row = observe("fetch", T::table_name(), || client.query_one(sql, &query_params))?;
+    let entity = T::from_row(&row)?;
+    Ok((entity, row))
+}
+
+/// # fetch_all_with_rows
+/// 
+/// Retrieves multiple records, each paired with the raw `Row` it was mapped from.
+/// Useful for pagination with a total count computed in the same query.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `params`: Query parameters (must implement SqlQuery, FromRow, and SqlParams traits)
+/// 
+/// ## Return Value
+/// - `Result<Vec<(T, Row)>, Error>`: On success, returns the records and their rows; on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,no_run
+/// use postgres::{types::ToSql, Client, Error, NoTls, Row};
+/// use parsql_postgres::fetch_all_with_rows;
+/// use parsql_postgres::macros::{FromRow, Queryable, SqlParams};
+/// use parsql_postgres::traits::{FromRow, SqlParams, SqlQuery};
+/// 
+/// #[derive(Queryable, FromRow, SqlParams)]
+/// #[table("users")]
+/// #[select("id, name, state, COUNT(*) OVER() AS total_count")]
+/// #[where_clause("state = $")]
+/// #[limit(20)]
+/// pub struct UserPage {
+///     pub id: i32,
+///     pub name: String,
+///     pub state: i16,
+/// }
+///
+/// fn main() -> Result<(), Error> {
+///     let mut client = Client::connect("host=localhost user=postgres dbname=test", NoTls)?;
+///
+///     let query = UserPage { id: 0, name: String::new(), state: 1 };
+///     let page = fetch_all_with_rows(&mut client, &query)?;
+///     let total: i64 = page.first().map_or(Ok(0), |(_, row)| row.try_get("total_count"))?;
+///     println!("{} of {} users", page.len(), total);
+///     Ok(())
+/// }
+/// ```
+pub fn fetch_all_with_rows<T: SqlQuery + FromRow + SqlParams>(
+    client: &mut Client,
+    params: &T,
+) -> Result<Vec<(T, Row)>, Error> {
+    let sql = T::query();
+    
+    trace::log_sql("PARSQL-POSTGRES", sql);
 
     let query_params = params.params();
-    let rows = client.query(&sql, &query_params)?;
+    let rows = observe("fetch_all", T::table_name(), || client.query(sql, &query_params))?;
     
     let mut results = Vec::with_capacity(rows.len());
-    for row in &rows {
-        results.push(T::from_row(row)?);
+    for row in rows {
+        results.push((T::from_row(&row)?, row));
     }
     
     Ok(results)
@@ -371,7 +963,7 @@ pub fn fetch_all<T: SqlQuery + FromRow + SqlParams>(
 /// 
 /// ## Parameters
 /// - `client`: Database connection client
-/// - `query`: Custom SQL query string
+/// - `query`: Custom SQL query (`&str`, `String`, or anything implementing `AsRef<str>`)
 /// - `params`: Array of query parameters
 /// 
 /// ## Return Value
@@ -404,17 +996,14 @@ pub fn fetch_all<T: SqlQuery + FromRow + SqlParams>(
 /// ```
 pub fn get_by_query<T: FromRow>(
     client: &mut Client,
-    query: &str,
+    query: impl AsRef<str>,
     params: &[&(dyn ToSql + Sync)],
 ) -> Result<Vec<T>, Error> {
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-POSTGRES] Execute SQL: {}", query);
-    }
+    let query = query.as_ref();
+    trace::log_sql("PARSQL-POSTGRES", query);
 
     let rows = client.query(query, params)?;
-    rows.iter()
-        .map(|row| T::from_row(row))
-        .collect::<Result<Vec<_>, _>>()
+    T::from_rows(&rows)
 }
 
 /// # select
@@ -505,14 +1094,12 @@ pub fn select<T: SqlQuery + SqlParams, F>(
 where
     F: Fn(&Row) -> Result<T, Error>,
 {
-    let sql = T::query();
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-POSTGRES] Execute SQL: {}", sql);
-    }
+    let sql = T::query_single();
+    trace::log_sql("PARSQL-POSTGRES", sql);
 
     let params = entity.params();
 
-    match client.query_one(&sql, &params) {
+    match observe("select", T::table_name(), || client.query_one(sql, &params)) {
         Ok(_row) => to_model(&_row),
         Err(e) => Err(e),
     }
@@ -609,13 +1196,9 @@ where
     F: Fn(&Row) -> Result<T, Error>,
 {
     let sql = T::query();
-    if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-        println!("[PARSQL-POSTGRES] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-POSTGRES", sql);
 
-    let params = entity.params();
-
-    let rows = client.query(&sql, &params)?;
+    let rows = observe("select_all", T::table_name(), || crate::params::query(client, sql, &entity))?;
 
     rows.iter()
         .map(|row| to_model(row))
@@ -655,3 +1238,213 @@ pub fn get_all<T: SqlQuery + FromRow + SqlParams>(
 ) -> Result<Vec<T>, Error> {
     fetch_all(client, params)
 }
+
+/// # insert_many
+/// 
+/// Inserts multiple records using multi-row `INSERT` statements.
+/// Records are split into chunks so that a single statement never binds more than
+/// 65535 parameters (PostgreSQL's limit).
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `entities`: Records to insert (must implement SqlQuery and SqlParams traits)
+/// - `options`: Chunk size and whether all chunks run inside one transaction
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of inserted rows; on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,no_run
+/// use postgres::{Client, NoTls, Error};
+/// use parsql::postgres::{insert_many, BatchOptions};
+/// 
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+///
+/// fn main() -> Result<(), Error> {
+///     let mut client = Client::connect("host=localhost user=postgres dbname=test", NoTls)?;
+///
+///     let users: Vec<InsertUser> = (0..100_000)
+///         .map(|i| InsertUser { name: format!("user{}", i), email: format!("user{}@example.com", i) })
+///         .collect();
+///
+///     let options = BatchOptions { transaction: true, ..Default::default() };
+///     let inserted = insert_many(&mut client, &users, options)?;
+///     println!("Inserted rows: {}", inserted);
+///     Ok(())
+/// }
+/// ```
+pub fn insert_many<T: SqlQuery + SqlParams>(
+    client: &mut Client,
+    entities: &[T],
+    options: BatchOptions,
+) -> Result<u64, Error> {
+    if options.transaction {
+        let mut tx = client.transaction()?;
+        let inserted = insert_chunks(&mut tx, entities, None, options)?;
+        tx.commit()?;
+        Ok(inserted)
+    } else {
+        insert_chunks(client, entities, None, options)
+    }
+}
+
+/// # upsert_many
+/// 
+/// Inserts multiple records like [`insert_many`], turning every chunk into one
+/// `INSERT ... ON CONFLICT (conflict) DO UPDATE` statement: a row whose `conflict`
+/// columns match an existing row updates its other columns from `EXCLUDED`.
+/// 
+/// A conflict key may appear only once per chunk; PostgreSQL rejects a statement that
+/// updates the same row twice.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `entities`: Records to upsert (must implement SqlQuery and SqlParams traits)
+/// - `conflict`: Columns of the unique constraint or index the rows collide on
+/// - `options`: Chunk size and whether all chunks run inside one transaction
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of inserted or updated rows; on failure, returns Error
+/// 
+/// ## Panics
+/// If a `conflict` entry is not a plain column name or the INSERT has no `VALUES` list.
+/// 
+/// ## Example Usage
+/// ```rust,no_run
+/// use postgres::{Client, NoTls, Error};
+/// use parsql::postgres::{upsert_many, BatchOptions};
+/// 
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+///
+/// fn main() -> Result<(), Error> {
+///     let mut client = Client::connect("host=localhost user=postgres dbname=test", NoTls)?;
+///
+///     let users = vec![
+///         InsertUser { name: "Ali".to_string(), email: "ali@example.com".to_string() },
+///         InsertUser { name: "Ayşe".to_string(), email: "ayse@example.com".to_string() },
+///     ];
+///
+///     let upserted = upsert_many(&mut client, &users, &["email"], BatchOptions::default())?;
+///     println!("Upserted rows: {}", upserted);
+///     Ok(())
+/// }
+/// ```
+pub fn upsert_many<T: SqlQuery + SqlParams>(
+    client: &mut Client,
+    entities: &[T],
+    conflict: &[&str],
+    options: BatchOptions,
+) -> Result<u64, Error> {
+    if with_upsert(T::query(), conflict).is_none() {
+        panic!("upsert_many: can't add ON CONFLICT ({}) to `{}`", conflict.join(", "), T::query());
+    }
+
+    if options.transaction {
+        let mut tx = client.transaction()?;
+        let upserted = insert_chunks(&mut tx, entities, Some(conflict), options)?;
+        tx.commit()?;
+        Ok(upserted)
+    } else {
+        insert_chunks(client, entities, Some(conflict), options)
+    }
+}
+
+pub(crate) fn insert_chunks<C: GenericClient, T: SqlQuery + SqlParams>(
+    client: &mut C,
+    entities: &[T],
+    conflict: Option<&[&str]>,
+    options: BatchOptions,
+) -> Result<u64, Error> {
+    let Some(first) = entities.first() else {
+        return Ok(0);
+    };
+
+    let sql = T::query();
+    let params_per_row = first.params().len();
+    let rows_per_chunk = options.rows_per_chunk(Dialect::Postgres, params_per_row);
+
+    let mut inserted = 0;
+    for chunk in entities.chunks(rows_per_chunk) {
+        // upsert_many ON CONFLICT eklenebildiğini baştan doğruladı
+        let chunk_sql = multi_row_insert(sql, params_per_row, chunk.len(), Dialect::Postgres)
+            .map(|chunk_sql| match conflict {
+                Some(conflict) => with_upsert(&chunk_sql, conflict).expect("checked by upsert_many"),
+                None => chunk_sql,
+            });
+        match chunk_sql {
+            Some(chunk_sql) => {
+                trace::log_sql("PARSQL-POSTGRES", &chunk_sql);
+
+                let params: Vec<&(dyn ToSql + Sync)> =
+                    chunk.iter().flat_map(|entity| entity.params()).collect();
+                inserted += client.execute(chunk_sql.as_str(), &params)?;
+            }
+            // VALUES listesi olmayan sorgular (INSERT ... SELECT) tek tek çalıştırılır
+            None => {
+                for entity in chunk {
+                    inserted += client.execute(sql, &entity.params())?;
+                }
+            }
+        }
+    }
+
+    Ok(inserted)
+}
+
+/// # update_many
+/// 
+/// Updates multiple records, reusing one prepared statement.
+/// 
+/// ## Parameters
+/// - `client`: Database connection client
+/// - `entities`: Records to update (must implement SqlQuery and UpdateParams traits)
+/// - `options`: Whether all updates run inside one transaction (`chunk_size` is not used,
+///   every record is a separate statement)
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the total number of updated rows; on failure, returns Error
+pub fn update_many<T: SqlQuery + UpdateParams>(
+    client: &mut Client,
+    entities: &[T],
+    options: BatchOptions,
+) -> Result<u64, Error> {
+    if options.transaction {
+        let mut tx = client.transaction()?;
+        let updated = update_each(&mut tx, entities)?;
+        tx.commit()?;
+        Ok(updated)
+    } else {
+        update_each(client, entities)
+    }
+}
+
+fn update_each<C: GenericClient, T: SqlQuery + UpdateParams>(
+    client: &mut C,
+    entities: &[T],
+) -> Result<u64, Error> {
+    if entities.is_empty() {
+        return Ok(0);
+    }
+
+    let sql = T::query();
+    trace::log_sql("PARSQL-POSTGRES", sql);
+
+    let stmt = client.prepare(sql)?;
+    let mut updated = 0;
+    for entity in entities {
+        updated += client.execute(&stmt, &entity.params())?;
+    }
+
+    Ok(updated)
+}
+
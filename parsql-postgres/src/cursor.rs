@@ -0,0 +1,90 @@
+//! Snapshot-consistent paging through a held cursor.
+//!
+//! [`open_cursor`] declares a cursor `WITH HOLD` for the query of a `Queryable` and
+//! returns its [`PageToken`]; [`fetch_next`] reads the following rows of that fixed
+//! result, so an export sees every row exactly once while the table changes:
+//!
+//! ```rust,no_run
+//! use parsql_postgres::cursor::{fetch_next, open_cursor};
+//! use parsql_postgres::macros::{FromRow, Queryable, SqlParams};
+//! use parsql_postgres::traits::{FromRow, SqlParams, SqlQuery};
+//! use postgres::{types::ToSql, Client, Error, NoTls, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow)]
+//! #[table("users")]
+//! #[where_clause("state = $")]
+//! #[order_by("id")]
+//! pub struct ActiveUser {
+//!     pub id: i64,
+//!     pub name: String,
+//!     pub state: i16,
+//! }
+//!
+//! fn main() -> Result<(), Error> {
+//!     let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+//!     let token = open_cursor(&mut client, &ActiveUser { id: 0, name: String::new(), state: 1 })?;
+//!     loop {
+//!         let page: Vec<ActiveUser> = fetch_next(&mut client, &token, 500)?;
+//!         // ... write the page
+//!         if page.len() < 500 {
+//!             break;
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The cursor lives in the session of `client`; later pages must be read on the same
+//! connection. `fetch_next` closes the cursor after a short page, [`close_cursor`]
+//! closes it earlier.
+
+pub use parsql_core::cursor::{InvalidPageToken, PageToken};
+
+use parsql_core::{cursor, metrics::observe, trace};
+use postgres::{Error, GenericClient};
+
+use crate::traits::{FromRow, SqlParams, SqlQuery};
+
+/// Declares a held cursor for the query of `params` and returns its token.
+///
+/// The query runs to completion when the cursor is declared; its result is kept on the
+/// server until the cursor is closed.
+pub fn open_cursor<C, T>(client: &mut C, params: &T) -> Result<PageToken, Error>
+where
+    C: GenericClient,
+    T: SqlQuery + SqlParams,
+{
+    let token = PageToken::generate();
+    let sql = cursor::declare_statement(&token, T::query());
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let params = params.params();
+    observe("open_cursor", T::table_name(), || client.execute(sql.as_str(), &params))?;
+    Ok(token)
+}
+
+/// Reads up to `count` rows after the ones already read from the cursor.
+///
+/// Fewer than `count` rows mean the result is exhausted; the cursor is then closed and
+/// the token can't be used again.
+pub fn fetch_next<C, T>(client: &mut C, token: &PageToken, count: u32) -> Result<Vec<T>, Error>
+where
+    C: GenericClient,
+    T: FromRow,
+{
+    let sql = cursor::fetch_statement(token, count);
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let rows = observe("fetch_next", "unknown", || client.query(sql.as_str(), &[]))?;
+    if rows.len() < usize::try_from(count).unwrap_or(usize::MAX) {
+        close_cursor(client, token)?;
+    }
+    T::from_rows(&rows)
+}
+
+/// Closes the cursor before its rows are all read.
+pub fn close_cursor<C: GenericClient>(client: &mut C, token: &PageToken) -> Result<(), Error> {
+    let sql = cursor::close_statement(token);
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+    client.batch_execute(&sql)
+}
@@ -0,0 +1,90 @@
+//! Rows of ad-hoc queries, without a result struct.
+//!
+//! [`query_dynamic`] runs any SQL with its parameters and returns every row as a map of
+//! column name to [`Value`], which is enough for REPL-like tools and debugging
+//! endpoints:
+//!
+//! ```rust,no_run
+//! use parsql_postgres::{query_dynamic, Value};
+//! use postgres::{Client, NoTls};
+//!
+//! fn main() -> Result<(), postgres::Error> {
+//!     let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+//!     let rows = query_dynamic(&mut client, "SELECT id, name, created_at FROM users WHERE state = $1", &[&1i16])?;
+//!     for row in &rows {
+//!         // created_at is not decoded by the driver and arrives as text
+//!         println!("{} {} {}", row["id"], row["name"], row["created_at"]);
+//!     }
+//!     assert!(rows.iter().all(|row| matches!(row["id"], Value::Int(_))));
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error as StdError;
+
+use parsql_core::{metrics::observe, trace, value::{self, Value}};
+use postgres::types::{FromSql, Kind, ToSql, Type};
+use postgres::{Error, GenericClient, Row};
+
+/// A column read as a [`Value`].
+struct Dynamic(Value);
+
+impl<'a> FromSql<'a> for Dynamic {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn StdError + Sync + Send>> {
+        // Enum değerleri etiketleriyle gelir
+        if matches!(ty.kind(), Kind::Enum(_)) {
+            return Ok(Dynamic(Value::Text(std::str::from_utf8(raw)?.to_string())));
+        }
+        Ok(Dynamic(value::from_postgres(ty.oid(), raw)?))
+    }
+
+    fn from_sql_null(_: &Type) -> Result<Self, Box<dyn StdError + Sync + Send>> {
+        Ok(Dynamic(Value::Null))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        decoded(ty)
+    }
+}
+
+fn decoded(ty: &Type) -> bool {
+    value::decodes_postgres(ty.oid()) || matches!(ty.kind(), Kind::Enum(_))
+}
+
+fn to_map(row: &Row) -> Result<HashMap<String, Value>, Error> {
+    row.columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| Ok((column.name().to_string(), row.try_get::<_, Dynamic>(i)?.0)))
+        .collect()
+}
+
+/// Runs `sql` and returns every row as a map of column name to value.
+///
+/// The statement is prepared first to learn its column types. When a column has a type
+/// [`Value`] doesn't cover, the query runs wrapped in a SELECT that casts it to text, so
+/// its result columns must have distinct names. Statements without result columns
+/// return no rows.
+pub fn query_dynamic<C: GenericClient>(
+    client: &mut C,
+    sql: &str,
+    params: &[&(dyn ToSql + Sync)],
+) -> Result<Vec<HashMap<String, Value>>, Error> {
+    let statement = client.prepare(sql)?;
+    let columns = statement
+        .columns()
+        .iter()
+        .map(|column| (column.name(), decoded(column.type_())))
+        .collect::<Vec<_>>();
+
+    let rows = if columns.iter().all(|(_, decoded)| *decoded) {
+        trace::log_sql("PARSQL-POSTGRES", sql);
+        observe("query_dynamic", "unknown", || client.query(&statement, params))?
+    } else {
+        let sql = value::with_text_columns(sql, &columns);
+        trace::log_sql("PARSQL-POSTGRES", &sql);
+        observe("query_dynamic", "unknown", || client.query(sql.as_str(), params))?
+    };
+    rows.iter().map(to_map).collect()
+}
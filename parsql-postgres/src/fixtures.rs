@@ -0,0 +1,70 @@
+//! Seed data.
+//!
+//! Tests and examples fill their tables from `Insertable` values instead of long
+//! hand-written `INSERT` scripts. [`load`] writes the rows of one table; a [`Fixtures`]
+//! set loads several tables in one transaction, in the order they were added, so
+//! parent rows exist before the rows that reference them:
+//!
+//! ```rust,ignore
+//! use parsql::postgres::fixtures::Fixtures;
+//!
+//! Fixtures::new()
+//!     .table(&users)
+//!     .table(&posts)
+//!     .table(&comments)
+//!     .load(&mut client)?;
+//! ```
+
+use parsql_core::BatchOptions;
+use postgres::{Client, Error, Transaction};
+
+use crate::crud_ops::insert_chunks;
+use crate::traits::{SqlParams, SqlQuery};
+
+/// Inserts `rows` in one transaction, returning the number of inserted rows.
+pub fn load<T: SqlQuery + SqlParams>(client: &mut Client, rows: &[T]) -> Result<u64, Error> {
+    Fixtures::new().table(rows).load(client)
+}
+
+/// Rows of several tables loaded together.
+#[derive(Default)]
+pub struct Fixtures<'a> {
+    sets: Vec<Box<dyn FixtureSet + 'a>>,
+}
+
+impl<'a> Fixtures<'a> {
+    /// An empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the rows of a table, inserted after the rows added before them.
+    pub fn table<T: SqlQuery + SqlParams>(mut self, rows: &'a [T]) -> Self {
+        self.sets.push(Box::new(Rows(rows)));
+        self
+    }
+
+    /// Inserts every table in one transaction and returns the total number of inserted
+    /// rows. When a row fails, nothing is written.
+    pub fn load(self, client: &mut Client) -> Result<u64, Error> {
+        let mut tx = client.transaction()?;
+        let mut inserted = 0;
+        for set in &self.sets {
+            inserted += set.insert(&mut tx)?;
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+}
+
+trait FixtureSet {
+    fn insert(&self, tx: &mut Transaction<'_>) -> Result<u64, Error>;
+}
+
+struct Rows<'a, T>(&'a [T]);
+
+impl<T: SqlQuery + SqlParams> FixtureSet for Rows<'_, T> {
+    fn insert(&self, tx: &mut Transaction<'_>) -> Result<u64, Error> {
+        insert_chunks(tx, self.0, None, BatchOptions::default())
+    }
+}
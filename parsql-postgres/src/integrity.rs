@@ -0,0 +1,68 @@
+//! Deferred constraints and trigger control.
+//!
+//! [`IntegrityControl`] adds the statements of bulk loads and data fixes to a
+//! transaction. Rows that reference each other can be written in any order once the
+//! foreign keys are deferred to the commit, and the audit triggers of a table can be
+//! switched off while it is rewritten:
+//!
+//! ```rust,no_run
+//! use parsql_postgres::integrity::IntegrityControl;
+//! # fn example(client: &mut postgres::Client) -> Result<(), postgres::Error> {
+//! let mut tx = client.transaction()?;
+//! tx.set_constraints_deferred(&["fk_posts_user"])?;
+//! tx.disable_triggers("posts")?;
+//! // ... posts first, then their users
+//! tx.set_constraints_immediate(&[])?;
+//! tx.enable_triggers("posts")?;
+//! tx.commit()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only constraints declared `DEFERRABLE` can be deferred. Disabled triggers stay
+//! disabled after the commit, so enable them again before it; a rollback undoes both.
+//! PostgreSQL does not alter a table whose deferred checks are still pending, which is
+//! why the example makes the constraints immediate before enabling the triggers.
+
+use parsql_core::{integrity, trace};
+use postgres::{Error, Transaction};
+
+/// Constraint and trigger statements run on a transaction.
+pub trait IntegrityControl {
+    /// Checks the given deferrable constraints, or all of them for an empty list, at the
+    /// commit instead of after each statement.
+    fn set_constraints_deferred(&mut self, constraints: &[&str]) -> Result<(), Error>;
+
+    /// Checks the given constraints after each statement again, and the rows written
+    /// while they were deferred right away.
+    fn set_constraints_immediate(&mut self, constraints: &[&str]) -> Result<(), Error>;
+
+    /// Disables the user-defined triggers of `table`.
+    fn disable_triggers(&mut self, table: &str) -> Result<(), Error>;
+
+    /// Enables the user-defined triggers of `table` again.
+    fn enable_triggers(&mut self, table: &str) -> Result<(), Error>;
+}
+
+impl IntegrityControl for Transaction<'_> {
+    fn set_constraints_deferred(&mut self, constraints: &[&str]) -> Result<(), Error> {
+        run(self, &integrity::set_constraints(constraints, true))
+    }
+
+    fn set_constraints_immediate(&mut self, constraints: &[&str]) -> Result<(), Error> {
+        run(self, &integrity::set_constraints(constraints, false))
+    }
+
+    fn disable_triggers(&mut self, table: &str) -> Result<(), Error> {
+        run(self, &integrity::alter_triggers(table, false))
+    }
+
+    fn enable_triggers(&mut self, table: &str) -> Result<(), Error> {
+        run(self, &integrity::alter_triggers(table, true))
+    }
+}
+
+fn run(tx: &mut Transaction<'_>, sql: &str) -> Result<(), Error> {
+    trace::log_sql("PARSQL-POSTGRES", sql);
+    tx.batch_execute(sql)
+}
@@ -0,0 +1,56 @@
+//! Query results as JSON objects (`json` feature).
+//!
+//! [`fetch_json`] and [`fetch_all_json`] run the SELECT of a `Queryable` type and
+//! return every row as a `serde_json::Value` object keyed by column name, without a
+//! `FromRow` struct. PostgreSQL builds the objects itself with `row_to_json`, so
+//! dates, numerics and nested `json_agg` columns keep their JSON form:
+//!
+//! ```rust,no_run
+//! use parsql_postgres::{fetch_all_json, macros::{Queryable, SqlParams}, traits::{SqlParams, SqlQuery}};
+//! use postgres::{types::ToSql, Client, NoTls};
+//!
+//! #[derive(Queryable, SqlParams)]
+//! #[table("users")]
+//! #[select("id, name, email")]
+//! #[where_clause("state = $")]
+//! pub struct ActiveUsers {
+//!     pub state: i16,
+//! }
+//!
+//! fn main() -> Result<(), postgres::Error> {
+//!     let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+//!     let rows = fetch_all_json(&mut client, &ActiveUsers { state: 1 })?;
+//!     // [{"id": 1, "name": "ali", "email": "ali@example.com"}, ...]
+//!     println!("{}", serde_json::Value::Array(rows));
+//!     Ok(())
+//! }
+//! ```
+
+use parsql_core::{json::row_to_json, metrics::observe, trace};
+use postgres::{Error, GenericClient};
+use serde_json::Value;
+
+use crate::traits::{SqlParams, SqlQuery};
+
+/// Retrieves the row selected by `params` as a JSON object.
+///
+/// Like `fetch`, the query runs with `LIMIT 2` unless it sets a limit itself, and a
+/// query without rows, or with more than one, is an error.
+pub fn fetch_json<C: GenericClient, T: SqlQuery + SqlParams>(client: &mut C, params: &T) -> Result<Value, Error> {
+    let sql = row_to_json(T::query_single());
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let query_params = params.params();
+    let row = observe("fetch", T::table_name(), || client.query_one(sql.as_str(), &query_params))?;
+    row.try_get(0)
+}
+
+/// Retrieves every row selected by `params` as JSON objects, in query order.
+pub fn fetch_all_json<C: GenericClient, T: SqlQuery + SqlParams>(client: &mut C, params: &T) -> Result<Vec<Value>, Error> {
+    let sql = row_to_json(T::query());
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    let query_params = params.params();
+    let rows = observe("fetch_all", T::table_name(), || client.query(sql.as_str(), &query_params))?;
+    rows.iter().map(|row| row.try_get(0)).collect()
+}
@@ -165,16 +165,94 @@ pub mod crud_ops;
 pub mod transaction_ops;
 pub mod traits;
 pub mod macros;
+pub mod cache;
+pub mod constraint;
+pub mod cursor;
+pub mod dynamic;
+pub mod repository;
+pub mod encryption;
+pub mod export;
+pub mod fixtures;
+pub mod integrity;
+#[cfg(feature = "postgis")]
+pub mod geo;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod locks;
+pub mod partitions;
+pub mod registry;
+pub mod schema;
+pub mod session;
+pub mod views;
+mod params;
+mod validation;
+
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+pub mod tls;
 
 pub use postgres::types::ToSql;
 pub use postgres::Transaction;
 pub use postgres::{Client, Error, Row};
+
+/// JSON / JSONB column wrapper, (de)serialized with serde (`json` feature).
+///
+/// Child rows aggregated with `json_agg` can be read into nested structures
+/// in the same query:
+///
+/// ```rust,no_run
+/// use parsql::postgres::{macros::{FromRow, Queryable, SqlParams}, traits::{FromRow, SqlParams, SqlQuery}, Json};
+/// use postgres::{types::ToSql, Error, Row};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Post {
+///     pub id: i32,
+///     pub title: String,
+/// }
+///
+/// #[derive(Queryable, SqlParams, FromRow)]
+/// #[table("users")]
+/// #[select("users.id, users.name, COALESCE(json_agg(posts.*) FILTER (WHERE posts.id IS NOT NULL), '[]') AS posts")]
+/// #[join("LEFT JOIN posts ON posts.user_id = users.id")]
+/// #[where_clause("users.id = $")]
+/// #[group_by("users.id")]
+/// pub struct UserWithPosts {
+///     pub id: i32,
+///     pub name: String,
+///     pub posts: Json<Vec<Post>>,
+/// }
+/// ```
+#[cfg(feature = "json")]
+pub use postgres::types::Json;
+/// Exact decimal for `NUMERIC` columns (`decimal` feature). `Decimal` fields bind and
+/// read like any other field type in the derived structs.
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
+pub use encryption::EncryptedColumn;
+/// PostGIS `geometry` / `geography` column holding a `geo-types` value (`postgis` feature).
+#[cfg(feature = "postgis")]
+pub use geo::{geo_types, PostGis};
+pub use constraint::{ConstraintError, ConstraintKind, ConstraintViolation};
+pub use dynamic::query_dynamic;
+pub use export::export_csv;
+#[cfg(feature = "json")]
+pub use json::{fetch_all_json, fetch_json};
+pub use partitions::ensure_monthly_partition;
+pub use views::refresh_materialized_view;
 pub use macros::*;
 
 // Re-export crud operations
 pub use crud_ops::{
-    delete, fetch, fetch_all, get_by_query, insert, select, select_all, update,
+    call, delete, delete_where, fetch, fetch_all, fetch_all_combined, fetch_all_limited, fetch_all_with_rows, fetch_first,
+    fetch_last, fetch_many, fetch_page, fetch_with_row, get_by_query, insert, insert_many, insert_returning,
+    insert_returning_id, insert_returning_opt, select, select_all, update, update_many, update_patch, update_where,
+    upsert_many,
 };
+pub use parsql_core::{lookup::Lookup, BatchOptions, OnError, Progress};
+pub use parsql_core::trace::set_trace;
+// `#[audited]` yazımlarının denetim tablosu
+pub use parsql_core::audit_log;
+pub use parsql_core::value::Value;
 
 // Eski isimlerle fonksiyonları deprecated olarak dışa aktar
 #[allow(deprecated)]
@@ -183,7 +261,9 @@ pub use crud_ops::{get, get_all};
 // Re-export transaction operations in a transactional module
 pub mod transactional {
     pub use crate::transaction_ops::{
-        begin, tx_delete, tx_fetch, tx_fetch_all, tx_insert, tx_select, tx_select_all, tx_update,
+        begin, create_temp_from, is_aborted, persist_graph, rollback, tx_delete, tx_delete_ref, tx_fetch, tx_fetch_all, tx_fetch_all_ref,
+        tx_fetch_ref, tx_insert, tx_insert_all, tx_insert_ref, tx_select, tx_select_all, tx_update,
+        tx_update_ref,
     };
 
     // Eski isimlerle fonksiyonları deprecated olarak dışa aktar
@@ -0,0 +1,63 @@
+//! Advisory locks.
+//!
+//! [`advisory_lock`] and [`try_advisory_lock`] take a session-level PostgreSQL
+//! advisory lock on a 64-bit key, [`advisory_unlock`] releases it. Instances of an
+//! application that use the same key run the guarded code one at a time, e.g. a cron
+//! job scheduled on every instance:
+//!
+//! ```rust,no_run
+//! use parsql::postgres::locks::{lock_key, try_advisory_lock, advisory_unlock};
+//! # fn example(client: &mut postgres::Client) -> Result<(), postgres::Error> {
+//! let key = lock_key("nightly-report");
+//! if try_advisory_lock(client, key)? {
+//!     // rapor yalnızca bu örnekte hazırlanır
+//!     advisory_unlock(client, key)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The lock belongs to the connection, so it has to be released on the same one.
+//! [`with_advisory_lock`] does that after the closure returns, also when it fails.
+
+use parsql_core::{locks, trace};
+use postgres::{Client, Error, GenericClient};
+
+pub use parsql_core::locks::lock_key;
+
+/// Waits until the lock on `key` is free and takes it.
+pub fn advisory_lock<C: GenericClient>(client: &mut C, key: i64) -> Result<(), Error> {
+    trace::log_sql("PARSQL-POSTGRES", locks::LOCK);
+    client.execute(locks::LOCK, &[&key])?;
+    Ok(())
+}
+
+/// Takes the lock on `key` if no other session holds it; returns whether it was taken.
+pub fn try_advisory_lock<C: GenericClient>(client: &mut C, key: i64) -> Result<bool, Error> {
+    trace::log_sql("PARSQL-POSTGRES", locks::TRY_LOCK);
+    client.query_one(locks::TRY_LOCK, &[&key])?.try_get(0)
+}
+
+/// Releases the lock on `key`; returns `false` when this session did not hold it.
+pub fn advisory_unlock<C: GenericClient>(client: &mut C, key: i64) -> Result<bool, Error> {
+    trace::log_sql("PARSQL-POSTGRES", locks::UNLOCK);
+    client.query_one(locks::UNLOCK, &[&key])?.try_get(0)
+}
+
+/// Runs `f` while holding the lock on `key`, waiting for it first.
+///
+/// The lock is released when `f` returns, whether it succeeded or not. An error of
+/// `f` takes precedence over an error of the unlock.
+pub fn with_advisory_lock<T, E, F>(client: &mut Client, key: i64, f: F) -> Result<T, E>
+where
+    F: FnOnce(&mut Client) -> Result<T, E>,
+    E: From<Error>,
+{
+    advisory_lock(client, key)?;
+    let result = f(client);
+    let unlocked = advisory_unlock(client, key);
+
+    let value = result?;
+    unlocked?;
+    Ok(value)
+}
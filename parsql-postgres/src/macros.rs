@@ -1,4 +1,4 @@
 pub use parsql_macros::{
-    Deletable, FromRowPostgres as FromRow, Insertable, Queryable, SqlParams, UpdateParams,
-    Updateable,
+    Callable, Deletable, Entity, FromRowPostgres as FromRow, Insertable, Paginated, Patch, Queryable, Repository,
+    SqlParams, UpdateParams, Updateable,
 };
@@ -0,0 +1,38 @@
+//! Parametrelerin CRUD fonksiyonlarına bağlanması.
+//!
+//! `params-iter` özelliği açıkken parametreler `SqlParams::params_iter` üzerinden
+//! doğrudan `query_raw`'a verilir. Derive makrosu bu yineleyiciyi sabit boyutlu bir
+//! diziyle sağladığı için çağrı başına parametre `Vec`'i ayrılmaz.
+
+#[cfg(feature = "params-iter")]
+use postgres::fallible_iterator::FallibleIterator;
+use postgres::{Error, GenericClient, Row};
+
+use crate::traits::SqlParams;
+
+/// `sql`'i `entity`'nin parametreleriyle çalıştırır ve etkilenen satır sayısını döner.
+#[cfg(feature = "params-iter")]
+pub(crate) fn execute<C: GenericClient, T: SqlParams>(client: &mut C, sql: &str, entity: &T) -> Result<u64, Error> {
+    let mut rows = client.query_raw(sql, entity.params_iter())?;
+    // Etkilenen satır sayısı, satırlar tüketildikten sonra bilinir
+    while rows.next()?.is_some() {}
+    Ok(rows.rows_affected().unwrap_or(0))
+}
+
+/// `sql`'i `entity`'nin parametreleriyle çalıştırır ve etkilenen satır sayısını döner.
+#[cfg(not(feature = "params-iter"))]
+pub(crate) fn execute<C: GenericClient, T: SqlParams>(client: &mut C, sql: &str, entity: &T) -> Result<u64, Error> {
+    client.execute(sql, &entity.params())
+}
+
+/// `sql`'in `entity`'nin parametreleriyle döndürdüğü satırlar.
+#[cfg(feature = "params-iter")]
+pub(crate) fn query<C: GenericClient, T: SqlParams>(client: &mut C, sql: &str, entity: &T) -> Result<Vec<Row>, Error> {
+    client.query_raw(sql, entity.params_iter())?.collect()
+}
+
+/// `sql`'in `entity`'nin parametreleriyle döndürdüğü satırlar.
+#[cfg(not(feature = "params-iter"))]
+pub(crate) fn query<C: GenericClient, T: SqlParams>(client: &mut C, sql: &str, entity: &T) -> Result<Vec<Row>, Error> {
+    client.query(sql, &entity.params())
+}
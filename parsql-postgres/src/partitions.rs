@@ -0,0 +1,51 @@
+//! Monthly partitions of range-partitioned tables.
+//!
+//! PostgreSQL rejects a row for which no partition exists, so time-series tables need
+//! their partitions created ahead of the data. A model annotated with
+//! `#[partitioned(by = "range(created_at)")]` implements [`Partitioned`], and
+//! [`ensure_monthly_partition`] creates the partition of a month unless it exists:
+//!
+//! ```rust,no_run
+//! use std::time::SystemTime;
+//!
+//! use parsql_postgres::macros::Insertable;
+//! use parsql_postgres::partitions::{ensure_monthly_partition, Month};
+//! use parsql_postgres::traits::{Partitioned, SqlQuery};
+//! use postgres::{Client, Error, NoTls};
+//!
+//! #[derive(Insertable)]
+//! #[table("events")]
+//! #[partitioned(by = "range(created_at)")]
+//! pub struct InsertEvent {
+//!     pub kind: String,
+//!     pub created_at: SystemTime,
+//! }
+//!
+//! fn main() -> Result<(), Error> {
+//!     let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+//!     // Bu ayın ve gelecek ayın bölümleri
+//!     let month = Month::containing(SystemTime::now());
+//!     ensure_monthly_partition::<InsertEvent, _>(&mut client, month)?;
+//!     ensure_monthly_partition::<InsertEvent, _>(&mut client, month.next())?;
+//!     Ok(())
+//! }
+//! ```
+
+pub use parsql_core::partition::Month;
+
+use parsql_core::{partition, trace};
+use postgres::{Error, GenericClient};
+
+use crate::traits::Partitioned;
+
+/// Creates the partition of `T`'s table holding `month`, named like `events_2024_03`,
+/// unless a table of that name exists.
+///
+/// A partition of another name already covering part of the month makes PostgreSQL
+/// reject the statement.
+pub fn ensure_monthly_partition<T: Partitioned, C: GenericClient>(client: &mut C, month: Month) -> Result<(), Error> {
+    let sql = partition::monthly_partition_statement(T::partitioned_table(), month);
+    trace::log_sql("PARSQL-POSTGRES", &sql);
+
+    client.batch_execute(&sql)
+}
@@ -0,0 +1,79 @@
+//! Tip güvenli CRUD repository'leri.
+//!
+//! [`Repository`], `#[derive(Repository)]` ile tanımlanan sorgu tiplerini herhangi bir
+//! `CrudOps` uygulayıcısı (istemci, transaction ya da `cache::Cached`) üzerinde çalıştırır.
+//! Böylece katmanlı uygulamalarda her varlık için aynı create/find/update/delete
+//! sarmalayıcılarını elle yazmak gerekmez:
+//!
+//! ```rust,ignore
+//! #[derive(Repository)]
+//! #[repository(key = i64, insert = UserInsert, find = UserById, find_all = UsersByState, update = UserUpdate, delete = UserDelete)]
+//! pub struct Users;
+//!
+//! let mut users = Repository::<Users, _>::new(client);
+//! let id: i64 = users.create(UserInsert::new("Ali", "ali@example.com", 1))?;
+//! let user = users.find(id)?;
+//! users.delete(id)?;
+//! ```
+
+use std::marker::PhantomData;
+
+use postgres::{types::FromSql, Error};
+
+use crate::traits::{CrudOps, RepositoryTypes};
+
+/// `R` ile tanımlanan varlık üzerinde, `E` üzerinde çalışan CRUD işlemleri.
+pub struct Repository<R, E> {
+    executor: E,
+    _types: PhantomData<fn() -> R>,
+}
+
+impl<R, E> Repository<R, E> {
+    /// Sorgularını `executor` üzerinde çalıştıran bir repository oluşturur.
+    pub fn new(executor: E) -> Self {
+        Self {
+            executor,
+            _types: PhantomData,
+        }
+    }
+
+    /// Sorguların çalıştırıldığı nesne.
+    pub fn executor(&mut self) -> &mut E {
+        &mut self.executor
+    }
+
+    /// Repository'yi çözerek sorguların çalıştırıldığı nesneyi döndürür.
+    pub fn into_inner(self) -> E {
+        self.executor
+    }
+}
+
+impl<R: RepositoryTypes, E: CrudOps> Repository<R, E> {
+    /// Kaydı ekler ve `#[returning]` sütununun değerini döndürür.
+    pub fn create<P>(&mut self, entity: R::Insert) -> Result<P, Error>
+    where
+        P: for<'a> FromSql<'a> + Send + Sync,
+    {
+        self.executor.insert(entity)
+    }
+
+    /// Verilen anahtara sahip kaydı getirir.
+    pub fn find(&mut self, key: R::Key) -> Result<R::Find, Error> {
+        self.executor.fetch(&R::Find::from(key))
+    }
+
+    /// `query` ile eşleşen tüm kayıtları getirir.
+    pub fn find_all(&mut self, query: R::FindAll) -> Result<Vec<R::FindAll>, Error> {
+        self.executor.fetch_all(&query)
+    }
+
+    /// Kaydı günceller ve etkilenen satır sayısını döndürür.
+    pub fn update(&mut self, entity: R::Update) -> Result<u64, Error> {
+        self.executor.update(entity)
+    }
+
+    /// Verilen anahtara sahip kaydı siler ve etkilenen satır sayısını döndürür.
+    pub fn delete(&mut self, key: R::Key) -> Result<u64, Error> {
+        self.executor.delete(R::Delete::from(key))
+    }
+}
@@ -0,0 +1,118 @@
+//! TLS connection helpers.
+//!
+//! This module builds the TLS connector of the enabled TLS backend
+//! (`native-tls` or `rustls` feature) and opens a `postgres::Client` with it,
+//! so secure connections don't require wiring the driver TLS types by hand.
+//!
+//! ```rust,no_run
+//! use parsql::postgres::tls::{connect_tls, TlsOptions};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let options = TlsOptions::default();
+//!     let mut client = connect_tls("host=db.example.com user=postgres sslmode=require", &options)?;
+//!     client.execute("SELECT 1", &[])?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::fmt;
+
+use postgres::{Client, Error};
+
+pub use parsql_core::tls::{ConnectorError, TlsOptions};
+#[cfg(feature = "native-tls")]
+pub use parsql_core::tls::native_tls_connector;
+#[cfg(feature = "rustls")]
+pub use parsql_core::tls::rustls_connector;
+
+/// Errors that can occur while opening a TLS connection.
+#[derive(Debug)]
+pub enum TlsError {
+    /// The TLS connector could not be built (invalid certificate, unsupported option, ...)
+    Connector(ConnectorError),
+    /// The database connection failed
+    Postgres(Error),
+}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsError::Connector(e) => write!(f, "TLS connector error: {}", e),
+            TlsError::Postgres(e) => write!(f, "PostgreSQL error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TlsError::Connector(e) => Some(e),
+            TlsError::Postgres(e) => Some(e),
+        }
+    }
+}
+
+impl From<ConnectorError> for TlsError {
+    fn from(e: ConnectorError) -> Self {
+        TlsError::Connector(e)
+    }
+}
+
+impl From<Error> for TlsError {
+    fn from(e: Error) -> Self {
+        TlsError::Postgres(e)
+    }
+}
+
+/// Opens a client over `native-tls`.
+#[cfg(feature = "native-tls")]
+pub fn connect_native_tls(params: &str, options: &TlsOptions) -> Result<Client, TlsError> {
+    let connector = native_tls_connector(options)?;
+    Ok(Client::connect(params, connector)?)
+}
+
+/// Opens a client over `rustls`.
+#[cfg(feature = "rustls")]
+pub fn connect_rustls(params: &str, options: &TlsOptions) -> Result<Client, TlsError> {
+    let connector = rustls_connector(options)?;
+    Ok(Client::connect(params, connector)?)
+}
+
+/// Opens a client over TLS using the enabled backend.
+/// When both features are enabled `native-tls` is used.
+///
+/// # Arguments
+/// * `params` - Connection string, e.g. `"host=localhost user=postgres sslmode=require"`
+/// * `options` - TLS options
+///
+/// # Returns
+/// * `Result<Client, TlsError>` - Connected client or an error
+pub fn connect_tls(params: &str, options: &TlsOptions) -> Result<Client, TlsError> {
+    #[cfg(feature = "native-tls")]
+    {
+        connect_native_tls(params, options)
+    }
+    #[cfg(not(feature = "native-tls"))]
+    {
+        connect_rustls(params, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_connector_and_connection_errors() {
+        let options = TlsOptions {
+            root_cert_pem: Some(b"not a certificate".to_vec()),
+            ..Default::default()
+        };
+        let error = connect_tls("host=localhost user=postgres", &options).err().unwrap();
+        assert!(matches!(error, TlsError::Connector(_)));
+
+        // Geçersiz bağlantı dizesi sunucuya gidilmeden reddedilir
+        let error = connect_tls("port=not-a-port", &TlsOptions::default()).err().unwrap();
+        assert!(matches!(error, TlsError::Postgres(_)));
+    }
+}
@@ -1,18 +1,40 @@
+use bytes::BytesMut;
 use postgres;
-use postgres::{types::{FromSql, ToSql}, Error, Row};
+use postgres::{types::{to_sql_checked, FromSql, IsNull, ToSql, Type}, Error, Row};
+use std::error::Error as StdError;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
 
-/// SQL sorguları oluşturmak için trait.
-/// Bu trait, `Queryable`, `Insertable`, `Updateable` ve `Deletable` derive makroları tarafından uygulanır.
-pub trait SqlQuery {
-    /// SQL sorgu string'ini döndürür.
-    fn query() -> String;
-}
+pub use parsql_core::query::SqlQuery;
+pub use parsql_core::validation::{Validate, ValidationError};
 
 /// SQL parametreleri sağlamak için trait.
 /// Bu trait, `SqlParams` derive makrosu tarafından uygulanır.
 pub trait SqlParams {
     /// SQL parametrelerinin referanslarını içeren bir vektör döndürür.
     fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// `params()` ile aynı referansları yineleyici olarak döndürür. Derive makrosu bunları
+    /// Vec ayırmadan sabit boyutlu bir diziden verir; `params-iter` özelliğiyle derlenen
+    /// CRUD fonksiyonları parametreleri bununla bağlar.
+    fn params_iter(&self) -> impl ExactSizeIterator<Item = &(dyn ToSql + Sync)> + Send + Sync
+    where
+        Self: Sized,
+    {
+        self.params().into_iter()
+    }
+
+    /// Sorguyu, parametre yerlerine değerleri yazılmış olarak döndürür,
+    /// örn. `SELECT ... WHERE name = 'John' AND state = 1`.
+    ///
+    /// Yalnızca hatalı sorguları loglamak içindir; sonuç asla çalıştırılmamalıdır.
+    fn debug_sql(&self) -> String
+    where
+        Self: SqlQuery,
+    {
+        parsql_core::debug::debug_sql(Self::query(), self.params())
+    }
 }
 
 /// UPDATE işlemleri için parametre sağlamak üzere trait.
@@ -20,6 +42,96 @@ pub trait SqlParams {
 pub trait UpdateParams {
     /// UPDATE işlemleri için SQL parametrelerinin referanslarını içeren bir vektör döndürür.
     fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// Sorguyu, UPDATE parametrelerinin değerleri yerlerine yazılmış olarak döndürür,
+    /// örn. `UPDATE users SET name = 'John' WHERE id = 1`.
+    ///
+    /// Yalnızca hatalı sorguları loglamak içindir; sonuç asla çalıştırılmamalıdır.
+    fn debug_sql(&self) -> String
+    where
+        Self: SqlQuery,
+    {
+        parsql_core::debug::debug_sql(Self::query(), self.params())
+    }
+}
+
+// Referans ve `Arc` üzerinden de sorgu çalıştırılabilsin diye: `client.insert(&user)` çağrısı
+// varlığı tüketmez, böylece sonrasında kullanmak için klonlamak gerekmez.
+impl<T: SqlParams + ?Sized> SqlParams for &T {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+impl<T: SqlParams + ?Sized> SqlParams for Arc<T> {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+impl<T: UpdateParams + ?Sized> UpdateParams for &T {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+impl<T: UpdateParams + ?Sized> UpdateParams for Arc<T> {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+/// `#[sql_with(fonksiyon)]` ile işaretlenen alanın bağlanırken dönüştürülmesi.
+///
+/// `SqlParams` ve `UpdateParams` derive makroları işaretli her alan için bu trait'i
+/// uygulayan bir tip üretir; fonksiyon, alanın referansını alır ve `ToSql` uygulayan
+/// bir değer döndürür (örn. e-postayı küçük harfe çevirmek). Üretilen kod `SqlWith`
+/// adını kullandığından trait, diğer trait'lerle birlikte içe aktarılmalıdır.
+pub trait SqlWith: Sized + 'static {
+    /// Dönüştürülen alanın tipi
+    type Field: Sync;
+
+    /// Dönüştürme fonksiyonunu çalıştırır.
+    fn convert(value: &Self::Field) -> Box<dyn ToSql + Sync>;
+
+    /// Alanı, sorgu çalıştırılırken dönüştürülen bir parametre olarak döndürür.
+    fn bind(value: &Self::Field) -> &(dyn ToSql + Sync) {
+        Converted::<Self>::from_field(value)
+    }
+}
+
+/// Alanın kendisiyle aynı bellek düzenine sahip, bağlanırken `C::convert` çağıran sarmalayıcı.
+#[repr(transparent)]
+struct Converted<C: SqlWith> {
+    conversion: PhantomData<fn() -> C>,
+    field: C::Field,
+}
+
+impl<C: SqlWith> Converted<C> {
+    fn from_field(field: &C::Field) -> &Self {
+        // SAFETY: `repr(transparent)` ve sıfır boyutlu `PhantomData` sayesinde `Converted<C>`
+        // ile `C::Field` aynı düzene sahiptir; referansın ömrü korunur.
+        unsafe { &*(field as *const C::Field as *const Self) }
+    }
+}
+
+impl<C: SqlWith> fmt::Debug for Converted<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        C::convert(&self.field).fmt(f)
+    }
+}
+
+impl<C: SqlWith> ToSql for Converted<C> {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn StdError + Sync + Send>> {
+        // Dönüştürülen değerin tipi derleme anında bilinmez; tip denetimini o yapar
+        C::convert(&self.field).to_sql_checked(ty, out)
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
 }
 
 /// Veritabanı satırlarını Rust struct'larına dönüştürmek için trait.
@@ -35,7 +147,204 @@ pub trait FromRow {
     fn from_row(row: &Row) -> Result<Self, Error>
     where
         Self: Sized;
-} 
+
+    /// Alanların sütun konumlarını, sonuç kümesinin sütun adlarından bir kez çözer.
+    ///
+    /// `FromRow` derive makrosu alan sırasıyla bir liste üretir; bulunamayan sütunlar
+    /// `None` olur. Varsayılan uygulama boş liste döndürür.
+    fn column_positions(columns: &[&str]) -> Vec<Option<usize>> {
+        let _ = columns;
+        Vec::new()
+    }
+
+    /// Satırı, `column_positions` ile çözülmüş konumlardan okur. Konumu bilinmeyen
+    /// sütunlar adıyla okunur; varsayılan uygulama `from_row` çağırır.
+    fn from_row_at(row: &Row, positions: &[Option<usize>]) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let _ = positions;
+        Self::from_row(row)
+    }
+
+    /// Aynı sorgunun satırlarını dönüştürür. Sütun adları ilk satırdan bir kez çözülür,
+    /// böylece her satırın her alanı için sütun adı aranmaz.
+    fn from_rows(rows: &[Row]) -> Result<Vec<Self>, Error>
+    where
+        Self: Sized,
+    {
+        let Some(first) = rows.first() else {
+            return Ok(Vec::new());
+        };
+        let columns = first.columns().iter().map(|column| column.name()).collect::<Vec<_>>();
+        let positions = Self::column_positions(&columns);
+        rows.iter().map(|row| Self::from_row_at(row, &positions)).collect()
+    }
+}
+
+/// `persist_graph` ile üst kayıttan sonra eklenen alt kayıtlar için trait.
+/// Bu trait, `#[parent_key("...")]` özniteliği verilen `Insertable` derive makrosu tarafından uygulanır.
+pub trait ParentKey {
+    /// Üst kaydın `RETURNING` ile döndürülen anahtarının tipi.
+    type Key;
+
+    /// Üst kaydın anahtarını, alt kaydın ilgili alanına yazar.
+    fn set_parent_key(&mut self, key: Self::Key);
+}
+
+/// `refresh_materialized_view` ile yenilenebilen materialized view'lar için trait.
+/// Bu trait, `#[materialized_view("...")]` özniteliği verilen `Queryable` derive makrosu tarafından uygulanır.
+pub trait MaterializedView {
+    /// View'ın (gerekirse şema ile nitelenmiş) adı.
+    fn view_name() -> &'static str;
+}
+
+/// `ensure_monthly_partition` ile bölümleri oluşturulan, aralığa göre bölümlenmiş tablolar için trait.
+/// Bu trait, `#[partitioned(by = "range(...)")]` özniteliği verilen `Insertable` ve `Queryable` derive makroları tarafından uygulanır.
+pub trait Partitioned {
+    /// Bölümlenmiş üst tablonun (gerekirse şema ile nitelenmiş) adı.
+    fn partitioned_table() -> &'static str;
+
+    /// Bölüm anahtarı sütunu.
+    fn partition_column() -> &'static str;
+}
+
+/// `fetch_many` ile anahtarlarına göre toplu okunan kayıtlar için trait.
+/// Bu trait, `#[primary_key("...")]` özniteliği verilen `Queryable` derive makrosu tarafından uygulanır.
+pub trait Keyed {
+    /// Anahtar alanının tipi.
+    type Key;
+
+    /// Sorgunun koşulsuz seçim listesi ve tabloları, `SELECT ... FROM ...`.
+    fn key_select() -> &'static str;
+
+    /// İstenen anahtarlarla karşılaştırılan sütun.
+    fn key_column() -> &'static str;
+
+    /// Kaydın anahtarı.
+    fn key(&self) -> &Self::Key;
+}
+
+/// Çalışma anında `LIMIT` / `OFFSET` eklenerek okunan bir sorgu sayfası.
+/// Bu trait, `Paginated` derive makrosunun ürettiği `<Sorgu>Page` tipi tarafından uygulanır
+/// ve `fetch_page` tarafından kullanılır.
+pub trait Paginated {
+    /// Sayfalanan sorgu tipi; satırlar da bu tipe okunur.
+    type Query: SqlQuery + SqlParams + FromRow;
+
+    /// Sayfalanan sorgu.
+    fn query(&self) -> &Self::Query;
+
+    /// Sayfadaki en fazla kayıt sayısı.
+    fn limit(&self) -> i64;
+
+    /// Sayfadan önce atlanan kayıt sayısı.
+    fn offset(&self) -> i64;
+}
+
+/// Yalnızca `Some` olan alanları yazan kısmi güncelleme.
+/// Bu trait, `Patch` derive makrosunun ürettiği `<Model>Patch` tipi tarafından uygulanır
+/// ve `update_patch` tarafından kullanılır.
+pub trait Patch {
+    /// Güncellenecek kaydı belirleyen anahtarın tipi.
+    type Key: ToSql + Sync;
+
+    /// Güncellenen tablo.
+    fn table() -> &'static str;
+
+    /// WHERE koşulunda anahtarla karşılaştırılan sütun.
+    fn key_column() -> &'static str;
+
+    /// `Some` olan alanların sütunları, tanım sırasıyla.
+    fn columns(&self) -> Vec<&'static str>;
+
+    /// `Some` olan alanların değerleri, `columns()` sırasıyla.
+    fn values(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// Kısmi UPDATE sorgusu; hiçbir alan verilmemişse `None`.
+    fn patch_sql(&self) -> Option<String> {
+        parsql_core::patch::update_statement(Self::table(), Self::key_column(), &self.columns(), parsql_core::Dialect::Postgres)
+    }
+}
+
+/// `fetch_all_combined` ile başka filtrelerle `AND` ile birleştirilen bir filtre.
+/// Tek tablolu `#[where_clause]` içeren her `Queryable` için uygulanır; koşul ve parametreler
+/// `SqlQuery::criteria` ve `params()` üzerinden alınır.
+pub trait WhereFragment {
+    /// İlk yer tutucudan numaralanan koşul.
+    fn condition(&self) -> &'static str;
+
+    /// Koşulun yer tutucularının değerleri, yer tutucu sırasıyla.
+    fn condition_params(&self) -> Vec<&(dyn ToSql + Sync)>;
+}
+
+impl<T: SqlQuery + SqlParams> WhereFragment for T {
+    fn condition(&self) -> &'static str {
+        T::criteria()
+            .unwrap_or_else(|| panic!("`{}` has no single-table WHERE clause to combine", std::any::type_name::<T>()))
+            .1
+    }
+
+    fn condition_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        let mut params = self.params();
+        params.truncate(parsql_core::criteria::where_param_count(self.condition()));
+        params
+    }
+}
+
+/// `fetch_all_combined`'a birlikte verilen filtreler, ör. `(&by_state, &created_between)`.
+/// Altı `WhereFragment`'e kadar olan demetler için uygulanır.
+pub trait WhereFragments {
+    /// Filtrelerin koşulları, demetteki sırayla.
+    fn conditions(&self) -> Vec<&'static str>;
+
+    /// Filtrelerin parametreleri, demetteki sırayla.
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+}
+
+macro_rules! where_fragments {
+    ($($filter:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($filter: WhereFragment),+> WhereFragments for ($($filter,)+) {
+            fn conditions(&self) -> Vec<&'static str> {
+                let ($($filter,)+) = self;
+                vec![$($filter.condition()),+]
+            }
+
+            fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+                let ($($filter,)+) = self;
+                let mut params = Vec::new();
+                $(params.extend($filter.condition_params());)+
+                params
+            }
+        }
+    };
+}
+
+where_fragments!(A);
+where_fragments!(A, B);
+where_fragments!(A, B, C);
+where_fragments!(A, B, C, D);
+where_fragments!(A, B, C, D, E);
+where_fragments!(A, B, C, D, E, F);
+
+/// Bir varlığın CRUD işlemlerinde kullanılan sorgu tipleri.
+/// Bu trait, `Repository` derive makrosu tarafından uygulanır ve `repository::Repository`
+/// tarafından kullanılır.
+pub trait RepositoryTypes {
+    /// Kaydı tanımlayan anahtarın tipi.
+    type Key;
+    /// `create` ile çalıştırılan INSERT sorgusu.
+    type Insert: SqlQuery + SqlParams;
+    /// `find` ile çalıştırılan, anahtardan oluşturulan SELECT sorgusu.
+    type Find: SqlQuery + SqlParams + FromRow + From<Self::Key>;
+    /// `find_all` ile çalıştırılan SELECT sorgusu.
+    type FindAll: SqlQuery + SqlParams + FromRow;
+    /// `update` ile çalıştırılan UPDATE sorgusu.
+    type Update: SqlQuery + UpdateParams;
+    /// `delete` ile çalıştırılan, anahtardan oluşturulan DELETE sorgusu.
+    type Delete: SqlQuery + SqlParams + From<Self::Key>;
+}
 
 /// CrudOps trait defines the CRUD (Create, Read, Update, Delete) operations
 /// that can be performed on a PostgreSQL database.
@@ -116,6 +425,15 @@ pub trait CrudOps {
     /// * `Result<u64, Error>` - On success, returns the number of deleted records; on failure, returns Error
     fn delete<T: SqlQuery + SqlParams>(&mut self, entity: T) -> Result<u64, Error>;
 
+    /// Runs a stored procedure or function call generated by the `Callable` derive.
+    /// 
+    /// # Arguments
+    /// * `entity` - Arguments of the call (must implement SqlQuery and SqlParams traits)
+    /// 
+    /// # Returns
+    /// * `Result<u64, Error>` - On success, returns the number of rows the call returned or affected; on failure, returns Error
+    fn call<T: SqlQuery + SqlParams>(&mut self, entity: T) -> Result<u64, Error>;
+
     /// Retrieves a single record from the PostgreSQL database.
     /// 
     /// # Arguments
@@ -1,63 +1,65 @@
-use postgres::{types::FromSql, Error, Row, Transaction};
-use crate::traits::{SqlQuery, SqlParams, FromRow, UpdateParams, CrudOps};
+use postgres::{error::SqlState, types::FromSql, Error, Row, Transaction};
+use crate::traits::{SqlQuery, SqlParams, FromRow, UpdateParams, CrudOps, ParentKey};
+use crate::validation::check;
+use parsql_core::{metrics::observe, temp::create_temp_as, trace, Dialect, OnError, Progress};
 
 /// CrudOps trait implementasyonu Transaction<'_> için.
 /// Bu sayede transaction içinde tüm CRUD işlemleri extension metotları olarak kullanılabilir.
 impl<'a> CrudOps for Transaction<'a> {
     fn insert<T: SqlQuery + SqlParams, P:for<'b> FromSql<'b> + Send + Sync>(&mut self, entity: T) -> Result<P, Error> {
+        check(self, entity.validation())?;
+
         let sql = T::query();
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-POSTGRES] Execute SQL (Transaction): {}", sql);
-        }
+        trace::log_sql("PARSQL-POSTGRES-TX", sql);
 
         let params = entity.params();
-        let row = self.query_one(&sql, &params)?;
+        let row = observe("insert", T::table_name(), || self.query_one(sql, &params))?;
         row.try_get::<_, P>(0)
     }
 
     fn update<T: SqlQuery + UpdateParams>(&mut self, entity: T) -> Result<u64, Error> {
+        check(self, entity.validation())?;
+
         let sql = T::query();
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-POSTGRES] Execute SQL (Transaction): {}", sql);
-        }
+        trace::log_sql("PARSQL-POSTGRES-TX", sql);
 
         let params = entity.params();
-        self.execute(&sql, &params)
+        observe("update", T::table_name(), || self.execute(sql, &params))
     }
 
     fn delete<T: SqlQuery + SqlParams>(&mut self, entity: T) -> Result<u64, Error> {
         let sql = T::query();
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-POSTGRES] Execute SQL (Transaction): {}", sql);
-        }
+        trace::log_sql("PARSQL-POSTGRES-TX", sql);
 
         let params = entity.params();
-        self.execute(&sql, &params)
+        observe("delete", T::table_name(), || self.execute(sql, &params))
     }
 
-    fn fetch<T: SqlQuery + FromRow + SqlParams>(&mut self, entity: &T) -> Result<T, Error> {
+    fn call<T: SqlQuery + SqlParams>(&mut self, entity: T) -> Result<u64, Error> {
         let sql = T::query();
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-POSTGRES] Execute SQL (Transaction): {}", sql);
-        }
+        trace::log_sql("PARSQL-POSTGRES-TX", sql);
+
+        let params = entity.params();
+        observe("call", T::table_name(), || self.execute(sql, &params))
+    }
+
+    fn fetch<T: SqlQuery + FromRow + SqlParams>(&mut self, entity: &T) -> Result<T, Error> {
+        let sql = T::query_single();
+        trace::log_sql("PARSQL-POSTGRES-TX", sql);
         
         let params = entity.params();
-        let row = self.query_one(&sql, &params)?;
+        let row = observe("fetch", T::table_name(), || self.query_one(sql, &params))?;
         T::from_row(&row)
     }
 
     fn fetch_all<T: SqlQuery + FromRow + SqlParams>(&mut self, entity: &T) -> Result<Vec<T>, Error> {
         let sql = T::query();
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-POSTGRES] Execute SQL (Transaction): {}", sql);
-        }
+        trace::log_sql("PARSQL-POSTGRES-TX", sql);
         
         let params = entity.params();
-        let rows = self.query(&sql, &params)?;
+        let rows = observe("fetch_all", T::table_name(), || self.query(sql, &params))?;
         
-        rows.iter()
-            .map(|row| T::from_row(row))
-            .collect::<Result<Vec<_>, _>>()
+        T::from_rows(&rows)
     }
 
     fn select<T, F, R>(&mut self, entity: &T, to_model: F) -> Result<R, Error>
@@ -65,13 +67,11 @@ impl<'a> CrudOps for Transaction<'a> {
         T: SqlQuery + SqlParams,
         F: FnOnce(&Row) -> Result<R, Error>,
     {
-        let sql = T::query();
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-POSTGRES] Execute SQL (Transaction): {}", sql);
-        }
+        let sql = T::query_single();
+        trace::log_sql("PARSQL-POSTGRES-TX", sql);
 
         let params = entity.params();
-        let row = self.query_one(&sql, &params)?;
+        let row = observe("select", T::table_name(), || self.query_one(sql, &params))?;
         to_model(&row)
     }
 
@@ -81,12 +81,10 @@ impl<'a> CrudOps for Transaction<'a> {
         F: FnMut(&Row) -> Result<R, Error>,
     {
         let sql = T::query();
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-POSTGRES] Execute SQL (Transaction): {}", sql);
-        }
+        trace::log_sql("PARSQL-POSTGRES-TX", sql);
 
         let params = entity.params();
-        let rows = self.query(&sql, &params)?;
+        let rows = observe("select_all", T::table_name(), || self.query(sql, &params))?;
         
         rows.iter().map(to_model).collect()
     }
@@ -126,6 +124,64 @@ pub fn begin<'a>(client: &'a mut postgres::Client) -> Result<Transaction<'a>, Er
     client.transaction()
 }
 
+/// # rollback
+/// 
+/// Transaction'ı açıkça geri alır. Düşürülen transaction da geri alınır, ancak bu
+/// sessizce olur ve ROLLBACK'in kendi hatası kaybolur; hata yollarında bu fonksiyon
+/// geri almayı görünür kılar.
+/// 
+/// ## Parametreler
+/// - `tx`: Geri alınacak transaction; commit ve rollback transaction'ı tükettiği için
+///   tamamlanmış bir transaction bir daha kullanılamaz
+/// 
+/// ## Dönüş Değeri
+/// - `Result<(), Error>`: ROLLBACK başarısız olursa Error döner
+/// 
+/// ## Örnek Kullanım
+/// ```rust,no_run
+/// use postgres::{Client, NoTls, Error};
+/// use parsql_postgres::transactional::{begin, rollback};
+/// 
+/// fn main() -> Result<(), Error> {
+///     let mut client = Client::connect("host=localhost user=postgres dbname=test", NoTls)?;
+///     let mut tx = begin(&mut client)?;
+///
+///     match tx.execute("UPDATE users SET state = 0 WHERE id = $1", &[&1i64]) {
+///         Ok(1) => tx.commit()?,
+///         Ok(_) => rollback(tx)?,
+///         Err(e) => {
+///             rollback(tx)?;
+///             return Err(e);
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn rollback(tx: Transaction<'_>) -> Result<(), Error> {
+    trace::log_sql("PARSQL-POSTGRES-TX", "ROLLBACK");
+    tx.rollback()
+}
+
+/// # is_aborted
+/// 
+/// Transaction içindeki bir ifade hata verdiyse PostgreSQL, transaction geri alınana
+/// kadar başka ifade çalıştırmaz (`25P02 in_failed_sql_transaction`). Bu fonksiyon
+/// transaction'ın bu durumda olup olmadığını sunucuya küçük bir sorguyla sorar; `true`
+/// dönerse yapılabilecek tek şey [`rollback`] çağırmaktır.
+/// 
+/// ## Parametreler
+/// - `tx`: Durumu sınanacak transaction
+/// 
+/// ## Dönüş Değeri
+/// - `Result<bool, Error>`: Transaction hata nedeniyle durduysa `true`; bağlantı hatalarında Error döner
+pub fn is_aborted(tx: &mut Transaction<'_>) -> Result<bool, Error> {
+    match tx.batch_execute("SELECT 1") {
+        Ok(()) => Ok(false),
+        Err(e) if e.code() == Some(&SqlState::IN_FAILED_SQL_TRANSACTION) => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
 /// # tx_insert
 /// 
 /// Transaction içinde bir kaydı veritabanına ekler.
@@ -179,6 +235,99 @@ where
     Ok((tx, result))
 }
 
+/// # tx_insert_all
+///
+/// Transaction içinde birden çok kaydı sırayla ekler ve her satırdan sonra ilerlemeyi bildirir.
+///
+/// `OnError::Stop` ilk hatalı satırda durur; PostgreSQL hatadan sonra transaction'daki diğer
+/// komutları reddettiği için bu durumda transaction geri alınmalıdır. `OnError::Continue` her
+/// satırı bir savepoint içinde çalıştırır: hatalı satır geri alınır, transaction kullanılabilir kalır.
+///
+/// ## Parametreler
+/// - `tx`: Transaction nesnesi
+/// - `entities`: Eklenecek veri nesneleri (SqlQuery ve SqlParams trait'lerini implement etmeli)
+/// - `on_error`: Hatalı satırda durulup durulmayacağı
+/// - `on_progress`: Her satırdan sonra çağrılan fonksiyon
+///
+/// ## Dönüş Değeri
+/// - `Result<(Transaction<'_>, Vec<Result<P, Error>>), Error>`: Başarılı olursa, transaction ve denenen her satırın sonucunu döner; savepoint komutları başarısız olursa Error döner
+///
+/// ## Örnek Kullanım
+/// ```rust,no_run
+/// use postgres::{Client, NoTls, Error};
+/// use parsql::postgres::OnError;
+/// use parsql::postgres::transactional::{begin, tx_insert_all};
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// #[returning("id")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+///
+/// fn main() -> Result<(), Error> {
+///     let mut client = Client::connect(
+///         "host=localhost user=postgres dbname=test",
+///         NoTls,
+///     )?;
+///
+///     let tx = begin(&mut client)?;
+///     let users = vec![
+///         InsertUser { name: "John".to_string(), email: "john@example.com".to_string() },
+///         InsertUser { name: "Jane".to_string(), email: "jane@example.com".to_string() },
+///     ];
+///
+///     let (tx, results) = tx_insert_all::<_, i32>(tx, users, OnError::Continue, |progress| {
+///         println!("{}/{} ({} hatalı)", progress.done, progress.total, progress.failed);
+///     })?;
+///
+///     // Hatalı satırlar geri alındı, diğerleri kaydedilir
+///     tx.commit()?;
+///     Ok(())
+/// }
+/// ```
+pub fn tx_insert_all<'a, T, P: for<'b> FromSql<'b> + Send + Sync>(
+    mut tx: Transaction<'a>,
+    entities: Vec<T>,
+    on_error: OnError,
+    mut on_progress: impl FnMut(Progress),
+) -> Result<(Transaction<'a>, Vec<Result<P, Error>>), Error>
+where
+    T: SqlQuery + SqlParams,
+{
+    let total = entities.len();
+    let mut results = Vec::with_capacity(total);
+    let mut failed = 0;
+
+    for entity in entities {
+        let result = if on_error == OnError::Continue {
+            tx.batch_execute("SAVEPOINT parsql_insert_all")?;
+            let result = tx.insert::<T, P>(entity);
+            // Hatalı satır savepoint'e geri alınır, transaction kullanılabilir kalır
+            let release = if result.is_ok() {
+                "RELEASE SAVEPOINT parsql_insert_all"
+            } else {
+                "ROLLBACK TO SAVEPOINT parsql_insert_all"
+            };
+            tx.batch_execute(release)?;
+            result
+        } else {
+            tx.insert::<T, P>(entity)
+        };
+
+        let stop = result.is_err() && on_error == OnError::Stop;
+        failed += result.is_err() as usize;
+        results.push(result);
+        on_progress(Progress { done: results.len(), total, failed });
+        if stop {
+            break;
+        }
+    }
+
+    Ok((tx, results))
+}
+
 /// # tx_update
 /// 
 /// Transaction içinde bir kaydı günceller.
@@ -440,6 +589,243 @@ where
     Ok((tx, result))
 }
 
+/// # tx_insert_ref
+///
+/// `tx_insert`'in transaction'ı ödünç alan hali. Transaction her çağrıda taşınmadığı için
+/// döngülerde ve koşullu akışlarda doğrudan kullanılabilir.
+///
+/// ## Parametreler
+/// - `tx`: Transaction nesnesine değiştirilebilir referans
+/// - `entity`: Eklenecek veri nesnesi (SqlQuery ve SqlParams trait'lerini implement etmeli)
+///
+/// ## Dönüş Değeri
+/// - `Result<P, Error>`: Başarılı olursa, `#[returning]` sütununun değerini döner; hata durumunda Error döner
+///
+/// ## Örnek Kullanım
+/// ```rust,no_run
+/// use postgres::{Client, NoTls, Error};
+/// use parsql::postgres::transactional::{begin, tx_insert_ref};
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// #[returning("id")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+///
+/// fn main() -> Result<(), Error> {
+///     let mut client = Client::connect(
+///         "host=localhost user=postgres dbname=test",
+///         NoTls,
+///     )?;
+///
+///     let mut tx = begin(&mut client)?;
+///     let mut ids = Vec::new();
+///     for name in ["John", "Jane"] {
+///         let user = InsertUser {
+///             name: name.to_string(),
+///             email: format!("{}@example.com", name.to_lowercase()),
+///         };
+///         ids.push(tx_insert_ref::<_, i32>(&mut tx, user)?);
+///     }
+///
+///     tx.commit()?;
+///     Ok(())
+/// }
+/// ```
+pub fn tx_insert_ref<T, P: for<'b> FromSql<'b> + Send + Sync>(tx: &mut Transaction<'_>, entity: T) -> Result<P, Error>
+where
+    T: SqlQuery + SqlParams,
+{
+    tx.insert::<T, P>(entity)
+}
+
+/// # tx_update_ref
+///
+/// `tx_update`'in transaction'ı ödünç alan hali.
+///
+/// ## Parametreler
+/// - `tx`: Transaction nesnesine değiştirilebilir referans
+/// - `entity`: Güncellenecek veri nesnesi (SqlQuery ve UpdateParams trait'lerini implement etmeli)
+///
+/// ## Dönüş Değeri
+/// - `Result<u64, Error>`: Başarılı olursa, etkilenen kayıt sayısını döner; hata durumunda Error döner
+pub fn tx_update_ref<T>(tx: &mut Transaction<'_>, entity: T) -> Result<u64, Error>
+where
+    T: SqlQuery + UpdateParams,
+{
+    tx.update(entity)
+}
+
+/// # tx_delete_ref
+///
+/// `tx_delete`'in transaction'ı ödünç alan hali.
+///
+/// ## Parametreler
+/// - `tx`: Transaction nesnesine değiştirilebilir referans
+/// - `entity`: Silinecek kaydı tanımlayan veri nesnesi (SqlQuery ve SqlParams trait'lerini implement etmeli)
+///
+/// ## Dönüş Değeri
+/// - `Result<u64, Error>`: Başarılı olursa, silinen kayıt sayısını döner; hata durumunda Error döner
+pub fn tx_delete_ref<T>(tx: &mut Transaction<'_>, entity: T) -> Result<u64, Error>
+where
+    T: SqlQuery + SqlParams,
+{
+    tx.delete(entity)
+}
+
+/// # tx_fetch_ref
+///
+/// `tx_fetch`'in transaction'ı ödünç alan hali.
+///
+/// ## Parametreler
+/// - `tx`: Transaction nesnesine değiştirilebilir referans
+/// - `entity`: Sorgu parametresi nesnesi (SqlQuery, FromRow ve SqlParams trait'lerini implement etmeli)
+///
+/// ## Dönüş Değeri
+/// - `Result<T, Error>`: Başarılı olursa, bulunan kaydı döner; hata durumunda Error döner
+pub fn tx_fetch_ref<T>(tx: &mut Transaction<'_>, entity: &T) -> Result<T, Error>
+where
+    T: SqlQuery + FromRow + SqlParams,
+{
+    tx.fetch(entity)
+}
+
+/// # tx_fetch_all_ref
+///
+/// `tx_fetch_all`'un transaction'ı ödünç alan hali.
+///
+/// ## Parametreler
+/// - `tx`: Transaction nesnesine değiştirilebilir referans
+/// - `entity`: Sorgu parametresi nesnesi (SqlQuery, FromRow ve SqlParams trait'lerini implement etmeli)
+///
+/// ## Dönüş Değeri
+/// - `Result<Vec<T>, Error>`: Başarılı olursa, bulunan kayıtların listesini döner; hata durumunda Error döner
+pub fn tx_fetch_all_ref<T>(tx: &mut Transaction<'_>, entity: &T) -> Result<Vec<T>, Error>
+where
+    T: SqlQuery + FromRow + SqlParams,
+{
+    tx.fetch_all(entity)
+}
+
+/// # create_temp_from
+/// 
+/// Sorgunun sonucunu transaction ile birlikte silinen (`ON COMMIT DROP`) geçici bir
+/// tabloya yazar. Büyük bir ara küme bir kez hazırlanır; aynı transaction'daki sonraki
+/// ifadeler tabloya adıyla erişir (`#[table("tmp_aktif")]` veya `#[join("JOIN tmp_aktif ON ...")]`).
+/// 
+/// ## Parametreler
+/// - `tx`: Transaction nesnesine değiştirilebilir referans
+/// - `entity`: Tabloyu dolduracak sorgu (SqlQuery ve SqlParams trait'lerini implement etmeli)
+/// - `name`: Geçici tablonun adı
+/// 
+/// ## Dönüş Değeri
+/// - `Result<u64, Error>`: Başarılı olursa, tabloya yazılan satır sayısını döner; hata durumunda Error döner
+/// 
+/// ## Panik
+/// `name` düz bir tablo adı değilse.
+/// 
+/// ## Örnek Kullanım
+/// ```rust,ignore
+/// #[derive(Queryable, SqlParams)]
+/// #[table("users")]
+/// #[select("id, email")]
+/// #[where_clause("state = $")]
+/// pub struct ActiveUsers { pub state: i16 }
+///
+/// #[derive(Updateable, UpdateParams)]
+/// #[table("orders")]
+/// #[update("priority")]
+/// #[from("tmp_active")]
+/// #[where_clause("orders.user_id = tmp_active.id")]
+/// pub struct PrioritizeActive { pub priority: i16 }
+///
+/// let mut tx = begin(&mut client)?;
+/// create_temp_from(&mut tx, &ActiveUsers { state: 1 }, "tmp_active")?;
+/// tx_update_ref(&mut tx, PrioritizeActive { priority: 1 })?;
+/// tx.commit()?; // tmp_active burada silinir
+/// ```
+pub fn create_temp_from<T: SqlQuery + SqlParams>(tx: &mut Transaction<'_>, entity: &T, name: &str) -> Result<u64, Error> {
+    let sql = create_temp_as(T::query(), name, Dialect::Postgres)
+        .unwrap_or_else(|| panic!("create_temp_from: `{}` is not a valid temporary table name", name));
+    trace::log_sql("PARSQL-POSTGRES-TX", &sql);
+    tx.execute(sql.as_str(), &entity.params())
+}
+
+/// # persist_graph
+/// 
+/// Üst kaydı ve ona bağlı alt kayıtları tek bir transaction içinde ekler.
+/// 
+/// Üst kayıt `RETURNING` ile eklenir, dönen anahtar her alt kaydın `#[parent_key("...")]`
+/// ile belirtilen alanına yazılır ve alt kayıtlar sırayla eklenir. Herhangi bir adım
+/// başarısız olursa transaction geri alınır.
+/// 
+/// ## Parametreler
+/// - `client`: Veritabanı bağlantı istemcisi
+/// - `parent`: Üst kayıt (`#[returning("...")]` özniteliği olmalı)
+/// - `children`: Alt kayıtlar (`#[parent_key("...")]` özniteliği olmalı)
+/// 
+/// ## Dönüş Değeri
+/// - `Result<C::Key, Error>`: Başarılı olursa, üst kaydın anahtarını döner; hata durumunda Error döner
+/// 
+/// ## Örnek Kullanım
+/// ```rust,no_run
+/// use postgres::{Client, NoTls, Error};
+/// use parsql::postgres::transactional::persist_graph;
+/// 
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// #[returning("id")]
+/// pub struct InsertUser {
+///     pub name: String,
+/// }
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("posts")]
+/// #[parent_key("user_id")]
+/// pub struct InsertPost {
+///     pub user_id: i32,
+///     pub content: String,
+/// }
+///
+/// fn main() -> Result<(), Error> {
+///     let mut client = Client::connect(
+///         "host=localhost user=postgres dbname=test",
+///         NoTls,
+///     )?;
+///     
+///     let user_id = persist_graph(
+///         &mut client,
+///         InsertUser { name: "John".to_string() },
+///         vec![
+///             InsertPost { user_id: 0, content: "Merhaba".to_string() },
+///             InsertPost { user_id: 0, content: "Dünya".to_string() },
+///         ],
+///     )?;
+///     Ok(())
+/// }
+/// ```
+pub fn persist_graph<P, C>(client: &mut postgres::Client, parent: P, children: Vec<C>) -> Result<C::Key, Error>
+where
+    P: SqlQuery + SqlParams,
+    C: SqlQuery + SqlParams + ParentKey,
+    C::Key: for<'b> FromSql<'b> + Clone + Send + Sync,
+{
+    let mut tx = client.transaction()?;
+    let key = tx.insert::<P, C::Key>(parent)?;
+
+    let sql = C::query();
+    for mut child in children {
+        child.set_parent_key(key.clone());
+        trace::log_sql("PARSQL-POSTGRES-TX", sql);
+        tx.execute(sql, &child.params())?;
+    }
+
+    tx.commit()?;
+    Ok(key)
+}
+
 // Geriye dönük uyumluluk için eski tx_get fonksiyonunu koruyalım
 #[deprecated(
     since = "0.2.0",
@@ -0,0 +1,44 @@
+//! Runs `#[validate]` checks before an entity is written.
+
+use bytes::BytesMut;
+use postgres::types::{to_sql_checked, IsNull, ToSql, Type};
+use postgres::{Error, GenericClient};
+use std::error::Error as StdError;
+
+use crate::traits::ValidationError;
+
+/// Reports the outcome of an entity's `validation()` as an `Error` whose source is the
+/// [`ValidationError`].
+pub(crate) fn check<C: GenericClient>(client: &mut C, validation: Result<(), ValidationError>) -> Result<(), Error> {
+    match validation {
+        Ok(()) => Ok(()),
+        Err(e) => Err(reject(client, e)),
+    }
+}
+
+/// An `Error` whose source is `e`, for failures found before a statement is sent.
+///
+/// `postgres::Error` can't be built outside the driver, so the error is raised by a
+/// parameter that refuses to encode: the driver encodes parameters before it sends
+/// anything, so nothing reaches the server.
+pub(crate) fn reject<C: GenericClient, E: StdError + Clone + Send + Sync + 'static>(client: &mut C, e: E) -> Error {
+    match client.query_typed("SELECT $1", &[(&Rejected(e), Type::TEXT)]) {
+        Err(e) => e,
+        Ok(_) => unreachable!("a rejected parameter is never encoded"),
+    }
+}
+
+#[derive(Debug)]
+struct Rejected<E>(E);
+
+impl<E: StdError + Clone + Send + Sync + 'static> ToSql for Rejected<E> {
+    fn to_sql(&self, _ty: &Type, _out: &mut BytesMut) -> Result<IsNull, Box<dyn StdError + Sync + Send>> {
+        Err(Box::new(self.0.clone()))
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
@@ -0,0 +1,172 @@
+//! Read-through caching for `CrudOps`.
+//!
+//! [`Cached`] wraps a client (or anything implementing `CrudOps`) together with a
+//! [`CacheProvider`]. `fetch` and `fetch_all` are answered from the cache when
+//! possible, and `insert`, `update` and `delete` invalidate the cached results that
+//! read the written table. Every other method reaches the wrapped client through
+//! `Deref`, so call sites stay the same.
+//!
+//! Results are keyed by the query and the SQLite values it binds. `Cached` implements
+//! `CrudOps` too, so code generic over the trait still invalidates on writes, but its
+//! reads are not cached: the trait's `fetch` does not require the `Clone + 'static` a
+//! cached result needs.
+//!
+//!
+//! ```rust,no_run
+//! use parsql_sqlite::cache::{Cached, MemoryCache};
+//! use parsql_sqlite::macros::{FromRow, Queryable, SqlParams};
+//! use parsql_sqlite::traits::{FromRow, SqlParams, SqlQuery};
+//! use rusqlite::{types::ToSql, Connection, Error, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow, Clone, Debug)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! pub struct GetUser {
+//!     pub id: i64,
+//!     pub name: String,
+//! }
+//!
+//! fn main() -> Result<(), Error> {
+//!     let conn = Connection::open("app.db")?;
+//!
+//!     let db = Cached::new(conn, MemoryCache::new());
+//!     let user = db.fetch(&GetUser { id: 1, name: String::new() })?;
+//!     // Served from the cache until the users table is written through `db`
+//!     let again = db.fetch(&GetUser { id: 1, name: String::new() })?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::ops::Deref;
+
+use parsql_core::cache::ReadThrough;
+use rusqlite::types::{FromSql, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::{Error, Row};
+
+use crate::traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams};
+
+pub use parsql_core::cache::{CacheKey, CacheProvider, CachedValue, MemoryCache};
+
+/// A `CrudOps` implementation with a read-through cache in front of it.
+pub struct Cached<C, P = MemoryCache> {
+    inner: C,
+    cache: ReadThrough<P>,
+}
+
+impl<C, P: CacheProvider> Cached<C, P> {
+    /// Wraps `inner`, caching its fetch results in `cache`.
+    pub fn new(inner: C, cache: P) -> Self {
+        Self { inner, cache: ReadThrough::new(cache, "PARSQL-SQLITE-CACHE") }
+    }
+
+    /// The cache provider.
+    pub fn cache(&self) -> &P {
+        self.cache.provider()
+    }
+
+    /// Returns the wrapped client.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C, P> Deref for Cached<C, P> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.inner
+    }
+}
+
+impl<C, P> Cached<C, P>
+where
+    C: CrudOps,
+    P: CacheProvider,
+{
+    /// Retrieves a single record, from the cache when it holds one.
+    pub fn fetch<T>(&self, params: &T) -> Result<T, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Clone + Send + Sync + 'static,
+    {
+        let values = encode_params(&params.params());
+        self.cache.fetch(T::query(), values.as_deref(), || self.inner.fetch(params))
+    }
+
+    /// Retrieves all matching records, from the cache when it holds them.
+    pub fn fetch_all<T>(&self, params: &T) -> Result<Vec<T>, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Clone + Send + Sync + 'static,
+    {
+        let values = encode_params(&params.params());
+        self.cache.fetch(T::query(), values.as_deref(), || self.inner.fetch_all(params))
+    }
+}
+
+impl<C, P> CrudOps for Cached<C, P>
+where
+    C: CrudOps,
+    P: CacheProvider,
+{
+    fn insert<T: SqlQuery + SqlParams, R: FromSql + Send + Sync>(&self, entity: T) -> Result<R, Error> {
+        self.cache.written(T::query(), self.inner.insert(entity))
+    }
+
+    fn insert_execute<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<usize, Error> {
+        self.cache.written(T::query(), self.inner.insert_execute(entity))
+    }
+
+    fn update<T: SqlQuery + UpdateParams>(&self, entity: T) -> Result<u64, Error> {
+        self.cache.written(T::query(), self.inner.update(entity))
+    }
+
+    fn delete<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<u64, Error> {
+        self.cache.written(T::query(), self.inner.delete(entity))
+    }
+
+    fn fetch<T: SqlQuery + FromRow + SqlParams>(&self, entity: &T) -> Result<T, Error> {
+        self.inner.fetch(entity)
+    }
+
+    fn fetch_all<T: SqlQuery + FromRow + SqlParams>(&self, entity: &T) -> Result<Vec<T>, Error> {
+        self.inner.fetch_all(entity)
+    }
+
+    fn select<T: SqlQuery + SqlParams, F, R>(&self, entity: &T, to_model: F) -> Result<R, Error>
+    where
+        F: Fn(&Row) -> Result<R, Error>,
+    {
+        self.inner.select(entity, to_model)
+    }
+
+    fn select_all<T: SqlQuery + SqlParams, F, R>(&self, entity: &T, to_model: F) -> Result<Vec<R>, Error>
+    where
+        F: Fn(&Row) -> Result<R, Error>,
+    {
+        self.inner.select_all(entity, to_model)
+    }
+}
+
+/// Encodes the bound values of a cached query: each one's storage class followed by its
+/// bytes. `None` when a value can not be converted, which leaves the query uncached.
+fn encode_params(params: &[&(dyn ToSql + Sync)]) -> Option<Vec<u8>> {
+    let mut encoded = Vec::new();
+    for param in params {
+        let output = param.to_sql().ok()?;
+        let value = match &output {
+            ToSqlOutput::Borrowed(value) => *value,
+            ToSqlOutput::Owned(value) => value.into(),
+            _ => return None,
+        };
+        let (class, bytes): (u8, Vec<u8>) = match value {
+            ValueRef::Null => (0, Vec::new()),
+            ValueRef::Integer(i) => (1, i.to_be_bytes().to_vec()),
+            ValueRef::Real(f) => (2, f.to_bits().to_be_bytes().to_vec()),
+            ValueRef::Text(text) => (3, text.to_vec()),
+            ValueRef::Blob(blob) => (4, blob.to_vec()),
+        };
+        encoded.push(class);
+        encoded.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+        encoded.extend_from_slice(&bytes);
+    }
+    Some(encoded)
+}
@@ -1,105 +1,128 @@
+use std::hash::Hash;
+
+use parsql_core::{batch::{multi_row_insert, with_upsert, BatchOptions, Dialect}, criteria, lookup::{self, Lookup}, metrics::observe, paging::{check_sortable, with_first_row, with_limit_offset}, trace};
 use rusqlite::{types::FromSql, Error, Row, ToSql};
 
-use crate::traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams};
+use crate::traits::{CrudOps, FromRow, Keyed, Paginated, Patch, SqlParams, SqlQuery, UpdateParams, WhereFragments};
+
+/// Runs the entity's `#[validate]` checks; a failure is returned as
+/// `Error::ToSqlConversionFailure` holding the [`ValidationError`](crate::traits::ValidationError).
+pub(crate) fn validate<T: SqlQuery>(entity: &T) -> Result<(), Error> {
+    entity
+        .validation()
+        .map_err(|e| Error::ToSqlConversionFailure(Box::new(e)))
+}
 
 // CrudOps trait implementasyonu rusqlite::Connection için
 impl CrudOps for rusqlite::Connection {
     fn insert<T: SqlQuery + SqlParams, P: for<'a> FromSql + Send + Sync>(&self, entity: T) -> Result<P, Error> {
+        validate(&entity)?;
+
         let sql = T::query();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-SQLITE] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
 
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        self.query_row(&sql, param_refs.as_slice(), |row| row.get(0))
+        observe("insert", T::table_name(), || self.query_row(sql, param_refs.as_slice(), |row| row.get(0)))
     }
 
-    fn update<T: SqlQuery + UpdateParams>(&self, entity: T) -> Result<usize, Error> {
+    fn insert_execute<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<usize, Error> {
+        validate(&entity)?;
+
         let sql = T::query();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-SQLITE] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
 
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        self.execute(&sql, param_refs.as_slice())
+        observe("insert", T::table_name(), || self.execute(sql, param_refs.as_slice()))
     }
 
-    fn delete<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<usize, Error> {
+    fn update<T: SqlQuery + UpdateParams>(&self, entity: T) -> Result<u64, Error> {
+        validate(&entity)?;
+
         let sql = T::query();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-SQLITE] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
 
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        self.execute(&sql, param_refs.as_slice())
+        observe("update", T::table_name(), || self.execute(sql, param_refs.as_slice()).map(|rows| rows as u64))
     }
 
-    fn fetch<T: SqlQuery + FromRow + SqlParams>(&self, entity: &T) -> Result<T, Error> {
+    fn delete<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<u64, Error> {
         let sql = T::query();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-SQLITE] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
 
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        let mut stmt = self.prepare(&sql)?;
-        let mut rows = stmt.query(param_refs.as_slice())?;
+        observe("delete", T::table_name(), || self.execute(sql, param_refs.as_slice()).map(|rows| rows as u64))
+    }
+
+    fn fetch<T: SqlQuery + FromRow + SqlParams>(&self, entity: &T) -> Result<T, Error> {
+        let sql = T::query_single();
         
-        if let Some(row) = rows.next()? {
-            let result = T::from_row(row)?;
-            Ok(result)
-        } else {
-            Err(Error::QueryReturnedNoRows)
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
+
+        let params = entity.params();
+        let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+        
+        observe("fetch", T::table_name(), || {
+            let mut stmt = self.prepare(sql)?;
+            let mut rows = stmt.query(param_refs.as_slice())?;
+        
+            if let Some(row) = rows.next()? {
+                let result = T::from_row(row)?;
+                Ok(result)
+            } else {
+                Err(Error::QueryReturnedNoRows)
+            }
+        })
     }
 
     fn fetch_all<T: SqlQuery + FromRow + SqlParams>(&self, entity: &T) -> Result<Vec<T>, Error> {
         let sql = T::query();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-SQLITE] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
 
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        let mut stmt = self.prepare(&sql)?;
-        let rows = stmt.query_map(param_refs.as_slice(), |row| T::from_row(row))?;
+        observe("fetch_all", T::table_name(), || {
+            let mut stmt = self.prepare(sql)?;
+            let positions = T::column_positions(&stmt.column_names());
+            let rows = stmt.query_map(param_refs.as_slice(), |row| T::from_row_at(row, &positions))?;
         
-        let mut results = Vec::new();
-        for row_result in rows {
-            results.push(row_result?);
-        }
+            let mut results = Vec::new();
+            for row_result in rows {
+                results.push(row_result?);
+            }
         
-        Ok(results)
+            Ok(results)
+        })
     }
 
     fn select<T: SqlQuery + SqlParams, F, R>(&self, entity: &T, to_model: F) -> Result<R, Error>
     where
         F: Fn(&Row) -> Result<R, Error>,
     {
-        let sql = T::query();
+        let sql = T::query_single();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-SQLITE] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
 
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        let mut stmt = self.prepare(&sql)?;
-        stmt.query_row(param_refs.as_slice(), to_model)
+        observe("select", T::table_name(), || {
+            let mut stmt = self.prepare(sql)?;
+            stmt.query_row(param_refs.as_slice(), to_model)
+        })
     }
 
     fn select_all<T: SqlQuery + SqlParams, F, R>(&self, entity: &T, to_model: F) -> Result<Vec<R>, Error>
@@ -108,22 +131,22 @@ impl CrudOps for rusqlite::Connection {
     {
         let sql = T::query();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-SQLITE] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
 
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        let mut stmt = self.prepare(&sql)?;
-        let rows = stmt.query_map(param_refs.as_slice(), to_model)?;
+        observe("select_all", T::table_name(), || {
+            let mut stmt = self.prepare(sql)?;
+            let rows = stmt.query_map(param_refs.as_slice(), to_model)?;
         
-        let mut results = Vec::new();
-        for row in rows {
-            results.push(row?);
-        }
+            let mut results = Vec::new();
+            for row in rows {
+                results.push(row?);
+            }
         
-        Ok(results)
+            Ok(results)
+        })
     }
 }
 
@@ -198,6 +221,52 @@ pub fn insert<T: SqlQuery + SqlParams, P: for<'a> FromSql + Send + Sync>(
     conn.insert(entity)
 }
 
+/// # insert_execute
+/// 
+/// Inserts a new record and returns the number of inserted rows.
+/// 
+/// `insert` reads the first column of the row produced by `#[returning]`, so it needs a
+/// result type at every call site. This function runs the statement with `execute`
+/// instead and works for entities without a `RETURNING` clause; the generated key can
+/// still be read with `Connection::last_insert_rowid`.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `entity`: Data object to be inserted (must implement SqlQuery and SqlParams traits)
+/// 
+/// ## Return Value
+/// - `Result<usize, rusqlite::Error>`: On success, returns the number of inserted records; on failure, returns Error
+/// 
+/// ## Example Usage
+/// 
+/// ```rust,no_run
+/// use rusqlite::{Connection, Result};
+/// use parsql_macros::{Insertable, SqlParams};
+/// use parsql_sqlite::insert_execute;
+/// 
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+/// 
+/// fn main() -> Result<()> {
+///     let conn = Connection::open("test.db")?;
+/// 
+///     let user = InsertUser { name: "John".to_string(), email: "john@example.com".to_string() };
+///     assert_eq!(insert_execute(&conn, user)?, 1);
+///     println!("Inserted id: {}", conn.last_insert_rowid());
+///     Ok(())
+/// }
+/// ```
+pub fn insert_execute<T: SqlQuery + SqlParams>(
+    conn: &rusqlite::Connection,
+    entity: T,
+) -> Result<usize, rusqlite::Error> {
+    conn.insert_execute(entity)
+}
+
 /// # update
 /// 
 /// Updates a record in the database.
@@ -207,7 +276,7 @@ pub fn insert<T: SqlQuery + SqlParams, P: for<'a> FromSql + Send + Sync>(
 /// - `entity`: The entity to update (must implement SqlQuery and UpdateParams traits)
 /// 
 /// ## Return Value
-/// - `Result<usize, Error>`: On success, returns the number of rows affected; on failure, returns Error
+/// - `Result<u64, Error>`: On success, returns the number of rows affected; on failure, returns Error
 /// 
 /// ## Struct Definition
 /// Structs used with this function should be annotated with the following derive macros:
@@ -268,10 +337,47 @@ pub fn insert<T: SqlQuery + SqlParams, P: for<'a> FromSql + Send + Sync>(
 pub fn update<T: SqlQuery + UpdateParams>(
     conn: &rusqlite::Connection,
     entity: T,
-) -> Result<usize, Error> {
+) -> Result<u64, Error> {
     conn.update(entity)
 }
 
+/// # update_patch
+/// 
+/// Updates only the fields of a `Patch` that are `Some`. The `SET` clause is built at
+/// run time from those fields and the key is bound after them.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `key`: Key of the record to update
+/// - `patch`: The `<Model>Patch` generated by the `Patch` derive
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of updated records (0 without a
+///   query when no field is set); on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// // UPDATE users SET email = ?1 WHERE id = ?2
+/// let patch = UserPatch { email: Some("new@example.com".to_string()), ..Default::default() };
+/// update_patch(&conn, 1, patch)?;
+/// ```
+pub fn update_patch<P: Patch>(
+    conn: &rusqlite::Connection,
+    key: P::Key,
+    patch: P,
+) -> Result<u64, Error> {
+    let Some(sql) = patch.patch_sql() else {
+        return Ok(0);
+    };
+    trace::log_sql("PARSQL-SQLITE", &sql);
+
+    let mut params = patch.values();
+    params.push(&key);
+    let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+
+    observe("update", P::table(), || conn.execute(&sql, param_refs.as_slice()).map(|rows| rows as u64))
+}
+
 /// # delete
 /// 
 /// Deletes records from the database based on a specific condition.
@@ -281,7 +387,7 @@ pub fn update<T: SqlQuery + UpdateParams>(
 /// - `entity`: Query parameter object (must implement SqlQuery and SqlParams traits)
 /// 
 /// ## Return Value
-/// - `Result<usize, Error>`: On success, returns the number of deleted records; on failure, returns Error
+/// - `Result<u64, Error>`: On success, returns the number of deleted records; on failure, returns Error
 /// 
 /// ## Struct Definition
 /// Structs used with this function should be annotated with the following derive macros:
@@ -330,10 +436,151 @@ pub fn update<T: SqlQuery + UpdateParams>(
 pub fn delete<T: SqlQuery + SqlParams>(
     conn: &rusqlite::Connection,
     entity: T,
-) -> Result<usize, Error> {
+) -> Result<u64, Error> {
     conn.delete(entity)
 }
 
+/// # delete_where
+/// 
+/// Deletes the rows a `Queryable` selects, reusing its `#[where_clause]` instead of
+/// repeating the condition on a `Deletable` type.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `criteria`: Queryable whose table and WHERE clause pick the rows (must implement SqlQuery and SqlParams traits)
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of deleted records; on failure, returns Error
+/// 
+/// ## Panics
+/// When the query has no WHERE clause or reads through `#[join]` / `union`.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// // DELETE FROM users WHERE state = ?1 AND last_login < ?2
+/// let deleted = delete_where(&conn, &GetInactiveUsers::new(0, cutoff))?;
+/// ```
+pub fn delete_where<T: SqlQuery + SqlParams>(
+    conn: &rusqlite::Connection,
+    criteria: &T,
+) -> Result<u64, Error> {
+    let (table, where_clause) = T::criteria().unwrap_or_else(|| {
+        panic!("delete_where: `{}` has no single-table WHERE clause to reuse", std::any::type_name::<T>())
+    });
+    let sql = criteria::delete_statement(table, where_clause);
+    trace::log_sql("PARSQL-SQLITE", &sql);
+
+    let params = criteria.params();
+    let param_refs: Vec<&dyn ToSql> = params[..criteria::where_param_count(where_clause)]
+        .iter()
+        .map(|p| *p as &dyn ToSql)
+        .collect();
+
+    observe("delete", T::table_name(), || conn.execute(&sql, param_refs.as_slice()).map(|rows| rows as u64))
+}
+
+/// # update_where
+/// 
+/// Writes the `Some` fields of a `Patch` to the rows a `Queryable` selects, reusing its
+/// `#[where_clause]`. The values of the patch are bound first, then the criteria.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `criteria`: Queryable whose table and WHERE clause pick the rows (must implement SqlQuery and SqlParams traits)
+/// - `changes`: The `<Model>Patch` generated by the `Patch` derive, for the same table
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of updated records (0 without a
+///   query when no field is set); on failure, returns Error
+/// 
+/// ## Panics
+/// When the query has no WHERE clause, reads through `#[join]` / `union`, or the patch
+/// belongs to another table.
+pub fn update_where<T: SqlQuery + SqlParams, P: Patch>(
+    conn: &rusqlite::Connection,
+    criteria: &T,
+    changes: &P,
+) -> Result<u64, Error> {
+    let (table, where_clause) = T::criteria().unwrap_or_else(|| {
+        panic!("update_where: `{}` has no single-table WHERE clause to reuse", std::any::type_name::<T>())
+    });
+    if P::table() != table {
+        panic!("update_where: the patch updates `{}` but the criteria select from `{}`", P::table(), table);
+    }
+    let Some(sql) = criteria::update_statement(table, &changes.columns(), where_clause, Dialect::Sqlite) else {
+        return Ok(0);
+    };
+    trace::log_sql("PARSQL-SQLITE", &sql);
+
+    let criteria_params = criteria.params();
+    let mut params = changes.values();
+    params.extend_from_slice(&criteria_params[..criteria::where_param_count(where_clause)]);
+    let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+
+    observe("update", T::table_name(), || conn.execute(&sql, param_refs.as_slice()).map(|rows| rows as u64))
+}
+
+/// # fetch_all_combined
+/// 
+/// Retrieves the records of `T` matching every filter of `filters`. The `#[where_clause]`
+/// of each filter is joined with `AND` and its placeholders are renumbered after the
+/// filters before it, so one filter struct can narrow any query over its table.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `filters`: Tuple of filters, e.g. `(&by_state, &created_since)`; each is a `Queryable`
+///   with a single-table WHERE clause
+/// 
+/// ## Return Value
+/// - `Result<Vec<T>, Error>`: On success, returns the matching records; on failure, returns Error
+/// 
+/// ## Errors
+/// When the query of `T` binds parameters or is a `UNION`; the error's source is a
+/// `CombineError` and nothing is sent to the database.
+/// 
+/// ## Panics
+/// When a filter has no single-table WHERE clause.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// #[derive(Queryable, SqlParams)]
+/// #[table("users")]
+/// #[select("id")]
+/// #[where_clause("state = $")]
+/// pub struct ByState {
+///     pub state: i16,
+/// }
+///
+/// #[derive(Queryable, SqlParams)]
+/// #[table("users")]
+/// #[select("id")]
+/// #[where_clause("created_at >= $since")]
+/// pub struct CreatedSince {
+///     pub since: NaiveDateTime,
+/// }
+///
+/// // SELECT id, name FROM users WHERE (state = ?1) AND (created_at >= ?2) ORDER BY id
+/// let users: Vec<UserRow> = fetch_all_combined(&conn, (&ByState { state: 1 }, &CreatedSince { since }))?;
+/// ```
+pub fn fetch_all_combined<T: SqlQuery + FromRow, F: WhereFragments>(
+    conn: &rusqlite::Connection,
+    filters: F,
+) -> Result<Vec<T>, Error> {
+    let sql = criteria::combined_query::<T>(&filters.conditions(), Dialect::Sqlite)
+        .map_err(|e| Error::ToSqlConversionFailure(Box::new(e)))?;
+    trace::log_sql("PARSQL-SQLITE", &sql);
+
+    let params = filters.params();
+    let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+
+    observe("fetch_all", T::table_name(), || {
+        let mut stmt = conn.prepare(&sql)?;
+        let positions = T::column_positions(&stmt.column_names());
+        let rows = stmt.query_map(param_refs.as_slice(), |row| T::from_row_at(row, &positions))?;
+        rows.collect()
+    })
+}
+
 /// # fetch
 /// 
 /// Retrieves a single record from the database based on a specific condition.
@@ -459,6 +706,202 @@ pub fn fetch_all<T: SqlQuery + FromRow + SqlParams>(
     conn.fetch_all(entity)
 }
 
+/// # fetch_all_limited
+/// 
+/// Retrieves one page of records, appending `LIMIT` and `OFFSET` to the query at
+/// execution time instead of baking `#[limit]` / `#[offset]` into the query type.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `entity`: Query parameter object (must implement SqlQuery, FromRow, and SqlParams traits)
+/// - `limit`: Maximum number of records to return
+/// - `offset`: Number of records to skip
+/// 
+/// ## Return Value
+/// - `Result<Vec<T>, Error>`: On success, returns the records of the page; on failure, returns Error
+/// 
+/// ## Panics
+/// Panics when the query of `T` already has a `LIMIT`, `OFFSET` or `FETCH` clause.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// // SELECT id, name FROM users WHERE active = $1 ORDER BY id LIMIT ?2 OFFSET ?3
+/// let second_page = fetch_all_limited(&conn, &query, 20, 20)?;
+/// ```
+pub fn fetch_all_limited<T: SqlQuery + FromRow + SqlParams>(
+    conn: &rusqlite::Connection,
+    entity: &T,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<T>, Error> {
+    let params = entity.params();
+    let sql = with_limit_offset(T::query(), params.len(), Dialect::Sqlite)
+        .unwrap_or_else(|e| panic!("fetch_all_limited: {}", e));
+
+    trace::log_sql("PARSQL-SQLITE", &sql);
+
+    let mut param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+    param_refs.push(&limit);
+    param_refs.push(&offset);
+
+    observe("fetch_all", T::table_name(), || {
+        let mut stmt = conn.prepare(&sql)?;
+        let positions = T::column_positions(&stmt.column_names());
+        let rows = stmt.query_map(param_refs.as_slice(), |row| T::from_row_at(row, &positions))?;
+        rows.collect()
+    })
+}
+
+/// # fetch_page
+/// 
+/// Retrieves the page generated by `#[derive(Paginated)]`, i.e. [`fetch_all_limited`]
+/// with the query, limit and offset held by the page.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `page`: The `<Query>Page` value
+/// 
+/// ## Return Value
+/// - `Result<Vec<P::Query>, Error>`: On success, returns the records of the page; on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// let second_page = fetch_page(&conn, &UsersByStatePage::nth(query, 2, 20))?;
+/// ```
+pub fn fetch_page<P: Paginated>(conn: &rusqlite::Connection, page: &P) -> Result<Vec<P::Query>, Error> {
+    fetch_all_limited(conn, page.query(), page.limit(), page.offset())
+}
+
+/// # fetch_first
+/// 
+/// Retrieves the first record of the query ordered by `column`, appending
+/// `ORDER BY column ASC NULLS LAST LIMIT 1` at execution time. `column` must be listed in
+/// the query type's `#[sortable("...")]`; an existing `ORDER BY` only breaks ties.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `entity`: Query parameter object (must implement SqlQuery, FromRow, and SqlParams traits)
+/// - `column`: The order column, one of the `#[sortable]` columns
+/// 
+/// ## Return Value
+/// - `Result<Option<T>, Error>`: On success, returns the record, or `None` when the query matches nothing;
+///   on failure, returns Error. An unlisted `column` is rejected with
+///   `Error::ToSqlConversionFailure` holding the [`ValidationError`](crate::traits::ValidationError).
+/// 
+/// ## Panics
+/// Panics when the query of `T` already has a `LIMIT`, `OFFSET` or `FETCH` clause.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// // SELECT ... WHERE customer_id = ?1 ORDER BY created_at ASC NULLS LAST LIMIT 1
+/// let oldest = fetch_first(&conn, &CustomerOrders::new(7), "created_at")?;
+/// ```
+pub fn fetch_first<T: SqlQuery + FromRow + SqlParams>(
+    conn: &rusqlite::Connection,
+    entity: &T,
+    column: &str,
+) -> Result<Option<T>, Error> {
+    fetch_end(conn, entity, column, false)
+}
+
+/// # fetch_last
+/// 
+/// Retrieves the last record of the query ordered by `column`, i.e. [`fetch_first`] with
+/// `ORDER BY column DESC NULLS LAST`.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `entity`: Query parameter object (must implement SqlQuery, FromRow, and SqlParams traits)
+/// - `column`: The order column, one of the `#[sortable]` columns
+/// 
+/// ## Return Value
+/// - `Result<Option<T>, Error>`: On success, returns the record, or `None` when the query matches nothing;
+///   on failure, returns Error
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// let latest = fetch_last(&conn, &CustomerOrders::new(7), "created_at")?;
+/// ```
+pub fn fetch_last<T: SqlQuery + FromRow + SqlParams>(
+    conn: &rusqlite::Connection,
+    entity: &T,
+    column: &str,
+) -> Result<Option<T>, Error> {
+    fetch_end(conn, entity, column, true)
+}
+
+fn fetch_end<T: SqlQuery + FromRow + SqlParams>(
+    conn: &rusqlite::Connection,
+    entity: &T,
+    column: &str,
+    last: bool,
+) -> Result<Option<T>, Error> {
+    check_sortable(column, T::sortable_columns())
+        .map_err(|e| Error::ToSqlConversionFailure(Box::new(e)))?;
+    let sql = with_first_row(T::query(), column, last)
+        .unwrap_or_else(|e| panic!("{}: {}", if last { "fetch_last" } else { "fetch_first" }, e));
+
+    trace::log_sql("PARSQL-SQLITE", &sql);
+
+    let params = entity.params();
+    let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+
+    observe("fetch", T::table_name(), || {
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query(param_refs.as_slice())?;
+        rows.next()?.map(T::from_row).transpose()
+    })
+}
+
+/// # fetch_many
+/// 
+/// Retrieves the records of a list of keys, e.g. the authors of a page of posts, with
+/// `WHERE id IN (?1, ...)` statements of at most [`SQLITE_CHUNK`](parsql_core::lookup::SQLITE_CHUNK)
+/// keys each. The records come back in the order of `keys`, and the keys without a record
+/// are listed in `missing`.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `keys`: The requested keys; a key given twice is read once
+/// 
+/// ## Return Value
+/// - `Result<Lookup<T, K>, Error>`: On success, returns the records found and the missing keys;
+///   on failure, returns Error
+/// 
+/// ## Struct Definition
+/// The query type derives `Queryable` with `#[primary_key("...")]` naming its key field;
+/// its select list, table and joins are used, its `where_clause` is not.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// // SELECT id, name, email FROM users WHERE id IN (?1, ?2, ?3)
+/// let users = fetch_many::<UserById, i64>(&conn, &[3, 1, 7])?;
+/// for id in &users.missing {
+///     println!("user {} not found", id);
+/// }
+/// ```
+pub fn fetch_many<T, K>(conn: &rusqlite::Connection, keys: &[K]) -> Result<Lookup<T, K>, Error>
+where
+    T: Keyed<Key = K> + SqlQuery + FromRow,
+    K: ToSql + Eq + Hash + Clone,
+{
+    let mut records = Vec::with_capacity(keys.len());
+    for chunk in lookup::distinct(keys).chunks(lookup::SQLITE_CHUNK) {
+        let sql = lookup::keys_query(T::key_select(), T::key_column(), chunk.len(), Dialect::Sqlite);
+
+        trace::log_sql("PARSQL-SQLITE", &sql);
+
+        let param_refs: Vec<&dyn ToSql> = chunk.iter().map(|key| *key as &dyn ToSql).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let positions = T::column_positions(&stmt.column_names());
+        let rows = observe("fetch_many", T::table_name(), || {
+            stmt.query_map(param_refs.as_slice(), |row| T::from_row_at(row, &positions))?.collect::<Result<Vec<_>, _>>()
+        })?;
+        records.extend(rows);
+    }
+    Ok(lookup::in_key_order(keys, records, T::key))
+}
+
 /// # get
 /// 
 /// Retrieves a single record from the database based on a specific condition.
@@ -649,3 +1092,217 @@ where
 {
     conn.select_all(entity, to_model)
 }
+
+/// # insert_many
+/// 
+/// Inserts multiple records using multi-row `INSERT` statements.
+/// Records are split into chunks so that a single statement never binds more than
+/// 999 parameters (SQLite's default limit).
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `entities`: Records to insert (must implement SqlQuery and SqlParams traits)
+/// - `options`: Chunk size and whether all chunks run inside one transaction
+/// 
+/// ## Return Value
+/// - `Result<usize, Error>`: On success, returns the number of inserted rows; on failure, returns Error
+/// 
+/// ## Example Usage
+/// 
+/// ```rust,no_run
+/// use rusqlite::{Connection, Result};
+/// use parsql_macros::{Insertable, SqlParams};
+/// use parsql_sqlite::{insert_many, BatchOptions};
+/// 
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+/// 
+/// fn main() -> Result<()> {
+///     let conn = Connection::open("test.db")?;
+///     let users: Vec<InsertUser> = (0..10_000)
+///         .map(|i| InsertUser { name: format!("user{}", i), email: format!("user{}@example.com", i) })
+///         .collect();
+/// 
+///     let options = BatchOptions { transaction: true, ..Default::default() };
+///     let inserted = insert_many(&conn, &users, options)?;
+///     println!("Inserted rows: {}", inserted);
+///     Ok(())
+/// }
+/// ```
+pub fn insert_many<T: SqlQuery + SqlParams>(
+    conn: &rusqlite::Connection,
+    entities: &[T],
+    options: BatchOptions,
+) -> Result<usize, Error> {
+    insert_chunks(conn, entities, None, options)
+}
+
+/// # upsert_many
+/// 
+/// Inserts multiple records like [`insert_many`], turning every chunk into one
+/// `INSERT ... ON CONFLICT (conflict) DO UPDATE` statement: a row whose `conflict`
+/// columns match an existing row updates its other columns from `excluded`.
+/// 
+/// A conflict key should appear only once per chunk; within one statement the later
+/// row overwrites the earlier one.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `entities`: Records to upsert (must implement SqlQuery and SqlParams traits)
+/// - `conflict`: Columns of the unique constraint or index the rows collide on
+/// - `options`: Chunk size and whether all chunks run inside one transaction
+/// 
+/// ## Return Value
+/// - `Result<usize, Error>`: On success, returns the number of inserted or updated rows; on failure, returns Error
+/// 
+/// ## Panics
+/// If a `conflict` entry is not a plain column name or the INSERT has no `VALUES` list.
+/// 
+/// ## Example Usage
+/// 
+/// ```rust,no_run
+/// use rusqlite::{Connection, Result};
+/// use parsql_macros::{Insertable, SqlParams};
+/// use parsql_sqlite::{upsert_many, BatchOptions};
+/// 
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+/// 
+/// fn main() -> Result<()> {
+///     let conn = Connection::open("test.db")?;
+///     let users = vec![
+///         InsertUser { name: "Ali".to_string(), email: "ali@example.com".to_string() },
+///         InsertUser { name: "Ayşe".to_string(), email: "ayse@example.com".to_string() },
+///     ];
+/// 
+///     let upserted = upsert_many(&conn, &users, &["email"], BatchOptions::default())?;
+///     println!("Upserted rows: {}", upserted);
+///     Ok(())
+/// }
+/// ```
+pub fn upsert_many<T: SqlQuery + SqlParams>(
+    conn: &rusqlite::Connection,
+    entities: &[T],
+    conflict: &[&str],
+    options: BatchOptions,
+) -> Result<usize, Error> {
+    if with_upsert(T::query(), conflict).is_none() {
+        panic!("upsert_many: can't add ON CONFLICT ({}) to `{}`", conflict.join(", "), T::query());
+    }
+
+    insert_chunks(conn, entities, Some(conflict), options)
+}
+
+pub(crate) fn insert_chunks<T: SqlQuery + SqlParams>(
+    conn: &rusqlite::Connection,
+    entities: &[T],
+    conflict: Option<&[&str]>,
+    options: BatchOptions,
+) -> Result<usize, Error> {
+    let Some(first) = entities.first() else {
+        return Ok(0);
+    };
+
+    let sql = T::query();
+    let params_per_row = first.params().len();
+    let rows_per_chunk = options.rows_per_chunk(Dialect::Sqlite, params_per_row);
+
+    let tx = if options.transaction {
+        Some(conn.unchecked_transaction()?)
+    } else {
+        None
+    };
+
+    let mut inserted = 0;
+    for chunk in entities.chunks(rows_per_chunk) {
+        // upsert_many ON CONFLICT eklenebildiğini baştan doğruladı
+        let chunk_sql = multi_row_insert(sql, params_per_row, chunk.len(), Dialect::Sqlite)
+            .map(|chunk_sql| match conflict {
+                Some(conflict) => with_upsert(&chunk_sql, conflict).expect("checked by upsert_many"),
+                None => chunk_sql,
+            });
+        match chunk_sql {
+            Some(chunk_sql) => {
+                trace::log_sql("PARSQL-SQLITE", &chunk_sql);
+
+                let params: Vec<&dyn ToSql> = chunk
+                    .iter()
+                    .flat_map(|entity| entity.params())
+                    .map(|p| p as &dyn ToSql)
+                    .collect();
+                inserted += conn.execute(&chunk_sql, params.as_slice())?;
+            }
+            // VALUES listesi olmayan sorgular (INSERT ... SELECT) tek tek çalıştırılır
+            None => {
+                for entity in chunk {
+                    let params = entity.params();
+                    let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+                    inserted += conn.execute(sql, param_refs.as_slice())?;
+                }
+            }
+        }
+    }
+
+    if let Some(tx) = tx {
+        tx.commit()?;
+    }
+
+    Ok(inserted)
+}
+
+/// # update_many
+/// 
+/// Updates multiple records, reusing one prepared statement.
+/// 
+/// ## Parameters
+/// - `conn`: SQLite database connection
+/// - `entities`: Records to update (must implement SqlQuery and UpdateParams traits)
+/// - `options`: Whether all updates run inside one transaction (`chunk_size` is not used,
+///   every record is a separate statement)
+/// 
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the total number of updated rows; on failure, returns Error
+pub fn update_many<T: SqlQuery + UpdateParams>(
+    conn: &rusqlite::Connection,
+    entities: &[T],
+    options: BatchOptions,
+) -> Result<u64, Error> {
+    if entities.is_empty() {
+        return Ok(0);
+    }
+
+    let sql = T::query();
+
+    trace::log_sql("PARSQL-SQLITE", sql);
+
+    let tx = if options.transaction {
+        Some(conn.unchecked_transaction()?)
+    } else {
+        None
+    };
+
+    let mut updated = 0;
+    {
+        let mut stmt = conn.prepare(sql)?;
+        for entity in entities {
+            let params = entity.params();
+            let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+            updated += stmt.execute(param_refs.as_slice())? as u64;
+        }
+    }
+
+    if let Some(tx) = tx {
+        tx.commit()?;
+    }
+
+    Ok(updated)
+}
+
@@ -0,0 +1,130 @@
+//! Decimal column support.
+//!
+//! SQLite has no exact numeric type: a `NUMERIC` or `REAL` column stores `12.30` as a
+//! float. [`DecimalText`] writes a [`Decimal`] as TEXT, so amounts round-trip without
+//! loss, and reads it back from TEXT as well as from INTEGER and REAL columns written
+//! by other tools:
+//!
+//! ```rust,no_run
+//! use parsql::sqlite::{macros::{FromRow, Insertable, SqlParams}, traits::{FromRow, SqlParams, SqlQuery}, Decimal, DecimalText};
+//! use rusqlite::{types::ToSql, Error, Row};
+//!
+//! #[derive(Insertable, SqlParams)]
+//! #[table("payments")]
+//! pub struct InsertPayment {
+//!     pub reference: String,
+//!     pub amount: DecimalText,
+//! }
+//!
+//! let payment = InsertPayment {
+//!     reference: "INV-1".into(),
+//!     amount: DecimalText(Decimal::new(1230, 2)), // 12.30
+//! };
+//! ```
+//!
+//! Declare such columns as `TEXT`; a column with `NUMERIC` affinity turns the text back
+//! into a float when it looks like a number. Comparing or summing them in SQL goes
+//! through `CAST(amount AS REAL)` and is only as exact as a float.
+
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+pub use rust_decimal::Decimal;
+
+/// A [`Decimal`] stored as TEXT.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DecimalText(pub Decimal);
+
+impl DecimalText {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> Decimal {
+        self.0
+    }
+}
+
+impl From<Decimal> for DecimalText {
+    fn from(value: Decimal) -> Self {
+        DecimalText(value)
+    }
+}
+
+impl Deref for DecimalText {
+    type Target = Decimal;
+
+    fn deref(&self) -> &Decimal {
+        &self.0
+    }
+}
+
+impl DerefMut for DecimalText {
+    fn deref_mut(&mut self) -> &mut Decimal {
+        &mut self.0
+    }
+}
+
+impl FromSql for DecimalText {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let decimal = match value {
+            ValueRef::Text(text) => std::str::from_utf8(text)
+                .map_err(|e| FromSqlError::Other(Box::new(e)))
+                .and_then(|text| {
+                    // Bilimsel gösterimle yazılmış değerler de kabul edilir
+                    Decimal::from_str(text.trim())
+                        .or_else(|_| Decimal::from_scientific(text.trim()))
+                        .map_err(|e| FromSqlError::Other(Box::new(e)))
+                })?,
+            ValueRef::Integer(value) => Decimal::from(value),
+            // REAL değerler en kısa ondalık gösterimleri üzerinden çevrilir (0.1 -> 0.1)
+            ValueRef::Real(value) => Decimal::from_str(&value.to_string())
+                .or_else(|_| Decimal::from_scientific(&format!("{:e}", value)))
+                .map_err(|e| FromSqlError::Other(Box::new(e)))?,
+            _ => return Err(FromSqlError::InvalidType),
+        };
+        Ok(DecimalText(decimal))
+    }
+}
+
+impl ToSql for DecimalText {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.0.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn read(conn: &Connection, sql: &str) -> DecimalText {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn round_trips_as_text() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE payments (amount TEXT)", []).unwrap();
+
+        let amount = DecimalText(Decimal::new(123_456_789_012_345_678, 8));
+        conn.execute("INSERT INTO payments (amount) VALUES (?1)", [&amount]).unwrap();
+
+        let stored: String = conn.query_row("SELECT typeof(amount) FROM payments", [], |row| row.get(0)).unwrap();
+        assert_eq!(stored, "text");
+        assert_eq!(read(&conn, "SELECT amount FROM payments"), amount);
+    }
+
+    #[test]
+    fn reads_numbers_and_rejects_other_text() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        assert_eq!(read(&conn, "SELECT 42").0, Decimal::from(42));
+        assert_eq!(read(&conn, "SELECT 0.1").0, Decimal::new(1, 1));
+        assert_eq!(read(&conn, "SELECT '1.5e3'").0, Decimal::from(1500));
+
+        let text: rusqlite::Result<DecimalText> = conn.query_row("SELECT 'abc'", [], |row| row.get(0));
+        assert!(text.is_err());
+        let null: rusqlite::Result<DecimalText> = conn.query_row("SELECT NULL", [], |row| row.get(0));
+        assert!(null.is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! Rows of ad-hoc queries, without a result struct.
+//!
+//! [`query_dynamic`] runs any SQL with its parameters and returns every row as a map of
+//! column name to [`Value`]. SQLite stores every value as one of five storage classes,
+//! each of which maps to a `Value` variant:
+//!
+//! ```rust,no_run
+//! use parsql_sqlite::{query_dynamic, Value};
+//! use rusqlite::Connection;
+//!
+//! fn main() -> rusqlite::Result<()> {
+//!     let conn = Connection::open("test.db")?;
+//!     let rows = query_dynamic(&conn, "SELECT id, name, created_at FROM users WHERE state = ?", &[&1])?;
+//!     for row in &rows {
+//!         println!("{} {} {}", row["id"], row["name"], row["created_at"]);
+//!     }
+//!     assert!(rows.iter().all(|row| matches!(row["id"], Value::Int(_))));
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use parsql_core::{metrics::observe, trace, value::Value};
+use rusqlite::types::{ToSql, ValueRef};
+use rusqlite::{Connection, Error, Row};
+
+fn to_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(value) => Value::Int(value),
+        ValueRef::Real(value) => Value::Float(value),
+        // SQLite metnin UTF-8 olduğunu garanti etmez
+        ValueRef::Text(text) => Value::Text(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(bytes) => Value::Bytes(bytes.to_vec()),
+    }
+}
+
+fn to_map(row: &Row<'_>, names: &[String]) -> Result<HashMap<String, Value>, Error> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| Ok((name.clone(), to_value(row.get_ref(i)?))))
+        .collect()
+}
+
+/// Runs `sql` and returns every row as a map of column name to value.
+///
+/// Booleans come back as `Value::Int`, since SQLite stores them as 0 and 1. Statements
+/// without result columns return no rows.
+pub fn query_dynamic(conn: &Connection, sql: &str, params: &[&dyn ToSql]) -> Result<Vec<HashMap<String, Value>>, Error> {
+    trace::log_sql("PARSQL-SQLITE", sql);
+
+    observe("query_dynamic", "unknown", || {
+        let mut stmt = conn.prepare(sql)?;
+        let names = stmt.column_names().into_iter().map(str::to_string).collect::<Vec<_>>();
+        let mut rows = stmt.query(params)?;
+        let mut maps = Vec::new();
+        while let Some(row) = rows.next()? {
+            maps.push(to_map(row, &names)?);
+        }
+        Ok(maps)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_every_storage_class() {
+        let conn = Connection::open_in_memory().unwrap();
+        let rows = query_dynamic(
+            &conn,
+            "SELECT 1 AS id, 'ali' AS name, 2.5 AS score, x'dead' AS data, NULL AS deleted_at WHERE ? = 1",
+            &[&1],
+        )
+        .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!(row["id"], Value::Int(1));
+        assert_eq!(row["name"].as_str(), Some("ali"));
+        assert_eq!(row["score"], Value::Float(2.5));
+        assert_eq!(row["data"].as_bytes(), Some(&[0xde, 0xad][..]));
+        assert!(row["deleted_at"].is_null());
+    }
+}
@@ -0,0 +1,120 @@
+//! Column-level encryption.
+//!
+//! [`EncryptedColumn`] stores its value as a `BLOB`, encrypted with the cipher registered
+//! through [`set_column_cipher`]. Parameters are encrypted when they are bound and
+//! columns decrypted when rows are converted, so the derives need no extra attributes:
+//!
+//! ```rust,ignore
+//! #[derive(Insertable, SqlParams)]
+//! #[table("users")]
+//! pub struct InsertUser {
+//!     pub name: String,
+//!     pub email: EncryptedColumn<String>,
+//! }
+//!
+//! set_column_cipher(MyAesGcm::new(key));
+//! let id: i64 = insert(&conn, InsertUser { name: "Ali".into(), email: "ali@example.com".to_string().into() })?;
+//! ```
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+use parsql_core::encryption;
+pub use parsql_core::encryption::{set_column_cipher, CipherError, ColumnCipher, Plaintext};
+
+/// A value stored encrypted with the registered [`ColumnCipher`].
+///
+/// `Debug` never prints the value, only a hash of it keyed with a per-process secret.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct EncryptedColumn<T>(pub T);
+
+impl<T> EncryptedColumn<T> {
+    /// Returns the wrapped (decrypted) value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for EncryptedColumn<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for EncryptedColumn<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for EncryptedColumn<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Plaintext> fmt::Debug for EncryptedColumn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncryptedColumn({})", encryption::redacted(&self.0))
+    }
+}
+
+impl<T: Plaintext> FromSql for EncryptedColumn<T> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        encryption::decrypt(value.as_blob()?)
+            .map(EncryptedColumn)
+            .map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+impl<T: Plaintext> ToSql for EncryptedColumn<T> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        encryption::encrypt(&self.0)
+            .map(ToSqlOutput::from)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    struct Xor(u8);
+
+    impl ColumnCipher for Xor {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CipherError> {
+            Ok(plaintext.iter().map(|b| b ^ self.0).collect())
+        }
+
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CipherError> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    #[test]
+    fn round_trips_through_blob_column() {
+        set_column_cipher(Xor(0x5a));
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE users (email BLOB NOT NULL)", [])
+            .unwrap();
+        let value = EncryptedColumn("ali@example.com".to_string());
+        conn.execute("INSERT INTO users (email) VALUES (?)", [&value])
+            .unwrap();
+
+        let stored: Vec<u8> = conn
+            .query_row("SELECT email FROM users", [], |row| row.get(0))
+            .unwrap();
+        assert_ne!(stored, b"ali@example.com");
+
+        let read: EncryptedColumn<String> = conn
+            .query_row("SELECT email FROM users", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(read, value);
+        assert!(!format!("{:?}", read).contains("ali"));
+    }
+}
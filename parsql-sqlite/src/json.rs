@@ -0,0 +1,82 @@
+//! JSON column support.
+//!
+//! SQLite stores JSON as text, so aggregated child rows built with
+//! `json_group_array(json_object(...))` can be read into nested Rust structures
+//! through the [`Json`] wrapper. With a `LEFT JOIN`, a parent without children
+//! still yields one joined row of NULLs; the `FILTER` clause leaves it out, so the
+//! field holds an empty list instead of one object of nulls:
+//!
+//! ```rust,no_run
+//! use parsql::sqlite::{macros::{FromRow, Queryable, SqlParams}, traits::{FromRow, SqlParams, SqlQuery}, Json};
+//! use rusqlite::{types::ToSql, Error, Row};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! pub struct Post {
+//!     pub id: i64,
+//!     pub title: String,
+//! }
+//!
+//! #[derive(Queryable, SqlParams, FromRow)]
+//! #[table("users")]
+//! #[select("users.id, users.name, json_group_array(json_object('id', posts.id, 'title', posts.title)) FILTER (WHERE posts.id IS NOT NULL) AS posts")]
+//! #[join("LEFT JOIN posts ON posts.user_id = users.id")]
+//! #[where_clause("users.id = $")]
+//! #[group_by("users.id")]
+//! pub struct UserWithPosts {
+//!     pub id: i64,
+//!     pub name: String,
+//!     pub posts: Json<Vec<Post>>,
+//! }
+//! ```
+
+use std::ops::{Deref, DerefMut};
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A value stored as JSON text, (de)serialized with serde.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Json<T>(pub T);
+
+impl<T> Json<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromSql for Json<T> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let bytes = match value {
+            ValueRef::Text(text) => text,
+            ValueRef::Blob(blob) => blob,
+            _ => return Err(FromSqlError::InvalidType),
+        };
+        serde_json::from_slice(bytes)
+            .map(Json)
+            .map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+impl<T: Serialize> ToSql for Json<T> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        serde_json::to_string(&self.0)
+            .map(ToSqlOutput::from)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+    }
+}
@@ -176,23 +176,63 @@ pub mod crud_ops;
 pub mod transactional_ops;
 pub mod traits;
 pub mod macros;
+pub mod options;
+pub mod cache;
+pub mod repository;
+pub mod encryption;
+pub mod fixtures;
+pub mod registry;
+pub mod schema;
+pub mod dynamic;
+
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "decimal")]
+pub mod decimal;
+
+#[cfg(feature = "pool")]
+pub mod pool;
 
 pub use macros::*;
 
 // Re-export sqlite types that might be needed
 pub use rusqlite::{Connection, Error, Row};
 pub use rusqlite::types::ToSql;
+#[cfg(feature = "json")]
+pub use json::Json;
+#[cfg(feature = "decimal")]
+pub use decimal::{Decimal, DecimalText};
+pub use encryption::EncryptedColumn;
+pub use dynamic::query_dynamic;
+pub use parsql_core::value::Value;
 
 // Re-export crud operations
 pub use crud_ops::{
     insert, 
+    insert_execute,
     select, 
     select_all, 
     update, 
+    update_patch,
+    update_where,
     delete, 
+    delete_where,
     fetch, 
     fetch_all,
+    fetch_all_combined,
+    fetch_all_limited,
+    fetch_first,
+    fetch_last,
+    fetch_many,
+    fetch_page,
+    insert_many,
+    upsert_many,
+    update_many,
 };
+pub use parsql_core::{lookup::Lookup, BatchOptions, OnError, Progress};
+pub use options::{open, open_with, retry_on_busy, JournalMode, SqliteOptions, Synchronous};
+pub use parsql_core::trace::set_trace;
 
 // Re-export transaction operations
 pub use transactional_ops as transactional;
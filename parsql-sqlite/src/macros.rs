@@ -1,7 +1,11 @@
 pub use parsql_macros::{
     Deletable,
+    Entity,
     Insertable,
+    Paginated,
+    Patch,
     Queryable,
+    Repository,
     SqlParams,
     Updateable,
     UpdateParams,
@@ -0,0 +1,196 @@
+//! Connection configuration for multi-threaded use.
+//!
+//! A fresh SQLite connection uses rollback journaling, fails immediately when another
+//! connection holds a lock and does not enforce foreign keys. [`SqliteOptions`] sets the
+//! pragmas most applications want and [`open`] / [`open_with`] apply them on connect:
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use parsql::sqlite::{open_with, retry_on_busy, JournalMode, SqliteOptions};
+//!
+//! # fn main() -> Result<(), rusqlite::Error> {
+//! let options = SqliteOptions::new()
+//!     .journal_mode(JournalMode::Wal)
+//!     .busy_timeout(Duration::from_secs(10));
+//! let conn = open_with("app.db", &options)?;
+//!
+//! // Writers that still collide after the busy timeout can be retried
+//! let deleted = retry_on_busy(3, Duration::from_millis(50), || {
+//!     conn.execute("DELETE FROM sessions WHERE expires_at < strftime('%s','now')", [])
+//! })?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use parsql_core::trace;
+use rusqlite::{Connection, Error, ErrorCode};
+
+/// `PRAGMA journal_mode` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    /// Write-ahead log: readers no longer block the writer
+    Wal,
+    Off,
+}
+
+impl JournalMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            JournalMode::Delete => "DELETE",
+            JournalMode::Truncate => "TRUNCATE",
+            JournalMode::Persist => "PERSIST",
+            JournalMode::Memory => "MEMORY",
+            JournalMode::Wal => "WAL",
+            JournalMode::Off => "OFF",
+        }
+    }
+}
+
+/// `PRAGMA synchronous` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Synchronous {
+    Off,
+    /// Safe with WAL, fsyncs only at checkpoints
+    Normal,
+    Full,
+    Extra,
+}
+
+impl Synchronous {
+    fn as_str(self) -> &'static str {
+        match self {
+            Synchronous::Off => "OFF",
+            Synchronous::Normal => "NORMAL",
+            Synchronous::Full => "FULL",
+            Synchronous::Extra => "EXTRA",
+        }
+    }
+}
+
+/// Pragmas applied to every connection opened with [`open_with`].
+///
+/// The defaults suit multi-threaded applications: `journal_mode=WAL`,
+/// `synchronous=NORMAL`, a 5 second `busy_timeout` and `foreign_keys=ON`.
+/// Setting an option to `None` leaves the SQLite default in place.
+#[derive(Debug, Clone)]
+pub struct SqliteOptions {
+    journal_mode: Option<JournalMode>,
+    synchronous: Option<Synchronous>,
+    busy_timeout: Option<Duration>,
+    foreign_keys: Option<bool>,
+}
+
+impl Default for SqliteOptions {
+    fn default() -> Self {
+        Self {
+            journal_mode: Some(JournalMode::Wal),
+            synchronous: Some(Synchronous::Normal),
+            busy_timeout: Some(Duration::from_secs(5)),
+            foreign_keys: Some(true),
+        }
+    }
+}
+
+impl SqliteOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets `PRAGMA journal_mode`.
+    pub fn journal_mode(mut self, mode: impl Into<Option<JournalMode>>) -> Self {
+        self.journal_mode = mode.into();
+        self
+    }
+
+    /// Sets `PRAGMA synchronous`.
+    pub fn synchronous(mut self, level: impl Into<Option<Synchronous>>) -> Self {
+        self.synchronous = level.into();
+        self
+    }
+
+    /// Sets how long a statement waits for a lock before failing with `SQLITE_BUSY`.
+    pub fn busy_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.busy_timeout = timeout.into();
+        self
+    }
+
+    /// Sets `PRAGMA foreign_keys`.
+    pub fn foreign_keys(mut self, enabled: impl Into<Option<bool>>) -> Self {
+        self.foreign_keys = enabled.into();
+        self
+    }
+
+    /// Applies the options to an already open connection.
+    pub fn apply(&self, conn: &Connection) -> Result<(), Error> {
+        if let Some(timeout) = self.busy_timeout {
+            conn.busy_timeout(timeout)?;
+        }
+        if let Some(mode) = self.journal_mode {
+            conn.pragma_update(None, "journal_mode", mode.as_str())?;
+        }
+        if let Some(level) = self.synchronous {
+            conn.pragma_update(None, "synchronous", level.as_str())?;
+        }
+        if let Some(enabled) = self.foreign_keys {
+            conn.pragma_update(None, "foreign_keys", enabled)?;
+        }
+
+        trace::log("PARSQL-SQLITE", format_args!("Connection options: {:?}", self));
+        Ok(())
+    }
+}
+
+/// Opens a connection configured with [`SqliteOptions::default`].
+pub fn open(path: impl AsRef<Path>) -> Result<Connection, Error> {
+    open_with(path, &SqliteOptions::default())
+}
+
+/// Opens a connection and applies `options` to it.
+pub fn open_with(path: impl AsRef<Path>, options: &SqliteOptions) -> Result<Connection, Error> {
+    let conn = Connection::open(path)?;
+    options.apply(&conn)?;
+    Ok(conn)
+}
+
+/// Runs `operation`, retrying it while SQLite reports the database as busy or locked.
+///
+/// The operation is attempted at most `max_retries + 1` times; the wait starts at `delay`
+/// and doubles after every attempt. Any other error is returned immediately.
+///
+/// `CrudOps` methods take the entity by value, so clone it inside the closure:
+/// `retry_on_busy(3, delay, || conn.insert(user.clone()))`.
+pub fn retry_on_busy<T>(
+    max_retries: u32,
+    delay: Duration,
+    mut operation: impl FnMut() -> Result<T, Error>,
+) -> Result<T, Error> {
+    let mut attempt = 0;
+    let mut wait = delay;
+    loop {
+        match operation() {
+            Err(e) if attempt < max_retries && is_busy(&e) => {
+                attempt += 1;
+                trace::log("PARSQL-SQLITE", format_args!("Database busy, retry {} after {:?}", attempt, wait));
+                thread::sleep(wait);
+                wait = wait.saturating_mul(2);
+            }
+            result => return result,
+        }
+    }
+}
+
+fn is_busy(error: &Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy) | Some(ErrorCode::DatabaseLocked)
+    )
+}
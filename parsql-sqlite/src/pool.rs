@@ -0,0 +1,128 @@
+//! Connection pooling with [r2d2](https://docs.rs/r2d2).
+//!
+//! A single `Connection` cannot be shared between threads without a mutex. With the
+//! `pool` feature, [`SqliteConnectionManager`] opens connections configured by
+//! [`SqliteOptions`] and the pooled handles implement [`CrudOps`], so each request
+//! handler can check out its own connection:
+//!
+//! ```rust,no_run
+//! use parsql::sqlite::pool::{Pool, SqliteConnectionManager};
+//! use parsql::sqlite::traits::{CrudOps, FromRow, SqlParams, SqlQuery};
+//! use parsql::sqlite::macros::{FromRow, Queryable, SqlParams};
+//! use rusqlite::{types::ToSql, Error, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! pub struct GetUser {
+//!     pub id: i64,
+//!     pub name: String,
+//! }
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = Pool::builder()
+//!     .max_size(8)
+//!     .build(SqliteConnectionManager::file("app.db"))?;
+//!
+//! let conn = pool.get()?;
+//! let user = conn.fetch(&GetUser { id: 1, name: String::new() })?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{types::FromSql, Connection, Error, Row};
+
+use crate::options::{open_with, SqliteOptions};
+use crate::traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams};
+
+pub use r2d2;
+
+/// Pool of SQLite connections.
+pub type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// Connection checked out of a [`Pool`]; returned to the pool when dropped.
+pub type PooledConnection = r2d2::PooledConnection<SqliteConnectionManager>;
+
+/// r2d2 connection manager opening SQLite database files.
+#[derive(Debug, Clone)]
+pub struct SqliteConnectionManager {
+    path: PathBuf,
+    options: SqliteOptions,
+}
+
+impl SqliteConnectionManager {
+    /// Manages connections to the database file at `path`, configured with
+    /// [`SqliteOptions::default`] (WAL, busy timeout, foreign keys).
+    pub fn file(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            options: SqliteOptions::default(),
+        }
+    }
+
+    /// Replaces the options applied to every new connection.
+    pub fn with_options(mut self, options: SqliteOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl r2d2::ManageConnection for SqliteConnectionManager {
+    type Connection = Connection;
+    type Error = Error;
+
+    fn connect(&self) -> Result<Connection, Error> {
+        open_with(&self.path, &self.options)
+    }
+
+    fn is_valid(&self, conn: &mut Connection) -> Result<(), Error> {
+        conn.execute_batch("")
+    }
+
+    fn has_broken(&self, _conn: &mut Connection) -> bool {
+        false
+    }
+}
+
+// Havuzdan alınan bağlantı, işlemleri doğrudan Connection implementasyonuna devreder
+impl CrudOps for PooledConnection {
+    fn insert<T: SqlQuery + SqlParams, P: for<'a> FromSql + Send + Sync>(&self, entity: T) -> Result<P, Error> {
+        <Connection as CrudOps>::insert(self, entity)
+    }
+
+    fn insert_execute<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<usize, Error> {
+        <Connection as CrudOps>::insert_execute(self, entity)
+    }
+
+    fn update<T: SqlQuery + UpdateParams>(&self, entity: T) -> Result<u64, Error> {
+        <Connection as CrudOps>::update(self, entity)
+    }
+
+    fn delete<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<u64, Error> {
+        <Connection as CrudOps>::delete(self, entity)
+    }
+
+    fn fetch<T: SqlQuery + FromRow + SqlParams>(&self, entity: &T) -> Result<T, Error> {
+        <Connection as CrudOps>::fetch(self, entity)
+    }
+
+    fn fetch_all<T: SqlQuery + FromRow + SqlParams>(&self, entity: &T) -> Result<Vec<T>, Error> {
+        <Connection as CrudOps>::fetch_all(self, entity)
+    }
+
+    fn select<T: SqlQuery + SqlParams, F, R>(&self, entity: &T, to_model: F) -> Result<R, Error>
+    where
+        F: Fn(&Row) -> Result<R, Error>,
+    {
+        <Connection as CrudOps>::select(self, entity, to_model)
+    }
+
+    fn select_all<T: SqlQuery + SqlParams, F, R>(&self, entity: &T, to_model: F) -> Result<Vec<R>, Error>
+    where
+        F: Fn(&Row) -> Result<R, Error>,
+    {
+        <Connection as CrudOps>::select_all(self, entity, to_model)
+    }
+}
@@ -0,0 +1,79 @@
+//! Startup-time preparation of the registered statements.
+//!
+//! Structs marked with `#[register_query]` add their generated SQL to a process-wide
+//! registry. Calling [`prepare_all`] once at startup compiles each statement
+//! against the schema, so a wrong column or table name stops the application before
+//! it serves traffic:
+//!
+//! ```rust,no_run
+//! use parsql::sqlite::{macros::Queryable, registry, traits::SqlQuery};
+//! use rusqlite::Connection;
+//!
+//! #[derive(Queryable)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! #[register_query]
+//! pub struct GetUser {
+//!     pub id: i64,
+//! }
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let conn = Connection::open("app.db")?;
+//!     let prepared = registry::prepare_all(&conn)?;
+//!     println!("{} statements prepared", prepared);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The statements go through `prepare_cached`; raise the cache capacity with
+//! `Connection::set_prepared_statement_cache_capacity` when more statements are
+//! registered than the default of 16.
+
+use std::fmt;
+
+use parsql_core::trace;
+use rusqlite::{Connection, Error};
+
+pub use parsql_core::registry::{queries, QueryEntry};
+#[doc(hidden)]
+pub use parsql_core::registry::inventory;
+
+/// A registered statement SQLite rejected.
+#[derive(Debug)]
+pub struct PrepareError {
+    /// Path of the struct the statement belongs to
+    pub name: &'static str,
+    /// The rejected SQL
+    pub sql: &'static str,
+    /// Error returned by SQLite
+    pub source: Error,
+}
+
+impl fmt::Display for PrepareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to prepare {} ({}): {}", self.name, self.sql, self.source)
+    }
+}
+
+impl std::error::Error for PrepareError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Prepares every registered statement on `conn`, returning how many were prepared.
+///
+/// Stops at the first statement SQLite rejects.
+pub fn prepare_all(conn: &Connection) -> Result<usize, PrepareError> {
+    let entries = queries();
+    for entry in &entries {
+        trace::log_sql("PARSQL-SQLITE", entry.sql);
+
+        conn.prepare_cached(entry.sql).map_err(|source| PrepareError {
+            name: entry.name,
+            sql: entry.sql,
+            source,
+        })?;
+    }
+    Ok(entries.len())
+}
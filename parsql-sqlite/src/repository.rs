@@ -0,0 +1,79 @@
+//! Typed CRUD repositories.
+//!
+//! [`Repository`] runs the queries named by a `#[derive(Repository)]` struct over any
+//! `CrudOps` executor (a connection, a pooled connection or a transaction), so a layered
+//! application doesn't have to hand-write the same create/find/update/delete wrappers
+//! for every entity:
+//!
+//! ```rust,ignore
+//! #[derive(Repository)]
+//! #[repository(key = i64, insert = UserInsert, find = UserById, find_all = UsersByState, update = UserUpdate, delete = UserDelete)]
+//! pub struct Users;
+//!
+//! let users = Repository::<Users, _>::new(conn);
+//! let id: i64 = users.create(UserInsert::new("Ali", "ali@example.com", 1))?;
+//! let user = users.find(id)?;
+//! users.delete(id)?;
+//! ```
+
+use std::marker::PhantomData;
+
+use rusqlite::{types::FromSql, Error};
+
+use crate::traits::{CrudOps, RepositoryTypes};
+
+/// CRUD operations on the entity described by `R`, run on executor `E`.
+pub struct Repository<R, E> {
+    executor: E,
+    _types: PhantomData<fn() -> R>,
+}
+
+impl<R, E> Repository<R, E> {
+    /// Creates a repository running its queries on `executor`.
+    pub fn new(executor: E) -> Self {
+        Self {
+            executor,
+            _types: PhantomData,
+        }
+    }
+
+    /// The executor the queries run on.
+    pub fn executor(&self) -> &E {
+        &self.executor
+    }
+
+    /// Consumes the repository, returning the executor.
+    pub fn into_inner(self) -> E {
+        self.executor
+    }
+}
+
+impl<R: RepositoryTypes, E: CrudOps> Repository<R, E> {
+    /// Inserts a record, returning the value of its `#[returning]` column.
+    pub fn create<P>(&self, entity: R::Insert) -> Result<P, Error>
+    where
+        P: FromSql + Send + Sync,
+    {
+        self.executor.insert(entity)
+    }
+
+    /// Retrieves the record with the given key.
+    pub fn find(&self, key: R::Key) -> Result<R::Find, Error> {
+        self.executor.fetch(&R::Find::from(key))
+    }
+
+    /// Retrieves every record matching `query`.
+    pub fn find_all(&self, query: R::FindAll) -> Result<Vec<R::FindAll>, Error> {
+        self.executor.fetch_all(&query)
+    }
+
+    /// Updates a record, returning the number of affected rows.
+    pub fn update(&self, entity: R::Update) -> Result<u64, Error> {
+        self.executor.update(entity)
+    }
+
+    /// Deletes the record with the given key, returning the number of affected rows.
+    pub fn delete(&self, key: R::Key) -> Result<u64, Error> {
+        self.executor.delete(R::Delete::from(key))
+    }
+}
@@ -0,0 +1,42 @@
+//! Snapshot of the database schema for compile-time checking.
+//!
+//! [`dump`] reads the tables and columns of the database into a `schema.json` the
+//! derive macros check structs against when the `schema-check` feature is enabled. Run
+//! it after each migration and commit the file next to `Cargo.toml`:
+//!
+//! ```rust,no_run
+//! use parsql::sqlite::schema;
+//! use rusqlite::Connection;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let conn = Connection::open("app.db")?;
+//!     let schema = schema::dump(&conn)?;
+//!     std::fs::write("schema.json", schema.to_json())?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The column types are the declared ones; the macros compare them by SQLite's type
+//! affinity rules.
+
+use parsql_core::trace;
+use rusqlite::{Connection, Error};
+
+pub use parsql_core::schema::{Column, Schema};
+use parsql_core::schema::SQLITE_COLUMNS;
+
+/// Reads the columns of every table and view.
+pub fn dump(conn: &Connection) -> Result<Schema, Error> {
+    trace::log_sql("PARSQL-SQLITE", SQLITE_COLUMNS);
+
+    let mut schema = Schema::new();
+    let mut stmt = conn.prepare(SQLITE_COLUMNS)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let table: String = row.get(0)?;
+        let column: String = row.get(1)?;
+        let data_type: String = row.get(2)?;
+        schema.add_column(&table, &column, &data_type, row.get(3)?);
+    }
+    Ok(schema)
+}
@@ -1,17 +1,37 @@
-use rusqlite::{types::{FromSql, ToSql}, Error, Row};
+use rusqlite::{types::{FromSql, ToSql, ToSqlOutput, ValueRef}, Error, Row};
+use parsql_core::debug::quote_literal;
+use std::marker::PhantomData;
+use std::sync::Arc;
 
-/// Trait for generating SQL queries.
-/// This trait is implemented by the derive macro `Queryable`, `Insertable`, `Updateable`, and `Deletable`.
-pub trait SqlQuery {
-    /// Returns the SQL query string.
-    fn query() -> String;
-}
+pub use parsql_core::query::SqlQuery;
+pub use parsql_core::validation::{Validate, ValidationError};
 
 /// Trait for providing SQL parameters.
 /// This trait is implemented by the derive macro `SqlParams`.
 pub trait SqlParams {
     /// Returns a vector of references to SQL parameters.
     fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// Returns the references of `params()` as an iterator. The derive yields them from a
+    /// fixed-size array without allocating; with the `params-iter` feature the CRUD
+    /// functions bind parameters through it.
+    fn params_iter(&self) -> impl ExactSizeIterator<Item = &(dyn ToSql + Sync)> + Send + Sync
+    where
+        Self: Sized,
+    {
+        self.params().into_iter()
+    }
+
+    /// Returns the query with the parameter values written in place of the
+    /// placeholders, e.g. `SELECT ... WHERE name = 'John' AND state = 1`.
+    ///
+    /// Intended for logging failing queries only; never execute the result.
+    fn debug_sql(&self) -> String
+    where
+        Self: SqlQuery,
+    {
+        parsql_core::debug::debug_sql_with(Self::query(), self.params(), sql_literal)
+    }
 }
 
 /// Trait for providing UPDATE parameters.
@@ -19,6 +39,114 @@ pub trait SqlParams {
 pub trait UpdateParams {
     /// Returns a vector of references to SQL parameters for UPDATE operations.
     fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// Returns the query with the UPDATE parameter values written in place of the
+    /// placeholders, e.g. `UPDATE users SET name = 'John' WHERE id = 1`.
+    ///
+    /// Intended for logging failing queries only; never execute the result.
+    fn debug_sql(&self) -> String
+    where
+        Self: SqlQuery,
+    {
+        parsql_core::debug::debug_sql_with(Self::query(), self.params(), sql_literal)
+    }
+}
+
+// Forwarding impls, so operations also accept `&T` and `Arc<T>`: `client.insert(&user)`
+// leaves `user` usable afterwards without cloning it.
+impl<T: SqlParams + ?Sized> SqlParams for &T {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+impl<T: SqlParams + ?Sized> SqlParams for Arc<T> {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+impl<T: UpdateParams + ?Sized> UpdateParams for &T {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+impl<T: UpdateParams + ?Sized> UpdateParams for Arc<T> {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        (**self).params()
+    }
+}
+
+/// Renders a bound value as an SQL literal for `debug_sql`.
+fn sql_literal(value: &(dyn ToSql + Sync)) -> String {
+    let value = match value.to_sql() {
+        Ok(ToSqlOutput::Borrowed(value)) => value,
+        Ok(ToSqlOutput::Owned(ref value)) => return value_literal(value.into()),
+        Ok(_) => return "?".to_string(),
+        Err(e) => return format!("<{}>", e),
+    };
+    value_literal(value)
+}
+
+fn value_literal(value: ValueRef<'_>) -> String {
+    match value {
+        ValueRef::Null => "NULL".to_string(),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(text) => quote_literal(&String::from_utf8_lossy(text)),
+        ValueRef::Blob(blob) => {
+            let hex: String = blob.iter().map(|b| format!("{:02X}", b)).collect();
+            format!("X'{}'", hex)
+        }
+    }
+}
+
+/// Conversion of a field marked `#[sql_with(func)]` when it is bound.
+///
+/// The `SqlParams` and `UpdateParams` derives generate a type implementing this trait
+/// for each marked field; the function takes a reference to the field and returns a
+/// value implementing `ToSql` (e.g. a lowercased email). The generated code refers to
+/// `SqlWith`, so import it together with the other traits.
+pub trait SqlWith: Sized + 'static {
+    /// Type of the converted field
+    type Field: Sync;
+
+    /// Runs the conversion function.
+    fn convert(value: &Self::Field) -> Box<dyn ToSql + Sync>;
+
+    /// Returns the field as a parameter that is converted when the statement runs.
+    fn bind(value: &Self::Field) -> &(dyn ToSql + Sync) {
+        Converted::<Self>::from_field(value)
+    }
+}
+
+/// Wrapper laid out like the field itself, calling `C::convert` when it is bound.
+#[repr(transparent)]
+struct Converted<C: SqlWith> {
+    conversion: PhantomData<fn() -> C>,
+    field: C::Field,
+}
+
+impl<C: SqlWith> Converted<C> {
+    fn from_field(field: &C::Field) -> &Self {
+        // SAFETY: with `repr(transparent)` and a zero-sized `PhantomData`, `Converted<C>`
+        // has the layout of `C::Field`; the lifetime of the reference is kept.
+        unsafe { &*(field as *const C::Field as *const Self) }
+    }
+}
+
+impl<C: SqlWith> ToSql for Converted<C> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        // The converted value is dropped on return, so borrowed output is copied
+        match C::convert(&self.field).to_sql()? {
+            ToSqlOutput::Borrowed(value) => Ok(ToSqlOutput::Owned(value.into())),
+            ToSqlOutput::Owned(value) => Ok(ToSqlOutput::Owned(value)),
+            _ => Err(Error::ToSqlConversionFailure(
+                "sql_with conversions must produce a plain value".into(),
+            )),
+        }
+    }
 }
 
 /// Trait for converting database rows to Rust structs.
@@ -34,7 +162,173 @@ pub trait FromRow {
     fn from_row(row: &Row) -> Result<Self, Error>
     where
         Self: Sized;
-} 
+
+    /// Resolves the column position of each field once, from the column names of a
+    /// statement (`stmt.column_names()`).
+    ///
+    /// The `FromRow` derive returns one entry per field, `None` for a column the
+    /// statement doesn't have. The default returns an empty list.
+    fn column_positions(columns: &[&str]) -> Vec<Option<usize>> {
+        let _ = columns;
+        Vec::new()
+    }
+
+    /// Reads a row at the positions resolved by `column_positions`, falling back to the
+    /// column name where the position is unknown. The default calls `from_row`.
+    fn from_row_at(row: &Row, positions: &[Option<usize>]) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let _ = positions;
+        Self::from_row(row)
+    }
+}
+
+/// Trait for child records inserted after their parent by `persist_graph`.
+/// This trait is implemented by the derive macro `Insertable` when `#[parent_key("...")]` is given.
+pub trait ParentKey {
+    /// Type of the parent's key, read from `last_insert_rowid()`.
+    type Key;
+
+    /// Writes the parent's key into the child's foreign key field.
+    fn set_parent_key(&mut self, key: Self::Key);
+}
+
+/// Records read in bulk by their key with `fetch_many`.
+/// This trait is implemented by the derive macro `Queryable` when `#[primary_key("...")]` is given.
+pub trait Keyed {
+    /// Type of the key field.
+    type Key;
+
+    /// The query's select list and tables, `SELECT ... FROM ...`, without its conditions.
+    fn key_select() -> &'static str;
+
+    /// Column compared with the requested keys.
+    fn key_column() -> &'static str;
+
+    /// The record's key.
+    fn key(&self) -> &Self::Key;
+}
+
+/// One page of a query type, read with `LIMIT` / `OFFSET` appended at runtime.
+/// This trait is implemented by the `<Query>Page` type of the derive macro `Paginated`
+/// and used by `fetch_page`.
+pub trait Paginated {
+    /// The paged query type, which the rows are read into.
+    type Query: SqlQuery + SqlParams + FromRow;
+
+    /// The paged query.
+    fn query(&self) -> &Self::Query;
+
+    /// Maximum number of records in the page.
+    fn limit(&self) -> i64;
+
+    /// Number of records skipped before the page.
+    fn offset(&self) -> i64;
+}
+
+/// Partial update writing only the fields that are `Some`.
+/// This trait is implemented by the `<Model>Patch` type of the derive macro `Patch`
+/// and used by `update_patch`.
+pub trait Patch {
+    /// Type of the key identifying the updated record.
+    type Key: ToSql + Sync;
+
+    /// The updated table.
+    fn table() -> &'static str;
+
+    /// Column compared with the key in the WHERE clause.
+    fn key_column() -> &'static str;
+
+    /// Columns of the fields that are `Some`, in declaration order.
+    fn columns(&self) -> Vec<&'static str>;
+
+    /// Values of the fields that are `Some`, in the order of `columns()`.
+    fn values(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// The partial UPDATE statement, or `None` when no field is set.
+    fn patch_sql(&self) -> Option<String> {
+        parsql_core::patch::update_statement(Self::table(), Self::key_column(), &self.columns(), parsql_core::Dialect::Sqlite)
+    }
+}
+
+/// A filter `fetch_all_combined` AND-combines with other filters.
+/// Implemented for every `Queryable` with a single-table `#[where_clause]`, whose
+/// condition and parameters are taken from `SqlQuery::criteria` and `params()`.
+pub trait WhereFragment {
+    /// The condition, numbered from the first placeholder.
+    fn condition(&self) -> &'static str;
+
+    /// Values of the condition's placeholders, in placeholder order.
+    fn condition_params(&self) -> Vec<&(dyn ToSql + Sync)>;
+}
+
+impl<T: SqlQuery + SqlParams> WhereFragment for T {
+    fn condition(&self) -> &'static str {
+        T::criteria()
+            .unwrap_or_else(|| panic!("`{}` has no single-table WHERE clause to combine", std::any::type_name::<T>()))
+            .1
+    }
+
+    fn condition_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        let mut params = self.params();
+        params.truncate(parsql_core::criteria::where_param_count(self.condition()));
+        params
+    }
+}
+
+/// The filters passed to `fetch_all_combined` together, e.g. `(&by_state, &created_between)`.
+/// Implemented for tuples of up to six `WhereFragment`s.
+pub trait WhereFragments {
+    /// Conditions of the filters, in tuple order.
+    fn conditions(&self) -> Vec<&'static str>;
+
+    /// Parameters of the filters, in tuple order.
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+}
+
+macro_rules! where_fragments {
+    ($($filter:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($filter: WhereFragment),+> WhereFragments for ($($filter,)+) {
+            fn conditions(&self) -> Vec<&'static str> {
+                let ($($filter,)+) = self;
+                vec![$($filter.condition()),+]
+            }
+
+            fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+                let ($($filter,)+) = self;
+                let mut params = Vec::new();
+                $(params.extend($filter.condition_params());)+
+                params
+            }
+        }
+    };
+}
+
+where_fragments!(A);
+where_fragments!(A, B);
+where_fragments!(A, B, C);
+where_fragments!(A, B, C, D);
+where_fragments!(A, B, C, D, E);
+where_fragments!(A, B, C, D, E, F);
+
+/// Query types behind the CRUD operations of an entity.
+/// This trait is implemented by the derive macro `Repository` and used by `repository::Repository`.
+pub trait RepositoryTypes {
+    /// Type of the key identifying a record.
+    type Key;
+    /// INSERT query run by `create`.
+    type Insert: SqlQuery + SqlParams;
+    /// SELECT query run by `find`, built from a key.
+    type Find: SqlQuery + SqlParams + FromRow + From<Self::Key>;
+    /// SELECT query run by `find_all`.
+    type FindAll: SqlQuery + SqlParams + FromRow;
+    /// UPDATE query run by `update`.
+    type Update: SqlQuery + UpdateParams;
+    /// DELETE query run by `delete`, built from a key.
+    type Delete: SqlQuery + SqlParams + From<Self::Key>;
+}
 
 /// CrudOps trait defines the CRUD (Create, Read, Update, Delete) operations
 /// that can be performed on a SQLite database.
@@ -94,17 +388,28 @@ pub trait CrudOps {
     /// * `entity` - Data object to be inserted (must implement SqlQuery and SqlParams traits)
     /// 
     /// # Returns
-    /// * `Result<usize, Error>` - On success, returns the number of inserted records; on failure, returns Error
+    /// * `Result<P, Error>` - On success, returns the first column of the row produced by
+    ///   the entity's `#[returning]` clause; on failure, returns Error
     fn insert<T: SqlQuery + SqlParams, P: for<'a> FromSql + Send + Sync>(&self, entity: T) -> Result<P, Error>;
 
+    /// Inserts a new record and returns the number of inserted rows. Unlike `insert` it
+    /// needs neither a `#[returning]` clause nor a result type.
+    /// 
+    /// # Arguments
+    /// * `entity` - Data object to be inserted (must implement SqlQuery and SqlParams traits)
+    /// 
+    /// # Returns
+    /// * `Result<usize, Error>` - On success, returns the number of inserted records; on failure, returns Error
+    fn insert_execute<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<usize, Error>;
+
     /// Updates records in the SQLite database.
     /// 
     /// # Arguments
     /// * `entity` - Data object containing the update information (must implement SqlQuery and UpdateParams traits)
     /// 
     /// # Returns
-    /// * `Result<usize, Error>` - On success, returns the number of updated records; on failure, returns Error
-    fn update<T: SqlQuery + UpdateParams>(&self, entity: T) -> Result<usize, Error>;
+    /// * `Result<u64, Error>` - On success, returns the number of updated records; on failure, returns Error
+    fn update<T: SqlQuery + UpdateParams>(&self, entity: T) -> Result<u64, Error>;
 
     /// Deletes records from the SQLite database.
     /// 
@@ -112,8 +417,8 @@ pub trait CrudOps {
     /// * `entity` - Data object containing delete conditions (must implement SqlQuery and SqlParams traits)
     /// 
     /// # Returns
-    /// * `Result<usize, Error>` - On success, returns the number of deleted records; on failure, returns Error
-    fn delete<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<usize, Error>;
+    /// * `Result<u64, Error>` - On success, returns the number of deleted records; on failure, returns Error
+    fn delete<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<u64, Error>;
 
     /// Retrieves a single record from the SQLite database.
     /// 
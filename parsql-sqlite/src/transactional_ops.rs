@@ -3,7 +3,9 @@
 //! This module provides functions for performing CRUD operations within a transaction.
 
 use rusqlite::{types::FromSql, Connection, Error, ToSql, Transaction};
-use crate::traits::{SqlParams, SqlQuery, UpdateParams, FromRow, CrudOps};
+use crate::traits::{SqlParams, SqlQuery, UpdateParams, FromRow, CrudOps, ParentKey};
+use crate::crud_ops::validate;
+use parsql_core::{metrics::observe, temp::create_temp_as, trace, Dialect, OnError, Progress};
 
 /// Implementation of CrudOps for Transaction
 impl<'conn> CrudOps for Transaction<'conn> {
@@ -46,16 +48,37 @@ impl<'conn> CrudOps for Transaction<'conn> {
     /// }
     /// ```
     fn insert<T: SqlQuery + SqlParams, P: for<'a> FromSql + Send + Sync>(&self, entity: T) -> Result<P, Error> {
+        validate(&entity)?;
+
         let sql = T::query();
         
-        // Debug log the SQL query
-        #[cfg(debug_assertions)]
-        println!("[SQL] {}", sql);
+        trace::log_sql("PARSQL-SQLITE-TX", sql);
         
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        self.query_row(&sql, param_refs.as_slice(), |row| row.get(0))
+        observe("insert", T::table_name(), || self.query_row(sql, param_refs.as_slice(), |row| row.get(0)))
+    }
+
+    /// Inserts a record within the transaction and returns the number of rows affected,
+    /// without reading a `RETURNING` column.
+    ///
+    /// # Arguments
+    /// * `entity` - A struct that implements Insertable and SqlParams traits
+    ///
+    /// # Returns
+    /// * `Result<usize, Error>` - Number of affected rows or an error
+    fn insert_execute<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<usize, Error> {
+        validate(&entity)?;
+
+        let sql = T::query();
+        
+        trace::log_sql("PARSQL-SQLITE-TX", sql);
+        
+        let params = entity.params();
+        let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+        
+        observe("insert", T::table_name(), || self.execute(sql, param_refs.as_slice()))
     }
 
     /// Updates a record in the database and returns the number of rows affected.
@@ -100,17 +123,17 @@ impl<'conn> CrudOps for Transaction<'conn> {
     ///     Ok(())
     /// }
     /// ```
-    fn update<T: SqlQuery + UpdateParams>(&self, entity: T) -> Result<usize, Error> {
+    fn update<T: SqlQuery + UpdateParams>(&self, entity: T) -> Result<u64, Error> {
+        validate(&entity)?;
+
         let sql = T::query();
         
-        // Debug log the SQL query
-        #[cfg(debug_assertions)]
-        println!("[SQL] {}", sql);
+        trace::log_sql("PARSQL-SQLITE-TX", sql);
         
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        self.execute(&sql, param_refs.as_slice())
+        observe("update", T::table_name(), || self.execute(sql, param_refs.as_slice()).map(|rows| rows as u64))
     }
 
     /// Deletes a record from the database and returns the number of rows affected.
@@ -148,17 +171,15 @@ impl<'conn> CrudOps for Transaction<'conn> {
     ///     Ok(())
     /// }
     /// ```
-    fn delete<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<usize, Error> {
+    fn delete<T: SqlQuery + SqlParams>(&self, entity: T) -> Result<u64, Error> {
         let sql = T::query();
         
-        // Debug log the SQL query
-        #[cfg(debug_assertions)]
-        println!("[SQL] {}", sql);
+        trace::log_sql("PARSQL-SQLITE-TX", sql);
         
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        self.execute(&sql, param_refs.as_slice())
+        observe("delete", T::table_name(), || self.execute(sql, param_refs.as_slice()).map(|rows| rows as u64))
     }
 
     /// Retrieves a single record from the database and converts it to a struct.
@@ -204,24 +225,24 @@ impl<'conn> CrudOps for Transaction<'conn> {
     /// }
     /// ```
     fn fetch<T: SqlQuery + FromRow + SqlParams>(&self, entity: &T) -> Result<T, Error> {
-        let sql = T::query();
+        let sql = T::query_single();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-SQLITE] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
         
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        let mut stmt = self.prepare(&sql)?;
-        let mut rows = stmt.query(param_refs.as_slice())?;
+        observe("fetch", T::table_name(), || {
+            let mut stmt = self.prepare(sql)?;
+            let mut rows = stmt.query(param_refs.as_slice())?;
         
-        if let Some(row) = rows.next()? {
-            let result = T::from_row(row)?;
-            Ok(result)
-        } else {
-            Err(Error::QueryReturnedNoRows)
-        }
+            if let Some(row) = rows.next()? {
+                let result = T::from_row(row)?;
+                Ok(result)
+            } else {
+                Err(Error::QueryReturnedNoRows)
+            }
+        })
     }
 
     /// Retrieves multiple records from the database and converts them to a vector of structs.
@@ -269,22 +290,23 @@ impl<'conn> CrudOps for Transaction<'conn> {
     fn fetch_all<T: SqlQuery + FromRow + SqlParams>(&self, entity: &T) -> Result<Vec<T>, Error> {
         let sql = T::query();
         
-        if std::env::var("PARSQL_TRACE").unwrap_or_default() == "1" {
-            println!("[PARSQL-SQLITE] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-SQLITE", sql);
         
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        let mut stmt = self.prepare(&sql)?;
-        let rows = stmt.query_map(param_refs.as_slice(), |row| T::from_row(row))?;
+        observe("fetch_all", T::table_name(), || {
+            let mut stmt = self.prepare(sql)?;
+            let positions = T::column_positions(&stmt.column_names());
+            let rows = stmt.query_map(param_refs.as_slice(), |row| T::from_row_at(row, &positions))?;
         
-        let mut results = Vec::new();
-        for row_result in rows {
-            results.push(row_result?);
-        }
+            let mut results = Vec::new();
+            for row_result in rows {
+                results.push(row_result?);
+            }
         
-        Ok(results)
+            Ok(results)
+        })
     }
 
     /// Executes a custom SELECT query and transforms the result using a provided function.
@@ -330,17 +352,17 @@ impl<'conn> CrudOps for Transaction<'conn> {
     where
         F: Fn(&rusqlite::Row) -> Result<R, Error>,
     {
-        let sql = T::query();
+        let sql = T::query_single();
         
-        // Debug log the SQL query
-        #[cfg(debug_assertions)]
-        println!("[SQL] {}", sql);
+        trace::log_sql("PARSQL-SQLITE-TX", sql);
         
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        let mut stmt = self.prepare(&sql)?;
-        stmt.query_row(param_refs.as_slice(), to_model)
+        observe("select", T::table_name(), || {
+            let mut stmt = self.prepare(sql)?;
+            stmt.query_row(param_refs.as_slice(), to_model)
+        })
     }
 
     /// Executes a custom SELECT query and transforms all results using a provided function.
@@ -390,22 +412,22 @@ impl<'conn> CrudOps for Transaction<'conn> {
     {
         let sql = T::query();
         
-        // Debug log the SQL query
-        #[cfg(debug_assertions)]
-        println!("[SQL] {}", sql);
+        trace::log_sql("PARSQL-SQLITE-TX", sql);
         
         let params = entity.params();
         let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
         
-        let mut stmt = self.prepare(&sql)?;
-        let rows = stmt.query_map(param_refs.as_slice(), to_model)?;
+        observe("select_all", T::table_name(), || {
+            let mut stmt = self.prepare(sql)?;
+            let rows = stmt.query_map(param_refs.as_slice(), to_model)?;
         
-        let mut results = Vec::new();
-        for row_result in rows {
-            results.push(row_result?);
-        }
+            let mut results = Vec::new();
+            for row_result in rows {
+                results.push(row_result?);
+            }
         
-        Ok(results)
+            Ok(results)
+        })
     }
 }
 
@@ -434,6 +456,44 @@ pub fn begin(conn: &Connection) -> Result<Transaction<'_>, Error> {
     conn.unchecked_transaction()
 }
 
+/// Rolls a transaction back explicitly.
+///
+/// A dropped transaction is rolled back as well, but any error of the ROLLBACK is
+/// ignored; error paths can call this to roll back visibly instead.
+///
+/// # Arguments
+/// * `tx` - Transaction to roll back
+///
+/// # Returns
+/// * `Result<(), Error>` - Error when the ROLLBACK fails
+///
+/// # Example
+/// ```rust,no_run
+/// use rusqlite::{Connection, Result};
+/// use parsql_sqlite::transactional;
+///
+/// fn main() -> Result<()> {
+///     let conn = Connection::open("test.db")?;
+///     let tx = transactional::begin(&conn)?;
+///     if let Err(e) = tx.execute("UPDATE users SET state = 0", []) {
+///         transactional::rollback(tx)?;
+///         return Err(e);
+///     }
+///     tx.commit()
+/// }
+/// ```
+pub fn rollback(tx: Transaction<'_>) -> Result<(), Error> {
+    trace::log_sql("PARSQL-SQLITE-TX", "ROLLBACK");
+    tx.rollback()
+}
+
+/// Returns `true` while a transaction is open on `conn`, whether it was started by
+/// [`begin`] or by a `BEGIN` statement. A failed statement doesn't end a SQLite
+/// transaction, so it stays open until committed or rolled back.
+pub fn in_transaction(conn: &Connection) -> bool {
+    !conn.is_autocommit()
+}
+
 /// Inserts a record into the database within a transaction.
 ///
 /// # Arguments
@@ -479,6 +539,76 @@ pub fn tx_insert<'a, T: SqlQuery + SqlParams, P: for<'b> FromSql + Send + Sync>(
     Ok((tx, result))
 }
 
+/// Inserts several records within a transaction, reporting progress after each one.
+///
+/// A failing statement only undoes its own changes in SQLite, so the transaction stays
+/// usable either way: `OnError::Stop` ends the batch at the first failing record,
+/// `OnError::Continue` records the error and goes on.
+///
+/// # Arguments
+/// * `tx` - Transaction
+/// * `entities` - Structs that implement Insertable and SqlParams traits
+/// * `on_error` - Whether to stop at the first failing record
+/// * `on_progress` - Called after every record
+///
+/// # Returns
+/// * `Result<(Transaction<'_>, Vec<Result<P, Error>>), Error>` - Transaction and the result of every attempted record
+///
+/// # Example
+/// ```rust,no_run
+/// use rusqlite::{Connection, Result};
+/// use parsql::sqlite::{transactional, OnError};
+/// use parsql::macros::{Insertable, SqlParams};
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// #[returning("id")]
+/// struct InsertUser {
+///     name: String,
+///     email: String,
+/// }
+///
+/// fn main() -> Result<()> {
+///     let conn = Connection::open("test.db")?;
+///     let tx = transactional::begin(&conn)?;
+///
+///     let users = vec![
+///         InsertUser { name: "John".to_string(), email: "john@example.com".to_string() },
+///         InsertUser { name: "Jane".to_string(), email: "jane@example.com".to_string() },
+///     ];
+///
+///     let (tx, results) = transactional::tx_insert_all::<_, i64>(tx, users, OnError::Continue, |progress| {
+///         println!("{}/{} ({} failed)", progress.done, progress.total, progress.failed);
+///     })?;
+///
+///     tx.commit()?;
+///     Ok(())
+/// }
+/// ```
+pub fn tx_insert_all<'a, T: SqlQuery + SqlParams, P: for<'b> FromSql + Send + Sync>(
+    tx: Transaction<'a>,
+    entities: Vec<T>,
+    on_error: OnError,
+    mut on_progress: impl FnMut(Progress),
+) -> Result<(Transaction<'a>, Vec<Result<P, Error>>), Error> {
+    let total = entities.len();
+    let mut results = Vec::with_capacity(total);
+    let mut failed = 0;
+
+    for entity in entities {
+        let result = tx.insert::<T, P>(entity);
+        let stop = result.is_err() && on_error == OnError::Stop;
+        failed += result.is_err() as usize;
+        results.push(result);
+        on_progress(Progress { done: results.len(), total, failed });
+        if stop {
+            break;
+        }
+    }
+
+    Ok((tx, results))
+}
+
 /// Updates a record in the database within a transaction.
 ///
 /// # Arguments
@@ -486,7 +616,7 @@ pub fn tx_insert<'a, T: SqlQuery + SqlParams, P: for<'b> FromSql + Send + Sync>(
 /// * `entity` - A struct that implements Updateable and UpdateParams traits
 ///
 /// # Returns
-/// * `Result<(Transaction<'_>, usize), Error>` - Transaction and number of affected rows or an error
+/// * `Result<(Transaction<'_>, u64), Error>` - Transaction and number of affected rows or an error
 ///
 /// # Example
 /// ```rust,no_run
@@ -523,7 +653,7 @@ pub fn tx_insert<'a, T: SqlQuery + SqlParams, P: for<'b> FromSql + Send + Sync>(
 pub fn tx_update<'a, T: SqlQuery + UpdateParams>(
     tx: Transaction<'a>,
     entity: T,
-) -> Result<(Transaction<'a>, usize), Error> {
+) -> Result<(Transaction<'a>, u64), Error> {
     let result = tx.update(entity)?;
     Ok((tx, result))
 }
@@ -535,7 +665,7 @@ pub fn tx_update<'a, T: SqlQuery + UpdateParams>(
 /// * `entity` - A struct that implements Deletable and SqlParams traits
 ///
 /// # Returns
-/// * `Result<(Transaction<'_>, usize), Error>` - Transaction and number of affected rows or an error
+/// * `Result<(Transaction<'_>, u64), Error>` - Transaction and number of affected rows or an error
 ///
 /// # Example
 /// ```rust,no_run
@@ -565,7 +695,7 @@ pub fn tx_update<'a, T: SqlQuery + UpdateParams>(
 pub fn tx_delete<'a, T: SqlQuery + SqlParams>(
     tx: Transaction<'a>,
     entity: T,
-) -> Result<(Transaction<'a>, usize), Error> {
+) -> Result<(Transaction<'a>, u64), Error> {
     let result = tx.delete(entity)?;
     Ok((tx, result))
 }
@@ -828,4 +958,114 @@ where
 {
     let results = tx.select_all(entity, to_model)?;
     Ok((tx, results))
-} 
\ No newline at end of file
+} 
+
+/// Fills a temporary table with the rows of a query.
+///
+/// SQLite keeps temporary tables until the connection closes, so drop the table
+/// (`DROP TABLE temp.tmp_active`) when the job is done if the connection is reused.
+/// Later statements reach it by name, in this transaction or after it.
+///
+/// # Arguments
+/// * `tx` - Transaction
+/// * `entity` - Query filling the table (must implement SqlQuery and SqlParams traits)
+/// * `name` - Name of the temporary table
+///
+/// # Returns
+/// * `Result<usize, Error>` - Number of rows written to the table or an error
+///
+/// # Panics
+/// If `name` is not a plain table name.
+///
+/// # Example
+/// ```rust,ignore
+/// let tx = transactional::begin(&conn)?;
+/// transactional::create_temp_from(&tx, &ActiveUsers { state: 1 }, "tmp_active")?;
+/// tx.execute("UPDATE orders SET priority = 1 WHERE user_id IN (SELECT id FROM tmp_active)", [])?;
+/// tx.commit()?;
+/// ```
+pub fn create_temp_from<T: SqlQuery + SqlParams>(tx: &Transaction<'_>, entity: &T, name: &str) -> Result<usize, Error> {
+    let sql = create_temp_as(T::query(), name, Dialect::Sqlite)
+        .unwrap_or_else(|| panic!("create_temp_from: `{}` is not a valid temporary table name", name));
+    trace::log_sql("PARSQL-SQLITE-TX", &sql);
+    let params = entity.params();
+    let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+    tx.execute(&sql, param_refs.as_slice())
+}
+
+/// Inserts a parent record and its children atomically.
+///
+/// The parent is inserted first and its `last_insert_rowid()` is written into the
+/// `#[parent_key("...")]` field of every child before it is inserted. Everything runs
+/// in one transaction, which is rolled back if any insert fails. Because the key comes
+/// from `last_insert_rowid()`, the parent doesn't need a `#[returning]` attribute.
+///
+/// # Arguments
+/// * `conn` - SQLite connection
+/// * `parent` - Parent record
+/// * `children` - Child records (must have a `#[parent_key("...")]` attribute)
+///
+/// # Returns
+/// * `Result<C::Key, Error>` - The parent's key or an error
+///
+/// # Example
+/// ```rust,no_run
+/// use rusqlite::{Connection, Result};
+/// use parsql::sqlite::transactional;
+/// use parsql::macros::{Insertable, SqlParams};
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// struct InsertUser {
+///     name: String,
+/// }
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("posts")]
+/// #[parent_key("user_id")]
+/// struct InsertPost {
+///     user_id: i64,
+///     content: String,
+/// }
+///
+/// fn main() -> Result<()> {
+///     let conn = Connection::open("test.db")?;
+///
+///     let user_id = transactional::persist_graph(
+///         &conn,
+///         InsertUser { name: "John".to_string() },
+///         vec![
+///             InsertPost { user_id: 0, content: "Hello".to_string() },
+///             InsertPost { user_id: 0, content: "World".to_string() },
+///         ],
+///     )?;
+///
+///     println!("Inserted user {}", user_id);
+///     Ok(())
+/// }
+/// ```
+pub fn persist_graph<P, C>(conn: &Connection, parent: P, children: Vec<C>) -> Result<C::Key, Error>
+where
+    P: SqlQuery + SqlParams,
+    C: SqlQuery + SqlParams + ParentKey,
+    C::Key: FromSql + Clone,
+{
+    let tx = begin(conn)?;
+    execute_params(&tx, P::query(), &parent.params())?;
+    let key: C::Key = tx.query_row("SELECT last_insert_rowid()", [], |row| row.get(0))?;
+
+    for mut child in children {
+        child.set_parent_key(key.clone());
+        execute_params(&tx, C::query(), &child.params())?;
+    }
+
+    tx.commit()?;
+    Ok(key)
+}
+
+fn execute_params(tx: &Transaction<'_>, sql: &str, params: &[&(dyn ToSql + Sync)]) -> Result<usize, Error> {
+    trace::log_sql("PARSQL-SQLITE-TX", sql);
+
+    let param_refs: Vec<&dyn ToSql> = params.iter().map(|p| *p as &dyn ToSql).collect();
+    tx.execute(sql, param_refs.as_slice())
+}
@@ -0,0 +1,82 @@
+//! Test databases for the synchronous `postgres` backend.
+//!
+//! Works like [`crate::TestDatabase`] without an async runtime, so it can be used from
+//! plain `#[test]` functions:
+//!
+//! ```rust,no_run
+//! use parsql_testing::blocking::TestDatabase;
+//!
+//! #[test]
+//! fn counts_users() -> Result<(), Box<dyn std::error::Error>> {
+//!     let db = TestDatabase::start(|client| client.batch_execute("CREATE TABLE users (id BIGSERIAL PRIMARY KEY)"))?;
+//!     let mut client = db.client()?;
+//!     let count: i64 = client.query_one("SELECT count(*) FROM users", &[])?.get(0);
+//!     assert_eq!(count, 0);
+//!     Ok(())
+//! }
+//! ```
+
+use postgres::{Client, Config, NoTls};
+use testcontainers::runners::SyncRunner;
+use testcontainers::{Container, ImageExt};
+use testcontainers_modules::postgres::Postgres;
+
+use crate::{isolated, postgres_tag, server_url, Error};
+
+/// A PostgreSQL schema set up for one test.
+///
+/// The container, if one was started, is removed when the value is dropped.
+pub struct TestDatabase {
+    config: Config,
+    schema: String,
+    _container: Option<Container<Postgres>>,
+}
+
+impl TestDatabase {
+    /// Starts the database and runs `setup` on it, with the new schema as the search path.
+    pub fn start<F>(setup: F) -> Result<Self, Error>
+    where
+        F: FnOnce(&mut Client) -> Result<(), postgres::Error>,
+    {
+        let (config, container) = match server_url() {
+            Some(url) => (url.parse::<tokio_postgres::Config>()?, None),
+            None => {
+                let container = Postgres::default().with_tag(postgres_tag()).start()?;
+                let mut config = tokio_postgres::Config::new();
+                config
+                    .host(container.get_host()?.to_string())
+                    .port(container.get_host_port_ipv4(5432)?)
+                    .user("postgres")
+                    .password("postgres")
+                    .dbname("postgres");
+                (config, Some(container))
+            }
+        };
+
+        let (config, schema) = isolated(config);
+        let database = Self {
+            config: config.into(),
+            schema,
+            _container: container,
+        };
+        let mut client = database.client()?;
+        client.batch_execute(&format!("CREATE SCHEMA {}", database.schema))?;
+        setup(&mut client)?;
+        Ok(database)
+    }
+
+    /// Connection settings of the database, with the schema as the search path.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Name of the schema the database lives in.
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Opens a connection to the database.
+    pub fn client(&self) -> Result<Client, Error> {
+        Ok(self.config.connect(NoTls)?)
+    }
+}
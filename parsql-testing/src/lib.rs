@@ -0,0 +1,212 @@
+//! # parsql-testing
+//!
+//! Disposable databases for tests of code written with parsql.
+//! [`TestDatabase::start`] starts PostgreSQL in a Docker container through
+//! [testcontainers](https://docs.rs/testcontainers), creates a schema of its own, runs
+//! the given setup on it and hands out connections of each backend that only see that
+//! schema:
+//!
+//! ```rust,no_run
+//! use parsql_testing::TestDatabase;
+//! use parsql_tokio_postgres::{
+//!     macros::{Insertable, SqlParams},
+//!     traits::{CrudOps, SqlParams, SqlQuery},
+//! };
+//! use tokio_postgres::types::ToSql;
+//!
+//! #[derive(Insertable, SqlParams)]
+//! #[table("users")]
+//! #[returning("id")]
+//! pub struct InsertUser {
+//!     pub name: String,
+//! }
+//!
+//! #[tokio::test]
+//! async fn registers_a_user() -> Result<(), Box<dyn std::error::Error>> {
+//!     let db = TestDatabase::start(|client| Box::pin(client.batch_execute("CREATE TABLE users (id BIGSERIAL PRIMARY KEY, name TEXT)"))).await?;
+//!     let client = db.tokio_postgres().await?;
+//!     let id: i64 = client.insert(InsertUser { name: "John".into() }).await?;
+//!     assert_eq!(id, 1);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! When `PARSQL_TEST_DATABASE_URL` is set, no container is started: the database lives
+//! in a new schema of that server instead, so CI jobs with a PostgreSQL service but no
+//! Docker run the same tests. Each database gets its own schema either way, so tests
+//! can run in parallel.
+//!
+//! ## Features
+//!
+//! - `postgres`: [`blocking::TestDatabase`] for tests of the synchronous backend
+//! - `deadpool-postgres`: [`TestDatabase::pool`]
+//! - `sqlite`: [`sqlite::open`], an in-memory SQLite database set up the same way
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, ImageExt, TestcontainersError};
+use testcontainers_modules::postgres::Postgres;
+use tokio_postgres::{Client, Config, NoTls};
+
+#[cfg(feature = "postgres")]
+pub mod blocking;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// Environment variable holding the connection string of a server to use instead of a container.
+pub const DATABASE_URL: &str = "PARSQL_TEST_DATABASE_URL";
+
+/// Environment variable overriding the tag of the `postgres` image, e.g. `13-alpine`.
+pub const POSTGRES_TAG: &str = "PARSQL_TEST_POSTGRES_TAG";
+
+/// Tag of the `postgres` image started when `PARSQL_TEST_POSTGRES_TAG` is not set.
+pub const DEFAULT_POSTGRES_TAG: &str = "16-alpine";
+
+/// Future returned by the setup closure of [`TestDatabase::start`], usually
+/// `Box::pin(client.batch_execute(SCHEMA))`.
+pub type SetupFuture<'c> = Pin<Box<dyn Future<Output = Result<(), tokio_postgres::Error>> + Send + 'c>>;
+
+/// A PostgreSQL schema set up for one test.
+///
+/// The container, if one was started, is removed when the value is dropped. Schemas
+/// created on a `PARSQL_TEST_DATABASE_URL` server are left in place for inspection.
+pub struct TestDatabase {
+    config: Config,
+    schema: String,
+    _container: Option<ContainerAsync<Postgres>>,
+}
+
+impl TestDatabase {
+    /// Starts the database and runs `setup` on it, with the new schema as the search path.
+    pub async fn start<F>(setup: F) -> Result<Self, Error>
+    where
+        F: for<'c> FnOnce(&'c Client) -> SetupFuture<'c>,
+    {
+        let (config, container) = match server_url() {
+            Some(url) => (url.parse::<Config>()?, None),
+            None => {
+                let container = Postgres::default().with_tag(postgres_tag()).start().await?;
+                let mut config = Config::new();
+                config
+                    .host(container.get_host().await?.to_string())
+                    .port(container.get_host_port_ipv4(5432).await?)
+                    .user("postgres")
+                    .password("postgres")
+                    .dbname("postgres");
+                (config, Some(container))
+            }
+        };
+
+        let (config, schema) = isolated(config);
+        let database = Self {
+            config,
+            schema,
+            _container: container,
+        };
+        let client = database.tokio_postgres().await?;
+        client.batch_execute(&format!("CREATE SCHEMA {}", database.schema)).await?;
+        setup(&client).await?;
+        Ok(database)
+    }
+
+    /// Connection settings of the database, with the schema as the search path.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Name of the schema the database lives in.
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Opens a `tokio-postgres` connection, driven by a spawned task.
+    pub async fn tokio_postgres(&self) -> Result<Client, Error> {
+        let (client, connection) = self.config.connect(NoTls).await?;
+        tokio::spawn(async move {
+            // Bağlantı hatası istemcinin bir sonraki sorgusunda da görülür
+            let _ = connection.await;
+        });
+        Ok(client)
+    }
+
+    /// Builds a `deadpool-postgres` pool of connections to the database.
+    #[cfg(feature = "deadpool-postgres")]
+    pub fn pool(&self) -> Result<deadpool_postgres::Pool, Error> {
+        let manager = deadpool_postgres::Manager::from_config(
+            self.config.clone(),
+            NoTls,
+            deadpool_postgres::ManagerConfig::default(),
+        );
+        deadpool_postgres::Pool::builder(manager)
+            .build()
+            .map_err(Error::Pool)
+    }
+}
+
+/// Errors of starting a test database.
+#[derive(Debug)]
+pub enum Error {
+    /// The container could not be started (is Docker running?)
+    Container(TestcontainersError),
+    /// Connecting or running the setup failed
+    Postgres(tokio_postgres::Error),
+    /// The connection pool could not be built
+    #[cfg(feature = "deadpool-postgres")]
+    Pool(deadpool_postgres::BuildError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Container(e) => write!(f, "cannot start the PostgreSQL container: {}", e),
+            Error::Postgres(e) => write!(f, "{}", e),
+            #[cfg(feature = "deadpool-postgres")]
+            Error::Pool(e) => write!(f, "cannot build the connection pool: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Container(e) => Some(e),
+            Error::Postgres(e) => Some(e),
+            #[cfg(feature = "deadpool-postgres")]
+            Error::Pool(e) => Some(e),
+        }
+    }
+}
+
+impl From<TestcontainersError> for Error {
+    fn from(e: TestcontainersError) -> Self {
+        Error::Container(e)
+    }
+}
+
+impl From<tokio_postgres::Error> for Error {
+    fn from(e: tokio_postgres::Error) -> Self {
+        Error::Postgres(e)
+    }
+}
+
+/// The server named by `PARSQL_TEST_DATABASE_URL`, if any.
+pub(crate) fn server_url() -> Option<String> {
+    std::env::var(DATABASE_URL).ok().filter(|url| !url.trim().is_empty())
+}
+
+pub(crate) fn postgres_tag() -> String {
+    std::env::var(POSTGRES_TAG).unwrap_or_else(|_| DEFAULT_POSTGRES_TAG.to_string())
+}
+
+/// Picks a schema name unique to this process and test and makes it the only schema on
+/// the search path of `config`.
+pub(crate) fn isolated(mut config: Config) -> (Config, String) {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let schema = format!("parsql_test_{}_{}", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed));
+    config.options(format!("-c search_path={}", schema));
+    (config, schema)
+}
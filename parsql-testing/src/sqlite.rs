@@ -0,0 +1,22 @@
+//! In-memory SQLite databases, set up like the PostgreSQL ones.
+//!
+//! Nothing has to be started for SQLite; [`open`] gives the SQLite tests of a crate the
+//! same one-call setup as [`crate::TestDatabase`]:
+//!
+//! ```rust
+//! let conn = parsql_testing::sqlite::open(|conn| conn.execute_batch("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)"))?;
+//! conn.execute("INSERT INTO users (name) VALUES ('John')", [])?;
+//! # Ok::<(), rusqlite::Error>(())
+//! ```
+
+use rusqlite::{Connection, Error};
+
+/// Opens an in-memory database and runs `setup` on it.
+pub fn open<F>(setup: F) -> Result<Connection, Error>
+where
+    F: FnOnce(&Connection) -> Result<(), Error>,
+{
+    let conn = Connection::open_in_memory()?;
+    setup(&conn)?;
+    Ok(conn)
+}
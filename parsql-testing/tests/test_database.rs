@@ -0,0 +1,91 @@
+//! Tests of the test databases.
+//!
+//! Docker is not assumed, so the PostgreSQL tests run only when
+//! `PARSQL_TEST_DATABASE_URL` points at a server.
+
+use parsql_testing::{TestDatabase, DATABASE_URL};
+use parsql_tokio_postgres::{
+    macros::{FromRow, Insertable, Queryable, SqlParams},
+    traits::{CrudOps, FromRow, SqlParams, SqlQuery},
+};
+use tokio_postgres::{types::ToSql, Error, Row};
+
+const SCHEMA: &str = "CREATE TABLE users (id BIGSERIAL PRIMARY KEY, name TEXT NOT NULL)";
+
+#[derive(Insertable, SqlParams)]
+#[table("users")]
+#[returning("id")]
+pub struct InsertUser {
+    pub name: String,
+}
+
+#[derive(Queryable, FromRow, SqlParams)]
+#[table("users")]
+#[where_clause("id = $")]
+pub struct GetUser {
+    pub id: i64,
+    pub name: String,
+}
+
+fn enabled() -> bool {
+    if std::env::var(DATABASE_URL).is_ok_and(|url| !url.trim().is_empty()) {
+        return true;
+    }
+    eprintln!("skipped: set {} to run the parsql-testing tests", DATABASE_URL);
+    false
+}
+
+#[tokio::test]
+async fn tokio_postgres_in_own_schema() {
+    if !enabled() {
+        return;
+    }
+    let first = TestDatabase::start(|client| Box::pin(client.batch_execute(SCHEMA))).await.unwrap();
+    let second = TestDatabase::start(|client| Box::pin(client.batch_execute(SCHEMA))).await.unwrap();
+    assert_ne!(first.schema(), second.schema());
+
+    let client = first.tokio_postgres().await.unwrap();
+    let id: i64 = client.insert(InsertUser { name: "John".into() }).await.unwrap();
+    let user = client.fetch(GetUser { id, name: String::new() }).await.unwrap();
+    assert_eq!(user.name, "John");
+
+    // İkinci veritabanının tablosu boş kalır
+    let other = second.tokio_postgres().await.unwrap();
+    let count: i64 = other.query_one("SELECT count(*) FROM users", &[]).await.unwrap().get(0);
+    assert_eq!(count, 0);
+}
+
+#[cfg(feature = "deadpool-postgres")]
+#[tokio::test]
+async fn pool_sees_the_schema() {
+    if !enabled() {
+        return;
+    }
+    let db = TestDatabase::start(|client| Box::pin(client.batch_execute(SCHEMA))).await.unwrap();
+    let pool = db.pool().unwrap();
+    let client = pool.get().await.unwrap();
+    let schema: String = client.query_one("SELECT current_schema()", &[]).await.unwrap().get(0);
+    assert_eq!(schema, db.schema());
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+fn blocking_client() {
+    if !enabled() {
+        return;
+    }
+    let db = parsql_testing::blocking::TestDatabase::start(|client| client.batch_execute(SCHEMA)).unwrap();
+    let mut client = db.client().unwrap();
+    client.execute("INSERT INTO users (name) VALUES ('John')", &[]).unwrap();
+    let count: i64 = client.query_one("SELECT count(*) FROM users", &[]).unwrap().get(0);
+    assert_eq!(count, 1);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_setup() {
+    let conn = parsql_testing::sqlite::open(|conn| conn.execute_batch("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)")).unwrap();
+    conn.execute("INSERT INTO users (name) VALUES ('John')", []).unwrap();
+    let count: i64 = conn.query_row("SELECT count(*) FROM users", [], |row| row.get(0)).unwrap();
+    assert_eq!(count, 1);
+}
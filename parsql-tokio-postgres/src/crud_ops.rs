@@ -1,132 +1,107 @@
-use crate::traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams};
-use postgres::types::FromSql;
-use std::sync::OnceLock;
-use tokio_postgres::{Client, Error, Row, Transaction};
+use std::hash::Hash;
+
+use crate::traits::{CrudOps, FetchBatch, FromRow, Keyed, Paginated, Patch, SqlParams, SqlQuery, UpdateParams, WhereFragments};
+use futures_util::future::try_join_all;
+use crate::ParsqlError;
+use parsql_core::{batch::{multi_row_insert, with_returning, with_upsert, BatchOptions, Dialect}, criteria, lookup::{self, Lookup}, metrics::observe_async, paging::{check_sortable, with_first_row, with_limit_offset}, trace};
+use postgres::types::{FromSql, ToSql};
+use tokio_postgres::{Client, Error, GenericClient, Row, Transaction};
 
 #[async_trait::async_trait]
 impl CrudOps for Client {
-    async fn insert<T, P: for<'a> FromSql<'a> + Send + Sync>(&self, entity: T) -> Result<P, Error>
+    async fn insert<T, P: for<'a> FromSql<'a> + Send + Sync>(&self, entity: T) -> Result<P, ParsqlError>
     where
         T: SqlQuery + SqlParams + Send + Sync + 'static,
     {
-        let sql = T::query();
+        entity.validation()?;
 
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled =
-            *TRACE_ENABLED.get_or_init(|| std::env::var("PARSQL_TRACE").unwrap_or_default() == "1");
+        let sql = T::query();
 
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
 
         let params = entity.params();
-        let row = self.query_one(&sql, &params).await?;
-        row.try_get::<_, P>(0)
+        let row = observe_async("insert", T::table_name(), self.query_one(sql, &params)).await?;
+        Ok(row.try_get::<_, P>(0)?)
     }
 
-    async fn update<T>(&self, entity: T) -> Result<bool, Error>
+    async fn update<T>(&self, entity: T) -> Result<u64, ParsqlError>
     where
         T: SqlQuery + UpdateParams + Send + Sync + 'static,
     {
+        entity.validation()?;
+
         let sql = T::query();
 
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled =
-            *TRACE_ENABLED.get_or_init(|| std::env::var("PARSQL_TRACE").unwrap_or_default() == "1");
-
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
 
         let params = entity.params();
-        let result = self.execute(&sql, &params).await?;
-        Ok(result > 0)
+        Ok(observe_async("update", T::table_name(), self.execute(sql, &params)).await?)
     }
 
-    async fn delete<T>(&self, entity: T) -> Result<u64, Error>
+    async fn delete<T>(&self, entity: T) -> Result<u64, ParsqlError>
     where
         T: SqlQuery + SqlParams + Send + Sync + 'static,
     {
         let sql = T::query();
 
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled =
-            *TRACE_ENABLED.get_or_init(|| std::env::var("PARSQL_TRACE").unwrap_or_default() == "1");
-
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
 
-        let params = entity.params();
-        self.execute(&sql, &params).await
+        Ok(observe_async("delete", T::table_name(), crate::params::execute(self, sql, &entity)).await?)
     }
 
-    async fn fetch<T>(&self, params: T) -> Result<T, Error>
+    async fn call<T>(&self, entity: T) -> Result<u64, ParsqlError>
     where
-        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
     {
         let sql = T::query();
 
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled =
-            *TRACE_ENABLED.get_or_init(|| std::env::var("PARSQL_TRACE").unwrap_or_default() == "1");
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
 
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES] Execute SQL: {}", sql);
-        }
+        Ok(observe_async("call", T::table_name(), crate::params::execute(self, sql, &entity)).await?)
+    }
+
+    async fn fetch<T>(&self, params: T) -> Result<T, ParsqlError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+    {
+        let sql = T::query_single();
+
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
 
         let query_params = params.params();
-        let row = self.query_one(&sql, &query_params).await?;
-        T::from_row(&row)
+        let row = observe_async("fetch", T::table_name(), self.query_one(sql, &query_params)).await?;
+        Ok(T::from_row(&row)?)
     }
 
-    async fn fetch_all<T>(&self, params: T) -> Result<Vec<T>, Error>
+    async fn fetch_all<T>(&self, params: T) -> Result<Vec<T>, ParsqlError>
     where
         T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
     {
         let sql = T::query();
 
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled =
-            *TRACE_ENABLED.get_or_init(|| std::env::var("PARSQL_TRACE").unwrap_or_default() == "1");
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
 
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES] Execute SQL: {}", sql);
-        }
+        let rows = observe_async("fetch_all", T::table_name(), crate::params::query(self, sql, &params)).await?;
 
-        let query_params = params.params();
-        let rows = self.query(&sql, &query_params).await?;
-
-        let mut results = Vec::with_capacity(rows.len());
-        for row in rows {
-            results.push(T::from_row(&row)?);
-        }
-
-        Ok(results)
+        Ok(T::from_rows(&rows)?)
     }
 
-    async fn select<T, F, R>(&self, entity: T, to_model: F) -> Result<R, Error>
+    async fn select<T, F, R>(&self, entity: T, to_model: F) -> Result<R, ParsqlError>
     where
         T: SqlQuery + SqlParams + Send + Sync + 'static,
         F: Fn(&Row) -> Result<R, Error> + Send + Sync + 'static,
         R: Send + 'static,
     {
-        let sql = T::query();
+        let sql = T::query_single();
 
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled =
-            *TRACE_ENABLED.get_or_init(|| std::env::var("PARSQL_TRACE").unwrap_or_default() == "1");
-
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
 
         let params = entity.params();
-        let row = self.query_one(&sql, &params).await?;
-        to_model(&row)
+        let row = observe_async("select", T::table_name(), self.query_one(sql, &params)).await?;
+        Ok(to_model(&row)?)
     }
 
-    async fn select_all<T, F, R>(&self, entity: T, to_model: F) -> Result<Vec<R>, Error>
+    async fn select_all<T, F, R>(&self, entity: T, to_model: F) -> Result<Vec<R>, ParsqlError>
     where
         T: SqlQuery + SqlParams + Send + Sync + 'static,
         F: Fn(&Row) -> R + Send + Sync + 'static,
@@ -134,16 +109,9 @@ impl CrudOps for Client {
     {
         let sql = T::query();
 
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled =
-            *TRACE_ENABLED.get_or_init(|| std::env::var("PARSQL_TRACE").unwrap_or_default() == "1");
-
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
 
-        let params = entity.params();
-        let rows = self.query(&sql, &params).await?;
+        let rows = observe_async("select_all", T::table_name(), crate::params::query(self, sql, &entity)).await?;
 
         let mut results = Vec::with_capacity(rows.len());
         for row in rows {
@@ -163,17 +131,117 @@ impl CrudOps for Client {
 /// - `entity`: Data object to be inserted (must implement SqlQuery and SqlParams traits)
 ///
 /// ## Return Value
-/// - `Result<u64, Error>`: On success, returns the number of inserted records; on failure, returns Error
+/// - `Result<u64, ParsqlError>`: On success, returns the number of inserted records; on failure, returns ParsqlError
 pub async fn insert<T, P: for<'a> FromSql<'a> + Send + Sync>(
     client: &Client,
     entity: T,
-) -> Result<P, Error>
+) -> Result<P, ParsqlError>
 where
     T: SqlQuery + SqlParams + Send + Sync + 'static,
 {
     client.insert::<T, P>(entity).await
 }
 
+/// # insert_returning_id
+///
+/// Inserts a new record and returns its generated `id`, appending `RETURNING id` when
+/// the struct has no `#[returning]` attribute. No `SELECT lastval()` round trip is needed.
+///
+/// ## Parameters
+/// - `client`: Database connection object or transaction
+/// - `entity`: Data object to be inserted (must implement SqlQuery and SqlParams traits)
+///
+/// ## Return Value
+/// - `Result<K, ParsqlError>`: On success, returns the generated key; on failure, returns ParsqlError
+///
+/// ## Example Usage
+/// ```rust,ignore
+/// // INSERT INTO users (name, email ) VALUES ($1, $2 ) RETURNING id
+/// let id: i64 = insert_returning_id(&client, InsertUser { name, email }).await?;
+/// ```
+pub async fn insert_returning_id<C, T, K: for<'a> FromSql<'a> + Send + Sync>(
+    client: &C,
+    entity: T,
+) -> Result<K, ParsqlError>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + SqlParams + Send + Sync + 'static,
+{
+    insert_returning(client, entity, "id").await
+}
+
+/// # insert_returning
+///
+/// [`insert_returning_id`] for a key column with another name, e.g. `user_id`.
+/// An existing `#[returning]` attribute takes precedence over `column`.
+///
+/// ## Parameters
+/// - `client`: Database connection object or transaction
+/// - `entity`: Data object to be inserted (must implement SqlQuery and SqlParams traits)
+/// - `column`: The key column to return
+///
+/// ## Return Value
+/// - `Result<K, ParsqlError>`: On success, returns the value of `column`; on failure, returns ParsqlError
+///
+/// ## Panics
+/// Panics when `column` is not a plain column name.
+pub async fn insert_returning<C, T, K: for<'a> FromSql<'a> + Send + Sync>(
+    client: &C,
+    entity: T,
+    column: &str,
+) -> Result<K, ParsqlError>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + SqlParams + Send + Sync + 'static,
+{
+    entity.validation()?;
+    let sql = with_returning(T::query(), column)
+        .unwrap_or_else(|| panic!("insert_returning: `{}` is not a plain column name", column));
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let params = entity.params();
+    let row = observe_async("insert", T::table_name(), client.query_one(sql.as_str(), &params)).await?;
+    Ok(row.try_get::<_, K>(0)?)
+}
+
+/// # insert_returning_opt
+///
+/// [`insert_returning`] for idempotent inserts. When the statement skips an existing row
+/// with `ON CONFLICT DO NOTHING` (see `#[on_conflict_do_nothing]`), nothing is returned
+/// and the result is `None` instead of an error.
+///
+/// ## Parameters
+/// - `client`: Database connection object or transaction
+/// - `entity`: Data object to be inserted (must implement SqlQuery and SqlParams traits)
+/// - `column`: The key column to return
+///
+/// ## Return Value
+/// - `Result<Option<K>, ParsqlError>`: On success, returns the value of `column`, or `None` when the
+///   row already existed; on failure, returns Error
+///
+/// ## Panics
+/// Panics when `column` is not a plain column name.
+pub async fn insert_returning_opt<C, T, K: for<'a> FromSql<'a> + Send + Sync>(
+    client: &C,
+    entity: T,
+    column: &str,
+) -> Result<Option<K>, ParsqlError>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + SqlParams + Send + Sync + 'static,
+{
+    entity.validation()?;
+    let sql = with_returning(T::query(), column)
+        .unwrap_or_else(|| panic!("insert_returning_opt: `{}` is not a plain column name", column));
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let params = entity.params();
+    let row = observe_async("insert", T::table_name(), client.query_opt(sql.as_str(), &params)).await?;
+    Ok(row.map(|row| row.try_get::<_, K>(0)).transpose()?)
+}
+
 /// # update
 ///
 /// Updates an existing record in the database.
@@ -183,14 +251,31 @@ where
 /// - `entity`: Data object containing the update information (must implement SqlQuery and UpdateParams traits)
 ///
 /// ## Return Value
-/// - `Result<bool, Error>`: On success, returns true; on failure, returns Error
-pub async fn update<T>(client: &Client, entity: T) -> Result<bool, Error>
+/// - `Result<u64, ParsqlError>`: On success, returns the number of updated records; on failure, returns ParsqlError
+pub async fn update<T>(client: &Client, entity: T) -> Result<u64, ParsqlError>
 where
     T: SqlQuery + UpdateParams + Send + Sync + 'static,
 {
     client.update(entity).await
 }
 
+// `update` önceden bool döndürüyordu; eski davranışı bekleyen kod için korunuyor
+#[deprecated(
+    since = "0.4.0",
+    note = "`update` now returns the number of affected rows; compare it with 0 instead."
+)]
+/// # update_applied
+///
+/// Updates an existing record, returning whether any record was updated.
+///
+/// This function is deprecated. Please use `update` instead.
+pub async fn update_applied<T>(client: &Client, entity: T) -> Result<bool, ParsqlError>
+where
+    T: SqlQuery + UpdateParams + Send + Sync + 'static,
+{
+    Ok(update(client, entity).await? > 0)
+}
+
 /// # delete
 ///
 /// Deletes a record from the database.
@@ -200,14 +285,229 @@ where
 /// - `entity`: Data object containing delete conditions (must implement SqlQuery and SqlParams traits)
 ///
 /// ## Return Value
-/// - `Result<u64, Error>`: On success, returns the number of deleted records; on failure, returns Error
-pub async fn delete<T>(client: &Client, entity: T) -> Result<u64, Error>
+/// - `Result<u64, ParsqlError>`: On success, returns the number of deleted records; on failure, returns ParsqlError
+pub async fn delete<T>(client: &Client, entity: T) -> Result<u64, ParsqlError>
 where
     T: SqlQuery + SqlParams + Send + Sync + 'static,
 {
     client.delete(entity).await
 }
 
+/// # update_patch
+///
+/// Updates only the fields of a `Patch` that are `Some`. The `SET` clause is built at
+/// run time from those fields and the key is bound after them.
+///
+/// ## Parameters
+/// - `client`: Database connection object or transaction
+/// - `key`: Key of the record to update
+/// - `patch`: The `<Model>Patch` generated by the `Patch` derive
+///
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of updated records (0 without a
+///   query when no field is set); on failure, returns Error
+pub async fn update_patch<C, P>(client: &C, key: P::Key, patch: P) -> Result<u64, Error>
+where
+    C: GenericClient + Sync,
+    P: Patch + Send + Sync,
+    P::Key: Send,
+{
+    let Some(sql) = patch.patch_sql() else {
+        return Ok(0);
+    };
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let mut params = patch.values();
+    params.push(&key);
+    observe_async("update", P::table(), client.execute(sql.as_str(), &params)).await
+}
+
+/// # delete_where
+///
+/// Deletes the rows a `Queryable` selects, reusing its `#[where_clause]` instead of
+/// repeating the condition on a `Deletable` type.
+///
+/// ## Parameters
+/// - `client`: Database connection object or transaction
+/// - `criteria`: Queryable whose table and WHERE clause pick the rows (must implement SqlQuery and SqlParams traits)
+///
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of deleted records; on failure, returns Error
+///
+/// ## Panics
+/// When the query has no WHERE clause or reads through `#[join]` / `union`.
+///
+/// ## Example Usage
+/// ```rust,ignore
+/// // DELETE FROM users WHERE state = $1 AND last_login < $2
+/// let deleted = delete_where(&client, &GetInactiveUsers::new(0, cutoff)).await?;
+/// ```
+pub async fn delete_where<C, T>(client: &C, criteria: &T) -> Result<u64, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + SqlParams + Sync,
+{
+    let (table, where_clause) = T::criteria().unwrap_or_else(|| {
+        panic!("delete_where: `{}` has no single-table WHERE clause to reuse", std::any::type_name::<T>())
+    });
+    let sql = criteria::delete_statement(table, where_clause);
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let params = criteria.params();
+    let params = &params[..criteria::where_param_count(where_clause)];
+    observe_async("delete", T::table_name(), client.execute(sql.as_str(), params)).await
+}
+
+/// # update_where
+///
+/// Writes the `Some` fields of a `Patch` to the rows a `Queryable` selects, reusing its
+/// `#[where_clause]`. The values of the patch are bound first, then the criteria.
+///
+/// ## Parameters
+/// - `client`: Database connection object or transaction
+/// - `criteria`: Queryable whose table and WHERE clause pick the rows (must implement SqlQuery and SqlParams traits)
+/// - `changes`: The `<Model>Patch` generated by the `Patch` derive, for the same table
+///
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of updated records (0 without a
+///   query when no field is set); on failure, returns Error
+///
+/// ## Panics
+/// When the query has no WHERE clause, reads through `#[join]` / `union`, or the patch
+/// belongs to another table.
+pub async fn update_where<C, T, P>(client: &C, criteria: &T, changes: &P) -> Result<u64, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + SqlParams + Sync,
+    P: Patch + Sync,
+{
+    let (table, where_clause) = T::criteria().unwrap_or_else(|| {
+        panic!("update_where: `{}` has no single-table WHERE clause to reuse", std::any::type_name::<T>())
+    });
+    if P::table() != table {
+        panic!("update_where: the patch updates `{}` but the criteria select from `{}`", P::table(), table);
+    }
+    let Some(sql) = criteria::update_statement(table, &changes.columns(), where_clause, Dialect::Postgres) else {
+        return Ok(0);
+    };
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let criteria_params = criteria.params();
+    let mut params = changes.values();
+    params.extend_from_slice(&criteria_params[..criteria::where_param_count(where_clause)]);
+    observe_async("update", T::table_name(), client.execute(sql.as_str(), &params)).await
+}
+
+/// # fetch_all_combined
+/// 
+/// Retrieves the records of `T` matching every filter of `filters`. The `#[where_clause]`
+/// of each filter is joined with `AND` and its placeholders are renumbered after the
+/// filters before it, so one filter struct can narrow any query over its table.
+/// 
+/// ## Parameters
+/// - `client`: Database connection object or transaction
+/// - `filters`: Tuple of filters, e.g. `(&by_state, &created_since)`; each is a `Queryable`
+///   with a single-table WHERE clause
+/// 
+/// ## Return Value
+/// - `Result<Vec<T>, ParsqlError>`: On success, returns the matching records; on failure, returns ParsqlError
+/// 
+/// ## Errors
+/// When the query of `T` binds parameters or is a `UNION`; the error's source is a
+/// `CombineError` and nothing is sent to the database.
+/// 
+/// ## Panics
+/// When a filter has no single-table WHERE clause.
+/// 
+/// ## Example Usage
+/// ```rust,ignore
+/// #[derive(Queryable, SqlParams)]
+/// #[table("users")]
+/// #[select("id")]
+/// #[where_clause("state = $")]
+/// pub struct ByState {
+///     pub state: i16,
+/// }
+///
+/// #[derive(Queryable, SqlParams)]
+/// #[table("users")]
+/// #[select("id")]
+/// #[where_clause("created_at >= $since")]
+/// pub struct CreatedSince {
+///     pub since: NaiveDateTime,
+/// }
+///
+/// // SELECT id, name FROM users WHERE (state = $1) AND (created_at >= $2) ORDER BY id
+/// let users: Vec<UserRow> = fetch_all_combined(&client, (&ByState { state: 1 }, &CreatedSince { since })).await?;
+/// ```
+pub async fn fetch_all_combined<C, T, F>(client: &C, filters: F) -> Result<Vec<T>, ParsqlError>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + FromRow,
+    F: WhereFragments + Sync,
+{
+    let sql = criteria::combined_query::<T>(&filters.conditions(), Dialect::Postgres)?;
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let params = filters.params();
+    let rows = observe_async("fetch_all", T::table_name(), client.query(sql.as_str(), &params)).await?;
+    Ok(T::from_rows(&rows)?)
+}
+
+/// # batch_fetch
+///
+/// Runs several independent SELECTs in one round trip and returns the records of each.
+/// The statements are pipelined: every request is sent before the first response is
+/// awaited, so a dashboard issuing a few queries waits about as long as for one of them.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `queries`: Tuple of up to six query references, e.g. `(&active_users, &recent_orders)`
+///
+/// ## Return Value
+/// - `Result<(Vec<A>, Vec<B>, ...), Error>`: On success, returns the records of each query
+///   in tuple order; on failure, returns the error of the first failing query
+///
+/// ## Example Usage
+/// ```rust,ignore
+/// let (users, orders, counts) = batch_fetch(&client, (&ActiveUsers::new(1), &RecentOrders::new(since), &UserCount::new(1))).await?;
+/// ```
+///
+/// The queries run as separate statements; outside a transaction they may see different
+/// snapshots of the database.
+pub async fn batch_fetch<C, Q>(client: &C, queries: Q) -> Result<Q::Output, Error>
+where
+    C: GenericClient + Sync,
+    Q: FetchBatch,
+{
+    let statements = queries.statements();
+    let pending = statements.iter().zip(Q::tables()).map(|((sql, params), table)| {
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
+        observe_async("batch_fetch", table, client.query(*sql, params))
+    });
+
+    // Futures birlikte beklendiğinde istekler yanıt beklenmeden gönderilir
+    Q::from_results(try_join_all(pending).await?)
+}
+
+/// # call
+///
+/// Runs a stored procedure (`CALL`) or function (`SELECT`) with the arguments of a
+/// `Callable` struct. Use `select` instead to read what a function returns.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `entity`: Arguments of the call (must implement SqlQuery and SqlParams traits)
+///
+/// ## Return Value
+/// - `Result<u64, ParsqlError>`: On success, returns the number of rows the call returned or affected; on failure, returns ParsqlError
+pub async fn call<T>(client: &Client, entity: T) -> Result<u64, ParsqlError>
+where
+    T: SqlQuery + SqlParams + Send + Sync + 'static,
+{
+    client.call(entity).await
+}
+
 /// # fetch
 ///
 /// Retrieves a single record from the database and converts it to a struct.
@@ -217,8 +517,8 @@ where
 /// - `params`: Data object containing query parameters (must implement SqlQuery, FromRow, and SqlParams traits)
 ///
 /// ## Return Value
-/// - `Result<T, Error>`: On success, returns the retrieved record as a struct; on failure, returns Error
-pub async fn fetch<T>(client: &Client, params: T) -> Result<T, Error>
+/// - `Result<T, ParsqlError>`: On success, returns the retrieved record as a struct; on failure, returns ParsqlError
+pub async fn fetch<T>(client: &Client, params: T) -> Result<T, ParsqlError>
 where
     T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
 {
@@ -234,14 +534,283 @@ where
 /// - `params`: Query parameter object (must implement SqlQuery, FromRow, and SqlParams traits)
 ///
 /// ## Return Value
-/// - `Result<Vec<T>, Error>`: On success, returns the list of found records; on failure, returns Error
-pub async fn fetch_all<T>(client: &Client, params: T) -> Result<Vec<T>, Error>
+/// - `Result<Vec<T>, ParsqlError>`: On success, returns the list of found records; on failure, returns ParsqlError
+pub async fn fetch_all<T>(client: &Client, params: T) -> Result<Vec<T>, ParsqlError>
 where
     T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
 {
     client.fetch_all(params).await
 }
 
+/// # fetch_all_limited
+///
+/// Retrieves one page of records, appending `LIMIT` and `OFFSET` to the query at
+/// execution time instead of baking `#[limit]` / `#[offset]` into the query type.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `params`: Query parameter object (must implement SqlQuery, FromRow, and SqlParams traits)
+/// - `limit`: Maximum number of records to return
+/// - `offset`: Number of records to skip
+///
+/// ## Return Value
+/// - `Result<Vec<T>, Error>`: On success, returns the records of the page; on failure, returns Error
+///
+/// ## Panics
+/// Panics when the query of `T` already has a `LIMIT`, `OFFSET` or `FETCH` clause.
+///
+/// ## Example Usage
+/// ```rust,ignore
+/// // SELECT id, name FROM users WHERE state = $1 ORDER BY id LIMIT $2 OFFSET $3
+/// let second_page = fetch_all_limited(&client, &UsersByState::new(1), 20, 20).await?;
+/// ```
+pub async fn fetch_all_limited<T>(
+    client: &Client,
+    params: &T,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<T>, Error>
+where
+    T: SqlQuery + FromRow + SqlParams + Sync,
+{
+    let mut query_params = params.params();
+    let sql = with_limit_offset(T::query(), query_params.len(), Dialect::Postgres)
+        .unwrap_or_else(|e| panic!("fetch_all_limited: {}", e));
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    query_params.push(&limit);
+    query_params.push(&offset);
+    let rows = observe_async("fetch_all", T::table_name(), client.query(sql.as_str(), &query_params)).await?;
+
+    T::from_rows(&rows)
+}
+
+/// # fetch_page
+///
+/// Retrieves the page generated by `#[derive(Paginated)]`, i.e. [`fetch_all_limited`]
+/// with the query, limit and offset held by the page.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `page`: The `<Query>Page` value
+///
+/// ## Return Value
+/// - `Result<Vec<P::Query>, Error>`: On success, returns the records of the page; on failure, returns Error
+///
+/// ## Example Usage
+/// ```rust,ignore
+/// let second_page = fetch_page(&client, &UsersByStatePage::nth(UsersByState::new(1), 2, 20)).await?;
+/// ```
+pub async fn fetch_page<P>(client: &Client, page: &P) -> Result<Vec<P::Query>, Error>
+where
+    P: Paginated,
+    P::Query: Sync,
+{
+    fetch_all_limited(client, page.query(), page.limit(), page.offset()).await
+}
+
+/// # fetch_first
+///
+/// Retrieves the first record of the query ordered by `column`, appending
+/// `ORDER BY column ASC NULLS LAST LIMIT 1` at execution time. `column` must be listed in
+/// the query type's `#[sortable("...")]`; an existing `ORDER BY` only breaks ties.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `params`: Query parameter object (must implement SqlQuery, FromRow, and SqlParams traits)
+/// - `column`: The order column, one of the `#[sortable]` columns
+///
+/// ## Return Value
+/// - `Result<Option<T>, ParsqlError>`: On success, returns the record, or `None` when the query matches nothing;
+///   on failure, returns Error. An unlisted `column` fails before anything is sent, with the
+///   [`ValidationError`](crate::traits::ValidationError) as the error's source.
+///
+/// ## Panics
+/// Panics when the query of `T` already has a `LIMIT`, `OFFSET` or `FETCH` clause.
+///
+/// ## Example Usage
+/// ```rust,ignore
+/// // SELECT ... WHERE customer_id = $1 ORDER BY total ASC NULLS LAST LIMIT 1
+/// let smallest = fetch_first(&client, &CustomerOrders::new(7), "total").await?;
+/// ```
+pub async fn fetch_first<T>(client: &Client, params: &T, column: &str) -> Result<Option<T>, ParsqlError>
+where
+    T: SqlQuery + FromRow + SqlParams + Sync,
+{
+    fetch_end(client, params, column, false).await
+}
+
+/// # fetch_last
+///
+/// Retrieves the last record of the query ordered by `column`, i.e. [`fetch_first`] with
+/// `ORDER BY column DESC NULLS LAST`, such as the latest order of a customer.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `params`: Query parameter object (must implement SqlQuery, FromRow, and SqlParams traits)
+/// - `column`: The order column, one of the `#[sortable]` columns
+///
+/// ## Return Value
+/// - `Result<Option<T>, ParsqlError>`: On success, returns the record, or `None` when the query matches nothing;
+///   on failure, returns Error
+///
+/// ## Example Usage
+/// ```rust,ignore
+/// // SELECT ... WHERE customer_id = $1 ORDER BY created_at DESC NULLS LAST LIMIT 1
+/// let latest = fetch_last(&client, &CustomerOrders::new(7), "created_at").await?;
+/// ```
+pub async fn fetch_last<T>(client: &Client, params: &T, column: &str) -> Result<Option<T>, ParsqlError>
+where
+    T: SqlQuery + FromRow + SqlParams + Sync,
+{
+    fetch_end(client, params, column, true).await
+}
+
+async fn fetch_end<T>(client: &Client, params: &T, column: &str, last: bool) -> Result<Option<T>, ParsqlError>
+where
+    T: SqlQuery + FromRow + SqlParams + Sync,
+{
+    check_sortable(column, T::sortable_columns())?;
+    let sql = with_first_row(T::query(), column, last)
+        .unwrap_or_else(|e| panic!("{}: {}", if last { "fetch_last" } else { "fetch_first" }, e));
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let query_params = params.params();
+    let row = observe_async("fetch", T::table_name(), client.query_opt(sql.as_str(), &query_params)).await?;
+    Ok(row.as_ref().map(T::from_row).transpose()?)
+}
+
+/// # fetch_many
+///
+/// Retrieves the records of a list of keys with a single `WHERE id = ANY($1)` query, the
+/// keys bound as one array; the usual way to resolve the related records of a page of
+/// results without a query per record. The records come back in the order of `keys`, and
+/// the keys without a record are listed in `missing`.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `keys`: The requested keys; a key given twice is read once
+///
+/// ## Return Value
+/// - `Result<Lookup<T, K>, Error>`: On success, returns the records found and the missing keys;
+///   on failure, returns Error
+///
+/// ## Example Usage
+/// ```rust,ignore
+/// #[derive(Queryable, FromRow, SqlParams)]
+/// #[table("users")]
+/// #[where_clause("id = $")]
+/// #[primary_key("id")]
+/// pub struct UserById { /* ... */ }
+///
+/// // SELECT id, name, email FROM users WHERE id = ANY($1)
+/// let authors = fetch_many::<UserById, i64>(&client, &author_ids).await?;
+/// ```
+pub async fn fetch_many<T, K>(client: &Client, keys: &[K]) -> Result<Lookup<T, K>, Error>
+where
+    T: Keyed<Key = K> + SqlQuery + FromRow,
+    K: ToSql + Sync + Eq + Hash + Clone,
+{
+    let sql = lookup::keys_query(T::key_select(), T::key_column(), 1, Dialect::Postgres);
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let distinct = lookup::distinct(keys);
+    let rows = observe_async("fetch_many", T::table_name(), client.query(sql.as_str(), &[&distinct])).await?;
+    let records = T::from_rows(&rows)?;
+    Ok(lookup::in_key_order(keys, records, T::key))
+}
+
+/// # fetch_with_row
+///
+/// Retrieves a single record together with the raw `Row` it was mapped from.
+/// Columns that are selected but not part of the struct (computed values, window functions)
+/// can be read from the returned row.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `params`: Data object containing query parameters (must implement SqlQuery, FromRow, and SqlParams traits)
+///
+/// ## Return Value
+/// - `Result<(T, Row), Error>`: On success, returns the record and its row; on failure, returns Error
+pub async fn fetch_with_row<T>(client: &Client, params: &T) -> Result<(T, Row), Error>
+where
+    T: SqlQuery + FromRow + SqlParams + Sync,
+{
+    let sql = T::query_single();
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
+
+    let query_params = params.params();
+    let row = observe_async("fetch", T::table_name(), client.query_one(sql, &query_params)).await?;
+    let entity = T::from_row(&row)?;
+    Ok((entity, row))
+}
+
+/// # fetch_all_with_rows
+///
+/// Retrieves multiple records, each paired with the raw `Row` it was mapped from.
+/// Useful for pagination with a total count computed in the same query.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `params`: Data object containing query parameters (must implement SqlQuery, FromRow, and SqlParams traits)
+///
+/// ## Return Value
+/// - `Result<Vec<(T, Row)>, Error>`: On success, returns the records and their rows; on failure, returns Error
+///
+/// ## Example Usage
+/// ```rust,no_run
+/// use tokio_postgres::{types::ToSql, Error, NoTls, Row};
+/// use parsql_tokio_postgres::fetch_all_with_rows;
+/// use parsql_tokio_postgres::macros::{FromRow, Queryable, SqlParams};
+/// use parsql_tokio_postgres::traits::{FromRow, SqlParams, SqlQuery};
+///
+/// #[derive(Queryable, FromRow, SqlParams)]
+/// #[table("users")]
+/// #[select("id, name, state, COUNT(*) OVER() AS total_count")]
+/// #[where_clause("state = $")]
+/// #[limit(20)]
+/// pub struct UserPage {
+///     pub id: i32,
+///     pub name: String,
+///     pub state: i16,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Error> {
+///     let (client, connection) =
+///         tokio_postgres::connect("host=localhost user=postgres dbname=test", NoTls).await?;
+///     tokio::spawn(async move { connection.await });
+///
+///     let query = UserPage { id: 0, name: String::new(), state: 1 };
+///     let page = fetch_all_with_rows(&client, &query).await?;
+///     let total: i64 = page.first().map_or(Ok(0), |(_, row)| row.try_get("total_count"))?;
+///     println!("{} of {} users", page.len(), total);
+///     Ok(())
+/// }
+/// ```
+pub async fn fetch_all_with_rows<T>(client: &Client, params: &T) -> Result<Vec<(T, Row)>, Error>
+where
+    T: SqlQuery + FromRow + SqlParams + Sync,
+{
+    let sql = T::query();
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
+
+    let query_params = params.params();
+    let rows = observe_async("fetch_all", T::table_name(), client.query(sql, &query_params)).await?;
+
+    let mut results = Vec::with_capacity(rows.len());
+    for row in rows {
+        results.push((T::from_row(&row)?, row));
+    }
+
+    Ok(results)
+}
+
 /// # select
 ///
 /// Retrieves a single record from the database using a custom transformation function.
@@ -253,8 +822,8 @@ where
 /// - `to_model`: Function to convert a Row object to the target object type
 ///
 /// ## Return Value
-/// - `Result<R, Error>`: On success, returns the transformed object; on failure, returns Error
-pub async fn select<T, F, R>(client: &Client, entity: T, to_model: F) -> Result<R, Error>
+/// - `Result<R, ParsqlError>`: On success, returns the transformed object; on failure, returns ParsqlError
+pub async fn select<T, F, R>(client: &Client, entity: T, to_model: F) -> Result<R, ParsqlError>
 where
     T: SqlQuery + SqlParams + Send + Sync + 'static,
     F: Fn(&Row) -> Result<R, Error> + Send + Sync + 'static,
@@ -274,8 +843,8 @@ where
 /// - `to_model`: Function to convert a Row object to the target object type
 ///
 /// ## Return Value
-/// - `Result<Vec<R>, Error>`: On success, returns the list of transformed objects; on failure, returns Error
-pub async fn select_all<T, F, R>(client: &Client, entity: T, to_model: F) -> Result<Vec<R>, Error>
+/// - `Result<Vec<R>, ParsqlError>`: On success, returns the list of transformed objects; on failure, returns ParsqlError
+pub async fn select_all<T, F, R>(client: &Client, entity: T, to_model: F) -> Result<Vec<R>, ParsqlError>
 where
     T: SqlQuery + SqlParams + Send + Sync + 'static,
     F: Fn(&Row) -> R + Send + Sync + 'static,
@@ -303,7 +872,7 @@ where
     since = "0.2.0",
     note = "Renamed to `fetch`. Please use `fetch` function instead."
 )]
-pub async fn get<T>(client: &Client, params: T) -> Result<T, Error>
+pub async fn get<T>(client: &Client, params: T) -> Result<T, ParsqlError>
 where
     T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
 {
@@ -327,9 +896,239 @@ where
     since = "0.2.0",
     note = "Renamed to `fetch_all`. Please use `fetch_all` function instead."
 )]
-pub async fn get_all<T>(client: &Client, params: T) -> Result<Vec<T>, Error>
+pub async fn get_all<T>(client: &Client, params: T) -> Result<Vec<T>, ParsqlError>
 where
     T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
 {
     fetch_all(client, params).await
 }
+
+/// # insert_many
+///
+/// Inserts multiple records using multi-row `INSERT` statements.
+/// Records are split into chunks so that a single statement never binds more than
+/// 65535 parameters (PostgreSQL's limit).
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `entities`: Records to insert (must implement SqlQuery and SqlParams traits)
+/// - `options`: Chunk size and whether all chunks run inside one transaction
+///
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of inserted rows; on failure, returns Error
+///
+/// ## Example Usage
+/// ```rust,no_run
+/// use tokio_postgres::{NoTls, Error};
+/// use parsql::tokio_postgres::{insert_many, BatchOptions};
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Error> {
+///     let (mut client, connection) =
+///         tokio_postgres::connect("host=localhost user=postgres dbname=test", NoTls).await?;
+///     tokio::spawn(async move { connection.await });
+///
+///     let users: Vec<InsertUser> = (0..100_000)
+///         .map(|i| InsertUser { name: format!("user{}", i), email: format!("user{}@example.com", i) })
+///         .collect();
+///
+///     let options = BatchOptions { transaction: true, ..Default::default() };
+///     let inserted = insert_many(&mut client, &users, options).await?;
+///     println!("Inserted rows: {}", inserted);
+///     Ok(())
+/// }
+/// ```
+pub async fn insert_many<T>(
+    client: &mut Client,
+    entities: &[T],
+    options: BatchOptions,
+) -> Result<u64, Error>
+where
+    T: SqlQuery + SqlParams + Send + Sync,
+{
+    if options.transaction {
+        let tx = client.transaction().await?;
+        let inserted = insert_chunks(&tx, entities, None, options).await?;
+        tx.commit().await?;
+        Ok(inserted)
+    } else {
+        insert_chunks(&*client, entities, None, options).await
+    }
+}
+
+/// # upsert_many
+///
+/// Inserts multiple records like [`insert_many`], turning every chunk into one
+/// `INSERT ... ON CONFLICT (conflict) DO UPDATE` statement: a row whose `conflict`
+/// columns match an existing row updates its other columns from `EXCLUDED`.
+///
+/// A conflict key may appear only once per chunk; PostgreSQL rejects a statement that
+/// updates the same row twice.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `entities`: Records to upsert (must implement SqlQuery and SqlParams traits)
+/// - `conflict`: Columns of the unique constraint or index the rows collide on
+/// - `options`: Chunk size and whether all chunks run inside one transaction
+///
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the number of inserted or updated rows; on failure, returns Error
+///
+/// ## Panics
+/// If a `conflict` entry is not a plain column name or the INSERT has no `VALUES` list.
+///
+/// ## Example Usage
+/// ```rust,no_run
+/// use tokio_postgres::{NoTls, Error};
+/// use parsql::tokio_postgres::{upsert_many, BatchOptions};
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// pub struct InsertUser {
+///     pub name: String,
+///     pub email: String,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Error> {
+///     let (mut client, connection) =
+///         tokio_postgres::connect("host=localhost user=postgres dbname=test", NoTls).await?;
+///     tokio::spawn(async move { connection.await });
+///
+///     let users = vec![
+///         InsertUser { name: "Ali".to_string(), email: "ali@example.com".to_string() },
+///         InsertUser { name: "Ayşe".to_string(), email: "ayse@example.com".to_string() },
+///     ];
+///
+///     let upserted = upsert_many(&mut client, &users, &["email"], BatchOptions::default()).await?;
+///     println!("Upserted rows: {}", upserted);
+///     Ok(())
+/// }
+/// ```
+pub async fn upsert_many<T>(
+    client: &mut Client,
+    entities: &[T],
+    conflict: &[&str],
+    options: BatchOptions,
+) -> Result<u64, Error>
+where
+    T: SqlQuery + SqlParams + Send + Sync,
+{
+    if with_upsert(T::query(), conflict).is_none() {
+        panic!("upsert_many: can't add ON CONFLICT ({}) to `{}`", conflict.join(", "), T::query());
+    }
+
+    if options.transaction {
+        let tx = client.transaction().await?;
+        let upserted = insert_chunks(&tx, entities, Some(conflict), options).await?;
+        tx.commit().await?;
+        Ok(upserted)
+    } else {
+        insert_chunks(&*client, entities, Some(conflict), options).await
+    }
+}
+
+pub(crate) async fn insert_chunks<C, T>(
+    client: &C,
+    entities: &[T],
+    conflict: Option<&[&str]>,
+    options: BatchOptions,
+) -> Result<u64, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + SqlParams + Send + Sync,
+{
+    let Some(first) = entities.first() else {
+        return Ok(0);
+    };
+
+    let sql = T::query();
+    let params_per_row = first.params().len();
+    let rows_per_chunk = options.rows_per_chunk(Dialect::Postgres, params_per_row);
+
+    let mut inserted = 0;
+    for chunk in entities.chunks(rows_per_chunk) {
+        // upsert_many ON CONFLICT eklenebildiğini baştan doğruladı
+        let chunk_sql = multi_row_insert(sql, params_per_row, chunk.len(), Dialect::Postgres)
+            .map(|chunk_sql| match conflict {
+                Some(conflict) => with_upsert(&chunk_sql, conflict).expect("checked by upsert_many"),
+                None => chunk_sql,
+            });
+        match chunk_sql {
+            Some(chunk_sql) => {
+                trace::log_sql("PARSQL-TOKIO-POSTGRES", &chunk_sql);
+
+                let params: Vec<&(dyn ToSql + Sync)> =
+                    chunk.iter().flat_map(|entity| entity.params()).collect();
+                inserted += client.execute(chunk_sql.as_str(), &params).await?;
+            }
+            // VALUES listesi olmayan sorgular (INSERT ... SELECT) tek tek çalıştırılır
+            None => {
+                for entity in chunk {
+                    inserted += client.execute(sql, &entity.params()).await?;
+                }
+            }
+        }
+    }
+
+    Ok(inserted)
+}
+
+/// # update_many
+///
+/// Updates multiple records, reusing one prepared statement.
+///
+/// ## Parameters
+/// - `client`: Database connection object
+/// - `entities`: Records to update (must implement SqlQuery and UpdateParams traits)
+/// - `options`: Whether all updates run inside one transaction (`chunk_size` is not used,
+///   every record is a separate statement)
+///
+/// ## Return Value
+/// - `Result<u64, Error>`: On success, returns the total number of updated rows; on failure, returns Error
+pub async fn update_many<T>(
+    client: &mut Client,
+    entities: &[T],
+    options: BatchOptions,
+) -> Result<u64, Error>
+where
+    T: SqlQuery + UpdateParams + Send + Sync,
+{
+    if options.transaction {
+        let tx = client.transaction().await?;
+        let updated = update_each(&tx, entities).await?;
+        tx.commit().await?;
+        Ok(updated)
+    } else {
+        update_each(&*client, entities).await
+    }
+}
+
+async fn update_each<C, T>(client: &C, entities: &[T]) -> Result<u64, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + UpdateParams + Send + Sync,
+{
+    if entities.is_empty() {
+        return Ok(0);
+    }
+
+    let sql = T::query();
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
+
+    let stmt = client.prepare(sql).await?;
+    let mut updated = 0;
+    for entity in entities {
+        updated += client.execute(&stmt, &entity.params()).await?;
+    }
+
+    Ok(updated)
+}
+
@@ -0,0 +1,92 @@
+//! Snapshot-consistent paging through a held cursor.
+//!
+//! [`open_cursor`] declares a cursor `WITH HOLD` for the query of a `Queryable` and
+//! returns its [`PageToken`]; [`fetch_next`] reads the following rows of that fixed
+//! result, so an export sees every row exactly once while the table changes:
+//!
+//! ```rust,no_run
+//! use parsql_tokio_postgres::cursor::{fetch_next, open_cursor};
+//! use parsql_tokio_postgres::macros::{FromRow, Queryable, SqlParams};
+//! use parsql_tokio_postgres::traits::{FromRow, SqlParams, SqlQuery};
+//! use tokio_postgres::{types::ToSql, Error, NoTls, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow)]
+//! #[table("users")]
+//! #[where_clause("state = $")]
+//! #[order_by("id")]
+//! pub struct ActiveUser {
+//!     pub id: i64,
+//!     pub name: String,
+//!     pub state: i16,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Error> {
+//!     let (client, connection) = tokio_postgres::connect("host=localhost user=postgres", NoTls).await?;
+//!     tokio::spawn(connection);
+//!     let token = open_cursor(&client, &ActiveUser { id: 0, name: String::new(), state: 1 }).await?;
+//!     loop {
+//!         let page: Vec<ActiveUser> = fetch_next(&client, &token, 500).await?;
+//!         // ... write the page
+//!         if page.len() < 500 {
+//!             break;
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The cursor lives in the session of `client`; later pages must be read on the same
+//! connection. `fetch_next` closes the cursor after a short page, [`close_cursor`]
+//! closes it earlier.
+
+pub use parsql_core::cursor::{InvalidPageToken, PageToken};
+
+use parsql_core::{cursor, metrics::observe_async, trace};
+use tokio_postgres::{Error, GenericClient};
+
+use crate::traits::{FromRow, SqlParams, SqlQuery};
+
+/// Declares a held cursor for the query of `params` and returns its token.
+///
+/// The query runs to completion when the cursor is declared; its result is kept on the
+/// server until the cursor is closed.
+pub async fn open_cursor<C, T>(client: &C, params: &T) -> Result<PageToken, Error>
+where
+    C: GenericClient + Sync,
+    T: SqlQuery + SqlParams + Sync,
+{
+    let token = PageToken::generate();
+    let sql = cursor::declare_statement(&token, T::query());
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let params = params.params();
+    observe_async("open_cursor", T::table_name(), client.execute(sql.as_str(), &params)).await?;
+    Ok(token)
+}
+
+/// Reads up to `count` rows after the ones already read from the cursor.
+///
+/// Fewer than `count` rows mean the result is exhausted; the cursor is then closed and
+/// the token can't be used again.
+pub async fn fetch_next<C, T>(client: &C, token: &PageToken, count: u32) -> Result<Vec<T>, Error>
+where
+    C: GenericClient + Sync,
+    T: FromRow,
+{
+    let sql = cursor::fetch_statement(token, count);
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    let rows = observe_async("fetch_next", "unknown", client.query(sql.as_str(), &[])).await?;
+    if rows.len() < usize::try_from(count).unwrap_or(usize::MAX) {
+        close_cursor(client, token).await?;
+    }
+    T::from_rows(&rows)
+}
+
+/// Closes the cursor before its rows are all read.
+pub async fn close_cursor<C: GenericClient + Sync>(client: &C, token: &PageToken) -> Result<(), Error> {
+    let sql = cursor::close_statement(token);
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+    client.batch_execute(&sql).await
+}
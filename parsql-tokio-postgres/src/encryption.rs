@@ -0,0 +1,124 @@
+//! Column-level encryption.
+//!
+//! [`EncryptedColumn`] stores its value as `BYTEA`, encrypted with the cipher registered
+//! through [`set_column_cipher`]. Parameters are encrypted when they are bound and
+//! columns decrypted when rows are converted, so the derives need no extra attributes:
+//!
+//! ```rust,ignore
+//! #[derive(Insertable, SqlParams)]
+//! #[table("users")]
+//! pub struct InsertUser {
+//!     pub name: String,
+//!     pub email: EncryptedColumn<String>,
+//! }
+//!
+//! set_column_cipher(MyAesGcm::new(key));
+//! let id: i64 = client.insert(InsertUser { name: "Ali".into(), email: "ali@example.com".to_string().into() }).await?;
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use bytes::BytesMut;
+use tokio_postgres::types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+use parsql_core::encryption;
+pub use parsql_core::encryption::{set_column_cipher, CipherError, ColumnCipher, Plaintext};
+
+/// A value stored encrypted with the registered [`ColumnCipher`].
+///
+/// `Debug` never prints the value, only a hash of it keyed with a per-process secret.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct EncryptedColumn<T>(pub T);
+
+impl<T> EncryptedColumn<T> {
+    /// Returns the wrapped (decrypted) value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for EncryptedColumn<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for EncryptedColumn<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for EncryptedColumn<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Plaintext> fmt::Debug for EncryptedColumn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncryptedColumn({})", encryption::redacted(&self.0))
+    }
+}
+
+impl<T: Plaintext> ToSql for EncryptedColumn<T> {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn StdError + Sync + Send>> {
+        encryption::encrypt(&self.0)?.to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <Vec<u8> as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a, T: Plaintext> FromSql<'a> for EncryptedColumn<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn StdError + Sync + Send>> {
+        let ciphertext = <&[u8] as FromSql>::from_sql(ty, raw)?;
+        Ok(Self(encryption::decrypt(ciphertext)?))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&[u8] as FromSql>::accepts(ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Xor(u8);
+
+    impl ColumnCipher for Xor {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CipherError> {
+            Ok(plaintext.iter().map(|b| b ^ self.0).collect())
+        }
+
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CipherError> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    #[test]
+    fn round_trips_through_bytea() {
+        set_column_cipher(Xor(0x5a));
+        let value = EncryptedColumn("ali@example.com".to_string());
+
+        let mut out = BytesMut::new();
+        value.to_sql(&Type::BYTEA, &mut out).unwrap();
+        assert_ne!(&out[..], b"ali@example.com");
+
+        let read = EncryptedColumn::<String>::from_sql(&Type::BYTEA, &out).unwrap();
+        assert_eq!(read, value);
+        assert!(!format!("{:?}", read).contains("ali"));
+    }
+}
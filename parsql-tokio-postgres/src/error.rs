@@ -0,0 +1,88 @@
+use std::fmt;
+
+use parsql_core::{criteria::CombineError, validation::ValidationError};
+use tokio_postgres::Error;
+
+use crate::executor::Operation;
+
+/// Error of the operations that check their input before a statement is sent.
+///
+/// `CrudOps`, `insert`, `update` and the other functions that run `#[validate]` (or
+/// reject an unusable query) return it. A failed check is reported as `Validation` or
+/// `Combine` without contacting the server; everything the driver reports is `Postgres`:
+///
+/// ```rust,no_run
+/// use parsql_tokio_postgres::ParsqlError;
+///
+/// fn is_unique_violation(error: &ParsqlError) -> bool {
+///     error.code() == Some(&tokio_postgres::error::SqlState::UNIQUE_VIOLATION)
+/// }
+/// ```
+#[derive(Debug)]
+pub enum ParsqlError {
+    /// The statement or the connection failed
+    Postgres(Error),
+    /// The entity failed its validation; nothing was sent to the database
+    Validation(ValidationError),
+    /// `fetch_all_combined` could not narrow the query; nothing was sent to the database
+    Combine(CombineError),
+    /// A `ParsqlExecutor` answered the operation with an output of the wrong kind or type
+    UnexpectedOutput(Operation),
+}
+
+impl ParsqlError {
+    /// Returns the driver error, if the database or the connection failed.
+    pub fn as_postgres(&self) -> Option<&Error> {
+        match self {
+            ParsqlError::Postgres(e) => Some(e),
+            ParsqlError::Validation(_) | ParsqlError::Combine(_) | ParsqlError::UnexpectedOutput(_) => None,
+        }
+    }
+
+    /// Returns the SQLSTATE of a database error.
+    pub fn code(&self) -> Option<&tokio_postgres::error::SqlState> {
+        self.as_postgres().and_then(Error::code)
+    }
+}
+
+impl fmt::Display for ParsqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsqlError::Postgres(e) => write!(f, "{}", e),
+            ParsqlError::Validation(e) => write!(f, "{}", e),
+            ParsqlError::Combine(e) => write!(f, "fetch_all_combined: {}", e),
+            ParsqlError::UnexpectedOutput(operation) => {
+                write!(f, "ParsqlExecutor returned an unexpected output for `{}`", operation.name())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParsqlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParsqlError::Postgres(e) => Some(e),
+            ParsqlError::Validation(e) => Some(e),
+            ParsqlError::Combine(e) => Some(e),
+            ParsqlError::UnexpectedOutput(_) => None,
+        }
+    }
+}
+
+impl From<Error> for ParsqlError {
+    fn from(e: Error) -> Self {
+        ParsqlError::Postgres(e)
+    }
+}
+
+impl From<ValidationError> for ParsqlError {
+    fn from(e: ValidationError) -> Self {
+        ParsqlError::Validation(e)
+    }
+}
+
+impl From<CombineError> for ParsqlError {
+    fn from(e: CombineError) -> Self {
+        ParsqlError::Combine(e)
+    }
+}
@@ -0,0 +1,366 @@
+//! Trait-object-friendly companion of `CrudOps`.
+//!
+//! `CrudOps` has generic methods, so it can't be used as `dyn CrudOps`.
+//! `ParsqlExecutor` receives type-erased calls instead, which makes it
+//! object-safe: application code can hold an `Arc<dyn ParsqlExecutor>` and
+//! swap between a `Client`, a `Transaction`, a pooled deadpool `Object` or a
+//! mock. The typed CRUD methods are available directly on `dyn ParsqlExecutor`.
+//!
+//! A deadpool `Pool` is not an executor: the errors of this trait are
+//! [`ParsqlError`], which can't carry a pool failure. Take an `Object`
+//! from the pool, or use the `parsql-deadpool-postgres` backend, whose
+//! `DeadpoolError` tells pool exhaustion and timeouts apart from SQL errors.
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use parsql_tokio_postgres::executor::ParsqlExecutor;
+//! use parsql_tokio_postgres::macros::{FromRow, Queryable, SqlParams};
+//! use parsql_tokio_postgres::traits::{FromRow, SqlParams, SqlQuery};
+//! use parsql_tokio_postgres::ParsqlError;
+//! use tokio_postgres::{types::ToSql, Error, NoTls, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow, Debug)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! pub struct GetUser {
+//!     pub id: i32,
+//!     pub name: String,
+//! }
+//!
+//! struct UserService {
+//!     db: Arc<dyn ParsqlExecutor>,
+//! }
+//!
+//! impl UserService {
+//!     async fn find(&self, id: i32) -> Result<GetUser, ParsqlError> {
+//!         self.db.fetch(GetUser { id, name: String::new() }).await
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), ParsqlError> {
+//!     let (client, connection) = tokio_postgres::connect("host=localhost user=postgres", NoTls).await?;
+//!     tokio::spawn(async move { connection.await });
+//!
+//!     let service = UserService { db: Arc::new(client) };
+//!     let user = service.find(1).await?;
+//!     println!("{:?}", user);
+//!     Ok(())
+//! }
+//! ```
+
+use std::any::Any;
+
+use parsql_core::{metrics::observe_async, trace};
+use postgres::types::{FromSql, ToSql};
+use tokio_postgres::{Client, Error, GenericClient, Row, Transaction};
+
+use crate::traits::{FromRow, SqlParams, SqlQuery, UpdateParams};
+use crate::ParsqlError;
+
+/// A type-erased value produced by a [`ParsqlExecutor`].
+pub type ErasedValue = Box<dyn Any + Send>;
+
+/// Converts a row into a type-erased value.
+pub type RowMapper = dyn Fn(&Row) -> Result<ErasedValue, Error> + Send + Sync;
+
+/// The `CrudOps` operation an [`ErasedCall`] stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Insert,
+    Update,
+    Delete,
+    Fetch,
+    FetchAll,
+    Select,
+    SelectAll,
+}
+
+impl Operation {
+    /// Name of the `CrudOps` method, as used in the `op` label of the query metrics.
+    pub fn name(self) -> &'static str {
+        match self {
+            Operation::Insert => "insert",
+            Operation::Update => "update",
+            Operation::Delete => "delete",
+            Operation::Fetch => "fetch",
+            Operation::FetchAll => "fetch_all",
+            Operation::Select => "select",
+            Operation::SelectAll => "select_all",
+        }
+    }
+}
+
+/// A type-erased `CrudOps` call.
+pub struct ErasedCall<'a> {
+    /// Operation to perform
+    pub operation: Operation,
+    /// SQL generated by the entity type
+    pub sql: &'static str,
+    /// Table of the entity type (`SqlQuery::table_name()`), used to label the query metrics
+    pub table: &'static str,
+    /// Parameters of the entity
+    pub params: Vec<&'a (dyn ToSql + Sync)>,
+    /// The entity itself, for executors that don't talk to a database
+    pub entity: &'a (dyn Any + Send + Sync),
+    /// Row conversion of `Insert`, `Fetch`, `FetchAll`, `Select` and `SelectAll`
+    pub mapper: Option<&'a RowMapper>,
+}
+
+/// Result of an [`ErasedCall`].
+pub enum ErasedOutput {
+    /// Number of affected rows (`Update`, `Delete`)
+    Affected(u64),
+    /// A single converted row (`Insert`, `Fetch`, `Select`)
+    One(ErasedValue),
+    /// All converted rows (`FetchAll`, `SelectAll`)
+    Many(Vec<ErasedValue>),
+    /// The complete, already typed result of the operation (e.g. `u64` for
+    /// `Update`, `Vec<T>` for `FetchAll`); used by executors without a database
+    Value(ErasedValue),
+}
+
+/// Object-safe executor of type-erased `CrudOps` calls.
+#[async_trait::async_trait]
+pub trait ParsqlExecutor: Send + Sync {
+    /// Executes a type-erased call.
+    ///
+    /// # Arguments
+    /// * `call` - Operation, SQL, parameters and row conversion of the call
+    ///
+    /// # Return Value
+    /// * `Result<ErasedOutput, ParsqlError>` - Output of the operation or error
+    async fn execute_erased(&self, call: ErasedCall<'_>) -> Result<ErasedOutput, ParsqlError>;
+}
+
+/// Runs an erased call on any tokio-postgres client.
+pub async fn execute_erased_on<C>(client: &C, call: ErasedCall<'_>) -> Result<ErasedOutput, ParsqlError>
+where
+    C: GenericClient + Sync,
+{
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", call.sql);
+
+    let mapper = || {
+        call.mapper
+            .expect("row mapper is required for row returning operations")
+    };
+
+    let op = call.operation.name();
+    let output = match call.operation {
+        Operation::Update | Operation::Delete => {
+            observe_async(op, call.table, client.execute(call.sql, &call.params))
+                .await
+                .map(ErasedOutput::Affected)?
+        }
+        Operation::Insert | Operation::Fetch | Operation::Select => {
+            let row = observe_async(op, call.table, client.query_one(call.sql, &call.params)).await?;
+            mapper()(&row).map(ErasedOutput::One)?
+        }
+        Operation::FetchAll | Operation::SelectAll => {
+            let rows = observe_async(op, call.table, client.query(call.sql, &call.params)).await?;
+            let mapper = mapper();
+            rows.iter()
+                .map(mapper)
+                .collect::<Result<Vec<_>, _>>()
+                .map(ErasedOutput::Many)?
+        }
+    };
+    Ok(output)
+}
+
+#[async_trait::async_trait]
+impl ParsqlExecutor for Client {
+    async fn execute_erased(&self, call: ErasedCall<'_>) -> Result<ErasedOutput, ParsqlError> {
+        execute_erased_on(self, call).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ParsqlExecutor for Transaction<'_> {
+    async fn execute_erased(&self, call: ErasedCall<'_>) -> Result<ErasedOutput, ParsqlError> {
+        execute_erased_on(self, call).await
+    }
+}
+
+#[cfg(feature = "deadpool-postgres")]
+#[async_trait::async_trait]
+impl ParsqlExecutor for deadpool_postgres::Object {
+    async fn execute_erased(&self, call: ErasedCall<'_>) -> Result<ErasedOutput, ParsqlError> {
+        execute_erased_on(&***self, call).await
+    }
+}
+
+fn unexpected_output<V>(operation: Operation) -> Result<V, ParsqlError> {
+    Err(ParsqlError::UnexpectedOutput(operation))
+}
+
+fn downcast<V: 'static>(operation: Operation, value: ErasedValue) -> Result<V, ParsqlError> {
+    match value.downcast::<V>() {
+        Ok(value) => Ok(*value),
+        Err(_) => unexpected_output(operation),
+    }
+}
+
+fn erase<V: Send + 'static>(value: V) -> ErasedValue {
+    Box::new(value)
+}
+
+/// Typed CRUD operations on trait objects, mirroring `CrudOps`.
+impl dyn ParsqlExecutor + '_ {
+    /// Validates the entity, inserts it and returns the first column of the returned row.
+    pub async fn insert<T, P>(&self, entity: T) -> Result<P, ParsqlError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
+        P: for<'a> FromSql<'a> + Send + 'static,
+    {
+        entity.validation()?;
+
+        let mapper = |row: &Row| row.try_get::<_, P>(0).map(erase);
+        let call = ErasedCall {
+            operation: Operation::Insert,
+            sql: T::query(),
+            table: T::table_name(),
+            params: entity.params(),
+            entity: &entity,
+            mapper: Some(&mapper),
+        };
+        match self.execute_erased(call).await? {
+            ErasedOutput::One(value) | ErasedOutput::Value(value) => downcast(Operation::Insert, value),
+            _ => unexpected_output(Operation::Insert),
+        }
+    }
+
+    /// Validates the entity and updates records, returning the number of updated rows.
+    pub async fn update<T>(&self, entity: T) -> Result<u64, ParsqlError>
+    where
+        T: SqlQuery + UpdateParams + Send + Sync + 'static,
+    {
+        entity.validation()?;
+
+        let call = ErasedCall {
+            operation: Operation::Update,
+            sql: T::query(),
+            table: T::table_name(),
+            params: entity.params(),
+            entity: &entity,
+            mapper: None,
+        };
+        match self.execute_erased(call).await? {
+            ErasedOutput::Affected(rows) => Ok(rows),
+            ErasedOutput::Value(value) => downcast(Operation::Update, value),
+            _ => unexpected_output(Operation::Update),
+        }
+    }
+
+    /// Deletes records, returning the number of deleted rows.
+    pub async fn delete<T>(&self, entity: T) -> Result<u64, ParsqlError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
+    {
+        let call = ErasedCall {
+            operation: Operation::Delete,
+            sql: T::query(),
+            table: T::table_name(),
+            params: entity.params(),
+            entity: &entity,
+            mapper: None,
+        };
+        match self.execute_erased(call).await? {
+            ErasedOutput::Affected(rows) => Ok(rows),
+            ErasedOutput::Value(value) => downcast(Operation::Delete, value),
+            _ => unexpected_output(Operation::Delete),
+        }
+    }
+
+    /// Fetches a single record.
+    pub async fn fetch<T>(&self, params: T) -> Result<T, ParsqlError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+    {
+        let mapper = |row: &Row| T::from_row(row).map(erase);
+        let call = ErasedCall {
+            operation: Operation::Fetch,
+            sql: T::query_single(),
+            table: T::table_name(),
+            params: params.params(),
+            entity: &params,
+            mapper: Some(&mapper),
+        };
+        match self.execute_erased(call).await? {
+            ErasedOutput::One(value) | ErasedOutput::Value(value) => downcast(Operation::Fetch, value),
+            _ => unexpected_output(Operation::Fetch),
+        }
+    }
+
+    /// Fetches all matching records.
+    pub async fn fetch_all<T>(&self, params: T) -> Result<Vec<T>, ParsqlError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+    {
+        let mapper = |row: &Row| T::from_row(row).map(erase);
+        let call = ErasedCall {
+            operation: Operation::FetchAll,
+            sql: T::query(),
+            table: T::table_name(),
+            params: params.params(),
+            entity: &params,
+            mapper: Some(&mapper),
+        };
+        match self.execute_erased(call).await? {
+            ErasedOutput::Many(values) => values
+                .into_iter()
+                .map(|value| downcast(Operation::FetchAll, value))
+                .collect(),
+            ErasedOutput::Value(value) => downcast(Operation::FetchAll, value),
+            _ => unexpected_output(Operation::FetchAll),
+        }
+    }
+
+    /// Executes a custom query and converts the single result row with `to_model`.
+    pub async fn select<T, F, R>(&self, entity: T, to_model: F) -> Result<R, ParsqlError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
+        F: Fn(&Row) -> Result<R, Error> + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        let mapper = move |row: &Row| to_model(row).map(erase);
+        let call = ErasedCall {
+            operation: Operation::Select,
+            sql: T::query_single(),
+            table: T::table_name(),
+            params: entity.params(),
+            entity: &entity,
+            mapper: Some(&mapper),
+        };
+        match self.execute_erased(call).await? {
+            ErasedOutput::One(value) | ErasedOutput::Value(value) => downcast(Operation::Select, value),
+            _ => unexpected_output(Operation::Select),
+        }
+    }
+
+    /// Executes a custom query and converts every result row with `to_model`.
+    pub async fn select_all<T, F, R>(&self, entity: T, to_model: F) -> Result<Vec<R>, ParsqlError>
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
+        F: Fn(&Row) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        let mapper = move |row: &Row| Ok(erase(to_model(row)));
+        let call = ErasedCall {
+            operation: Operation::SelectAll,
+            sql: T::query(),
+            table: T::table_name(),
+            params: entity.params(),
+            entity: &entity,
+            mapper: Some(&mapper),
+        };
+        match self.execute_erased(call).await? {
+            ErasedOutput::Many(values) => values
+                .into_iter()
+                .map(|value| downcast(Operation::SelectAll, value))
+                .collect(),
+            ErasedOutput::Value(value) => downcast(Operation::SelectAll, value),
+            _ => unexpected_output(Operation::SelectAll),
+        }
+    }
+}
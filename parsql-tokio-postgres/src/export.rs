@@ -0,0 +1,148 @@
+//! Streaming CSV export.
+//!
+//! [`export_csv`] runs the SELECT generated for a `Queryable` type through
+//! `COPY (...) TO STDOUT WITH (FORMAT csv, HEADER)` and streams the result into any
+//! `AsyncWrite`, without converting the rows into structs first:
+//!
+//! ```rust,no_run
+//! use parsql::tokio_postgres::{export_csv, macros::{Queryable, SqlParams}, traits::{SqlParams, SqlQuery}};
+//! use tokio_postgres::{types::ToSql, NoTls};
+//!
+//! #[derive(Queryable, SqlParams)]
+//! #[table("users")]
+//! #[select("id, name, email")]
+//! #[where_clause("state = $")]
+//! pub struct ActiveUsers {
+//!     pub state: i16,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let (client, connection) = tokio_postgres::connect("host=localhost user=postgres", NoTls).await?;
+//!     tokio::spawn(connection);
+//!     let file = tokio::fs::File::create("users.csv").await?;
+//!     let bytes = export_csv(&client, &ActiveUsers { state: 1 }, file).await?;
+//!     println!("{} bytes written", bytes);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! `COPY` doesn't accept bind parameters, so the parameter values are first turned
+//! into literals by the server (`quote_nullable`), each cast to the type the query
+//! expects, and written into the statement.
+
+use std::fmt;
+use std::io;
+use std::pin::pin;
+
+use futures_util::StreamExt;
+use parsql_core::{debug::interpolate, trace};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_postgres::{types::ToSql, Error, GenericClient};
+
+use crate::traits::{SqlParams, SqlQuery};
+
+/// Errors that can occur while exporting query results.
+#[derive(Debug)]
+pub enum ExportError {
+    /// The query failed
+    Postgres(Error),
+    /// Writing the exported data failed
+    Io(io::Error),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Postgres(e) => write!(f, "PostgreSQL error: {}", e),
+            ExportError::Io(e) => write!(f, "export write error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExportError::Postgres(e) => Some(e),
+            ExportError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<Error> for ExportError {
+    fn from(e: Error) -> Self {
+        ExportError::Postgres(e)
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+/// Writes the rows selected by `params` to `writer` as CSV with a header line,
+/// returning the number of bytes written.
+///
+/// Works with a `Client` as well as a `Transaction`.
+pub async fn export_csv<T, C, W>(client: &C, params: &T, mut writer: W) -> Result<u64, ExportError>
+where
+    T: SqlQuery + SqlParams + Sync,
+    C: GenericClient + Sync,
+    W: AsyncWrite + Unpin,
+{
+    let sql = copy_query(client, T::query(), &params.params()).await?;
+
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    // Transaction içindeyken de aynı bağlantı kullanıldığı için COPY transaction'a dahildir
+    let mut stream = pin!(client.client().copy_out(sql.as_str()).await?);
+    let mut written = 0;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        writer.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+    }
+    writer.flush().await?;
+    Ok(written)
+}
+
+/// Builds the `COPY` statement of `sql`, with the parameters written in as literals.
+async fn copy_query<C: GenericClient>(
+    client: &C,
+    sql: &str,
+    params: &[&(dyn ToSql + Sync)],
+) -> Result<String, Error> {
+    let select = if params.is_empty() {
+        sql.to_string()
+    } else {
+        let statement = client.prepare(sql).await?;
+        let casts: Vec<String> = statement
+            .params()
+            .iter()
+            .map(|ty| format!("{}.{}", quote_ident(ty.schema()), quote_ident(ty.name())))
+            .collect();
+        let probes: Vec<String> = casts
+            .iter()
+            .enumerate()
+            .map(|(i, cast)| format!("quote_nullable(${}::{})", i + 1, cast))
+            .collect();
+        let row = client
+            .query_one(format!("SELECT {}", probes.join(", ")).as_str(), params)
+            .await?;
+        let literals: Vec<String> = casts
+            .iter()
+            .enumerate()
+            .map(|(i, cast)| format!("{}::{}", row.get::<_, String>(i), cast))
+            .collect();
+        interpolate(sql, &literals)
+    };
+    Ok(format!(
+        "COPY ({}) TO STDOUT WITH (FORMAT csv, HEADER)",
+        select
+    ))
+}
+
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
@@ -0,0 +1,82 @@
+//! Seed data.
+//!
+//! Tests and examples fill their tables from `Insertable` values instead of long
+//! hand-written `INSERT` scripts. [`load`] writes the rows of one table; a [`Fixtures`]
+//! set loads several tables in one transaction, in the order they were added, so
+//! parent rows exist before the rows that reference them:
+//!
+//! ```rust,ignore
+//! use parsql::tokio_postgres::fixtures::Fixtures;
+//!
+//! Fixtures::new()
+//!     .table(&users)
+//!     .table(&posts)
+//!     .table(&comments)
+//!     .load(&mut client)
+//!     .await?;
+//! ```
+
+use parsql_core::BatchOptions;
+use tokio_postgres::{Client, Error, Transaction};
+
+use crate::crud_ops::insert_chunks;
+use crate::traits::{SqlParams, SqlQuery};
+
+/// Inserts `rows` in one transaction, returning the number of inserted rows.
+pub async fn load<T>(client: &mut Client, rows: &[T]) -> Result<u64, Error>
+where
+    T: SqlQuery + SqlParams + Send + Sync,
+{
+    Fixtures::new().table(rows).load(client).await
+}
+
+/// Rows of several tables loaded together.
+#[derive(Default)]
+pub struct Fixtures<'a> {
+    sets: Vec<Box<dyn FixtureSet + 'a>>,
+}
+
+impl<'a> Fixtures<'a> {
+    /// An empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the rows of a table, inserted after the rows added before them.
+    pub fn table<T>(mut self, rows: &'a [T]) -> Self
+    where
+        T: SqlQuery + SqlParams + Send + Sync,
+    {
+        self.sets.push(Box::new(Rows(rows)));
+        self
+    }
+
+    /// Inserts every table in one transaction and returns the total number of inserted
+    /// rows. When a row fails, nothing is written.
+    pub async fn load(self, client: &mut Client) -> Result<u64, Error> {
+        let tx = client.transaction().await?;
+        let mut inserted = 0;
+        for set in &self.sets {
+            inserted += set.insert(&tx).await?;
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+}
+
+#[async_trait::async_trait]
+trait FixtureSet: Send + Sync {
+    async fn insert(&self, tx: &Transaction<'_>) -> Result<u64, Error>;
+}
+
+struct Rows<'a, T>(&'a [T]);
+
+#[async_trait::async_trait]
+impl<T> FixtureSet for Rows<'_, T>
+where
+    T: SqlQuery + SqlParams + Send + Sync,
+{
+    async fn insert(&self, tx: &Transaction<'_>) -> Result<u64, Error> {
+        insert_chunks(tx, self.0, None, BatchOptions::default()).await
+    }
+}
@@ -0,0 +1,126 @@
+//! PostGIS columns (`postgis` feature).
+//!
+//! [`PostGis`] binds and reads `geometry` and `geography` columns as `geo-types`
+//! values, so location data goes through the derives like any other field. Combine it
+//! with `#[within("location", center = "center", distance = "meters")]` for radius
+//! searches:
+//!
+//! ```rust,ignore
+//! #[derive(Insertable, SqlParams)]
+//! #[table("shops")]
+//! pub struct InsertShop {
+//!     pub name: String,
+//!     pub location: PostGis<Point>,
+//! }
+//!
+//! let shop = InsertShop { name: "Kadıköy".into(), location: PostGis::wgs84(Point::new(29.02, 40.99)) };
+//! let id: i64 = client.insert(shop).await?;
+//! ```
+
+use std::error::Error as StdError;
+use std::ops::{Deref, DerefMut};
+
+use bytes::BytesMut;
+use tokio_postgres::types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+use parsql_core::geo::{self, geo_types::Geometry};
+pub use parsql_core::geo::geo_types;
+
+/// SRID of WGS 84 longitude / latitude, the usual SRID of `geography` columns.
+pub const WGS84: i32 = 4326;
+
+/// A `geo-types` geometry stored in a PostGIS column.
+///
+/// `T` is `Geometry<f64>` or one of its variants such as `Point<f64>`; reading a row
+/// whose value is of another type fails. The SRID must match the column's: a
+/// `geometry(Point, 4326)` column rejects values without one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PostGis<T> {
+    /// The geometry
+    pub geometry: T,
+    /// Its spatial reference system, read back from the column when set
+    pub srid: Option<i32>,
+}
+
+impl<T> PostGis<T> {
+    /// A geometry without SRID.
+    pub fn new(geometry: T) -> Self {
+        Self { geometry, srid: None }
+    }
+
+    /// A geometry in longitude / latitude ([`WGS84`]).
+    pub fn wgs84(geometry: T) -> Self {
+        Self { geometry, srid: Some(WGS84) }
+    }
+
+    /// Sets the SRID.
+    pub fn with_srid(mut self, srid: i32) -> Self {
+        self.srid = Some(srid);
+        self
+    }
+
+    /// Returns the wrapped geometry.
+    pub fn into_inner(self) -> T {
+        self.geometry
+    }
+}
+
+impl<T> From<T> for PostGis<T> {
+    fn from(geometry: T) -> Self {
+        Self::new(geometry)
+    }
+}
+
+impl<T> Deref for PostGis<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.geometry
+    }
+}
+
+impl<T> DerefMut for PostGis<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.geometry
+    }
+}
+
+/// Whether `ty` is one of the PostGIS types; they have no fixed OID.
+fn is_postgis(ty: &Type) -> bool {
+    matches!(ty.name(), "geometry" | "geography")
+}
+
+impl<T> ToSql for PostGis<T>
+where
+    T: Clone + Into<Geometry<f64>> + std::fmt::Debug,
+{
+    fn to_sql(
+        &self,
+        _ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn StdError + Sync + Send>> {
+        out.extend_from_slice(&geo::encode(&self.geometry.clone().into(), self.srid));
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        is_postgis(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a, T> FromSql<'a> for PostGis<T>
+where
+    T: TryFrom<Geometry<f64>>,
+    T::Error: StdError + Sync + Send + 'static,
+{
+    fn from_sql(_ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn StdError + Sync + Send>> {
+        let (geometry, srid) = geo::decode(raw)?;
+        Ok(Self { geometry: T::try_from(geometry)?, srid })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        is_postgis(ty)
+    }
+}
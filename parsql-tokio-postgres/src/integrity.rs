@@ -0,0 +1,70 @@
+//! Deferred constraints and trigger control.
+//!
+//! [`IntegrityControl`] adds the statements of bulk loads and data fixes to a
+//! transaction. Rows that reference each other can be written in any order once the
+//! foreign keys are deferred to the commit, and the audit triggers of a table can be
+//! switched off while it is rewritten:
+//!
+//! ```rust,no_run
+//! use parsql_tokio_postgres::integrity::IntegrityControl;
+//! # async fn example(client: &mut tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+//! let tx = client.transaction().await?;
+//! tx.set_constraints_deferred(&["fk_posts_user"]).await?;
+//! tx.disable_triggers("posts").await?;
+//! // ... posts first, then their users
+//! tx.set_constraints_immediate(&[]).await?;
+//! tx.enable_triggers("posts").await?;
+//! tx.commit().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only constraints declared `DEFERRABLE` can be deferred. Disabled triggers stay
+//! disabled after the commit, so enable them again before it; a rollback undoes both.
+//! PostgreSQL does not alter a table whose deferred checks are still pending, which is
+//! why the example makes the constraints immediate before enabling the triggers.
+
+use parsql_core::{integrity, trace};
+use tokio_postgres::{Error, Transaction};
+
+/// Constraint and trigger statements run on a transaction.
+#[async_trait::async_trait]
+pub trait IntegrityControl {
+    /// Checks the given deferrable constraints, or all of them for an empty list, at the
+    /// commit instead of after each statement.
+    async fn set_constraints_deferred(&self, constraints: &[&str]) -> Result<(), Error>;
+
+    /// Checks the given constraints after each statement again, and the rows written
+    /// while they were deferred right away.
+    async fn set_constraints_immediate(&self, constraints: &[&str]) -> Result<(), Error>;
+
+    /// Disables the user-defined triggers of `table`.
+    async fn disable_triggers(&self, table: &str) -> Result<(), Error>;
+
+    /// Enables the user-defined triggers of `table` again.
+    async fn enable_triggers(&self, table: &str) -> Result<(), Error>;
+}
+
+#[async_trait::async_trait]
+impl IntegrityControl for Transaction<'_> {
+    async fn set_constraints_deferred(&self, constraints: &[&str]) -> Result<(), Error> {
+        run(self, &integrity::set_constraints(constraints, true)).await
+    }
+
+    async fn set_constraints_immediate(&self, constraints: &[&str]) -> Result<(), Error> {
+        run(self, &integrity::set_constraints(constraints, false)).await
+    }
+
+    async fn disable_triggers(&self, table: &str) -> Result<(), Error> {
+        run(self, &integrity::alter_triggers(table, false)).await
+    }
+
+    async fn enable_triggers(&self, table: &str) -> Result<(), Error> {
+        run(self, &integrity::alter_triggers(table, true)).await
+    }
+}
+
+async fn run(tx: &Transaction<'_>, sql: &str) -> Result<(), Error> {
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", sql);
+    tx.batch_execute(sql).await
+}
@@ -77,10 +77,11 @@
 //! }
 //! ```
 //!
-//! The trait-based extension methods can also be used with client objects from deadpool-postgres:
+//! Clients taken from a deadpool-postgres pool get the same methods from the
+//! `parsql-deadpool-postgres` backend, returning its `DeadpoolError`:
 //!
 //! ```rust,no_run
-//! use parsql::tokio_postgres::CrudOps;
+//! use parsql::deadpool_postgres::CrudOps;
 //! use deadpool_postgres::{Config, Pool};
 //! use tokio_postgres::NoTls;
 //!
@@ -95,7 +96,7 @@
 //!     let client = pool.get().await?;
 //!     
 //!     // Use extension methods
-//!     let users = client.fetch_all(active_users_query).await?;
+//!     let users = client.fetch_all(&active_users_query).await?;
 //!     
 //!     Ok(())
 //! }
@@ -104,6 +105,35 @@
 pub mod crud_ops;
 pub mod traits;
 pub mod macros;
+pub mod executor;
+pub mod cache;
+pub mod constraint;
+pub mod cursor;
+pub mod dynamic;
+pub mod singleflight;
+pub mod loader;
+pub mod repository;
+pub mod encryption;
+pub mod export;
+pub mod fixtures;
+pub mod integrity;
+#[cfg(feature = "postgis")]
+pub mod geo;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod locks;
+pub mod partitions;
+pub mod registry;
+pub mod schema;
+pub mod pipeline;
+pub mod session;
+pub mod two_phase;
+pub mod views;
+mod error;
+mod params;
+
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+pub mod tls;
 
 /// Transaction support module 
 /// 
@@ -156,17 +186,63 @@ pub mod transaction_ops;
 
 // Re-export tokio-postgres types that might be needed
 pub use tokio_postgres::{types::ToSql, Row, Error, Client};
+pub use error::ParsqlError;
+/// JSON / JSONB column wrapper, (de)serialized with serde (`json` feature).
+/// Use it as the field type of a `json_agg(...)` column to map child rows into nested structures.
+#[cfg(feature = "json")]
+pub use tokio_postgres::types::Json;
+/// Exact decimal for `NUMERIC` columns (`decimal` feature), usable as a field type of the derived structs.
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
+pub use encryption::EncryptedColumn;
+/// PostGIS `geometry` / `geography` column holding a `geo-types` value (`postgis` feature).
+#[cfg(feature = "postgis")]
+pub use geo::{geo_types, PostGis};
+pub use constraint::{ConstraintError, ConstraintKind, ConstraintViolation};
+pub use dynamic::query_dynamic;
+pub use export::export_csv;
+#[cfg(feature = "json")]
+pub use json::{fetch_all_json, fetch_json};
+pub use partitions::ensure_monthly_partition;
+pub use views::refresh_materialized_view;
 pub use macros::*;
+pub use executor::ParsqlExecutor;
 // Re-export crud operations
 pub use crate::crud_ops::{
     insert,
+    insert_returning,
+    insert_returning_id,
+    insert_returning_opt,
     update,
+    update_patch,
+    update_where,
     delete,
+    delete_where,
+    call,
+    batch_fetch,
     fetch,
     fetch_all,
+    fetch_all_combined,
+    fetch_all_limited,
+    fetch_first,
+    fetch_last,
+    fetch_many,
+    fetch_page,
+    fetch_with_row,
+    fetch_all_with_rows,
     select,
-    select_all
+    select_all,
+    insert_many,
+    upsert_many,
+    update_many
 };
+#[allow(deprecated)]
+pub use crate::crud_ops::update_applied;
+pub use parsql_core::{lookup::Lookup, BatchOptions, OnError, Progress};
+pub use parsql_core::trace::set_trace;
+// `#[audited]` yazımlarının denetim tablosu
+pub use parsql_core::audit_log;
+pub use parsql_core::value::Value;
 
 // Geriye dönük uyumluluk için eski fonksiyonları deprecated olarak dışa aktaralım
 #[allow(deprecated)]
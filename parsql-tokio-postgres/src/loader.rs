@@ -0,0 +1,166 @@
+//! Batched loading of records by key.
+//!
+//! [`ParsqlLoader`] is the DataLoader pattern of GraphQL servers: resolvers call
+//! [`load`](ParsqlLoader::load) with one key each, and the keys requested before the
+//! batch runs are read together with a single [`fetch_many`] query. Resolving the author
+//! of every post on a page then costs one query instead of one per post. The loader is
+//! cheap to clone and meant to live for one request, e.g. in the async-graphql or
+//! juniper context:
+//!
+//! ```rust,no_run
+//! use parsql_tokio_postgres::loader::ParsqlLoader;
+//! use parsql_tokio_postgres::macros::{FromRow, Queryable, SqlParams};
+//! use parsql_tokio_postgres::traits::{FromRow, Keyed, SqlParams, SqlQuery};
+//! use std::sync::Arc;
+//! use tokio_postgres::{types::ToSql, Error, NoTls, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow, Clone, Debug)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! #[primary_key("id")]
+//! pub struct GetUser {
+//!     pub id: i32,
+//!     pub name: String,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Error> {
+//!     let (client, connection) = tokio_postgres::connect("host=localhost user=postgres", NoTls).await?;
+//!     tokio::spawn(async move { connection.await });
+//!
+//!     let users = ParsqlLoader::<GetUser>::new(Arc::new(client));
+//!     // SELECT id, name FROM users WHERE id = ANY($1), sent once for the three calls
+//!     let (first, second, third) = tokio::join!(users.load(1), users.load(2), users.load(1));
+//!     assert_eq!(first?.map(|u| u.name), third?.map(|u| u.name));
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The batch runs on a spawned task, so `load` must be called within a Tokio runtime.
+//! Errors are not shared: when the batch query fails, each caller reads its own key.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use parsql_core::trace;
+use postgres::types::ToSql;
+use tokio::sync::oneshot;
+use tokio_postgres::{Client, Error};
+
+use crate::crud_ops::fetch_many;
+use crate::traits::{FromRow, Keyed, SqlQuery};
+
+type Waiters<T> = Vec<(<T as Keyed>::Key, oneshot::Sender<Option<T>>)>;
+
+/// Loads records of `T` by key, coalescing the loads of a tick into one query.
+pub struct ParsqlLoader<T: Keyed> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T: Keyed> {
+    client: Arc<Client>,
+    delay: Option<Duration>,
+    pending: Mutex<Waiters<T>>,
+}
+
+impl<T: Keyed> ParsqlLoader<T> {
+    /// Creates a loader reading from `client`.
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                client,
+                delay: None,
+                pending: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Waits `delay` before running a batch instead of a single scheduler turn, so loads
+    /// spread over a longer window (e.g. resolvers awaiting other I/O first) share it.
+    pub fn with_delay(self, delay: Duration) -> Self {
+        let inner = Arc::try_unwrap(self.inner)
+            .unwrap_or_else(|_| panic!("with_delay must be called before the loader is cloned"));
+        Self {
+            inner: Arc::new(Inner {
+                delay: Some(delay),
+                ..inner
+            }),
+        }
+    }
+
+    /// The client the batches run on.
+    pub fn client(&self) -> &Client {
+        &self.inner.client
+    }
+}
+
+impl<T: Keyed> Clone for ParsqlLoader<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> ParsqlLoader<T>
+where
+    T: Keyed + SqlQuery + FromRow + Clone + Send + Sync + 'static,
+    T::Key: ToSql + Eq + Hash + Clone + Send + Sync + 'static,
+{
+    /// Loads the record of `key`, or `None` when there is none.
+    ///
+    /// The key joins the pending batch; the first load of a batch schedules it.
+    pub async fn load(&self, key: T::Key) -> Result<Option<T>, Error> {
+        let (sender, receiver) = oneshot::channel();
+        let first = {
+            let mut pending = self.inner.pending.lock().unwrap();
+            pending.push((key.clone(), sender));
+            pending.len() == 1
+        };
+        if first {
+            let inner = Arc::clone(&self.inner);
+            tokio::spawn(async move { inner.dispatch().await });
+        }
+
+        match receiver.await {
+            Ok(record) => Ok(record),
+            // Toplu sorgu başarısız oldu; hata kopyalanamadığından anahtar tek başına okunur
+            Err(_) => Ok(fetch_many::<T, T::Key>(&self.inner.client, &[key]).await?.found.pop()),
+        }
+    }
+
+    /// Loads the records of `keys`, in their order.
+    pub async fn load_many(&self, keys: &[T::Key]) -> Result<Vec<Option<T>>, Error> {
+        futures_util::future::try_join_all(keys.iter().cloned().map(|key| self.load(key))).await
+    }
+}
+
+impl<T> Inner<T>
+where
+    T: Keyed + SqlQuery + FromRow + Clone + Send + Sync + 'static,
+    T::Key: ToSql + Eq + Hash + Clone + Send + Sync + 'static,
+{
+    /// Runs the pending batch once the loads of the current tick have joined it.
+    async fn dispatch(&self) {
+        match self.delay {
+            Some(delay) => tokio::time::sleep(delay).await,
+            None => tokio::task::yield_now().await,
+        }
+        let waiters = std::mem::take(&mut *self.pending.lock().unwrap());
+        let keys: Vec<T::Key> = waiters.iter().map(|(key, _)| key.clone()).collect();
+
+        trace::log("PARSQL-TOKIO-POSTGRES-LOADER", format!("Loading {} keys in one batch", keys.len()));
+
+        // Hata durumunda göndericiler düşer ve her çağrı kendi anahtarını okur
+        let Ok(lookup) = fetch_many::<T, T::Key>(&self.client, &keys).await else {
+            return;
+        };
+        let records: HashMap<&T::Key, &T> = lookup.found.iter().map(|record| (record.key(), record)).collect();
+        for (key, sender) in waiters {
+            // Çağıran vazgeçtiyse gönderim hata döner, bu normaldir
+            let _ = sender.send(records.get(&key).map(|record| (*record).clone()));
+        }
+    }
+}
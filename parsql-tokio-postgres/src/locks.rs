@@ -0,0 +1,66 @@
+//! Advisory locks.
+//!
+//! [`advisory_lock`] and [`try_advisory_lock`] take a session-level PostgreSQL
+//! advisory lock on a 64-bit key, [`advisory_unlock`] releases it. Instances of an
+//! application that use the same key run the guarded code one at a time, e.g. a cron
+//! job scheduled on every instance:
+//!
+//! ```rust,no_run
+//! use parsql::tokio_postgres::locks::{lock_key, with_advisory_lock};
+//! # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+//! with_advisory_lock(client, lock_key("nightly-report"), |client| {
+//!     client.batch_execute("REFRESH MATERIALIZED VIEW daily_sales")
+//! })
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The lock belongs to the connection, so it has to be released on the same one.
+//! [`with_advisory_lock`] does that after the closure's future completes, also when it
+//! fails.
+
+use std::future::Future;
+
+use parsql_core::{locks, trace};
+use tokio_postgres::{Client, Error, GenericClient};
+
+pub use parsql_core::locks::lock_key;
+
+/// Waits until the lock on `key` is free and takes it.
+pub async fn advisory_lock<C: GenericClient + Sync>(client: &C, key: i64) -> Result<(), Error> {
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", locks::LOCK);
+    client.execute(locks::LOCK, &[&key]).await?;
+    Ok(())
+}
+
+/// Takes the lock on `key` if no other session holds it; returns whether it was taken.
+pub async fn try_advisory_lock<C: GenericClient + Sync>(client: &C, key: i64) -> Result<bool, Error> {
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", locks::TRY_LOCK);
+    client.query_one(locks::TRY_LOCK, &[&key]).await?.try_get(0)
+}
+
+/// Releases the lock on `key`; returns `false` when this session did not hold it.
+pub async fn advisory_unlock<C: GenericClient + Sync>(client: &C, key: i64) -> Result<bool, Error> {
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", locks::UNLOCK);
+    client.query_one(locks::UNLOCK, &[&key]).await?.try_get(0)
+}
+
+/// Runs `f` while holding the lock on `key`, waiting for it first.
+///
+/// The lock is released when the future returned by `f` completes, whether it
+/// succeeded or not. An error of `f` takes precedence over an error of the unlock.
+pub async fn with_advisory_lock<'c, T, E, F, Fut>(client: &'c Client, key: i64, f: F) -> Result<T, E>
+where
+    F: FnOnce(&'c Client) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: From<Error>,
+{
+    advisory_lock(client, key).await?;
+    let result = f(client).await;
+    let unlocked = advisory_unlock(client, key).await;
+
+    let value = result?;
+    unlocked?;
+    Ok(value)
+}
@@ -1,3 +1,3 @@
 pub use parsql_macros::{
-    Deletable, FromRowPostgres as FromRow, Insertable, Queryable, SqlParams, Updateable, UpdateParams
+    Callable, Deletable, Entity, FromRowPostgres as FromRow, Insertable, Paginated, Patch, Queryable, Repository, SqlParams, Updateable, UpdateParams
 };
@@ -0,0 +1,52 @@
+//! Monthly partitions of range-partitioned tables.
+//!
+//! PostgreSQL rejects a row for which no partition exists, so time-series tables need
+//! their partitions created ahead of the data. A model annotated with
+//! `#[partitioned(by = "range(created_at)")]` implements [`Partitioned`], and
+//! [`ensure_monthly_partition`] creates the partition of a month unless it exists. A
+//! scheduled task can keep the next month's partition ready:
+//!
+//! ```rust,no_run
+//! use std::time::SystemTime;
+//!
+//! use parsql_tokio_postgres::macros::Insertable;
+//! use parsql_tokio_postgres::partitions::{ensure_monthly_partition, Month};
+//! use parsql_tokio_postgres::traits::{Partitioned, SqlQuery};
+//! use tokio_postgres::{Client, Error};
+//!
+//! #[derive(Insertable)]
+//! #[table("events")]
+//! #[partitioned(by = "range(created_at)")]
+//! pub struct InsertEvent {
+//!     pub kind: String,
+//!     pub created_at: SystemTime,
+//! }
+//!
+//! async fn prepare_partitions(client: &Client) -> Result<(), Error> {
+//!     let month = Month::containing(SystemTime::now());
+//!     ensure_monthly_partition::<InsertEvent, _>(client, month).await?;
+//!     ensure_monthly_partition::<InsertEvent, _>(client, month.next()).await
+//! }
+//! ```
+
+pub use parsql_core::partition::Month;
+
+use parsql_core::{partition, trace};
+use tokio_postgres::{Error, GenericClient};
+
+use crate::traits::Partitioned;
+
+/// Creates the partition of `T`'s table holding `month`, named like `events_2024_03`,
+/// unless a table of that name exists.
+///
+/// A partition of another name already covering part of the month makes PostgreSQL
+/// reject the statement.
+pub async fn ensure_monthly_partition<T: Partitioned, C: GenericClient + Sync>(
+    client: &C,
+    month: Month,
+) -> Result<(), Error> {
+    let sql = partition::monthly_partition_statement(T::partitioned_table(), month);
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    client.batch_execute(&sql).await
+}
@@ -0,0 +1,136 @@
+//! Pipelined statements.
+//!
+//! tokio-postgres sends the requests of futures polled together without waiting for
+//! each response, so a batch of statements costs about as many round trips as a single
+//! one. [`Pipeline`] queues entities and executes their statements that way:
+//!
+//! ```rust,no_run
+//! use parsql_tokio_postgres::{pipeline::Pipeline, transactional};
+//! # use parsql_tokio_postgres::{macros::{Deletable, Insertable, SqlParams}, traits::{SqlParams, SqlQuery}};
+//! # use tokio_postgres::{types::ToSql, Error};
+//! # #[derive(Insertable, SqlParams)]
+//! # #[table("orders")]
+//! # pub struct InsertOrder { pub customer_id: i64 }
+//! # #[derive(Deletable, SqlParams)]
+//! # #[table("carts")]
+//! # #[where_clause("customer_id = $")]
+//! # pub struct DeleteCart { pub customer_id: i64 }
+//! # async fn example(client: &mut tokio_postgres::Client) -> Result<(), Error> {
+//! let mut tx = transactional::begin(client).await?;
+//! let affected = Pipeline::new()
+//!     .insert(InsertOrder { customer_id: 7 })
+//!     .delete(DeleteCart { customer_id: 7 })
+//!     .execute(&mut tx)
+//!     .await?;
+//! tx.commit().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The statements don't see each other's results, so a pipeline can't use an id
+//! returned by one of its own inserts.
+
+use futures_util::future::try_join_all;
+use parsql_core::trace;
+use tokio_postgres::{types::ToSql, Error, Transaction};
+
+use crate::traits::{SqlParams, SqlQuery, UpdateParams};
+
+/// Statements queued for one pipelined round trip, executed in queue order.
+#[derive(Default)]
+pub struct Pipeline<'a> {
+    statements: Vec<Box<dyn Queued + Send + Sync + 'a>>,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Creates an empty pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the INSERT of `entity`.
+    pub fn insert<T>(mut self, entity: T) -> Self
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'a,
+    {
+        self.statements.push(Box::new(Insert(entity)));
+        self
+    }
+
+    /// Queues the UPDATE of `entity`.
+    pub fn update<T>(mut self, entity: T) -> Self
+    where
+        T: SqlQuery + UpdateParams + Send + Sync + 'a,
+    {
+        self.statements.push(Box::new(Update(entity)));
+        self
+    }
+
+    /// Queues the DELETE of `entity`.
+    pub fn delete<T>(mut self, entity: T) -> Self
+    where
+        T: SqlQuery + SqlParams + Send + Sync + 'a,
+    {
+        self.statements.push(Box::new(Delete(entity)));
+        self
+    }
+
+    /// Executes the queued statements in `tx` and returns the number of rows each one
+    /// affected, in queue order.
+    ///
+    /// When a statement fails the rest of the transaction is aborted; the error of the
+    /// first failing statement is returned and the transaction should be rolled back.
+    pub async fn execute(self, tx: &mut Transaction<'_>) -> Result<Vec<u64>, Error> {
+        let params: Vec<Vec<&(dyn ToSql + Sync)>> =
+            self.statements.iter().map(|statement| statement.params()).collect();
+
+        let tx = &*tx;
+        let pending = self.statements.iter().zip(&params).map(|(statement, params)| {
+            trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", statement.sql());
+            tx.execute(statement.sql(), params)
+        });
+
+        // Futures birlikte beklendiğinde istekler yanıt beklenmeden gönderilir
+        try_join_all(pending).await
+    }
+}
+
+/// A queued entity with the statement it is executed by.
+trait Queued {
+    fn sql(&self) -> &'static str;
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)>;
+}
+
+struct Insert<T>(T);
+struct Update<T>(T);
+struct Delete<T>(T);
+
+impl<T: SqlQuery + SqlParams> Queued for Insert<T> {
+    fn sql(&self) -> &'static str {
+        T::query()
+    }
+
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        SqlParams::params(&self.0)
+    }
+}
+
+impl<T: SqlQuery + UpdateParams> Queued for Update<T> {
+    fn sql(&self) -> &'static str {
+        T::query()
+    }
+
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        UpdateParams::params(&self.0)
+    }
+}
+
+impl<T: SqlQuery + SqlParams> Queued for Delete<T> {
+    fn sql(&self) -> &'static str {
+        T::query()
+    }
+
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        SqlParams::params(&self.0)
+    }
+}
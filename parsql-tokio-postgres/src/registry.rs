@@ -0,0 +1,73 @@
+//! Startup-time preparation of the registered statements.
+//!
+//! Structs marked with `#[register_query]` add their generated SQL to a process-wide
+//! registry. Calling [`prepare_all`] once at startup sends each statement to the
+//! server, so a wrong column or table name stops the deployment before it serves
+//! traffic:
+//!
+//! ```rust,no_run
+//! use parsql::tokio_postgres::{macros::Queryable, registry, traits::SqlQuery};
+//!
+//! #[derive(Queryable)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! #[register_query]
+//! pub struct GetUser {
+//!     pub id: i64,
+//! }
+//!
+//! # async fn example(client: &tokio_postgres::Client) -> Result<(), Box<dyn std::error::Error>> {
+//! let prepared = registry::prepare_all(client).await?;
+//! println!("{} statements prepared", prepared);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use parsql_core::trace;
+use tokio_postgres::{Error, GenericClient};
+
+pub use parsql_core::registry::{queries, QueryEntry};
+#[doc(hidden)]
+pub use parsql_core::registry::inventory;
+
+/// A registered statement the server rejected.
+#[derive(Debug)]
+pub struct PrepareError {
+    /// Path of the struct the statement belongs to
+    pub name: &'static str,
+    /// The rejected SQL
+    pub sql: &'static str,
+    /// Error returned by the server
+    pub source: Error,
+}
+
+impl fmt::Display for PrepareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to prepare {} ({}): {}", self.name, self.sql, self.source)
+    }
+}
+
+impl std::error::Error for PrepareError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Prepares every registered statement on `client`, returning how many were prepared.
+///
+/// Stops at the first statement the server rejects.
+pub async fn prepare_all<C: GenericClient>(client: &C) -> Result<usize, PrepareError> {
+    let entries = queries();
+    for entry in &entries {
+        trace::log_sql("PARSQL-TOKIO-POSTGRES", entry.sql);
+
+        client.prepare(entry.sql).await.map_err(|source| PrepareError {
+            name: entry.name,
+            sql: entry.sql,
+            source,
+        })?;
+    }
+    Ok(entries.len())
+}
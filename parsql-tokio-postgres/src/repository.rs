@@ -0,0 +1,89 @@
+//! Typed CRUD repositories.
+//!
+//! [`Repository`] runs the queries named by a `#[derive(Repository)]` struct over any
+//! `CrudOps` executor (a client, a transaction or a wrapper such as `cache::Cached`),
+//! so a layered application doesn't have to hand-write the same create/find/update/delete
+//! wrappers for every entity:
+//!
+//! ```rust,ignore
+//! #[derive(Repository)]
+//! #[repository(key = i64, insert = UserInsert, find = UserById, find_all = UsersByState, update = UserUpdate, delete = UserDelete)]
+//! pub struct Users;
+//!
+//! let users = Repository::<Users, _>::new(client);
+//! let id: i64 = users.create(UserInsert::new("Ali", "ali@example.com", 1)).await?;
+//! let user = users.find(id).await?;
+//! users.delete(id).await?;
+//! ```
+
+use std::marker::PhantomData;
+
+use postgres::types::FromSql;
+use crate::traits::{CrudOps, RepositoryTypes};
+use crate::ParsqlError;
+
+/// CRUD operations on the entity described by `R`, run on executor `E`.
+pub struct Repository<R, E> {
+    executor: E,
+    _types: PhantomData<fn() -> R>,
+}
+
+impl<R, E> Repository<R, E> {
+    /// Creates a repository running its queries on `executor`.
+    pub fn new(executor: E) -> Self {
+        Self {
+            executor,
+            _types: PhantomData,
+        }
+    }
+
+    /// The executor the queries run on.
+    pub fn executor(&self) -> &E {
+        &self.executor
+    }
+
+    /// Consumes the repository, returning the executor.
+    pub fn into_inner(self) -> E {
+        self.executor
+    }
+}
+
+impl<R, E> Repository<R, E>
+where
+    R: RepositoryTypes,
+    R::Key: Send,
+    R::Insert: Send + Sync + 'static,
+    R::Find: Send + Sync + 'static,
+    R::FindAll: Send + Sync + 'static,
+    R::Update: Send + Sync + 'static,
+    R::Delete: Send + Sync + 'static,
+    E: CrudOps + Sync,
+{
+    /// Inserts a record, returning the value of its `#[returning]` column.
+    pub async fn create<P>(&self, entity: R::Insert) -> Result<P, ParsqlError>
+    where
+        P: for<'a> FromSql<'a> + Send + Sync,
+    {
+        self.executor.insert(entity).await
+    }
+
+    /// Retrieves the record with the given key.
+    pub async fn find(&self, key: R::Key) -> Result<R::Find, ParsqlError> {
+        self.executor.fetch(R::Find::from(key)).await
+    }
+
+    /// Retrieves every record matching `query`.
+    pub async fn find_all(&self, query: R::FindAll) -> Result<Vec<R::FindAll>, ParsqlError> {
+        self.executor.fetch_all(query).await
+    }
+
+    /// Updates a record, returning the number of affected rows.
+    pub async fn update(&self, entity: R::Update) -> Result<u64, ParsqlError> {
+        self.executor.update(entity).await
+    }
+
+    /// Deletes the record with the given key, returning the number of affected rows.
+    pub async fn delete(&self, key: R::Key) -> Result<u64, ParsqlError> {
+        self.executor.delete(R::Delete::from(key)).await
+    }
+}
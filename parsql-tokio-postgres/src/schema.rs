@@ -0,0 +1,32 @@
+//! Snapshot of the database schema for compile-time checking.
+//!
+//! [`dump`] reads the tables and columns of the database into a `schema.json` the
+//! derive macros check structs against when the `schema-check` feature is enabled. Run
+//! it after each migration and commit the file next to `Cargo.toml`:
+//!
+//! ```rust,no_run
+//! use parsql::tokio_postgres::schema;
+//!
+//! # async fn example(client: &tokio_postgres::Client) -> Result<(), Box<dyn std::error::Error>> {
+//! let schema = schema::dump(client).await?;
+//! std::fs::write("schema.json", schema.to_json())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use parsql_core::trace;
+use tokio_postgres::{Error, GenericClient};
+
+pub use parsql_core::schema::{Column, Schema};
+use parsql_core::schema::POSTGRES_COLUMNS;
+
+/// Reads the columns of every table and view outside the system schemas.
+pub async fn dump<C: GenericClient>(client: &C) -> Result<Schema, Error> {
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", POSTGRES_COLUMNS);
+
+    let mut schema = Schema::new();
+    for row in client.query(POSTGRES_COLUMNS, &[]).await? {
+        schema.add_column(row.get(0), row.get(1), row.get(2), row.get(3));
+    }
+    Ok(schema)
+}
@@ -0,0 +1,106 @@
+//! Per-call search path, role and row level security user.
+//!
+//! Schema-per-tenant deployments keep the same tables in one schema per tenant.
+//! [`with_search_path`] runs a closure inside a transaction whose `search_path` is
+//! set with `SET LOCAL`, so the unqualified table names of the generated queries
+//! resolve to the tenant's tables and the entities work unchanged:
+//!
+//! ```rust,no_run
+//! use parsql::tokio_postgres::{session::with_search_path, traits::CrudOps};
+//! # use parsql::tokio_postgres::{macros::{FromRow, Queryable, SqlParams}, traits::{FromRow, SqlParams, SqlQuery}};
+//! # use tokio_postgres::{types::ToSql, Error, Row};
+//! # #[derive(Queryable, SqlParams, FromRow)]
+//! # #[table("orders")]
+//! # #[where_clause("id = $")]
+//! # pub struct GetOrder { pub id: i64 }
+//! # async fn example(client: &mut tokio_postgres::Client) -> Result<(), Error> {
+//! let order = with_search_path(client, "tenant_42", |tx| {
+//!     Box::pin(async move { tx.fetch(GetOrder { id: 7 }).await })
+//! })
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`with_role`] does the same for the current role, e.g. to apply the row level
+//! security policies of a restricted role. [`with_rls_user`] stores the user a request
+//! acts for in `app.current_user_id`, which the policies of RLS-protected tables compare
+//! with their owner column:
+//!
+//! ```sql
+//! ALTER TABLE orders ENABLE ROW LEVEL SECURITY;
+//! CREATE POLICY orders_owner ON orders
+//!     USING (user_id = current_setting('app.current_user_id', true)::bigint);
+//! ```
+//!
+//! The `Queryable` types of such tables need no condition on the user; the database
+//! filters their rows. [`with_options`] applies the [`ExecOptions`] of one operation,
+//! such as a tighter `statement_timeout` for a report query. All of these settings end
+//! with the transaction.
+
+use std::fmt::Display;
+
+use futures_util::future::BoxFuture;
+use parsql_core::{session, trace};
+use tokio_postgres::{Client, Error, Transaction};
+
+pub use parsql_core::session::ExecOptions;
+
+/// Runs `f` in a transaction with `schemas` as the search path.
+///
+/// `schemas` is one schema or a comma separated list (`"tenant_42, public"`); each
+/// name is quoted, so it is matched case-sensitively. The transaction is committed
+/// when the future returned by `f` succeeds and rolled back when it fails.
+pub async fn with_search_path<T, E, F>(client: &mut Client, schemas: &str, f: F) -> Result<T, E>
+where
+    F: for<'t> FnOnce(&'t Transaction<'t>) -> BoxFuture<'t, Result<T, E>>,
+    E: From<Error>,
+{
+    scoped(client, &[("search_path", session::search_path(schemas))], f).await
+}
+
+/// Runs `f` in a transaction as `role`; see [`with_search_path`].
+pub async fn with_role<T, E, F>(client: &mut Client, role: &str, f: F) -> Result<T, E>
+where
+    F: for<'t> FnOnce(&'t Transaction<'t>) -> BoxFuture<'t, Result<T, E>>,
+    E: From<Error>,
+{
+    scoped(client, &[("role", role.to_string())], f).await
+}
+
+/// Runs `f` in a transaction on behalf of `user_id`, stored in the `app.current_user_id`
+/// setting read by row level security policies; see [`with_search_path`].
+pub async fn with_rls_user<T, E, F>(client: &mut Client, user_id: impl Display, f: F) -> Result<T, E>
+where
+    F: for<'t> FnOnce(&'t Transaction<'t>) -> BoxFuture<'t, Result<T, E>>,
+    E: From<Error>,
+{
+    scoped(client, &[(session::RLS_USER, user_id.to_string())], f).await
+}
+
+/// Runs `f` in a transaction with the settings of `options`; see [`with_search_path`].
+pub async fn with_options<T, E, F>(client: &mut Client, options: &ExecOptions, f: F) -> Result<T, E>
+where
+    F: for<'t> FnOnce(&'t Transaction<'t>) -> BoxFuture<'t, Result<T, E>>,
+    E: From<Error>,
+{
+    scoped(client, &options.settings(), f).await
+}
+
+async fn scoped<T, E, F>(client: &mut Client, settings: &[(&str, String)], f: F) -> Result<T, E>
+where
+    F: for<'t> FnOnce(&'t Transaction<'t>) -> BoxFuture<'t, Result<T, E>>,
+    E: From<Error>,
+{
+    let tx = client.transaction().await?;
+
+    for (setting, value) in settings {
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", session::SET_LOCAL);
+        tx.execute(session::SET_LOCAL, &[setting, value]).await?;
+    }
+
+    // Hata durumunda transaction düşürülürken geri alınır
+    let result = f(&tx).await?;
+    tx.commit().await?;
+    Ok(result)
+}
@@ -0,0 +1,193 @@
+//! Coalescing of concurrent identical fetches.
+//!
+//! [`SingleFlight`] wraps a client (or anything implementing `CrudOps`). When several
+//! tasks call `fetch` or `fetch_all` with the same result type and the same bound
+//! values (compared by their wire encoding) while a matching query is still running, only the first one reaches the
+//! database; the others wait for it and receive a clone of its result. This keeps
+//! a burst of requests for the same hot row (e.g. right after a cache entry expires)
+//! from turning into a burst of identical queries. Every other method reaches the
+//! wrapped client through `Deref`:
+//!
+//! ```rust,no_run
+//! use parsql_tokio_postgres::singleflight::SingleFlight;
+//! use parsql_tokio_postgres::macros::{FromRow, Queryable, SqlParams};
+//! use parsql_tokio_postgres::traits::{FromRow, SqlParams, SqlQuery};
+//! use std::sync::Arc;
+//! use parsql_tokio_postgres::ParsqlError;
+//! use tokio_postgres::{types::ToSql, Error, NoTls, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow, Clone, Debug)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! pub struct GetUser {
+//!     pub id: i32,
+//!     pub name: String,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), ParsqlError> {
+//!     let (client, connection) = tokio_postgres::connect("host=localhost user=postgres", NoTls).await?;
+//!     tokio::spawn(async move { connection.await });
+//!
+//!     let db = Arc::new(SingleFlight::new(client));
+//!     let (first, second) = tokio::join!(
+//!         db.fetch(GetUser { id: 1, name: String::new() }),
+//!         db.fetch(GetUser { id: 1, name: String::new() }),
+//!     );
+//!     // A single SELECT was sent for both calls
+//!     assert_eq!(first?.name, second?.name);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Errors are not shared: `ParsqlError` can't be cloned, so when the leading
+//! query fails (or its future is dropped) each waiting caller runs the query itself.
+
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use parsql_core::cache::{encode_postgres_params, CacheKey, CachedValue};
+use parsql_core::trace;
+use tokio::sync::broadcast;
+
+use crate::traits::{CrudOps, FromRow, SqlParams, SqlQuery};
+use crate::ParsqlError;
+
+type InFlight = Mutex<HashMap<CacheKey, broadcast::Sender<CachedValue>>>;
+
+/// A `CrudOps` implementation that runs concurrent identical fetches only once.
+pub struct SingleFlight<C> {
+    inner: C,
+    in_flight: InFlight,
+}
+
+impl<C> SingleFlight<C> {
+    /// Wraps `inner`.
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the wrapped client.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Joins the running query of `key` if there is one, otherwise registers the
+    /// caller as the one that runs it.
+    fn join(&self, key: CacheKey) -> Flight<'_> {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some(sender) = in_flight.get(&key) {
+            return Flight::Waiting(sender.subscribe());
+        }
+        let (sender, _) = broadcast::channel(1);
+        in_flight.insert(key, sender);
+        Flight::Leading(Leader {
+            in_flight: &self.in_flight,
+            key,
+        })
+    }
+}
+
+impl<C> Deref for SingleFlight<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.inner
+    }
+}
+
+enum Flight<'a> {
+    Leading(Leader<'a>),
+    Waiting(broadcast::Receiver<CachedValue>),
+}
+
+/// Owns the in-flight entry of a query; dropping it without `finish` (an error or a
+/// cancelled future) closes the channel so the waiting callers run the query themselves.
+struct Leader<'a> {
+    in_flight: &'a InFlight,
+    key: CacheKey,
+}
+
+impl Leader<'_> {
+    fn finish(self, value: CachedValue) {
+        let sender = self.in_flight.lock().unwrap().remove(&self.key);
+        // Drop, bu arada aynı anahtarla başlayan yeni sorgunun kaydını silmemeli
+        std::mem::forget(self);
+        if let Some(sender) = sender {
+            // Bekleyen kimse yoksa gönderim hata döner, bu normaldir
+            let _ = sender.send(value);
+        }
+    }
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        self.in_flight.lock().unwrap().remove(&self.key);
+    }
+}
+
+impl<C> SingleFlight<C>
+where
+    C: CrudOps + Sync,
+{
+    /// Retrieves a single record, sharing the result of an identical fetch that is already running.
+    pub async fn fetch<T>(&self, params: T) -> Result<T, ParsqlError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Clone + Send + Sync + 'static,
+    {
+        // Kodlanamayan değerlerle sorgu paylaşılmadan çalışır
+        let Some(values) = encode_postgres_params(&params.params()) else {
+            return self.inner.fetch(params).await;
+        };
+        match self.join(CacheKey::of::<T>(T::query(), &values)) {
+            Flight::Leading(leader) => {
+                let result = self.inner.fetch(params).await?;
+                leader.finish(Arc::new(result.clone()));
+                Ok(result)
+            }
+            Flight::Waiting(mut receiver) => {
+                if let Some(value) = shared::<T>(&mut receiver).await {
+                    return Ok(value);
+                }
+                self.inner.fetch(params).await
+            }
+        }
+    }
+
+    /// Retrieves all matching records, sharing the result of an identical fetch that is already running.
+    pub async fn fetch_all<T>(&self, params: T) -> Result<Vec<T>, ParsqlError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Clone + Send + Sync + 'static,
+    {
+        // Kodlanamayan değerlerle sorgu paylaşılmadan çalışır
+        let Some(values) = encode_postgres_params(&params.params()) else {
+            return self.inner.fetch_all(params).await;
+        };
+        match self.join(CacheKey::of::<Vec<T>>(T::query(), &values)) {
+            Flight::Leading(leader) => {
+                let result = self.inner.fetch_all(params).await?;
+                leader.finish(Arc::new(result.clone()));
+                Ok(result)
+            }
+            Flight::Waiting(mut receiver) => {
+                if let Some(value) = shared::<Vec<T>>(&mut receiver).await {
+                    return Ok(value);
+                }
+                self.inner.fetch_all(params).await
+            }
+        }
+    }
+}
+
+/// Waits for the leading query; `None` when it failed or was cancelled.
+async fn shared<R: Clone + 'static>(receiver: &mut broadcast::Receiver<CachedValue>) -> Option<R> {
+    let value = receiver.recv().await.ok()?.downcast_ref::<R>().cloned();
+    if value.is_some() {
+        trace::log("PARSQL-TOKIO-POSTGRES-SINGLEFLIGHT", "Joined an in-flight query");
+    }
+    value
+}
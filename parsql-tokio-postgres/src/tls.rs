@@ -0,0 +1,135 @@
+//! TLS connection helpers.
+//!
+//! Builds the connector of the enabled TLS backend (`native-tls` or `rustls`
+//! feature) and connects a `tokio_postgres::Client` with it. The connection
+//! task is spawned on the current tokio runtime.
+//!
+//! ```rust,no_run
+//! use parsql_tokio_postgres::tls::{connect_tls, TlsOptions};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let options = TlsOptions {
+//!         root_cert_pem: Some(std::fs::read("ca.pem")?),
+//!         ..Default::default()
+//!     };
+//!     let client = connect_tls("host=db.example.com user=postgres sslmode=require", &options).await?;
+//!     client.execute("SELECT 1", &[]).await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::fmt;
+
+use parsql_core::trace;
+use tokio_postgres::{Client, Error};
+
+pub use parsql_core::tls::{ConnectorError, TlsOptions};
+#[cfg(feature = "native-tls")]
+pub use parsql_core::tls::native_tls_connector;
+#[cfg(feature = "rustls")]
+pub use parsql_core::tls::rustls_connector;
+
+/// Errors that can occur while opening a TLS connection.
+#[derive(Debug)]
+pub enum TlsError {
+    /// The TLS connector could not be built (invalid certificate, unsupported option, ...)
+    Connector(ConnectorError),
+    /// The database connection failed
+    Postgres(Error),
+}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsError::Connector(e) => write!(f, "TLS connector error: {}", e),
+            TlsError::Postgres(e) => write!(f, "PostgreSQL error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TlsError::Connector(e) => Some(e),
+            TlsError::Postgres(e) => Some(e),
+        }
+    }
+}
+
+impl From<ConnectorError> for TlsError {
+    fn from(e: ConnectorError) -> Self {
+        TlsError::Connector(e)
+    }
+}
+
+impl From<Error> for TlsError {
+    fn from(e: Error) -> Self {
+        TlsError::Postgres(e)
+    }
+}
+
+/// Connects over `native-tls` and spawns the connection task.
+#[cfg(feature = "native-tls")]
+pub async fn connect_native_tls(params: &str, options: &TlsOptions) -> Result<Client, TlsError> {
+    let connector = native_tls_connector(options)?;
+    let (client, connection) = tokio_postgres::connect(params, connector).await?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            trace::log("PARSQL-TOKIO-POSTGRES", format_args!("connection error: {}", e));
+        }
+    });
+    Ok(client)
+}
+
+/// Connects over `rustls` and spawns the connection task.
+#[cfg(feature = "rustls")]
+pub async fn connect_rustls(params: &str, options: &TlsOptions) -> Result<Client, TlsError> {
+    let connector = rustls_connector(options)?;
+    let (client, connection) = tokio_postgres::connect(params, connector).await?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            trace::log("PARSQL-TOKIO-POSTGRES", format_args!("connection error: {}", e));
+        }
+    });
+    Ok(client)
+}
+
+/// Connects over TLS using the enabled backend.
+/// When both features are enabled `native-tls` is used.
+///
+/// # Arguments
+/// * `params` - Connection string, e.g. `"host=localhost user=postgres sslmode=require"`
+/// * `options` - TLS options
+///
+/// # Returns
+/// * `Result<Client, TlsError>` - Connected client or an error
+pub async fn connect_tls(params: &str, options: &TlsOptions) -> Result<Client, TlsError> {
+    #[cfg(feature = "native-tls")]
+    {
+        connect_native_tls(params, options).await
+    }
+    #[cfg(not(feature = "native-tls"))]
+    {
+        connect_rustls(params, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_connector_and_connection_errors() {
+        let options = TlsOptions {
+            root_cert_pem: Some(b"not a certificate".to_vec()),
+            ..Default::default()
+        };
+        let error = connect_tls("host=localhost user=postgres", &options).await.err().unwrap();
+        assert!(matches!(error, TlsError::Connector(_)));
+
+        // Geçersiz bağlantı dizesi sunucuya gidilmeden reddedilir
+        let error = connect_tls("port=not-a-port", &TlsOptions::default()).await.err().unwrap();
+        assert!(matches!(error, TlsError::Postgres(_)));
+    }
+}
@@ -1,7 +1,8 @@
 use postgres::types::FromSql;
-use tokio_postgres::{Error, Row, Client, Transaction};
-use std::sync::OnceLock;
-use crate::traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams};
+use tokio_postgres::{error::SqlState, Error, Row, Client, Transaction};
+use parsql_core::{metrics::observe_async, temp::create_temp_as, trace, Dialect, OnError, Progress};
+use crate::traits::{CrudOps, FromRow, ParentKey, SqlParams, SqlQuery, UpdateParams};
+use crate::ParsqlError;
 
 /// Creates and begins a new transaction.
 /// 
@@ -31,6 +32,55 @@ pub async fn begin(client: &mut Client) -> Result<Transaction<'_>, Error> {
     client.transaction().await
 }
 
+/// Rolls a transaction back explicitly.
+/// 
+/// Dropping a transaction also rolls it back, but silently, in a background task that
+/// reports no error. On error paths this makes the rollback visible and returns its
+/// failure. `commit` and `rollback` take the transaction by value, so a completed
+/// transaction can't be used again.
+/// 
+/// # Return Value
+/// * `Result<(), Error>` - Error when the ROLLBACK fails
+/// 
+/// # Example
+/// ```rust,no_run
+/// # use tokio_postgres::{NoTls, Error};
+/// # use parsql_tokio_postgres::transactional;
+/// # 
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let (mut client, connection) = tokio_postgres::connect("", NoTls).await?;
+/// # tokio::spawn(async move { connection.await; });
+/// let transaction = transactional::begin(&mut client).await?;
+/// 
+/// if let Err(e) = transaction.batch_execute("UPDATE users SET state = 0").await {
+///     transactional::rollback(transaction).await?;
+///     return Err(e.into());
+/// }
+/// transaction.commit().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn rollback(transaction: Transaction<'_>) -> Result<(), Error> {
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", "ROLLBACK");
+    transaction.rollback().await
+}
+
+/// Checks whether a transaction is aborted.
+/// 
+/// After a failed statement PostgreSQL rejects everything but a rollback with
+/// `25P02 in_failed_sql_transaction`. This asks the server with a trivial query; when
+/// it returns `true` the transaction can only be passed to [`rollback`].
+/// 
+/// # Return Value
+/// * `Result<bool, Error>` - `true` for an aborted transaction; Error when the connection fails
+pub async fn is_aborted(transaction: &Transaction<'_>) -> Result<bool, Error> {
+    match transaction.batch_execute("SELECT 1").await {
+        Ok(()) => Ok(false),
+        Err(e) if e.code() == Some(&SqlState::IN_FAILED_SQL_TRANSACTION) => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
 /// Inserts a record within a transaction.
 /// 
 /// This function executes an INSERT SQL query within the given transaction.
@@ -41,7 +91,7 @@ pub async fn begin(client: &mut Client) -> Result<Transaction<'_>, Error> {
 /// * `entity` - Data object to be inserted (must implement SqlQuery and SqlParams traits)
 /// 
 /// # Return Value
-/// * `Result<(Transaction<'_>, u64), Error>` - On success, returns the transaction and the number of affected rows; on failure, returns Error
+/// * `Result<(Transaction<'_>, u64), ParsqlError>` - On success, returns the transaction and the number of affected rows; on failure, returns ParsqlError
 ///
 /// # Example
 /// ```rust,no_run
@@ -73,26 +123,116 @@ pub async fn begin(client: &mut Client) -> Result<Transaction<'_>, Error> {
 pub async fn tx_insert<T>(
     transaction: Transaction<'_>,
     entity: T,
-) -> Result<(Transaction<'_>, u64), Error>
+) -> Result<(Transaction<'_>, u64), ParsqlError>
 where
     T: SqlQuery + SqlParams + Send + Sync + 'static
 {
+    entity.validation()?;
+
     let sql = T::query();
     
-    static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-    let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-        std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-    });
-    
-    if is_trace_enabled {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let params = entity.params();
-    let result = transaction.execute(&sql, &params).await?;
+    let result = transaction.execute(sql, &params).await?;
     Ok((transaction, result))
 }
 
+/// Inserts several records within a transaction, one statement per record, reporting
+/// progress after each one.
+///
+/// With `OnError::Stop` the first failing record ends the batch; PostgreSQL rejects every
+/// further command of the transaction after an error, so roll it back. With
+/// `OnError::Continue` each record runs in a savepoint: a failing record is rolled back
+/// and the transaction stays usable.
+///
+/// # Arguments
+/// * `transaction` - An active transaction
+/// * `entities` - Data objects to insert (must implement SqlQuery and SqlParams traits)
+/// * `on_error` - Whether to stop at the first failing record
+/// * `on_progress` - Called after every record
+///
+/// # Return Value
+/// * `Result<(Transaction<'_>, Vec<Result<u64, ParsqlError>>), Error>` - On success, returns the transaction and the result of every attempted record; an error if a savepoint command fails
+///
+/// # Example
+/// ```rust,no_run
+/// # use tokio_postgres::{NoTls, Error};
+/// # use parsql::tokio_postgres::{transactional, OnError};
+/// # use parsql::macros::{Insertable, SqlParams};
+/// #
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// struct InsertUser {
+///     name: String,
+///     email: String,
+/// }
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let (mut client, connection) = tokio_postgres::connect("", NoTls).await?;
+/// # tokio::spawn(async move { connection.await; });
+/// let users = vec![
+///     InsertUser { name: "John".to_string(), email: "john@example.com".to_string() },
+///     InsertUser { name: "Jane".to_string(), email: "jane@example.com".to_string() },
+/// ];
+///
+/// let transaction = transactional::begin(&mut client).await?;
+/// let (transaction, results) = transactional::tx_insert_all(transaction, users, OnError::Continue, |progress| {
+///     println!("{}/{} ({} failed)", progress.done, progress.total, progress.failed);
+/// }).await?;
+/// transaction.commit().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn tx_insert_all<T>(
+    transaction: Transaction<'_>,
+    entities: Vec<T>,
+    on_error: OnError,
+    mut on_progress: impl FnMut(Progress),
+) -> Result<(Transaction<'_>, Vec<Result<u64, ParsqlError>>), Error>
+where
+    T: SqlQuery + SqlParams + Send + Sync + 'static
+{
+    let total = entities.len();
+    let mut results = Vec::with_capacity(total);
+    let mut failed = 0;
+    let sql = T::query();
+
+    for entity in entities {
+        if on_error == OnError::Continue {
+            transaction.batch_execute("SAVEPOINT parsql_insert_all").await?;
+        }
+
+        let result = match entity.validation() {
+            Ok(()) => {
+                trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
+                transaction.execute(sql, &entity.params()).await.map_err(ParsqlError::from)
+            }
+            Err(e) => Err(e.into()),
+        };
+
+        if on_error == OnError::Continue {
+            // A failed record is rolled back to the savepoint so the transaction stays usable
+            let release = if result.is_ok() {
+                "RELEASE SAVEPOINT parsql_insert_all"
+            } else {
+                "ROLLBACK TO SAVEPOINT parsql_insert_all"
+            };
+            transaction.batch_execute(release).await?;
+        }
+
+        let stop = result.is_err() && on_error == OnError::Stop;
+        failed += result.is_err() as usize;
+        results.push(result);
+        on_progress(Progress { done: results.len(), total, failed });
+        if stop {
+            break;
+        }
+    }
+
+    Ok((transaction, results))
+}
+
 /// Updates a record within a transaction.
 /// 
 /// # Arguments
@@ -100,7 +240,7 @@ where
 /// * `entity` - Data object containing the update information (must implement SqlQuery and UpdateParams traits)
 /// 
 /// # Return Value
-/// * `Result<(Transaction<'_>, bool), Error>` - On success, returns the transaction and whether any record was updated
+/// * `Result<(Transaction<'_>, u64), ParsqlError>` - On success, returns the transaction and the number of updated records
 ///
 /// # Example
 /// ```rust,no_run
@@ -128,7 +268,7 @@ where
 /// };
 ///
 /// let transaction = transactional::begin(&client).await?;
-/// let (transaction, updated) = transactional::tx_update(transaction, user).await?;
+/// let (transaction, rows_affected) = transactional::tx_update(transaction, user).await?;
 /// transaction.commit().await?;
 /// # Ok(())
 /// # }
@@ -136,24 +276,19 @@ where
 pub async fn tx_update<T>(
     transaction: Transaction<'_>,
     entity: T,
-) -> Result<(Transaction<'_>, bool), Error>
+) -> Result<(Transaction<'_>, u64), ParsqlError>
 where
     T: SqlQuery + UpdateParams + Send + Sync + 'static
 {
+    entity.validation()?;
+
     let sql = T::query();
     
-    static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-    let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-        std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-    });
-    
-    if is_trace_enabled {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let params = entity.params();
-    let result = transaction.execute(&sql, &params).await?;
-    Ok((transaction, result > 0))
+    let result = transaction.execute(sql, &params).await?;
+    Ok((transaction, result))
 }
 
 /// Deletes a record within a transaction.
@@ -198,17 +333,10 @@ where
 {
     let sql = T::query();
     
-    static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-    let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-        std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-    });
-    
-    if is_trace_enabled {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let params = entity.params();
-    let result = transaction.execute(&sql, &params).await?;
+    let result = transaction.execute(sql, &params).await?;
     Ok((transaction, result))
 }
 
@@ -258,19 +386,12 @@ pub async fn tx_fetch<T>(
 where
     T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static
 {
-    let sql = T::query();
+    let sql = T::query_single();
     
-    static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-    let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-        std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-    });
-    
-    if is_trace_enabled {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let query_params = params.params();
-    let row = transaction.query_one(&sql, &query_params).await?;
+    let row = transaction.query_one(sql, &query_params).await?;
     let result = T::from_row(&row)?;
     Ok((transaction, result))
 }
@@ -325,22 +446,12 @@ where
 {
     let sql = T::query();
     
-    static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-    let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-        std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-    });
-    
-    if is_trace_enabled {
-        println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-    }
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
     let query_params = params.params();
-    let rows = transaction.query(&sql, &query_params).await?;
+    let rows = transaction.query(sql, &query_params).await?;
     
-    let mut results = Vec::with_capacity(rows.len());
-    for row in rows {
-        results.push(T::from_row(&row)?);
-    }
+    let results = T::from_rows(&rows)?;
     
     Ok((transaction, results))
 }
@@ -395,6 +506,115 @@ where
     tx_fetch_all(transaction, params).await
 }
 
+/// Fills a temporary table with the result of a query.
+///
+/// The table is created `ON COMMIT DROP`, so it lives as long as the transaction.
+/// Statements later in the transaction reach it by name, e.g. a `Queryable` with
+/// `#[join("JOIN tmp_active ON tmp_active.id = orders.user_id")]`, which lets an
+/// ETL-style job stage a large intermediate set once.
+///
+/// # Arguments
+/// * `transaction` - An active transaction
+/// * `entity` - Query whose rows fill the table (must implement SqlQuery and SqlParams traits)
+/// * `name` - Name of the temporary table
+///
+/// # Return Value
+/// * `Result<u64, Error>` - On success, returns the number of rows written to the table
+///
+/// # Panics
+/// If `name` is not a plain table name.
+///
+/// # Example
+/// ```rust,ignore
+/// let transaction = transactional::begin(&mut client).await?;
+/// transactional::create_temp_from(&transaction, &ActiveUsers { state: 1 }, "tmp_active").await?;
+/// let orders = transaction.fetch_all(&OrdersOfActiveUsers {}).await?;
+/// transaction.commit().await?;
+/// ```
+pub async fn create_temp_from<T>(transaction: &Transaction<'_>, entity: &T, name: &str) -> Result<u64, Error>
+where
+    T: SqlQuery + SqlParams + Send + Sync,
+{
+    let sql = create_temp_as(T::query(), name, Dialect::Postgres)
+        .unwrap_or_else(|| panic!("create_temp_from: `{}` is not a valid temporary table name", name));
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", &sql);
+    transaction.execute(sql.as_str(), &entity.params()).await
+}
+
+/// Inserts a parent record and its children atomically.
+///
+/// The parent is inserted with `RETURNING` and the returned key is written into each
+/// child's `#[parent_key("...")]` field before the child is inserted. Everything runs in
+/// one transaction, which is rolled back if any insert fails.
+///
+/// # Arguments
+/// * `client` - Database client
+/// * `parent` - Parent record (needs a `#[returning("...")]` attribute)
+/// * `children` - Child records (need a `#[parent_key("...")]` attribute)
+///
+/// # Return Value
+/// * `Result<C::Key, Error>` - On success, returns the parent's key; on failure, returns Error
+///
+/// # Example
+/// ```rust,no_run
+/// # use tokio_postgres::{NoTls, Error};
+/// # use parsql::tokio_postgres::transactional;
+/// # use parsql::macros::{Insertable, SqlParams};
+/// #
+/// #[derive(Insertable, SqlParams)]
+/// #[table("users")]
+/// #[returning("id")]
+/// struct InsertUser {
+///     name: String,
+/// }
+///
+/// #[derive(Insertable, SqlParams)]
+/// #[table("posts")]
+/// #[parent_key("user_id")]
+/// struct InsertPost {
+///     user_id: i32,
+///     content: String,
+/// }
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let (mut client, connection) = tokio_postgres::connect("", NoTls).await?;
+/// # tokio::spawn(async move { connection.await; });
+/// let user_id = transactional::persist_graph(
+///     &mut client,
+///     InsertUser { name: "John".to_string() },
+///     vec![
+///         InsertPost { user_id: 0, content: "Hello".to_string() },
+///         InsertPost { user_id: 0, content: "World".to_string() },
+///     ],
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn persist_graph<P, C>(client: &mut Client, parent: P, children: Vec<C>) -> Result<C::Key, Error>
+where
+    P: SqlQuery + SqlParams + Send + Sync + 'static,
+    C: SqlQuery + SqlParams + ParentKey + Send + Sync + 'static,
+    C::Key: for<'a> FromSql<'a> + Clone + Send + Sync,
+{
+    let transaction = client.transaction().await?;
+
+    let sql = P::query();
+    trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
+    let row = transaction.query_one(sql, &parent.params()).await?;
+    let key: C::Key = row.try_get(0)?;
+
+    let sql = C::query();
+    for mut child in children {
+        child.set_parent_key(key.clone());
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
+        transaction.execute(sql, &child.params()).await?;
+    }
+
+    transaction.commit().await?;
+    Ok(key)
+}
+
 /// Implementation of the CrudOps trait for Transactions
 ///
 /// This implementation allows using the `CrudOps` trait methods directly on 
@@ -435,114 +655,89 @@ where
 /// ```
 #[async_trait::async_trait]
 impl<'a> CrudOps for Transaction<'a> {
-    async fn insert<T, P:for<'b> FromSql<'b> + Send + Sync>(&self, entity: T) -> Result<P, Error>
+    async fn insert<T, P:for<'b> FromSql<'b> + Send + Sync>(&self, entity: T) -> Result<P, ParsqlError>
     where
         T: SqlQuery + SqlParams + Send + Sync + 'static,
     {
+        entity.validation()?;
+
         let sql = T::query();
         
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-            std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-        });
-        
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
         let params = entity.params();
-        let row = self.query_one(&sql, &params).await?;
-        row.try_get::<_, P>(0)
+        let row = observe_async("insert", T::table_name(), self.query_one(sql, &params)).await?;
+        Ok(row.try_get::<_, P>(0)?)
     }
 
-    async fn update<T>(&self, entity: T) -> Result<bool, Error>
+    async fn update<T>(&self, entity: T) -> Result<u64, ParsqlError>
     where
         T: SqlQuery + UpdateParams + Send + Sync + 'static,
     {
+        entity.validation()?;
+
         let sql = T::query();
         
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-            std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-        });
-        
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
         let params = entity.params();
-        let result = self.execute(&sql, &params).await?;
-        Ok(result > 0)
+        Ok(observe_async("update", T::table_name(), self.execute(sql, &params)).await?)
     }
 
-    async fn delete<T>(&self, entity: T) -> Result<u64, Error>
+    async fn delete<T>(&self, entity: T) -> Result<u64, ParsqlError>
     where
         T: SqlQuery + SqlParams + Send + Sync + 'static,
     {
         let sql = T::query();
         
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-            std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-        });
-        
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
         let params = entity.params();
-        self.execute(&sql, &params).await
+        Ok(observe_async("delete", T::table_name(), self.execute(sql, &params)).await?)
     }
 
-    async fn fetch<T>(&self, params: T) -> Result<T, Error>
+    async fn call<T>(&self, entity: T) -> Result<u64, ParsqlError>
     where
-        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+        T: SqlQuery + SqlParams + Send + Sync + 'static,
     {
         let sql = T::query();
         
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-            std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-        });
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
+
+        let params = entity.params();
+        Ok(observe_async("call", T::table_name(), self.execute(sql, &params)).await?)
+    }
+
+    async fn fetch<T>(&self, params: T) -> Result<T, ParsqlError>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+    {
+        let sql = T::query_single();
         
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
         let query_params = params.params();
-        let row = self.query_one(&sql, &query_params).await?;
-        T::from_row(&row)
+        let row = observe_async("fetch", T::table_name(), self.query_one(sql, &query_params)).await?;
+        Ok(T::from_row(&row)?)
     }
 
-    async fn fetch_all<T>(&self, params: T) -> Result<Vec<T>, Error>
+    async fn fetch_all<T>(&self, params: T) -> Result<Vec<T>, ParsqlError>
     where
         T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
     {
         let sql = T::query();
         
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-            std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-        });
-        
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
         let query_params = params.params();
-        let rows = self.query(&sql, &query_params).await?;
+        let rows = observe_async("fetch_all", T::table_name(), self.query(sql, &query_params)).await?;
         
-        let mut results = Vec::with_capacity(rows.len());
-        for row in rows {
-            results.push(T::from_row(&row)?);
-        }
-        
-        Ok(results)
+        Ok(T::from_rows(&rows)?)
     }
     
     // Use #[allow(deprecated)] to suppress warnings when implementing deprecated methods
     #[allow(deprecated)]
-    async fn get<T>(&self, params: T) -> Result<T, Error>
+    async fn get<T>(&self, params: T) -> Result<T, ParsqlError>
     where
         T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
     {
@@ -551,36 +746,29 @@ impl<'a> CrudOps for Transaction<'a> {
 
     // Use #[allow(deprecated)] to suppress warnings when implementing deprecated methods
     #[allow(deprecated)]
-    async fn get_all<T>(&self, params: T) -> Result<Vec<T>, Error>
+    async fn get_all<T>(&self, params: T) -> Result<Vec<T>, ParsqlError>
     where
         T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
     {
         self.fetch_all(params).await
     }
 
-    async fn select<T, F, R>(&self, entity: T, to_model: F) -> Result<R, Error>
+    async fn select<T, F, R>(&self, entity: T, to_model: F) -> Result<R, ParsqlError>
     where
         T: SqlQuery + SqlParams + Send + Sync + 'static,
         F: Fn(&Row) -> Result<R, Error> + Send + Sync + 'static,
         R: Send + 'static,
     {
-        let sql = T::query();
-        
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-            std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-        });
+        let sql = T::query_single();
         
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
         let params = entity.params();
-        let row = self.query_one(&sql, &params).await?;
-        to_model(&row)
+        let row = observe_async("select", T::table_name(), self.query_one(sql, &params)).await?;
+        Ok(to_model(&row)?)
     }
 
-    async fn select_all<T, F, R>(&self, entity: T, to_model: F) -> Result<Vec<R>, Error>
+    async fn select_all<T, F, R>(&self, entity: T, to_model: F) -> Result<Vec<R>, ParsqlError>
     where
         T: SqlQuery + SqlParams + Send + Sync + 'static,
         F: Fn(&Row) -> R + Send + Sync + 'static,
@@ -588,17 +776,10 @@ impl<'a> CrudOps for Transaction<'a> {
     {
         let sql = T::query();
         
-        static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-        let is_trace_enabled = *TRACE_ENABLED.get_or_init(|| {
-            std::env::var("PARSQL_TRACE").unwrap_or_default() == "1"
-        });
-        
-        if is_trace_enabled {
-            println!("[PARSQL-TOKIO-POSTGRES-TX] Execute SQL: {}", sql);
-        }
+        trace::log_sql("PARSQL-TOKIO-POSTGRES-TX", sql);
 
         let params = entity.params();
-        let rows = self.query(&sql, &params).await?;
+        let rows = observe_async("select_all", T::table_name(), self.query(sql, &params)).await?;
         
         let mut results = Vec::with_capacity(rows.len());
         for row in &rows {
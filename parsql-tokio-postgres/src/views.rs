@@ -0,0 +1,39 @@
+//! Materialized view refresh.
+//!
+//! A `Queryable` annotated with `#[materialized_view("...")]` selects from the view and
+//! implements [`MaterializedView`], so reporting pipelines can rebuild the view and read
+//! it with the same struct:
+//!
+//! ```rust,no_run
+//! use parsql::tokio_postgres::{fetch_all, views::refresh_materialized_view};
+//! # use parsql::tokio_postgres::{macros::{FromRow, Queryable, SqlParams}, traits::{FromRow, MaterializedView, SqlParams, SqlQuery}};
+//! # use tokio_postgres::{types::ToSql, Error, Row};
+//! # #[derive(Queryable, SqlParams, FromRow)]
+//! # #[materialized_view("daily_sales")]
+//! # #[where_clause("day >= $")]
+//! # pub struct DailySales { pub day: i32, pub total: i64 }
+//! # async fn example(client: &tokio_postgres::Client) -> Result<(), Error> {
+//! refresh_materialized_view::<DailySales, _>(client, true).await?;
+//! let sales = fetch_all(client, DailySales { day: 20240101, total: 0 }).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use parsql_core::{trace, views};
+use tokio_postgres::{Error, GenericClient};
+
+use crate::traits::MaterializedView;
+
+/// Rebuilds the materialized view of `T`.
+///
+/// With `concurrently` readers are not blocked while the view is rebuilt; PostgreSQL
+/// then needs a unique index on the view and rejects the statement inside a transaction.
+pub async fn refresh_materialized_view<T: MaterializedView, C: GenericClient + Sync>(
+    client: &C,
+    concurrently: bool,
+) -> Result<(), Error> {
+    let sql = views::refresh_statement(T::view_name(), concurrently);
+    trace::log_sql("PARSQL-TOKIO-POSTGRES", &sql);
+
+    client.batch_execute(&sql).await
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+
+use parsql_sqlite::SqliteOptions;
+use rusqlite::{ffi, Error};
+use tokio::sync::oneshot;
+
+type Job = Box<dyn FnOnce(&mut rusqlite::Connection) + Send>;
+
+/// Asynchronous handle to a SQLite connection.
+///
+/// The `rusqlite::Connection` lives on a dedicated worker thread; every call is sent
+/// to that thread and awaited, so blocking SQLite I/O never runs on the async runtime.
+/// The handle is cheap to clone and all clones share the same connection. The worker
+/// stops and the connection closes when the last clone is dropped.
+#[derive(Clone)]
+pub struct Connection {
+    sender: mpsc::Sender<Job>,
+}
+
+impl Connection {
+    /// Opens the database at `path` with [`SqliteOptions::default`].
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::open_with(path, SqliteOptions::default()).await
+    }
+
+    /// Opens the database at `path` and applies `options` to the connection.
+    pub async fn open_with(path: impl AsRef<Path>, options: SqliteOptions) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        Self::start(move || parsql_sqlite::open_with(&path, &options)).await
+    }
+
+    /// Opens an in-memory database.
+    pub async fn open_in_memory() -> Result<Self, Error> {
+        Self::start(rusqlite::Connection::open_in_memory).await
+    }
+
+    /// Moves an already configured connection onto a worker thread.
+    pub async fn from_connection(conn: rusqlite::Connection) -> Result<Self, Error> {
+        Self::start(move || Ok(conn)).await
+    }
+
+    async fn start<F>(open: F) -> Result<Self, Error>
+    where
+        F: FnOnce() -> Result<rusqlite::Connection, Error> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let (ready, opened) = oneshot::channel();
+
+        thread::Builder::new()
+            .name("parsql-sqlite".to_string())
+            .spawn(move || {
+                let mut conn = match open() {
+                    Ok(conn) => {
+                        let _ = ready.send(Ok(()));
+                        conn
+                    }
+                    Err(e) => {
+                        let _ = ready.send(Err(e));
+                        return;
+                    }
+                };
+                // Tüm handle'lar bırakıldığında döngü biter ve bağlantı kapanır
+                for job in receiver {
+                    job(&mut conn);
+                }
+            })
+            .map_err(|e| worker_error(format!("failed to start connection thread: {}", e)))?;
+
+        opened.await.map_err(|_| stopped())??;
+        Ok(Self { sender })
+    }
+
+    /// Runs `f` with the underlying connection on the worker thread.
+    ///
+    /// Use it for anything the `CrudOps` methods don't cover, such as transactions:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: parsql_tokio_sqlite::Connection) -> Result<(), rusqlite::Error> {
+    /// conn.call(|conn| {
+    ///     let tx = conn.transaction()?;
+    ///     tx.execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1", [])?;
+    ///     tx.execute("UPDATE accounts SET balance = balance + 10 WHERE id = 2", [])?;
+    ///     tx.commit()
+    /// })
+    /// .await
+    /// # }
+    /// ```
+    pub async fn call<F, R>(&self, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut rusqlite::Connection) -> Result<R, Error> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        self.sender
+            .send(Box::new(move |conn| {
+                let _ = reply.send(f(conn));
+            }))
+            .map_err(|_| stopped())?;
+
+        // İş parçacığı panik ile sonlanırsa cevap kanalı kapanır
+        result.await.map_err(|_| stopped())?
+    }
+}
+
+fn stopped() -> Error {
+    worker_error("connection thread has stopped".to_string())
+}
+
+fn worker_error(message: String) -> Error {
+    Error::SqliteFailure(ffi::Error::new(ffi::SQLITE_MISUSE), Some(message))
+}
@@ -0,0 +1,153 @@
+use parsql_sqlite::traits::CrudOps as SyncCrudOps;
+use rusqlite::types::{FromSql, ToSql, ToSqlOutput, Value};
+use rusqlite::{Error, Row};
+
+use crate::traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams};
+use crate::Connection;
+
+// Her işlem, parsql-sqlite'ın senkron implementasyonunu bağlantının iş parçacığında çalıştırır
+#[async_trait::async_trait]
+impl CrudOps for Connection {
+    async fn insert<T, P>(&self, entity: T) -> Result<P, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static,
+        P: FromSql + Send + Sync + 'static,
+    {
+        self.call(move |conn| SyncCrudOps::insert(&*conn, entity)).await
+    }
+
+    async fn insert_execute<T>(&self, entity: T) -> Result<usize, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static,
+    {
+        self.call(move |conn| SyncCrudOps::insert_execute(&*conn, entity)).await
+    }
+
+    async fn update<T>(&self, entity: T) -> Result<u64, Error>
+    where
+        T: SqlQuery + UpdateParams + Send + 'static,
+    {
+        self.call(move |conn| SyncCrudOps::update(&*conn, entity)).await
+    }
+
+    async fn delete<T>(&self, entity: T) -> Result<u64, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static,
+    {
+        self.call(move |conn| SyncCrudOps::delete(&*conn, entity)).await
+    }
+
+    async fn fetch<T>(&self, params: &T) -> Result<T, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+    {
+        let bound = Bound::<T>::of(params)?;
+        let found = self.call(move |conn| SyncCrudOps::fetch(&*conn, &bound)).await?;
+        Ok(found.into_row())
+    }
+
+    async fn fetch_all<T>(&self, params: &T) -> Result<Vec<T>, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static,
+    {
+        let bound = Bound::<T>::of(params)?;
+        let found = self.call(move |conn| SyncCrudOps::fetch_all(&*conn, &bound)).await?;
+        Ok(found.into_iter().map(Bound::into_row).collect())
+    }
+
+    async fn select<T, F, R>(&self, entity: T, to_model: F) -> Result<R, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static,
+        F: Fn(&Row) -> Result<R, Error> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.call(move |conn| SyncCrudOps::select(&*conn, &entity, to_model)).await
+    }
+
+    async fn select_all<T, F, R>(&self, entity: T, to_model: F) -> Result<Vec<R>, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static,
+        F: Fn(&Row) -> Result<R, Error> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.call(move |conn| SyncCrudOps::select_all(&*conn, &entity, to_model)).await
+    }
+}
+
+/// A query of type `T` whose parameters were copied out of a borrowed `T`, so that it
+/// can be moved to the worker thread; the rows it reads are `T`s.
+struct Bound<T> {
+    values: Vec<Value>,
+    row: Option<T>,
+}
+
+impl<T: SqlParams> Bound<T> {
+    fn of(params: &T) -> Result<Self, Error> {
+        let values = params
+            .params()
+            .into_iter()
+            .map(|value| match value.to_sql()? {
+                ToSqlOutput::Borrowed(value) => Ok(value.into()),
+                ToSqlOutput::Owned(value) => Ok(value),
+                _ => Err(Error::ToSqlConversionFailure(
+                    "parameters must produce a plain value".into(),
+                )),
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self { values, row: None })
+    }
+}
+
+impl<T> Bound<T> {
+    fn into_row(self) -> T {
+        self.row.expect("rows are read through FromRow")
+    }
+}
+
+impl<T: SqlQuery> SqlQuery for Bound<T> {
+    fn query() -> &'static str {
+        T::query()
+    }
+
+    fn query_single() -> &'static str {
+        T::query_single()
+    }
+
+    fn table_name() -> &'static str {
+        T::table_name()
+    }
+
+    fn param_count() -> usize {
+        T::param_count()
+    }
+
+    fn param_names() -> &'static [&'static str] {
+        T::param_names()
+    }
+}
+
+impl<T> SqlParams for Bound<T> {
+    fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.values.iter().map(|value| value as &(dyn ToSql + Sync)).collect()
+    }
+}
+
+impl<T: FromRow> FromRow for Bound<T> {
+    fn from_row(row: &Row) -> Result<Self, Error> {
+        Ok(Self {
+            values: Vec::new(),
+            row: Some(T::from_row(row)?),
+        })
+    }
+
+    fn column_positions(columns: &[&str]) -> Vec<Option<usize>> {
+        T::column_positions(columns)
+    }
+
+    fn from_row_at(row: &Row, positions: &[Option<usize>]) -> Result<Self, Error> {
+        Ok(Self {
+            values: Vec::new(),
+            row: Some(T::from_row_at(row, positions)?),
+        })
+    }
+}
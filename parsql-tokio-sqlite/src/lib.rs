@@ -0,0 +1,69 @@
+//! # parsql-tokio-sqlite
+//!
+//! Asynchronous SQLite integration for parsql.
+//! The connection runs on a dedicated worker thread and the `CrudOps` methods are
+//! `async`, giving SQLite the same API shape as the tokio-postgres backend.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use parsql_tokio_sqlite::{
+//!     macros::{FromRow, Queryable, SqlParams, Updateable, UpdateParams},
+//!     traits::{CrudOps, FromRow, SqlParams, SqlQuery, UpdateParams},
+//!     Connection,
+//! };
+//! use rusqlite::{types::ToSql, Error, Row};
+//!
+//! #[derive(Queryable, SqlParams, FromRow, Debug)]
+//! #[table("users")]
+//! #[where_clause("id = $")]
+//! pub struct GetUser {
+//!     pub id: i64,
+//!     pub name: String,
+//! }
+//!
+//! #[derive(Updateable, UpdateParams)]
+//! #[table("users")]
+//! #[update("name")]
+//! #[where_clause("id = $")]
+//! pub struct RenameUser {
+//!     pub id: i64,
+//!     pub name: String,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Error> {
+//!     let conn = Connection::open("app.db").await?;
+//!
+//!     conn.update(RenameUser { id: 1, name: "John".to_string() }).await?;
+//!     let user = conn.fetch(&GetUser { id: 1, name: String::new() }).await?;
+//!     println!("{:?}", user);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ## Installation
+//!
+//! ```toml
+//! [dependencies]
+//! parsql = { version = "0.4.0", features = ["tokio-sqlite"] }
+//! ```
+
+mod connection;
+pub mod crud_ops;
+pub mod macros;
+pub mod repository;
+pub mod traits;
+
+pub use connection::Connection;
+pub use macros::*;
+
+// Re-export sqlite types that might be needed
+pub use rusqlite::{types::ToSql, Error, Row};
+#[cfg(feature = "json")]
+pub use parsql_sqlite::Json;
+#[cfg(feature = "decimal")]
+pub use parsql_sqlite::{Decimal, DecimalText};
+pub use parsql_sqlite::{encryption, EncryptedColumn};
+pub use parsql_sqlite::{JournalMode, SqliteOptions, Synchronous};
+pub use parsql_sqlite::set_trace;
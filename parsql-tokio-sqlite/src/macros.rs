@@ -0,0 +1,11 @@
+pub use parsql_macros::{
+    Deletable,
+    Entity,
+    Insertable,
+    Queryable,
+    Repository,
+    SqlParams,
+    Updateable,
+    UpdateParams,
+    FromRowSqlite as FromRow
+};
@@ -0,0 +1,57 @@
+use rusqlite::{types::FromSql, Error, Row};
+
+// Türetilmiş sorgu trait'leri SQLite ile aynıdır, sadece CrudOps asenkrondur
+pub use parsql_sqlite::traits::{FromRow, Keyed, ParentKey, RepositoryTypes, SqlParams, SqlQuery, SqlWith, UpdateParams, Validate, ValidationError};
+
+/// Asynchronous CRUD operations on a SQLite [`Connection`](crate::Connection).
+///
+/// Mirrors the `CrudOps` trait of `parsql-tokio-postgres`. Entities are taken by value
+/// because they are moved to the connection's worker thread; the queries of `fetch`
+/// and `fetch_all` are borrowed and only their bound values are copied.
+#[async_trait::async_trait]
+pub trait CrudOps {
+    /// Inserts a record and returns the first column of the row produced by `RETURNING`.
+    async fn insert<T, P>(&self, entity: T) -> Result<P, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static,
+        P: FromSql + Send + Sync + 'static;
+
+    /// Inserts a record and returns the number of inserted rows; needs no `RETURNING` clause.
+    async fn insert_execute<T>(&self, entity: T) -> Result<usize, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static;
+
+    /// Updates records and returns the number of affected rows.
+    async fn update<T>(&self, entity: T) -> Result<u64, Error>
+    where
+        T: SqlQuery + UpdateParams + Send + 'static;
+
+    /// Deletes records and returns the number of affected rows.
+    async fn delete<T>(&self, entity: T) -> Result<u64, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static;
+
+    /// Retrieves a single record.
+    async fn fetch<T>(&self, params: &T) -> Result<T, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static;
+
+    /// Retrieves all matching records.
+    async fn fetch_all<T>(&self, params: &T) -> Result<Vec<T>, Error>
+    where
+        T: SqlQuery + FromRow + SqlParams + Send + Sync + 'static;
+
+    /// Retrieves a single record, converting the row with `to_model`.
+    async fn select<T, F, R>(&self, entity: T, to_model: F) -> Result<R, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static,
+        F: Fn(&Row) -> Result<R, Error> + Send + 'static,
+        R: Send + 'static;
+
+    /// Retrieves all matching records, converting each row with `to_model`.
+    async fn select_all<T, F, R>(&self, entity: T, to_model: F) -> Result<Vec<R>, Error>
+    where
+        T: SqlQuery + SqlParams + Send + 'static,
+        F: Fn(&Row) -> Result<R, Error> + Send + 'static,
+        R: Send + 'static;
+}
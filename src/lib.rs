@@ -1,3 +1,6 @@
+// `query_dynamic` sonuçlarının sütun değeri; tüm sürücülerde aynı tiptir
+pub use parsql_core::value::Value;
+
 #[cfg(feature = "sqlite")]
 pub use parsql_sqlite as sqlite;
 
@@ -7,5 +10,11 @@ pub use parsql_postgres as postgres;
 #[cfg(feature = "tokio-postgres")]
 pub use parsql_tokio_postgres as tokio_postgres;
 
+#[cfg(feature = "tokio-sqlite")]
+pub use parsql_tokio_sqlite as tokio_sqlite;
+
 #[cfg(feature = "deadpool-postgres")]
 pub use parsql_deadpool_postgres as deadpool_postgres;
+
+#[cfg(feature = "mock")]
+pub use parsql_mock as mock;
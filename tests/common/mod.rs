@@ -0,0 +1,174 @@
+//! Shared setup of the PostgreSQL integration tests.
+//!
+//! The tests run only when `PARSQL_TEST_DATABASE_URL` points at a server, e.g. the
+//! one started by `docker compose -f tests/docker-compose.yml up -d`:
+//!
+//! ```text
+//! PARSQL_TEST_DATABASE_URL="host=localhost user=postgres password=postgres" \
+//!     cargo test -p parsql --features postgres,tokio-postgres,deadpool-postgres
+//! ```
+//!
+//! Every test gets its own schema from `parsql_testing::TestDatabase`, so the tests of
+//! all backends can run in parallel against the same database. The `#[ignore]`d
+//! `container` tests start a PostgreSQL container instead and only need Docker.
+
+#![allow(dead_code)]
+
+/// Whether the integration tests are enabled; prints a note on stderr when they are not.
+pub fn enabled() -> bool {
+    if std::env::var(parsql_testing::DATABASE_URL).is_ok_and(|url| !url.trim().is_empty()) {
+        return true;
+    }
+    eprintln!("skipped: set {} to run the PostgreSQL integration tests", parsql_testing::DATABASE_URL);
+    false
+}
+
+/// The `users` table every test starts with, created in the schema of the test.
+pub const USERS_SQL: &str = "CREATE TABLE users (
+             id BIGSERIAL PRIMARY KEY,
+             name TEXT NOT NULL,
+             email TEXT NOT NULL,
+             state SMALLINT NOT NULL
+         );";
+
+/// Creates `schema` next to the one of the test, with an empty copy of its `users` table.
+pub fn tenant_sql(schema: &str) -> String {
+    format!("CREATE SCHEMA {schema}; CREATE TABLE {schema}.users (LIKE users INCLUDING ALL);")
+}
+
+/// Materialized view of the user count per state, read with `UserCount`. The unique
+/// index allows `REFRESH MATERIALIZED VIEW CONCURRENTLY`.
+pub const USER_COUNTS_SQL: &str = "CREATE MATERIALIZED VIEW user_counts AS
+         SELECT state, count(*) AS total FROM users GROUP BY state;
+     CREATE UNIQUE INDEX ON user_counts (state);";
+
+/// Generated `tsvector` column searched by `SearchUsers`.
+pub const SEARCH_SQL: &str = "ALTER TABLE users ADD COLUMN search_vector tsvector
+         GENERATED ALWAYS AS (to_tsvector('simple', name)) STORED;";
+
+/// Routines called through `MoveUsers` and `CountUsers`.
+pub const ROUTINES_SQL: &str = "CREATE PROCEDURE move_users(from_state SMALLINT, to_state SMALLINT)
+         LANGUAGE SQL AS 'UPDATE users SET state = to_state WHERE state = from_state';
+     CREATE FUNCTION count_users(of_state SMALLINT) RETURNS BIGINT
+         LANGUAGE SQL AS 'SELECT count(*) FROM users WHERE state = of_state';";
+
+/// `posts` with a deferrable foreign key to `users` and an insert trigger writing to
+/// `post_audit`, used by the integrity control tests.
+pub const INTEGRITY_SQL: &str = "CREATE TABLE posts (
+             id BIGINT PRIMARY KEY,
+             user_id BIGINT NOT NULL,
+             CONSTRAINT fk_posts_user FOREIGN KEY (user_id) REFERENCES users (id) DEFERRABLE
+         );
+     CREATE TABLE post_audit (post_id BIGINT NOT NULL);
+     CREATE FUNCTION audit_post() RETURNS trigger LANGUAGE plpgsql
+         AS 'BEGIN INSERT INTO post_audit VALUES (NEW.id); RETURN NEW; END';
+     CREATE TRIGGER posts_audit AFTER INSERT ON posts FOR EACH ROW EXECUTE FUNCTION audit_post();";
+
+/// Statement writing a post before its author, which only passes with `fk_posts_user` deferred.
+pub const ORPHAN_POST_SQL: &str = "INSERT INTO posts VALUES (1, 100)";
+
+/// Author of the post written by `ORPHAN_POST_SQL`.
+pub const AUTHOR_SQL: &str = "INSERT INTO users (id, name, email, state) VALUES (100, 'deniz', 'deniz@example.com', 1)";
+
+/// Posts and their authors, by post id.
+pub const POSTS_SQL: &str = "SELECT id, user_id FROM posts ORDER BY id";
+
+/// User whose name needs quoting both as an SQL literal and as a CSV field.
+pub const QUOTED_NAME: &str = "o'neil, \"jr\"";
+
+/// `export_csv` output of `ExportUsers` for `ali` and the `QUOTED_NAME` user added after the seed.
+pub const EXPORT_CSV: &str = "id,name,email\n1,ali,ali@example.com\n7,\"o'neil, \"\"jr\"\"\",oneil@example.com\n";
+
+/// Rows written to `audit_log` by the `#[audited]` models, oldest first.
+pub const AUDIT_ROWS_SQL: &str = "SELECT op, pk, diff::text, actor FROM audit_log ORDER BY id";
+
+/// Audit rows of inserting, renaming (as user 42) and deleting the user `id`: the
+/// operation, the key, the changes as JSON text and the actor.
+pub fn assert_audit_rows(rows: &[(String, String, String, Option<String>)], id: i64) {
+    let ops = rows.iter().map(|row| row.0.as_str()).collect::<Vec<_>>();
+    assert_eq!(ops, ["INSERT", "UPDATE", "DELETE"]);
+    assert!(rows.iter().all(|row| row.1 == id.to_string()));
+    assert!(rows[0].2.contains("\"email\": \"ali@example.com\""));
+    assert_eq!(rows[1].2, "{\"name\": \"veli\"}");
+    assert!(rows[2].2.contains("\"name\": \"veli\""));
+    let actors = rows.iter().map(|row| row.3.as_deref()).collect::<Vec<_>>();
+    assert_eq!(actors, [None, Some("42"), None]);
+}
+
+/// Ad-hoc query over the seeded users: decoded columns, a `numeric` and a `timestamp`
+/// that are read as text, and a NULL.
+pub const DYNAMIC_SQL: &str = "SELECT id, name, state = 1 AS active, 1.50::numeric AS score, \
+    '2024-01-02 03:04:05+00'::timestamptz AT TIME ZONE 'UTC' AS seen_at, NULL::text AS note \
+    FROM users WHERE state = $1 ORDER BY id";
+
+/// Rows of [`DYNAMIC_SQL`] for the active users `ids`.
+pub fn assert_dynamic_rows(rows: &[std::collections::HashMap<String, parsql::Value>], ids: &[i64]) {
+    use parsql::Value;
+
+    assert_eq!(rows.iter().map(|row| row["id"].clone()).collect::<Vec<_>>(), ids[..4].iter().map(|id| Value::Int(*id)).collect::<Vec<_>>());
+    assert_eq!(rows[0]["name"], Value::Text("ali".into()));
+    assert_eq!(rows[0]["active"], Value::Bool(true));
+    assert_eq!(rows[0]["score"], Value::Text("1.50".into()));
+    assert_eq!(rows[0]["seen_at"].as_str(), Some("2024-01-02 03:04:05"));
+    assert!(rows[0]["note"].is_null());
+}
+
+/// Changes made while a cursor over the active users is half read: the last active
+/// user is deleted and a new one is added.
+pub const CHANGE_ACTIVE_USERS_SQL: &str = "DELETE FROM users WHERE id = (SELECT max(id) FROM users WHERE state = 1); \
+    INSERT INTO users (name, email, state) VALUES ('nur', 'nur@example.com', 1)";
+
+/// `events`, partitioned by month on `created_at` and without any partition yet.
+pub const EVENTS_SQL: &str = "CREATE TABLE events (
+             id BIGSERIAL,
+             kind TEXT NOT NULL,
+             created_at TIMESTAMPTZ NOT NULL
+         ) PARTITION BY RANGE (created_at);";
+
+/// 2024-03-15 00:00 UTC, the time of the events the partition tests insert.
+pub fn event_time() -> std::time::SystemTime {
+    std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_710_460_800)
+}
+
+/// Rows in the partition of March 2024.
+pub const MARCH_EVENTS_SQL: &str = "SELECT count(*) FROM events_2024_03";
+
+/// `slow_fetch(state)`, called by `SlowUser` once per matching row: it counts the call
+/// in the `slow_fetches` sequence, sleeps 200ms and fails for passive users.
+pub const SLOW_FETCH_SQL: &str = "CREATE SEQUENCE slow_fetches;
+     CREATE FUNCTION slow_fetch(state SMALLINT) RETURNS BOOLEAN LANGUAGE plpgsql AS '
+         BEGIN
+             PERFORM nextval(''slow_fetches'');
+             PERFORM pg_sleep(0.2);
+             IF state = 0 THEN RAISE EXCEPTION ''passive user''; END IF;
+             RETURN true;
+         END';";
+
+/// Number of `slow_fetch` calls so far; sequence values are kept when a query fails.
+pub const SLOW_FETCH_COUNT_SQL: &str =
+    "SELECT CASE WHEN is_called THEN last_value ELSE 0 END FROM slow_fetches";
+
+/// Whether the server allows `PREPARE TRANSACTION`, which the two-phase commit tests need.
+pub const PREPARED_TRANSACTIONS_SQL: &str = "SELECT current_setting('max_prepared_transactions')::int > 0";
+
+/// Prepares the transaction `gid` inserting the active user `name`, as an interrupted
+/// two-phase commit leaves it behind.
+pub fn prepared_insert_sql(gid: &str, name: &str) -> String {
+    format!(
+        "BEGIN;
+         INSERT INTO users (name, email, state) VALUES ('{name}', '{name}@example.com', 1);
+         PREPARE TRANSACTION '{gid}'"
+    )
+}
+
+/// Users inserted by the tests: four active (`state = 1`) and two passive ones.
+pub fn seed() -> Vec<(String, String, i16)> {
+    ["ali", "ayse", "mehmet", "zeynep", "can", "elif"]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let state = if i < 4 { 1 } else { 0 };
+            (name.to_string(), format!("{}@example.com", name), state)
+        })
+        .collect()
+}
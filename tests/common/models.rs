@@ -0,0 +1,641 @@
+// Query types shared by the integration tests. The file is `include!`d into a module
+// of each test crate, after the backend's derive macros, traits and `registry` are imported.
+
+#[derive(Insertable, SqlParams, Debug)]
+#[table("users")]
+#[register_query]
+#[returning("id")]
+pub struct InsertUser {
+    #[validate(non_empty)]
+    pub name: String,
+    #[validate(email)]
+    pub email: String,
+    pub state: i16,
+}
+
+/// `InsertUser` without `#[returning]`, read back with `insert_returning_id`.
+#[derive(Insertable, SqlParams)]
+#[table("users")]
+pub struct AddUser {
+    pub name: String,
+    pub email: String,
+    pub state: i16,
+}
+
+/// `AddUser` that skips an existing email, read back with `insert_returning_opt`.
+#[derive(Insertable, SqlParams)]
+#[table("users")]
+#[on_conflict_do_nothing("email")]
+pub struct AddUserOnce {
+    pub name: String,
+    pub email: String,
+    pub state: i16,
+}
+
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone, PartialEq)]
+#[table("users")]
+#[register_query]
+#[where_clause("id = $")]
+#[primary_key("id")]
+pub struct UserById {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+    pub state: i16,
+}
+
+#[derive(Queryable, SqlParams, FromRow, Paginated, Debug, Clone)]
+#[table("users")]
+#[register_query]
+#[where_clause("state = $")]
+#[order_by("id")]
+#[require_order]
+#[sortable("id, name")]
+pub struct UsersByState {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+    pub state: i16,
+}
+
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+#[table("users")]
+#[register_query]
+#[select("id, name, email, state")]
+#[where_clause("state = $")]
+#[order_by("id")]
+#[limit("$page_size")]
+#[offset("$skip")]
+#[require_order]
+pub struct UsersPage {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+    pub state: i16,
+    #[from_row(default)]
+    pub page_size: i64,
+    #[from_row(default)]
+    pub skip: i64,
+}
+
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+#[table("users")]
+#[select("state, array_agg(id ORDER BY id) AS ids, array_agg(name ORDER BY id) FILTER (WHERE name LIKE 'a%') AS names")]
+#[group_by("state")]
+#[order_by("state DESC")]
+pub struct UsersGroupedByState {
+    pub state: i16,
+    pub ids: Vec<i64>,
+    pub names: Vec<String>,
+}
+
+/// Every user; narrowed at runtime by the filters of `fetch_all_combined`.
+#[derive(Queryable, FromRow, Debug, Clone)]
+#[table("users")]
+#[order_by("id")]
+pub struct UserRow {
+    pub id: i64,
+    pub name: String,
+    pub state: i16,
+}
+
+/// Users exported by `export_csv`: an array, a text and a nullable parameter.
+#[derive(Queryable, SqlParams)]
+#[table("users")]
+#[select("id, name, email")]
+#[where_clause("name = ANY($names) AND email IS DISTINCT FROM $email")]
+#[order_by("id")]
+pub struct ExportUsers {
+    pub names: Vec<String>,
+    pub email: Option<String>,
+}
+
+/// Newest user of a state; its own ORDER BY and LIMIT stay inside the union.
+#[derive(Queryable, SqlParams)]
+#[table("users")]
+#[select("id, name")]
+#[where_clause("state = $")]
+#[order_by("id DESC")]
+#[limit(1)]
+pub struct NewestByState {
+    pub state: i16,
+}
+
+#[derive(Queryable, SqlParams)]
+#[table("users")]
+#[select("id, name")]
+#[where_clause("name = $")]
+#[union_all(NewestByState)]
+#[order_by("id")]
+pub struct NamedOrNewest {
+    pub name: String,
+    pub newest: NewestByState,
+}
+
+/// Filter of users whose name matches a `LIKE` pattern.
+#[derive(Queryable, SqlParams)]
+#[table("users")]
+#[select("id")]
+#[where_clause("name LIKE $name_pattern")]
+pub struct NameLike {
+    pub name_pattern: String,
+}
+
+/// Filter of users whose id lies in a range; binds two parameters.
+#[derive(Queryable, SqlParams)]
+#[table("users")]
+#[select("id")]
+#[where_clause("id BETWEEN $from AND $to")]
+pub struct IdBetween {
+    pub from: i64,
+    pub to: i64,
+}
+
+#[derive(Updateable, UpdateParams)]
+#[table("users")]
+#[register_query]
+#[update("name, email")]
+#[where_clause("id = $")]
+pub struct UpdateUser {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+}
+
+/// Columns shared by models that pull them in with `#[include_fields(UserContact)]`.
+#[derive(SqlParams, FromRow, Debug, Clone, PartialEq)]
+pub struct UserContact {
+    pub email: String,
+    pub state: i16,
+}
+
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+#[table("users")]
+#[include_fields(UserContact)]
+#[where_clause("id = $")]
+pub struct UserWithContact {
+    pub id: i64,
+    pub name: String,
+    pub contact: UserContact,
+}
+
+#[derive(Deletable, SqlParams, Debug)]
+#[table("users")]
+#[register_query]
+#[where_clause("id = $")]
+pub struct DeleteUser {
+    pub id: i64,
+}
+
+/// User looked up by email, however its case is typed.
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+#[table("users")]
+#[where_clause("email = $")]
+pub struct UserByEmail {
+    pub id: i64,
+    pub name: String,
+    #[case_insensitive]
+    pub email: String,
+}
+
+#[derive(Deletable, SqlParams, Debug)]
+#[table("users")]
+#[where_clause("email = $ AND state = $")]
+pub struct DeleteUserByEmail {
+    #[case_insensitive]
+    pub email: String,
+    pub state: i16,
+}
+
+/// Gives the users whose name matches the prefix the state of another user.
+#[derive(Updateable, UpdateParams)]
+#[table("users")]
+#[update_expr("state = source.state")]
+#[from("(SELECT state FROM users WHERE id = $source_id) AS source")]
+#[where_clause("users.state <> source.state AND users.name LIKE $name_prefix")]
+pub struct CopyUserState {
+    pub source_id: i64,
+    pub name_prefix: String,
+}
+
+/// Deletes the users in the same state as another user.
+#[derive(Deletable, SqlParams, Debug)]
+#[table("users")]
+#[using("users AS source")]
+#[where_clause("source.id = $source_id AND users.state = source.state")]
+pub struct DeleteUsersLike {
+    pub source_id: i64,
+}
+
+/// `AddUser` recorded in `audit_log`; returns the new key without `#[returning]`.
+#[derive(Insertable, SqlParams)]
+#[table("users")]
+#[audited]
+pub struct AuditedAddUser {
+    pub name: String,
+    pub email: String,
+    pub state: i16,
+}
+
+/// Renames a user, recording the new name in `audit_log`.
+#[derive(Updateable, UpdateParams, Debug)]
+#[table("users")]
+#[audited]
+#[update("name")]
+#[where_clause("id = $")]
+pub struct AuditedRenameUser {
+    pub id: i64,
+    pub name: String,
+}
+
+/// `DeleteUser` recording the deleted row in `audit_log`.
+#[derive(Deletable, SqlParams, Debug)]
+#[table("users")]
+#[audited]
+#[where_clause("id = $")]
+pub struct AuditedDeleteUser {
+    pub id: i64,
+}
+
+/// Event of the `events` table, whose monthly partitions the tests create.
+#[derive(Insertable, SqlParams)]
+#[table("events")]
+#[returning("id")]
+#[partitioned(by = "range(created_at)")]
+pub struct InsertEvent {
+    pub kind: String,
+    pub created_at: std::time::SystemTime,
+}
+
+/// Number of users per state, read from the `user_counts` materialized view.
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone, PartialEq)]
+#[materialized_view("user_counts")]
+#[where_clause("state = $")]
+pub struct UserCount {
+    pub state: i16,
+    pub total: i64,
+}
+
+/// Users filtered by whichever of `state` and `name_prefix` is set.
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+#[table("users")]
+#[select("id, name, email, state")]
+#[where_if("state = $", when = "state.is_some()")]
+#[where_if("name LIKE $name_prefix", when = "name_prefix.is_some()")]
+#[order_by("id")]
+pub struct UsersFiltered {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+    pub state: Option<i16>,
+    #[from_row(default)]
+    pub name_prefix: Option<String>,
+}
+
+/// The first page of a state's users, with the number of all its users.
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+#[table("users")]
+#[select_extra("COUNT(*) OVER() AS total_rows")]
+#[where_clause("state = $")]
+#[order_by("id")]
+#[limit(2)]
+pub struct UsersWithTotal {
+    pub id: i64,
+    pub name: String,
+    pub state: i16,
+    pub total_rows: i64,
+}
+
+/// The first page of a state's users; the user count is a column outside the struct,
+/// read from the raw row returned by `fetch_all_with_rows`.
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+#[table("users")]
+#[select("id, name, state, COUNT(*) OVER() AS total_count")]
+#[where_clause("state = $")]
+#[order_by("id")]
+#[limit(2)]
+pub struct UserPage {
+    pub id: i64,
+    pub name: String,
+    pub state: i16,
+}
+
+/// Post written after its author by `persist_graph`, into the `posts` table of `INTEGRITY_SQL`.
+#[derive(Insertable, SqlParams)]
+#[table("posts")]
+#[parent_key("user_id")]
+pub struct InsertPost {
+    pub id: i64,
+    pub user_id: i64,
+}
+
+impl InsertPost {
+    pub fn new(id: i64) -> Self {
+        Self { id, user_id: 0 }
+    }
+}
+
+/// User read through `slow_fetch`, which makes every lookup take a while; see `SLOW_FETCH_SQL`.
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone, PartialEq)]
+#[table("users")]
+#[select("id, name")]
+#[where_clause("id = $ AND slow_fetch(state)")]
+pub struct SlowUser {
+    pub id: i64,
+    pub name: String,
+}
+
+impl SlowUser {
+    pub fn new(id: i64) -> Self {
+        Self { id, name: String::new() }
+    }
+}
+
+/// Users of a state whose name matches `terms`, most relevant first; see `SEARCH_SQL`.
+#[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+#[table("users")]
+#[fts("search_vector", query = "terms", config = "simple", rank)]
+#[where_clause("state = $")]
+#[order_by("id")]
+pub struct SearchUsers {
+    pub id: i64,
+    pub name: String,
+    pub state: i16,
+    #[from_row(default)]
+    pub terms: String,
+}
+
+/// Users of a state within `meters` of `center`, read from a PostGIS `location` column.
+#[cfg(feature = "postgis")]
+#[derive(Queryable, SqlParams, FromRow, Debug)]
+#[table("users")]
+#[select("id, name")]
+#[where_clause("state = $")]
+#[within("location", center = "center", distance = "meters", geography)]
+pub struct UsersNear {
+    pub id: i64,
+    pub name: String,
+    pub state: i16,
+    #[from_row(default)]
+    pub center: PostGis<Point>,
+    #[from_row(default)]
+    pub meters: f64,
+}
+
+/// Moves the users of one state to another, through the `move_users` procedure.
+#[derive(Callable, SqlParams, Debug)]
+#[procedure("move_users")]
+pub struct MoveUsers {
+    pub from_state: i16,
+    pub to_state: i16,
+}
+
+#[derive(Callable, SqlParams, Debug)]
+#[function("count_users")]
+pub struct CountUsers {
+    pub state: i16,
+}
+
+#[derive(Patch)]
+#[table("users")]
+pub struct User {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+    pub state: i16,
+}
+
+/// `users` through the backend's `Repository`; `find` and `delete` are built from the id.
+#[derive(Repository)]
+#[repository(key = i64, insert = InsertUser, find = UserById, find_all = UsersByState, update = UpdateUser, delete = DeleteUser)]
+pub struct Users;
+
+/// `users` through `Entity`: read by key, written with `MemberInsert`, `MemberUpdate`
+/// and `MemberDelete`.
+#[derive(Entity, Debug, Clone)]
+#[entity(table = "users", pk = "id")]
+pub struct Member {
+    pub id: i64,
+    #[validate(non_empty)]
+    pub name: String,
+    pub email: String,
+    pub state: i16,
+}
+
+impl UserById {
+    pub fn new(id: i64) -> Self {
+        Self { id, name: String::new(), email: String::new(), state: 0 }
+    }
+}
+
+impl From<i64> for UserById {
+    fn from(id: i64) -> Self {
+        Self::new(id)
+    }
+}
+
+impl From<i64> for DeleteUser {
+    fn from(id: i64) -> Self {
+        Self { id }
+    }
+}
+
+impl UserByEmail {
+    pub fn new(email: &str) -> Self {
+        Self { id: 0, name: String::new(), email: email.to_string() }
+    }
+}
+
+impl UsersByState {
+    pub fn new(state: i16) -> Self {
+        Self { id: 0, name: String::new(), email: String::new(), state }
+    }
+}
+
+impl UserWithContact {
+    pub fn new(id: i64) -> Self {
+        Self { id, name: String::new(), contact: UserContact { email: String::new(), state: 0 } }
+    }
+}
+
+impl UsersGroupedByState {
+    pub fn new() -> Self {
+        Self { state: 0, ids: Vec::new(), names: Vec::new() }
+    }
+}
+
+impl UserCount {
+    pub fn new(state: i16) -> Self {
+        Self { state, total: 0 }
+    }
+}
+
+impl UsersWithTotal {
+    pub fn new(state: i16) -> Self {
+        Self { id: 0, name: String::new(), state, total_rows: 0 }
+    }
+}
+
+impl UserPage {
+    pub fn new(state: i16) -> Self {
+        Self { id: 0, name: String::new(), state }
+    }
+}
+
+impl SearchUsers {
+    pub fn new(state: i16, terms: &str) -> Self {
+        Self { id: 0, name: String::new(), state, terms: terms.to_string() }
+    }
+}
+
+impl UsersFiltered {
+    pub fn new(state: Option<i16>, name_prefix: Option<&str>) -> Self {
+        let name_prefix = name_prefix.map(String::from);
+        Self { id: 0, name: String::new(), email: String::new(), state, name_prefix }
+    }
+}
+
+impl UsersPage {
+    pub fn new(state: i16, page_size: i64, skip: i64) -> Self {
+        Self { id: 0, name: String::new(), email: String::new(), state, page_size, skip }
+    }
+}
+
+/// The SQL the derive macros generate for the shared query types. A change in the
+/// macros that alters one of these statements fails every backend's suite.
+pub fn assert_sql_snapshots() {
+    let snapshots = [
+        (
+            InsertUser::SQL,
+            "INSERT INTO users (name, email, state ) VALUES ($1, $2, $3 ) RETURNING id",
+        ),
+        (
+            AddUser::SQL,
+            "INSERT INTO users (name, email, state ) VALUES ($1, $2, $3 )",
+        ),
+        (
+            AddUserOnce::SQL,
+            "INSERT INTO users (name, email, state ) VALUES ($1, $2, $3 ) ON CONFLICT (email) DO NOTHING",
+        ),
+        (
+            UserById::SQL,
+            "SELECT id, name, email, state FROM users WHERE id = $1",
+        ),
+        (
+            UsersByState::SQL,
+            "SELECT id, name, email, state FROM users WHERE state = $1 ORDER BY id",
+        ),
+        (
+            UsersPage::SQL,
+            "SELECT id, name, email, state FROM users WHERE state = $1 ORDER BY id LIMIT $2 OFFSET $3",
+        ),
+        (
+            UpdateUser::SQL,
+            "UPDATE users SET name = $1, email = $2 WHERE id = $3",
+        ),
+        (
+            DeleteUser::SQL,
+            "DELETE FROM users WHERE id = $1",
+        ),
+        (
+            CopyUserState::SQL,
+            "UPDATE users SET state = source.state FROM (SELECT state FROM users WHERE id = $1) AS source WHERE users.state <> source.state AND users.name LIKE $2",
+        ),
+        (
+            DeleteUsersLike::SQL,
+            "DELETE FROM users USING users AS source WHERE source.id = $1 AND users.state = source.state",
+        ),
+        (
+            AuditedDeleteUser::SQL,
+            "WITH written AS (DELETE FROM users WHERE id = $1 RETURNING users.*) INSERT INTO audit_log \
+             (table_name, op, pk, diff, actor) SELECT 'users', 'DELETE', written.id::text, to_jsonb(written), \
+             NULLIF(current_setting('app.current_user_id', true), '') FROM written",
+        ),
+        (
+            UserCount::SQL,
+            "SELECT state, total FROM user_counts WHERE state = $1",
+        ),
+        (
+            UsersFiltered::SQL,
+            "SELECT id, name, email, state FROM users WHERE (NOT $1 OR (state = $2)) AND (NOT $3 OR (name LIKE $4)) ORDER BY id",
+        ),
+        (
+            MoveUsers::SQL,
+            "CALL move_users($1, $2)",
+        ),
+        (
+            CountUsers::SQL,
+            "SELECT count_users($1)",
+        ),
+        (
+            UsersWithTotal::SQL,
+            "SELECT id, name, state, COUNT(*) OVER() AS total_rows FROM users WHERE state = $1 ORDER BY id LIMIT 2",
+        ),
+        (
+            Member::SQL,
+            "SELECT id, name, email, state FROM users WHERE id = $1",
+        ),
+        (
+            MemberInsert::SQL,
+            "INSERT INTO users (name, email, state ) VALUES ($1, $2, $3 ) RETURNING id",
+        ),
+        (
+            MemberUpdate::SQL,
+            "UPDATE users SET name = $1, email = $2, state = $3 WHERE id = $4",
+        ),
+        (
+            MemberDelete::SQL,
+            "DELETE FROM users WHERE id = $1",
+        ),
+        (
+            UserByEmail::SQL,
+            "SELECT id, name, email FROM users WHERE LOWER(email) = LOWER($1)",
+        ),
+        (
+            DeleteUserByEmail::SQL,
+            "DELETE FROM users WHERE LOWER(email) = LOWER($1) AND state = $2",
+        ),
+        (
+            SearchUsers::SQL,
+            "SELECT id, name, state FROM users WHERE (state = $1) AND search_vector @@ plainto_tsquery('simple', $2) \
+             ORDER BY ts_rank(search_vector, plainto_tsquery('simple', $2)) DESC, id",
+        ),
+    ];
+    assert_eq!(UserCount::view_name(), "user_counts");
+    let metadata = [
+        (UserCount::table_name(), UserCount::param_count()),
+        (UsersPage::table_name(), UsersPage::param_count()),
+        (UsersFiltered::table_name(), UsersFiltered::param_count()),
+        (UpdateUser::table_name(), UpdateUser::param_count()),
+        (UserWithContact::table_name(), UserWithContact::param_count()),
+    ];
+    assert_eq!(metadata, [("user_counts", 1), ("users", 3), ("users", 4), ("users", 3), ("users", 1)]);
+    for (generated, expected) in snapshots {
+        assert_eq!(generated, expected);
+    }
+    assert_eq!(UserById::query(), UserById::SQL);
+    #[cfg(feature = "postgis")]
+    assert_eq!(
+        UsersNear::SQL,
+        "SELECT id, name FROM users WHERE (state = $1) AND ST_DWithin(location::geography, $2::geography, $3)"
+    );
+    let patch = UserPatch { email: Some("ali@example.com".to_string()), ..Default::default() };
+    assert_eq!(patch.patch_sql().unwrap(), "UPDATE users SET email = $1 WHERE id = $2");
+    assert!(UserPatch::default().patch_sql().is_none());
+    assert_eq!(UsersPage::criteria(), Some(("users", "state = $1")));
+    assert!(UserCount::criteria().is_none());
+    assert_eq!(
+        UserWithContact::query(),
+        "SELECT id, name, email, state FROM users WHERE id = $1"
+    );
+}
+
+/// Arrays built by `array_agg`: the passive users have no name starting with `a`,
+/// so their `names` aggregate is NULL and reads as an empty vector.
+pub fn assert_grouped(groups: &[UsersGroupedByState], ids: &[i64]) {
+    assert_eq!(groups.len(), 2);
+    assert_eq!((groups[0].state, groups[0].ids.as_slice()), (1, &ids[..4]));
+    assert_eq!(groups[0].names, ["ali", "ayse"]);
+    assert_eq!((groups[1].state, groups[1].ids.as_slice()), (0, &ids[4..]));
+    assert!(groups[1].names.is_empty());
+}
@@ -0,0 +1,689 @@
+//! Integration tests of the `deadpool-postgres` backend.
+
+mod common;
+
+#[allow(dead_code)]
+mod models {
+    use parsql::deadpool_postgres::{macros::*, registry, traits::*};
+    #[cfg(feature = "postgis")]
+    use parsql::deadpool_postgres::{geo_types::Point, PostGis};
+    use tokio_postgres::{types::ToSql, Error, Row};
+
+    include!("common/models.rs");
+}
+
+/// Models derived for the `tokio-postgres` traits the `ParsqlExecutor` methods take.
+#[cfg(feature = "tokio-postgres")]
+mod executor_models {
+    use parsql::tokio_postgres::{macros::*, traits::*};
+    use tokio_postgres::{types::ToSql, Error, Row};
+
+    #[derive(Insertable, SqlParams)]
+    #[table("users")]
+    #[returning("id")]
+    pub struct NewUser {
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    pub struct UserRecord {
+        pub id: i64,
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    #[derive(Updateable, UpdateParams)]
+    #[table("users")]
+    #[update("email")]
+    #[where_clause("id = $")]
+    pub struct UserEmail {
+        pub id: i64,
+        pub email: String,
+    }
+
+    #[derive(Deletable, SqlParams)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    pub struct RemoveUser {
+        pub id: i64,
+    }
+}
+
+use deadpool_postgres::{Manager, ManagerConfig, Pool};
+use models::*;
+use parsql::deadpool_postgres::{
+    acquire::{set_acquire_retry, AcquireRetry},
+    audit_log,
+    cursor::Cursor,
+    batch_get, cancel::Cancellation, priority::{Lane, PriorityExecutor}, integrity::IntegrityControl, get, get_all, get_all_combined, get_all_limited, get_all_with_rows, get_with_row, get_first, insert_returning_id, get_last, get_many, get_page, locks, ensure_monthly_partition, export_csv, partitions, query_dynamic,
+    registry, repository::Repository, session, traits::Partitioned, transactional, two_phase::{self, NoRecovery, TwoPhaseError}, CancellationToken, ConstraintViolation, CrudOps, DeadpoolError, TransactionOps,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use parsql_testing::TestDatabase;
+use tokio_postgres::{Config, NoTls};
+
+/// Starts a test database holding an empty `users` table and creates a pool of up to
+/// four connections to it.
+async fn connect() -> Option<(TestDatabase, Pool)> {
+    if !common::enabled() {
+        return None;
+    }
+    let db = TestDatabase::start(|client| Box::pin(client.batch_execute(common::USERS_SQL)))
+        .await
+        .expect("cannot start the test database");
+    let pool = db.pool().unwrap();
+    pool.resize(4);
+    Some((db, pool))
+}
+
+async fn seed(pool: &Pool) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for (name, email, state) in common::seed() {
+        ids.push(pool.insert(InsertUser { name, email, state }).await.unwrap());
+    }
+    ids
+}
+
+#[test]
+fn generated_sql() {
+    assert_sql_snapshots();
+}
+
+#[tokio::test]
+async fn acquire_retry() {
+    let retry = AcquireRetry::new(2).delay(Duration::from_millis(20)).max_delay(Duration::from_millis(30));
+    assert_eq!(retry.backoff(1), Duration::from_millis(20));
+    assert_eq!(retry.backoff(2), Duration::from_millis(30));
+    assert!(retry.clone().jitter(true).backoff(1) < Duration::from_millis(20));
+
+    // Dinlenmeyen bir porta bağlanan havuz her denemede bağlantı hatası verir
+    let config: Config = "host=127.0.0.1 port=1 user=postgres".parse().unwrap();
+    let pool = Pool::builder(Manager::from_config(config, NoTls, ManagerConfig::default())).build().unwrap();
+    set_acquire_retry(Some(retry));
+    let started = Instant::now();
+    let result = get(&pool, &UserById::new(1)).await;
+    set_acquire_retry(None);
+    assert!(matches!(result, Err(DeadpoolError::Pool(_))));
+    assert!(started.elapsed() >= Duration::from_millis(50));
+}
+
+#[tokio::test]
+async fn crud() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    let user = pool.fetch(&UserById::new(ids[0])).await.unwrap();
+    assert_eq!((user.name.as_str(), user.state), ("ali", 1));
+    let with_contact = pool.fetch(&UserWithContact::new(ids[0])).await.unwrap();
+    assert_eq!(with_contact.contact, UserContact { email: "ali@example.com".into(), state: 1 });
+
+    let active = pool.fetch_all(&UsersByState::new(1)).await.unwrap();
+    assert_eq!(active.len(), 4);
+
+    let rename = UpdateUser { id: ids[0], name: "veli".into(), email: "veli@example.com".into() };
+    assert_eq!(pool.update(rename).await.unwrap(), 1);
+    let missing = UpdateUser { id: -1, name: String::new(), email: String::new() };
+    assert_eq!(pool.update(missing).await.unwrap(), 0);
+    assert_eq!(pool.fetch(&UserById::new(ids[0])).await.unwrap().name, "veli");
+
+    assert_eq!(pool.delete(DeleteUser { id: ids[0] }).await.unwrap(), 1);
+    assert!(pool.fetch(&UserById::new(ids[0])).await.is_err());
+
+    let user = AddUser { name: "deniz".into(), email: "deniz@example.com".into(), state: 1 };
+    let id: i64 = insert_returning_id(&pool, user).await.unwrap();
+    assert_eq!(pool.fetch(&UserById::new(id)).await.unwrap().name, "deniz");
+}
+
+/// The README usage: the `CrudOps` methods on a client taken from the pool.
+#[tokio::test]
+async fn pooled_client() {
+    let Some((_db, pool)) = connect().await else { return };
+    let client: deadpool_postgres::Object = pool.get().await.unwrap();
+
+    let user = InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let id: i64 = client.insert(user).await.unwrap();
+    assert_eq!(client.fetch(&UserById::new(id)).await.unwrap().name, "ali");
+    assert_eq!(client.fetch_all(&UsersByState::new(1)).await.unwrap().len(), 1);
+    let names = client
+        .select_all(UsersByState::new(1), |row| row.get::<_, String>("name"))
+        .await
+        .unwrap();
+    assert_eq!(names, ["ali"]);
+
+    let rename = UpdateUser { id, name: "veli".into(), email: "veli@example.com".into() };
+    assert_eq!(client.update(rename).await.unwrap(), 1);
+    assert_eq!(client.delete(DeleteUser { id }).await.unwrap(), 1);
+}
+
+/// A pooled client used as a `dyn ParsqlExecutor`.
+#[cfg(feature = "tokio-postgres")]
+#[tokio::test]
+async fn dyn_executor() {
+    use executor_models::*;
+    use parsql::tokio_postgres::ParsqlExecutor;
+
+    let Some((_db, pool)) = connect().await else { return };
+    let client: deadpool_postgres::Object = pool.get().await.unwrap();
+    let executor: &dyn ParsqlExecutor = &client;
+
+    let user = NewUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let id: i64 = executor.insert(user).await.unwrap();
+    let record = |id| UserRecord { id, name: String::new(), email: String::new(), state: 0 };
+    let user = executor.fetch(record(id)).await.unwrap();
+    assert_eq!((user.name.as_str(), user.state), ("ali", 1));
+    assert_eq!(executor.fetch_all(record(id)).await.unwrap().len(), 1);
+    let email: String = executor.select(record(id), |row| row.try_get("email")).await.unwrap();
+    assert_eq!(email, "ali@example.com");
+    let names = executor.select_all(record(id), |row| row.get::<_, String>("name")).await.unwrap();
+    assert_eq!(names, ["ali"]);
+
+    assert_eq!(executor.update(UserEmail { id, email: "ali@example.org".into() }).await.unwrap(), 1);
+    assert_eq!(executor.fetch(record(id)).await.unwrap().email, "ali@example.org");
+    assert_eq!(executor.delete(RemoveUser { id }).await.unwrap(), 1);
+    assert!(executor.fetch(record(id)).await.is_err());
+}
+
+#[tokio::test]
+async fn transactions() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+    let mut client = pool.get().await.unwrap();
+
+    let tx = transactional::begin(&mut client).await.unwrap();
+    let (tx, deleted) = transactional::tx_delete(tx, DeleteUser { id: ids[0] }).await.unwrap();
+    assert_eq!(deleted, 1);
+    tx.commit().await.unwrap();
+
+    let tx = transactional::begin(&mut client).await.unwrap();
+    let (tx, deleted) = transactional::tx_delete(tx, DeleteUser { id: ids[1] }).await.unwrap();
+    assert_eq!(deleted, 1);
+    let (tx, active) = transactional::tx_fetch_all(tx, UsersByState::new(1)).await.unwrap();
+    assert_eq!(active.len(), 2);
+    let update = UpdateUser { id: ids[2], name: "Renamed".into(), email: "renamed@example.com".into() };
+    let (tx, updated) = transactional::tx_update(tx, update).await.unwrap();
+    assert_eq!(updated, 1);
+    let (tx, user) = transactional::tx_fetch(tx, UserById::new(ids[2])).await.unwrap();
+    assert_eq!(user.name, "Renamed");
+    assert!(!transactional::is_aborted(&tx).await.unwrap());
+    transactional::rollback(tx).await.unwrap();
+
+    assert!(pool.fetch(&UserById::new(ids[0])).await.is_err());
+    assert!(pool.fetch(&UserById::new(ids[1])).await.is_ok());
+
+    // Hata veren bir ifadeden sonra işlem yalnızca geri alınabilir
+    let tx = transactional::begin(&mut client).await.unwrap();
+    let (tx, deleted) = transactional::tx_delete(tx, DeleteUser { id: ids[1] }).await.unwrap();
+    assert_eq!(deleted, 1);
+    assert!(tx.batch_execute("SELECT * FROM missing_table").await.is_err());
+    assert!(transactional::is_aborted(&tx).await.unwrap());
+    transactional::rollback(tx).await.unwrap();
+    assert!(pool.fetch(&UserById::new(ids[1])).await.is_ok());
+}
+
+#[tokio::test]
+async fn validation() {
+    let Some((_db, pool)) = connect().await else { return };
+
+    let user = InsertUser { name: "ali".into(), email: "ali.example.com".into(), state: 1 };
+    match pool.insert::<_, i64>(user).await {
+        Err(DeadpoolError::Validation(e)) => assert_eq!(e.field, "email"),
+        other => panic!("expected a validation error, got {:?}", other.map(|_| ())),
+    }
+    assert!(pool.fetch_all(&UsersByState::new(1)).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn pagination() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    let page = pool.fetch_all(&UsersPage::new(1, 2, 1)).await.unwrap();
+    assert_eq!(page.iter().map(|u| u.id).collect::<Vec<_>>(), ids[1..3]);
+
+    let page = get_all_limited(&pool, &UsersByState::new(1), 3, 2).await.unwrap();
+    assert_eq!(page.iter().map(|u| u.id).collect::<Vec<_>>(), ids[2..4]);
+
+    let page = UsersByStatePage::nth(UsersByState::new(1), 2, 2);
+    assert_eq!(get_page(&pool, &page).await.unwrap().len(), 2);
+    assert!(get_page(&pool, &page.next()).await.unwrap().is_empty());
+
+    let last = get_last(&pool, &UsersByState::new(1), "id").await.unwrap();
+    assert_eq!(last.map(|u| u.id), Some(ids[3]));
+    assert!(get_first(&pool, &UsersByState::new(2), "name").await.unwrap().is_none());
+    let error = get_first(&pool, &UsersByState::new(1), "email").await.unwrap_err();
+    assert!(matches!(error, DeadpoolError::Validation(e) if e.field == "email"));
+}
+
+#[tokio::test]
+async fn fetch_by_keys() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    let users = get_many::<UserById, i64>(&pool, &[ids[4], ids[0]]).await.unwrap();
+    assert_eq!(users.found.iter().map(|user| user.id).collect::<Vec<_>>(), [ids[4], ids[0]]);
+    assert!(users.is_complete());
+}
+
+#[tokio::test]
+async fn raw_rows() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    let page = get_all_with_rows(&pool, &UserPage::new(1)).await.unwrap();
+    assert_eq!(page.iter().map(|(user, _)| user.id).collect::<Vec<_>>(), ids[..2]);
+    assert!(page.iter().all(|(_, row)| row.get::<_, i64>("total_count") == 4));
+
+    let (user, row) = get_with_row(&pool, &UserById::new(ids[5])).await.unwrap();
+    assert_eq!((user.name.as_str(), row.get::<_, &str>("email")), ("elif", "elif@example.com"));
+}
+
+#[tokio::test]
+async fn array_columns() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    assert_grouped(&pool.fetch_all(&UsersGroupedByState::new()).await.unwrap(), &ids);
+}
+
+#[tokio::test]
+async fn prepared_statements() {
+    let Some((_db, pool)) = connect().await else { return };
+    let client = pool.get().await.unwrap();
+    assert_eq!(registry::prepare_all(&client).await.unwrap(), 6);
+}
+
+#[tokio::test]
+async fn search_path() {
+    let Some((db, pool)) = connect().await else { return };
+    seed(&pool).await;
+    let mut client = pool.get().await.unwrap();
+    let tenant = format!("{}_b", db.schema());
+    client.batch_execute(&common::tenant_sql(&tenant)).await.unwrap();
+
+    let users = session::with_search_path(&mut client, &tenant, |tx| {
+        Box::pin(async move { tx.fetch_all(UsersByState::new(1)).await })
+    })
+    .await
+    .unwrap();
+    assert!(users.is_empty());
+
+    // SET LOCAL transaction ile birlikte sona erer
+    assert_eq!(client.fetch_all(&UsersByState::new(1)).await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn object_graph() {
+    let Some((_db, pool)) = connect().await else { return };
+    let mut client = pool.get().await.unwrap();
+    client.batch_execute(common::INTEGRITY_SQL).await.unwrap();
+
+    let id = transactional::persist_graph(&mut client, InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 }, vec![InsertPost::new(1), InsertPost::new(2)]).await.unwrap();
+    let posts: Vec<(i64, i64)> = client.query(common::POSTS_SQL, &[]).await.unwrap().iter().map(|row| (row.get(0), row.get(1))).collect();
+    assert_eq!(posts, [(1, id), (2, id)]);
+
+    // İkinci yazı eklenemediği için yazar ve ilk yazı da geri alınır
+    let failed = transactional::persist_graph(&mut client, InsertUser { name: "ayse".into(), email: "ayse@example.com".into(), state: 1 }, vec![InsertPost::new(3), InsertPost::new(1)]).await;
+    assert!(failed.is_err());
+    assert_eq!(client.query(common::POSTS_SQL, &[]).await.unwrap().len(), 2);
+    assert!(client.fetch_all(&UsersByState::new(1)).await.unwrap().iter().all(|user| user.name == "ali"));
+}
+
+#[tokio::test]
+async fn csv_export() {
+    let Some((_db, pool)) = connect().await else { return };
+    seed(&pool).await;
+    pool.insert::<_, i64>(InsertUser { name: common::QUOTED_NAME.into(), email: "oneil@example.com".into(), state: 1 }).await.unwrap();
+
+    // Dizi, tırnak içeren metin ve NULL parametreler COPY sorgusuna literal olarak yazılır
+    let mut csv = Vec::new();
+    let params = ExportUsers { names: vec!["ali".into(), common::QUOTED_NAME.into()], email: None };
+    let written = export_csv(&pool, &params, &mut csv).await.unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), common::EXPORT_CSV);
+    assert_eq!(written, common::EXPORT_CSV.len() as u64);
+
+    let mut csv = Vec::new();
+    let params = ExportUsers { names: vec!["ali".into(), common::QUOTED_NAME.into()], email: Some("ali@example.com".into()) };
+    export_csv(&pool, &params, &mut csv).await.unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap().lines().collect::<Vec<_>>(), ["id,name,email", "7,\"o'neil, \"\"jr\"\"\",oneil@example.com"]);
+}
+
+#[tokio::test]
+async fn two_phase_commit() {
+    let Some((db, pool)) = connect().await else { return };
+    let enabled: bool = pool.get().await.unwrap().query_one(common::PREPARED_TRANSACTIONS_SQL, &[]).await.unwrap().get(0);
+    if !enabled {
+        eprintln!("skipped: the server has max_prepared_transactions = 0");
+        return;
+    }
+    async fn names(pool: &Pool) -> Vec<String> {
+        pool.fetch_all(&UsersByState::new(1)).await.unwrap().into_iter().map(|user| user.name).collect()
+    }
+    let user = |name: &str| InsertUser { name: name.into(), email: format!("{}@example.com", name), state: 1 };
+    let mut orders = pool.get().await.unwrap();
+    let mut billing = pool.get().await.unwrap();
+
+    let orders_tx = orders.transaction().await.unwrap();
+    let billing_tx = billing.transaction().await.unwrap();
+    orders_tx.insert(user("ali")).await.unwrap();
+    billing_tx.insert(user("ayse")).await.unwrap();
+    let gids = two_phase::commit(vec![orders_tx, billing_tx], &NoRecovery).await.unwrap();
+    assert_eq!(gids.len(), 2);
+    assert_eq!(names(&pool).await, ["ali", "ayse"]);
+    assert!(!two_phase::pending(&pool).await.unwrap().iter().any(|gid| gids.contains(gid)));
+
+    // Hazırlanamayan bir katılımcı, hazırlanmış olanları da geri aldırır
+    let orders_tx = orders.transaction().await.unwrap();
+    let billing_tx = billing.transaction().await.unwrap();
+    orders_tx.insert(user("mehmet")).await.unwrap();
+    assert!(billing_tx.batch_execute("SELECT * FROM missing_table").await.is_err());
+    let error = two_phase::commit(vec![orders_tx, billing_tx], &NoRecovery).await.unwrap_err();
+    assert!(matches!(error, TwoPhaseError::Prepare(_)));
+    assert_eq!(names(&pool).await, ["ali", "ayse"]);
+
+    // Yarıda kalan commit'lerin hazırlanmış transaction'ları sonradan bitirilir
+    let kept = format!("{}{}-kept", two_phase::GID_PREFIX, db.schema());
+    let dropped = format!("{}{}-dropped", two_phase::GID_PREFIX, db.schema());
+    orders.batch_execute(&common::prepared_insert_sql(&kept, "zeynep")).await.unwrap();
+    orders.batch_execute(&common::prepared_insert_sql(&dropped, "can")).await.unwrap();
+    let pending = two_phase::pending(&pool).await.unwrap();
+    assert!(pending.contains(&kept) && pending.contains(&dropped));
+    two_phase::commit_prepared(&pool, &kept).await.unwrap();
+    two_phase::rollback_prepared(&pool, &dropped).await.unwrap();
+    assert!(!two_phase::pending(&pool).await.unwrap().iter().any(|gid| *gid == kept || *gid == dropped));
+    assert_eq!(names(&pool).await, ["ali", "ayse", "zeynep"]);
+}
+
+#[tokio::test]
+async fn advisory_locks() {
+    let Some((_db, pool)) = connect().await else { return };
+    let client = pool.get().await.unwrap();
+    let other = pool.get().await.unwrap();
+    let key = locks::lock_key("parsql_it_deadpool_locks");
+
+    let value = locks::with_advisory_lock(&client, key, |_| async {
+        assert!(!locks::try_advisory_lock(&other, key).await?);
+        Ok::<_, tokio_postgres::Error>(42)
+    })
+    .await
+    .unwrap();
+    assert_eq!(value, 42);
+
+    // Kilit aynı havuz istemcisinde bırakıldı, diğer istemci alabilir
+    assert!(locks::try_advisory_lock(&other, key).await.unwrap());
+    assert!(locks::advisory_unlock(&other, key).await.unwrap());
+}
+
+#[tokio::test]
+async fn constraint_violations() {
+    let Some((_db, pool)) = connect().await else { return };
+    seed(&pool).await;
+    let client = pool.get().await.unwrap();
+    client.batch_execute("CREATE UNIQUE INDEX users_email_key ON users (email)").await.unwrap();
+
+    let duplicate = InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let error = pool.insert::<_, i64>(duplicate).await.unwrap_err();
+    assert!(matches!(error, DeadpoolError::Postgres(_)));
+    assert!(error.is_unique_violation());
+    assert!(error.constraint().unwrap().is("users_email_key"));
+}
+
+#[tokio::test]
+async fn integrity_control() {
+    let Some((_db, pool)) = connect().await else { return };
+    let mut client = pool.get().await.unwrap();
+    client.batch_execute(common::INTEGRITY_SQL).await.unwrap();
+
+    let tx = client.transaction().await.unwrap();
+    tx.set_constraints_deferred(&["fk_posts_user"]).await.unwrap();
+    tx.disable_triggers("posts").await.unwrap();
+    tx.batch_execute(common::ORPHAN_POST_SQL).await.unwrap();
+    tx.batch_execute(common::AUTHOR_SQL).await.unwrap();
+    tx.set_constraints_immediate(&[]).await.unwrap();
+    tx.enable_triggers("posts").await.unwrap();
+    tx.commit().await.unwrap();
+
+    // Tetikleyici devre dışıyken yazılan gönderi denetlenmez, sonrakiler denetlenir
+    client.batch_execute("INSERT INTO posts VALUES (2, 100)").await.unwrap();
+    let audited: Vec<i64> = client.query("SELECT post_id FROM post_audit", &[]).await.unwrap().iter().map(|row| row.get(0)).collect();
+    assert_eq!(audited, [2]);
+
+    let tx = client.transaction().await.unwrap();
+    tx.set_constraints_deferred(&[]).await.unwrap();
+    tx.batch_execute("INSERT INTO posts VALUES (3, 999)").await.unwrap();
+    assert!(tx.set_constraints_immediate(&["fk_posts_user"]).await.is_err());
+}
+
+#[tokio::test]
+async fn audited_writes() {
+    let Some((_db, pool)) = connect().await else { return };
+    let mut client = pool.get().await.unwrap();
+    client.batch_execute(audit_log::CREATE_TABLE).await.unwrap();
+
+    let user = AuditedAddUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let id: i64 = pool.insert(user).await.unwrap();
+    let renamed = session::with_rls_user(&mut client, 42, |tx| {
+        Box::pin(async move { tx.update(AuditedRenameUser { id, name: "veli".into() }).await })
+    })
+    .await
+    .unwrap();
+    assert_eq!(renamed, 1);
+    assert_eq!(pool.delete(AuditedDeleteUser { id }).await.unwrap(), 1);
+    assert_eq!(pool.delete(AuditedDeleteUser { id }).await.unwrap(), 0);
+
+    let rows = client.query(common::AUDIT_ROWS_SQL, &[]).await.unwrap();
+    let rows: Vec<_> = rows.iter().map(|row| (row.get(0), row.get(1), row.get(2), row.get(3))).collect();
+    common::assert_audit_rows(&rows, id);
+}
+
+#[tokio::test]
+async fn dynamic_queries() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    let rows = query_dynamic(&pool, common::DYNAMIC_SQL, &[&1i16]).await.unwrap();
+    common::assert_dynamic_rows(&rows, &ids);
+}
+
+#[tokio::test]
+async fn priority_lanes() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+    let executor = PriorityExecutor::new(pool.clone(), 1);
+    let report = || async {
+        let client = pool.get().await?;
+        client.execute("SELECT pg_sleep(0.3)", &[]).await?;
+        Ok::<_, DeadpoolError>(())
+    };
+
+    let started = Instant::now();
+    let (first, second, user) = tokio::join!(
+        executor.background(report()),
+        executor.run(Lane::Background, report()),
+        async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert_eq!(executor.background_running(), 1);
+            let user = executor.run(Lane::Interactive, get(&pool, &UserById::new(ids[0]))).await;
+            (user, started.elapsed())
+        }
+    );
+    first.unwrap();
+    second.unwrap();
+    // The user is read while the first report runs; the second waits for its turn
+    assert_eq!(user.0.unwrap().name, "ali");
+    assert!(user.1 < Duration::from_millis(300));
+    assert!(started.elapsed() >= Duration::from_millis(600));
+    assert_eq!(executor.background_running(), 0);
+}
+
+#[tokio::test]
+async fn background_lane_cap() {
+    let Some((_db, pool)) = connect().await else { return };
+    let executor = PriorityExecutor::new(pool.clone(), 2);
+    let running = AtomicUsize::new(0);
+    let peak = AtomicUsize::new(0);
+    let report = || async {
+        let client = pool.get().await?;
+        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+        peak.fetch_max(now, Ordering::SeqCst);
+        client.execute("SELECT pg_sleep(0.1)", &[]).await?;
+        running.fetch_sub(1, Ordering::SeqCst);
+        Ok::<_, DeadpoolError>(())
+    };
+
+    // Six reports on a pool of four never hold more than two connections at once
+    let started = Instant::now();
+    let results = tokio::join!(
+        executor.background(report()),
+        executor.background(report()),
+        executor.background(report()),
+        executor.background(report()),
+        executor.background(report()),
+        executor.background(report()),
+    );
+    for result in [results.0, results.1, results.2, results.3, results.4, results.5] {
+        result.unwrap();
+    }
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    assert_eq!(executor.background_running(), 0);
+}
+
+#[tokio::test]
+async fn combined_filters() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    let filters = (&UsersByState::new(1), &NameLike { name_pattern: "%e%".into() });
+    let users: Vec<UserRow> = get_all_combined(&pool, filters).await.unwrap();
+    assert_eq!(users.iter().map(|user| user.id).collect::<Vec<_>>(), ids[1..4]);
+}
+
+#[tokio::test]
+async fn repository() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    // Her işlem havuzdan ayrı bir bağlantı alır
+    let users = Repository::<Users, _>::new(pool.clone());
+    let id: i64 = users.create(InsertUser { name: "veli".into(), email: "veli@example.com".into(), state: 0 }).await.unwrap();
+    assert_eq!(users.find(ids[0]).await.unwrap().name, "ali");
+    let passive = users.find_all(UsersByState::new(0)).await.unwrap();
+    assert_eq!(passive.iter().map(|user| user.id).collect::<Vec<_>>(), [ids[4], ids[5], id]);
+
+    let update = UpdateUser { id, name: "veli".into(), email: "veli@example.org".into() };
+    assert_eq!(users.update(update).await.unwrap(), 1);
+    assert_eq!(users.find(id).await.unwrap().email, "veli@example.org");
+    assert_eq!(users.delete(id).await.unwrap(), 1);
+    assert_eq!(users.delete(id).await.unwrap(), 0);
+    assert!(users.find(id).await.is_err());
+}
+
+#[tokio::test]
+async fn cursor_pages() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    let mut cursor = Cursor::open(&pool, &UsersByState::new(1)).await.unwrap();
+    let first: Vec<UsersByState> = cursor.fetch_next(3).await.unwrap();
+    pool.get().await.unwrap().batch_execute(common::CHANGE_ACTIVE_USERS_SQL).await.unwrap();
+    let rest: Vec<UsersByState> = cursor.fetch_next(3).await.unwrap();
+
+    // The pages show the rows as they were when the cursor was opened
+    let read = first.iter().chain(&rest).map(|user| user.id).collect::<Vec<_>>();
+    assert_eq!(read, ids[..4]);
+    assert!(cursor.is_exhausted());
+    cursor.close().await.unwrap();
+
+    let cursor = Cursor::open(&pool, &UsersByState::new(1)).await.unwrap();
+    cursor.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn monthly_partitions() {
+    let Some((_db, pool)) = connect().await else { return };
+    pool.get().await.unwrap().batch_execute(common::EVENTS_SQL).await.unwrap();
+    let event = || InsertEvent { kind: "login".into(), created_at: common::event_time() };
+    assert!(pool.insert::<_, i64>(event()).await.is_err());
+
+    let month = partitions::Month::containing(common::event_time());
+    ensure_monthly_partition::<InsertEvent>(&pool, month).await.unwrap();
+    ensure_monthly_partition::<InsertEvent>(&pool, month).await.unwrap();
+    let _: i64 = pool.insert(event()).await.unwrap();
+    let count: i64 = pool.get().await.unwrap().query_one(common::MARCH_EVENTS_SQL, &[]).await.unwrap().get(0);
+    assert_eq!((InsertEvent::partition_column(), count), ("created_at", 1));
+}
+
+#[tokio::test]
+async fn case_insensitive_lookup() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    assert_eq!(get(&pool, &UserByEmail::new("Ayse@Example.COM")).await.unwrap().id, ids[1]);
+    let passive = DeleteUserByEmail { email: "CAN@example.com".into(), state: 0 };
+    assert_eq!(pool.delete(passive).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn batched_queries() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+
+    let queries = (&UsersByState::new(1), &UsersByState::new(0), &UserByEmail::new("ELIF@example.com"));
+    let (active, passive, found) = batch_get(&pool, queries).await.unwrap();
+    assert_eq!(active.iter().map(|user| user.id).collect::<Vec<_>>(), ids[..4]);
+    assert_eq!(passive.iter().map(|user| user.id).collect::<Vec<_>>(), ids[4..]);
+    assert_eq!(found.iter().map(|user| user.id).collect::<Vec<_>>(), [ids[5]]);
+}
+
+#[tokio::test]
+async fn cancellation() {
+    let Some((_db, pool)) = connect().await else { return };
+    let ids = seed(&pool).await;
+    let query = UserById::new(ids[0]);
+
+    let limits = Cancellation::new().timeout(Duration::from_secs(5));
+    assert_eq!(limits.run(get(&pool, &query)).await.unwrap().name, "ali");
+
+    // An exhausted pool waits for a connection until the deadline cuts it short
+    let mut held = Vec::new();
+    for _ in 0..pool.status().max_size {
+        held.push(pool.get().await.unwrap());
+    }
+    let limits = Cancellation::new().timeout(Duration::from_millis(50));
+    assert!(matches!(limits.run(get(&pool, &query)).await, Err(DeadpoolError::Timeout)));
+
+    let token = CancellationToken::new();
+    let limits = Cancellation::new().token(token.clone());
+    tokio::spawn(async move { token.cancel() });
+    assert!(matches!(limits.run(get_all(&pool, &UsersByState::new(1))).await, Err(DeadpoolError::Cancelled)));
+
+    drop(held);
+    assert_eq!(get(&pool, &query).await.unwrap().name, "ali");
+
+    // A statement cut short by the deadline is cancelled on the server as well
+    let limits = Cancellation::new().timeout(Duration::from_millis(200));
+    let started = Instant::now();
+    let sleep = query_dynamic(&pool, "SELECT pg_sleep(30)", &[]);
+    assert!(matches!(limits.run(sleep).await, Err(DeadpoolError::Timeout)));
+    assert!(started.elapsed() < Duration::from_secs(5));
+    let client = pool.get().await.unwrap();
+    let mut running = i64::MAX;
+    for _ in 0..50 {
+        let row = client
+            .query_one("SELECT count(*) FROM pg_stat_activity WHERE state = 'active' AND query LIKE '%pg_sleep(30)%' AND pid <> pg_backend_pid()", &[])
+            .await
+            .unwrap();
+        running = row.get(0);
+        if running == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(running, 0);
+}
@@ -0,0 +1,894 @@
+//! Integration tests of the synchronous `postgres` backend.
+
+mod common;
+
+#[allow(dead_code)]
+mod models {
+    use parsql::postgres::{macros::*, registry, traits::*};
+    #[cfg(feature = "postgis")]
+    use parsql::postgres::{geo_types::Point, PostGis};
+    use postgres::{types::ToSql, Error, Row};
+
+    include!("common/models.rs");
+}
+
+use models::*;
+use parsql::postgres::{
+    audit_log, call, constraint::{ConstraintKind, ConstraintViolation}, cursor, delete, delete_where, export_csv, fetch, fetch_all, fetch_all_combined,
+    fixtures::{self, Fixtures},
+    integrity::IntegrityControl,
+    fetch_all_limited, fetch_all_with_rows, fetch_first, fetch_last, fetch_many, fetch_page, fetch_with_row, insert,
+    ensure_monthly_partition, insert_returning_id, insert_returning_opt, locks, partitions, query_dynamic, refresh_materialized_view, registry, repository::Repository, session, traits::{CrudOps, Partitioned, ValidationError, WhereFragments}, transactional, update,
+    update_patch, update_where, upsert_many, BatchOptions, OnError, Progress,
+};
+use parsql_core::{criteria::{combined_query, CombineError}, Dialect};
+use parsql_testing::blocking::TestDatabase;
+use postgres::Client;
+use std::error::Error as _;
+
+/// Starts a test database holding an empty `users` table and connects to it.
+fn connect() -> Option<(TestDatabase, Client)> {
+    if !common::enabled() {
+        return None;
+    }
+    let db = TestDatabase::start(|client| client.batch_execute(common::USERS_SQL))
+        .expect("cannot start the test database");
+    let client = db.client().unwrap();
+    Some((db, client))
+}
+
+fn seed(client: &mut Client) -> Vec<i64> {
+    common::seed()
+        .into_iter()
+        .map(|(name, email, state)| insert(client, InsertUser { name, email, state }).unwrap())
+        .collect()
+}
+
+#[test]
+fn generated_sql() {
+    assert_sql_snapshots();
+}
+
+#[test]
+fn crud() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let user = fetch(&mut client, &UserById::new(ids[0])).unwrap();
+    assert_eq!((user.name.as_str(), user.state), ("ali", 1));
+    let with_contact = fetch(&mut client, &UserWithContact::new(ids[0])).unwrap();
+    assert_eq!(with_contact.contact, UserContact { email: "ali@example.com".into(), state: 1 });
+
+    let active = fetch_all(&mut client, &UsersByState::new(1)).unwrap();
+    assert_eq!(active.len(), 4);
+
+    let rename = UpdateUser { id: ids[0], name: "veli".into(), email: "veli@example.com".into() };
+    assert_eq!(update(&mut client, rename).unwrap(), 1);
+    let missing = UpdateUser { id: -1, name: String::new(), email: String::new() };
+    assert_eq!(update(&mut client, missing).unwrap(), 0);
+    assert_eq!(fetch(&mut client, &UserById::new(ids[0])).unwrap().name, "veli");
+
+    assert_eq!(delete(&mut client, DeleteUser { id: ids[0] }).unwrap(), 1);
+    assert!(fetch(&mut client, &UserById::new(ids[0])).is_err());
+
+    let user = AddUser { name: "deniz".into(), email: "deniz@example.com".into(), state: 1 };
+    let id: i64 = insert_returning_id(&mut client, user).unwrap();
+    assert_eq!(fetch(&mut client, &UserById::new(id)).unwrap().name, "deniz");
+}
+
+#[test]
+fn single_row_reads() {
+    let Some((_db, mut client)) = connect() else { return };
+    seed(&mut client);
+
+    // İki satırla eşleşen fetch, LIMIT 2 ile sınırlansa da hata verir
+    assert!(fetch(&mut client, &UsersByState::new(0)).is_err());
+}
+
+#[test]
+fn transactions() {
+    let Some((_db, mut client)) = connect() else { return };
+
+    let tx = transactional::begin(&mut client).unwrap();
+    let user = InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let (tx, committed): (_, i64) = transactional::tx_insert(tx, user).unwrap();
+    tx.commit().unwrap();
+
+    let tx = transactional::begin(&mut client).unwrap();
+    let user = InsertUser { name: "ayse".into(), email: "ayse@example.com".into(), state: 1 };
+    let (tx, rolled_back): (_, i64) = transactional::tx_insert(tx, user).unwrap();
+    let (mut tx, seen) = transactional::tx_fetch(tx, &UserById::new(rolled_back)).unwrap();
+    assert_eq!(seen.name, "ayse");
+    assert!(!transactional::is_aborted(&mut tx).unwrap());
+    transactional::rollback(tx).unwrap();
+
+    assert!(fetch(&mut client, &UserById::new(committed)).is_ok());
+    assert!(fetch(&mut client, &UserById::new(rolled_back)).is_err());
+
+    // Hata veren bir ifadeden sonra transaction yalnızca geri alınabilir
+    let mut tx = transactional::begin(&mut client).unwrap();
+    assert!(tx.batch_execute("SELECT * FROM missing_table").is_err());
+    assert!(transactional::is_aborted(&mut tx).unwrap());
+    transactional::rollback(tx).unwrap();
+
+    // Ödünç alan yardımcılar transaction'ı döngü boyunca taşımadan kullanır
+    let mut tx = transactional::begin(&mut client).unwrap();
+    let mut ids = Vec::new();
+    for (name, email, state) in common::seed() {
+        ids.push(transactional::tx_insert_ref::<_, i64>(&mut tx, InsertUser { name, email, state }).unwrap());
+    }
+    for id in &ids[1..] {
+        let user = transactional::tx_fetch_ref(&mut tx, &UserById::new(*id)).unwrap();
+        let rename = UpdateUser { id: *id, name: user.name.to_uppercase(), email: user.email };
+        assert_eq!(transactional::tx_update_ref(&mut tx, rename).unwrap(), 1);
+    }
+    assert_eq!(transactional::tx_delete_ref(&mut tx, DeleteUser { id: ids[0] }).unwrap(), 1);
+    tx.commit().unwrap();
+
+    let active = fetch_all(&mut client, &UsersByState::new(1)).unwrap();
+    let names = active.iter().map(|user| user.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["ali", "AYSE", "MEHMET", "ZEYNEP"]);
+}
+
+#[test]
+fn temp_tables() {
+    let Some((_db, mut client)) = connect() else { return };
+    seed(&mut client);
+
+    let mut tx = transactional::begin(&mut client).unwrap();
+    assert_eq!(transactional::create_temp_from(&mut tx, &UsersByState::new(1), "tmp_active").unwrap(), 4);
+    tx.execute("UPDATE users SET state = 2 FROM tmp_active WHERE users.id = tmp_active.id", &[]).unwrap();
+    tx.commit().unwrap();
+
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(2)).unwrap().len(), 4);
+    // ON COMMIT DROP: tablo transaction ile birlikte silinir
+    assert!(client.batch_execute("SELECT * FROM tmp_active").is_err());
+}
+
+#[test]
+fn insert_all() {
+    let Some((_db, mut client)) = connect() else { return };
+    client.batch_execute("CREATE UNIQUE INDEX ON users (email)").unwrap();
+    let batch = || {
+        vec![
+            InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 },
+            InsertUser { name: "veli".into(), email: "ali@example.com".into(), state: 1 },
+            InsertUser { name: "ayse".into(), email: "ayse.example.com".into(), state: 1 },
+            InsertUser { name: "fatma".into(), email: "fatma@example.com".into(), state: 1 },
+        ]
+    };
+
+    // Hatalı satırlar savepoint'e geri alınır, geri kalanlar kaydedilir
+    let mut progress = Vec::new();
+    let tx = transactional::begin(&mut client).unwrap();
+    let (tx, results) =
+        transactional::tx_insert_all::<_, i64>(tx, batch(), OnError::Continue, |p| progress.push(p)).unwrap();
+    tx.commit().unwrap();
+    assert_eq!(results.iter().map(Result::is_ok).collect::<Vec<_>>(), [true, false, false, true]);
+    assert_eq!(progress.last(), Some(&Progress { done: 4, total: 4, failed: 2 }));
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(1)).unwrap().len(), 2);
+
+    let tx = transactional::begin(&mut client).unwrap();
+    let (tx, results) = transactional::tx_insert_all::<_, i64>(tx, batch(), OnError::Stop, |_| {}).unwrap();
+    tx.rollback().unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
+
+#[test]
+fn validation() {
+    let Some((_db, mut client)) = connect() else { return };
+
+    let user = InsertUser { name: "ali".into(), email: "ali.example.com".into(), state: 1 };
+    let error = insert::<_, i64>(&mut client, user).unwrap_err();
+    assert_eq!(ValidationError::find(&error).unwrap().field, "email");
+
+    // Reddedilen varlık sunucuya gitmez, transaction kullanılmaya devam eder
+    let mut tx = transactional::begin(&mut client).unwrap();
+    let user = InsertUser { name: " ".into(), email: "ali@example.com".into(), state: 1 };
+    let error = tx.insert::<_, i64>(user).unwrap_err();
+    assert_eq!(ValidationError::find(&error).unwrap().field, "name");
+    let user = InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    tx.insert::<_, i64>(user).unwrap();
+    tx.commit().unwrap();
+
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(1)).unwrap().len(), 1);
+}
+
+#[test]
+fn pagination() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let page = fetch_all(&mut client, &UsersPage::new(1, 2, 1)).unwrap();
+    assert_eq!(page.iter().map(|u| u.id).collect::<Vec<_>>(), ids[1..3]);
+
+    let page = fetch_all_limited(&mut client, &UsersByState::new(1), 3, 2).unwrap();
+    assert_eq!(page.iter().map(|u| u.id).collect::<Vec<_>>(), ids[2..4]);
+
+    let page = UsersByStatePage::nth(UsersByState::new(1), 2, 2);
+    assert_eq!(fetch_page(&mut client, &page).unwrap().len(), 2);
+    assert!(fetch_page(&mut client, &page.next()).unwrap().is_empty());
+
+    let last = fetch_last(&mut client, &UsersByState::new(1), "name").unwrap();
+    assert_eq!(last.map(|u| u.name).as_deref(), Some("zeynep"));
+    assert!(fetch_first(&mut client, &UsersByState::new(2), "id").unwrap().is_none());
+    let error = fetch_first(&mut client, &UsersByState::new(1), "email").unwrap_err();
+    assert_eq!(ValidationError::find(&error).unwrap().field, "email");
+}
+
+#[test]
+fn paginated_pages() {
+    // Sayfa numarası 1'den başlar; 0 ve negatif numaralar ilk sayfayı verir
+    let page = UsersByStatePage::nth(UsersByState::new(1), 3, 20);
+    assert_eq!((page.limit, page.offset), (20, 40));
+    assert_eq!(UsersByStatePage::nth(UsersByState::new(1), 0, 20).offset, 0);
+    let next = page.next();
+    assert_eq!((next.limit, next.offset), (20, 60));
+
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    // Son sayfa eksik kalır, ondan sonraki sayfa boştur
+    let first = UsersByStatePage::nth(UsersByState::new(1), 1, 3);
+    assert_eq!(fetch_page(&mut client, &first).unwrap().iter().map(|u| u.id).collect::<Vec<_>>(), ids[..3]);
+    let last = first.next();
+    assert_eq!(fetch_page(&mut client, &last).unwrap().iter().map(|u| u.id).collect::<Vec<_>>(), ids[3..4]);
+    assert!(fetch_page(&mut client, &last.next()).unwrap().is_empty());
+
+    // Sayfalar boş sayfaya kadar dolaşıldığında tüm satırlar bir kez okunur
+    let mut page = UsersByStatePage::nth(UsersByState::new(1), 1, 3);
+    let mut total = 0;
+    loop {
+        let rows = fetch_page(&mut client, &page).unwrap();
+        if rows.is_empty() {
+            break;
+        }
+        total += rows.len();
+        page = page.next();
+    }
+    assert_eq!(total, fetch_all(&mut client, &UsersByState::new(1)).unwrap().len());
+
+    // Hiç satırla eşleşmeyen sorgunun ilk sayfası da boştur
+    assert!(fetch_page(&mut client, &UsersByStatePage::nth(UsersByState::new(2), 1, 3)).unwrap().is_empty());
+}
+
+#[test]
+fn fetch_by_keys() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let unknown = ids[5] + 100;
+    let users = fetch_many::<UserById, i64>(&mut client, &[ids[3], unknown, ids[0], ids[3]]).unwrap();
+    assert_eq!(users.found.iter().map(|user| user.id).collect::<Vec<_>>(), [ids[3], ids[0]]);
+    assert_eq!(users.missing, [unknown]);
+    assert!(fetch_many::<UserById, i64>(&mut client, &[]).unwrap().found.is_empty());
+}
+
+#[test]
+fn conditional_filters() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let ids_of = |users: Vec<UsersFiltered>| users.iter().map(|u| u.id).collect::<Vec<_>>();
+    assert_eq!(ids_of(fetch_all(&mut client, &UsersFiltered::new(None, None)).unwrap()), ids);
+    assert_eq!(ids_of(fetch_all(&mut client, &UsersFiltered::new(Some(0), None)).unwrap()), ids[4..]);
+    assert_eq!(ids_of(fetch_all(&mut client, &UsersFiltered::new(None, Some("a%"))).unwrap()), ids[..2]);
+    assert!(fetch_all(&mut client, &UsersFiltered::new(Some(0), Some("a%"))).unwrap().is_empty());
+}
+
+#[test]
+fn joined_statements() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let copy = CopyUserState { source_id: ids[4], name_prefix: "a%".into() };
+    assert_eq!(update(&mut client, copy).unwrap(), 2);
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(0)).unwrap().len(), 4);
+
+    assert_eq!(delete(&mut client, DeleteUsersLike { source_id: ids[4] }).unwrap(), 4);
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(1)).unwrap().len(), 2);
+}
+
+/// `Decimal` fields bind and read `NUMERIC` columns without rounding.
+#[cfg(feature = "decimal")]
+#[test]
+fn decimal_columns() {
+    use parsql::postgres::{macros::*, traits::*, Decimal};
+    use postgres::{types::ToSql, Error, Row};
+
+    #[derive(Insertable, SqlParams)]
+    #[table("payments")]
+    #[returning("id")]
+    struct InsertPayment {
+        amount: Decimal,
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug)]
+    #[table("payments")]
+    #[where_clause("amount >= $")]
+    #[order_by("id")]
+    struct PaymentsFrom {
+        amount: Decimal,
+    }
+
+    let Some((_db, mut client)) = connect() else { return };
+    client
+        .batch_execute("CREATE TABLE payments (id BIGSERIAL PRIMARY KEY, amount NUMERIC(20, 4) NOT NULL)")
+        .unwrap();
+    for amount in [Decimal::new(1, 1), Decimal::new(12_345_678_901_234_567, 4)] {
+        let _: i64 = insert(&mut client, InsertPayment { amount }).unwrap();
+    }
+
+    let payments = fetch_all(&mut client, &PaymentsFrom { amount: Decimal::new(2, 1) }).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].amount.to_string(), "1234567890123.4567");
+}
+
+#[test]
+fn default_values() {
+    use parsql::postgres::{macros::*, traits::*};
+    use postgres::{types::ToSql, Error, Row};
+
+    // token yalnızca sütun adını verir, değeri bağlanmaz
+    #[allow(dead_code)]
+    #[derive(Insertable, SqlParams)]
+    #[table("events")]
+    #[returning("id")]
+    struct InsertEvent {
+        name: String,
+        #[default_sql("md5(random()::text)")]
+        token: String,
+        #[default_sql("'guest'")]
+        role: Option<String>,
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug)]
+    #[table("events")]
+    #[where_clause("id = $")]
+    struct EventById {
+        id: i64,
+        token: String,
+        role: String,
+    }
+
+    // Option olmayan alanın yerine ifade yazılır ve bağlanmaz; Option alan COALESCE ile bağlanır
+    assert_eq!(InsertEvent::query(), "INSERT INTO events (name, token, role ) VALUES ($1, md5(random()::text), COALESCE($2, 'guest') ) RETURNING id");
+    assert_eq!(InsertEvent::param_count(), 2);
+    let event = |role: Option<&str>| InsertEvent { name: "login".into(), token: String::new(), role: role.map(Into::into) };
+    assert_eq!(event(None).params().len(), 2);
+
+    let Some((_db, mut client)) = connect() else { return };
+    client
+        .batch_execute("CREATE TABLE events (id BIGSERIAL PRIMARY KEY, name TEXT NOT NULL, token TEXT NOT NULL, role TEXT NOT NULL)")
+        .unwrap();
+    let guest: i64 = insert(&mut client, event(None)).unwrap();
+    let admin: i64 = insert(&mut client, event(Some("admin"))).unwrap();
+
+    let by_id = |id| EventById { id, token: String::new(), role: String::new() };
+    let guest = fetch(&mut client, &by_id(guest)).unwrap();
+    assert_eq!(guest.role, "guest");
+    assert_eq!(guest.token.len(), 32);
+    assert_eq!(fetch(&mut client, &by_id(admin)).unwrap().role, "admin");
+}
+
+#[test]
+fn generic_structs() {
+    use parsql::postgres::{macros::*, traits::*};
+    use postgres::types::ToSql;
+
+    #[allow(dead_code)]
+    #[derive(Queryable)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    struct UserByKey<K: ToSql + Sync> {
+        id: K,
+        name: String,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deletable)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    struct DeleteByKey<K>
+    where
+        K: ToSql + Sync,
+    {
+        id: K,
+    }
+
+    // SQL sabiti ve SqlQuery, tipin jenerik parametreleri ve where koşuluyla üretilir
+    assert_eq!(UserByKey::<i64>::SQL, "SELECT id, name FROM users WHERE id = $1");
+    assert_eq!(<UserByKey<i32> as SqlQuery>::query(), UserByKey::<i64>::SQL);
+    assert_eq!(DeleteByKey::<i64>::SQL, "DELETE FROM users WHERE id = $1");
+    assert_eq!(<DeleteByKey<String> as SqlQuery>::param_count(), 1);
+}
+
+#[test]
+fn mixed_case_tables() {
+    use parsql::postgres::{macros::*, traits::*};
+    use postgres::{types::ToSql, Error, Row};
+
+    #[derive(Insertable, SqlParams)]
+    #[table("UserAccounts")]
+    #[returning("accountId")]
+    #[rename_all("camelCase")]
+    struct InsertAccount {
+        display_name: String,
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug)]
+    #[table("UserAccounts")]
+    #[rename_all("camelCase")]
+    #[where_clause("\"accountId\" = $")]
+    struct AccountById {
+        account_id: i64,
+        display_name: String,
+    }
+
+    // Büyük harfli tablo ve sütun adları tırnaklanır, PostgreSQL küçük harfe çevirmez
+    assert!(InsertAccount::query().starts_with("INSERT INTO \"UserAccounts\""));
+    assert!(AccountById::query().contains("FROM \"UserAccounts\""));
+
+    let Some((_db, mut client)) = connect() else { return };
+    client
+        .batch_execute("CREATE TABLE \"UserAccounts\" (\"accountId\" BIGSERIAL PRIMARY KEY, \"displayName\" TEXT NOT NULL)")
+        .unwrap();
+    let id: i64 = insert(&mut client, InsertAccount { display_name: "ali".into() }).unwrap();
+
+    let account = fetch(&mut client, &AccountById { account_id: id, display_name: String::new() }).unwrap();
+    assert_eq!((account.account_id, account.display_name.as_str()), (id, "ali"));
+}
+
+#[test]
+fn materialized_views() {
+    let Some((_db, mut client)) = connect() else { return };
+    client.batch_execute(common::USER_COUNTS_SQL).unwrap();
+    seed(&mut client);
+    assert!(fetch_all(&mut client, &UserCount::new(1)).unwrap().is_empty());
+
+    refresh_materialized_view::<UserCount, _>(&mut client, false).unwrap();
+    assert_eq!(fetch(&mut client, &UserCount::new(1)).unwrap().total, 4);
+
+    let user = InsertUser { name: "deniz".into(), email: "deniz@example.com".into(), state: 1 };
+    let _: i64 = insert(&mut client, user).unwrap();
+    refresh_materialized_view::<UserCount, _>(&mut client, true).unwrap();
+    assert_eq!(fetch(&mut client, &UserCount::new(1)).unwrap().total, 5);
+}
+
+#[test]
+fn integrity_control() {
+    let Some((_db, mut client)) = connect() else { return };
+    client.batch_execute(common::INTEGRITY_SQL).unwrap();
+
+    let mut tx = client.transaction().unwrap();
+    tx.set_constraints_deferred(&["fk_posts_user"]).unwrap();
+    tx.disable_triggers("posts").unwrap();
+    tx.batch_execute(common::ORPHAN_POST_SQL).unwrap();
+    tx.batch_execute(common::AUTHOR_SQL).unwrap();
+    tx.set_constraints_immediate(&[]).unwrap();
+    tx.enable_triggers("posts").unwrap();
+    tx.commit().unwrap();
+
+    // Tetikleyici devre dışıyken yazılan gönderi denetlenmez, sonrakiler denetlenir
+    client.batch_execute("INSERT INTO posts VALUES (2, 100)").unwrap();
+    let audited: Vec<i64> = client.query("SELECT post_id FROM post_audit", &[]).unwrap().iter().map(|row| row.get(0)).collect();
+    assert_eq!(audited, [2]);
+
+    let mut tx = client.transaction().unwrap();
+    tx.set_constraints_deferred(&[]).unwrap();
+    tx.batch_execute("INSERT INTO posts VALUES (3, 999)").unwrap();
+    assert!(tx.set_constraints_immediate(&["fk_posts_user"]).is_err());
+}
+
+#[test]
+fn audited_writes() {
+    let Some((_db, mut client)) = connect() else { return };
+    client.batch_execute(audit_log::CREATE_TABLE).unwrap();
+
+    let user = AuditedAddUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let id: i64 = insert(&mut client, user).unwrap();
+    let renamed = session::with_rls_user(&mut client, 42, |tx| tx.update(AuditedRenameUser { id, name: "veli".into() })).unwrap();
+    assert_eq!(renamed, 1);
+    assert_eq!(delete(&mut client, AuditedDeleteUser { id }).unwrap(), 1);
+    assert_eq!(delete(&mut client, AuditedDeleteUser { id }).unwrap(), 0);
+
+    let rows = client.query(common::AUDIT_ROWS_SQL, &[]).unwrap();
+    let rows: Vec<_> = rows.iter().map(|row| (row.get(0), row.get(1), row.get(2), row.get(3))).collect();
+    common::assert_audit_rows(&rows, id);
+}
+
+#[test]
+fn dynamic_queries() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let rows = query_dynamic(&mut client, common::DYNAMIC_SQL, &[&1i16]).unwrap();
+    common::assert_dynamic_rows(&rows, &ids);
+}
+
+#[test]
+fn combined_filters() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let filters = (&UsersByState::new(1), &NameLike { name_pattern: "%e%".into() });
+    let users: Vec<UserRow> = fetch_all_combined(&mut client, filters).unwrap();
+    assert_eq!(users.iter().map(|user| user.id).collect::<Vec<_>>(), ids[1..4]);
+
+    // Her filtrenin yer tutucuları kendinden öncekilerin parametrelerinden sonra numaralanır
+    let filters = (&UsersByState::new(1), &IdBetween { from: ids[0], to: ids[2] }, &NameLike { name_pattern: "%e%".into() });
+    assert_eq!(
+        combined_query::<UserRow>(&filters.conditions(), Dialect::Postgres).unwrap(),
+        "SELECT id, name, state FROM users WHERE (state = $1) AND (id BETWEEN $2 AND $3) AND (name LIKE $4) ORDER BY id"
+    );
+    let users: Vec<UserRow> = fetch_all_combined(&mut client, filters).unwrap();
+    assert_eq!(users.iter().map(|user| user.id).collect::<Vec<_>>(), ids[1..3]);
+
+    // Kendi parametresini bağlayan sorgu panik yerine hata döndürür
+    let error = fetch_all_combined::<_, UsersByState, _>(&mut client, (&NameLike { name_pattern: "%".into() },)).unwrap_err();
+    let source = error.source().and_then(|e| e.downcast_ref::<CombineError>());
+    assert!(matches!(source, Some(CombineError::BoundParams(name)) if name.ends_with("UsersByState")));
+}
+
+#[test]
+fn repository() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let mut users = Repository::<Users, _>::new(client);
+    let id: i64 = users.create(InsertUser { name: "veli".into(), email: "veli@example.com".into(), state: 0 }).unwrap();
+    assert_eq!(users.find(ids[0]).unwrap().name, "ali");
+    let passive = users.find_all(UsersByState::new(0)).unwrap();
+    assert_eq!(passive.iter().map(|user| user.id).collect::<Vec<_>>(), [ids[4], ids[5], id]);
+
+    let update = UpdateUser { id, name: "veli".into(), email: "veli@example.org".into() };
+    assert_eq!(users.update(update).unwrap(), 1);
+    assert_eq!(users.find(id).unwrap().email, "veli@example.org");
+    assert_eq!(users.delete(id).unwrap(), 1);
+    assert_eq!(users.delete(id).unwrap(), 0);
+    assert!(users.find(id).is_err());
+}
+
+#[test]
+fn cursor_pages() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let token = cursor::open_cursor(&mut client, &UsersByState::new(1)).unwrap();
+    let token: cursor::PageToken = token.to_string().parse().unwrap();
+    let first: Vec<UsersByState> = cursor::fetch_next(&mut client, &token, 3).unwrap();
+    client.batch_execute(common::CHANGE_ACTIVE_USERS_SQL).unwrap();
+    let rest: Vec<UsersByState> = cursor::fetch_next(&mut client, &token, 3).unwrap();
+
+    // The pages show the rows as they were when the cursor was opened
+    let read = first.iter().chain(&rest).map(|user| user.id).collect::<Vec<_>>();
+    assert_eq!(read, ids[..4]);
+    assert!(cursor::fetch_next::<_, UsersByState>(&mut client, &token, 3).is_err());
+}
+
+#[test]
+fn monthly_partitions() {
+    let Some((_db, mut client)) = connect() else { return };
+    client.batch_execute(common::EVENTS_SQL).unwrap();
+    let event = || InsertEvent { kind: "login".into(), created_at: common::event_time() };
+    assert!(insert::<_, i64>(&mut client, event()).is_err());
+
+    let month = partitions::Month::containing(common::event_time());
+    ensure_monthly_partition::<InsertEvent, _>(&mut client, month).unwrap();
+    ensure_monthly_partition::<InsertEvent, _>(&mut client, month).unwrap();
+    let _: i64 = insert(&mut client, event()).unwrap();
+    let count: i64 = client.query_one(common::MARCH_EVENTS_SQL, &[]).unwrap().get(0);
+    assert_eq!((InsertEvent::partition_column(), count), ("created_at", 1));
+}
+
+#[test]
+fn case_insensitive_lookup() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    assert_eq!(fetch(&mut client, &UserByEmail::new("Ayse@Example.COM")).unwrap().id, ids[1]);
+    let passive = DeleteUserByEmail { email: "CAN@example.com".into(), state: 0 };
+    assert_eq!(delete(&mut client, passive).unwrap(), 1);
+}
+
+#[test]
+fn computed_columns() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let page = fetch_all(&mut client, &UsersWithTotal::new(1)).unwrap();
+    assert_eq!(page.iter().map(|user| user.id).collect::<Vec<_>>(), ids[..2]);
+    assert!(page.iter().all(|user| user.total_rows == 4));
+}
+
+#[test]
+fn raw_rows() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let page = fetch_all_with_rows(&mut client, &UserPage::new(1)).unwrap();
+    assert_eq!(page.iter().map(|(user, _)| user.id).collect::<Vec<_>>(), ids[..2]);
+    assert!(page.iter().all(|(_, row)| row.get::<_, i64>("total_count") == 4));
+
+    let (user, row) = fetch_with_row(&mut client, &UserById::new(ids[5])).unwrap();
+    assert_eq!((user.name.as_str(), row.get::<_, &str>("email")), ("elif", "elif@example.com"));
+}
+
+#[test]
+fn full_text_search() {
+    let Some((_db, mut client)) = connect() else { return };
+    client.batch_execute(common::SEARCH_SQL).unwrap();
+    seed(&mut client);
+
+    let found = fetch_all(&mut client, &SearchUsers::new(1, "zeynep")).unwrap();
+    assert_eq!(found.iter().map(|user| user.name.as_str()).collect::<Vec<_>>(), ["zeynep"]);
+    assert!(fetch_all(&mut client, &SearchUsers::new(0, "zeynep")).unwrap().is_empty());
+    assert_eq!(fetch(&mut client, &SearchUsers::new(0, "elif")).unwrap().state, 0);
+}
+
+#[test]
+fn stored_routines() {
+    let Some((_db, mut client)) = connect() else { return };
+    client.batch_execute(common::ROUTINES_SQL).unwrap();
+    seed(&mut client);
+
+    client.call(MoveUsers { from_state: 0, to_state: 1 }).unwrap();
+    assert_eq!(call(&mut client, CountUsers { state: 1 }).unwrap(), 1);
+    let active = client.select(&CountUsers { state: 1 }, |row| row.try_get::<_, i64>(0)).unwrap();
+    assert_eq!(active, 6);
+}
+
+#[test]
+fn partial_update() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+    let before = fetch(&mut client, &UserById::new(ids[0])).unwrap();
+
+    let patch = UserPatch { email: Some("ali@example.org".to_string()), state: Some(0), ..Default::default() };
+    assert_eq!(update_patch(&mut client, ids[0], patch).unwrap(), 1);
+    let after = fetch(&mut client, &UserById::new(ids[0])).unwrap();
+    assert_eq!((after.name, after.email.as_str(), after.state), (before.name, "ali@example.org", 0));
+
+    assert_eq!(update_patch(&mut client, ids[0], UserPatch::default()).unwrap(), 0);
+}
+
+#[test]
+fn criteria_statements() {
+    let Some((_db, mut client)) = connect() else { return };
+    seed(&mut client);
+
+    // LIMIT / OFFSET parameters of the page aren't bound to the WHERE clause
+    let passive = UsersPage::new(0, 1, 0);
+    let changes = UserPatch { state: Some(2), ..Default::default() };
+    assert_eq!(update_where(&mut client, &passive, &changes).unwrap(), 2);
+    assert_eq!(update_where(&mut client, &passive, &UserPatch::default()).unwrap(), 0);
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(2)).unwrap().len(), 2);
+
+    assert_eq!(delete_where(&mut client, &UsersByState::new(2)).unwrap(), 2);
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(1)).unwrap().len(), 4);
+}
+
+#[test]
+fn object_graph() {
+    let Some((_db, mut client)) = connect() else { return };
+    client.batch_execute(common::INTEGRITY_SQL).unwrap();
+
+    let id = transactional::persist_graph(&mut client, InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 }, vec![InsertPost::new(1), InsertPost::new(2)]).unwrap();
+    let posts: Vec<(i64, i64)> = client.query(common::POSTS_SQL, &[]).unwrap().iter().map(|row| (row.get(0), row.get(1))).collect();
+    assert_eq!(posts, [(1, id), (2, id)]);
+
+    // İkinci yazı eklenemediği için yazar ve ilk yazı da geri alınır
+    let failed = transactional::persist_graph(&mut client, InsertUser { name: "ayse".into(), email: "ayse@example.com".into(), state: 1 }, vec![InsertPost::new(3), InsertPost::new(1)]);
+    assert!(failed.is_err());
+    assert_eq!(client.query(common::POSTS_SQL, &[]).unwrap().len(), 2);
+    assert!(fetch_all(&mut client, &UsersByState::new(1)).unwrap().iter().all(|user| user.name == "ali"));
+}
+
+#[test]
+fn csv_export() {
+    let Some((_db, mut client)) = connect() else { return };
+    seed(&mut client);
+    insert::<_, i64>(&mut client, InsertUser { name: common::QUOTED_NAME.into(), email: "oneil@example.com".into(), state: 1 }).unwrap();
+
+    // Dizi, tırnak içeren metin ve NULL parametreler COPY sorgusuna literal olarak yazılır
+    let mut csv = Vec::new();
+    let params = ExportUsers { names: vec!["ali".into(), common::QUOTED_NAME.into()], email: None };
+    let written = export_csv(&mut client, &params, &mut csv).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), common::EXPORT_CSV);
+    assert_eq!(written, common::EXPORT_CSV.len() as u64);
+
+    let mut csv = Vec::new();
+    let params = ExportUsers { names: vec!["ali".into(), common::QUOTED_NAME.into()], email: Some("ali@example.com".into()) };
+    export_csv(&mut client, &params, &mut csv).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap().lines().collect::<Vec<_>>(), ["id,name,email", "7,\"o'neil, \"\"jr\"\"\",oneil@example.com"]);
+
+    // Transaction içinden de çalışır ve henüz commit edilmemiş satırları görür
+    let mut tx = client.transaction().unwrap();
+    tx.execute("DELETE FROM users WHERE name = 'ali'", &[]).unwrap();
+    let mut csv = Vec::new();
+    let params = ExportUsers { names: vec!["ali".into()], email: None };
+    export_csv(&mut tx, &params, &mut csv).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), "id,name,email\n");
+}
+
+#[test]
+fn union_members() {
+    let Some((_db, mut client)) = connect() else { return };
+    seed(&mut client);
+
+    // İkinci sorgunun LIMIT'i yalnızca kendi satırlarına uygulanır
+    let query = NamedOrNewest { name: "ali".into(), newest: NewestByState { state: 0 } };
+    let sql = <NamedOrNewest as parsql::postgres::traits::SqlQuery>::query();
+    assert!(sql.starts_with("SELECT * FROM (SELECT id, name FROM users WHERE name = $1) AS u1 UNION ALL SELECT * FROM (SELECT"));
+    let rows = client.query(sql, &parsql::postgres::traits::SqlParams::params(&query)).unwrap();
+    assert_eq!(rows.iter().map(|row| row.get::<_, String>("name")).collect::<Vec<_>>(), ["ali", "elif"]);
+}
+
+#[test]
+fn advisory_locks() {
+    let Some((db, mut client)) = connect() else { return };
+    let mut other = db.client().unwrap();
+    let key = locks::lock_key("parsql_it_pg_locks");
+
+    assert!(locks::try_advisory_lock(&mut client, key).unwrap());
+    assert!(!locks::try_advisory_lock(&mut other, key).unwrap());
+    assert!(locks::advisory_unlock(&mut client, key).unwrap());
+    assert!(!locks::advisory_unlock(&mut client, key).unwrap());
+
+    // Kapanış hata döndürse de kilit bırakılır
+    let result: Result<(), postgres::Error> = locks::with_advisory_lock(&mut client, key, |client| {
+        assert!(!locks::try_advisory_lock(&mut other, key).unwrap());
+        client.batch_execute("SELECT * FROM missing_table")
+    });
+    assert!(result.is_err());
+    assert!(locks::try_advisory_lock(&mut other, key).unwrap());
+    assert!(locks::advisory_unlock(&mut other, key).unwrap());
+}
+
+#[test]
+fn array_columns() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    assert_grouped(&fetch_all(&mut client, &UsersGroupedByState::new()).unwrap(), &ids);
+}
+
+#[test]
+fn prepared_statements() {
+    let Some((_db, mut client)) = connect() else { return };
+    assert_eq!(registry::prepare_all(&mut client).unwrap(), 6);
+}
+
+#[test]
+fn search_path() {
+    let Some((db, mut client)) = connect() else { return };
+    seed(&mut client);
+    let tenant = format!("{}_b", db.schema());
+    client.batch_execute(&common::tenant_sql(&tenant)).unwrap();
+
+    let users = session::with_search_path(&mut client, &tenant, |tx| {
+        tx.fetch_all(&UsersByState::new(1))
+    })
+    .unwrap();
+    assert!(users.is_empty());
+
+    // SET LOCAL transaction ile birlikte sona erer
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(1)).unwrap().len(), 4);
+}
+
+#[test]
+fn execution_options() {
+    let Some((_db, mut client)) = connect() else { return };
+    seed(&mut client);
+
+    let options = session::ExecOptions {
+        statement_timeout: Some(std::time::Duration::from_millis(100)),
+        application_name: Some("parsql-report".into()),
+        ..Default::default()
+    };
+    let name = session::with_options(&mut client, &options, |tx| {
+        assert_eq!(tx.fetch_all(&UsersByState::new(1))?.len(), 4);
+        tx.query_one("SHOW application_name", &[])?.try_get::<_, String>(0)
+    })
+    .unwrap();
+    assert_eq!(name, "parsql-report");
+
+    let timed_out = session::with_options(&mut client, &options, |tx| tx.batch_execute("SELECT pg_sleep(1)"));
+    assert_eq!(timed_out.unwrap_err().code(), Some(&postgres::error::SqlState::QUERY_CANCELED));
+
+    let name = client.query_one("SHOW application_name", &[]).unwrap().try_get::<_, String>(0).unwrap();
+    assert_ne!(name, "parsql-report");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_rows() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+
+    let user = parsql::postgres::fetch_json(&mut client, &UserById::new(ids[0])).unwrap();
+    assert_eq!((user["name"].as_str(), user["state"].as_i64()), (Some("ali"), Some(1)));
+
+    let active = parsql::postgres::fetch_all_json(&mut client, &UsersByState::new(1)).unwrap();
+    assert_eq!(active.len(), 4);
+    assert_eq!(active[0]["id"].as_i64(), Some(ids[0]));
+}
+
+#[test]
+fn constraint_violations() {
+    let Some((_db, mut client)) = connect() else { return };
+    seed(&mut client);
+    client
+        .batch_execute(
+            "CREATE UNIQUE INDEX users_email_key ON users (email);
+             ALTER TABLE users ADD CONSTRAINT users_state_check CHECK (state >= 0);",
+        )
+        .unwrap();
+
+    let duplicate = InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let error = insert::<_, i64>(&mut client, duplicate).unwrap_err();
+    assert!(error.is_unique_violation());
+    let violation = error.constraint().unwrap();
+    assert!(violation.is("users_email_key"));
+    assert_eq!(violation.table.as_deref(), Some("users"));
+
+    let negative = InsertUser { name: "veli".into(), email: "veli@example.com".into(), state: -1 };
+    let error = insert::<_, i64>(&mut client, negative).unwrap_err();
+    assert!(error.is_check_violation() && !error.is_unique_violation());
+    assert_eq!(error.constraint().unwrap().kind, ConstraintKind::Check);
+
+    let error = client.batch_execute("SELECT * FROM missing_table").unwrap_err();
+    assert!(error.constraint().is_none());
+}
+
+#[test]
+fn bulk_upsert() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+    client.batch_execute("CREATE UNIQUE INDEX users_email_key ON users (email)").unwrap();
+
+    let users = vec![
+        InsertUser { name: "ali veli".into(), email: "ali@example.com".into(), state: 0 },
+        InsertUser { name: "deniz".into(), email: "deniz@example.com".into(), state: 1 },
+    ];
+    let options = BatchOptions { transaction: true, ..Default::default() };
+    assert_eq!(upsert_many(&mut client, &users, &["email"], options).unwrap(), 2);
+
+    let user = fetch(&mut client, &UserById::new(ids[0])).unwrap();
+    assert_eq!((user.name.as_str(), user.state), ("ali veli", 0));
+    let total: i64 = client.query_one("SELECT count(*) FROM users", &[]).unwrap().get(0);
+    assert_eq!(total as usize, ids.len() + 1);
+}
+
+#[test]
+fn idempotent_insert() {
+    let Some((_db, mut client)) = connect() else { return };
+    let ids = seed(&mut client);
+    client.batch_execute("CREATE UNIQUE INDEX users_email_key ON users (email)").unwrap();
+
+    let existing = AddUserOnce { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    assert_eq!(insert_returning_opt::<_, _, i64>(&mut client, existing, "id").unwrap(), None);
+    let new = AddUserOnce { name: "deniz".into(), email: "deniz@example.com".into(), state: 1 };
+    let id = insert_returning_opt::<_, _, i64>(&mut client, new, "id").unwrap().unwrap();
+    assert!(id > ids[ids.len() - 1]);
+}
+
+#[test]
+fn fixture_loading() {
+    let Some((_db, mut client)) = connect() else { return };
+    client.batch_execute("CREATE UNIQUE INDEX users_email_key ON users (email)").unwrap();
+    let users = common::seed()
+        .into_iter()
+        .map(|(name, email, state)| AddUser { name, email, state })
+        .collect::<Vec<_>>();
+    assert_eq!(fixtures::load(&mut client, &users[..4]).unwrap(), 4);
+
+    // The second set repeats an email, so neither set is written
+    let failing = Fixtures::new().table(&users[4..]).table(&users[..1]).load(&mut client);
+    assert!(failing.is_err());
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(0)).unwrap().len(), 0);
+
+    assert_eq!(Fixtures::new().table(&users[4..]).load(&mut client).unwrap(), 2);
+    assert_eq!(fetch_all(&mut client, &UsersByState::new(0)).unwrap().len(), 2);
+}
@@ -0,0 +1,479 @@
+//! Tests of the `sqlite` backend against an in-memory database.
+
+#[allow(dead_code)]
+mod models {
+    use parsql::sqlite::{macros::*, traits::*, Error, Row, ToSql};
+
+    #[derive(Insertable, SqlParams)]
+    #[table("users")]
+    pub struct InsertUser {
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    impl InsertUser {
+        pub fn new(name: &str, state: i16) -> Self {
+            Self { name: name.into(), email: format!("{}@example.com", name), state }
+        }
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug, Clone, PartialEq)]
+    #[table("users")]
+    #[where_clause("state = $")]
+    #[order_by("id")]
+    pub struct UsersByState {
+        pub id: i64,
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    impl UsersByState {
+        pub fn new(state: i16) -> Self {
+            Self { id: 0, name: String::new(), email: String::new(), state }
+        }
+    }
+
+    #[derive(Updateable, UpdateParams)]
+    #[table("users")]
+    #[update("state")]
+    #[where_clause("name = $")]
+    pub struct UpdateState {
+        pub name: String,
+        pub state: i16,
+    }
+
+    #[derive(Deletable, SqlParams)]
+    #[table("users")]
+    #[where_clause("state = $")]
+    pub struct DeleteByState {
+        pub state: i16,
+    }
+
+    #[cfg(feature = "json")]
+    #[derive(Queryable, SqlParams, FromRow)]
+    #[table("users")]
+    #[select("users.state, users.name, json_group_array(json_object('id', posts.id)) FILTER (WHERE posts.id IS NOT NULL) AS posts")]
+    #[join("LEFT JOIN posts ON posts.user_id = users.id")]
+    #[where_clause("users.state = $")]
+    #[group_by("users.id")]
+    #[order_by("users.id")]
+    pub struct UsersWithPosts {
+        pub state: i16,
+        pub name: String,
+        pub posts: parsql::sqlite::Json<Vec<std::collections::BTreeMap<String, i64>>>,
+    }
+
+    /// Newest user of a state; its own ORDER BY and LIMIT stay inside the union.
+    #[derive(Queryable, SqlParams)]
+    #[table("users")]
+    #[select("id, name")]
+    #[where_clause("state = $")]
+    #[order_by("id DESC")]
+    #[limit(1)]
+    pub struct NewestByState {
+        pub state: i16,
+    }
+
+    #[derive(Queryable, SqlParams)]
+    #[table("users")]
+    #[select("id, name")]
+    #[where_clause("name = $")]
+    #[union_all(NewestByState)]
+    #[order_by("id")]
+    pub struct NamedOrNewest {
+        pub name: String,
+        pub newest: NewestByState,
+    }
+
+    #[derive(Insertable, SqlParams)]
+    #[table("posts")]
+    #[parent_key("user_id")]
+    pub struct InsertPost {
+        pub id: i64,
+        pub user_id: i64,
+    }
+
+    impl InsertPost {
+        pub fn new(id: i64) -> Self {
+            Self { id, user_id: 0 }
+        }
+    }
+
+    /// `InsertUser` returning the new id, for `Repository::create`.
+    #[derive(Insertable, SqlParams)]
+    #[table("users")]
+    #[returning("id")]
+    pub struct CreateUser {
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug, Clone, PartialEq)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    pub struct UserById {
+        pub id: i64,
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    impl From<i64> for UserById {
+        fn from(id: i64) -> Self {
+            Self { id, name: String::new(), email: String::new(), state: 0 }
+        }
+    }
+
+    #[derive(Updateable, UpdateParams)]
+    #[table("users")]
+    #[update("name, email")]
+    #[where_clause("id = $")]
+    pub struct UpdateUser {
+        pub id: i64,
+        pub name: String,
+        pub email: String,
+    }
+
+    #[derive(Deletable, SqlParams)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    pub struct DeleteUser {
+        pub id: i64,
+    }
+
+    impl From<i64> for DeleteUser {
+        fn from(id: i64) -> Self {
+            Self { id }
+        }
+    }
+
+    #[derive(Repository)]
+    #[repository(key = i64, insert = CreateUser, find = UserById, find_all = UsersByState, update = UpdateUser, delete = DeleteUser)]
+    pub struct Users;
+
+    #[derive(Queryable, SqlParams)]
+    #[table("users")]
+    #[select("id, name")]
+    #[where_clause("state = $")]
+    #[order_by("id")]
+    pub struct UserNames {
+        pub state: i16,
+    }
+
+    /// Raises a user's state by `state` in the database and sets the email.
+    #[derive(Updateable, UpdateParams)]
+    #[table("users")]
+    #[update("email")]
+    #[update_expr("state = state + $")]
+    #[where_clause("name = $")]
+    pub struct PromoteUser {
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    /// Reads `state` but not `email`, which keeps its default.
+    #[derive(Queryable, SqlParams, FromRow, Debug, PartialEq)]
+    #[table("users")]
+    #[select("id, name, state")]
+    #[where_clause("state = $")]
+    #[order_by("id")]
+    pub struct UserSummary {
+        pub id: i64,
+        pub name: String,
+        #[from_row(default)]
+        pub email: String,
+        #[from_row(default)]
+        pub state: i16,
+    }
+
+    /// Columns read by position.
+    #[derive(FromRow, Debug, PartialEq)]
+    pub struct UserId(pub i64);
+
+    #[derive(FromRow, Debug, PartialEq)]
+    pub struct IdAndName(pub i64, pub String);
+
+    /// Copies the users of a state into `archive` without reading them.
+    #[derive(Insertable, SqlParams)]
+    #[table("archive")]
+    #[insert_from(UserNames)]
+    #[columns("id, name")]
+    pub struct ArchiveUsers {
+        pub source: UserNames,
+    }
+}
+
+use models::*;
+use parsql::sqlite::{
+    cache::{Cached, MemoryCache}, fetch_all, open_with, repository::Repository, retry_on_busy, select_all, traits::{CrudOps, FromRow}, transactional, Connection,
+    SqliteOptions,
+};
+use std::time::Duration;
+
+const SCHEMA: &str = "CREATE TABLE users (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    email TEXT NOT NULL UNIQUE,
+    state INTEGER NOT NULL
+);
+CREATE TABLE posts (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users (id)
+);
+CREATE TABLE archive (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL
+);";
+
+fn connect() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(SCHEMA).unwrap();
+    conn
+}
+
+/// Path of a database file for the tests that need more than one connection.
+fn temp_db(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("parsql-{}-{}.db", name, std::process::id()))
+}
+
+/// Removes the database file and its WAL files.
+fn remove_db(path: &std::path::Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+}
+
+fn posts(conn: &Connection) -> Vec<(i64, i64)> {
+    let mut statement = conn.prepare("SELECT id, user_id FROM posts ORDER BY id").unwrap();
+    statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap().map(Result::unwrap).collect()
+}
+
+#[test]
+fn affected_rows() {
+    let conn = connect();
+    for (name, state) in [("ali", 1), ("ayse", 1), ("can", 0)] {
+        conn.insert_execute(InsertUser::new(name, state)).unwrap();
+    }
+
+    // update ve delete, PostgreSQL arka uçlarında olduğu gibi u64 döndürür
+    let updated: u64 = conn.update(UpdateState { name: "can".into(), state: 1 }).unwrap();
+    assert_eq!(updated, 1);
+    assert_eq!(conn.update(UpdateState { name: "veli".into(), state: 1 }).unwrap(), 0);
+
+    let deleted: u64 = conn.delete(DeleteByState { state: 1 }).unwrap();
+    assert_eq!(deleted, 3);
+    assert_eq!(conn.delete(DeleteByState { state: 1 }).unwrap(), 0);
+}
+
+#[test]
+fn transaction_state() {
+    let conn = connect();
+    assert!(!transactional::in_transaction(&conn));
+
+    let tx = transactional::begin(&conn).unwrap();
+    assert!(transactional::in_transaction(&tx));
+    tx.insert_execute(InsertUser::new("ali", 1)).unwrap();
+    // SQLite'ta hata veren bir ifade işlemi kapatmaz
+    assert!(tx.execute("INSERT INTO missing_table VALUES (1)", []).is_err());
+    assert!(transactional::in_transaction(&tx));
+    transactional::rollback(tx).unwrap();
+
+    assert!(!transactional::in_transaction(&conn));
+    assert!(conn.fetch_all(&UsersByState::new(1)).unwrap().is_empty());
+}
+
+#[test]
+fn cached_reads() {
+    let db = Cached::new(connect(), MemoryCache::new());
+    db.insert_execute(InsertUser::new("ali", 1)).unwrap();
+
+    let names = |users: Vec<UsersByState>| users.into_iter().map(|user| user.name).collect::<Vec<_>>();
+    assert_eq!(names(db.fetch_all(&UsersByState::new(1)).unwrap()), ["ali"]);
+    assert_eq!(db.cache().len(), 1);
+
+    // Önbelleği atlayan yazma görülmez, sonuç önbellekten döner
+    db.execute("INSERT INTO users (name, email, state) VALUES ('ayse', 'ayse@example.com', 1)", []).unwrap();
+    assert_eq!(names(db.fetch_all(&UsersByState::new(1)).unwrap()), ["ali"]);
+    // Farklı parametreler ayrı bir kayıttır
+    assert_eq!(names(db.fetch_all(&UsersByState::new(0)).unwrap()), Vec::<String>::new());
+    assert_eq!(db.cache().len(), 2);
+
+    // Sarmalayıcı üzerinden yazma, tabloyu okuyan kayıtları siler
+    assert_eq!(db.update(UpdateState { name: "veli".into(), state: 0 }).unwrap(), 0);
+    assert!(db.cache().is_empty());
+    assert_eq!(names(db.fetch_all(&UsersByState::new(1)).unwrap()), ["ali", "ayse"]);
+}
+
+#[test]
+fn repository() {
+    let users = Repository::<Users, _>::new(connect());
+    let id: i64 = users.create(CreateUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 }).unwrap();
+    assert_eq!(users.find(id).unwrap().name, "ali");
+    assert_eq!(users.find_all(UsersByState::new(1)).unwrap().len(), 1);
+
+    let update = UpdateUser { id, name: "veli".into(), email: "veli@example.com".into() };
+    assert_eq!(users.update(update).unwrap(), 1);
+    assert_eq!(users.find(id).unwrap().name, "veli");
+    assert_eq!(users.delete(id).unwrap(), 1);
+    assert_eq!(users.delete(id).unwrap(), 0);
+    assert!(users.find(id).is_err());
+}
+
+#[test]
+fn missing_columns() {
+    let conn = connect();
+    conn.insert_execute(InsertUser::new("ali", 1)).unwrap();
+
+    // Seçilmeyen sütun varsayılan değeri alır, seçilen sütun okunur
+    let users = fetch_all(&conn, &UserSummary { id: 0, name: String::new(), email: String::new(), state: 1 }).unwrap();
+    assert_eq!(users, [UserSummary { id: 1, name: "ali".into(), email: String::new(), state: 1 }]);
+}
+
+#[test]
+fn positional_rows() {
+    let conn = connect();
+    for (name, state) in [("ali", 1), ("ayse", 0), ("can", 1)] {
+        conn.insert_execute(InsertUser::new(name, state)).unwrap();
+    }
+
+    let query = UserNames { state: 1 };
+    assert_eq!(select_all(&conn, &query, UserId::from_row).unwrap(), [UserId(1), UserId(3)]);
+    let rows = select_all(&conn, &query, IdAndName::from_row).unwrap();
+    assert_eq!(rows, [IdAndName(1, "ali".into()), IdAndName(3, "can".into())]);
+}
+
+#[test]
+fn update_expressions() {
+    let conn = connect();
+    conn.insert_execute(InsertUser::new("ali", 1)).unwrap();
+    conn.insert_execute(InsertUser::new("ayse", 1)).unwrap();
+
+    // İfadenin yer tutucusu `update` sütunlarından, WHERE'inkiler ondan sonra numaralanır
+    for email in ["ali@example.org", "ali@example.net"] {
+        let promote = PromoteUser { name: "ali".into(), email: email.into(), state: 2 };
+        assert_eq!(conn.update(promote).unwrap(), 1);
+    }
+    let users = fetch_all(&conn, &UsersByState::new(5)).unwrap();
+    assert_eq!(users.iter().map(|user| (user.name.as_str(), user.email.as_str())).collect::<Vec<_>>(), [("ali", "ali@example.net")]);
+    assert_eq!(fetch_all(&conn, &UsersByState::new(1)).unwrap().len(), 1);
+}
+
+#[test]
+fn insert_from_select() {
+    let conn = connect();
+    for (name, state) in [("ali", 1), ("ayse", 0), ("can", 1)] {
+        conn.insert_execute(InsertUser::new(name, state)).unwrap();
+    }
+
+    // Satırlar uygulamaya okunmadan kaynak sorgunun parametresiyle kopyalanır
+    let copied = conn.insert_execute(ArchiveUsers { source: UserNames { state: 1 } }).unwrap();
+    assert_eq!(copied, 2);
+    let mut statement = conn.prepare("SELECT id, name FROM archive ORDER BY id").unwrap();
+    let archived: Vec<(i64, String)> = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap().map(Result::unwrap).collect();
+    assert_eq!(archived, [(1, "ali".to_string()), (3, "can".to_string())]);
+}
+
+#[test]
+fn busy_retries() {
+    let path = temp_db("busy");
+    let first = open_with(&path, &SqliteOptions::new()).unwrap();
+    first.execute_batch(SCHEMA).unwrap();
+    let journal_mode: String = first.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+    let foreign_keys: bool = first.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+    assert_eq!((journal_mode.as_str(), foreign_keys), ("wal", true));
+
+    // Bekleme süresi olmayan ikinci bağlantı, kilit bırakılana kadar yeniden dener
+    let second = open_with(&path, &SqliteOptions::new().busy_timeout(Duration::ZERO)).unwrap();
+    first.execute_batch("BEGIN IMMEDIATE").unwrap();
+    let mut attempts = 0;
+    let inserted = retry_on_busy(3, Duration::from_millis(1), || {
+        attempts += 1;
+        let result = second.insert_execute(InsertUser::new("ali", 1));
+        if attempts == 1 {
+            assert!(result.is_err());
+            first.execute_batch("COMMIT").unwrap();
+        }
+        result
+    })
+    .unwrap();
+    assert_eq!((inserted, attempts), (1, 2));
+
+    // Meşgul olmayan hatalar yeniden denenmez
+    let mut attempts = 0;
+    let duplicate = retry_on_busy(3, Duration::from_millis(1), || {
+        attempts += 1;
+        second.insert_execute(InsertUser::new("ali", 1))
+    });
+    assert!(duplicate.is_err());
+    assert_eq!(attempts, 1);
+
+    drop((first, second));
+    remove_db(&path);
+}
+
+#[test]
+fn union_members() {
+    let conn = connect();
+    for (name, state) in [("ali", 1), ("ayse", 1), ("can", 0), ("elif", 0)] {
+        conn.insert_execute(InsertUser::new(name, state)).unwrap();
+    }
+
+    // İkinci sorgunun LIMIT'i yalnızca kendi satırlarına uygulanır
+    let query = NamedOrNewest { name: "ali".into(), newest: NewestByState { state: 0 } };
+    let rows = select_all(&conn, &query, |row| row.get::<_, String>("name")).unwrap();
+    assert_eq!(rows, ["ali", "elif"]);
+}
+
+#[test]
+fn object_graph() {
+    let conn = connect();
+
+    let id: i64 = transactional::persist_graph(&conn, InsertUser::new("ali", 1), vec![InsertPost::new(1), InsertPost::new(2)]).unwrap();
+    assert_eq!(posts(&conn), [(1, id), (2, id)]);
+
+    // İkinci yazı eklenemediği için yazar ve ilk yazı da geri alınır
+    let failed = transactional::persist_graph(&conn, InsertUser::new("ayse", 1), vec![InsertPost::new(3), InsertPost::new(1)]);
+    assert!(failed.is_err());
+    assert_eq!(posts(&conn).len(), 2);
+    let users = fetch_all(&conn, &UsersByState::new(1)).unwrap();
+    assert_eq!(users.iter().map(|user| user.name.as_str()).collect::<Vec<_>>(), ["ali"]);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_children() {
+    let conn = connect();
+    transactional::persist_graph(&conn, InsertUser::new("ali", 1), vec![InsertPost::new(1), InsertPost::new(2)]).unwrap();
+    conn.insert_execute(InsertUser::new("ayse", 1)).unwrap();
+
+    // Yazısı olmayan kullanıcı için LEFT JOIN'in NULL satırı listeye girmez
+    let users = fetch_all(&conn, &UsersWithPosts { state: 1, name: String::new(), posts: Default::default() }).unwrap();
+    let posts: Vec<(&str, Vec<i64>)> = users.iter().map(|user| (user.name.as_str(), user.posts.iter().map(|post| post["id"]).collect())).collect();
+    assert_eq!(posts, [("ali", vec![1, 2]), ("ayse", vec![])]);
+}
+
+#[cfg(feature = "sqlite-pool")]
+#[test]
+fn pooled_connections() {
+    use parsql::sqlite::pool::{Pool, SqliteConnectionManager};
+
+    let path = temp_db("pool");
+    let pool = Pool::builder().max_size(2).build(SqliteConnectionManager::file(&path)).unwrap();
+    pool.get().unwrap().execute_batch(SCHEMA).unwrap();
+
+    // Bir bağlantıdan yazılan satır, havuzdan alınan diğer bağlantıdan okunur
+    let writer = pool.get().unwrap();
+    writer.insert_execute(InsertUser::new("ali", 1)).unwrap();
+    let reader = pool.get().unwrap();
+    let users = reader.fetch_all(&UsersByState::new(1)).unwrap();
+    assert_eq!(users.iter().map(|user| (user.name.as_str(), user.email.as_str())).collect::<Vec<_>>(), [("ali", "ali@example.com")]);
+    assert_eq!(reader.delete(DeleteByState { state: 1 }).unwrap(), 1);
+    assert!(writer.fetch_all(&UsersByState::new(1)).unwrap().is_empty());
+
+    drop((writer, reader, pool));
+    remove_db(&path);
+}
@@ -0,0 +1,810 @@
+//! Integration tests of the `tokio-postgres` backend.
+
+mod common;
+
+#[allow(dead_code)]
+mod models {
+    use parsql::tokio_postgres::{macros::*, registry, traits::*};
+    #[cfg(feature = "postgis")]
+    use parsql::tokio_postgres::{geo_types::Point, PostGis};
+    use tokio_postgres::{types::ToSql, Error, Row};
+
+    include!("common/models.rs");
+
+    /// A value whose `Debug` output hides it, like a redacted secret.
+    #[derive(Clone)]
+    pub struct Masked(pub String);
+
+    impl std::fmt::Debug for Masked {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("***")
+        }
+    }
+
+    impl ToSql for Masked {
+        fn to_sql(&self, ty: &tokio_postgres::types::Type, out: &mut bytes::BytesMut) -> Result<tokio_postgres::types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+            self.0.to_sql(ty, out)
+        }
+
+        fn accepts(ty: &tokio_postgres::types::Type) -> bool {
+            <String as ToSql>::accepts(ty)
+        }
+
+        tokio_postgres::types::to_sql_checked!();
+    }
+
+    impl<'a> tokio_postgres::types::FromSql<'a> for Masked {
+        fn from_sql(ty: &tokio_postgres::types::Type, raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+            String::from_sql(ty, raw).map(Masked)
+        }
+
+        fn accepts(ty: &tokio_postgres::types::Type) -> bool {
+            <String as tokio_postgres::types::FromSql>::accepts(ty)
+        }
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+    #[table("users")]
+    #[select("id, name")]
+    #[where_clause("name = $")]
+    pub struct UserByMaskedName {
+        pub id: i64,
+        pub name: Masked,
+    }
+}
+
+use models::*;
+use parsql::tokio_postgres::{
+    cache::{install_invalidation, listen, Cached, MemoryCache},
+    loader::ParsqlLoader,
+    audit_log,
+    cursor,
+    integrity::IntegrityControl,
+    batch_fetch, ensure_monthly_partition, export_csv, fetch_all_combined, fetch_all_limited, fetch_all_with_rows, fetch_first, fetch_with_row, insert_returning_id, fetch_last, fetch_many, fetch_page, partitions, pipeline::Pipeline, query_dynamic, refresh_materialized_view, registry, repository::Repository, session, singleflight::SingleFlight, two_phase::{self, NoRecovery, TwoPhaseError},
+    traits::{CrudOps, Partitioned, SqlParams, SqlQuery, ValidationError}, transactional, OnError, ParsqlError, ParsqlExecutor, Progress,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use parsql_core::criteria::CombineError;
+use parsql_testing::TestDatabase;
+use tokio_postgres::{Client, NoTls};
+
+/// Starts a test database holding an empty `users` table and connects to it.
+async fn connect() -> Option<(TestDatabase, Client)> {
+    if !common::enabled() {
+        return None;
+    }
+    let db = TestDatabase::start(|client| Box::pin(client.batch_execute(common::USERS_SQL)))
+        .await
+        .expect("cannot start the test database");
+    let client = db.tokio_postgres().await.unwrap();
+    Some((db, client))
+}
+
+async fn seed(client: &Client) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for (name, email, state) in common::seed() {
+        ids.push(client.insert(InsertUser { name, email, state }).await.unwrap());
+    }
+    ids
+}
+
+/// The same setup on a container started by `parsql-testing`, for machines with Docker
+/// but without a test server: `cargo test -p parsql --features tokio-postgres -- --ignored`.
+#[tokio::test]
+#[ignore = "starts a PostgreSQL container, needs Docker"]
+async fn container() {
+    let db = TestDatabase::start(|client| Box::pin(client.batch_execute(common::USERS_SQL)))
+        .await
+        .expect("cannot start the PostgreSQL container");
+    let client = db.tokio_postgres().await.unwrap();
+    let ids = seed(&client).await;
+
+    assert_eq!(client.fetch(UserById::new(ids[0])).await.unwrap().name, "ali");
+    assert_eq!(client.fetch_all(UsersByState::new(1)).await.unwrap().len(), 4);
+}
+
+#[test]
+fn generated_sql() {
+    assert_sql_snapshots();
+}
+
+#[test]
+fn params_iter() {
+    let debug = |params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)>| params.iter().map(|p| format!("{:?}", p)).collect::<Vec<_>>();
+
+    // Yineleyici params() ile aynı değerleri, yer tutucu sırasıyla ve param_count() kadar verir
+    let user = InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    assert_eq!(user.params_iter().len(), InsertUser::param_count());
+    assert_eq!(debug(user.params_iter().collect()), ["\"ali\"", "\"ali@example.com\"", "1"]);
+    assert_eq!(debug(user.params_iter().collect()), debug(user.params()));
+
+    let page = UsersPage::new(1, 20, 40);
+    assert_eq!(page.params_iter().len(), UsersPage::param_count());
+    assert_eq!(debug(page.params_iter().collect()), ["1", "20", "40"]);
+    assert_eq!(debug(page.params_iter().collect()), debug(page.params()));
+}
+
+#[tokio::test]
+async fn crud() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let user = client.fetch(UserById::new(ids[0])).await.unwrap();
+    assert_eq!((user.name.as_str(), user.state), ("ali", 1));
+    let with_contact = client.fetch(UserWithContact::new(ids[0])).await.unwrap();
+    assert_eq!(with_contact.contact, UserContact { email: "ali@example.com".into(), state: 1 });
+
+    let active = client.fetch_all(UsersByState::new(1)).await.unwrap();
+    assert_eq!(active.len(), 4);
+
+    let rename = UpdateUser { id: ids[0], name: "veli".into(), email: "veli@example.com".into() };
+    assert_eq!(client.update(rename).await.unwrap(), 1);
+    let missing = UpdateUser { id: -1, name: String::new(), email: String::new() };
+    assert_eq!(client.update(missing).await.unwrap(), 0);
+    assert_eq!(client.fetch(UserById::new(ids[0])).await.unwrap().name, "veli");
+
+    assert_eq!(client.delete(DeleteUser { id: ids[0] }).await.unwrap(), 1);
+    assert!(client.fetch(UserById::new(ids[0])).await.is_err());
+
+    let user = AddUser { name: "deniz".into(), email: "deniz@example.com".into(), state: 1 };
+    let id: i64 = insert_returning_id(&client, user).await.unwrap();
+    assert_eq!(client.fetch(UserById::new(id)).await.unwrap().name, "deniz");
+}
+
+#[tokio::test]
+async fn transactions() {
+    let Some((_db, mut client)) = connect().await else { return };
+
+    let tx = transactional::begin(&mut client).await.unwrap();
+    let user = InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let committed: i64 = tx.insert(user).await.unwrap();
+    tx.commit().await.unwrap();
+
+    let tx = transactional::begin(&mut client).await.unwrap();
+    let user = InsertUser { name: "ayse".into(), email: "ayse@example.com".into(), state: 1 };
+    let rolled_back: i64 = tx.insert(user).await.unwrap();
+    assert_eq!(tx.fetch(UserById::new(rolled_back)).await.unwrap().name, "ayse");
+    assert!(!transactional::is_aborted(&tx).await.unwrap());
+    transactional::rollback(tx).await.unwrap();
+
+    assert!(client.fetch(UserById::new(committed)).await.is_ok());
+    assert!(client.fetch(UserById::new(rolled_back)).await.is_err());
+
+    // Hata veren bir ifadeden sonra işlem yalnızca geri alınabilir
+    let tx = transactional::begin(&mut client).await.unwrap();
+    assert!(tx.batch_execute("SELECT * FROM missing_table").await.is_err());
+    assert!(transactional::is_aborted(&tx).await.unwrap());
+    assert!(tx.fetch(UserById::new(committed)).await.is_err());
+    transactional::rollback(tx).await.unwrap();
+    assert!(client.fetch(UserById::new(committed)).await.is_ok());
+}
+
+#[tokio::test]
+async fn insert_all() {
+    let Some((_db, mut client)) = connect().await else { return };
+    client.batch_execute("CREATE UNIQUE INDEX ON users (email)").await.unwrap();
+    let batch = || {
+        vec![
+            InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 },
+            InsertUser { name: "veli".into(), email: "ali@example.com".into(), state: 1 },
+            InsertUser { name: "ayse".into(), email: "ayse.example.com".into(), state: 1 },
+            InsertUser { name: "fatma".into(), email: "fatma@example.com".into(), state: 1 },
+        ]
+    };
+
+    let mut progress = Vec::new();
+    let tx = transactional::begin(&mut client).await.unwrap();
+    let (tx, results) = transactional::tx_insert_all(tx, batch(), OnError::Continue, |p| progress.push(p))
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    assert_eq!(results.iter().map(Result::is_ok).collect::<Vec<_>>(), [true, false, false, true]);
+    assert_eq!(progress.last(), Some(&Progress { done: 4, total: 4, failed: 2 }));
+    assert_eq!(client.fetch_all(UsersByState::new(1)).await.unwrap().len(), 2);
+
+    let tx = transactional::begin(&mut client).await.unwrap();
+    let (tx, results) = transactional::tx_insert_all(tx, batch(), OnError::Stop, |_| {}).await.unwrap();
+    tx.rollback().await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
+
+#[tokio::test]
+async fn validation() {
+    let Some((_db, mut client)) = connect().await else { return };
+
+    let user = InsertUser { name: "ali".into(), email: "ali.example.com".into(), state: 1 };
+    let error = client.insert::<_, i64>(user).await.unwrap_err();
+    assert!(matches!(error, ParsqlError::Validation(ref e) if e.field == "email"));
+
+    // Trait nesnesi üzerinden de doğrulama sunucuya gitmeden reddeder
+    let executor: &dyn ParsqlExecutor = &client;
+    let user = InsertUser { name: "ali".into(), email: "ali.example.com".into(), state: 1 };
+    let error = executor.insert::<_, i64>(user).await.unwrap_err();
+    assert!(matches!(error, ParsqlError::Validation(ref e) if e.field == "email"));
+
+    let tx = transactional::begin(&mut client).await.unwrap();
+    let user = InsertUser { name: " ".into(), email: "ali@example.com".into(), state: 1 };
+    let error = tx.insert::<_, i64>(user).await.unwrap_err();
+    assert_eq!(ValidationError::find(&error).unwrap().field, "name");
+    let user = InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    tx.insert::<_, i64>(user).await.unwrap();
+    tx.commit().await.unwrap();
+
+    assert_eq!(client.fetch_all(UsersByState::new(1)).await.unwrap().len(), 1);
+}
+
+/// Runs every typed operation of `dyn ParsqlExecutor` on the seeded `users` table.
+async fn crud_through_executor(executor: &dyn ParsqlExecutor, name: &str) {
+    let user = InsertUser { name: name.into(), email: format!("{}@example.com", name), state: 1 };
+    let id: i64 = executor.insert(user).await.unwrap();
+    assert_eq!(executor.fetch(UserById::new(id)).await.unwrap().name, name);
+
+    let active = executor.fetch_all(UsersByState::new(1)).await.unwrap();
+    assert_eq!(active.len(), 5);
+    assert_eq!(active.last().map(|user| user.id), Some(id));
+
+    let email: String = executor.select(UserById::new(id), |row| row.try_get("email")).await.unwrap();
+    assert_eq!(email, format!("{}@example.com", name));
+    let names = executor.select_all(UsersByState::new(1), |row| row.get::<_, String>("name")).await.unwrap();
+    assert_eq!(names, ["ali", "ayse", "mehmet", "zeynep", name]);
+
+    let update = UpdateUser { id, name: name.into(), email: format!("{}@example.org", name) };
+    assert_eq!(executor.update(update).await.unwrap(), 1);
+    assert_eq!(executor.fetch(UserById::new(id)).await.unwrap().email, format!("{}@example.org", name));
+
+    assert_eq!(executor.delete(DeleteUser { id }).await.unwrap(), 1);
+    // Kayıt bulunamayınca sürücü hatası döner
+    assert!(executor.fetch(UserById::new(id)).await.unwrap_err().as_postgres().is_some());
+}
+
+#[tokio::test]
+async fn dyn_executor() {
+    let Some((_db, mut client)) = connect().await else { return };
+    seed(&client).await;
+
+    crud_through_executor(&client, "veli").await;
+
+    let tx = client.transaction().await.unwrap();
+    crud_through_executor(&tx, "deniz").await;
+    tx.commit().await.unwrap();
+}
+
+#[tokio::test]
+async fn single_row_reads() {
+    let Some((_db, client)) = connect().await else { return };
+    seed(&client).await;
+
+    // LIMIT 2 işi sınırlar ama iki satırla eşleşen okuma yine hata verir, rastgele bir satır dönmez
+    assert!(UsersByState::query_single().ends_with("LIMIT 2"));
+    let error = client.fetch(UsersByState::new(0)).await.unwrap_err();
+    assert!(error.as_postgres().is_some());
+    let names = client.select(UsersByState::new(0), |row| row.try_get::<_, String>("name")).await;
+    assert!(names.is_err());
+}
+
+#[tokio::test]
+async fn pipeline() {
+    let Some((_db, mut client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let mut tx = transactional::begin(&mut client).await.unwrap();
+    let affected = Pipeline::new()
+        .insert(InsertUser { name: "veli".into(), email: "veli@example.com".into(), state: 1 })
+        .update(UpdateUser { id: ids[0], name: "ali".into(), email: "ali@example.org".into() })
+        .delete(DeleteUser { id: ids[1] })
+        .delete(DeleteUser { id: -1 })
+        .execute(&mut tx)
+        .await
+        .unwrap();
+    assert_eq!(affected, [1, 1, 1, 0]);
+    tx.commit().await.unwrap();
+
+    let active = client.fetch_all(UsersByState::new(1)).await.unwrap();
+    assert_eq!(active.len(), 4);
+    assert_eq!(active[0].email, "ali@example.org");
+}
+
+#[tokio::test]
+async fn pagination() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let page = client.fetch_all(UsersPage::new(1, 2, 1)).await.unwrap();
+    assert_eq!(page.iter().map(|u| u.id).collect::<Vec<_>>(), ids[1..3]);
+
+    let page = fetch_all_limited(&client, &UsersByState::new(1), 3, 2).await.unwrap();
+    assert_eq!(page.iter().map(|u| u.id).collect::<Vec<_>>(), ids[2..4]);
+
+    let page = UsersByStatePage::nth(UsersByState::new(1), 2, 2);
+    assert_eq!(fetch_page(&client, &page).await.unwrap().len(), 2);
+    assert!(fetch_page(&client, &page.next()).await.unwrap().is_empty());
+
+    let first = fetch_first(&client, &UsersByState::new(1), "name").await.unwrap();
+    assert_eq!(first.map(|u| u.name).as_deref(), Some("ali"));
+    let last = fetch_last(&client, &UsersByState::new(0), "id").await.unwrap();
+    assert_eq!(last.map(|u| u.id), Some(ids[5]));
+    let error = fetch_last(&client, &UsersByState::new(1), "email").await.unwrap_err();
+    assert_eq!(ValidationError::find(&error).unwrap().field, "email");
+}
+
+#[tokio::test]
+async fn fetch_by_keys() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let unknown = ids[5] + 100;
+    let users = fetch_many::<UserById, i64>(&client, &[ids[2], unknown, ids[1]]).await.unwrap();
+    assert_eq!(users.found.iter().map(|user| user.id).collect::<Vec<_>>(), [ids[2], ids[1]]);
+    assert_eq!(users.missing, [unknown]);
+    assert!(!users.is_complete());
+}
+
+#[tokio::test]
+async fn raw_rows() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let page = fetch_all_with_rows(&client, &UserPage::new(1)).await.unwrap();
+    assert_eq!(page.iter().map(|(user, _)| user.id).collect::<Vec<_>>(), ids[..2]);
+    assert!(page.iter().all(|(_, row)| row.get::<_, i64>("total_count") == 4));
+
+    let (user, row) = fetch_with_row(&client, &UserById::new(ids[5])).await.unwrap();
+    assert_eq!((user.name.as_str(), row.get::<_, &str>("email")), ("elif", "elif@example.com"));
+}
+
+#[tokio::test]
+async fn entity_crud() {
+    let Some((_db, client)) = connect().await else { return };
+
+    let new = MemberInsert { name: "ali".to_string(), email: "ali@example.com".to_string(), state: 1 };
+    let id: i64 = client.insert(new).await.unwrap();
+    let mut member = fetch_many::<Member, i64>(&client, &[id]).await.unwrap().found.remove(0);
+    assert_eq!((member.name.as_str(), member.state), ("ali", 1));
+
+    member.email = "ali@example.org".to_string();
+    assert_eq!(client.update(MemberUpdate::from(member.clone())).await.unwrap(), 1);
+    assert_eq!(client.fetch(member.clone()).await.unwrap().email, "ali@example.org");
+
+    member.name.clear();
+    let error = client.update(MemberUpdate::from(member)).await.unwrap_err();
+    assert_eq!(ValidationError::find(&error).unwrap().field, "name");
+
+    assert_eq!(client.delete(MemberDelete::from(id)).await.unwrap(), 1);
+    assert!(fetch_many::<Member, i64>(&client, &[id]).await.unwrap().found.is_empty());
+}
+
+#[tokio::test]
+async fn batched_loads() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let users = ParsqlLoader::<UserById>::new(Arc::new(client));
+    let unknown = ids[5] + 100;
+    let (first, missing, again) = tokio::join!(users.load(ids[1]), users.load(unknown), users.load(ids[1]));
+    assert_eq!(first.unwrap().map(|user| user.id), Some(ids[1]));
+    assert!(missing.unwrap().is_none());
+    assert_eq!(again.unwrap().map(|user| user.name).as_deref(), Some("ayse"));
+
+    let loaded = users.load_many(&[ids[3], ids[0]]).await.unwrap();
+    assert_eq!(loaded.into_iter().map(|user| user.unwrap().id).collect::<Vec<_>>(), [ids[3], ids[0]]);
+}
+
+#[tokio::test]
+async fn materialized_views() {
+    let Some((_db, client)) = connect().await else { return };
+    client.batch_execute(common::USER_COUNTS_SQL).await.unwrap();
+    seed(&client).await;
+    assert!(client.fetch_all(UserCount::new(1)).await.unwrap().is_empty());
+
+    refresh_materialized_view::<UserCount, _>(&client, false).await.unwrap();
+    assert_eq!(client.fetch(UserCount::new(1)).await.unwrap().total, 4);
+
+    let user = InsertUser { name: "deniz".into(), email: "deniz@example.com".into(), state: 1 };
+    let _: i64 = client.insert(user).await.unwrap();
+    refresh_materialized_view::<UserCount, _>(&client, true).await.unwrap();
+    assert_eq!(client.fetch(UserCount::new(1)).await.unwrap().total, 5);
+}
+
+#[tokio::test]
+async fn integrity_control() {
+    let Some((_db, mut client)) = connect().await else { return };
+    client.batch_execute(common::INTEGRITY_SQL).await.unwrap();
+
+    let tx = client.transaction().await.unwrap();
+    tx.set_constraints_deferred(&["fk_posts_user"]).await.unwrap();
+    tx.disable_triggers("posts").await.unwrap();
+    tx.batch_execute(common::ORPHAN_POST_SQL).await.unwrap();
+    tx.batch_execute(common::AUTHOR_SQL).await.unwrap();
+    tx.set_constraints_immediate(&[]).await.unwrap();
+    tx.enable_triggers("posts").await.unwrap();
+    tx.commit().await.unwrap();
+
+    // Tetikleyici devre dışıyken yazılan gönderi denetlenmez, sonrakiler denetlenir
+    client.batch_execute("INSERT INTO posts VALUES (2, 100)").await.unwrap();
+    let audited: Vec<i64> = client.query("SELECT post_id FROM post_audit", &[]).await.unwrap().iter().map(|row| row.get(0)).collect();
+    assert_eq!(audited, [2]);
+
+    let tx = client.transaction().await.unwrap();
+    tx.set_constraints_deferred(&[]).await.unwrap();
+    tx.batch_execute("INSERT INTO posts VALUES (3, 999)").await.unwrap();
+    assert!(tx.set_constraints_immediate(&["fk_posts_user"]).await.is_err());
+}
+
+#[tokio::test]
+async fn audited_writes() {
+    let Some((_db, mut client)) = connect().await else { return };
+    client.batch_execute(audit_log::CREATE_TABLE).await.unwrap();
+
+    let user = AuditedAddUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let id: i64 = client.insert(user).await.unwrap();
+    let renamed = session::with_rls_user(&mut client, 42, |tx| {
+        Box::pin(async move { tx.update(AuditedRenameUser { id, name: "veli".into() }).await })
+    })
+    .await
+    .unwrap();
+    assert_eq!(renamed, 1);
+    assert_eq!(client.delete(AuditedDeleteUser { id }).await.unwrap(), 1);
+    assert_eq!(client.delete(AuditedDeleteUser { id }).await.unwrap(), 0);
+
+    let rows = client.query(common::AUDIT_ROWS_SQL, &[]).await.unwrap();
+    let rows: Vec<_> = rows.iter().map(|row| (row.get(0), row.get(1), row.get(2), row.get(3))).collect();
+    common::assert_audit_rows(&rows, id);
+}
+
+#[tokio::test]
+async fn dynamic_queries() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let rows = query_dynamic(&client, common::DYNAMIC_SQL, &[&1i16]).await.unwrap();
+    common::assert_dynamic_rows(&rows, &ids);
+}
+
+#[tokio::test]
+async fn combined_filters() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let filters = (&UsersByState::new(1), &NameLike { name_pattern: "%e%".into() });
+    let users: Vec<UserRow> = fetch_all_combined(&client, filters).await.unwrap();
+    assert_eq!(users.iter().map(|user| user.id).collect::<Vec<_>>(), ids[1..4]);
+
+    // Kendi parametresi olan sorgu daraltılamaz; sunucuya gitmeden reddedilir
+    let error = fetch_all_combined::<_, UsersByState, _>(&client, filters).await.unwrap_err();
+    assert!(matches!(error, ParsqlError::Combine(CombineError::BoundParams(_))));
+}
+
+#[tokio::test]
+async fn repository() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let users = Repository::<Users, _>::new(client);
+    let id: i64 = users.create(InsertUser { name: "veli".into(), email: "veli@example.com".into(), state: 0 }).await.unwrap();
+    assert_eq!(users.find(ids[0]).await.unwrap().name, "ali");
+    let passive = users.find_all(UsersByState::new(0)).await.unwrap();
+    assert_eq!(passive.iter().map(|user| user.id).collect::<Vec<_>>(), [ids[4], ids[5], id]);
+
+    let update = UpdateUser { id, name: "veli".into(), email: "veli@example.org".into() };
+    assert_eq!(users.update(update).await.unwrap(), 1);
+    assert_eq!(users.find(id).await.unwrap().email, "veli@example.org");
+    assert_eq!(users.delete(id).await.unwrap(), 1);
+    assert_eq!(users.delete(id).await.unwrap(), 0);
+    assert!(users.find(id).await.is_err());
+}
+
+#[tokio::test]
+async fn cursor_pages() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let token = cursor::open_cursor(&client, &UsersByState::new(1)).await.unwrap();
+    let token: cursor::PageToken = token.to_string().parse().unwrap();
+    let first: Vec<UsersByState> = cursor::fetch_next(&client, &token, 3).await.unwrap();
+    client.batch_execute(common::CHANGE_ACTIVE_USERS_SQL).await.unwrap();
+    let rest: Vec<UsersByState> = cursor::fetch_next(&client, &token, 3).await.unwrap();
+
+    // The pages show the rows as they were when the cursor was opened
+    let read = first.iter().chain(&rest).map(|user| user.id).collect::<Vec<_>>();
+    assert_eq!(read, ids[..4]);
+    assert!(cursor::fetch_next::<_, UsersByState>(&client, &token, 3).await.is_err());
+}
+
+#[tokio::test]
+async fn monthly_partitions() {
+    let Some((_db, client)) = connect().await else { return };
+    client.batch_execute(common::EVENTS_SQL).await.unwrap();
+    let event = || InsertEvent { kind: "login".into(), created_at: common::event_time() };
+    assert!(client.insert::<_, i64>(event()).await.is_err());
+
+    let month = partitions::Month::containing(common::event_time());
+    ensure_monthly_partition::<InsertEvent, _>(&client, month).await.unwrap();
+    ensure_monthly_partition::<InsertEvent, _>(&client, month).await.unwrap();
+    let _: i64 = client.insert(event()).await.unwrap();
+    let count: i64 = client.query_one(common::MARCH_EVENTS_SQL, &[]).await.unwrap().get(0);
+    assert_eq!((InsertEvent::partition_column(), count), ("created_at", 1));
+}
+
+#[tokio::test]
+async fn case_insensitive_lookup() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    assert_eq!(client.fetch(UserByEmail::new("Ayse@Example.COM")).await.unwrap().id, ids[1]);
+    let passive = DeleteUserByEmail { email: "CAN@example.com".into(), state: 0 };
+    assert_eq!(client.delete(passive).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn batched_queries() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let queries = (&UsersByState::new(1), &UsersByState::new(0), &UserByEmail::new("ELIF@example.com"));
+    let (active, passive, found) = batch_fetch(&client, queries).await.unwrap();
+    assert_eq!(active.iter().map(|user| user.id).collect::<Vec<_>>(), ids[..4]);
+    assert_eq!(passive.iter().map(|user| user.id).collect::<Vec<_>>(), ids[4..]);
+    assert_eq!(found.iter().map(|user| user.id).collect::<Vec<_>>(), [ids[5]]);
+}
+
+#[tokio::test]
+async fn stored_routines() {
+    let Some((_db, client)) = connect().await else { return };
+    client.batch_execute(common::ROUTINES_SQL).await.unwrap();
+    seed(&client).await;
+
+    client.call(MoveUsers { from_state: 1, to_state: 0 }).await.unwrap();
+    let passive = client.select(CountUsers { state: 0 }, |row| row.try_get::<_, i64>(0)).await.unwrap();
+    assert_eq!(passive, 6);
+}
+
+#[tokio::test]
+async fn array_columns() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    assert_grouped(&client.fetch_all(UsersGroupedByState::new()).await.unwrap(), &ids);
+}
+
+#[tokio::test]
+async fn prepared_statements() {
+    let Some((_db, client)) = connect().await else { return };
+    assert_eq!(registry::prepare_all(&client).await.unwrap(), 6);
+}
+
+#[tokio::test]
+async fn search_path() {
+    let Some((db, mut client)) = connect().await else { return };
+    seed(&client).await;
+    let tenant = format!("{}_b", db.schema());
+    client.batch_execute(&common::tenant_sql(&tenant)).await.unwrap();
+
+    let users = session::with_search_path(&mut client, &tenant, |tx| {
+        Box::pin(async move { tx.fetch_all(UsersByState::new(1)).await })
+    })
+    .await
+    .unwrap();
+    assert!(users.is_empty());
+
+    // SET LOCAL transaction ile birlikte sona erer
+    assert_eq!(client.fetch_all(UsersByState::new(1)).await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn rls_user() {
+    let Some((_db, mut client)) = connect().await else { return };
+    let current = "SELECT current_setting('app.current_user_id', true)";
+
+    let user: Option<String> = session::with_rls_user(&mut client, 42, |tx| {
+        Box::pin(async move { tx.query_one(current, &[]).await.map(|row| row.get(0)) })
+    })
+    .await
+    .unwrap();
+    assert_eq!(user.as_deref(), Some("42"));
+
+    let after: Option<String> = client.query_one(current, &[]).await.unwrap().get(0);
+    assert!(after.unwrap_or_default().is_empty());
+}
+
+#[tokio::test]
+async fn cache_invalidation() {
+    let Some((db, client)) = connect().await else { return };
+    seed(&client).await;
+    install_invalidation(&client, "users").await.unwrap();
+
+    let cache = Arc::new(MemoryCache::new());
+    let (listener, connection) = db.config().connect(NoTls).await.unwrap();
+    tokio::spawn(listen(listener, connection, cache.clone()));
+
+    let db = Cached::new(client, cache.clone());
+    assert_eq!(db.fetch_all(UsersByState::new(1)).await.unwrap().len(), 4);
+    assert_eq!(cache.len(), 1);
+
+    // Başka bir örneğin yazması gibi önbelleği atlar; LISTEN henüz tamamlanmamış olabileceği için tekrarlanır
+    for _ in 0..50 {
+        db.execute("UPDATE users SET state = state WHERE id < 0", &[]).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if cache.is_empty() {
+            return;
+        }
+    }
+    panic!("the cached result was not invalidated");
+}
+
+#[tokio::test]
+async fn cache_keys() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+    let db = Cached::new(client, MemoryCache::new());
+
+    // İki sorgunun debug_sql çıktısı aynıdır, önbellek anahtarı ise değerlerin kodlamasıdır
+    let ali = UserByMaskedName { id: 0, name: Masked("ali".into()) };
+    let ayse = UserByMaskedName { id: 0, name: Masked("ayse".into()) };
+    assert_eq!(ali.debug_sql(), ayse.debug_sql());
+    assert_eq!(db.fetch(ali).await.unwrap().name.0, "ali");
+    assert_eq!(db.fetch(ayse).await.unwrap().name.0, "ayse");
+    assert_eq!(db.cache().len(), 2);
+
+    // CrudOps üzerinden genel kod da yazarken önbelleği temizler
+    async fn rename(db: &impl CrudOps, id: i64) -> u64 {
+        db.update(UpdateUser { id, name: "veli".into(), email: "veli@example.com".into() }).await.unwrap()
+    }
+    assert_eq!(rename(&db, ids[0]).await, 1);
+    assert!(db.cache().is_empty());
+    assert!(db.fetch(UserByMaskedName { id: 0, name: Masked("ali".into()) }).await.is_err());
+}
+
+#[tokio::test]
+async fn object_graph() {
+    let Some((_db, mut client)) = connect().await else { return };
+    client.batch_execute(common::INTEGRITY_SQL).await.unwrap();
+
+    let id = transactional::persist_graph(&mut client, InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 }, vec![InsertPost::new(1), InsertPost::new(2)]).await.unwrap();
+    let posts: Vec<(i64, i64)> = client.query(common::POSTS_SQL, &[]).await.unwrap().iter().map(|row| (row.get(0), row.get(1))).collect();
+    assert_eq!(posts, [(1, id), (2, id)]);
+
+    // İkinci yazı eklenemediği için yazar ve ilk yazı da geri alınır
+    let failed = transactional::persist_graph(&mut client, InsertUser { name: "ayse".into(), email: "ayse@example.com".into(), state: 1 }, vec![InsertPost::new(3), InsertPost::new(1)]).await;
+    assert!(failed.is_err());
+    assert_eq!(client.query(common::POSTS_SQL, &[]).await.unwrap().len(), 2);
+    assert!(client.fetch_all(UsersByState::new(1)).await.unwrap().iter().all(|user| user.name == "ali"));
+}
+
+#[tokio::test]
+async fn csv_export() {
+    let Some((_db, mut client)) = connect().await else { return };
+    seed(&client).await;
+    client.insert::<_, i64>(InsertUser { name: common::QUOTED_NAME.into(), email: "oneil@example.com".into(), state: 1 }).await.unwrap();
+
+    // Dizi, tırnak içeren metin ve NULL parametreler COPY sorgusuna literal olarak yazılır
+    let mut csv = Vec::new();
+    let params = ExportUsers { names: vec!["ali".into(), common::QUOTED_NAME.into()], email: None };
+    let written = export_csv(&client, &params, &mut csv).await.unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), common::EXPORT_CSV);
+    assert_eq!(written, common::EXPORT_CSV.len() as u64);
+
+    let mut csv = Vec::new();
+    let params = ExportUsers { names: vec!["ali".into(), common::QUOTED_NAME.into()], email: Some("ali@example.com".into()) };
+    export_csv(&client, &params, &mut csv).await.unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap().lines().collect::<Vec<_>>(), ["id,name,email", "7,\"o'neil, \"\"jr\"\"\",oneil@example.com"]);
+
+    // Transaction içinden de çalışır ve henüz commit edilmemiş satırları görür
+    let tx = client.transaction().await.unwrap();
+    tx.execute("DELETE FROM users WHERE name = 'ali'", &[]).await.unwrap();
+    let mut csv = Vec::new();
+    let params = ExportUsers { names: vec!["ali".into()], email: None };
+    export_csv(&tx, &params, &mut csv).await.unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), "id,name,email\n");
+}
+
+#[tokio::test]
+async fn two_phase_commit() {
+    let Some((db, client)) = connect().await else { return };
+    let enabled: bool = client.query_one(common::PREPARED_TRANSACTIONS_SQL, &[]).await.unwrap().get(0);
+    if !enabled {
+        eprintln!("skipped: the server has max_prepared_transactions = 0");
+        return;
+    }
+    async fn names(client: &Client) -> Vec<String> {
+        client.fetch_all(UsersByState::new(1)).await.unwrap().into_iter().map(|user| user.name).collect()
+    }
+    let user = |name: &str| InsertUser { name: name.into(), email: format!("{}@example.com", name), state: 1 };
+    let mut orders = db.tokio_postgres().await.unwrap();
+    let mut billing = db.tokio_postgres().await.unwrap();
+
+    let orders_tx = orders.transaction().await.unwrap();
+    let billing_tx = billing.transaction().await.unwrap();
+    orders_tx.insert::<_, i64>(user("ali")).await.unwrap();
+    billing_tx.insert::<_, i64>(user("ayse")).await.unwrap();
+    let gids = two_phase::commit(vec![orders_tx, billing_tx], &NoRecovery).await.unwrap();
+    assert_eq!(gids.len(), 2);
+    assert_eq!(names(&client).await, ["ali", "ayse"]);
+    assert!(!two_phase::pending(&client).await.unwrap().iter().any(|gid| gids.contains(gid)));
+
+    // Hazırlanamayan bir katılımcı, hazırlanmış olanları da geri aldırır
+    let orders_tx = orders.transaction().await.unwrap();
+    let billing_tx = billing.transaction().await.unwrap();
+    orders_tx.insert::<_, i64>(user("mehmet")).await.unwrap();
+    assert!(billing_tx.batch_execute("SELECT * FROM missing_table").await.is_err());
+    let error = two_phase::commit(vec![orders_tx, billing_tx], &NoRecovery).await.unwrap_err();
+    assert!(matches!(error, TwoPhaseError::Prepare(_)));
+    assert_eq!(names(&client).await, ["ali", "ayse"]);
+
+    // Yarıda kalan commit'lerin hazırlanmış transaction'ları sonradan bitirilir
+    let kept = format!("{}{}-kept", two_phase::GID_PREFIX, db.schema());
+    let dropped = format!("{}{}-dropped", two_phase::GID_PREFIX, db.schema());
+    orders.batch_execute(&common::prepared_insert_sql(&kept, "zeynep")).await.unwrap();
+    orders.batch_execute(&common::prepared_insert_sql(&dropped, "can")).await.unwrap();
+    let pending = two_phase::pending(&client).await.unwrap();
+    assert!(pending.contains(&kept) && pending.contains(&dropped));
+    two_phase::commit_prepared(&client, &kept).await.unwrap();
+    two_phase::rollback_prepared(&client, &dropped).await.unwrap();
+    assert!(!two_phase::pending(&client).await.unwrap().iter().any(|gid| *gid == kept || *gid == dropped));
+    assert_eq!(names(&client).await, ["ali", "ayse", "zeynep"]);
+}
+
+#[tokio::test]
+async fn single_flight() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+    client.batch_execute(common::SLOW_FETCH_SQL).await.unwrap();
+    let db = SingleFlight::new(client);
+    async fn calls(client: &Client) -> i64 {
+        client.query_one(common::SLOW_FETCH_COUNT_SQL, &[]).await.unwrap().get(0)
+    }
+
+    // Aynı anahtarla bekleyenler tek sorgunun sonucunu paylaşır
+    let (first, second, third) = tokio::join!(
+        db.fetch(SlowUser::new(ids[0])),
+        db.fetch(SlowUser::new(ids[0])),
+        db.fetch(SlowUser::new(ids[0])),
+    );
+    assert_eq!(first.unwrap().name, "ali");
+    assert_eq!(second.unwrap(), third.unwrap());
+    assert_eq!(calls(&db).await, 1);
+
+    // Farklı anahtarlar ayrı çalışır
+    let (ali, ayse) = tokio::join!(db.fetch(SlowUser::new(ids[0])), db.fetch(SlowUser::new(ids[1])));
+    assert_eq!((ali.unwrap().name, ayse.unwrap().name), ("ali".to_string(), "ayse".to_string()));
+    assert_eq!(calls(&db).await, 3);
+
+    // Öncünün hatası bekleyenlerin hepsine ulaşır; her biri sorguyu kendisi tekrarlar
+    let (first, second, third) = tokio::join!(
+        db.fetch(SlowUser::new(ids[4])),
+        db.fetch(SlowUser::new(ids[4])),
+        db.fetch(SlowUser::new(ids[4])),
+    );
+    for result in [first, second, third] {
+        let error = result.unwrap_err();
+        assert_eq!(error.as_postgres().and_then(|e| e.as_db_error()).unwrap().message(), "passive user");
+    }
+    assert_eq!(calls(&db).await, 6);
+
+    // Öncü iptal edilince bekleyen asılı kalmaz, sorguyu kendisi çalıştırır
+    let (leader, waiter) = tokio::join!(
+        tokio::time::timeout(Duration::from_millis(50), db.fetch(SlowUser::new(ids[2]))),
+        tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            db.fetch(SlowUser::new(ids[2])).await
+        }),
+    );
+    assert!(leader.is_err());
+    assert_eq!(waiter.expect("the waiter hung").unwrap().name, "mehmet");
+    assert_eq!(calls(&db).await, 8);
+}
+
+#[cfg(feature = "json")]
+#[tokio::test]
+async fn json_rows() {
+    let Some((_db, client)) = connect().await else { return };
+    let ids = seed(&client).await;
+
+    let user = parsql::tokio_postgres::fetch_json(&client, &UserById::new(ids[0])).await.unwrap();
+    assert_eq!((user["name"].as_str(), user["state"].as_i64()), (Some("ali"), Some(1)));
+
+    let active = parsql::tokio_postgres::fetch_all_json(&client, &UsersByState::new(1)).await.unwrap();
+    assert_eq!(active.len(), 4);
+    assert_eq!(active[0]["id"].as_i64(), Some(ids[0]));
+}
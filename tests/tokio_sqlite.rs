@@ -0,0 +1,197 @@
+//! Tests of the `tokio-sqlite` backend against an in-memory database.
+
+#[allow(dead_code)]
+mod models {
+    use parsql::tokio_sqlite::{macros::*, traits::*, Error, Row, ToSql};
+
+    #[derive(Insertable, SqlParams)]
+    #[table("users")]
+    pub struct InsertUser {
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug, Clone, PartialEq)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    pub struct UserById {
+        pub id: i64,
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    impl UserById {
+        pub fn new(id: i64) -> Self {
+            Self { id, name: String::new(), email: String::new(), state: 0 }
+        }
+    }
+
+    #[derive(Queryable, SqlParams, FromRow, Debug, Clone)]
+    #[table("users")]
+    #[where_clause("state = $")]
+    #[order_by("id")]
+    pub struct UsersByState {
+        pub id: i64,
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    impl UsersByState {
+        pub fn new(state: i16) -> Self {
+            Self { id: 0, name: String::new(), email: String::new(), state }
+        }
+    }
+
+    #[derive(Updateable, UpdateParams)]
+    #[table("users")]
+    #[update("name, email")]
+    #[where_clause("id = $")]
+    pub struct UpdateUser {
+        pub id: i64,
+        pub name: String,
+        pub email: String,
+    }
+
+    #[derive(Deletable, SqlParams)]
+    #[table("users")]
+    #[where_clause("id = $")]
+    pub struct DeleteUser {
+        pub id: i64,
+    }
+
+    impl From<i64> for UserById {
+        fn from(id: i64) -> Self {
+            Self::new(id)
+        }
+    }
+
+    impl From<i64> for DeleteUser {
+        fn from(id: i64) -> Self {
+            Self { id }
+        }
+    }
+
+    /// `InsertUser` returning the new id, for `Repository::create`.
+    #[derive(Insertable, SqlParams)]
+    #[table("users")]
+    #[returning("id")]
+    pub struct CreateUser {
+        pub name: String,
+        pub email: String,
+        pub state: i16,
+    }
+
+    #[derive(Repository)]
+    #[repository(key = i64, insert = CreateUser, find = UserById, find_all = UsersByState, update = UpdateUser, delete = DeleteUser)]
+    pub struct Users;
+}
+
+use models::*;
+use parsql::tokio_sqlite::{repository::Repository, traits::CrudOps, Connection, Error};
+
+const SCHEMA: &str = "CREATE TABLE users (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    email TEXT NOT NULL UNIQUE,
+    state INTEGER NOT NULL
+)";
+
+async fn connect() -> Connection {
+    let conn = Connection::open_in_memory().await.unwrap();
+    conn.call(|conn| conn.execute_batch(SCHEMA)).await.unwrap();
+    conn
+}
+
+async fn seed(conn: &Connection) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for (name, state) in [("ali", 1), ("ayse", 1), ("can", 0)] {
+        let user = InsertUser { name: name.into(), email: format!("{}@example.com", name), state };
+        assert_eq!(conn.insert_execute(user).await.unwrap(), 1);
+        ids.push(conn.call(|conn| Ok(conn.last_insert_rowid())).await.unwrap());
+    }
+    ids
+}
+
+#[tokio::test]
+async fn crud() {
+    let conn = connect().await;
+    let ids = seed(&conn).await;
+
+    let query = UserById::new(ids[0]);
+    let user = conn.fetch(&query).await.unwrap();
+    assert_eq!((user.name.as_str(), user.email.as_str(), user.state), ("ali", "ali@example.com", 1));
+    // The query is only borrowed and stays usable
+    assert_eq!(conn.fetch(&query).await.unwrap(), user);
+
+    let active = conn.fetch_all(&UsersByState::new(1)).await.unwrap();
+    assert_eq!(active.iter().map(|user| user.id).collect::<Vec<_>>(), ids[..2]);
+
+    let update = UpdateUser { id: ids[0], name: "veli".into(), email: "veli@example.com".into() };
+    assert_eq!(conn.update(update).await.unwrap(), 1);
+    assert_eq!(conn.fetch(&query).await.unwrap().name, "veli");
+
+    assert_eq!(conn.delete(DeleteUser { id: ids[0] }).await.unwrap(), 1);
+    assert!(matches!(conn.fetch(&query).await, Err(Error::QueryReturnedNoRows)));
+    assert_eq!(conn.delete(DeleteUser { id: ids[0] }).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn repository() {
+    let users = Repository::<Users, _>::new(connect().await);
+    let id: i64 = users.create(CreateUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 }).await.unwrap();
+    assert_eq!(users.find(id).await.unwrap().name, "ali");
+    assert_eq!(users.find_all(UsersByState::new(1)).await.unwrap().len(), 1);
+
+    let update = UpdateUser { id, name: "veli".into(), email: "veli@example.com".into() };
+    assert_eq!(users.update(update).await.unwrap(), 1);
+    assert_eq!(users.find(id).await.unwrap().name, "veli");
+    assert_eq!(users.delete(id).await.unwrap(), 1);
+    assert_eq!(users.delete(id).await.unwrap(), 0);
+    assert!(matches!(users.find(id).await, Err(Error::QueryReturnedNoRows)));
+}
+
+#[tokio::test]
+async fn call() {
+    let conn = connect().await;
+    seed(&conn).await;
+
+    let moved = conn
+        .call(|conn| {
+            let tx = conn.transaction()?;
+            let moved = tx.execute("UPDATE users SET state = 2 WHERE state = 1", [])?;
+            tx.commit()?;
+            Ok(moved)
+        })
+        .await
+        .unwrap();
+    assert_eq!(moved, 2);
+
+    // Clones share the connection
+    let clone = conn.clone();
+    assert_eq!(clone.fetch_all(&UsersByState::new(2)).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn worker_errors() {
+    let conn = connect().await;
+    seed(&conn).await;
+
+    // SQLite errors raised on the worker thread reach the caller
+    let duplicate = InsertUser { name: "ali".into(), email: "ali@example.com".into(), state: 1 };
+    let error = conn.insert_execute(duplicate).await.unwrap_err();
+    assert!(error.to_string().contains("UNIQUE constraint failed"));
+    let error = conn.call(|conn| conn.execute("SELECT * FROM missing", [])).await.unwrap_err();
+    assert!(error.to_string().contains("missing"));
+
+    // A panicking job stops the worker; later calls fail instead of hanging
+    let panicked = conn.call(|_| -> Result<(), Error> { panic!("job failed") }).await;
+    assert!(panicked.unwrap_err().to_string().contains("connection thread has stopped"));
+    let after = conn.fetch(&UserById::new(1)).await;
+    assert!(after.unwrap_err().to_string().contains("connection thread has stopped"));
+
+    let missing = Connection::open("/nonexistent/dir/app.db").await;
+    assert!(missing.is_err());
+}